@@ -0,0 +1,54 @@
+//! Console setup shared by every CLI mode.
+
+/// Ensure the Windows console uses UTF-8 for output so box-drawing and
+/// other Unicode characters render correctly, even in cmd.exe or legacy
+/// PowerShell hosts that default to an OEM code page.
+///
+/// This does three things:
+/// 1. Sets the input and output code pages to 65001 (UTF-8).
+/// 2. Switches the console font to Consolas (a TrueType font with full
+///    Unicode box-drawing support). The default "Raster Fonts" in cmd.exe
+///    cannot render ╔═╗║ etc.
+/// 3. Enables Virtual Terminal Processing so ANSI escape sequences (used by
+///    crossterm for colours and cursor movement) work correctly.
+pub fn enable_utf8_console() {
+    #[cfg(windows)]
+    {
+        use windows::Win32::System::Console::{
+            GetConsoleMode, GetStdHandle, SetConsoleCP, SetConsoleMode, SetConsoleOutputCP,
+            SetCurrentConsoleFontEx, CONSOLE_FONT_INFOEX, COORD, ENABLE_PROCESSED_OUTPUT,
+            ENABLE_VIRTUAL_TERMINAL_PROCESSING, STD_OUTPUT_HANDLE,
+        };
+
+        unsafe {
+            // 1. UTF-8 code pages
+            let _ = SetConsoleOutputCP(65001);
+            let _ = SetConsoleCP(65001);
+
+            let handle = match GetStdHandle(STD_OUTPUT_HANDLE) {
+                Ok(h) => h,
+                Err(_) => return,
+            };
+
+            // 2. TrueType font with Unicode support
+            let mut font = CONSOLE_FONT_INFOEX {
+                cbSize: std::mem::size_of::<CONSOLE_FONT_INFOEX>() as u32,
+                dwFontSize: COORD { X: 0, Y: 18 },
+                FontWeight: 400, // FW_NORMAL
+                ..Default::default()
+            };
+            let name: Vec<u16> = "Consolas\0".encode_utf16().collect();
+            font.FaceName[..name.len()].copy_from_slice(&name);
+            let _ = SetCurrentConsoleFontEx(handle, false, &font);
+
+            // 3. Enable VT processing (ANSI escape sequences)
+            let mut mode = Default::default();
+            if GetConsoleMode(handle, &mut mode).is_ok() {
+                let _ = SetConsoleMode(
+                    handle,
+                    mode | ENABLE_PROCESSED_OUTPUT | ENABLE_VIRTUAL_TERMINAL_PROCESSING,
+                );
+            }
+        }
+    }
+}
@@ -5,21 +5,51 @@
 //! profile on display connect, session unlock, and logon events.
 //!
 //! Can also run as a Windows service for always-on monitoring.
+//!
+//! The TUI, service, DDC/CI and toast layers are cargo features (all on by
+//! default). `--no-default-features` builds a slim binary with just the
+//! profile, detect/apply, config and ICC commands, e.g. for a scheduled-task
+//! deployment.
 
 use clap::{Parser, Subcommand};
+#[cfg(feature = "ddc")]
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+#[cfg(feature = "ddc")]
 use crossterm::terminal;
+use lg_core::toast_template::ToastTally;
+#[cfg(feature = "notify")]
+use lg_core::toast_template::{self, ToastValues};
 use lg_core::{
+    audit,
+    bench::{self, PhaseTimings, TimedSteps},
+    colorstore,
     config::{self, Config},
-    state as app_state,
+    i18n,
+    pipeline::{ModeProfiles, MonitorResult, ReapplyOptions, ReapplyPipeline, ReapplySteps},
+    preset, quirks,
+    report::{self, DeviceEntry, InstallReport},
+    state as app_state, validate,
 };
+use output::{fail, say, Format, Verbosity};
 use std::error::Error;
-use std::io::{self, IsTerminal};
+use std::io;
+#[cfg(any(feature = "tui", feature = "ddc"))]
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
-use std::time::{Duration, Instant};
+use std::time::Duration;
+use std::time::Instant;
 
+mod console;
 mod elevation;
+mod output;
+#[cfg(feature = "service")]
+mod packaging;
+mod pattern;
+mod timing;
+#[cfg(feature = "tui")]
 mod tui;
+#[cfg(feature = "service")]
+mod watch_log;
 
 #[derive(Parser)]
 #[command(
@@ -32,9 +62,17 @@ mod tui;
         to force Windows to reload the profile."
 )]
 struct Cli {
-    /// Enable verbose output
-    #[arg(short, long, global = true)]
-    verbose: bool,
+    /// More output: -v info, -vv debug, -vvv trace
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Print errors only
+    #[arg(short, long, global = true, conflicts_with_all = ["verbose", "format"])]
+    quiet: bool,
+
+    /// Command output: human, or json for one JSON object per line
+    #[arg(long, global = true, value_name = "FORMAT", default_value = "human", value_parser = parse_output_format)]
+    format: Format,
 
     /// Simulate operations without making changes
     #[arg(long, global = true)]
@@ -48,6 +86,19 @@ struct Cli {
     #[arg(long, global = true)]
     skip_elevation: bool,
 
+    /// Use a named config for this run instead of the active one
+    #[arg(long, global = true, value_name = "NAME")]
+    config_name: Option<String>,
+
+    /// Keep config, logs and state next to the executable (no ProgramData,
+    /// no service)
+    #[arg(long, global = true)]
+    portable: bool,
+
+    /// Print how long each startup and command phase took
+    #[arg(long, global = true)]
+    timing: bool,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -55,6 +106,7 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Install color profile and/or service
+    #[cfg(feature = "service")]
     Install {
         /// Monitor name pattern (case-insensitive substring match)
         #[arg(short, long)]
@@ -76,14 +128,19 @@ enum Commands {
         #[arg(long)]
         profile_path: Option<String>,
 
-        /// Also associate profile in per-user scope (default: system-wide only)
+        /// Associate now from your account, setting per-user defaults too
+        /// (the service itself works system-wide)
         #[arg(long)]
         per_user: bool,
 
-        /// Skip HDR/advanced-color association
+        /// Skip HDR/advanced-color association (saved to config for the service)
         #[arg(long)]
         skip_hdr: bool,
 
+        /// Associate now and make the active profile each monitor's generic default
+        #[arg(long)]
+        generic_default: bool,
+
         /// Skip hash check — always overwrite profile in color store
         #[arg(long)]
         skip_hash_check: bool,
@@ -95,9 +152,22 @@ enum Commands {
         /// Skip monitor detection during install
         #[arg(long)]
         skip_detect: bool,
+
+        /// For package manager scripts: no elevation prompt, no monitor
+        /// detection or prompts, no config written; fixed exit codes
+        #[arg(
+            long,
+            conflicts_with_all = ["pattern", "regex", "per_user", "skip_hdr", "generic_default"]
+        )]
+        for_packaging: bool,
+
+        /// With --dry-run, print the plan as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Uninstall service and/or profile
+    #[cfg(feature = "service")]
     Uninstall {
         /// Remove everything (service + profile + config)
         #[arg(long)]
@@ -106,9 +176,23 @@ enum Commands {
         /// Also remove the ICC profile from color store
         #[arg(long)]
         profile: bool,
+
+        /// With --full, first write a local usage summary (reapply, failure
+        /// and trigger counts) to FILE or the temp directory
+        #[arg(long, value_name = "FILE", num_args = 0..=1, requires = "full")]
+        feedback: Option<Option<PathBuf>>,
+
+        /// For package manager scripts: no elevation prompt; fixed exit codes
+        #[arg(long, conflicts_with = "feedback")]
+        for_packaging: bool,
+
+        /// With --dry-run, print the plan as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Clean reinstall (uninstall then install)
+    #[cfg(feature = "service")]
     Reinstall {
         /// Monitor name pattern override
         #[arg(short, long)]
@@ -117,6 +201,10 @@ enum Commands {
         /// Use regex pattern matching instead of substring
         #[arg(long)]
         regex: bool,
+
+        /// With --dry-run, print the plan as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Detect connected monitors matching a pattern
@@ -144,7 +232,17 @@ enum Commands {
         #[arg(long)]
         profile_path: Option<String>,
 
-        /// Also associate profile in per-user scope
+        /// Name of a profile already in the color store (see `profile list`);
+        /// the extension may be left off
+        #[arg(long, value_name = "NAME", conflicts_with = "profile_path")]
+        profile_name: Option<String>,
+
+        /// Apply to this device instance (e.g. "DISPLAY\LGS\001") instead of
+        /// matching names; repeatable
+        #[arg(long, value_name = "KEY", conflicts_with_all = ["pattern", "regex"])]
+        device_key: Vec<String>,
+
+        /// Also set per-user defaults (associations always cover both scopes)
         #[arg(long)]
         per_user: bool,
 
@@ -152,6 +250,10 @@ enum Commands {
         #[arg(long)]
         skip_hdr: bool,
 
+        /// Also make the active profile each monitor's generic default
+        #[arg(long)]
+        generic_default: bool,
+
         /// Enable toast notification for this run
         #[arg(long, conflicts_with = "no_toast")]
         toast: bool,
@@ -162,6 +264,7 @@ enum Commands {
     },
 
     /// Run event watcher in foreground (Ctrl+C to stop)
+    #[cfg(feature = "service")]
     Watch {
         /// Monitor name pattern override
         #[arg(short, long)]
@@ -170,6 +273,14 @@ enum Commands {
         /// Use regex pattern matching instead of substring
         #[arg(long)]
         regex: bool,
+
+        /// Also write log records to the Windows Event Log, like the service
+        #[arg(long)]
+        event_log: bool,
+
+        /// Don't append log records to state\watch.log
+        #[arg(long)]
+        no_log_file: bool,
     },
 
     /// Configuration management
@@ -179,17 +290,31 @@ enum Commands {
     },
 
     /// Windows service management (advanced)
+    #[cfg(feature = "service")]
     Service {
         #[command(subcommand)]
         action: ServiceAction,
     },
 
+    /// Opt-in weekly usage ping from the service (off by default)
+    #[cfg(feature = "service")]
+    Telemetry {
+        #[command(subcommand)]
+        action: TelemetryAction,
+    },
+
     /// Run diagnostic tests
     Test {
         #[command(subcommand)]
         action: TestAction,
     },
 
+    /// Record screen luminance readings (see `service status --stats`)
+    Measure {
+        #[command(subcommand)]
+        action: MeasureAction,
+    },
+
     /// ICC conversion, inspection, validation, and tag manipulation utilities
     Icc {
         #[command(subcommand)]
@@ -197,11 +322,36 @@ enum Commands {
     },
 
     /// DDC/CI monitor control (brightness, color presets, display mode, resets)
+    #[cfg(feature = "ddc")]
     Ddc {
         #[command(subcommand)]
         action: DdcAction,
     },
 
+    /// Windows display settings on matched monitors (HDR, SDR content brightness, mode)
+    Display {
+        #[command(subcommand)]
+        action: DisplayAction,
+    },
+
+    /// Back up or restore every display's profile associations and defaults
+    Colorstore {
+        #[command(subcommand)]
+        action: ColorstoreAction,
+    },
+
+    /// Audit log of every system change made by the tool and the service
+    Audit {
+        #[command(subcommand)]
+        action: AuditAction,
+    },
+
+    /// Model quirk database (built-in plus community packs)
+    Quirks {
+        #[command(subcommand)]
+        action: QuirksAction,
+    },
+
     /// Automation engine (ambient sensor + per-app rules + self-heal settings)
     Automation {
         #[command(subcommand)]
@@ -230,6 +380,129 @@ enum Commands {
         #[arg(long)]
         regex: bool,
     },
+
+    /// Time real reapplies phase by phase (reassociates like `apply`)
+    #[command(hide = true)]
+    Bench {
+        /// Reapplies to time
+        #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..=1000))]
+        iterations: u32,
+    },
+
+    /// Color store housekeeping
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+
+    /// Fast commands for button bindings (sent to the running service)
+    #[cfg(feature = "service")]
+    Quick {
+        #[command(subcommand)]
+        action: QuickAction,
+    },
+
+    /// Show a toast in this session (started by the service for the
+    /// console user)
+    #[cfg(feature = "notify")]
+    #[command(name = "session-toast", hide = true)]
+    SessionToast {
+        #[arg(long)]
+        title: String,
+
+        #[arg(long)]
+        body: String,
+
+        /// Focus Assist handling: show, suppress or queue
+        #[arg(long, default_value = "suppress")]
+        quiet_hours: String,
+    },
+}
+
+impl Commands {
+    /// `install`/`uninstall --for-packaging`, which check elevation
+    /// themselves instead of relaunching.
+    fn for_packaging(&self) -> bool {
+        #[cfg(feature = "service")]
+        if let Commands::Install { for_packaging, .. } | Commands::Uninstall { for_packaging, .. } =
+            self
+        {
+            return *for_packaging;
+        }
+        false
+    }
+
+    /// Commands that register the service, which `--portable` rules out.
+    fn installs_service(&self) -> bool {
+        #[cfg(feature = "service")]
+        if matches!(
+            self,
+            Commands::Install {
+                profile_only: false,
+                ..
+            } | Commands::Reinstall { .. }
+                | Commands::Service {
+                    action: ServiceAction::Install { .. }
+                }
+        ) {
+            return true;
+        }
+        false
+    }
+
+    /// Commands that write to the color store or manage the service.
+    fn needs_admin(&self) -> bool {
+        #[cfg(feature = "service")]
+        if let Commands::Service { action } = self {
+            // History is read over the control pipe, which any user may open,
+            // and any user may read the Application event log and the
+            // counters file.
+            return !matches!(
+                action,
+                ServiceAction::History { .. }
+                    | ServiceAction::Logs { .. }
+                    | ServiceAction::Counters { .. }
+            );
+        }
+        #[cfg(feature = "service")]
+        if let Commands::Telemetry { action } = self {
+            return !matches!(action, TelemetryAction::Status);
+        }
+        #[cfg(feature = "service")]
+        if matches!(
+            self,
+            Commands::Install { .. }
+                | Commands::Uninstall { .. }
+                | Commands::Reinstall { .. }
+                | Commands::Watch { .. }
+        ) {
+            return true;
+        }
+        matches!(
+            self,
+            Commands::Apply { .. }
+                | Commands::Bench { .. }
+                | Commands::Profile {
+                    action: ProfileAction::Audit { fix: true }
+                        | ProfileAction::Install {
+                            scope: colorstore::Scope::SystemWide,
+                            ..
+                        }
+                        | ProfileAction::Generate { .. }
+                        | ProfileAction::Compare { .. }
+                }
+                | Commands::Test {
+                    action: TestAction::Pattern { .. }
+                }
+                // Reapplies the profile after switching.
+                | Commands::Display {
+                    action: DisplayAction::Hdr { .. }
+                }
+                | Commands::Colorstore {
+                    action: ColorstoreAction::Restore { .. }
+                }
+        )
+    }
 }
 
 #[derive(Subcommand)]
@@ -240,8 +513,42 @@ enum ConfigAction {
     Path,
     /// Reset config to defaults
     Reset,
+    /// List named configs and show which one is active
+    List,
+    /// Switch the active config (created from the current one if missing)
+    Use {
+        /// Config name ("default" is config.toml)
+        name: String,
+    },
+    /// Check the config for unknown keys, bad values and conflicts
+    Validate {
+        /// Config file to check (default: the active config)
+        path: Option<PathBuf>,
+    },
+    /// Apply a bundled preset (kvm, dock, aggressive, default)
+    Preset {
+        #[command(subcommand)]
+        action: PresetAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum PresetAction {
+    /// List the bundled presets
+    List,
+    /// Apply a preset to the active config
+    Apply {
+        /// Preset name
+        name: String,
+    },
+    /// Show what applying a preset would change
+    Diff {
+        /// Preset name
+        name: String,
+    },
 }
 
+#[cfg(feature = "service")]
 #[derive(Subcommand)]
 enum ServiceAction {
     /// Install the Windows service
@@ -261,14 +568,64 @@ enum ServiceAction {
     /// Stop the service
     Stop,
     /// Show service status
-    Status,
+    Status {
+        /// Also show apply latency and the daily luminance range
+        #[arg(long)]
+        stats: bool,
+    },
+    /// Show the service's recent reapply attempts
+    History {
+        /// Only show the last N attempts
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+    },
+    /// Show the service's entries in the Application event log
+    Logs {
+        /// Only show the last N events
+        #[arg(short = 'n', long, default_value_t = 50)]
+        limit: usize,
+
+        /// Lowest level to show: error, warn or info
+        #[arg(long, default_value = "info", value_parser = parse_event_level)]
+        level: lg_service::EventLevel,
+
+        /// Keep printing new events as they are logged (Ctrl+C to stop)
+        #[arg(short, long)]
+        follow: bool,
+    },
+    /// Print the service's performance counters as typeperf-style CSV
+    Counters {
+        /// Seconds between samples
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..=3600))]
+        interval: u64,
+
+        /// Number of samples to print (default: until Ctrl+C)
+        #[arg(short = 'n', long)]
+        samples: Option<u64>,
+    },
     /// Run as Windows service (SCM dispatch — do not call directly)
     Run,
 }
 
+#[cfg(feature = "service")]
+#[derive(Subcommand)]
+enum TelemetryAction {
+    /// Show whether the ping is on, when it last ran, and the exact payload
+    Status,
+    /// Turn the ping on (restarts the service)
+    Enable {
+        /// https URL to send the ping to (default: the configured one)
+        #[arg(long)]
+        endpoint: Option<String>,
+    },
+    /// Turn the ping off (restarts the service)
+    Disable,
+}
+
 #[derive(Subcommand)]
 enum TestAction {
     /// Send a test toast notification
+    #[cfg(feature = "notify")]
     Toast {
         /// Custom title for test notification
         #[arg(long, default_value = "LG UltraGear Test")]
@@ -290,6 +647,32 @@ enum TestAction {
         #[arg(long)]
         regex: bool,
     },
+    /// Show gray ramps and near-black steps fullscreen on a matched monitor
+    Pattern {
+        /// Monitor name pattern (the first match is used)
+        #[arg(short, long)]
+        pattern: Option<String>,
+
+        /// Use regex pattern matching instead of substring
+        #[arg(long)]
+        regex: bool,
+
+        /// Profile Space switches to for "before": stock, identity, an
+        /// installed profile name or a file
+        #[arg(long, default_value = "stock")]
+        before: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum MeasureAction {
+    /// Run measure_command now and record its reading
+    Run,
+    /// Record a reading taken with your own meter
+    Add {
+        /// Luminance in cd/m²
+        cd_m2: f64,
+    },
 }
 
 #[derive(Subcommand)]
@@ -310,6 +693,91 @@ enum TrayAction {
     Run,
 }
 
+#[cfg(feature = "service")]
+#[derive(Subcommand)]
+enum QuickAction {
+    /// Reapply the profile now
+    Reapply,
+    /// Set DDC brightness (0-100) or step it (+N / -N)
+    Brightness {
+        #[arg(allow_hyphen_values = true)]
+        value: String,
+    },
+    /// Pin an ICC preset until the service restarts (`auto` to unpin)
+    Preset { name: String },
+}
+
+#[derive(Subcommand)]
+enum ProfileAction {
+    /// Find stale files, duplicate copies and dead associations
+    Audit {
+        /// Delete the files and remove the associations that were found
+        #[arg(long)]
+        fix: bool,
+    },
+    /// List profiles installed in the color store, one name per line (the
+    /// names `apply --profile-name` accepts)
+    List,
+    /// Copy an ICC/ICM file into the color store for a scope
+    Install {
+        /// ICC/ICM file to install
+        file: PathBuf,
+        /// system (registered with Windows, needs admin) or user (no admin)
+        #[arg(long, default_value = "system", value_parser = parse_profile_scope)]
+        scope: colorstore::Scope,
+    },
+    /// Write and register a generated profile in the color store
+    #[command(group(clap::ArgGroup::new("kind").required(true).args(["identity", "variant"])))]
+    Generate {
+        /// Neutral profile with a linear vcgt, for A/B testing whether the
+        /// calibration or the association toggle fixes the dimming
+        #[arg(long)]
+        identity: bool,
+        /// Active profile moved to another white point: 6500k, 5000k or 9300k
+        #[arg(long, value_parser = parse_white_point_variant)]
+        variant: Option<lg_profile::WhitePointVariant>,
+    },
+    /// Write a copy of a profile with its vcgt calibration curve adjusted
+    Adjust {
+        /// ICC/ICM file to start from (defaults to the active generated profile)
+        #[arg(short, long)]
+        input: Option<String>,
+        /// Output ICC/ICM file
+        #[arg(short, long)]
+        output: String,
+        /// Brightness offset in percent (-50 to +50)
+        #[arg(long, default_value_t = 0.0, allow_hyphen_values = true)]
+        brightness: f64,
+        /// Contrast change in percent (-50 to +50)
+        #[arg(long, default_value_t = 0.0, allow_hyphen_values = true)]
+        contrast: f64,
+        /// Target gamma, taking the current curve as 2.2 (1.2 to 3.0)
+        #[arg(long, default_value_t = lg_profile::PRESET_GAMMA_22)]
+        gamma: f64,
+    },
+    /// Alternate matching monitors between two profiles until a key is pressed
+    Compare {
+        /// First profile: stock, fix, identity, an installed name or a file
+        #[arg(long = "a", default_value = "stock")]
+        a: String,
+        /// Second profile, same forms as --a
+        #[arg(long = "b", default_value = "fix")]
+        b: String,
+        /// Seconds each profile stays on screen
+        #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u64).range(1..=600))]
+        interval: u64,
+        /// Monitor name pattern override
+        #[arg(short, long)]
+        pattern: Option<String>,
+        /// Use regex pattern matching instead of substring
+        #[arg(long)]
+        regex: bool,
+        /// Also show a toast at every switch
+        #[arg(long)]
+        toast: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum BundleAction {
     /// Export config/state/profile artifacts into a folder bundle
@@ -326,6 +794,125 @@ enum BundleAction {
     },
 }
 
+#[derive(Subcommand)]
+enum DisplayAction {
+    /// Switch HDR on matched monitors, then reapply the matching profile
+    Hdr {
+        /// on | off | toggle
+        #[arg(value_parser = parse_hdr_switch)]
+        state: lg_monitor::HdrSwitch,
+
+        /// Monitor name pattern override
+        #[arg(short, long)]
+        pattern: Option<String>,
+
+        /// Use regex pattern matching instead of substring
+        #[arg(long)]
+        regex: bool,
+    },
+    /// SDR content brightness of HDR-enabled monitors
+    SdrWhite {
+        #[command(subcommand)]
+        action: SdrWhiteAction,
+    },
+    /// Resolution and refresh rate kept by the display mode guard
+    Mode {
+        #[command(subcommand)]
+        action: DisplayModeAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum ColorstoreAction {
+    /// Save all associations and defaults (system-wide and per-user) to JSON
+    Backup {
+        /// Backup file to write
+        file: PathBuf,
+    },
+    /// Put the associations and defaults from a backup back exactly
+    Restore {
+        /// Backup file written by `colorstore backup`
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditAction {
+    /// Show the most recent audit entries, newest first
+    Show {
+        /// Number of entries to show
+        #[arg(short = 'n', long, default_value_t = 50)]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum QuirksAction {
+    /// List every quirk in effect and any pack that was skipped
+    List,
+    /// Check a quirk pack file before dropping it into the quirks directory
+    Validate {
+        /// Quirk pack TOML
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum DisplayModeAction {
+    /// Show the current and recorded mode of each matched monitor
+    Show {
+        /// Monitor name pattern override
+        #[arg(short, long)]
+        pattern: Option<String>,
+
+        /// Use regex pattern matching instead of substring
+        #[arg(long)]
+        regex: bool,
+    },
+    /// Switch matched monitors to a mode and pin it for the guard
+    Set {
+        /// WIDTHxHEIGHT@HZ, e.g. 2560x1440@240 (default: pin the current mode)
+        #[arg(value_parser = parse_display_mode)]
+        mode: Option<lg_monitor::DisplayMode>,
+
+        /// Monitor name pattern override
+        #[arg(short, long)]
+        pattern: Option<String>,
+
+        /// Use regex pattern matching instead of substring
+        #[arg(long)]
+        regex: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SdrWhiteAction {
+    /// Show the SDR white level of each matched monitor
+    Get {
+        /// Monitor name pattern override
+        #[arg(short, long)]
+        pattern: Option<String>,
+
+        /// Use regex pattern matching instead of substring
+        #[arg(long)]
+        regex: bool,
+    },
+    /// Set the SDR white level on each matched monitor with HDR on
+    Set {
+        /// Level in nits (80–480)
+        nits: u32,
+
+        /// Monitor name pattern override
+        #[arg(short, long)]
+        pattern: Option<String>,
+
+        /// Use regex pattern matching instead of substring
+        #[arg(long)]
+        regex: bool,
+    },
+}
+
+#[cfg(feature = "ddc")]
 #[derive(Subcommand)]
 enum DdcAction {
     /// Set brightness on all monitors (or use --pattern to target one)
@@ -385,6 +972,50 @@ enum DdcAction {
         #[arg(short, long)]
         pattern: Option<String>,
     },
+    /// Read sharpness (VCP 0x87)
+    Sharpness {
+        /// Monitor name pattern override
+        #[arg(short, long)]
+        pattern: Option<String>,
+    },
+    /// Set sharpness (VCP 0x87)
+    SetSharpness {
+        /// Sharpness value (model-specific range, usually 0–100)
+        value: u32,
+
+        /// Monitor name pattern override
+        #[arg(short, long)]
+        pattern: Option<String>,
+    },
+    /// Read response time / overdrive (model-specific VCP code)
+    ResponseTime {
+        /// VCP code in hex (default: the candidate guessed from the capabilities string)
+        #[arg(short, long, value_parser = parse_hex_u8)]
+        code: Option<u8>,
+
+        /// Monitor name pattern override
+        #[arg(short, long)]
+        pattern: Option<String>,
+    },
+    /// Set response time / overdrive (model-specific VCP code)
+    SetResponseTime {
+        /// Response time value (one of the values advertised by the monitor)
+        value: u32,
+
+        /// VCP code in hex for this model (required; `ddc capabilities` shows a candidate)
+        #[arg(short, long, value_parser = parse_hex_u8)]
+        code: u8,
+
+        /// Monitor name pattern override
+        #[arg(short, long)]
+        pattern: Option<String>,
+    },
+    /// Print the monitor's MCCS capabilities string and advertised VCP codes
+    Capabilities {
+        /// Monitor name pattern override
+        #[arg(short, long)]
+        pattern: Option<String>,
+    },
     /// Read any VCP code (advanced)
     GetVcp {
         /// VCP code in hex (e.g. 10, 14, DC)
@@ -408,8 +1039,45 @@ enum DdcAction {
         #[arg(short, long)]
         pattern: Option<String>,
     },
-    /// List all physical monitors visible via DDC/CI
-    List,
+    /// Poll VCP codes at an interval and print timestamped value changes
+    Watch {
+        /// VCP codes in hex, comma-separated (e.g. 10,12)
+        #[arg(short, long, value_parser = parse_hex_u8, value_delimiter = ',', default_value = "10")]
+        codes: Vec<u8>,
+
+        /// Polling interval in milliseconds
+        #[arg(short, long, default_value_t = 1000)]
+        interval_ms: u64,
+
+        /// Stop after this many polls (0 = run until Ctrl+C)
+        #[arg(short = 'n', long, default_value_t = 0)]
+        samples: u64,
+
+        /// Monitor name pattern override
+        #[arg(short, long)]
+        pattern: Option<String>,
+    },
+    /// Run a TOML script of VCP set/get/sleep/assert steps on one monitor
+    Script {
+        /// Script file
+        file: PathBuf,
+
+        /// Monitor name pattern override (default: the script's monitor_match)
+        #[arg(short, long)]
+        pattern: Option<String>,
+    },
+    /// Turn monitors on, to standby or off (VCP 0xD6)
+    Power {
+        /// on | standby | off
+        #[arg(value_parser = parse_power_mode)]
+        state: lg_monitor::ddc::PowerMode,
+
+        /// Monitor name pattern override ("" = every monitor)
+        #[arg(short, long)]
+        pattern: Option<String>,
+    },
+    /// List all physical monitors visible via DDC/CI
+    List,
     /// Build a DDC capability map by probing known VCP codes
     Map,
 }
@@ -551,6 +1219,11 @@ enum IccAction {
 }
 
 /// Parse a hex string (with or without 0x prefix) into a u8.
+#[cfg(feature = "ddc")]
+fn parse_output_format(s: &str) -> Result<Format, String> {
+    Format::parse(s).ok_or_else(|| format!("Invalid format '{}': expected human or json", s))
+}
+
 fn parse_hex_u8(s: &str) -> Result<u8, String> {
     let s = s
         .strip_prefix("0x")
@@ -559,6 +1232,40 @@ fn parse_hex_u8(s: &str) -> Result<u8, String> {
     u8::from_str_radix(s, 16).map_err(|e| format!("Invalid hex byte '{}': {}", s, e))
 }
 
+/// Parse a `profile generate --variant` white point.
+fn parse_profile_scope(s: &str) -> Result<colorstore::Scope, String> {
+    colorstore::Scope::parse(s)
+        .ok_or_else(|| format!("Invalid scope '{}': expected system or user", s))
+}
+
+fn parse_white_point_variant(s: &str) -> Result<lg_profile::WhitePointVariant, String> {
+    lg_profile::WhitePointVariant::parse(s)
+        .ok_or_else(|| format!("Invalid variant '{}': expected 6500k, 5000k or 9300k", s))
+}
+
+/// Parse a `ddc power` state.
+#[cfg(feature = "ddc")]
+fn parse_power_mode(s: &str) -> Result<lg_monitor::ddc::PowerMode, String> {
+    lg_monitor::ddc::PowerMode::parse(s)
+        .ok_or_else(|| format!("Invalid power state '{}': expected on, standby or off", s))
+}
+
+#[cfg(feature = "service")]
+fn parse_event_level(s: &str) -> Result<lg_service::EventLevel, String> {
+    lg_service::EventLevel::parse(s)
+        .ok_or_else(|| format!("Invalid level '{}': expected error, warn or info", s))
+}
+
+fn parse_hdr_switch(s: &str) -> Result<lg_monitor::HdrSwitch, String> {
+    lg_monitor::HdrSwitch::parse(s)
+        .ok_or_else(|| format!("Invalid HDR state '{}': expected on, off or toggle", s))
+}
+
+fn parse_display_mode(s: &str) -> Result<lg_monitor::DisplayMode, String> {
+    lg_monitor::DisplayMode::parse(s)
+        .ok_or_else(|| format!("Invalid display mode '{}': expected e.g. 2560x1440@240", s))
+}
+
 fn parse_hex_bytes(input: &str) -> Result<Vec<u8>, Box<dyn Error>> {
     let compact = input
         .chars()
@@ -595,14 +1302,33 @@ fn parse_hex_bytes(input: &str) -> Result<Vec<u8>, Box<dyn Error>> {
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let started = Instant::now();
+
     // Set console to UTF-8 early — before any output or elevation relaunch.
     // This ensures box-drawing characters render correctly even in cmd.exe
     // or legacy PowerShell that default to OEM code pages (437/850).
-    tui::enable_utf8_console();
+    console::enable_utf8_console();
+
+    let cli = timing::phase("parse args", Cli::parse);
+    let _timing_report = cli.timing.then(|| timing::ReportOnExit(started));
+    output::set_format(if cli.quiet { Format::Quiet } else { cli.format });
+    let verbosity = Verbosity {
+        quiet: cli.quiet,
+        verbose: cli.verbose,
+    };
 
-    let cli = Cli::parse();
+    if cli.portable {
+        config::enable_portable()?;
+        if cli.command.as_ref().is_some_and(Commands::installs_service) {
+            return Err("portable mode runs without the service; use `watch` instead".into());
+        }
+    }
+    if let Some(name) = cli.config_name.as_deref() {
+        config::set_config_name_override(name)?;
+    }
 
     // SCM dispatch — must happen before any logger initialization
+    #[cfg(feature = "service")]
     if matches!(
         &cli.command,
         Some(Commands::Service {
@@ -610,15 +1336,35 @@ fn main() -> Result<(), Box<dyn Error>> {
         })
     ) {
         winlog::init("lg-ultragear-color-svc").ok();
-        return lg_service::run();
+        return lg_service::run(env!("APP_VERSION"));
+    }
+
+    // `quick` only talks to the running service over its control pipe —
+    // skip elevation, logging and config loading to keep it fast.
+    #[cfg(feature = "service")]
+    if let Some(Commands::Quick { action }) = &cli.command {
+        return cmd_quick(action, cli.dry_run);
+    }
+
+    // Toast helper started by the service in the console user's session.
+    #[cfg(feature = "notify")]
+    if let Some(Commands::SessionToast {
+        title,
+        body,
+        quiet_hours,
+    }) = &cli.command
+    {
+        lg_notify::run_session_toast(title, body, lg_notify::QuietHours::parse(quiet_hours));
+        return Ok(());
     }
 
     // No subcommand → interactive TUI (unless --non-interactive or not a terminal)
     if cli.command.is_none() {
+        #[cfg(feature = "tui")]
         if !cli.non_interactive && std::io::stdout().is_terminal() {
             // Auto-elevate for TUI mode (profile + service install needs admin)
             if !cli.skip_elevation && !elevation::is_elevated() {
-                println!("[INFO] Requesting administrator privileges...");
+                say!("[INFO] Requesting administrator privileges...");
                 elevation::relaunch_elevated()?;
             }
             return tui::run();
@@ -626,39 +1372,55 @@ fn main() -> Result<(), Box<dyn Error>> {
         // Non-interactive or not a terminal → show help
         use clap::CommandFactory;
         Cli::command().print_help()?;
-        println!();
+        say!();
         return Ok(());
     }
 
     // Auto-elevate for commands that need admin privileges
-    if !cli.skip_elevation && !cli.dry_run {
-        let needs_admin = matches!(
-            &cli.command,
-            Some(Commands::Install { .. })
-                | Some(Commands::Uninstall { .. })
-                | Some(Commands::Reinstall { .. })
-                | Some(Commands::Apply { .. })
-                | Some(Commands::Watch { .. })
-                | Some(Commands::Service { .. })
-        );
-        if needs_admin && !elevation::is_elevated() {
-            println!("[INFO] Requesting administrator privileges...");
+    let for_packaging = cli.command.as_ref().is_some_and(Commands::for_packaging);
+    if !cli.skip_elevation && !cli.dry_run && !for_packaging {
+        let needs_admin = cli.command.as_ref().is_some_and(Commands::needs_admin);
+        if needs_admin && !timing::phase("elevation check", elevation::is_elevated) {
+            say!("[INFO] Requesting administrator privileges...");
             elevation::relaunch_elevated()?;
         }
     }
 
-    // CLI mode — console logger
-    env_logger::Builder::new()
-        .filter_level(if cli.verbose {
-            log::LevelFilter::Debug
-        } else {
-            log::LevelFilter::Warn
-        })
-        .format_timestamp(None)
-        .init();
+    // CLI mode — console logger; `watch` also keeps a log file and can
+    // mirror to the Event Log.
+    timing::phase("logger init", || {
+        #[cfg(feature = "service")]
+        if let Some(Commands::Watch {
+            event_log,
+            no_log_file,
+            ..
+        }) = &cli.command
+        {
+            let log_file = (!no_log_file).then(app_state::watch_log_path);
+            watch_log::init(verbosity, log_file.as_deref(), *event_log);
+            return;
+        }
+        env_logger::Builder::new()
+            .filter_level(verbosity.level())
+            .format_timestamp(None)
+            .init()
+    });
+    // Only looked up if a solar schedule actually needs the position.
+    #[cfg(feature = "service")]
+    lg_service::enable_windows_location();
+    // `config validate` reports malformed match expressions too.
+    validate::set_pattern_check(lg_monitor::check_match_pattern);
+
+    let result = run_command(cli, verbosity);
+    output::finish(&result);
+    result
+}
 
+/// Run the subcommand `main` didn't handle itself.
+fn run_command(cli: Cli, verbosity: Verbosity) -> Result<(), Box<dyn Error>> {
     match cli.command {
-        None => unreachable!(), // handled above
+        None => unreachable!(), // handled in main
+        #[cfg(feature = "service")]
         Some(Commands::Install {
             pattern,
             regex,
@@ -667,54 +1429,113 @@ fn main() -> Result<(), Box<dyn Error>> {
             profile_path,
             per_user,
             skip_hdr,
+            generic_default,
             skip_hash_check,
             force,
             skip_detect,
-        }) => cmd_install(InstallOpts {
+            for_packaging,
+            json,
+        }) => {
+            let opts = InstallOpts {
+                pattern,
+                regex,
+                profile_only,
+                service_only,
+                custom_profile: profile_path,
+                per_user,
+                skip_hdr,
+                generic_default,
+                skip_hash_check,
+                force,
+                skip_detect,
+                dry_run: cli.dry_run,
+                json: plan_json(json, cli.dry_run, output::format())?,
+                for_packaging,
+            };
+            if for_packaging && !cli.dry_run {
+                run_for_packaging(|| cmd_install(opts));
+            }
+            cmd_install(opts)?
+        }
+        #[cfg(feature = "service")]
+        Some(Commands::Uninstall {
+            full,
+            profile,
+            feedback,
+            for_packaging,
+            json,
+        }) => {
+            let json = plan_json(json, cli.dry_run, output::format())?;
+            if for_packaging && !cli.dry_run {
+                run_for_packaging(|| cmd_uninstall(full, profile, feedback, false, false));
+            }
+            cmd_uninstall(full, profile, feedback, cli.dry_run, json)?
+        }
+        #[cfg(feature = "service")]
+        Some(Commands::Reinstall {
             pattern,
             regex,
-            profile_only,
-            service_only,
-            custom_profile: profile_path,
-            per_user,
-            skip_hdr,
-            skip_hash_check,
-            force,
-            skip_detect,
-            dry_run: cli.dry_run,
-        })?,
-        Some(Commands::Uninstall { full, profile }) => cmd_uninstall(full, profile, cli.dry_run)?,
-        Some(Commands::Reinstall { pattern, regex }) => cmd_reinstall(pattern, regex, cli.dry_run)?,
+            json,
+        }) => cmd_reinstall(
+            pattern,
+            regex,
+            cli.dry_run,
+            plan_json(json, cli.dry_run, output::format())?,
+        )?,
         Some(Commands::Detect { pattern, regex }) => cmd_detect(pattern, regex)?,
         Some(Commands::Apply {
             pattern,
             regex,
             profile_path,
+            profile_name,
+            device_key,
             per_user,
             skip_hdr,
+            generic_default,
             toast,
             no_toast,
         }) => cmd_apply(ApplyOpts {
             pattern,
             regex,
             profile_path,
+            profile_name,
+            device_keys: device_key,
             per_user,
             skip_hdr,
+            generic_default,
             toast,
             no_toast,
-            verbose: cli.verbose,
+            verbose: verbosity.is_verbose(),
             dry_run: cli.dry_run,
         })?,
-        Some(Commands::Watch { pattern, regex }) => cmd_watch(pattern, regex)?,
-        Some(Commands::Config { action }) => cmd_config(action)?,
+        #[cfg(feature = "service")]
+        Some(Commands::Watch { pattern, regex, .. }) => cmd_watch(pattern, regex)?,
+        Some(Commands::Config { action }) => cmd_config(action, cli.dry_run)?,
+        #[cfg(feature = "service")]
         Some(Commands::Service { action }) => cmd_service(action)?,
+        #[cfg(feature = "service")]
+        Some(Commands::Telemetry { action }) => cmd_telemetry(action, cli.dry_run)?,
         Some(Commands::Test { action }) => cmd_test(action)?,
+        Some(Commands::Measure { action }) => cmd_measure(action, cli.dry_run)?,
         Some(Commands::Icc { action }) => cmd_icc(action, cli.dry_run)?,
-        Some(Commands::Ddc { action }) => cmd_ddc(action, cli.dry_run)?,
+        #[cfg(feature = "ddc")]
+        Some(Commands::Ddc { action }) => {
+            cmd_ddc(action, cli.dry_run).map_err(explain_ddc_error)?
+        }
+        Some(Commands::Display { action }) => cmd_display(action, cli.dry_run)?,
+        Some(Commands::Colorstore { action }) => cmd_colorstore(action, cli.dry_run)?,
+        Some(Commands::Audit { action }) => cmd_audit(action)?,
+        Some(Commands::Quirks { action }) => cmd_quirks(action)?,
         Some(Commands::Automation { action }) => cmd_automation(action, cli.dry_run)?,
         Some(Commands::Tray { action }) => cmd_tray(action, cli.dry_run)?,
         Some(Commands::Bundle { action }) => cmd_bundle(action, cli.dry_run)?,
         Some(Commands::Probe { pattern, regex }) => cmd_probe(pattern, regex)?,
+        Some(Commands::Bench { iterations }) => cmd_bench(iterations)?,
+        Some(Commands::Profile { action }) => cmd_profile(action, cli.dry_run)?,
+        #[cfg(feature = "service")]
+        Some(Commands::Quick { .. }) => unreachable!(), // handled in main
+        #[cfg(feature = "notify")]
+        Some(Commands::SessionToast { .. }) => unreachable!(), // handled in main
     }
 
     Ok(())
@@ -724,6 +1545,16 @@ fn main() -> Result<(), Box<dyn Error>> {
 // Command implementations
 // ============================================================================
 
+/// Report a classified DDC failure by its message; `main` prints errors in
+/// `Debug` form, which for a [`lg_monitor::ddc::DdcError`] is the raw struct.
+#[cfg(feature = "ddc")]
+fn explain_ddc_error(err: Box<dyn Error>) -> Box<dyn Error> {
+    match lg_monitor::ddc::error_kind(err.as_ref()) {
+        Some(_) => err.to_string().into(),
+        None => err,
+    }
+}
+
 fn resolve_active_profile_path(cfg: &Config) -> std::path::PathBuf {
     resolve_active_profile_path_for_mode(cfg, false)
 }
@@ -778,13 +1609,14 @@ fn sync_mode_presets_to_active(cfg: &mut Config) {
 }
 
 fn effective_preset_for_mode(cfg: &Config, hdr_mode: bool) -> String {
-    lg_profile::select_effective_preset(
+    lg_profile::select_effective_preset_for_period(
         &cfg.icc_active_preset,
         &cfg.icc_sdr_preset,
         &cfg.icc_hdr_preset,
         &cfg.icc_schedule_day_preset,
         &cfg.icc_schedule_night_preset,
         hdr_mode,
+        lg_core::schedule::is_daytime_now(cfg),
     )
 }
 
@@ -829,6 +1661,17 @@ fn monitor_match_mode(use_regex: bool) -> lg_monitor::MonitorMatchMode {
     }
 }
 
+/// How `pattern` is matched, for `detect` and `probe`.
+fn match_mode_label(pattern: &str, use_regex: bool) -> &'static str {
+    if lg_monitor::match_expr::is_expression(pattern) {
+        "expression"
+    } else if use_regex {
+        "regex"
+    } else {
+        "substring"
+    }
+}
+
 fn find_matching_monitors(
     pattern: &str,
     use_regex: bool,
@@ -864,6 +1707,7 @@ fn maybe_capture_last_good_cli(
     }
 }
 
+#[cfg(feature = "ddc")]
 fn ddc_guardrail_error(vcp_code: u8, value: u32) -> Option<String> {
     let guardrails = app_state::load_ddc_guardrails();
     if guardrails.enabled && vcp_code == lg_monitor::ddc::VCP_BRIGHTNESS {
@@ -918,22 +1762,84 @@ fn emit_apply_latency_cli(started: Instant, success: bool, details: &str) {
     );
 }
 
+/// Start an install report when `install_report` is on.
+fn begin_install_report_cli(cfg: &Config, action: &str) -> Option<InstallReport> {
+    if !cfg.install_report {
+        return None;
+    }
+    let mut report = InstallReport::new(action, "cli");
+    report.color_directory = lg_profile::color_directory().display().to_string();
+    report.association_api = lg_profile::wcs_api_path().label().to_string();
+    Some(report)
+}
+
+/// Record the SDR/HDR (or custom) profiles `install` placed in the color store.
+#[cfg(feature = "service")]
+fn add_installed_profiles(report: &mut InstallReport, cfg: &Config, custom: Option<&str>) {
+    if let Some(custom) = custom {
+        report.add_profile("custom", Path::new(custom));
+        return;
+    }
+    let color_dir = lg_profile::color_directory();
+    for (role, hdr) in [("sdr", false), ("hdr", true)] {
+        let preset = effective_preset_for_mode(cfg, hdr);
+        report.add_profile(
+            role,
+            &lg_profile::resolve_active_profile_path(&color_dir, &preset, &cfg.profile_name),
+        );
+    }
+}
+
+/// When profile registration failed on a locked file, list who holds it.
+fn explain_profile_lock(e: &(dyn Error + 'static)) {
+    let Some(locked) = e.downcast_ref::<lg_profile::ProfileLockedError>() else {
+        return;
+    };
+    say!(
+        "[WARN] {} is locked by another process",
+        locked.path.display()
+    );
+    for holder in &locked.holders {
+        say!("[WARN]   held by {}", holder);
+    }
+    say!(
+        "[NOTE] Close the program(s) above, or wait for the color store scan to finish, and retry."
+    );
+}
+
+/// Stamp the outcome on `report` and write it; a failed write only warns.
+fn finish_install_report_cli(report: Option<InstallReport>, error: Option<String>) {
+    let Some(mut report) = report else {
+        return;
+    };
+    report.success = error.is_none();
+    report.error = error;
+    match report::write_install_report(&report) {
+        Ok(path) => say!("[INFO] Install report: {}", path.display()),
+        Err(e) => say!("[WARN] Could not write install report: {}", e),
+    }
+}
+
+#[cfg(feature = "ddc")]
 fn is_risky_vcp_write(vcp_code: u8, automation_cfg: &app_state::AutomationConfig) -> bool {
     app_state::risky_vcp_codes_from_csv(&automation_cfg.ddc_safety.risky_vcp_codes)
         .into_iter()
         .any(|code| code == vcp_code)
 }
 
+#[cfg(feature = "ddc")]
 fn prompt_confirm_risky_write(vcp_code: u8, value: u32) -> io::Result<bool> {
-    println!(
+    say!(
         "[WARN] Risky VCP write detected: 0x{:02X}={}. Type YES to continue.",
-        vcp_code, value
+        vcp_code,
+        value
     );
     let mut input = String::new();
     std::io::stdin().read_line(&mut input)?;
     Ok(matches!(input.trim(), "YES" | "yes"))
 }
 
+#[cfg(feature = "ddc")]
 fn wait_for_keep_key(timeout_ms: u64, keep_key: &str) -> io::Result<bool> {
     if keep_key.trim().is_empty() {
         return Ok(false);
@@ -970,6 +1876,7 @@ fn wait_for_keep_key(timeout_ms: u64, keep_key: &str) -> io::Result<bool> {
     Ok(false)
 }
 
+#[cfg(feature = "ddc")]
 fn set_vcp_with_safety(pattern: &str, vcp_code: u8, value: u32) -> Result<(), Box<dyn Error>> {
     let automation_cfg = app_state::load_automation_config();
     let risky = is_risky_vcp_write(vcp_code, &automation_cfg);
@@ -999,7 +1906,7 @@ fn set_vcp_with_safety(pattern: &str, vcp_code: u8, value: u32) -> Result<(), Bo
     {
         let timeout_ms = automation_cfg.ddc_safety.rollback_timeout_ms;
         let keep_key = automation_cfg.ddc_safety.keep_key.trim().to_string();
-        println!(
+        say!(
             "[SAFE] Press {} within {} ms to keep this VCP write. Otherwise it will roll back.",
             if keep_key.is_empty() { "K" } else { &keep_key },
             timeout_ms
@@ -1018,9 +1925,10 @@ fn set_vcp_with_safety(pattern: &str, vcp_code: u8, value: u32) -> Result<(), Bo
                     "ddc_rollback",
                     &format!("rolled back 0x{:02X} from {} to {}", vcp_code, value, prev),
                 );
-                println!(
+                say!(
                     "[SAFE] Rollback applied for VCP 0x{:02X} -> {}",
-                    vcp_code, prev
+                    vcp_code,
+                    prev
                 );
             }
         } else {
@@ -1037,25 +1945,27 @@ fn set_vcp_with_safety(pattern: &str, vcp_code: u8, value: u32) -> Result<(), Bo
 }
 
 fn cmd_detect(pattern: Option<String>, regex: bool) -> Result<(), Box<dyn Error>> {
-    let cfg = Config::load();
+    let cfg = timing::phase("config load", Config::load);
     let pattern = pattern.as_deref().unwrap_or(&cfg.monitor_match);
     let use_regex = effective_regex(regex, &cfg);
 
-    println!(
+    say!(
         "Scanning for monitors matching \"{}\" (mode: {})...\n",
         pattern,
-        if use_regex { "regex" } else { "substring" }
+        match_mode_label(pattern, use_regex)
     );
 
-    let devices = find_matching_monitors(pattern, use_regex)?;
+    let devices = timing::phase("monitor scan (WMI)", || {
+        find_matching_monitors(pattern, use_regex)
+    })?;
     if devices.is_empty() {
-        println!("No matching monitors found.");
+        say!("No matching monitors found.");
     } else {
-        println!("Found {} monitor(s):\n", devices.len());
+        say!("Found {} monitor(s):\n", devices.len());
         for (i, device) in devices.iter().enumerate() {
-            println!("  {}. {}", i + 1, device.name);
-            println!("     Device: {}", device.device_key);
-            println!(
+            say!("  {}. {}", i + 1, device.name);
+            say!("     Device: {}", device.device_key);
+            say!(
                 "     Serial: {}",
                 if device.serial.is_empty() {
                     "(unknown)"
@@ -1063,13 +1973,15 @@ fn cmd_detect(pattern: Option<String>, regex: bool) -> Result<(), Box<dyn Error>
                     &device.serial
                 }
             );
+            say!("     Fields: {}", or_unknown(&device.field_expression()));
         }
     }
 
-    let active_profile_path = resolve_active_profile_path(&cfg);
-    println!("\nProfile: {}", active_profile_path.display());
-    let _ = ensure_active_profile(&cfg);
-    println!(
+    // One ensure pass; its path is the resolved one unless generation failed.
+    let ensured = timing::phase("profile ensure", || ensure_active_profile(&cfg));
+    let active_profile_path = ensured.unwrap_or_else(|_| resolve_active_profile_path(&cfg));
+    say!("\nProfile: {}", active_profile_path.display());
+    say!(
         "Installed: {}",
         if lg_profile::is_profile_installed(&active_profile_path) {
             "yes"
@@ -1081,15 +1993,260 @@ fn cmd_detect(pattern: Option<String>, regex: bool) -> Result<(), Box<dyn Error>
     Ok(())
 }
 
+/// `apply` side of the [`ReapplyPipeline`]: prints progress and fills the
+/// install report.
+struct CliApplySteps<'a> {
+    cfg: &'a Config,
+    use_regex: bool,
+    /// `--profile-path`, used for every monitor and both modes.
+    custom_profile: Option<&'a Path>,
+    sdr_preset: &'a str,
+    hdr_preset: &'a str,
+    /// `--device-key` targets; when set, names aren't matched at all.
+    device_keys: &'a [String],
+    /// Device keys of every matching monitor.
+    connected: Vec<String>,
+    /// Toast placeholder values.
+    tally: ToastTally,
+    report: Option<InstallReport>,
+}
+
+impl ReapplySteps for CliApplySteps<'_> {
+    type Monitor = lg_monitor::MatchedMonitor;
+
+    fn ensure_shared_profiles(&mut self) -> Result<Option<ModeProfiles>, Box<dyn Error>> {
+        if let Some(path) = self.custom_profile {
+            lg_profile::ensure_profile_installed_with_gamma_luminance_and_tuning(
+                path,
+                self.cfg.icc_gamma,
+                self.cfg.icc_luminance_cd_m2,
+                tuning_from_config(self.cfg),
+            )
+            .inspect_err(|e| explain_profile_lock(e.as_ref()))?;
+            return Ok(Some(ModeProfiles::single(path.to_path_buf())));
+        }
+        if self.cfg.icc_per_monitor_profiles {
+            return Ok(None);
+        }
+        let (sdr, hdr) = lg_profile::ensure_mode_profiles_installed_tuned(
+            &lg_profile::color_directory(),
+            self.sdr_preset,
+            self.hdr_preset,
+            &self.cfg.profile_name,
+            self.cfg.icc_gamma,
+            self.cfg.icc_luminance_cd_m2,
+            self.cfg.icc_generate_specialized_profiles,
+            tuning_from_config(self.cfg),
+        )
+        .inspect_err(|e| explain_profile_lock(e.as_ref()))?;
+        Ok(Some(ModeProfiles { sdr, hdr }))
+    }
+
+    fn is_installed(&self, path: &Path) -> bool {
+        lg_profile::is_profile_installed(path)
+    }
+
+    fn find_monitors(&mut self) -> Result<Vec<lg_monitor::MatchedMonitor>, Box<dyn Error>> {
+        let devices = if self.device_keys.is_empty() {
+            find_matching_monitors(&self.cfg.monitor_match, self.use_regex)?
+        } else {
+            self.device_keys
+                .iter()
+                .map(|key| monitor_for_device_key(key))
+                .collect()
+        };
+        self.connected = devices.iter().map(|d| d.device_key.clone()).collect();
+        warn_model_quirks(&devices);
+        Ok(devices)
+    }
+
+    fn inherit_associations(&mut self, device: &lg_monitor::MatchedMonitor) {
+        say!("[INFO] Found: {}", device.name);
+        match lg_profile::inherit_previous_device_associations(&device.device_key, &self.connected)
+        {
+            Ok(Some(previous)) => say!(
+                "[INFO] Carried {} association(s) over from {} (new device instance after a driver update?)",
+                previous.profiles.len(),
+                previous.device_key
+            ),
+            Ok(None) => {}
+            Err(e) => say!("[WARN] Could not check previous device associations: {}", e),
+        }
+    }
+
+    fn ensure_monitor_profiles(
+        &mut self,
+        device: &lg_monitor::MatchedMonitor,
+    ) -> Result<ModeProfiles, Box<dyn Error>> {
+        let (sdr, hdr) = lg_profile::ensure_mode_profiles_installed_tuned_for_monitor(
+            &lg_profile::color_directory(),
+            self.sdr_preset,
+            self.hdr_preset,
+            &self.cfg.profile_name,
+            self.cfg.icc_gamma,
+            self.cfg.icc_luminance_cd_m2,
+            self.cfg.icc_generate_specialized_profiles,
+            tuning_from_config(self.cfg),
+            &identity_from_monitor(device),
+        )?;
+        Ok(ModeProfiles { sdr, hdr })
+    }
+
+    fn associate(
+        &mut self,
+        device: &lg_monitor::MatchedMonitor,
+        active: &Path,
+        sdr: &Path,
+        hdr: Option<&Path>,
+        per_user: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        lg_profile::reapply_profile_with_mode_associations(
+            &device.device_key,
+            active,
+            sdr,
+            hdr,
+            self.cfg.toggle_delay_ms,
+            per_user,
+        )
+    }
+
+    fn set_generic_default(
+        &mut self,
+        device: &lg_monitor::MatchedMonitor,
+        active: &Path,
+        per_user: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        lg_profile::set_generic_default(&device.device_key, active, per_user)
+    }
+
+    fn refresh(&mut self) {
+        // Keep post-apply refresh non-disruptive; hard refresh is handled as
+        // an internal fallback inside lg_profile when verification fails.
+        lg_profile::refresh_display(
+            false,
+            self.cfg.refresh_broadcast_color,
+            self.cfg.refresh_invalidate,
+        );
+        lg_profile::trigger_calibration_loader(self.cfg.refresh_calibration_loader);
+    }
+
+    fn set_ddc_brightness(&mut self, level: u32) -> Result<usize, Box<dyn Error>> {
+        #[cfg(feature = "ddc")]
+        {
+            lg_monitor::ddc::set_brightness_all(level)
+        }
+        #[cfg(not(feature = "ddc"))]
+        {
+            Err(format!("cannot set brightness {}: built without DDC/CI", level).into())
+        }
+    }
+
+    fn toast(&mut self) {
+        #[cfg(feature = "notify")]
+        {
+            if self.cfg.toast_enabled {
+                say!("[INFO] Sending toast notification...");
+            }
+            let values = self.tally.values();
+            lg_notify::show_reapply_toast_quiet_aware(
+                self.cfg.toast_enabled,
+                &toast_template::render(&self.cfg.toast_title, &values),
+                &toast_template::render(i18n::toast_body(self.cfg), &values),
+                lg_notify::QuietHours::parse(&self.cfg.toast_quiet_hours),
+                self.cfg.verbose,
+            );
+            lg_notify::show_reapply_osd(
+                self.cfg.osd_enabled,
+                &self.cfg.osd_position,
+                self.cfg.osd_duration_ms,
+            );
+        }
+    }
+
+    fn monitor_done(&mut self, device: &lg_monitor::MatchedMonitor, result: &MonitorResult<'_>) {
+        if let Some(report) = self.report.as_mut() {
+            let path_text =
+                |p: Option<&Path>| p.map(|p| p.display().to_string()).unwrap_or_default();
+            if let Some(profiles) = result.profiles {
+                report.add_profile("sdr", &profiles.sdr);
+                report.add_profile("hdr", &profiles.hdr);
+            }
+            report.devices.push(DeviceEntry {
+                name: device.name.clone(),
+                device_key: device.device_key.clone(),
+                active_profile: path_text(result.active),
+                sdr_profile: path_text(result.profiles.map(|p| p.sdr.as_path())),
+                hdr_profile: path_text(result.profiles.map(|p| p.hdr.as_path())),
+                steps: result.steps.clone(),
+            });
+        }
+        match result.error {
+            Some(e) => {
+                fail!("[FAIL] {}: {}", device.name, e);
+                explain_profile_lock(e);
+            }
+            None => {
+                say!("[OK]   SDR/HDR profiles associated for {}", device.name);
+                if let Some(active) = result.active {
+                    self.tally.record(&device.name, active);
+                }
+            }
+        }
+    }
+}
+
+/// The monitor behind `--device-key`, with its WMI identity when WMI lists
+/// it. Interface paths (`\\?\DISPLAY#...#{guid}`) are accepted as well.
+fn monitor_for_device_key(key: &str) -> lg_monitor::MatchedMonitor {
+    let device_key = lg_monitor::device_key_from_interface_path(key);
+    match lg_monitor::find_monitor_by_device_key(&device_key) {
+        Ok(Some(monitor)) => monitor,
+        Ok(None) => {
+            say!(
+                "[WARN] {} is not listed by WMI; applying by key only",
+                device_key
+            );
+            bare_monitor(device_key)
+        }
+        Err(e) => {
+            say!("[WARN] WMI lookup for {} failed: {}", device_key, e);
+            bare_monitor(device_key)
+        }
+    }
+}
+
+fn bare_monitor(device_key: String) -> lg_monitor::MatchedMonitor {
+    lg_monitor::MatchedMonitor {
+        name: device_key.clone(),
+        device_key,
+        serial: String::new(),
+        manufacturer_id: String::new(),
+        product_code: String::new(),
+    }
+}
+
+/// DDC/CI brightness for a reapply, when `ddc_brightness_on_reapply` is on
+/// and dxva2 is available.
+fn ddc_reapply_level_cli(cfg: &Config) -> Option<u32> {
+    #[cfg(feature = "ddc")]
+    let supported = lg_monitor::ddc::is_supported();
+    #[cfg(not(feature = "ddc"))]
+    let supported = false;
+    (cfg.ddc_brightness_on_reapply && supported).then_some(cfg.ddc_brightness_value)
+}
+
 /// Options for apply command (avoids too-many-arguments lint).
 struct ApplyOpts {
     pattern: Option<String>,
     regex: bool,
     profile_path: Option<String>,
-    #[allow(dead_code)]
+    /// Installed profile to apply instead of a file path.
+    profile_name: Option<String>,
+    /// Explicit device keys; empty means match by pattern.
+    device_keys: Vec<String>,
     per_user: bool,
-    #[allow(dead_code)]
     skip_hdr: bool,
+    generic_default: bool,
     toast: bool,
     no_toast: bool,
     verbose: bool,
@@ -1111,22 +2268,23 @@ fn cmd_apply(opts: ApplyOpts) -> Result<(), Box<dyn Error>> {
     } else if opts.no_toast {
         cfg.toast_enabled = false;
     }
-    let using_custom_profile = opts.profile_path.is_some();
-    let include_hdr_association = !opts.skip_hdr;
+    let custom_profile = match &opts.profile_name {
+        Some(name) => Some(lg_profile::resolve_installed_profile(name)?),
+        None => opts.profile_path.as_deref().map(PathBuf::from),
+    };
+    let include_hdr_association = cfg.icc_hdr_association && !opts.skip_hdr;
     let active_hdr_mode = lg_monitor::is_any_display_hdr_enabled().unwrap_or(false);
     let use_regex = effective_regex(opts.regex, &cfg);
     let sdr_preset = effective_preset_for_mode(&cfg, false);
     let hdr_preset = effective_preset_for_mode(&cfg, true);
-    let mut sdr_shared_profile = if using_custom_profile {
-        std::path::PathBuf::from(opts.profile_path.as_deref().unwrap_or_default())
-    } else {
+    let sdr_shared_profile = custom_profile.clone().unwrap_or_else(|| {
         lg_profile::resolve_active_profile_path(
             &lg_profile::color_directory(),
             &sdr_preset,
             &cfg.profile_name,
         )
-    };
-    let mut hdr_shared_profile = if using_custom_profile {
+    });
+    let hdr_shared_profile = if custom_profile.is_some() || !include_hdr_association {
         sdr_shared_profile.clone()
     } else {
         lg_profile::resolve_active_profile_path(
@@ -1135,9 +2293,6 @@ fn cmd_apply(opts: ApplyOpts) -> Result<(), Box<dyn Error>> {
             &cfg.profile_name,
         )
     };
-    if !include_hdr_association {
-        hdr_shared_profile = sdr_shared_profile.clone();
-    }
     let active_profile = if active_hdr_mode {
         hdr_shared_profile.clone()
     } else {
@@ -1151,7 +2306,13 @@ fn cmd_apply(opts: ApplyOpts) -> Result<(), Box<dyn Error>> {
         &format!(
             "pattern=\"{}\" mode={} profile={} sdr={} hdr={} hdr_assoc={}",
             cfg.monitor_match,
-            if use_regex { "regex" } else { "substring" },
+            if !opts.device_keys.is_empty() {
+                "device_key"
+            } else if use_regex {
+                "regex"
+            } else {
+                "substring"
+            },
             active_profile.display(),
             sdr_shared_profile.display(),
             hdr_shared_profile.display(),
@@ -1159,26 +2320,38 @@ fn cmd_apply(opts: ApplyOpts) -> Result<(), Box<dyn Error>> {
         ),
     );
 
-    println!("[INFO] Running one-shot profile reapply...");
-    println!("[INFO] Config:  {}", config::config_path().display());
-    println!("[INFO] Pattern: {}", cfg.monitor_match);
-    println!(
-        "[INFO] Match:   {}",
-        if use_regex { "regex" } else { "substring" }
-    );
-    println!("[INFO] Active Profile: {}", active_profile.display());
-    println!("[INFO] SDR Profile:    {}", sdr_shared_profile.display());
-    println!("[INFO] HDR Profile:    {}", hdr_shared_profile.display());
-    println!("[INFO] HDR Assoc:      {}", include_hdr_association);
-    println!(
+    say!("[INFO] Running one-shot profile reapply...");
+    say!("[INFO] Config:  {}", config::config_path().display());
+    if opts.device_keys.is_empty() {
+        say!("[INFO] Pattern: {}", cfg.monitor_match);
+        say!(
+            "[INFO] Match:   {}",
+            if use_regex { "regex" } else { "substring" }
+        );
+    } else {
+        say!("[INFO] Devices: {}", opts.device_keys.join(", "));
+    }
+    say!("[INFO] Active Profile: {}", active_profile.display());
+    say!("[INFO] SDR Profile:    {}", sdr_shared_profile.display());
+    say!("[INFO] HDR Profile:    {}", hdr_shared_profile.display());
+    say!("[INFO] HDR Assoc:      {}", include_hdr_association);
+    say!(
         "[INFO] Toast:   {}",
         if cfg.toast_enabled { "on" } else { "off" }
     );
-    println!();
+    say!();
 
     if opts.dry_run {
-        let devices = find_matching_monitors(&cfg.monitor_match, use_regex)?;
-        println!(
+        let devices = if opts.device_keys.is_empty() {
+            find_matching_monitors(&cfg.monitor_match, use_regex)?
+        } else {
+            opts.device_keys
+                .iter()
+                .map(|key| monitor_for_device_key(key))
+                .collect()
+        };
+        warn_model_quirks(&devices);
+        say!(
             "[DRY RUN] Would reapply mode-aware profiles for {} matching monitor(s)",
             devices.len()
         );
@@ -1192,126 +2365,90 @@ fn cmd_apply(opts: ApplyOpts) -> Result<(), Box<dyn Error>> {
         return Ok(());
     }
 
-    if using_custom_profile {
-        lg_profile::ensure_profile_installed_with_gamma_luminance_and_tuning(
-            &sdr_shared_profile,
-            cfg.icc_gamma,
-            cfg.icc_luminance_cd_m2,
-            tuning_from_config(&cfg),
-        )?;
-    } else if !cfg.icc_per_monitor_profiles {
-        let (sdr_path, hdr_path) = lg_profile::ensure_mode_profiles_installed_tuned(
-            &lg_profile::color_directory(),
-            &sdr_preset,
-            &hdr_preset,
-            &cfg.profile_name,
-            cfg.icc_gamma,
-            cfg.icc_luminance_cd_m2,
-            cfg.icc_generate_specialized_profiles,
-            tuning_from_config(&cfg),
-        )?;
-        sdr_shared_profile = sdr_path;
-        hdr_shared_profile = if include_hdr_association {
-            hdr_path
-        } else {
-            sdr_shared_profile.clone()
-        };
-    }
-
-    if !cfg.icc_per_monitor_profiles
-        && (!lg_profile::is_profile_installed(&sdr_shared_profile)
-            || !lg_profile::is_profile_installed(&hdr_shared_profile))
-    {
-        return Err(format!(
-            "ICC mode profile not found (sdr={}, hdr={})",
-            sdr_shared_profile.display(),
-            hdr_shared_profile.display()
-        )
-        .into());
+    let mut report = begin_install_report_cli(&cfg, "apply");
+    if let Some(report) = report.as_mut() {
+        report.per_user_requested = opts.per_user;
+        report.hdr_mode_active = active_hdr_mode;
     }
+    let mut steps = CliApplySteps {
+        cfg: &cfg,
+        use_regex,
+        custom_profile: custom_profile.as_deref(),
+        sdr_preset: &sdr_preset,
+        hdr_preset: &hdr_preset,
+        device_keys: &opts.device_keys,
+        connected: Vec::new(),
+        tally: ToastTally::new("apply", started),
+        report,
+    };
+    let pipeline = ReapplyPipeline::new(ReapplyOptions {
+        per_user: opts.per_user,
+        hdr: include_hdr_association,
+        hdr_active: active_hdr_mode,
+        generic_default: opts.generic_default,
+        ddc_brightness: ddc_reapply_level_cli(&cfg),
+        toast: cfg!(feature = "notify") && (cfg.toast_enabled || cfg.osd_enabled),
+    });
+    let outcome = match pipeline.run(&mut steps) {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            finish_install_report_cli(steps.report, Some(e.to_string()));
+            return Err(e.into());
+        }
+    };
 
-    let devices = find_matching_monitors(&cfg.monitor_match, use_regex)?;
-    let success = if devices.is_empty() {
-        println!("[SKIP] No matching monitors found.");
+    let text = i18n::strings(&cfg);
+    let mut failure = None;
+    let success = if outcome.found == 0 {
+        say!("[SKIP] {}", text.no_monitors);
         app_state::append_diagnostic_event("cli", "WARN", "apply_skip", "no matching monitors");
+        finish_install_report_cli(steps.report, Some("no matching monitors".to_string()));
         false
     } else {
-        let mut last_applied_profile: Option<std::path::PathBuf> = None;
-        for device in &devices {
-            println!("[INFO] Found: {}", device.name);
-            let (sdr_profile_for_device, hdr_profile_for_device) = if using_custom_profile {
-                (sdr_shared_profile.clone(), hdr_shared_profile.clone())
-            } else if cfg.icc_per_monitor_profiles {
-                let identity = identity_from_monitor(device);
-                let (sdr_path, hdr_path) =
-                    lg_profile::ensure_mode_profiles_installed_tuned_for_monitor(
-                        &lg_profile::color_directory(),
-                        &sdr_preset,
-                        &hdr_preset,
-                        &cfg.profile_name,
-                        cfg.icc_gamma,
-                        cfg.icc_luminance_cd_m2,
-                        cfg.icc_generate_specialized_profiles,
-                        tuning_from_config(&cfg),
-                        &identity,
-                    )?;
-                if include_hdr_association {
-                    (sdr_path, hdr_path)
-                } else {
-                    (sdr_path.clone(), sdr_path)
-                }
-            } else {
-                (sdr_shared_profile.clone(), hdr_shared_profile.clone())
-            };
-            let active_profile_for_device = if active_hdr_mode {
-                &hdr_profile_for_device
-            } else {
-                &sdr_profile_for_device
-            };
-            lg_profile::reapply_profile_with_mode_associations(
-                &device.device_key,
-                active_profile_for_device,
-                &sdr_profile_for_device,
-                &hdr_profile_for_device,
-                cfg.toggle_delay_ms,
-                opts.per_user,
-            )?;
-            last_applied_profile = Some(active_profile_for_device.clone());
-            println!("[OK]   SDR/HDR profiles associated for {}", device.name);
-        }
-
-        // Keep post-apply refresh non-disruptive; hard refresh is handled as
-        // an internal fallback inside lg_profile when verification fails.
-        lg_profile::refresh_display(false, cfg.refresh_broadcast_color, cfg.refresh_invalidate);
-        lg_profile::trigger_calibration_loader(cfg.refresh_calibration_loader);
-
-        if cfg.toast_enabled {
-            println!("[INFO] Sending toast notification...");
-            lg_notify::show_reapply_toast(true, &cfg.toast_title, &cfg.toast_body, cfg.verbose);
+        match &outcome.ddc {
+            Some(Ok(n)) => say!(
+                "[OK]   DDC brightness set to {} on {} monitor(s)",
+                cfg.ddc_brightness_value.min(100),
+                n
+            ),
+            Some(Err(e)) => say!("[WARN] DDC brightness failed: {} (non-fatal)", e),
+            None => {}
         }
-
-        if let Some(profile_path) = last_applied_profile.as_ref() {
+        if let Some(profile_path) = outcome.last_applied.as_ref() {
             maybe_capture_last_good_cli(
                 &cfg,
                 profile_path,
                 "CLI apply success",
-                if cfg.ddc_brightness_on_reapply {
-                    Some(cfg.ddc_brightness_value)
-                } else {
-                    None
-                },
+                pipeline.options().ddc_brightness,
             );
         }
-        app_state::append_diagnostic_event(
-            "cli",
-            "INFO",
-            "apply_success",
-            &format!("reapplied for {} monitor(s)", devices.len()),
-        );
-
-        println!("\n[DONE] All profiles reapplied.");
-        true
-    };
+        if let Some(e) = &outcome.first_error {
+            let message = format!(
+                "{}: {}",
+                i18n::ENGLISH.monitors_failed(outcome.failed(), outcome.found),
+                e
+            );
+            app_state::append_diagnostic_event("cli", "ERROR", "apply_error", &message);
+            finish_install_report_cli(steps.report, Some(message));
+            failure = Some(format!(
+                "{}: {}",
+                text.monitors_failed(outcome.failed(), outcome.found),
+                e
+            ));
+            false
+        } else {
+            app_state::append_diagnostic_event(
+                "cli",
+                "INFO",
+                "apply_success",
+                &format!("reapplied for {} monitor(s)", outcome.applied),
+            );
+            finish_install_report_cli(steps.report, None);
+            measure_after_apply_cli(&cfg);
+            say!("\n[DONE] {}", text.all_reapplied);
+            true
+        }
+    };
 
     emit_apply_latency_cli(
         started,
@@ -1324,9 +2461,124 @@ fn cmd_apply(opts: ApplyOpts) -> Result<(), Box<dyn Error>> {
         ),
     );
 
+    match failure {
+        Some(message) => Err(message.into()),
+        None => Ok(()),
+    }
+}
+
+/// `bench`: run real reapplies through [`TimedSteps`] and print how long
+/// each phase took, to compare builds with numbers. Toasts stay off so the
+/// run doesn't flood the screen; everything else is what `apply` does.
+fn cmd_bench(iterations: u32) -> Result<(), Box<dyn Error>> {
+    let mut timings = PhaseTimings::new();
+    let cfg = timings.time("config load", Config::load);
+    let hdr_active = timings.time("hdr query", || {
+        lg_monitor::is_any_display_hdr_enabled().unwrap_or(false)
+    });
+    let use_regex = effective_regex(false, &cfg);
+    let sdr_preset = effective_preset_for_mode(&cfg, false);
+    let hdr_preset = effective_preset_for_mode(&cfg, true);
+    let pipeline = ReapplyPipeline::new(ReapplyOptions {
+        per_user: false,
+        hdr: cfg.icc_hdr_association,
+        hdr_active,
+        generic_default: false,
+        ddc_brightness: ddc_reapply_level_cli(&cfg),
+        toast: false,
+    });
+
+    say!(
+        "[INFO] Timing {} reapplies for monitors matching \"{}\"...",
+        iterations,
+        cfg.monitor_match
+    );
+    let mut failures = 0;
+    for _ in 0..iterations {
+        let mut steps = CliApplySteps {
+            cfg: &cfg,
+            use_regex,
+            custom_profile: None,
+            sdr_preset: &sdr_preset,
+            hdr_preset: &hdr_preset,
+            device_keys: &[],
+            connected: Vec::new(),
+            tally: ToastTally::new("bench", Instant::now()),
+            report: None,
+        };
+        let started = Instant::now();
+        let outcome = pipeline.run(&mut TimedSteps::new(&mut steps, &mut timings))?;
+        timings.record(bench::PHASE_TOTAL, started.elapsed());
+        if outcome.found == 0 {
+            return Err("no matching monitors to reapply to".into());
+        }
+        failures += outcome.failed();
+    }
+
+    say!();
+    output::block(&timings.format_table());
+    if failures > 0 {
+        say!(
+            "[WARN] {} monitor reapply(s) failed; their phases are timed up to the failure",
+            failures
+        );
+    }
+    Ok(())
+}
+
+/// Take and print the `measure_command` reading after an apply.
+fn measure_after_apply_cli(cfg: &Config) {
+    match lg_core::measure::measure_after_reapply(cfg, "apply") {
+        Some(Ok(cd_m2)) => say!("[INFO] Luminance: {:.1} cd/m²", cd_m2),
+        Some(Err(e)) => {
+            say!("[WARN] Luminance measurement failed: {}", e);
+            app_state::append_diagnostic_event("cli", "WARN", "measure_error", &e.to_string());
+        }
+        None => {}
+    }
+}
+
+fn cmd_measure(action: MeasureAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    use lg_core::measure::{self, LuminanceReading};
+
+    let (cd_m2, source) = match action {
+        MeasureAction::Run => {
+            let cfg = Config::load();
+            if cfg.measure_command.trim().is_empty() {
+                return Err("measure_command is not set in the config".into());
+            }
+            if dry_run {
+                say!("[DRY RUN] Would run: {}", cfg.measure_command);
+                return Ok(());
+            }
+            say!("[INFO] Running: {}", cfg.measure_command);
+            let cd_m2 = measure::run_measure_command(
+                &cfg.measure_command,
+                Duration::from_millis(cfg.measure_timeout_ms),
+            )?;
+            (cd_m2, measure::SOURCE_COMMAND)
+        }
+        MeasureAction::Add { cd_m2 } => {
+            if !cd_m2.is_finite() || cd_m2 < 0.0 {
+                return Err(format!("invalid luminance {}", cd_m2).into());
+            }
+            (cd_m2, measure::SOURCE_MANUAL)
+        }
+    };
+    if dry_run {
+        say!("[DRY RUN] Would record {:.1} cd/m²", cd_m2);
+        return Ok(());
+    }
+    measure::append_reading(&LuminanceReading::now(cd_m2, source, "manual"))?;
+    say!(
+        "[OK] Recorded {:.1} cd/m² in {}",
+        cd_m2,
+        measure::luminance_log_path().display()
+    );
     Ok(())
 }
 
+#[cfg(feature = "service")]
 fn cmd_watch(pattern: Option<String>, regex: bool) -> Result<(), Box<dyn Error>> {
     let mut cfg = Config::load();
     if let Some(p) = pattern {
@@ -1335,167 +2587,342 @@ fn cmd_watch(pattern: Option<String>, regex: bool) -> Result<(), Box<dyn Error>>
     if regex {
         cfg.monitor_match_regex = true;
     }
-    lg_service::watch(&cfg)?;
+    lg_service::watch(&cfg, output::block)?;
     Ok(())
 }
 
-fn cmd_config(action: Option<ConfigAction>) -> Result<(), Box<dyn Error>> {
+fn cmd_config(action: Option<ConfigAction>, dry_run: bool) -> Result<(), Box<dyn Error>> {
     match action {
         None | Some(ConfigAction::Show) => {
             let cfg = Config::load();
             let path = config::config_path();
-            println!("Config file: {}\n", path.display());
-            println!("── Monitor Detection ──");
-            println!("  monitor_match            = \"{}\"", cfg.monitor_match);
-            println!("  monitor_match_regex      = {}", cfg.monitor_match_regex);
-            println!("  profile_name             = \"{}\"", cfg.profile_name);
-            println!("  icc_gamma                = {:.3}", cfg.icc_gamma);
-            println!("  icc_active_preset        = \"{}\"", cfg.icc_active_preset);
-            println!("  icc_sdr_preset           = \"{}\"", cfg.icc_sdr_preset);
-            println!("  icc_hdr_preset           = \"{}\"", cfg.icc_hdr_preset);
-            println!(
+            say!("Config file: {}", path.display());
+            say!("Config name: {}\n", config::active_config_name());
+            say!("── Monitor Detection ──");
+            say!("  monitor_match            = \"{}\"", cfg.monitor_match);
+            say!("  monitor_match_regex      = {}", cfg.monitor_match_regex);
+            say!("  profile_name             = \"{}\"", cfg.profile_name);
+            say!("  icc_gamma                = {:.3}", cfg.icc_gamma);
+            say!("  icc_active_preset        = \"{}\"", cfg.icc_active_preset);
+            say!("  icc_sdr_preset           = \"{}\"", cfg.icc_sdr_preset);
+            say!("  icc_hdr_preset           = \"{}\"", cfg.icc_hdr_preset);
+            say!(
                 "  icc_schedule_day_preset  = \"{}\"",
                 cfg.icc_schedule_day_preset
             );
-            println!(
+            say!(
                 "  icc_schedule_night_preset = \"{}\"",
                 cfg.icc_schedule_night_preset
             );
-            println!(
+            say!(
                 "  icc_generate_specialized_profiles = {}",
                 cfg.icc_generate_specialized_profiles
             );
-            println!(
+            say!(
                 "  icc_luminance_cd_m2      = {:.1}",
                 cfg.icc_luminance_cd_m2
             );
-            println!("  icc_tuning_preset        = \"{}\"", cfg.icc_tuning_preset);
-            println!(
+            say!("  icc_tuning_preset        = \"{}\"", cfg.icc_tuning_preset);
+            say!(
                 "  icc_tuning_overlay_manual = {}",
                 cfg.icc_tuning_overlay_manual
             );
-            println!("  icc_black_lift           = {:.3}", cfg.icc_black_lift);
-            println!("  icc_midtone_boost        = {:.3}", cfg.icc_midtone_boost);
-            println!(
+            say!("  icc_black_lift           = {:.3}", cfg.icc_black_lift);
+            say!("  icc_midtone_boost        = {:.3}", cfg.icc_midtone_boost);
+            say!(
                 "  icc_white_compression    = {:.3}",
                 cfg.icc_white_compression
             );
-            println!("  icc_gamma_r              = {:.3}", cfg.icc_gamma_r);
-            println!("  icc_gamma_g              = {:.3}", cfg.icc_gamma_g);
-            println!("  icc_gamma_b              = {:.3}", cfg.icc_gamma_b);
-            println!("  icc_vcgt_enabled         = {}", cfg.icc_vcgt_enabled);
-            println!("  icc_vcgt_strength        = {:.3}", cfg.icc_vcgt_strength);
-            println!(
+            say!("  icc_gamma_r              = {:.3}", cfg.icc_gamma_r);
+            say!("  icc_gamma_g              = {:.3}", cfg.icc_gamma_g);
+            say!("  icc_gamma_b              = {:.3}", cfg.icc_gamma_b);
+            say!("  icc_vcgt_enabled         = {}", cfg.icc_vcgt_enabled);
+            say!("  icc_vcgt_strength        = {:.3}", cfg.icc_vcgt_strength);
+            say!(
                 "  icc_target_black_cd_m2   = {:.3}",
                 cfg.icc_target_black_cd_m2
             );
-            println!(
+            say!(
                 "  icc_include_media_black_point = {}",
                 cfg.icc_include_media_black_point
             );
-            println!(
+            say!(
                 "  icc_include_device_descriptions = {}",
                 cfg.icc_include_device_descriptions
             );
-            println!(
+            say!(
                 "  icc_include_characterization_target = {}",
                 cfg.icc_include_characterization_target
             );
-            println!(
+            say!(
                 "  icc_include_viewing_cond_desc = {}",
                 cfg.icc_include_viewing_cond_desc
             );
-            println!(
+            say!(
                 "  icc_technology_signature = \"{}\"",
                 cfg.icc_technology_signature
             );
-            println!(
+            say!(
                 "  icc_ciis_signature       = \"{}\"",
                 cfg.icc_ciis_signature
             );
-            println!("  icc_cicp_enabled         = {}", cfg.icc_cicp_enabled);
-            println!("  icc_cicp_primaries       = {}", cfg.icc_cicp_primaries);
-            println!("  icc_cicp_transfer        = {}", cfg.icc_cicp_transfer);
-            println!("  icc_cicp_matrix          = {}", cfg.icc_cicp_matrix);
-            println!("  icc_cicp_full_range      = {}", cfg.icc_cicp_full_range);
-            println!("  icc_metadata_enabled     = {}", cfg.icc_metadata_enabled);
-            println!(
+            say!("  icc_cicp_enabled         = {}", cfg.icc_cicp_enabled);
+            say!("  icc_cicp_primaries       = {}", cfg.icc_cicp_primaries);
+            say!("  icc_cicp_transfer        = {}", cfg.icc_cicp_transfer);
+            say!("  icc_cicp_matrix          = {}", cfg.icc_cicp_matrix);
+            say!("  icc_cicp_full_range      = {}", cfg.icc_cicp_full_range);
+            say!("  icc_metadata_enabled     = {}", cfg.icc_metadata_enabled);
+            say!(
                 "  icc_include_calibration_datetime = {}",
                 cfg.icc_include_calibration_datetime
             );
-            println!(
+            say!(
                 "  icc_include_chromatic_adaptation = {}",
                 cfg.icc_include_chromatic_adaptation
             );
-            println!(
+            say!(
                 "  icc_include_chromaticity = {}",
                 cfg.icc_include_chromaticity
             );
-            println!(
+            say!(
                 "  icc_include_measurement  = {}",
                 cfg.icc_include_measurement
             );
-            println!(
+            say!(
                 "  icc_include_viewing_conditions = {}",
                 cfg.icc_include_viewing_conditions
             );
-            println!(
+            say!(
                 "  icc_include_spectral_scaffold = {}",
                 cfg.icc_include_spectral_scaffold
             );
-            println!(
+            say!(
                 "  icc_per_monitor_profiles = {}",
                 cfg.icc_per_monitor_profiles
             );
-            println!(
+            say!("  icc_hdr_association      = {}", cfg.icc_hdr_association);
+            say!(
                 "  icc_auto_apply_on_change = {}",
                 cfg.icc_auto_apply_on_change
             );
-            println!(
+            say!(
                 "  active_profile_path      = \"{}\"",
                 resolve_active_profile_path(&cfg).display()
             );
-            println!("\n── Toast Notifications ──");
-            println!("  toast_enabled            = {}", cfg.toast_enabled);
-            println!("  toast_title              = \"{}\"", cfg.toast_title);
-            println!("  toast_body               = \"{}\"", cfg.toast_body);
-            println!("\n── Timing ──");
-            println!("  stabilize_delay_ms       = {}", cfg.stabilize_delay_ms);
-            println!("  toggle_delay_ms          = {}", cfg.toggle_delay_ms);
-            println!("  reapply_delay_ms         = {}", cfg.reapply_delay_ms);
-            println!("\n── Refresh Methods ──");
-            println!(
+            say!("\n── Toast Notifications ──");
+            say!("  toast_enabled            = {}", cfg.toast_enabled);
+            say!("  toast_title              = \"{}\"", cfg.toast_title);
+            say!("  toast_body               = \"{}\"", cfg.toast_body);
+            say!("\n── On-screen Badge ──");
+            say!("  osd_enabled              = {}", cfg.osd_enabled);
+            say!("  osd_position             = \"{}\"", cfg.osd_position);
+            say!("  osd_duration_ms          = {}", cfg.osd_duration_ms);
+            say!("\n── Luminance Measurement ──");
+            say!("  measure_command          = \"{}\"", cfg.measure_command);
+            say!("  measure_timeout_ms       = {}", cfg.measure_timeout_ms);
+            say!("\n── Usage Ping ──");
+            say!("  telemetry_enabled        = {}", cfg.telemetry_enabled);
+            say!(
+                "  telemetry_endpoint       = \"{}\"",
+                cfg.telemetry_endpoint
+            );
+            say!("\n── Timing ──");
+            say!("  stabilize_delay_ms       = {}", cfg.stabilize_delay_ms);
+            say!("  toggle_delay_ms          = {}", cfg.toggle_delay_ms);
+            say!("  reapply_delay_ms         = {}", cfg.reapply_delay_ms);
+            say!("  defer_reapply            = {}", cfg.defer_reapply);
+            say!("  defer_reapply_idle_ms    = {}", cfg.defer_reapply_idle_ms);
+            say!("  defer_reapply_max_ms     = {}", cfg.defer_reapply_max_ms);
+            say!("  kvm_mode                 = {}", cfg.kvm_mode);
+            say!("  dock_mode                = {}", cfg.dock_mode);
+            say!("  min_matching_monitors    = {}", cfg.min_matching_monitors);
+            say!(
+                "  only_when_monitor_count  = {}",
+                cfg.only_when_monitor_count
+            );
+            say!(
+                "  skip_reapply_lid_closed  = {}",
+                cfg.skip_reapply_lid_closed
+            );
+            say!("\n── Refresh Methods ──");
+            say!(
                 "  refresh_display_settings = {}",
                 cfg.refresh_display_settings
             );
-            println!(
+            say!(
                 "  refresh_broadcast_color  = {}",
                 cfg.refresh_broadcast_color
             );
-            println!("  refresh_invalidate       = {}", cfg.refresh_invalidate);
-            println!(
+            say!("  refresh_invalidate       = {}", cfg.refresh_invalidate);
+            say!(
                 "  refresh_calibration_loader = {}",
                 cfg.refresh_calibration_loader
             );
-            println!("\n── DDC/CI Brightness ──");
-            println!(
+            say!(
+                "  postpone_refresh_in_fullscreen = {}",
+                cfg.postpone_refresh_in_fullscreen
+            );
+            say!("\n── DDC/CI Brightness ──");
+            say!(
                 "  ddc_brightness_on_reapply = {}",
                 cfg.ddc_brightness_on_reapply
             );
-            println!("  ddc_brightness_value      = {}", cfg.ddc_brightness_value);
-            println!("\n── Debug ──");
-            println!("  verbose                  = {}", cfg.verbose);
+            say!("  ddc_brightness_value      = {}", cfg.ddc_brightness_value);
+            say!("\n── SDR White Level ──");
+            say!("  sdr_white_level_nits     = {}", cfg.sdr_white_level_nits);
+            say!("\n── Display Mode Guard ──");
+            say!("  display_mode_guard       = {}", cfg.display_mode_guard);
+            say!("\n── Debug ──");
+            say!("  verbose                  = {}", cfg.verbose);
+            say!("  install_report           = {}", cfg.install_report);
         }
         Some(ConfigAction::Path) => {
-            println!("{}", config::config_path().display());
+            say!("{}", config::config_path().display());
         }
         Some(ConfigAction::Reset) => {
             Config::write_default()?;
-            println!(
+            say!(
                 "[OK] Config reset to defaults at {}",
                 config::config_path().display()
             );
         }
+        Some(ConfigAction::List) => {
+            let active = config::active_config_name();
+            for name in config::list_config_names() {
+                let marker = if name.eq_ignore_ascii_case(&active) {
+                    "*"
+                } else {
+                    " "
+                };
+                say!(
+                    "{} {:<16} {}",
+                    marker,
+                    name,
+                    config::named_config_path(&name).display()
+                );
+            }
+        }
+        Some(ConfigAction::Use { name }) => {
+            config::validate_config_name(&name)?;
+            let path = config::named_config_path(&name);
+            if !path.exists() {
+                Config::write_config_to(&Config::load(), &path)?;
+                say!(
+                    "[OK] Created config \"{}\" from the current settings at {}",
+                    name,
+                    path.display()
+                );
+            }
+            config::set_active_config_name(&name)?;
+            say!("[OK] Active config: {}", name);
+            #[cfg(feature = "service")]
+            if lg_service::query_service_info().1 {
+                say!("[NOTE] Restart the service to pick up the new config.");
+            }
+        }
+        Some(ConfigAction::Validate { path }) => {
+            let path = path.unwrap_or_else(config::config_path);
+            if !path.exists() {
+                say!(
+                    "[INFO] No config file at {} — defaults are in use",
+                    path.display()
+                );
+                return Ok(());
+            }
+            let issues = validate::validate_file(&path)
+                .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+            for issue in &issues {
+                match issue.line {
+                    Some(line) => say!(
+                        "{}:{}: {}: {}",
+                        path.display(),
+                        line,
+                        issue.severity,
+                        issue.message
+                    ),
+                    None => say!("{}: {}: {}", path.display(), issue.severity, issue.message),
+                }
+            }
+            let errors = issues
+                .iter()
+                .filter(|i| i.severity == validate::Severity::Error)
+                .count();
+            if errors > 0 {
+                return Err(format!(
+                    "{} has {} error(s) and {} warning(s)",
+                    path.display(),
+                    errors,
+                    issues.len() - errors
+                )
+                .into());
+            }
+            if issues.is_empty() {
+                say!("[OK] {} is valid", path.display());
+            } else {
+                say!(
+                    "[OK] {} is valid with {} warning(s)",
+                    path.display(),
+                    issues.len()
+                );
+            }
+        }
+        Some(ConfigAction::Preset { action }) => cmd_config_preset(action, dry_run)?,
+    }
+    Ok(())
+}
+
+fn cmd_config_preset(action: PresetAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    let find = |name: &str| {
+        preset::find(name).ok_or_else(|| {
+            format!(
+                "Unknown preset \"{}\" (expected one of: {})",
+                name,
+                preset::names()
+            )
+        })
+    };
+    match action {
+        PresetAction::List => {
+            for p in preset::PRESETS {
+                say!("{:<12} {}", p.name, p.summary);
+            }
+        }
+        PresetAction::Diff { name } => {
+            let p = find(&name)?;
+            let changes = p.diff(&Config::load())?;
+            if changes.is_empty() {
+                say!("[OK] Preset \"{}\" is already applied", p.name);
+            }
+            for change in &changes {
+                say!("  {}", change);
+            }
+        }
+        PresetAction::Apply { name } => {
+            let p = find(&name)?;
+            let cfg = Config::load();
+            let changes = p.diff(&cfg)?;
+            if changes.is_empty() {
+                say!("[OK] Preset \"{}\" is already applied", p.name);
+                return Ok(());
+            }
+            for change in &changes {
+                say!("  {}", change);
+            }
+            if dry_run {
+                say!("[DRY RUN] Would apply preset \"{}\"", p.name);
+                return Ok(());
+            }
+            Config::write_config(&p.apply(&cfg)?)?;
+            say!(
+                "[OK] Applied preset \"{}\" to {}",
+                p.name,
+                config::config_path().display()
+            );
+            #[cfg(feature = "service")]
+            if lg_service::query_service_info().1 {
+                say!("[NOTE] Restart the service to pick up the new config.");
+            }
+        }
     }
     Ok(())
 }
@@ -1504,42 +2931,45 @@ fn cmd_automation(action: AutomationAction, dry_run: bool) -> Result<(), Box<dyn
     match action {
         AutomationAction::Show => {
             let cfg = app_state::load_automation_config();
-            println!(
+            say!(
                 "Automation config: {}\n",
                 app_state::automation_config_path().display()
             );
-            println!("{}", toml::to_string_pretty(&cfg)?);
-            println!("\nHints:");
-            println!("  - ambient.sensor_method: ddc_brightness | powershell | command | env | simulated");
-            println!("  - app_rules.match_mode: contains | exact | regex");
-            println!("  - ddc_safety.risky_vcp_codes accepts hex CSV, e.g. 04,06,0A,60,D6,DC");
+            say!("{}", toml::to_string_pretty(&cfg)?);
+            say!("\nHints:");
+            say!("  - ambient.sensor_method: ddc_brightness | powershell | command | env | simulated");
+            say!("  - app_rules.match_mode: contains | exact | regex");
+            say!("  - ddc_safety.risky_vcp_codes accepts hex CSV, e.g. 04,06,0A,60,D6,DC");
         }
         AutomationAction::Path => {
-            println!("{}", app_state::automation_config_path().display());
+            say!("{}", app_state::automation_config_path().display());
         }
         AutomationAction::Reset => {
             if dry_run {
-                println!(
+                say!(
                     "[DRY RUN] Would reset automation config at {}",
                     app_state::automation_config_path().display()
                 );
             } else {
                 app_state::save_automation_config(&app_state::AutomationConfig::default())?;
-                println!(
+                say!(
                     "[OK] Automation config reset to defaults at {}",
                     app_state::automation_config_path().display()
                 );
             }
         }
         AutomationAction::ApplyNow => {
-            println!("[INFO] Running one-shot apply with current config...");
-            println!("[INFO] Full ambient/app automation runs continuously in service/watch mode.");
+            say!("[INFO] Running one-shot apply with current config...");
+            say!("[INFO] Full ambient/app automation runs continuously in service/watch mode.");
             cmd_apply(ApplyOpts {
                 pattern: None,
                 regex: false,
                 profile_path: None,
+                profile_name: None,
+                device_keys: Vec::new(),
                 per_user: false,
                 skip_hdr: false,
+                generic_default: false,
                 toast: false,
                 no_toast: false,
                 verbose: false,
@@ -1556,7 +2986,7 @@ fn cmd_tray(action: TrayAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
             let cfg = app_state::load_automation_config();
             let tray = cfg.tray;
             if !tray.enabled {
-                println!(
+                say!(
                     "[WARN] Tray mode is disabled in automation config. Enable tray.enabled=true first."
                 );
             }
@@ -1628,7 +3058,7 @@ $notify.ContextMenuStrip = $menu
 "#;
 
             if dry_run {
-                println!(
+                say!(
                     "[DRY RUN] Would start tray host script at {}",
                     script_path.display()
                 );
@@ -1636,7 +3066,7 @@ $notify.ContextMenuStrip = $menu
             }
 
             std::fs::write(&script_path, script)?;
-            println!("[INFO] Launching tray mode...");
+            say!("[INFO] Launching tray mode...");
             let status = std::process::Command::new("powershell")
                 .args([
                     "-NoProfile",
@@ -1695,12 +3125,32 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[cfg(feature = "service")]
+fn cmd_quick(action: &QuickAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    let request = match action {
+        QuickAction::Reapply => "reapply".to_string(),
+        QuickAction::Brightness { value } => format!("brightness {}", value),
+        QuickAction::Preset { name } => format!("preset {}", name),
+    };
+    if dry_run {
+        say!(
+            "[DRY RUN] Would send to {}: {}",
+            lg_service::PIPE_NAME,
+            request
+        );
+        return Ok(());
+    }
+    let reply = timing::phase("control pipe", || lg_service::send_quick_command(&request))?;
+    say!("[OK] {}", reply);
+    Ok(())
+}
+
 fn cmd_bundle(action: BundleAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
     match action {
         BundleAction::Export { output } => {
             let out_dir = PathBuf::from(output);
             if dry_run {
-                println!("[DRY RUN] Would export bundle to {}", out_dir.display());
+                say!("[DRY RUN] Would export bundle to {}", out_dir.display());
                 return Ok(());
             }
             if !out_dir.exists() {
@@ -1744,7 +3194,7 @@ fn cmd_bundle(action: BundleAction, dry_run: bool) -> Result<(), Box<dyn Error>>
                 chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
             );
             std::fs::write(out_dir.join("manifest.toml"), manifest)?;
-            println!("[OK] Bundle exported to {}", out_dir.display());
+            say!("[OK] Bundle exported to {}", out_dir.display());
         }
         BundleAction::Import { input } => {
             let in_dir = PathBuf::from(input);
@@ -1752,7 +3202,7 @@ fn cmd_bundle(action: BundleAction, dry_run: bool) -> Result<(), Box<dyn Error>>
                 return Err(format!("bundle path does not exist: {}", in_dir.display()).into());
             }
             if dry_run {
-                println!("[DRY RUN] Would import bundle from {}", in_dir.display());
+                say!("[DRY RUN] Would import bundle from {}", in_dir.display());
                 return Ok(());
             }
 
@@ -1764,7 +3214,9 @@ fn cmd_bundle(action: BundleAction, dry_run: bool) -> Result<(), Box<dyn Error>>
             }
             let cfg_src = in_dir.join("config.toml");
             if cfg_src.exists() {
-                std::fs::copy(&cfg_src, &cfg_dst)?;
+                audit::file_change("import_config", &cfg_dst, || {
+                    std::fs::copy(&cfg_src, &cfg_dst)
+                })?;
             }
 
             let automation_dst = app_state::automation_config_path();
@@ -1785,12 +3237,13 @@ fn cmd_bundle(action: BundleAction, dry_run: bool) -> Result<(), Box<dyn Error>>
             let color_src = in_dir.join("color");
             let color_dst = app_state::windows_color_directory();
             copy_dir_recursive(&color_src, &color_dst)?;
-            println!("[OK] Bundle imported from {}", in_dir.display());
+            say!("[OK] Bundle imported from {}", in_dir.display());
         }
     }
     Ok(())
 }
 
+#[cfg(feature = "service")]
 fn cmd_service(action: ServiceAction) -> Result<(), Box<dyn Error>> {
     match action {
         ServiceAction::Install {
@@ -1803,9 +3256,9 @@ fn cmd_service(action: ServiceAction) -> Result<(), Box<dyn Error>> {
             let cfg_path = config::config_path();
             if !cfg_path.exists() {
                 Config::write_default()?;
-                println!("[OK] Default config written to {}", cfg_path.display());
+                say!("[OK] Default config written to {}", cfg_path.display());
             } else {
-                println!("[OK] Config already exists at {}", cfg_path.display());
+                say!("[OK] Config already exists at {}", cfg_path.display());
             }
 
             // Update monitor_match in config if provided on CLI
@@ -1813,49 +3266,76 @@ fn cmd_service(action: ServiceAction) -> Result<(), Box<dyn Error>> {
             if monitor_match != "LG ULTRAGEAR" {
                 cfg.monitor_match = monitor_match.to_string();
                 Config::write_config(&cfg)?;
-                println!(
+                say!(
                     "[OK] Config updated with monitor pattern: {}",
                     monitor_match
                 );
             }
 
-            if let Err(e) = lg_service::install(&cfg.monitor_match) {
-                print_service_binary_placement(true);
-                return Err(e);
+            let migration = match lg_service::install_with_progress(
+                &cfg.monitor_match,
+                &mut print_progress,
+                &lg_core::shutdown::ShutdownToken::new(),
+            ) {
+                Ok(migration) => migration,
+                Err(e) => {
+                    print_service_binary_placement(true);
+                    return Err(e);
+                }
+            };
+            print_legacy_migration(&migration);
+            if migration.is_some() {
+                // Legacy settings may have replaced the default pattern.
+                cfg = Config::load();
             }
-            println!(
+            say!(
                 "[OK] Service installed. Monitor pattern: {}",
                 cfg.monitor_match
             );
             print_service_binary_placement(false);
             lg_service::start_service()?;
-            println!("[OK] Service started.");
-            println!("     Binary: {}", config::install_path().display());
-            println!("     Config: {}", cfg_path.display());
+            say!("[OK] Service started.");
+            say!("     Binary: {}", config::install_path().display());
+            say!("     Config: {}", cfg_path.display());
         }
         ServiceAction::Uninstall => {
-            lg_service::uninstall()?;
-            println!("[OK] Service uninstalled.");
-            println!(
+            lg_service::uninstall_with_progress(
+                &mut print_progress,
+                &lg_core::shutdown::ShutdownToken::new(),
+            )?;
+            say!("[OK] Service uninstalled.");
+            say!(
                 "     Config preserved at: {}",
                 config::config_path().display()
             );
-            println!(
+            say!(
                 "     Binary removed from: {}",
                 config::install_path().display()
             );
         }
         ServiceAction::Start => {
             lg_service::start_service()?;
-            println!("[OK] Service started.");
+            say!("[OK] Service started.");
         }
         ServiceAction::Stop => {
             lg_service::stop_service()?;
-            println!("[OK] Service stopped.");
+            say!("[OK] Service stopped.");
+        }
+        ServiceAction::Status { stats } => {
+            lg_service::report_status(output::block)?;
+            if stats {
+                print_stats();
+            }
         }
-        ServiceAction::Status => {
-            lg_service::print_status()?;
+        ServiceAction::History { limit } => {
+            print_reapply_history(&lg_service::fetch_reapply_history(limit)?);
         }
+        ServiceAction::Logs {
+            limit,
+            level,
+            follow,
+        } => cmd_service_logs(limit, level, follow)?,
+        ServiceAction::Counters { interval, samples } => cmd_service_counters(interval, samples),
         ServiceAction::Run => {
             // Handled in main() — should never reach here
             unreachable!("SCM mode handled in main()");
@@ -1864,18 +3344,253 @@ fn cmd_service(action: ServiceAction) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[cfg(feature = "service")]
+fn cmd_telemetry(action: TelemetryAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    let mut cfg = Config::load();
+    match action {
+        TelemetryAction::Status => {
+            let state = lg_core::telemetry::load_telemetry_state();
+            say!(
+                "Usage ping: {}",
+                if cfg.telemetry_enabled { "on" } else { "off" }
+            );
+            say!(
+                "Endpoint:   {}",
+                if cfg.telemetry_endpoint.is_empty() {
+                    "(not set)"
+                } else {
+                    &cfg.telemetry_endpoint
+                }
+            );
+            say!(
+                "Last sent:  {}",
+                state.last_sent.as_deref().unwrap_or("never")
+            );
+            if !state.last_error.is_empty() {
+                say!(
+                    "Last error: {} ({})",
+                    state.last_error,
+                    state.last_attempt.as_deref().unwrap_or("-")
+                );
+            }
+            say!();
+            say!("Payload (sent as-is, once a week):");
+            say!(
+                "{}",
+                lg_service::telemetry_payload(&cfg, env!("APP_VERSION")).to_json()
+            );
+            return Ok(());
+        }
+        TelemetryAction::Enable { endpoint } => {
+            if let Some(endpoint) = endpoint {
+                cfg.telemetry_endpoint = endpoint.trim().to_string();
+            }
+            if lg_core::telemetry::split_https_url(&cfg.telemetry_endpoint).is_none() {
+                return Err(if cfg.telemetry_endpoint.is_empty() {
+                    "no telemetry_endpoint configured; pass --endpoint <https URL>".into()
+                } else {
+                    format!(
+                        "telemetry_endpoint \"{}\" is not an https URL",
+                        cfg.telemetry_endpoint
+                    )
+                    .into()
+                });
+            }
+            cfg.telemetry_enabled = true;
+        }
+        TelemetryAction::Disable => cfg.telemetry_enabled = false,
+    }
+
+    let state = if cfg.telemetry_enabled { "on" } else { "off" };
+    if dry_run {
+        say!(
+            "[DRY RUN] Would turn the usage ping {} and restart the service",
+            state
+        );
+        return Ok(());
+    }
+    Config::write_config(&cfg)?;
+    say!("[OK] Usage ping turned {}.", state);
+    if cfg.telemetry_enabled {
+        say!(
+            "     Weekly to {}; `telemetry status` shows the payload.",
+            cfg.telemetry_endpoint
+        );
+    }
+    // The service reads the setting when it starts.
+    if lg_service::query_service_info().1 {
+        lg_service::stop_service()?;
+        lg_service::start_service()?;
+        say!("[OK] Service restarted.");
+    }
+    Ok(())
+}
+
+/// Days shown by `service status --stats`.
+#[cfg(feature = "service")]
+const STATS_DAYS: usize = 14;
+
+#[cfg(feature = "service")]
+fn print_stats() {
+    let metrics_cfg = app_state::load_automation_config().metrics;
+    let metrics = app_state::compute_apply_latency_metrics(metrics_cfg.rolling_window);
+    say!();
+    say!("── Apply latency (last {} applies) ──", metrics.samples);
+    if metrics.samples == 0 {
+        say!("  No applies recorded yet.");
+    } else {
+        say!(
+            "  avg {:.1} ms  p95 {} ms  last {} ms  ok {}  failed {}",
+            metrics.avg_ms,
+            metrics.p95_ms,
+            metrics.last_ms,
+            metrics.success_count,
+            metrics.failure_count
+        );
+    }
+
+    say!();
+    say!("── Luminance (daily min–max, cd/m²) ──");
+    let readings = lg_core::measure::read_readings().unwrap_or_default();
+    let ranges = lg_core::measure::daily_ranges(&readings);
+    let shown = &ranges[ranges.len().saturating_sub(STATS_DAYS)..];
+    if shown.is_empty() {
+        say!("  No readings yet. Set measure_command or run `measure add <cd/m2>`.");
+        return;
+    }
+    for range in shown {
+        say!(
+            "  {}  {:>7.1} – {:<7.1} {}  ({})",
+            range.day,
+            range.min,
+            range.max,
+            lg_core::measure::range_bar(range, shown, 30),
+            range.samples
+        );
+    }
+}
+
+/// Sample the service's counters file like `typeperf -si <interval> -sc
+/// <samples>` samples PDH counters: a header, then one CSV row per sample.
+#[cfg(feature = "service")]
+fn cmd_service_counters(interval: u64, samples: Option<u64>) {
+    use lg_core::counters::{self, CounterSample};
+
+    let host = std::env::var("COMPUTERNAME").unwrap_or_else(|_| "localhost".to_string());
+    say!("{}", counters::csv_header(&host));
+    let mut previous: Option<CounterSample> = None;
+    let mut taken = 0;
+    loop {
+        let sample = CounterSample {
+            at: chrono::Local::now(),
+            counters: counters::load_counters().unwrap_or_default(),
+        };
+        say!("{}", counters::csv_row(previous.as_ref(), &sample));
+        previous = Some(sample);
+        taken += 1;
+        if samples.is_some_and(|n| taken >= n) {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_secs(interval));
+    }
+}
+
+/// How often `service logs --follow` checks for new events.
+#[cfg(feature = "service")]
+const LOG_FOLLOW_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+#[cfg(feature = "service")]
+fn cmd_service_logs(
+    limit: usize,
+    level: lg_service::EventLevel,
+    follow: bool,
+) -> Result<(), Box<dyn Error>> {
+    let events = lg_service::recent_events(limit, level)?;
+    if events.is_empty() && !follow {
+        say!("[INFO] No events from the service in the Application log.");
+        return Ok(());
+    }
+    for event in &events {
+        say!("{}", event);
+    }
+    if !follow {
+        return Ok(());
+    }
+
+    say!("[WATCH] Waiting for new events (Ctrl+C to stop)...");
+    let mut last = events.last().map(|e| e.record_id);
+    if last.is_none() {
+        // Nothing at this level yet: start after the newest event of any
+        // level so older entries aren't replayed.
+        last = lg_service::recent_events(1, lg_service::EventLevel::Info)?
+            .last()
+            .map(|e| e.record_id);
+    }
+    loop {
+        std::thread::sleep(LOG_FOLLOW_INTERVAL);
+        for event in lg_service::events_after(last.unwrap_or(0), level)? {
+            say!("{}", event);
+            last = Some(event.record_id);
+        }
+    }
+}
+
+#[cfg(feature = "service")]
+fn print_reapply_history(records: &[lg_core::report::ReapplyRecord]) {
+    if records.is_empty() {
+        say!("[INFO] No reapply attempts since the service started.");
+        return;
+    }
+    say!("Recent reapply attempts (oldest first):");
+    for record in records {
+        say!();
+        say!(
+            "  {}  {}  {}  {} ms",
+            record.at,
+            record.trigger,
+            if record.success { "[OK]" } else { "[WARN]" },
+            record.duration_ms
+        );
+        if !record.events.is_empty() {
+            say!("    Events: {}", record.events.join(", "));
+        }
+        if let Some(reason) = &record.skipped {
+            say!("    Skipped: {}", reason);
+        }
+        if let Some(cd_m2) = record.luminance_cd_m2 {
+            say!("    Luminance: {:.1} cd/m²", cd_m2);
+        }
+        for device in &record.devices {
+            say!("    {} ({})", device.name, device.device_key);
+            for step in &device.steps {
+                match &step.error {
+                    None => say!("      [OK] {}", step.step),
+                    Some(e) => say!("      [WARN] {}: {}", step.step, e),
+                }
+            }
+        }
+    }
+}
+
+/// Print one step of a service install/uninstall as `[1/6] Stopping...`.
+#[cfg(feature = "service")]
+fn print_progress(event: &lg_core::progress::ProgressEvent) {
+    say!("{}", event);
+}
+
+#[cfg(feature = "service")]
 fn print_service_binary_placement(after_failed_install: bool) {
     let path = config::install_path();
     match std::fs::metadata(&path) {
         Ok(meta) if meta.is_file() => {
             if after_failed_install {
-                println!(
+                say!(
                     "[NOTE] Service binary is present at {} ({} bytes); install failed in a later step.",
                     path.display(),
                     meta.len()
                 );
             } else {
-                println!(
+                say!(
                     "[OK] Service binary placed at {} ({} bytes)",
                     path.display(),
                     meta.len()
@@ -1883,13 +3598,13 @@ fn print_service_binary_placement(after_failed_install: bool) {
             }
         }
         Ok(_) => {
-            println!(
+            say!(
                 "[WARN] Service install path exists but is not a file: {}",
                 path.display()
             );
         }
         Err(e) => {
-            println!(
+            say!(
                 "[WARN] Service binary not found at {} ({})",
                 path.display(),
                 e
@@ -1898,28 +3613,98 @@ fn print_service_binary_placement(after_failed_install: bool) {
     }
 }
 
+#[cfg(feature = "service")]
+fn print_legacy_migration(migration: &Option<lg_service::LegacyMigration>) {
+    let Some(migration) = migration else {
+        return;
+    };
+    say!(
+        "[OK] Migrated legacy service (was {})",
+        migration.legacy_binary
+    );
+    if migration.imported.is_empty() {
+        say!("     No legacy settings needed importing");
+    } else {
+        say!("     Imported: {}", migration.imported.join(", "));
+    }
+    say!("[NOTE] The old binary was left in place; delete it once you're happy with the new service.");
+}
+
 // ============================================================================
 // New top-level commands (parity with PowerShell installer)
 // ============================================================================
 
 /// Options for install command (avoids too-many-arguments lint).
+#[cfg(feature = "service")]
 struct InstallOpts {
     pattern: Option<String>,
     regex: bool,
     profile_only: bool,
     service_only: bool,
     custom_profile: Option<String>,
-    #[allow(dead_code)]
     per_user: bool,
-    #[allow(dead_code)]
     skip_hdr: bool,
-    #[allow(dead_code)]
+    generic_default: bool,
     skip_hash_check: bool,
     force: bool,
     skip_detect: bool,
     dry_run: bool,
+    /// Print the dry-run plan as JSON.
+    json: bool,
+    /// Unattended package-manager install: no detection, prompts or
+    /// config writes (see [`packaging`]).
+    for_packaging: bool,
+}
+
+/// Whether `--dry-run` prints the plan as JSON: with `--json`, or with
+/// `--format json`. `--json` only shapes `--dry-run` output, and asks for
+/// output `--quiet` would drop.
+#[cfg(feature = "service")]
+fn plan_json(json: bool, dry_run: bool, format: Format) -> Result<bool, Box<dyn Error>> {
+    if json && !dry_run {
+        return Err("--json only applies together with --dry-run".into());
+    }
+    if json && format == Format::Quiet {
+        return Err("--json can't be combined with --quiet".into());
+    }
+    Ok(dry_run && (json || format == Format::Json))
+}
+
+#[cfg(feature = "service")]
+fn print_plan(plan: &lg_core::plan::Plan, json: bool) -> Result<(), Box<dyn Error>> {
+    if json {
+        // Already JSON, so it bypasses the line formatting; on one line
+        // with `--format json`, like the rest of that output.
+        let text = match output::format() {
+            Format::Json => serde_json::to_string(plan)?,
+            _ => plan.to_json()?,
+        };
+        println!("{}", text);
+    } else {
+        output::block(&plan.render());
+    }
+    Ok(())
 }
 
+/// Run an install or uninstall for `--for-packaging` and exit with its
+/// [`packaging`] exit code. Refuses to start without elevation, since there
+/// is nobody to answer a UAC prompt.
+#[cfg(feature = "service")]
+fn run_for_packaging(run: impl FnOnce() -> Result<(), Box<dyn Error>>) -> ! {
+    let code = if elevation::is_elevated() {
+        let result = run();
+        if let Err(e) = &result {
+            eprintln!("Error: {}", e);
+        }
+        packaging::exit_code(&result)
+    } else {
+        eprintln!("Error: --for-packaging needs an elevated process");
+        packaging::EXIT_NOT_ELEVATED
+    };
+    std::process::exit(code)
+}
+
+#[cfg(feature = "service")]
 fn cmd_install(opts: InstallOpts) -> Result<(), Box<dyn Error>> {
     let mut cfg = Config::load();
     if let Some(ref p) = opts.pattern {
@@ -1928,80 +3713,22 @@ fn cmd_install(opts: InstallOpts) -> Result<(), Box<dyn Error>> {
     if opts.regex {
         cfg.monitor_match_regex = true;
     }
+    if opts.skip_hdr {
+        cfg.icc_hdr_association = false;
+    }
     let use_regex = effective_regex(opts.regex, &cfg);
+    // Without the hash check an unchanged profile is rewritten as well.
+    let overwrite = opts.force || opts.skip_hash_check;
+
+    if opts.dry_run {
+        let mut plan = lg_core::plan::Plan::new("install");
+        plan_install(&mut plan, &cfg, &opts, use_regex)?;
+        return print_plan(&plan, opts.json);
+    }
 
     if opts.profile_only {
-        // Profile-only install
-        if opts.dry_run {
-            println!("[DRY RUN] Would extract SDR/HDR ICC profile(s) to color store");
-            return Ok(());
-        }
-        let custom_profile = opts.custom_profile.is_some();
-        let sdr_preset = effective_preset_for_mode(&cfg, false);
-        let hdr_preset = effective_preset_for_mode(&cfg, true);
-        let profile_path = if let Some(ref custom) = opts.custom_profile {
-            std::path::PathBuf::from(custom)
-        } else {
-            lg_profile::resolve_active_profile_path(
-                &lg_profile::color_directory(),
-                &sdr_preset,
-                &cfg.profile_name,
-            )
-        };
-        let wrote = if custom_profile {
-            lg_profile::ensure_profile_installed_with_gamma_luminance_and_tuning(
-                &profile_path,
-                cfg.icc_gamma,
-                cfg.icc_luminance_cd_m2,
-                tuning_from_config(&cfg),
-            )?
-        } else {
-            let _ = lg_profile::ensure_mode_profiles_installed_tuned(
-                &lg_profile::color_directory(),
-                &sdr_preset,
-                &hdr_preset,
-                &cfg.profile_name,
-                cfg.icc_gamma,
-                cfg.icc_luminance_cd_m2,
-                cfg.icc_generate_specialized_profiles,
-                tuning_from_config(&cfg),
-            )?;
-            true
-        };
-        match wrote {
-            true => println!("[OK] ICC profile installed to {}", profile_path.display()),
-            false => {
-                if opts.force {
-                    // Force overwrite: remove and re-extract
-                    let _ = lg_profile::remove_profile(&profile_path);
-                    if custom_profile {
-                        lg_profile::ensure_profile_installed_with_gamma_luminance_and_tuning(
-                            &profile_path,
-                            cfg.icc_gamma,
-                            cfg.icc_luminance_cd_m2,
-                            tuning_from_config(&cfg),
-                        )?;
-                    } else {
-                        let _ = lg_profile::ensure_mode_profiles_installed_tuned(
-                            &lg_profile::color_directory(),
-                            &sdr_preset,
-                            &hdr_preset,
-                            &cfg.profile_name,
-                            cfg.icc_gamma,
-                            cfg.icc_luminance_cd_m2,
-                            cfg.icc_generate_specialized_profiles,
-                            tuning_from_config(&cfg),
-                        )?;
-                    }
-                    println!(
-                        "[OK] ICC profile force-installed to {}",
-                        profile_path.display()
-                    );
-                } else {
-                    println!("[OK] ICC profile already present");
-                }
-            }
-        }
+        let profile_path = install_profiles(&cfg, &opts, overwrite)
+            .map_err(packaging::failed_at(packaging::Step::Profile))?;
 
         // Clean up any stale/leftover ICM files (from test runs, etc.)
         let expected_name = profile_path
@@ -2010,118 +3737,69 @@ fn cmd_install(opts: InstallOpts) -> Result<(), Box<dyn Error>> {
             .unwrap_or_else(|| cfg.profile_name.clone());
         let stale = lg_profile::cleanup_stale_profiles(&expected_name);
         for p in &stale {
-            println!("[OK] Removed stale profile: {}", p.display());
+            say!("[OK] Removed stale profile: {}", p.display());
         }
 
-        println!("[DONE] Profile install complete.");
+        let mut report = begin_install_report_cli(&cfg, "install");
+        if let Some(report) = report.as_mut() {
+            report.per_user_requested = opts.per_user;
+            add_installed_profiles(report, &cfg, opts.custom_profile.as_deref());
+        }
+        finish_install_report_cli(report, None);
+
+        say!("[DONE] Profile install complete.");
         return Ok(());
     }
 
-    if opts.dry_run {
+    // Extract ICC profile (unless service-only)
+    if !opts.service_only {
+        install_profiles(&cfg, &opts, overwrite)
+            .map_err(packaging::failed_at(packaging::Step::Profile))?;
+    }
+
+    let mut report = begin_install_report_cli(&cfg, "install");
+    if let Some(report) = report.as_mut() {
+        report.per_user_requested = opts.per_user;
         if !opts.service_only {
-            println!("[DRY RUN] Would extract SDR/HDR ICC profile(s) to color store");
-        }
-        if !opts.skip_detect {
-            println!(
-                "[DRY RUN] Would detect matching monitors ({})",
-                if use_regex { "regex" } else { "substring" }
-            );
+            add_installed_profiles(report, &cfg, opts.custom_profile.as_deref());
         }
-        println!("[DRY RUN] Would write default config");
-        println!("[DRY RUN] Would install Windows service");
-        println!("[DRY RUN] Would start service");
-        return Ok(());
     }
 
-    // Extract ICC profile (unless service-only)
-    if !opts.service_only {
-        let custom_profile = opts.custom_profile.is_some();
-        let sdr_preset = effective_preset_for_mode(&cfg, false);
-        let hdr_preset = effective_preset_for_mode(&cfg, true);
-        let profile_path = if let Some(ref custom) = opts.custom_profile {
-            std::path::PathBuf::from(custom)
-        } else {
-            lg_profile::resolve_active_profile_path(
-                &lg_profile::color_directory(),
-                &sdr_preset,
-                &cfg.profile_name,
-            )
-        };
-        let wrote = if custom_profile {
-            lg_profile::ensure_profile_installed_with_gamma_luminance_and_tuning(
-                &profile_path,
-                cfg.icc_gamma,
-                cfg.icc_luminance_cd_m2,
-                tuning_from_config(&cfg),
-            )?
+    // Detect monitors (unless skipped)
+    if !opts.skip_detect && !opts.for_packaging {
+        let devices = find_matching_monitors(&cfg.monitor_match, use_regex)?;
+        if devices.is_empty() {
+            say!(
+                "[NOTE] No monitors matching \"{}\" found",
+                cfg.monitor_match
+            );
         } else {
-            let _ = lg_profile::ensure_mode_profiles_installed_tuned(
-                &lg_profile::color_directory(),
-                &sdr_preset,
-                &hdr_preset,
-                &cfg.profile_name,
-                cfg.icc_gamma,
-                cfg.icc_luminance_cd_m2,
-                cfg.icc_generate_specialized_profiles,
-                tuning_from_config(&cfg),
-            )?;
-            true
-        };
-        match wrote {
-            true => println!("[OK] ICC profile installed to {}", profile_path.display()),
-            false => {
-                if opts.force {
-                    let _ = lg_profile::remove_profile(&profile_path);
-                    if custom_profile {
-                        lg_profile::ensure_profile_installed_with_gamma_luminance_and_tuning(
-                            &profile_path,
-                            cfg.icc_gamma,
-                            cfg.icc_luminance_cd_m2,
-                            tuning_from_config(&cfg),
-                        )?;
-                    } else {
-                        let _ = lg_profile::ensure_mode_profiles_installed_tuned(
-                            &lg_profile::color_directory(),
-                            &sdr_preset,
-                            &hdr_preset,
-                            &cfg.profile_name,
-                            cfg.icc_gamma,
-                            cfg.icc_luminance_cd_m2,
-                            cfg.icc_generate_specialized_profiles,
-                            tuning_from_config(&cfg),
-                        )?;
-                    }
-                    println!(
-                        "[OK] ICC profile force-installed to {}",
-                        profile_path.display()
-                    );
-                } else {
-                    println!("[OK] ICC profile already present");
-                }
-            }
-        }
-    }
-
-    // Detect monitors (unless skipped)
-    if !opts.skip_detect {
-        let devices = find_matching_monitors(&cfg.monitor_match, use_regex)?;
-        if devices.is_empty() {
-            println!(
-                "[NOTE] No monitors matching \"{}\" found",
-                cfg.monitor_match
-            );
-        } else {
-            println!(
+            say!(
                 "[OK] Found {} monitor(s) matching \"{}\"",
                 devices.len(),
                 cfg.monitor_match
             );
-            if cfg.icc_per_monitor_profiles && !opts.service_only {
+            warn_model_quirks(&devices);
+            check_known_bad_profiles(&devices);
+            let per_monitor = cfg.icc_per_monitor_profiles && !opts.service_only;
+            if let Some(report) = report.as_mut().filter(|_| !per_monitor) {
+                report
+                    .devices
+                    .extend(devices.iter().map(|device| DeviceEntry {
+                        name: device.name.clone(),
+                        device_key: device.device_key.clone(),
+                        active_profile: String::new(),
+                        sdr_profile: String::new(),
+                        hdr_profile: String::new(),
+                        steps: Vec::new(),
+                    }));
+            }
+            if per_monitor {
                 let sdr_preset = effective_preset_for_mode(&cfg, false);
                 let hdr_preset = effective_preset_for_mode(&cfg, true);
                 for device in &devices {
                     let identity = identity_from_monitor(device);
-                    match lg_profile::ensure_mode_profiles_installed_tuned_for_monitor(
+                    let result = lg_profile::ensure_mode_profiles_installed_tuned_for_monitor(
                         &lg_profile::color_directory(),
                         &sdr_preset,
                         &hdr_preset,
@@ -2131,36 +3809,63 @@ fn cmd_install(opts: InstallOpts) -> Result<(), Box<dyn Error>> {
                         cfg.icc_generate_specialized_profiles,
                         tuning_from_config(&cfg),
                         &identity,
-                    ) {
-                        Ok((sdr_path, hdr_path)) => println!(
+                    );
+                    if let Some(report) = report.as_mut() {
+                        let (sdr, hdr) = match &result {
+                            Ok((sdr_path, hdr_path)) => {
+                                report.add_profile("sdr", sdr_path);
+                                report.add_profile("hdr", hdr_path);
+                                (
+                                    sdr_path.display().to_string(),
+                                    hdr_path.display().to_string(),
+                                )
+                            }
+                            Err(_) => (String::new(), String::new()),
+                        };
+                        report.devices.push(DeviceEntry {
+                            name: device.name.clone(),
+                            device_key: device.device_key.clone(),
+                            active_profile: String::new(),
+                            sdr_profile: sdr,
+                            hdr_profile: hdr,
+                            steps: vec![StepResult::from_result("monitor_profiles", &result)],
+                        });
+                    }
+                    match result {
+                        Ok((sdr_path, hdr_path)) => say!(
                             "[OK] Monitor-scoped profiles ready for {}: SDR={} HDR={}",
                             device.name,
                             sdr_path.display(),
                             hdr_path.display()
                         ),
-                        Err(e) => println!(
+                        Err(e) => say!(
                             "[WARN] Failed to generate monitor-scoped profile for {}: {}",
-                            device.name, e
+                            device.name,
+                            e
                         ),
                     }
                 }
             }
         }
     }
+    finish_install_report_cli(report, None);
 
-    // Write default config
+    // Write default config; a package install leaves that to the user, and
+    // the service runs on defaults until then.
     let cfg_path = config::config_path();
-    if !cfg_path.exists() {
-        Config::write_default()?;
-        println!("[OK] Default config written to {}", cfg_path.display());
+    if cfg_path.exists() {
+        say!("[OK] Config already exists at {}", cfg_path.display());
+    } else if opts.for_packaging {
+        say!("[NOTE] No config written (defaults apply)");
     } else {
-        println!("[OK] Config already exists at {}", cfg_path.display());
+        Config::write_default()?;
+        say!("[OK] Default config written to {}", cfg_path.display());
     }
 
     // Update monitor_match in config if provided on CLI
     if opts.pattern.is_some() || opts.regex {
         Config::write_config(&cfg)?;
-        println!(
+        say!(
             "[OK] Config updated with monitor pattern: {} (mode: {})",
             cfg.monitor_match,
             if cfg.monitor_match_regex {
@@ -2170,50 +3875,433 @@ fn cmd_install(opts: InstallOpts) -> Result<(), Box<dyn Error>> {
             }
         );
     }
+    // The service reads this on every reapply.
+    if opts.skip_hdr {
+        Config::write_config(&cfg)?;
+        say!("[OK] Config updated: HDR/advanced-color association off");
+    }
+
+    // The service associates system-wide; per-user defaults and the generic
+    // default need one pass from the installing account.
+    if (opts.per_user || opts.generic_default) && !opts.service_only && !opts.skip_detect {
+        install_initial_associations(&cfg, &opts, use_regex);
+    }
 
     // Install service
-    if let Err(e) = lg_service::install(&cfg.monitor_match) {
-        print_service_binary_placement(true);
-        return Err(e);
+    match lg_service::install_with_progress(
+        &cfg.monitor_match,
+        &mut print_progress,
+        &lg_core::shutdown::ShutdownToken::new(),
+    ) {
+        Ok(migration) => print_legacy_migration(&migration),
+        Err(e) => {
+            print_service_binary_placement(true);
+            return Err(packaging::failed_at(packaging::Step::Service)(e));
+        }
     }
-    println!("[OK] Service installed");
+    say!("[OK] Service installed");
     print_service_binary_placement(false);
-    println!("     Binary: {}", config::install_path().display());
-    println!("     Config: {}", cfg_path.display());
+    say!("     Binary: {}", config::install_path().display());
+    say!("     Config: {}", cfg_path.display());
 
     // Start service
-    lg_service::start_service()?;
-    println!("[OK] Service started");
-    println!("\n[DONE] Install complete!");
+    lg_service::start_service().map_err(packaging::failed_at(packaging::Step::ServiceStart))?;
+    say!("[OK] Service started");
+    say!("\n[DONE] Install complete!");
     Ok(())
 }
 
-fn cmd_uninstall(full: bool, profile: bool, dry_run: bool) -> Result<(), Box<dyn Error>> {
-    if dry_run {
-        if full {
-            println!("[DRY RUN] Would uninstall service");
-            println!("[DRY RUN] Would remove ICC profile");
-            println!("[DRY RUN] Would remove config directory");
-        } else if profile {
-            println!("[DRY RUN] Would uninstall service");
-            println!("[DRY RUN] Would remove ICC profile");
+/// Write the ICC profiles for `install` (the configured mode profiles, or
+/// the custom profile), rewriting unchanged ones when `overwrite` is set.
+/// Returns the active profile's path.
+#[cfg(feature = "service")]
+fn install_profiles(
+    cfg: &Config,
+    opts: &InstallOpts,
+    overwrite: bool,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let custom_profile = opts.custom_profile.is_some();
+    let sdr_preset = effective_preset_for_mode(cfg, false);
+    let hdr_preset = effective_preset_for_mode(cfg, true);
+    let profile_path = if let Some(ref custom) = opts.custom_profile {
+        std::path::PathBuf::from(custom)
+    } else {
+        lg_profile::resolve_active_profile_path(
+            &lg_profile::color_directory(),
+            &sdr_preset,
+            &cfg.profile_name,
+        )
+    };
+    let wrote = if custom_profile {
+        lg_profile::ensure_profile_installed_with_gamma_luminance_and_tuning(
+            &profile_path,
+            cfg.icc_gamma,
+            cfg.icc_luminance_cd_m2,
+            tuning_from_config(cfg),
+        )
+        .inspect_err(|e| explain_profile_lock(e.as_ref()))?
+    } else {
+        let _ = lg_profile::ensure_mode_profiles_installed_tuned(
+            &lg_profile::color_directory(),
+            &sdr_preset,
+            &hdr_preset,
+            &cfg.profile_name,
+            cfg.icc_gamma,
+            cfg.icc_luminance_cd_m2,
+            cfg.icc_generate_specialized_profiles,
+            tuning_from_config(cfg),
+        )
+        .inspect_err(|e| explain_profile_lock(e.as_ref()))?;
+        true
+    };
+    match wrote {
+        true => say!("[OK] ICC profile installed to {}", profile_path.display()),
+        false => {
+            if overwrite {
+                let _ = lg_profile::remove_profile(&profile_path);
+                if custom_profile {
+                    lg_profile::ensure_profile_installed_with_gamma_luminance_and_tuning(
+                        &profile_path,
+                        cfg.icc_gamma,
+                        cfg.icc_luminance_cd_m2,
+                        tuning_from_config(cfg),
+                    )
+                    .inspect_err(|e| explain_profile_lock(e.as_ref()))?;
+                } else {
+                    let _ = lg_profile::ensure_mode_profiles_installed_tuned(
+                        &lg_profile::color_directory(),
+                        &sdr_preset,
+                        &hdr_preset,
+                        &cfg.profile_name,
+                        cfg.icc_gamma,
+                        cfg.icc_luminance_cd_m2,
+                        cfg.icc_generate_specialized_profiles,
+                        tuning_from_config(cfg),
+                    )
+                    .inspect_err(|e| explain_profile_lock(e.as_ref()))?;
+                }
+                say!(
+                    "[OK] ICC profile force-installed to {}",
+                    profile_path.display()
+                );
+            } else {
+                say!("[OK] ICC profile already present");
+            }
+        }
+    }
+    Ok(profile_path)
+}
+
+/// Warn about matched monitors whose model has a quirk warning (the profile
+/// is known not to help, or another fix applies), with where to go instead.
+fn warn_model_quirks(devices: &[lg_monitor::MatchedMonitor]) {
+    for device in devices {
+        let model = lg_core::telemetry::model_code(&device.manufacturer_id, &device.product_code);
+        for found in quirks::model_warnings(quirks::all(), &device.name, model.as_deref()) {
+            say!(
+                "[WARN] {}{}: {}",
+                device.name,
+                model
+                    .as_deref()
+                    .map(|m| format!(" ({})", m))
+                    .unwrap_or_default(),
+                found.warning
+            );
+            if let Some(profile) = &found.profile {
+                say!("       Recommended profile: {}", profile);
+            }
+            if !found.more_info.is_empty() {
+                say!("       More info: {}", found.more_info);
+            }
+        }
+    }
+}
+
+/// Flag factory profiles from the quirk database that are associated with
+/// the matched monitors, and offer to disassociate each one. Without a
+/// console to ask on, only warns.
+#[cfg(feature = "service")]
+fn check_known_bad_profiles(devices: &[lg_monitor::MatchedMonitor]) {
+    use std::io::IsTerminal;
+
+    let found = match known_bad_associations(devices) {
+        Ok(found) => found,
+        Err(e) => {
+            say!("[WARN] Cannot read existing profile associations: {}", e);
+            return;
+        }
+    };
+    // Only ask when the warning above the question was shown.
+    let interactive = io::stdin().is_terminal() && output::format() == Format::Human;
+    for (device, bad) in found {
+        say!(
+            "[WARN] {} has {} associated — {}",
+            device.name,
+            bad.profile,
+            bad.note
+        );
+        if !interactive {
+            say!("       Remove it in Color Management (colorcpl) to keep the fix in effect");
+            continue;
+        }
+        output::prompt("       Disassociate it? [y/N] ");
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer).is_err()
+            || !matches!(answer.trim(), "y" | "Y" | "yes" | "YES")
+        {
+            continue;
+        }
+        match lg_profile::remove_device_association(&device.device_key, &bad.profile) {
+            Ok(()) => say!("[OK] Disassociated {} from {}", bad.profile, device.name),
+            Err(e) => say!("[WARN] Could not disassociate {}: {}", bad.profile, e),
+        }
+    }
+}
+
+/// Quirk-database known-bad profiles associated with each of `devices`.
+#[cfg(feature = "service")]
+fn known_bad_associations(
+    devices: &[lg_monitor::MatchedMonitor],
+) -> Result<Vec<(&lg_monitor::MatchedMonitor, quirks::KnownBadProfile)>, Box<dyn Error>> {
+    let associations = lg_profile::read_device_associations()?;
+    let mut found = Vec::new();
+    for device in devices {
+        let associated: Vec<String> = associations
+            .iter()
+            .filter(|a| lg_profile::device_keys_match(&a.device_key, &device.device_key))
+            .flat_map(|a| a.profiles.iter().cloned())
+            .collect();
+        let model = lg_core::telemetry::model_code(&device.manufacturer_id, &device.product_code);
+        found.extend(
+            quirks::find_known_bad_profiles(
+                quirks::all(),
+                &device.name,
+                model.as_deref(),
+                &associated,
+            )
+            .into_iter()
+            .map(|bad| (device, bad)),
+        );
+    }
+    Ok(found)
+}
+
+/// Build the `--dry-run` plan of [`cmd_install`]: the same steps in the
+/// same order, checked against this machine's current state.
+#[cfg(feature = "service")]
+fn plan_install(
+    plan: &mut lg_core::plan::Plan,
+    cfg: &Config,
+    opts: &InstallOpts,
+    use_regex: bool,
+) -> Result<(), Box<dyn Error>> {
+    let color_dir = lg_profile::color_directory();
+    let sdr_preset = effective_preset_for_mode(cfg, false);
+    let hdr_preset = effective_preset_for_mode(cfg, true);
+    let plan_mode_profiles =
+        |plan: &mut lg_core::plan::Plan, identity: Option<&lg_profile::DynamicMonitorIdentity>| {
+            lg_profile::plan_mode_profiles_install(
+                plan,
+                &color_dir,
+                &sdr_preset,
+                &hdr_preset,
+                &cfg.profile_name,
+                cfg.icc_gamma,
+                cfg.icc_luminance_cd_m2,
+                cfg.icc_generate_specialized_profiles,
+                tuning_from_config(cfg),
+                identity,
+            )
+        };
+
+    // (SDR, HDR) profiles shared by every monitor.
+    let mut shared = None;
+    if !opts.service_only {
+        if let Some(custom) = &opts.custom_profile {
+            let path = PathBuf::from(custom);
+            let plan_custom = |plan: &mut lg_core::plan::Plan| {
+                lg_profile::plan_profile_install(
+                    plan,
+                    &path,
+                    cfg.icc_gamma,
+                    cfg.icc_luminance_cd_m2,
+                    tuning_from_config(cfg),
+                    None,
+                )
+            };
+            if !plan_custom(plan)? && (opts.force || opts.skip_hash_check) {
+                plan.remove_file("remove_profile", &path);
+                plan_custom(plan)?;
+            }
+            shared = Some((path.clone(), path));
         } else {
-            println!("[DRY RUN] Would uninstall service");
+            shared = Some(plan_mode_profiles(plan, None)?);
+        }
+    }
+
+    if opts.profile_only {
+        let expected_name = shared
+            .as_ref()
+            .and_then(|(sdr, _)| sdr.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| cfg.profile_name.clone());
+        for path in lg_profile::stale_profiles(&expected_name) {
+            plan.remove_file("remove_stale_profile", &path);
         }
         return Ok(());
     }
 
+    let mut devices = Vec::new();
+    let mut per_monitor = Vec::new();
+    if !opts.skip_detect && !opts.for_packaging {
+        devices = find_matching_monitors(&cfg.monitor_match, use_regex)?;
+        plan.note(format!(
+            "{} monitor(s) match \"{}\" ({})",
+            devices.len(),
+            cfg.monitor_match,
+            if use_regex { "regex" } else { "substring" }
+        ));
+        match known_bad_associations(&devices) {
+            Ok(found) => {
+                for (device, bad) in found {
+                    plan.note(format!(
+                        "{} has known-bad {} associated; install asks to disassociate it",
+                        device.name, bad.profile
+                    ));
+                }
+            }
+            Err(e) => plan.note(format!("cannot read existing associations: {}", e)),
+        }
+        if cfg.icc_per_monitor_profiles && !opts.service_only {
+            for device in &devices {
+                let identity = identity_from_monitor(device);
+                per_monitor.push(plan_mode_profiles(plan, Some(&identity))?);
+            }
+        }
+    }
+
+    let cfg_path = config::config_path();
+    if !plan.file_exists(&cfg_path) && !opts.for_packaging {
+        Config::plan_write_to(plan, &Config::default(), &cfg_path);
+    }
+    if opts.pattern.is_some() || opts.regex || opts.skip_hdr {
+        Config::plan_write_to(plan, cfg, &cfg_path);
+    }
+
+    if (opts.per_user || opts.generic_default) && !opts.service_only && !opts.skip_detect {
+        let hdr_active = lg_monitor::is_any_display_hdr_enabled().unwrap_or(false);
+        for (i, device) in devices.iter().enumerate() {
+            let Some((sdr, hdr)) = per_monitor.get(i).or(shared.as_ref()) else {
+                continue;
+            };
+            let hdr = Some(hdr.as_path()).filter(|_| cfg.icc_hdr_association);
+            let active = hdr.filter(|_| hdr_active).unwrap_or(sdr.as_path());
+            lg_profile::plan_mode_associations(
+                plan,
+                &device.device_key,
+                active,
+                sdr,
+                hdr,
+                opts.generic_default,
+                opts.per_user,
+            );
+        }
+    }
+
+    lg_service::plan_install(plan, &cfg.monitor_match)?;
+    lg_service::plan_start_service(plan);
+    plan.note("the service reapplies profiles to matching monitors when it starts");
+    Ok(())
+}
+
+/// One association pass for `install --per-user` / `--generic-default`;
+/// failures only warn, since the service reapplies on start anyway.
+#[cfg(feature = "service")]
+fn install_initial_associations(cfg: &Config, opts: &InstallOpts, use_regex: bool) {
+    let sdr_preset = effective_preset_for_mode(cfg, false);
+    let hdr_preset = effective_preset_for_mode(cfg, true);
+    let mut steps = CliApplySteps {
+        cfg,
+        use_regex,
+        custom_profile: opts.custom_profile.as_deref().map(Path::new),
+        sdr_preset: &sdr_preset,
+        hdr_preset: &hdr_preset,
+        device_keys: &[],
+        connected: Vec::new(),
+        tally: ToastTally::new("install", Instant::now()),
+        report: None,
+    };
+    let pipeline = ReapplyPipeline::new(ReapplyOptions {
+        per_user: opts.per_user,
+        hdr: cfg.icc_hdr_association,
+        hdr_active: lg_monitor::is_any_display_hdr_enabled().unwrap_or(false),
+        generic_default: opts.generic_default,
+        ddc_brightness: None,
+        toast: false,
+    });
+    match pipeline.run(&mut steps) {
+        Ok(outcome) if outcome.failed() > 0 => say!(
+            "[WARN] Associations failed for {} of {} monitor(s); the service will retry",
+            outcome.failed(),
+            outcome.found
+        ),
+        Ok(outcome) => say!(
+            "[OK] Profiles associated for {} monitor(s)",
+            outcome.applied
+        ),
+        Err(e) => say!("[WARN] Could not associate profiles: {}", e),
+    }
+}
+
+#[cfg(feature = "service")]
+fn cmd_uninstall(
+    full: bool,
+    profile: bool,
+    feedback: Option<Option<PathBuf>>,
+    dry_run: bool,
+    json: bool,
+) -> Result<(), Box<dyn Error>> {
+    let feedback_path = feedback.map(|path| {
+        path.unwrap_or_else(|| {
+            std::env::temp_dir().join(format!(
+                "lg-ultragear-feedback-{}.txt",
+                chrono::Local::now().format("%Y%m%d-%H%M%S")
+            ))
+        })
+    });
+
+    if dry_run {
+        let mut plan = lg_core::plan::Plan::new("uninstall");
+        plan_uninstall(&mut plan, full, profile, feedback_path.as_deref());
+        return print_plan(&plan, json);
+    }
+
+    // The summary is built from the diagnostics log, which goes away with
+    // the config directory below.
+    if let Some(path) = &feedback_path {
+        match write_feedback_summary(path) {
+            Ok(()) => say!(
+                "[OK] Feedback summary written to {} (local only; attach it to an issue if you like)",
+                path.display()
+            ),
+            Err(e) => say!("[WARN] Could not write feedback summary: {}", e),
+        }
+    }
+
     // Always remove service (unless profile-only removal requested without --full)
     if full || !profile {
-        match lg_service::uninstall() {
+        match lg_service::uninstall_with_progress(
+            &mut print_progress,
+            &lg_core::shutdown::ShutdownToken::new(),
+        ) {
             Ok(()) => {
-                println!("[OK] Service uninstalled.");
+                say!("[OK] Service uninstalled.");
             }
             Err(e) => {
                 if full {
-                    println!("[NOTE] Service removal: {} (continuing)", e);
+                    say!("[NOTE] Service removal: {} (continuing)", e);
                 } else {
-                    return Err(e);
+                    return Err(packaging::failed_at(packaging::Step::Service)(e));
                 }
             }
         }
@@ -2233,13 +4321,15 @@ fn cmd_uninstall(full: bool, profile: bool, dry_run: bool) -> Result<(), Box<dyn
 
         let mut removed_any = false;
         for profile_path in targets {
-            if lg_profile::remove_profile(&profile_path)? {
-                println!("[OK] ICC profile removed from {}", profile_path.display());
+            if lg_profile::remove_profile(&profile_path)
+                .map_err(packaging::failed_at(packaging::Step::Profile))?
+            {
+                say!("[OK] ICC profile removed from {}", profile_path.display());
                 removed_any = true;
             }
         }
         if !removed_any {
-            println!("[NOTE] ICC profile not found (already removed)");
+            say!("[NOTE] ICC profile not found (already removed)");
         }
 
         // Clean up any stale/leftover ICM files (from test runs, etc.)
@@ -2249,7 +4339,7 @@ fn cmd_uninstall(full: bool, profile: bool, dry_run: bool) -> Result<(), Box<dyn
             .unwrap_or_else(|| cfg.profile_name.clone());
         let stale = lg_profile::cleanup_stale_profiles(&expected_name);
         for p in &stale {
-            println!("[OK] Removed stale profile: {}", p.display());
+            say!("[OK] Removed stale profile: {}", p.display());
         }
     }
 
@@ -2272,20 +4362,20 @@ fn cmd_uninstall(full: bool, profile: bool, dry_run: bool) -> Result<(), Box<dyn
                 }
                 match std::fs::remove_dir_all(&cfg_dir) {
                     Ok(()) => {
-                        println!("[OK] Config directory removed: {}", cfg_dir.display());
+                        say!("[OK] Config directory removed: {}", cfg_dir.display());
                         removed = true;
                         break;
                     }
                     Err(_) if attempt < 4 => continue,
                     Err(e) => {
-                        println!("[WARN] Could not remove config dir: {}", e);
+                        say!("[WARN] Could not remove config dir: {}", e);
                     }
                 }
             }
             if !removed {
                 // Schedule the directory itself for reboot-deletion.
                 lg_service::schedule_reboot_delete(&cfg_dir);
-                println!(
+                say!(
                     "[NOTE] Config directory scheduled for removal on next reboot: {}",
                     cfg_dir.display()
                 );
@@ -2294,40 +4384,90 @@ fn cmd_uninstall(full: bool, profile: bool, dry_run: bool) -> Result<(), Box<dyn
     }
 
     if !full && !profile {
-        println!(
+        say!(
             "     Config preserved at: {}",
             config::config_path().display()
         );
     }
 
     if full {
-        println!("\n[DONE] Full uninstall complete.");
+        say!("\n[DONE] Full uninstall complete.");
     } else {
-        println!("\n[DONE] Uninstall complete.");
+        say!("\n[DONE] Uninstall complete.");
+    }
+    Ok(())
+}
+
+/// Write the [`app_state::UsageSummary`] of the diagnostics log to `path`.
+#[cfg(feature = "service")]
+fn write_feedback_summary(path: &Path) -> Result<(), Box<dyn Error>> {
+    let summary = app_state::summarize_usage()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
     }
+    std::fs::write(path, summary.render(env!("APP_VERSION")))?;
     Ok(())
 }
 
+/// Build the `--dry-run` plan of [`cmd_uninstall`].
+#[cfg(feature = "service")]
+fn plan_uninstall(
+    plan: &mut lg_core::plan::Plan,
+    full: bool,
+    profile: bool,
+    feedback_path: Option<&Path>,
+) {
+    use lg_core::plan::OpKind;
+
+    if let Some(path) = feedback_path {
+        plan.push(OpKind::File, "write_feedback", path.display().to_string());
+    }
+    if full || !profile {
+        lg_service::plan_uninstall(plan);
+    }
+    if full || profile {
+        let cfg = Config::load();
+        let color_dir = lg_profile::color_directory();
+        let active = resolve_active_profile_path(&cfg);
+        let mut targets = vec![
+            active.clone(),
+            color_dir.join(lg_profile::GAMMA22_PROFILE_NAME),
+            color_dir.join(lg_profile::GAMMA24_PROFILE_NAME),
+        ];
+        targets.sort();
+        targets.dedup();
+        let mut removed_any = false;
+        for profile_path in targets {
+            removed_any |= plan.remove_file("remove_profile", &profile_path);
+        }
+        if !removed_any {
+            plan.note("ICC profile not found (already removed)");
+        }
+        let expected_name = active
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| cfg.profile_name.clone());
+        for path in lg_profile::stale_profiles(&expected_name) {
+            plan.remove_file("remove_stale_profile", &path);
+        }
+    }
+    if full {
+        let cfg_dir = config::config_dir();
+        if cfg_dir.exists() {
+            plan.push(OpKind::File, "remove_dir", cfg_dir.display().to_string())
+                .detail("config, state, logs and audit log");
+        }
+    }
+}
+
+#[cfg(feature = "service")]
 fn cmd_reinstall(
     pattern: Option<String>,
     regex: bool,
     dry_run: bool,
+    json: bool,
 ) -> Result<(), Box<dyn Error>> {
-    if dry_run {
-        println!("[DRY RUN] Would uninstall existing service");
-        println!("[DRY RUN] Would reinstall profile + service");
-        println!("[DRY RUN] Would start service");
-        return Ok(());
-    }
-
-    println!("[INFO] Removing existing installation...");
-    match lg_service::uninstall() {
-        Ok(()) => println!("[OK] Service uninstalled"),
-        Err(e) => println!("[NOTE] Service removal: {} (continuing)", e),
-    }
-
-    println!("\n[INFO] Installing fresh...");
-    cmd_install(InstallOpts {
+    let opts = InstallOpts {
         pattern,
         regex,
         profile_only: false,
@@ -2335,11 +4475,41 @@ fn cmd_reinstall(
         custom_profile: None,
         per_user: false,
         skip_hdr: false,
+        generic_default: false,
         skip_hash_check: false,
         force: false,
         skip_detect: false,
         dry_run: false,
-    })
+        json: false,
+        for_packaging: false,
+    };
+
+    if dry_run {
+        let mut cfg = Config::load();
+        if let Some(ref p) = opts.pattern {
+            cfg.monitor_match = p.clone();
+        }
+        if opts.regex {
+            cfg.monitor_match_regex = true;
+        }
+        let use_regex = effective_regex(opts.regex, &cfg);
+        let mut plan = lg_core::plan::Plan::new("reinstall");
+        lg_service::plan_uninstall(&mut plan);
+        plan_install(&mut plan, &cfg, &opts, use_regex)?;
+        return print_plan(&plan, json);
+    }
+
+    say!("[INFO] Removing existing installation...");
+    match lg_service::uninstall_with_progress(
+        &mut print_progress,
+        &lg_core::shutdown::ShutdownToken::new(),
+    ) {
+        Ok(()) => say!("[OK] Service uninstalled"),
+        Err(e) => say!("[NOTE] Service removal: {} (continuing)", e),
+    }
+
+    say!("\n[INFO] Installing fresh...");
+    cmd_install(opts)
 }
 
 // ============================================================================
@@ -2348,12 +4518,16 @@ fn cmd_reinstall(
 
 fn cmd_test(action: TestAction) -> Result<(), Box<dyn Error>> {
     match action {
+        #[cfg(feature = "notify")]
         TestAction::Toast { title, body } => {
-            println!("[INFO] Sending test toast notification...");
-            println!("[INFO] Title: {}", title);
-            println!("[INFO] Body:  {}", body);
+            let values = ToastValues::sample();
+            let title = toast_template::render(&title, &values);
+            let body = toast_template::render(&body, &values);
+            say!("[INFO] Sending test toast notification...");
+            say!("[INFO] Title: {}", title);
+            say!("[INFO] Body:  {}", body);
             lg_notify::show_reapply_toast(true, &title, &body, true);
-            println!("[DONE] Toast notification sent (check your notification center).");
+            say!("[DONE] Toast notification sent (check your notification center).");
         }
         TestAction::Profile => {
             let cfg = Config::load();
@@ -2366,8 +4540,8 @@ fn cmd_test(action: TestAction) -> Result<(), Box<dyn Error>> {
                 cfg.icc_luminance_cd_m2,
                 tuning_for_active_preset(&cfg, &selected_preset),
             )?;
-            println!("[INFO] Profile: {}", profile_path.display());
-            println!(
+            say!("[INFO] Profile: {}", profile_path.display());
+            say!(
                 "[INFO] Installed: {}",
                 if lg_profile::is_profile_installed(&profile_path) {
                     "yes"
@@ -2375,14 +4549,14 @@ fn cmd_test(action: TestAction) -> Result<(), Box<dyn Error>> {
                     "no"
                 }
             );
-            println!(
+            say!(
                 "[INFO] Dynamic size (gamma {:.3}, luminance {:.1}): {} bytes",
                 active_gamma,
                 cfg.icc_luminance_cd_m2,
                 generated.len()
             );
             let generated_report = lg_profile::validate_icc_profile_bytes(&generated);
-            println!(
+            say!(
                 "[INFO] Generated ICC validation: {}",
                 if generated_report.is_valid() {
                     "valid"
@@ -2391,13 +4565,13 @@ fn cmd_test(action: TestAction) -> Result<(), Box<dyn Error>> {
                 }
             );
             if !generated_report.warnings.is_empty() {
-                println!(
+                say!(
                     "[NOTE] Generated ICC warnings: {}",
                     generated_report.warnings.join(" | ")
                 );
             }
             if !generated_report.errors.is_empty() {
-                println!(
+                say!(
                     "[WARN] Generated ICC errors: {}",
                     generated_report.errors.join(" | ")
                 );
@@ -2407,9 +4581,9 @@ fn cmd_test(action: TestAction) -> Result<(), Box<dyn Error>> {
             if lg_profile::is_profile_installed(&profile_path) {
                 let on_disk = std::fs::read(&profile_path)?;
                 if on_disk == generated {
-                    println!("[OK] Profile on disk matches generated dynamic ICC");
+                    say!("[OK] Profile on disk matches generated dynamic ICC");
                 } else {
-                    println!(
+                    say!(
                         "[WARN] Profile on disk ({} bytes) differs from generated ({} bytes)",
                         on_disk.len(),
                         generated.len()
@@ -2417,7 +4591,7 @@ fn cmd_test(action: TestAction) -> Result<(), Box<dyn Error>> {
                 }
 
                 let on_disk_report = lg_profile::validate_icc_profile_bytes(&on_disk);
-                println!(
+                say!(
                     "[INFO] On-disk ICC validation: {}",
                     if on_disk_report.is_valid() {
                         "valid"
@@ -2426,42 +4600,42 @@ fn cmd_test(action: TestAction) -> Result<(), Box<dyn Error>> {
                     }
                 );
                 if !on_disk_report.warnings.is_empty() {
-                    println!(
+                    say!(
                         "[NOTE] On-disk ICC warnings: {}",
                         on_disk_report.warnings.join(" | ")
                     );
                 }
                 if !on_disk_report.errors.is_empty() {
-                    println!(
+                    say!(
                         "[WARN] On-disk ICC errors: {}",
                         on_disk_report.errors.join(" | ")
                     );
                 }
             } else {
-                println!("[NOTE] Profile not installed — run 'install' to generate it");
+                say!("[NOTE] Profile not installed — run 'install' to generate it");
             }
         }
         TestAction::Monitors { pattern, regex } => {
             let cfg = Config::load();
             let pattern = pattern.as_deref().unwrap_or(&cfg.monitor_match);
             let use_regex = effective_regex(regex, &cfg);
-            println!("[INFO] Testing monitor detection...");
-            println!(
+            say!("[INFO] Testing monitor detection...");
+            say!(
                 "[INFO] Pattern: \"{}\" ({})",
                 pattern,
                 if use_regex { "regex" } else { "substring" }
             );
-            println!();
+            say!();
 
             let devices = find_matching_monitors(pattern, use_regex)?;
             if devices.is_empty() {
-                println!("[WARN] No monitors matching \"{}\"", pattern);
+                say!("[WARN] No monitors matching \"{}\"", pattern);
             } else {
-                println!("[OK] Found {} monitor(s):\n", devices.len());
+                say!("[OK] Found {} monitor(s):\n", devices.len());
                 for (i, device) in devices.iter().enumerate() {
-                    println!("  {}. {}", i + 1, device.name);
-                    println!("     Device key: {}", device.device_key);
-                    println!(
+                    say!("  {}. {}", i + 1, device.name);
+                    say!("     Device key: {}", device.device_key);
+                    say!(
                         "     Serial: {}",
                         if device.serial.is_empty() {
                             "(unknown)"
@@ -2472,7 +4646,79 @@ fn cmd_test(action: TestAction) -> Result<(), Box<dyn Error>> {
                 }
             }
         }
+        TestAction::Pattern {
+            pattern,
+            regex,
+            before,
+        } => cmd_test_pattern(pattern, regex, &before)?,
+    }
+    Ok(())
+}
+
+/// Cover the first matched monitor with the test pattern. Space swaps its
+/// profile between `before` and the fix; closing leaves the fix applied.
+fn cmd_test_pattern(
+    pattern: Option<String>,
+    regex: bool,
+    before: &str,
+) -> Result<(), Box<dyn Error>> {
+    let cfg = Config::load();
+    let pattern = pattern.as_deref().unwrap_or(&cfg.monitor_match);
+    let use_regex = effective_regex(regex, &cfg);
+    let device = find_matching_monitors(pattern, use_regex)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("no monitors match '{}'", pattern))?;
+    let before_path = resolve_compare_profile(before, &cfg)?;
+    let after_path = resolve_compare_profile("fix", &cfg)
+        .map_err(|_| "the fix profile is not installed; run `apply` first")?;
+    let area = match lg_monitor::monitor_desktop_rect(&device.device_key)? {
+        Some(area) => area,
+        None => return Err(format!("{} is not part of the desktop right now", device.name).into()),
+    };
+
+    say!("[INFO] Monitor: {} ({})", device.name, device.device_key);
+    say!("[INFO] Before: {} ({})", before, before_path.display());
+    say!("[INFO] After:  fix ({})", after_path.display());
+    say!("[INFO] Space or click toggles before/after; Esc closes.");
+
+    let caption = |label: &str, spec: &str| {
+        format!(
+            "{}: {}   \u{2014}   Space / click: toggle   \u{00B7}   Esc: close",
+            label, spec
+        )
+    };
+    let apply = |path: &Path| {
+        if let Err(e) = lg_profile::reapply_profile_with_mode_associations(
+            &device.device_key,
+            path,
+            path,
+            None,
+            cfg.toggle_delay_ms,
+            false,
+        ) {
+            say!("[WARN] {}: {}", device.name, e);
+        }
+        lg_profile::refresh_display(false, cfg.refresh_broadcast_color, cfg.refresh_invalidate);
+        lg_profile::trigger_calibration_loader(cfg.refresh_calibration_loader);
+    };
+
+    let mut showing_before = false;
+    pattern::show(area, &caption("After", "fix"), || {
+        showing_before = !showing_before;
+        let (label, spec, path) = if showing_before {
+            ("Before", before, &before_path)
+        } else {
+            ("After", "fix", &after_path)
+        };
+        apply(path);
+        say!("[{}] {}", label.to_ascii_uppercase(), spec);
+        caption(label, spec)
+    })?;
+    if showing_before {
+        apply(&after_path);
     }
+    say!("[DONE] Test pattern closed; the fix profile is applied");
     Ok(())
 }
 
@@ -2552,14 +4798,14 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                     &[],
                 )?;
             if dry_run {
-                println!("[DRY RUN] Would write ICC to {}", output);
+                say!("[DRY RUN] Would write ICC to {}", output);
             } else {
                 std::fs::write(&output, &bytes)?;
-                println!("[OK] ICC generated: {}", output);
+                say!("[OK] ICC generated: {}", output);
             }
             let report = lg_profile::validate_icc_profile_bytes(&bytes);
-            println!("[INFO] Size: {} bytes", bytes.len());
-            println!(
+            say!("[INFO] Size: {} bytes", bytes.len());
+            say!(
                 "[INFO] Validation: {}",
                 if report.is_valid() {
                     "valid"
@@ -2568,16 +4814,16 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 }
             );
             if !report.warnings.is_empty() {
-                println!("[NOTE] Warnings: {}", report.warnings.join(" | "));
+                say!("[NOTE] Warnings: {}", report.warnings.join(" | "));
             }
             if !report.errors.is_empty() {
-                println!("[WARN] Errors: {}", report.errors.join(" | "));
+                say!("[WARN] Errors: {}", report.errors.join(" | "));
             }
         }
         IccAction::Validate { input, detailed } => {
             let bytes = std::fs::read(&input)?;
             let report = lg_profile::validate_icc_profile_bytes(&bytes);
-            println!(
+            say!(
                 "[INFO] ICC validation for {}: {}",
                 input,
                 if report.is_valid() {
@@ -2586,7 +4832,7 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                     "INVALID"
                 }
             );
-            println!(
+            say!(
                 "[INFO] Tags: {:?}, size: {}, declared: {:?}, known: {}, unknown: {}",
                 report.tag_count,
                 report.actual_size,
@@ -2595,7 +4841,7 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 report.unknown_tag_count
             );
             if detailed {
-                println!("[INFO] Tag details ({}):", report.tag_details.len());
+                say!("[INFO] Tag details ({}):", report.tag_details.len());
                 for detail in &report.tag_details {
                     let type_sig = detail.type_signature.as_deref().unwrap_or("----");
                     let reserved = match detail.reserved_bytes_zero {
@@ -2603,7 +4849,7 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                         Some(false) => "reserved=nonzero",
                         None => "reserved=n/a",
                     };
-                    println!(
+                    say!(
                         "  - {} type={} size={} known_sig={} known_type={} {}",
                         detail.signature,
                         type_sig,
@@ -2615,30 +4861,31 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 }
             }
             if !report.warnings.is_empty() {
-                println!("[NOTE] Warnings: {}", report.warnings.join(" | "));
+                say!("[NOTE] Warnings: {}", report.warnings.join(" | "));
             }
             if !report.errors.is_empty() {
-                println!("[WARN] Errors: {}", report.errors.join(" | "));
+                say!("[WARN] Errors: {}", report.errors.join(" | "));
                 return Err("ICC validation failed".into());
             }
         }
         IccAction::Inspect { input, detailed } => {
             let bytes = std::fs::read(&input)?;
             let report = lg_profile::inspect_icc_profile_bytes(&bytes)?;
-            println!("[INFO] ICC: {}", input);
-            println!("[INFO] Size: {}", report.profile_size);
-            println!("[INFO] Class: {}", report.device_class);
-            println!("[INFO] Color space: {}", report.data_color_space);
-            println!(
+            say!("[INFO] ICC: {}", input);
+            say!("[INFO] Size: {}", report.profile_size);
+            say!("[INFO] Class: {}", report.device_class);
+            say!("[INFO] Color space: {}", report.data_color_space);
+            say!(
                 "[INFO] Known tags: {}, unknown tags: {}",
-                report.known_tag_count, report.unknown_tag_count
+                report.known_tag_count,
+                report.unknown_tag_count
             );
-            println!("[INFO] Tags ({}):", report.tag_signatures.len());
+            say!("[INFO] Tags ({}):", report.tag_signatures.len());
             for sig in &report.tag_signatures {
-                println!("  - {}", sig);
+                say!("  - {}", sig);
             }
             if detailed {
-                println!("[INFO] Detailed tags:");
+                say!("[INFO] Detailed tags:");
                 for detail in &report.tag_details {
                     let type_sig = detail.type_signature.as_deref().unwrap_or("----");
                     let reserved = match detail.reserved_bytes_zero {
@@ -2646,7 +4893,7 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                         Some(false) => "reserved=nonzero",
                         None => "reserved=n/a",
                     };
-                    println!(
+                    say!(
                         "  - {} type={} size={} known_sig={} known_type={} {}",
                         detail.signature,
                         type_sig,
@@ -2662,12 +4909,12 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
             let bytes = std::fs::read(&input)?;
             let normalized = lg_profile::normalize_icc_profile_bytes(&bytes)?;
             if dry_run {
-                println!("[DRY RUN] Would write normalized ICC to {}", output);
+                say!("[DRY RUN] Would write normalized ICC to {}", output);
             } else {
                 std::fs::write(&output, &normalized)?;
-                println!("[OK] Normalized ICC written to {}", output);
+                say!("[OK] Normalized ICC written to {}", output);
             }
-            println!("[INFO] Size: {} -> {} bytes", bytes.len(), normalized.len());
+            say!("[INFO] Size: {} -> {} bytes", bytes.len(), normalized.len());
         }
         IccAction::SetTag {
             input,
@@ -2701,13 +4948,14 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 &[],
             )?;
             if dry_run {
-                println!(
+                say!(
                     "[DRY RUN] Would write ICC with tag {} to {}",
-                    signature, output
+                    signature,
+                    output
                 );
             } else {
                 std::fs::write(&output, &patched)?;
-                println!("[OK] Wrote ICC with tag {} to {}", signature, output);
+                say!("[OK] Wrote ICC with tag {} to {}", signature, output);
             }
         }
         IccAction::RemoveTag {
@@ -2719,13 +4967,14 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
             let source = std::fs::read(&input)?;
             let patched = lg_profile::patch_icc_profile_bytes(&source, &[], &[tag_signature])?;
             if dry_run {
-                println!(
+                say!(
                     "[DRY RUN] Would remove tag {} and write {}",
-                    signature, output
+                    signature,
+                    output
                 );
             } else {
                 std::fs::write(&output, &patched)?;
-                println!("[OK] Removed tag {} and wrote {}", signature, output);
+                say!("[OK] Removed tag {} and wrote {}", signature, output);
             }
         }
         IccAction::ImportI1 { input, output } => {
@@ -2748,24 +4997,26 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 out.to_string_lossy().to_string()
             });
             if dry_run {
-                println!(
+                say!(
                     "[DRY RUN] Would import i1 profile to {} (likely_i1={})",
-                    output_path, is_likely_i1
+                    output_path,
+                    is_likely_i1
                 );
             } else {
                 std::fs::write(&output_path, &normalized)?;
-                println!(
+                say!(
                     "[OK] Imported i1 profile to {} (likely_i1={})",
-                    output_path, is_likely_i1
+                    output_path,
+                    is_likely_i1
                 );
             }
-            println!(
+            say!(
                 "[INFO] Source tags: {}, class: {}, color space: {}",
                 inspection.tag_signatures.len(),
                 inspection.device_class,
                 inspection.data_color_space
             );
-            println!(
+            say!(
                 "[INFO] Validation: {}",
                 if report.is_valid() {
                     "valid"
@@ -2774,10 +5025,10 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 }
             );
             if !report.warnings.is_empty() {
-                println!("[NOTE] Warnings: {}", report.warnings.join(" | "));
+                say!("[NOTE] Warnings: {}", report.warnings.join(" | "));
             }
             if !report.errors.is_empty() {
-                println!("[WARN] Errors: {}", report.errors.join(" | "));
+                say!("[WARN] Errors: {}", report.errors.join(" | "));
                 return Err("imported ICC failed validation".into());
             }
         }
@@ -2856,18 +5107,21 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
             let report = lg_profile::validate_icc_profile_bytes(&bytes);
 
             if dry_run {
-                println!(
+                say!(
                     "[DRY RUN] Would generate optimized ICC at {}",
                     profile_path.display()
                 );
             } else {
-                println!("[OK] Optimized ICC generated: {}", profile_path.display());
+                say!("[OK] Optimized ICC generated: {}", profile_path.display());
             }
-            println!(
+            say!(
                 "[INFO] Preset='{}' active_preset='{}' gamma={:.3} luminance={:.1}",
-                cfg.icc_tuning_preset, active_preset, active_gamma, cfg.icc_luminance_cd_m2
+                cfg.icc_tuning_preset,
+                active_preset,
+                active_gamma,
+                cfg.icc_luminance_cd_m2
             );
-            println!(
+            say!(
                 "[INFO] Resolved tuning: lift={:.3} mid={:.3} comp={:.3} vcgt={} strength={:.3} target_black={:.3}",
                 tuning.black_lift,
                 tuning.midtone_boost,
@@ -2876,7 +5130,7 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 tuning.vcgt_strength,
                 tuning.target_black_cd_m2
             );
-            println!(
+            say!(
                 "[INFO] Validation: {}",
                 if report.is_valid() {
                     "valid"
@@ -2885,19 +5139,19 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 }
             );
             if !report.warnings.is_empty() {
-                println!("[NOTE] Warnings: {}", report.warnings.join(" | "));
+                say!("[NOTE] Warnings: {}", report.warnings.join(" | "));
             }
             if !report.errors.is_empty() {
-                println!("[WARN] Errors: {}", report.errors.join(" | "));
+                say!("[WARN] Errors: {}", report.errors.join(" | "));
                 return Err("optimized ICC validation failed".into());
             }
 
             if let Some(path) = output {
                 if dry_run {
-                    println!("[DRY RUN] Would export ICC to {}", path);
+                    say!("[DRY RUN] Would export ICC to {}", path);
                 } else {
                     std::fs::write(&path, &bytes)?;
-                    println!("[OK] Exported ICC to {}", path);
+                    say!("[OK] Exported ICC to {}", path);
                 }
             }
 
@@ -2908,13 +5162,13 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                     );
                 }
                 if dry_run {
-                    println!(
+                    say!(
                         "[DRY RUN] Would save optimized ICC settings to {}",
                         config::config_path().display()
                     );
                 } else {
                     Config::write_config(&cfg)?;
-                    println!(
+                    say!(
                         "[OK] Saved optimized ICC settings to {}",
                         config::config_path().display()
                     );
@@ -2925,7 +5179,7 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 let use_regex = cfg.monitor_match_regex;
                 let devices = find_matching_monitors(&cfg.monitor_match, use_regex)?;
                 if devices.is_empty() {
-                    println!("[WARN] No matching monitors found for apply.");
+                    say!("[WARN] No matching monitors found for apply.");
                 } else {
                     let active_hdr_mode = lg_monitor::is_any_display_hdr_enabled().unwrap_or(false);
                     let sdr_preset = effective_preset_for_mode(&cfg, false);
@@ -2972,7 +5226,7 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                             } else {
                                 &sdr_profile_for_device
                             };
-                            println!(
+                            say!(
                                 "[DRY RUN] Would apply optimized ICC active={} (sdr={}, hdr={}) to {}",
                                 active_profile_for_device.display(),
                                 sdr_profile_for_device.display(),
@@ -2980,8 +5234,8 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                                 device.name
                             );
                         }
-                        println!("[DRY RUN] Would refresh display and trigger calibration loader");
-                        println!(
+                        say!("[DRY RUN] Would refresh display and trigger calibration loader");
+                        say!(
                             "[DRY RUN] Would apply optimized ICC to {} monitor(s).",
                             devices.len()
                         );
@@ -3027,17 +5281,18 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                                 &device.device_key,
                                 active_profile_for_device,
                                 &sdr_profile_for_device,
-                                &hdr_profile_for_device,
+                                cfg.icc_hdr_association
+                                    .then_some(hdr_profile_for_device.as_path()),
                                 cfg.toggle_delay_ms,
                                 false,
                             )?;
-                            println!("[OK] Applied optimized ICC to {}", device.name);
+                            say!("[OK] Applied optimized ICC to {}", device.name);
                         }
                         // Use a non-disruptive refresh first to avoid monitor
                         // mode flashes/flicker during successful apply.
                         lg_profile::refresh_display(false, true, false);
                         lg_profile::trigger_calibration_loader(true);
-                        println!(
+                        say!(
                             "[DONE] Optimized ICC applied to {} monitor(s).",
                             devices.len()
                         );
@@ -3054,9 +5309,429 @@ fn cmd_icc(action: IccAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
 // DDC/CI commands
 // ============================================================================
 
-fn cmd_ddc(action: DdcAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
+fn cmd_display(action: DisplayAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
     let cfg = Config::load();
-
+    let action = match action {
+        DisplayAction::Hdr {
+            state,
+            pattern,
+            regex,
+        } => return cmd_display_hdr(&cfg, state, pattern, regex, dry_run),
+        DisplayAction::SdrWhite { action } => action,
+        DisplayAction::Mode { action } => return cmd_display_mode(&cfg, action, dry_run),
+    };
+
+    match action {
+        SdrWhiteAction::Get { pattern, regex } => {
+            let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
+            let monitors = find_matching_monitors(pat, effective_regex(regex, &cfg))?;
+            if monitors.is_empty() {
+                say!("[INFO] No monitors matching \"{}\"", pat);
+                return Ok(());
+            }
+            for mon in &monitors {
+                match lg_monitor::sdr_white_level(&mon.device_key)? {
+                    Some(level) => say!(
+                        "  {} — {} nits (HDR {})",
+                        mon.name,
+                        level.nits,
+                        if level.hdr_enabled { "on" } else { "off" }
+                    ),
+                    None => say!("  {} — not an active display", mon.name),
+                }
+            }
+        }
+
+        SdrWhiteAction::Set {
+            nits,
+            pattern,
+            regex,
+        } => {
+            if !(lg_monitor::SDR_WHITE_LEVEL_MIN_NITS..=lg_monitor::SDR_WHITE_LEVEL_MAX_NITS)
+                .contains(&nits)
+            {
+                return Err(format!(
+                    "SDR white level must be {}–{} nits",
+                    lg_monitor::SDR_WHITE_LEVEL_MIN_NITS,
+                    lg_monitor::SDR_WHITE_LEVEL_MAX_NITS
+                )
+                .into());
+            }
+            let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
+            if dry_run {
+                say!(
+                    "[DRY RUN] Would set SDR white level to {} nits for \"{}\"",
+                    nits,
+                    pat
+                );
+                return Ok(());
+            }
+            let monitors = find_matching_monitors(pat, effective_regex(regex, &cfg))?;
+            let mut changed = 0;
+            for mon in &monitors {
+                if lg_monitor::set_sdr_white_level(&mon.device_key, nits)? {
+                    changed += 1;
+                } else {
+                    say!("[SKIP] {} — HDR is off or display inactive", mon.name);
+                }
+            }
+            say!(
+                "[OK] SDR white level set to {} nits on {} monitor(s)",
+                nits,
+                changed
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_display_mode(
+    cfg: &Config,
+    action: DisplayModeAction,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    match action {
+        DisplayModeAction::Show { pattern, regex } => {
+            let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
+            let monitors = find_matching_monitors(pat, effective_regex(regex, cfg))?;
+            if monitors.is_empty() {
+                say!("[INFO] No monitors matching \"{}\"", pat);
+                return Ok(());
+            }
+            let state = app_state::load_display_mode_state();
+            say!(
+                "Display mode guard: {}",
+                if cfg.display_mode_guard { "on" } else { "off" }
+            );
+            for mon in &monitors {
+                let current = match lg_monitor::display_mode(&mon.device_key)? {
+                    Some(mode) => mode.to_string(),
+                    None => "not an active display".to_string(),
+                };
+                let expected = match state.expected(&mon.device_key) {
+                    Some(r) if r.pinned => format!("{} (pinned)", r.label()),
+                    Some(r) => r.label(),
+                    None => "-".to_string(),
+                };
+                say!("  {}", mon.name);
+                say!("    current:  {}", current);
+                say!("    expected: {}", expected);
+            }
+        }
+
+        DisplayModeAction::Set {
+            mode,
+            pattern,
+            regex,
+        } => {
+            let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
+            let monitors = find_matching_monitors(pat, effective_regex(regex, cfg))?;
+            if monitors.is_empty() {
+                say!("[INFO] No monitors matching \"{}\"", pat);
+                return Ok(());
+            }
+            if dry_run {
+                say!(
+                    "[DRY RUN] Would set and pin {} on {} monitor(s)",
+                    mode.map_or("the current mode".to_string(), |m| m.to_string()),
+                    monitors.len()
+                );
+                return Ok(());
+            }
+            let mut state = app_state::load_display_mode_state();
+            for mon in &monitors {
+                let pinned = match mode {
+                    Some(mode) => {
+                        if !lg_monitor::set_display_mode(&mon.device_key, mode)? {
+                            say!("[SKIP] {}: not an active display", mon.name);
+                            continue;
+                        }
+                        mode
+                    }
+                    None => match lg_monitor::display_mode(&mon.device_key)? {
+                        Some(current) => current,
+                        None => {
+                            say!("[SKIP] {}: not an active display", mon.name);
+                            continue;
+                        }
+                    },
+                };
+                state.record(app_state::DisplayModeRecord {
+                    device_key: mon.device_key.clone(),
+                    width: pinned.width,
+                    height: pinned.height,
+                    refresh_hz: pinned.refresh_hz,
+                    pinned: true,
+                });
+                say!("[OK] {}: {} (pinned)", mon.name, pinned);
+            }
+            app_state::save_display_mode_state(&state)?;
+            if !cfg.display_mode_guard {
+                say!("[NOTE] display_mode_guard is off; the service won't restore this mode");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Switch HDR on matched monitors and reapply to the ones that changed, so
+/// they pick up the profile for their new mode.
+fn cmd_display_hdr(
+    cfg: &Config,
+    state: lg_monitor::HdrSwitch,
+    pattern: Option<String>,
+    regex: bool,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
+    let monitors = find_matching_monitors(pat, effective_regex(regex, cfg))?;
+    if monitors.is_empty() {
+        say!("[INFO] No monitors matching \"{}\"", pat);
+        return Ok(());
+    }
+    if dry_run {
+        say!(
+            "[DRY RUN] Would switch HDR {} on {} monitor(s) and reapply",
+            state.as_str(),
+            monitors.len()
+        );
+        return Ok(());
+    }
+
+    let mut changed = Vec::new();
+    for mon in &monitors {
+        match lg_monitor::set_hdr(&mon.device_key, state)? {
+            Some(change) if change.changed() => {
+                say!(
+                    "[OK] {}: HDR {}",
+                    mon.name,
+                    if change.enabled { "on" } else { "off" }
+                );
+                changed.push(mon.device_key.clone());
+            }
+            Some(change) => say!(
+                "[SKIP] {}: HDR already {}",
+                mon.name,
+                if change.enabled { "on" } else { "off" }
+            ),
+            None => say!("[SKIP] {}: no HDR support or display inactive", mon.name),
+        }
+    }
+    app_state::append_diagnostic_event(
+        "cli",
+        "INFO",
+        "hdr_switch",
+        &format!("state={} changed={}", state.as_str(), changed.len()),
+    );
+    if changed.is_empty() {
+        return Ok(());
+    }
+
+    // Windows needs a moment to finish the mode change before the
+    // association for the new mode can be applied.
+    std::thread::sleep(Duration::from_millis(cfg.stabilize_delay_ms));
+    say!();
+    cmd_apply(ApplyOpts {
+        pattern: None,
+        regex,
+        profile_path: None,
+        profile_name: None,
+        device_keys: changed,
+        per_user: false,
+        skip_hdr: false,
+        generic_default: false,
+        toast: false,
+        no_toast: false,
+        verbose: false,
+        dry_run: false,
+    })
+}
+
+fn cmd_colorstore(action: ColorstoreAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    // Every connected display, so their SDR/HDR display defaults are read too.
+    let connected: Vec<String> = find_matching_monitors("", false)?
+        .into_iter()
+        .map(|m| m.device_key)
+        .collect();
+    let current = lg_profile::capture_color_store(&connected)?;
+
+    match action {
+        ColorstoreAction::Backup { file } => {
+            if dry_run {
+                say!(
+                    "[DRY RUN] Would save {} display(s) to {}",
+                    current.devices.len(),
+                    file.display()
+                );
+                return Ok(());
+            }
+            std::fs::write(&file, current.to_json()?)
+                .map_err(|e| format!("cannot write {}: {}", file.display(), e))?;
+            say!(
+                "[OK] Color store of {} display(s) saved to {}",
+                current.devices.len(),
+                file.display()
+            );
+        }
+        ColorstoreAction::Restore { file } => {
+            let raw = std::fs::read_to_string(&file)
+                .map_err(|e| format!("cannot read {}: {}", file.display(), e))?;
+            let backup = colorstore::ColorStoreBackup::from_json(&raw)
+                .map_err(|e| format!("{} is not a color store backup: {}", file.display(), e))?;
+            let plan = colorstore::restore_plan(&current, &backup);
+            if plan.is_empty() {
+                say!("[OK] Color store already matches {}", file.display());
+                return Ok(());
+            }
+            let mut failed = 0;
+            for change in &plan {
+                if dry_run {
+                    say!("[DRY RUN] Would restore: {}", change);
+                    continue;
+                }
+                match lg_profile::apply_restore_change(change) {
+                    Ok(()) => say!("[OK] {}", change),
+                    Err(e) => {
+                        failed += 1;
+                        say!("[WARN] Could not restore {}: {}", change, e);
+                    }
+                }
+            }
+            if dry_run {
+                return Ok(());
+            }
+            lg_profile::refresh_display(false, true, false);
+            app_state::append_diagnostic_event(
+                "cli",
+                "INFO",
+                "colorstore_restore",
+                &format!(
+                    "file={} changes={} failed={}",
+                    file.display(),
+                    plan.len(),
+                    failed
+                ),
+            );
+            if failed > 0 {
+                return Err(format!("{} change(s) could not be restored", failed).into());
+            }
+            say!("[DONE] Color store restored from {}", file.display());
+        }
+    }
+    Ok(())
+}
+
+fn cmd_audit(action: AuditAction) -> Result<(), Box<dyn Error>> {
+    match action {
+        AuditAction::Show { limit } => {
+            let entries = audit::read_recent_entries(limit)?;
+            if entries.is_empty() {
+                say!(
+                    "[INFO] No audit entries in {}",
+                    audit::audit_log_path().display()
+                );
+                return Ok(());
+            }
+            say!("Recent changes (newest first):");
+            for entry in &entries {
+                say!("  {}", entry);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn cmd_quirks(action: QuirksAction) -> Result<(), Box<dyn Error>> {
+    match action {
+        QuirksAction::List => {
+            let db = quirks::database();
+            let from_packs = db
+                .quirks
+                .iter()
+                .filter(|q| q.source != quirks::BUILTIN_SOURCE)
+                .count();
+            say!(
+                "Model quirks ({}, {} from packs in {}):",
+                db.quirks.len(),
+                from_packs,
+                quirks::quirks_dir().display()
+            );
+            for quirk in &db.quirks {
+                print_quirk(quirk);
+            }
+            for e in &db.errors {
+                say!("[WARN] Skipped pack {}: {}", e.path.display(), e.error);
+            }
+        }
+        QuirksAction::Validate { file } => {
+            let pack = quirks::load_pack(&file)
+                .map_err(|e| format!("Invalid quirk pack {}: {}", file.display(), e))?;
+            for quirk in &pack {
+                print_quirk(quirk);
+            }
+            let replaced = pack
+                .iter()
+                .filter(|q| {
+                    quirks::builtin()
+                        .iter()
+                        .any(|b| b.name.eq_ignore_ascii_case(&q.name))
+                })
+                .count();
+            if replaced > 0 {
+                say!(
+                    "[NOTE] {} quirk(s) replace a built-in of the same name",
+                    replaced
+                );
+            }
+            say!("[OK] {}: {} valid quirk(s)", file.display(), pack.len());
+        }
+    }
+    Ok(())
+}
+
+fn print_quirk(quirk: &quirks::ModelQuirk) {
+    say!("  {} [{}]", quirk.name, quirk.source);
+    if quirk.models.is_empty() {
+        say!("      models: (every matched monitor)");
+    } else {
+        say!("      models: {}", quirk.models.join(", "));
+    }
+    if !quirk.known_bad_profiles.is_empty() {
+        say!(
+            "      known-bad profiles: {}",
+            quirk.known_bad_profiles.join(", ")
+        );
+    }
+    if let Some(ms) = quirk.stabilize_delay_ms {
+        say!("      stabilize_delay_ms: {}", ms);
+    }
+    if let Some(ms) = quirk.reapply_delay_ms {
+        say!("      reapply_delay_ms: {}", ms);
+    }
+    if let Some(profile) = &quirk.profile {
+        say!("      profile: {}", profile);
+    }
+    if !quirk.ddc_steps.is_empty() {
+        let steps: Vec<String> = quirk.ddc_steps.iter().map(|s| s.to_string()).collect();
+        say!("      DDC steps: {}", steps.join("; "));
+    }
+    if !quirk.warning.is_empty() {
+        say!("      warning: {}", quirk.warning);
+    }
+    if !quirk.more_info.is_empty() {
+        say!("      more info: {}", quirk.more_info);
+    }
+    if !quirk.note.is_empty() {
+        say!("      {}", quirk.note);
+    }
+}
+
+#[cfg(feature = "ddc")]
+fn cmd_ddc(action: DdcAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    let cfg = Config::load();
+
     match action {
         DdcAction::Brightness { value, pattern } => {
             if value > 100 {
@@ -3066,34 +5741,37 @@ fn cmd_ddc(action: DdcAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 return Err(message.into());
             }
             if dry_run {
-                println!("[DRY RUN] Would set DDC brightness to {}", value);
+                say!("[DRY RUN] Would set DDC brightness to {}", value);
                 return Ok(());
             }
             if let Some(ref pat) = pattern {
-                println!(
+                say!(
                     "[INFO] Setting DDC brightness to {} for monitors matching \"{}\"...",
-                    value, pat
+                    value,
+                    pat
                 );
                 set_vcp_with_safety(pat, lg_monitor::ddc::VCP_BRIGHTNESS, value)?;
-                println!("[OK] Brightness set to {}", value);
+                say!("[OK] Brightness set to {}", value);
             } else {
-                println!(
+                say!(
                     "[INFO] Setting DDC brightness to {} on all monitors...",
                     value
                 );
                 let count = lg_monitor::ddc::set_brightness_all(value)?;
-                println!("[OK] Brightness set to {} on {} monitor(s)", value, count);
+                say!("[OK] Brightness set to {} on {} monitor(s)", value, count);
             }
         }
 
         DdcAction::ColorPreset { pattern } => {
             let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
-            println!("[INFO] Reading color preset from \"{}\"...", pat);
+            say!("[INFO] Reading color preset from \"{}\"...", pat);
             let val = lg_monitor::ddc::get_vcp_by_pattern(pat, lg_monitor::ddc::VCP_COLOR_PRESET)?;
             let name = color_preset_name(val.current);
-            println!(
+            say!(
                 "[OK] Color Preset: {} (value={}, max={})",
-                name, val.current, val.max
+                name,
+                val.current,
+                val.max
             );
         }
 
@@ -3103,28 +5781,33 @@ fn cmd_ddc(action: DdcAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 return Err(message.into());
             }
             if dry_run {
-                println!(
+                say!(
                     "[DRY RUN] Would set color preset to {} for \"{}\"",
-                    value, pat
+                    value,
+                    pat
                 );
                 return Ok(());
             }
             let name = color_preset_name(value);
-            println!(
+            say!(
                 "[INFO] Setting color preset to {} ({}) for \"{}\"...",
-                name, value, pat
+                name,
+                value,
+                pat
             );
             set_vcp_with_safety(pat, lg_monitor::ddc::VCP_COLOR_PRESET, value)?;
-            println!("[OK] Color preset set to {} ({})", name, value);
+            say!("[OK] Color preset set to {} ({})", name, value);
         }
 
         DdcAction::DisplayMode { pattern } => {
             let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
-            println!("[INFO] Reading display mode from \"{}\"...", pat);
+            say!("[INFO] Reading display mode from \"{}\"...", pat);
             let val = lg_monitor::ddc::get_vcp_by_pattern(pat, lg_monitor::ddc::VCP_DISPLAY_MODE)?;
-            println!(
+            say!(
                 "[OK] Display Mode: current={}, max={} (type={})",
-                val.current, val.max, val.vcp_type
+                val.current,
+                val.max,
+                val.vcp_type
             );
         }
 
@@ -3134,18 +5817,20 @@ fn cmd_ddc(action: DdcAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 return Err(message.into());
             }
             if dry_run {
-                println!(
+                say!(
                     "[DRY RUN] Would set display mode to {} for \"{}\"",
-                    value, pat
+                    value,
+                    pat
                 );
                 return Ok(());
             }
-            println!(
+            say!(
                 "[INFO] Setting display mode to {} for \"{}\"...",
-                value, pat
+                value,
+                pat
             );
             set_vcp_with_safety(pat, lg_monitor::ddc::VCP_DISPLAY_MODE, value)?;
-            println!("[OK] Display mode set to {}", value);
+            say!("[OK] Display mode set to {}", value);
         }
 
         DdcAction::ResetBrightnessContrast { pattern } => {
@@ -3156,12 +5841,12 @@ fn cmd_ddc(action: DdcAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 return Err(message.into());
             }
             if dry_run {
-                println!("[DRY RUN] Would reset brightness/contrast for \"{}\"", pat);
+                say!("[DRY RUN] Would reset brightness/contrast for \"{}\"", pat);
                 return Ok(());
             }
-            println!("[INFO] Resetting brightness + contrast for \"{}\"...", pat);
+            say!("[INFO] Resetting brightness + contrast for \"{}\"...", pat);
             set_vcp_with_safety(pat, lg_monitor::ddc::VCP_RESET_BRIGHTNESS_CONTRAST, 1)?;
-            println!("[OK] Brightness + contrast reset sent");
+            say!("[OK] Brightness + contrast reset sent");
         }
 
         DdcAction::ResetColor { pattern } => {
@@ -3170,33 +5855,142 @@ fn cmd_ddc(action: DdcAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 return Err(message.into());
             }
             if dry_run {
-                println!("[DRY RUN] Would reset color for \"{}\"", pat);
+                say!("[DRY RUN] Would reset color for \"{}\"", pat);
                 return Ok(());
             }
-            println!("[INFO] Resetting color for \"{}\"...", pat);
+            say!("[INFO] Resetting color for \"{}\"...", pat);
             set_vcp_with_safety(pat, lg_monitor::ddc::VCP_RESET_COLOR, 1)?;
-            println!("[OK] Color reset sent");
+            say!("[OK] Color reset sent");
         }
 
         DdcAction::Version { pattern } => {
             let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
-            println!("[INFO] Reading VCP version from \"{}\"...", pat);
+            say!("[INFO] Reading VCP version from \"{}\"...", pat);
             let val = lg_monitor::ddc::get_vcp_by_pattern(pat, lg_monitor::ddc::VCP_VERSION)?;
             let major = (val.current >> 8) & 0xFF;
             let minor = val.current & 0xFF;
-            println!(
+            say!(
                 "[OK] VCP Version: {}.{} (raw={})",
-                major, minor, val.current
+                major,
+                minor,
+                val.current
+            );
+        }
+
+        DdcAction::Sharpness { pattern } => {
+            let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
+            say!("[INFO] Reading sharpness from \"{}\"...", pat);
+            let val = lg_monitor::ddc::get_vcp_by_pattern(pat, lg_monitor::ddc::VCP_SHARPNESS)?;
+            say!("[OK] Sharpness: current={}, max={}", val.current, val.max);
+        }
+
+        DdcAction::SetSharpness { value, pattern } => {
+            let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
+            if let Some(message) = ddc_guardrail_error(lg_monitor::ddc::VCP_SHARPNESS, value) {
+                return Err(message.into());
+            }
+            if dry_run {
+                say!("[DRY RUN] Would set sharpness to {} for \"{}\"", value, pat);
+                return Ok(());
+            }
+            say!("[INFO] Setting sharpness to {} for \"{}\"...", value, pat);
+            set_vcp_with_safety(pat, lg_monitor::ddc::VCP_SHARPNESS, value)?;
+            say!("[OK] Sharpness set to {}", value);
+        }
+
+        DdcAction::ResponseTime { code, pattern } => {
+            let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
+            let (code, candidate) = match code {
+                Some(code) => (code, false),
+                None => (response_time_candidate_code(pat)?, true),
+            };
+            say!(
+                "[INFO] Reading response time (VCP 0x{:02X}) from \"{}\"...",
+                code,
+                pat
+            );
+            let val = lg_monitor::ddc::get_vcp_by_pattern(pat, code)?;
+            say!(
+                "[OK] Response Time: current={}, max={} (VCP 0x{:02X})",
+                val.current,
+                val.max,
+                code
+            );
+            if candidate {
+                say!(
+                    "[NOTE] VCP 0x{:02X} is a candidate guessed from the capabilities string; \
+                     set-response-time needs the code passed with --code",
+                    code
+                );
+            }
+        }
+
+        DdcAction::SetResponseTime {
+            value,
+            code,
+            pattern,
+        } => {
+            let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
+            if let Some(message) = ddc_guardrail_error(code, value) {
+                return Err(message.into());
+            }
+            if dry_run {
+                say!(
+                    "[DRY RUN] Would set response time (VCP 0x{:02X}) to {} for \"{}\"",
+                    code,
+                    value,
+                    pat
+                );
+                return Ok(());
+            }
+            say!(
+                "[INFO] Setting response time (VCP 0x{:02X}) to {} for \"{}\"...",
+                code,
+                value,
+                pat
             );
+            set_vcp_with_safety(pat, code, value)?;
+            say!("[OK] Response time set to {}", value);
+        }
+
+        DdcAction::Capabilities { pattern } => {
+            let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
+            say!("[INFO] Reading capabilities string from \"{}\"...", pat);
+            let caps = lg_monitor::ddc::get_capabilities_by_pattern(pat)?;
+            say!("{}\n", caps);
+            let entries = lg_monitor::ddc::parse_capabilities_vcp(&caps);
+            for entry in &entries {
+                let label = vcp_label(entry.code);
+                if entry.values.is_empty() {
+                    say!("  0x{:02X} {}", entry.code, label);
+                } else {
+                    let values: Vec<String> =
+                        entry.values.iter().map(|v| format!("{:02X}", v)).collect();
+                    say!(
+                        "  0x{:02X} {} values=[{}]",
+                        entry.code,
+                        label,
+                        values.join(" ")
+                    );
+                }
+            }
+            match lg_monitor::ddc::response_time_candidate(&entries) {
+                Some(code) => say!("\n[OK] Response time candidate: VCP 0x{:02X}", code),
+                None => say!("\n[NOTE] No response time candidate advertised"),
+            }
+            say!("[OK] {} VCP code(s) advertised", entries.len());
         }
 
         DdcAction::GetVcp { code, pattern } => {
             let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
-            println!("[INFO] Reading VCP 0x{:02X} from \"{}\"...", code, pat);
+            say!("[INFO] Reading VCP 0x{:02X} from \"{}\"...", code, pat);
             let val = lg_monitor::ddc::get_vcp_by_pattern(pat, code)?;
-            println!(
+            say!(
                 "[OK] VCP 0x{:02X}: current={}, max={}, type={}",
-                code, val.current, val.max, val.vcp_type
+                code,
+                val.current,
+                val.max,
+                val.vcp_type
             );
         }
 
@@ -3210,25 +6004,81 @@ fn cmd_ddc(action: DdcAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 return Err(message.into());
             }
             if dry_run {
-                println!(
+                say!(
                     "[DRY RUN] Would set VCP 0x{:02X} = {} for \"{}\"",
-                    code, value, pat
+                    code,
+                    value,
+                    pat
                 );
                 return Ok(());
             }
-            println!(
+            say!(
                 "[INFO] Setting VCP 0x{:02X} = {} for \"{}\"...",
-                code, value, pat
+                code,
+                value,
+                pat
             );
             set_vcp_with_safety(pat, code, value)?;
-            println!("[OK] VCP 0x{:02X} set to {}", code, value);
+            say!("[OK] VCP 0x{:02X} set to {}", code, value);
+        }
+
+        DdcAction::Watch {
+            codes,
+            interval_ms,
+            samples,
+            pattern,
+        } => {
+            let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
+            cmd_ddc_watch(pat, &codes, interval_ms, samples)?;
+        }
+
+        DdcAction::Script { file, pattern } => {
+            cmd_ddc_script(&file, pattern.as_deref(), &cfg, dry_run)?;
+        }
+
+        DdcAction::Power { state, pattern } => {
+            let pat = pattern.as_deref().unwrap_or(&cfg.monitor_match);
+            let target = if pat.is_empty() {
+                "all monitors".to_string()
+            } else {
+                format!("\"{}\"", pat)
+            };
+            if dry_run {
+                say!(
+                    "[DRY RUN] Would set power {} for {}",
+                    state.as_str(),
+                    target
+                );
+                return Ok(());
+            }
+            say!("[INFO] Setting power {} for {}...", state.as_str(), target);
+            let result = lg_monitor::ddc::set_power_by_pattern(pat, state)?;
+            for name in &result.set {
+                say!("[OK] {}: power {}", name, state.as_str());
+            }
+            for (name, kind) in &result.failed {
+                say!(
+                    "[WARN] {}: DDC/CI power {} failed{}",
+                    name,
+                    state.as_str(),
+                    kind.guidance()
+                        .map(|g| format!(" ({})", g))
+                        .unwrap_or_default()
+                );
+            }
+            if result.broadcast {
+                say!(
+                    "[NOTE] DDC/CI reached no monitor; asked Windows to set power {} on every display",
+                    state.as_str()
+                );
+            }
         }
 
         DdcAction::List => {
-            println!("[INFO] Listing physical monitors via DDC/CI...\n");
+            say!("[INFO] Listing physical monitors via DDC/CI...\n");
             let monitors = lg_monitor::ddc::list_physical_monitors()?;
             if monitors.is_empty() {
-                println!("  (no physical monitors found)");
+                say!("  (no physical monitors found)");
             } else {
                 for (idx, desc) in &monitors {
                     let label = if desc.is_empty() {
@@ -3236,20 +6086,20 @@ fn cmd_ddc(action: DdcAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                     } else {
                         desc.as_str()
                     };
-                    println!("  [{}] {}", idx, label);
+                    say!("  [{}] {}", idx, label);
                 }
-                println!("\n[OK] {} physical monitor(s) found", monitors.len());
+                say!("\n[OK] {} physical monitor(s) found", monitors.len());
             }
         }
         DdcAction::Map => {
-            println!("[INFO] Probing monitor DDC capability map...\n");
+            say!("[INFO] Probing monitor DDC capability map...\n");
             let maps = lg_monitor::ddc::probe_monitor_capabilities()?;
             if maps.is_empty() {
-                println!("  (no physical monitors found)");
+                say!("  (no physical monitors found)");
                 return Ok(());
             }
             for map in maps {
-                println!(
+                say!(
                     "── Monitor #{}: {} ──",
                     map.index,
                     if map.name.trim().is_empty() {
@@ -3260,7 +6110,7 @@ fn cmd_ddc(action: DdcAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                 );
                 for cap in map.capabilities {
                     if cap.supported {
-                        println!(
+                        say!(
                             "  0x{:02X} {:28} supported current={:?} max={:?} type={:?}{}",
                             cap.code,
                             cap.label,
@@ -3270,7 +6120,7 @@ fn cmd_ddc(action: DdcAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                             if cap.risky { " [risky]" } else { "" }
                         );
                     } else {
-                        println!(
+                        say!(
                             "  0x{:02X} {:28} not-supported{}",
                             cap.code,
                             cap.label,
@@ -3278,15 +6128,208 @@ fn cmd_ddc(action: DdcAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
                         );
                     }
                 }
-                println!();
+                say!();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Minimum `ddc watch` polling interval — DDC/CI round-trips take ~40ms
+/// and polling faster than this only produces I2C bus errors.
+#[cfg(feature = "ddc")]
+const DDC_WATCH_MIN_INTERVAL_MS: u64 = 100;
+
+#[cfg(feature = "ddc")]
+fn ddc_watch_interval(interval_ms: u64) -> std::time::Duration {
+    std::time::Duration::from_millis(interval_ms.max(DDC_WATCH_MIN_INTERVAL_MS))
+}
+
+/// Label for a VCP code from the known-code table, or empty if unknown.
+#[cfg(feature = "ddc")]
+fn vcp_label(code: u8) -> &'static str {
+    lg_monitor::ddc::known_vcp_codes()
+        .iter()
+        .find(|(known, _, _)| *known == code)
+        .map(|(_, label, _)| *label)
+        .unwrap_or("")
+}
+
+/// Poll `codes` on the monitor matching `pattern` and print every value
+/// change with a timestamp. Changes are also written to the diagnostics log
+/// so firmware-initiated dimming can be correlated with other events.
+#[cfg(feature = "ddc")]
+fn cmd_ddc_watch(
+    pattern: &str,
+    codes: &[u8],
+    interval_ms: u64,
+    samples: u64,
+) -> Result<(), Box<dyn Error>> {
+    if codes.is_empty() {
+        return Err("At least one VCP code is required".into());
+    }
+    let interval = ddc_watch_interval(interval_ms);
+    let code_list: Vec<String> = codes.iter().map(|c| format!("0x{:02X}", c)).collect();
+    say!(
+        "[INFO] Watching VCP {} on \"{}\" every {}ms (Ctrl+C to stop)...",
+        code_list.join(", "),
+        pattern,
+        interval.as_millis()
+    );
+
+    let mut last: Vec<Option<u32>> = vec![None; codes.len()];
+    let mut changes = 0u64;
+    let mut polls = 0u64;
+    loop {
+        for (slot, &code) in last.iter_mut().zip(codes) {
+            let stamp = chrono::Local::now().format("%H:%M:%S%.3f");
+            let val = match lg_monitor::ddc::get_vcp_by_pattern(pattern, code) {
+                Ok(val) => val,
+                Err(e) => {
+                    say!("[WARN] {} 0x{:02X} read failed: {}", stamp, code, e);
+                    continue;
+                }
+            };
+            match *slot {
+                None => say!(
+                    "[WATCH] {} 0x{:02X} {} initial={} (max={})",
+                    stamp,
+                    code,
+                    vcp_label(code),
+                    val.current,
+                    val.max
+                ),
+                Some(prev) if prev != val.current => {
+                    changes += 1;
+                    say!(
+                        "[WATCH] {} 0x{:02X} {} changed {} -> {} (max={})",
+                        stamp,
+                        code,
+                        vcp_label(code),
+                        prev,
+                        val.current,
+                        val.max
+                    );
+                    app_state::append_diagnostic_event(
+                        "cli",
+                        "INFO",
+                        "ddc_watch_change",
+                        &format!(
+                            "code=0x{:02X} from={} to={} max={}",
+                            code, prev, val.current, val.max
+                        ),
+                    );
+                }
+                Some(_) => {}
             }
+            *slot = Some(val.current);
+        }
+
+        polls += 1;
+        if samples > 0 && polls >= samples {
+            break;
         }
+        std::thread::sleep(interval);
     }
 
+    say!("[DONE] {} poll(s), {} change(s) observed", polls, changes);
     Ok(())
 }
 
+/// Script port over the monitor matching a pattern. Each call takes the
+/// DDC lock on its own, so a script's sleeps don't hold off the service.
+#[cfg(feature = "ddc")]
+struct PatternPort<'a>(&'a str);
+
+#[cfg(feature = "ddc")]
+impl lg_core::ddc_script::VcpPort for PatternPort<'_> {
+    fn get_vcp(&mut self, code: u8) -> Result<(u32, u32), Box<dyn Error>> {
+        lg_monitor::ddc::get_vcp_by_pattern(self.0, code).map(|v| (v.current, v.max))
+    }
+
+    fn set_vcp(&mut self, code: u8, value: u32) -> Result<(), Box<dyn Error>> {
+        lg_monitor::ddc::set_vcp_by_pattern(self.0, code, value)
+    }
+}
+
+/// Run a `ddc script` file, or list its steps with `--dry-run`. Every write
+/// is checked against the DDC guardrails before the first step runs.
+#[cfg(feature = "ddc")]
+fn cmd_ddc_script(
+    file: &Path,
+    pattern: Option<&str>,
+    cfg: &Config,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let source = std::fs::read_to_string(file)
+        .map_err(|e| format!("Cannot read {}: {}", file.display(), e))?;
+    let script = lg_core::ddc_script::DdcScript::from_toml(&source)
+        .map_err(|e| format!("Invalid script {}: {}", file.display(), e))?;
+    let pat = pattern
+        .or(script.monitor_match.as_deref())
+        .unwrap_or(&cfg.monitor_match);
+    for (code, value) in script.writes() {
+        if let Some(message) = ddc_guardrail_error(code, value) {
+            return Err(message.into());
+        }
+    }
+
+    let title = if script.name.is_empty() {
+        file.display().to_string()
+    } else {
+        script.name.clone()
+    };
+    say!(
+        "[INFO] Script \"{}\": {} step(s) on \"{}\"",
+        title,
+        script.steps.len(),
+        pat
+    );
+    if !script.description.is_empty() {
+        say!("       {}", script.description);
+    }
+
+    if dry_run {
+        for (i, step) in script.steps.iter().enumerate() {
+            say!("[DRY RUN] {:>3}. {}", i + 1, step);
+        }
+        return Ok(());
+    }
+
+    let results = lg_core::ddc_script::run(&script, &mut PatternPort(pat));
+    for result in &results {
+        say!("  {}", result);
+    }
+    match results.iter().find(|r| !r.ok) {
+        Some(failed) => Err(format!(
+            "Script stopped at step {} of {}",
+            failed.number,
+            script.steps.len()
+        )
+        .into()),
+        None => {
+            say!("[DONE] {} step(s) completed", results.len());
+            Ok(())
+        }
+    }
+}
+
+/// Guess the response-time VCP code from the monitor's capabilities string.
+/// Reads only: writes take the code from `--code`.
+#[cfg(feature = "ddc")]
+fn response_time_candidate_code(pattern: &str) -> Result<u8, Box<dyn Error>> {
+    let caps = lg_monitor::ddc::get_capabilities_by_pattern(pattern)?;
+    let entries = lg_monitor::ddc::parse_capabilities_vcp(&caps);
+    lg_monitor::ddc::response_time_candidate(&entries).ok_or_else(|| {
+        "No response time candidate found in the monitor capabilities; pass --code <hex> \
+         (see `ddc capabilities`)"
+            .into()
+    })
+}
+
 /// Human-readable color preset name from VCP 0x14 value.
+#[cfg(feature = "ddc")]
 fn color_preset_name(value: u32) -> &'static str {
     match value {
         1 => "sRGB",
@@ -3304,21 +6347,360 @@ fn color_preset_name(value: u32) -> &'static str {
     }
 }
 
+fn or_unknown(value: &str) -> &str {
+    if value.is_empty() {
+        "(unknown)"
+    } else {
+        value
+    }
+}
+
+fn cmd_profile(action: ProfileAction, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    match action {
+        ProfileAction::List => {
+            for name in lg_profile::installed_color_profiles()? {
+                say!("{}", name);
+            }
+        }
+        ProfileAction::Install { file, scope } => {
+            // Picks up `color_directory` for the user store.
+            Config::load();
+            let dir = lg_core::color_dir::directory_for(scope);
+            if dry_run {
+                say!(
+                    "[DRY RUN] Would install {} into {} ({} store)",
+                    file.display(),
+                    dir.display(),
+                    scope.label()
+                );
+                return Ok(());
+            }
+            let path = lg_profile::install_profile_for_scope(&file, scope)?;
+            match scope {
+                colorstore::Scope::SystemWide => {
+                    say!("[OK] Installed and registered: {}", path.display())
+                }
+                colorstore::Scope::CurrentUser => {
+                    say!("[OK] Installed in the user store: {}", path.display());
+                    say!(
+                        "[NOTE] Windows registers profiles machine-wide only; use --scope system \
+                         (as administrator) to associate it with a display"
+                    );
+                }
+            }
+        }
+        ProfileAction::Generate {
+            variant: Some(variant),
+            ..
+        } => {
+            let color_dir = lg_profile::color_directory();
+            let source = resolve_active_profile_path(&Config::load());
+            if dry_run {
+                say!(
+                    "[DRY RUN] Would write {} from {}",
+                    color_dir.join(variant.profile_name()).display(),
+                    source.display()
+                );
+                return Ok(());
+            }
+            let (path, written) =
+                lg_profile::ensure_white_point_variant_installed(&color_dir, &source, variant)?;
+            if written {
+                say!(
+                    "[OK] {} profile written: {}",
+                    variant.as_str(),
+                    path.display()
+                );
+            } else {
+                say!(
+                    "[OK] {} profile up to date: {}",
+                    variant.as_str(),
+                    path.display()
+                );
+            }
+            say!(
+                "[INFO] Use it with `apply --profile-name {}`",
+                variant.profile_name()
+            );
+        }
+        ProfileAction::Compare {
+            a,
+            b,
+            interval,
+            pattern,
+            regex,
+            toast,
+        } => cmd_profile_compare(&a, &b, interval, pattern, regex, toast, dry_run)?,
+        ProfileAction::Generate { variant: None, .. } => {
+            let color_dir = lg_profile::color_directory();
+            if dry_run {
+                say!(
+                    "[DRY RUN] Would write {}",
+                    color_dir.join(lg_profile::IDENTITY_PROFILE_NAME).display()
+                );
+                return Ok(());
+            }
+            let (path, written) = lg_profile::ensure_identity_profile_installed(&color_dir)?;
+            if written {
+                say!("[OK] Identity profile written: {}", path.display());
+            } else {
+                say!("[OK] Identity profile up to date: {}", path.display());
+            }
+            say!(
+                "[INFO] A/B test: `apply --profile-name {}`, then `apply` to go back",
+                lg_profile::IDENTITY_PROFILE_NAME
+            );
+        }
+        ProfileAction::Adjust {
+            input,
+            output,
+            brightness,
+            contrast,
+            gamma,
+        } => {
+            let input = match input {
+                Some(path) => PathBuf::from(path),
+                None => resolve_active_profile_path(&Config::load()),
+            };
+            let bytes = std::fs::read(&input)
+                .map_err(|e| format!("cannot read {}: {}", input.display(), e))?;
+            let adjusted = lg_profile::adjust_vcgt_profile_bytes(
+                &bytes,
+                lg_profile::VcgtAdjustment {
+                    brightness,
+                    contrast,
+                    gamma,
+                },
+            )?;
+            if dry_run {
+                say!("[DRY RUN] Would write adjusted ICC to {}", output);
+            } else {
+                std::fs::write(&output, &adjusted)?;
+                say!("[OK] Adjusted ICC written to {}", output);
+            }
+            say!(
+                "[INFO] {} -> brightness {:+}%, contrast {:+}%, gamma {:.2}",
+                input.display(),
+                brightness,
+                contrast,
+                gamma
+            );
+        }
+        ProfileAction::Audit { fix } => {
+            let cfg = Config::load();
+            let expected_name = resolve_active_profile_path(&cfg)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| cfg.profile_name.clone());
+            // Every connected display, not just the matched ones: a foreign
+            // monitor is still "connected" for the disconnected-device check.
+            let connected: Vec<String> = find_matching_monitors("", false)?
+                .into_iter()
+                .map(|m| m.device_key)
+                .collect();
+
+            say!("Auditing {}", lg_profile::color_directory().display());
+            let findings = lg_profile::audit_profile_store(&expected_name, &connected)?;
+            if findings.is_empty() {
+                say!("[OK] Color store is clean");
+                return Ok(());
+            }
+            for finding in &findings {
+                say!("[WARN] {}", finding);
+            }
+
+            if !fix {
+                say!(
+                    "[INFO] {} issue(s) found; run `profile audit --fix` to clean up",
+                    findings.len()
+                );
+                return Ok(());
+            }
+            let mut failed = 0;
+            for finding in &findings {
+                if dry_run {
+                    say!("[DRY RUN] Would fix: {}", finding);
+                    continue;
+                }
+                match lg_profile::fix_profile_audit_finding(finding) {
+                    Ok(()) => say!("[OK] Fixed: {}", finding),
+                    Err(e) => {
+                        failed += 1;
+                        say!("[WARN] Could not fix {}: {}", finding, e);
+                    }
+                }
+            }
+            if failed > 0 {
+                return Err(format!("{} issue(s) could not be fixed", failed).into());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Profile Windows falls back to when nothing else is associated.
+const STOCK_PROFILE_NAME: &str = "sRGB Color Space Profile.icm";
+
+/// A `profile compare` side: `stock` (the Windows sRGB default), `fix` (the
+/// active generated profile), `identity`, an installed profile name or a
+/// file path.
+fn resolve_compare_profile(spec: &str, cfg: &Config) -> Result<PathBuf, Box<dyn Error>> {
+    let path = match spec.trim().to_ascii_lowercase().as_str() {
+        "stock" => lg_profile::resolve_installed_profile(STOCK_PROFILE_NAME)?,
+        "fix" => resolve_active_profile_path(cfg),
+        "identity" => lg_profile::color_directory().join(lg_profile::IDENTITY_PROFILE_NAME),
+        _ if Path::new(spec).is_file() => PathBuf::from(spec),
+        _ => lg_profile::resolve_installed_profile(spec)?,
+    };
+    if !path.exists() {
+        return Err(format!("'{}' resolves to missing {}", spec, path.display()).into());
+    }
+    Ok(path)
+}
+
+fn cmd_profile_compare(
+    a: &str,
+    b: &str,
+    interval: u64,
+    pattern: Option<String>,
+    regex: bool,
+    toast: bool,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut cfg = Config::load();
+    if let Some(p) = pattern {
+        cfg.monitor_match = p;
+    }
+    let use_regex = effective_regex(regex, &cfg);
+    let sides = [
+        ("A", a, resolve_compare_profile(a, &cfg)?),
+        ("B", b, resolve_compare_profile(b, &cfg)?),
+    ];
+    let devices = find_matching_monitors(&cfg.monitor_match, use_regex)?;
+    if devices.is_empty() {
+        return Err(format!("no monitors match '{}'", cfg.monitor_match).into());
+    }
+    for (label, spec, path) in &sides {
+        say!("[INFO] {} = {} ({})", label, spec, path.display());
+    }
+    if dry_run {
+        say!(
+            "[DRY RUN] Would alternate {} monitor(s) every {}s",
+            devices.len(),
+            interval
+        );
+        return Ok(());
+    }
+
+    say!(
+        "[INFO] Switching {} monitor(s) every {}s. Press any key to stop.",
+        devices.len(),
+        interval
+    );
+    let mut last = 0;
+    for (i, (label, spec, path)) in sides.iter().cycle().enumerate() {
+        for device in &devices {
+            if let Err(e) = lg_profile::reapply_profile_with_mode_associations(
+                &device.device_key,
+                path,
+                path,
+                None,
+                cfg.toggle_delay_ms,
+                false,
+            ) {
+                say!("[WARN] {}: {}", device.name, e);
+            }
+        }
+        lg_profile::refresh_display(false, cfg.refresh_broadcast_color, cfg.refresh_invalidate);
+        lg_profile::trigger_calibration_loader(cfg.refresh_calibration_loader);
+        say!("[{}] {}", label, spec);
+        #[cfg(feature = "notify")]
+        if toast {
+            lg_notify::show_reapply_toast(
+                true,
+                "Profile compare",
+                &format!("{}: {}", label, spec),
+                false,
+            );
+        }
+        #[cfg(not(feature = "notify"))]
+        let _ = toast;
+        last = i % sides.len();
+        if wait_for_any_key(Duration::from_secs(interval))? {
+            break;
+        }
+    }
+    let (label, spec, _) = &sides[last];
+    say!(
+        "[DONE] Left {} ({}) associated; run `apply` to restore the fix",
+        label,
+        spec
+    );
+    Ok(())
+}
+
+/// Wait up to `timeout` for a key press. Built without crossterm (no `ddc`
+/// feature) only Enter is seen, read on a helper thread.
+#[cfg(feature = "ddc")]
+fn wait_for_any_key(timeout: Duration) -> io::Result<bool> {
+    terminal::enable_raw_mode()?;
+    let deadline = Instant::now() + timeout;
+    let pressed = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break Ok(false);
+        }
+        match event::poll(remaining.min(Duration::from_millis(200))) {
+            Ok(true) => match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => break Ok(true),
+                Ok(_) => {}
+                Err(e) => break Err(e),
+            },
+            Ok(false) => {}
+            Err(e) => break Err(e),
+        }
+    };
+    terminal::disable_raw_mode()?;
+    pressed
+}
+
+#[cfg(not(feature = "ddc"))]
+fn wait_for_any_key(timeout: Duration) -> io::Result<bool> {
+    use std::sync::{mpsc, Mutex, OnceLock};
+
+    static ENTER: OnceLock<Mutex<mpsc::Receiver<()>>> = OnceLock::new();
+    let rx = ENTER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut line = String::new();
+            let _ = io::stdin().read_line(&mut line);
+            let _ = tx.send(());
+        });
+        Mutex::new(rx)
+    });
+    let rx = rx.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(rx.recv_timeout(timeout).is_ok())
+}
+
 fn cmd_probe(pattern: Option<String>, regex: bool) -> Result<(), Box<dyn Error>> {
-    let cfg = Config::load();
+    let cfg = timing::phase("config load", Config::load);
     let pattern_str = pattern.as_deref().unwrap_or(&cfg.monitor_match);
     let use_regex = effective_regex(regex, &cfg);
     let selected_preset = effective_preset_for_mode(&cfg, false);
     let preset = lg_profile::parse_dynamic_icc_preset(&selected_preset);
     let active_gamma = preset.gamma(cfg.icc_gamma);
 
-    println!("═══ LG UltraGear Probe ═══\n");
+    say!("═══ LG UltraGear Probe ═══\n");
 
     // Profile status
-    let profile_path = resolve_active_profile_path(&cfg);
-    println!("── Profile ──");
-    println!("  Path:      {}", profile_path.display());
-    println!(
+    let profile_path = lg_profile::resolve_active_profile_path(
+        &lg_profile::color_directory(),
+        &selected_preset,
+        &cfg.profile_name,
+    );
+    say!("── Profile ──");
+    say!("  Path:      {}", profile_path.display());
+    say!(
         "  Installed: {}",
         if lg_profile::is_profile_installed(&profile_path) {
             "yes ✓"
@@ -3326,7 +6708,7 @@ fn cmd_probe(pattern: Option<String>, regex: bool) -> Result<(), Box<dyn Error>>
             "no ✗"
         }
     );
-    println!(
+    say!(
         "  Dynamic:   {} bytes (gamma {:.3}, luminance {:.1})",
         lg_profile::generate_dynamic_profile_bytes_with_luminance_and_tuning(
             active_gamma,
@@ -3337,40 +6719,109 @@ fn cmd_probe(pattern: Option<String>, regex: bool) -> Result<(), Box<dyn Error>>
         active_gamma,
         cfg.icc_luminance_cd_m2
     );
+    say!("  WCS API:   {}", lg_profile::wcs_api_path().label());
+    #[cfg(feature = "ddc")]
+    {
+        // A brightness read tells "DDC/CI off in the OSD / KVM in the way"
+        // apart from a working link.
+        let link_problem = lg_monitor::ddc::is_supported()
+            .then(|| {
+                lg_monitor::ddc::get_vcp_by_pattern(
+                    &cfg.monitor_match,
+                    lg_monitor::ddc::VCP_BRIGHTNESS,
+                )
+                .err()
+            })
+            .flatten()
+            .and_then(|e| lg_monitor::ddc::error_kind(e.as_ref()))
+            .filter(|kind| kind.is_link_problem());
+        say!(
+            "  DDC/CI:    {}",
+            match (lg_monitor::ddc::is_supported(), link_problem) {
+                (false, _) => "not supported on this system",
+                (true, Some(_)) => "not responding",
+                (true, None) => "available",
+            }
+        );
+        if let Some(guidance) = link_problem.and_then(|kind| kind.guidance()) {
+            say!("  [NOTE] DDC/CI: {}", guidance);
+        }
+    }
 
     // Service status
-    println!("\n── Service ──");
-    let (installed, running) = lg_service::query_service_info();
-    println!("  Installed: {}", if installed { "yes ✓" } else { "no ✗" });
-    println!("  Running:   {}", if running { "yes ✓" } else { "no ✗" });
+    #[cfg(feature = "service")]
+    {
+        say!("\n── Service ──");
+        let (installed, running) = timing::phase("service query", lg_service::query_service_info);
+        say!("  Installed: {}", if installed { "yes ✓" } else { "no ✗" });
+        say!("  Running:   {}", if running { "yes ✓" } else { "no ✗" });
+    }
 
     // Config summary
-    println!("\n── Config ──");
-    println!("  File:    {}", config::config_path().display());
-    println!("  Pattern: \"{}\"", cfg.monitor_match);
-    println!(
+    say!("\n── Config ──");
+    say!("  File:    {}", config::config_path().display());
+    say!("  Pattern: \"{}\"", cfg.monitor_match);
+    say!(
         "  Match:   {}",
-        if use_regex { "regex" } else { "substring" }
+        match_mode_label(&cfg.monitor_match, use_regex)
     );
-    println!("  Preset:  \"{}\"", selected_preset);
-    println!("  Gamma:   {:.3}", active_gamma);
-    println!("  Lumi:    {:.1} cd/m^2", cfg.icc_luminance_cd_m2);
-    println!(
+    say!("  Preset:  \"{}\"", selected_preset);
+    say!("  Gamma:   {:.3}", active_gamma);
+    say!("  Lumi:    {:.1} cd/m^2", cfg.icc_luminance_cd_m2);
+    say!(
         "  Toast:   {}",
         if cfg.toast_enabled { "on" } else { "off" }
     );
-    println!("  Verbose: {}", cfg.verbose);
+    say!("  Verbose: {}", cfg.verbose);
+
+    // Display topology, as the reapply display-count conditions see it
+    say!("\n── Displays ──");
+    match lg_monitor::query_display_topology() {
+        Ok(topology) => {
+            say!("  Active:    {}", topology.active_displays);
+            say!(
+                "  Built-in:  {}",
+                match topology.internal_panel {
+                    Some(true) => "on",
+                    Some(false) => "off",
+                    None => "none",
+                }
+            );
+        }
+        Err(e) => say!("  [WARN] Display query failed: {}", e),
+    }
+    #[cfg(feature = "service")]
+    say!(
+        "  Lid:       {}",
+        match lg_service::fetch_lid_state() {
+            Ok(Some(lid)) => lid.as_str(),
+            Ok(None) => "unknown (no lid reported)",
+            Err(_) => "unknown (service not running)",
+        }
+    );
 
     // Monitor detection
-    println!("\n── Monitors (matching \"{}\") ──", pattern_str);
-    let devices = find_matching_monitors(pattern_str, use_regex)?;
+    say!("\n── Monitors (matching \"{}\") ──", pattern_str);
+    let devices = timing::phase("monitor scan (WMI)", || {
+        find_matching_monitors(pattern_str, use_regex)
+    })?;
+    let adapters = if devices.is_empty() {
+        Vec::new()
+    } else {
+        timing::phase("adapter query (WMI)", lg_monitor::query_display_adapters).unwrap_or_else(
+            |e| {
+                say!("  [WARN] Adapter query failed: {}", e);
+                Vec::new()
+            },
+        )
+    };
     if devices.is_empty() {
-        println!("  (none found)");
+        say!("  (none found)");
     } else {
         for (i, device) in devices.iter().enumerate() {
-            println!("  {}. {}", i + 1, device.name);
-            println!("     Device: {}", device.device_key);
-            println!(
+            say!("  {}. {}", i + 1, device.name);
+            say!("     Device: {}", device.device_key);
+            say!(
                 "     Serial: {}",
                 if device.serial.is_empty() {
                     "(unknown)"
@@ -3378,10 +6829,64 @@ fn cmd_probe(pattern: Option<String>, regex: bool) -> Result<(), Box<dyn Error>>
                     &device.serial
                 }
             );
+            say!("     Fields: {}", or_unknown(&device.field_expression()));
+            match lg_monitor::adapter_for_monitor(&adapters, device) {
+                Some(adapter) => {
+                    say!("     GPU:    {}", or_unknown(&adapter.adapter_name));
+                    say!("     Driver: {}", or_unknown(&adapter.driver_version));
+                    say!("     Output: {}", adapter.connector);
+                }
+                None => say!("     GPU:    (not on an active display path)"),
+            }
+        }
+    }
+
+    // Our profile associated with a device key that is gone, usually because
+    // a driver update re-enumerated the monitor under a new instance ID.
+    let associations = lg_profile::read_device_associations().unwrap_or_default();
+    let connected: Vec<String> = find_matching_monitors("", false)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|m| m.device_key)
+        .collect();
+    let stale: Vec<_> = associations
+        .iter()
+        .filter(|a| {
+            a.profiles
+                .iter()
+                .any(|p| p.to_ascii_lowercase().starts_with("lg-ultragear-"))
+                && !connected
+                    .iter()
+                    .any(|k| lg_profile::device_keys_match(k, &a.device_key))
+        })
+        .collect();
+    if !stale.is_empty() {
+        say!("\n── Stored device keys ──");
+        for assoc in stale {
+            say!(
+                "  [WARN] {} no longer exists (profile: {})",
+                assoc.device_key,
+                assoc.profiles.join(", ")
+            );
+            let successor = devices.iter().find(|d| {
+                lg_profile::find_previous_device_associations(
+                    &d.device_key,
+                    &associations,
+                    &connected,
+                )
+                .is_some_and(|p| p.device_key == assoc.device_key)
+            });
+            if let Some(device) = successor {
+                say!(
+                    "         Likely re-enumerated as {} (driver update?)",
+                    device.device_key
+                );
+            }
+            say!("         `profile audit --fix` removes the stale association");
         }
     }
 
-    println!("\n═══ Probe complete ═══");
+    say!("\n═══ Probe complete ═══");
     Ok(())
 }
 
@@ -0,0 +1,249 @@
+//! Command output.
+//!
+//! Commands report through [`say!`] (and [`fail!`] for a step that failed)
+//! instead of printing, and the run's [`Format`] decides where that goes:
+//!
+//! - `human` prints each line as written
+//! - `json` prints each line as a JSON object (`kind`, `text`), one per
+//!   line, with a final `error` object when the command fails
+//! - `quiet` (`--quiet`) drops everything but failures
+//!
+//! The `kind` is the line's `[OK]`/`[WARN]`-style tag in lower case
+//! (`dry_run` for `[DRY RUN]`), or `text` for an untagged line.
+//!
+//! [`capture`] also keeps a copy of what is reported, which the TUI shows
+//! in its result pane once an action finishes.
+//!
+//! `-v`, `-vv` and `-vvv` raise the log level from `warn` to `info`,
+//! `debug` and `trace`, the same for the console and for `watch`'s log file
+//! and Event Log. `--quiet` lowers the console to `error`.
+
+use log::LevelFilter;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+/// How command output is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Format {
+    #[default]
+    Human,
+    Json,
+    Quiet,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "human" => Some(Format::Human),
+            "json" => Some(Format::Json),
+            "quiet" => Some(Format::Quiet),
+            _ => None,
+        }
+    }
+}
+
+static FORMAT: Mutex<Format> = Mutex::new(Format::Human);
+
+/// Use `format` for the rest of the run.
+pub(crate) fn set_format(format: Format) {
+    if let Ok(mut current) = FORMAT.lock() {
+        *current = format;
+    }
+}
+
+pub(crate) fn format() -> Format {
+    FORMAT.lock().map(|f| *f).unwrap_or_default()
+}
+
+/// `-v` count and `--quiet` from the command line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Verbosity {
+    pub quiet: bool,
+    pub verbose: u8,
+}
+
+impl Verbosity {
+    /// Console log level.
+    pub fn level(self) -> LevelFilter {
+        if self.quiet {
+            return LevelFilter::Error;
+        }
+        match self.verbose {
+            0 => LevelFilter::Warn,
+            1 => LevelFilter::Info,
+            2 => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        }
+    }
+
+    /// Level for the log file and the Event Log: the console's, but never
+    /// below `info`, so they keep a record of what a quiet run did.
+    #[cfg(feature = "service")]
+    pub fn sink_level(self) -> LevelFilter {
+        self.level().max(LevelFilter::Info)
+    }
+
+    /// Whether `-v` was given (and not overridden by `--quiet`).
+    pub fn is_verbose(self) -> bool {
+        !self.quiet && self.verbose > 0
+    }
+}
+
+/// One line of command output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Line {
+    pub kind: String,
+    pub text: String,
+}
+
+impl Line {
+    /// Split a leading `[TAG]` off `text`. Untagged lines keep their
+    /// indentation and have kind `text`.
+    pub fn parse(text: &str) -> Self {
+        let tagged = text.trim_start().strip_prefix('[').and_then(|rest| {
+            let (tag, rest) = rest.split_once(']')?;
+            let is_tag = !tag.is_empty() && tag.chars().all(|c| c.is_ascii_uppercase() || c == ' ');
+            is_tag.then(|| {
+                (
+                    tag.to_ascii_lowercase().replace(' ', "_"),
+                    rest.trim_start(),
+                )
+            })
+        });
+        match tagged {
+            Some((kind, rest)) => Line {
+                kind,
+                text: rest.to_string(),
+            },
+            None => Line {
+                kind: "text".to_string(),
+                text: text.to_string(),
+            },
+        }
+    }
+
+    /// The line as one JSON object.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+thread_local! {
+    /// Lines kept by a running [`capture`] on this thread.
+    static CAPTURED: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Run `f`, returning what it reported on this thread along with its
+/// result. Output is still shown as usual while `f` runs.
+#[cfg(feature = "tui")]
+pub(crate) fn capture<T>(f: impl FnOnce() -> T) -> (T, Vec<String>) {
+    let outer = CAPTURED.with(|c| c.borrow_mut().replace(Vec::new()));
+    let result = f();
+    let lines = CAPTURED.with(|c| {
+        let mut c = c.borrow_mut();
+        let lines = c.take().unwrap_or_default();
+        // A capture inside another one reports to both.
+        *c = outer.map(|mut outer| {
+            outer.extend(lines.iter().cloned());
+            outer
+        });
+        lines
+    });
+    (result, lines)
+}
+
+/// Add `text` to a running [`capture`], line by line. For output shown
+/// without [`say!`], like the TUI's coloured tags.
+pub(crate) fn record(text: &str) {
+    CAPTURED.with(|c| {
+        if let Some(lines) = c.borrow_mut().as_mut() {
+            if text.is_empty() {
+                lines.push(String::new());
+            }
+            lines.extend(text.lines().map(str::to_string));
+        }
+    });
+}
+
+/// Report a line; used through [`say!`].
+pub(crate) fn say_line(args: fmt::Arguments) {
+    let text = args.to_string();
+    record(&text);
+    match format() {
+        Format::Human => println!("{}", text),
+        Format::Json => println!("{}", Line::parse(&text).to_json()),
+        Format::Quiet => {}
+    }
+}
+
+/// Report a failed step; used through [`fail!`]. Shown even when quiet.
+pub(crate) fn fail_line(args: fmt::Arguments) {
+    let text = args.to_string();
+    record(&text);
+    match format() {
+        Format::Human | Format::Quiet => println!("{}", text),
+        Format::Json => println!("{}", Line::parse(&text).to_json()),
+    }
+}
+
+/// Report already rendered text (a table, a plan) line by line. An empty
+/// `text` is one empty line.
+pub(crate) fn block(text: &str) {
+    if text.is_empty() {
+        say_line(format_args!(""));
+    }
+    for line in text.lines() {
+        say_line(format_args!("{}", line));
+    }
+}
+
+/// Ask for an answer on the same line. Goes to stderr unless the output is
+/// for people, so JSON output stays parseable.
+pub(crate) fn prompt(text: &str) {
+    if format() == Format::Human {
+        print!("{}", text);
+        let _ = io::stdout().flush();
+    } else {
+        eprint!("{}", text);
+    }
+}
+
+/// End of the command: in JSON a failure becomes a last `error` line.
+/// `main` still returns the error, for the exit code and stderr.
+pub(crate) fn finish(result: &Result<(), Box<dyn Error>>) {
+    if let (Format::Json, Err(e)) = (format(), result) {
+        let line = Line {
+            kind: "error".to_string(),
+            text: e.to_string(),
+        };
+        println!("{}", line.to_json());
+    }
+}
+
+/// `println!`-style reporting through the run's [`Format`]. Everything a
+/// command prints goes through this except its failures.
+macro_rules! say {
+    () => {
+        $crate::output::say_line(format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        $crate::output::say_line(format_args!($($arg)*))
+    };
+}
+pub(crate) use say;
+
+/// Like [`say!`], for a step that failed; shown even with `--quiet`.
+macro_rules! fail {
+    ($($arg:tt)*) => {
+        $crate::output::fail_line(format_args!($($arg)*))
+    };
+}
+pub(crate) use fail;
+
+#[cfg(test)]
+#[path = "tests/output_tests.rs"]
+mod tests;
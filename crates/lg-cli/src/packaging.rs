@@ -0,0 +1,82 @@
+//! `install --for-packaging` / `uninstall --for-packaging`.
+//!
+//! Package manager scripts (winget, Chocolatey) run the binary from an
+//! already elevated, unattended context. In this mode install never
+//! relaunches through UAC, never prompts, skips monitor detection and leaves
+//! the config file alone, so every machine gets the same files. The outcome
+//! is reported as one of the fixed exit codes below instead of an error
+//! message the script would have to parse.
+
+use std::error::Error;
+use std::fmt;
+
+/// Everything was done.
+pub const EXIT_OK: i32 = 0;
+/// A failure not tied to one step.
+pub const EXIT_FAILED: i32 = 1;
+// 2 is taken by clap for usage errors.
+/// The process is not elevated; nothing was changed.
+pub const EXIT_NOT_ELEVATED: i32 = 3;
+/// Writing or removing the ICC profiles failed.
+pub const EXIT_PROFILE_FAILED: i32 = 4;
+/// Registering or removing the service failed.
+pub const EXIT_SERVICE_FAILED: i32 = 5;
+/// The service was installed but did not start.
+pub const EXIT_SERVICE_START_FAILED: i32 = 6;
+
+/// Step of an install or uninstall that can fail on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Profile,
+    Service,
+    ServiceStart,
+}
+
+impl Step {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Step::Profile => EXIT_PROFILE_FAILED,
+            Step::Service => EXIT_SERVICE_FAILED,
+            Step::ServiceStart => EXIT_SERVICE_START_FAILED,
+        }
+    }
+}
+
+/// An error marked with the step it came from. Displays as the error it
+/// wraps, so the normal install output is unchanged.
+#[derive(Debug)]
+pub struct StepFailed {
+    pub step: Step,
+    pub source: Box<dyn Error>,
+}
+
+impl fmt::Display for StepFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl Error for StepFailed {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// `map_err` adapter marking an error with `step`.
+pub fn failed_at(step: Step) -> impl FnOnce(Box<dyn Error>) -> Box<dyn Error> {
+    move |source| Box::new(StepFailed { step, source })
+}
+
+/// Exit code for the result of a packaging run.
+pub fn exit_code(result: &Result<(), Box<dyn Error>>) -> i32 {
+    match result {
+        Ok(()) => EXIT_OK,
+        Err(e) => e
+            .downcast_ref::<StepFailed>()
+            .map_or(EXIT_FAILED, |failed| failed.step.exit_code()),
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/packaging_tests.rs"]
+mod tests;
@@ -0,0 +1,226 @@
+//! Fullscreen test pattern for `test pattern`.
+//!
+//! Covers one monitor with three rows of gray patches: a smooth 0–255
+//! ramp, a 16-step ramp and near-black steps (0–30). With the fix active
+//! the ramps stay even and the darkest steps stay apart; the Windows
+//! default profile on a dimming UltraGear shows the difference straight
+//! away. Space (or a click) calls back into the caller to swap profiles;
+//! Esc, Enter or Q closes the window.
+
+use std::error::Error;
+
+use lg_monitor::DesktopRect;
+use windows::core::{w, PCWSTR};
+use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    BeginPaint, CreateFontW, CreateSolidBrush, DeleteObject, DrawTextW, EndPaint, FillRect,
+    InvalidateRect, SelectObject, SetBkMode, SetTextColor, CLEARTYPE_QUALITY, DEFAULT_CHARSET,
+    DT_CENTER, DT_END_ELLIPSIS, DT_SINGLELINE, DT_VCENTER, FW_NORMAL, PAINTSTRUCT, TRANSPARENT,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetClientRect, GetMessageW,
+    GetWindowTextW, PostQuitMessage, RegisterClassW, SetForegroundWindow, SetWindowTextW,
+    ShowWindow, TranslateMessage, MSG, SW_SHOW, WM_DESTROY, WM_ERASEBKGND, WM_KEYDOWN,
+    WM_LBUTTONUP, WM_PAINT, WNDCLASSW, WS_EX_TOPMOST, WS_POPUP,
+};
+
+/// Height of the caption strip along the bottom edge.
+pub(crate) const CAPTION_HEIGHT: i32 = 56;
+/// Columns in the smooth ramp (one per 8-bit level).
+const SMOOTH_STEPS: i32 = 256;
+/// Patches in the stepped ramp and in the near-black row.
+const COARSE_STEPS: i32 = 16;
+/// Level difference between neighbouring near-black patches.
+const NEAR_BLACK_STEP: i32 = 2;
+
+const VK_RETURN: usize = 0x0D;
+const VK_ESCAPE: usize = 0x1B;
+const VK_SPACE: usize = 0x20;
+const VK_Q: usize = 0x51;
+
+/// One flat gray rectangle of the pattern, in client coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Patch {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+    pub level: u8,
+}
+
+/// The pattern for a `width` × `height` client area: smooth ramp on top,
+/// 16-step ramp in the middle, near-black steps below, leaving
+/// [`CAPTION_HEIGHT`] at the bottom for the caption.
+pub(crate) fn pattern_patches(width: i32, height: i32) -> Vec<Patch> {
+    let rows_height = (height - CAPTION_HEIGHT).max(3);
+    let row_top = |row: i32| rows_height * row / 3;
+    let mut patches = Vec::with_capacity((SMOOTH_STEPS + 2 * COARSE_STEPS) as usize);
+    let mut row = |index: i32, steps: i32, level: &dyn Fn(i32) -> i32| {
+        for i in 0..steps {
+            patches.push(Patch {
+                left: width * i / steps,
+                top: row_top(index),
+                right: width * (i + 1) / steps,
+                bottom: row_top(index + 1),
+                level: level(i).clamp(0, 255) as u8,
+            });
+        }
+    };
+    row(0, SMOOTH_STEPS, &|i| i);
+    row(1, COARSE_STEPS, &|i| i * 255 / (COARSE_STEPS - 1));
+    row(2, COARSE_STEPS, &|i| i * NEAR_BLACK_STEP);
+    patches
+}
+
+/// Show the pattern over `area` with `caption` underneath and pump
+/// messages until it is closed. `on_toggle` runs for every Space press or
+/// click and returns the next caption.
+pub(crate) fn show(
+    area: DesktopRect,
+    caption: &str,
+    mut on_toggle: impl FnMut() -> String,
+) -> Result<(), Box<dyn Error>> {
+    let class_name = w!("LGUltraGearTestPattern");
+    unsafe {
+        let instance = GetModuleHandleW(PCWSTR::null())?;
+        let wc = WNDCLASSW {
+            lpfnWndProc: Some(pattern_proc),
+            hInstance: instance.into(),
+            lpszClassName: class_name,
+            ..Default::default()
+        };
+        RegisterClassW(&wc);
+
+        let title = wide(caption);
+        let hwnd = CreateWindowExW(
+            WS_EX_TOPMOST,
+            class_name,
+            PCWSTR(title.as_ptr()),
+            WS_POPUP,
+            area.x,
+            area.y,
+            area.width as i32,
+            area.height as i32,
+            None,
+            None,
+            wc.hInstance,
+            None,
+        )?;
+        let _ = ShowWindow(hwnd, SW_SHOW);
+        let _ = SetForegroundWindow(hwnd);
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+            let toggle = match msg.message {
+                WM_KEYDOWN => match msg.wParam.0 {
+                    VK_ESCAPE | VK_RETURN | VK_Q => {
+                        let _ = DestroyWindow(hwnd);
+                        continue;
+                    }
+                    VK_SPACE => true,
+                    _ => false,
+                },
+                WM_LBUTTONUP => true,
+                _ => false,
+            };
+            if toggle {
+                let caption = wide(&on_toggle());
+                let _ = SetWindowTextW(hwnd, PCWSTR(caption.as_ptr()));
+                let _ = InvalidateRect(hwnd, None, false);
+                continue;
+            }
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+    Ok(())
+}
+
+fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+fn gray(level: u8) -> COLORREF {
+    let level = level as u32;
+    COLORREF(level | (level << 8) | (level << 16))
+}
+
+unsafe extern "system" fn pattern_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        // Everything is painted in WM_PAINT; skipping the erase avoids a
+        // flash of the class background on every toggle.
+        WM_ERASEBKGND => LRESULT(1),
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+            let mut client = RECT::default();
+            let _ = GetClientRect(hwnd, &mut client);
+
+            let black = CreateSolidBrush(gray(0));
+            FillRect(hdc, &client, black);
+            let _ = DeleteObject(black);
+            for patch in pattern_patches(client.right, client.bottom) {
+                let rect = RECT {
+                    left: patch.left,
+                    top: patch.top,
+                    right: patch.right,
+                    bottom: patch.bottom,
+                };
+                let brush = CreateSolidBrush(gray(patch.level));
+                FillRect(hdc, &rect, brush);
+                let _ = DeleteObject(brush);
+            }
+
+            let font = CreateFontW(
+                -22,
+                0,
+                0,
+                0,
+                FW_NORMAL.0 as i32,
+                0,
+                0,
+                0,
+                DEFAULT_CHARSET.0 as u32,
+                0,
+                0,
+                CLEARTYPE_QUALITY.0 as u32,
+                0,
+                w!("Segoe UI"),
+            );
+            let previous = SelectObject(hdc, font);
+            SetBkMode(hdc, TRANSPARENT);
+            SetTextColor(hdc, gray(200));
+            let mut caption_rect = RECT {
+                top: client.bottom - CAPTION_HEIGHT,
+                ..client
+            };
+            let mut text = [0u16; 512];
+            let len = GetWindowTextW(hwnd, &mut text).max(0) as usize;
+            DrawTextW(
+                hdc,
+                &mut text[..len],
+                &mut caption_rect,
+                DT_CENTER | DT_VCENTER | DT_SINGLELINE | DT_END_ELLIPSIS,
+            );
+            SelectObject(hdc, previous);
+            let _ = DeleteObject(font);
+            let _ = EndPaint(hwnd, &ps);
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            PostQuitMessage(0);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/pattern_tests.rs"]
+mod tests;
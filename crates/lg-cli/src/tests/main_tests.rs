@@ -25,6 +25,7 @@ impl Drop for FileBackup {
     }
 }
 
+#[cfg(feature = "ddc")]
 #[test]
 fn parse_hex_u8_supports_prefixed_and_plain_values() {
     assert_eq!(parse_hex_u8("0x10").unwrap(), 0x10);
@@ -32,6 +33,212 @@ fn parse_hex_u8_supports_prefixed_and_plain_values() {
     assert!(parse_hex_u8("xyz").is_err());
 }
 
+#[cfg(feature = "ddc")]
+#[test]
+fn ddc_power_parses_state_and_pattern() {
+    let cli = Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "ddc",
+        "power",
+        "standby",
+        "--pattern",
+        "27GR95QE",
+    ])
+    .expect("parse");
+    match cli.command {
+        Some(Commands::Ddc {
+            action: DdcAction::Power { state, pattern },
+        }) => {
+            assert_eq!(state, lg_monitor::ddc::PowerMode::Standby);
+            assert_eq!(pattern.as_deref(), Some("27GR95QE"));
+        }
+        _ => panic!("expected ddc power"),
+    }
+    assert!(Cli::try_parse_from(["lg-ultragear-dimming-fix", "ddc", "power", "sleep"]).is_err());
+}
+
+#[cfg(feature = "ddc")]
+#[test]
+fn ddc_script_parses_file_and_pattern() {
+    let cli = Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "ddc",
+        "script",
+        "dimming-off.toml",
+        "-p",
+        "27GN950",
+    ])
+    .expect("parse");
+    match cli.command {
+        Some(Commands::Ddc {
+            action: DdcAction::Script { file, pattern },
+        }) => {
+            assert_eq!(file, PathBuf::from("dimming-off.toml"));
+            assert_eq!(pattern.as_deref(), Some("27GN950"));
+        }
+        _ => panic!("expected ddc script"),
+    }
+}
+
+#[test]
+fn display_sdr_white_set_parses_nits_and_pattern() {
+    let cli = Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "display",
+        "sdr-white",
+        "set",
+        "240",
+        "--pattern",
+        "27GR95QE",
+    ])
+    .expect("parse");
+    match cli.command {
+        Some(Commands::Display {
+            action:
+                DisplayAction::SdrWhite {
+                    action:
+                        SdrWhiteAction::Set {
+                            nits,
+                            pattern,
+                            regex,
+                        },
+                },
+        }) => {
+            assert_eq!(nits, 240);
+            assert_eq!(pattern.as_deref(), Some("27GR95QE"));
+            assert!(!regex);
+        }
+        _ => panic!("expected display sdr-white set"),
+    }
+    assert!(
+        Cli::try_parse_from(["lg-ultragear-dimming-fix", "display", "sdr-white", "get"]).is_ok()
+    );
+    assert!(
+        Cli::try_parse_from(["lg-ultragear-dimming-fix", "display", "sdr-white", "set"]).is_err()
+    );
+}
+
+#[test]
+fn config_preset_apply_parses_name() {
+    let cli = Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "config",
+        "preset",
+        "apply",
+        "dock",
+    ])
+    .expect("parse");
+    match cli.command.expect("command") {
+        Commands::Config {
+            action:
+                Some(ConfigAction::Preset {
+                    action: PresetAction::Apply { name },
+                }),
+        } => assert_eq!(name, "dock"),
+        _ => panic!("expected config preset apply"),
+    }
+    assert!(Cli::try_parse_from(["lg-ultragear-dimming-fix", "config", "preset", "diff"]).is_err());
+}
+
+#[test]
+fn colorstore_restore_parses_file_and_needs_admin() {
+    let cli = Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "colorstore",
+        "restore",
+        "before.json",
+    ])
+    .expect("parse");
+    let command = cli.command.expect("command");
+    assert!(command.needs_admin());
+    match command {
+        Commands::Colorstore {
+            action: ColorstoreAction::Restore { file },
+        } => assert_eq!(file, PathBuf::from("before.json")),
+        _ => panic!("expected colorstore restore"),
+    }
+    let backup =
+        Cli::try_parse_from(["lg-ultragear-dimming-fix", "colorstore", "backup", "b.json"])
+            .expect("parse");
+    assert!(!backup.command.expect("command").needs_admin());
+}
+
+#[test]
+fn audit_show_defaults_to_fifty_entries() {
+    let cli = Cli::try_parse_from(["lg-ultragear-dimming-fix", "audit", "show"]).expect("parse");
+    let command = cli.command.expect("command");
+    assert!(!command.needs_admin());
+    match command {
+        Commands::Audit {
+            action: AuditAction::Show { limit },
+        } => assert_eq!(limit, 50),
+        _ => panic!("expected audit show"),
+    }
+    let cli = Cli::try_parse_from(["lg-ultragear-dimming-fix", "audit", "show", "-n", "5"])
+        .expect("parse");
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Audit {
+            action: AuditAction::Show { limit: 5 }
+        })
+    ));
+}
+
+#[test]
+fn display_hdr_parses_state_and_needs_admin() {
+    let cli = Cli::try_parse_from(["lg-ultragear-dimming-fix", "display", "hdr", "toggle"])
+        .expect("parse");
+    let command = cli.command.expect("command");
+    assert!(command.needs_admin());
+    match command {
+        Commands::Display {
+            action: DisplayAction::Hdr { state, pattern, .. },
+        } => {
+            assert_eq!(state, lg_monitor::HdrSwitch::Toggle);
+            assert_eq!(pattern, None);
+        }
+        _ => panic!("expected display hdr"),
+    }
+    assert!(Cli::try_parse_from(["lg-ultragear-dimming-fix", "display", "hdr", "auto"]).is_err());
+}
+
+#[test]
+fn display_mode_set_parses_optional_mode() {
+    let cli = Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "display",
+        "mode",
+        "set",
+        "2560x1440@240",
+    ])
+    .expect("parse");
+    match cli.command {
+        Some(Commands::Display {
+            action:
+                DisplayAction::Mode {
+                    action: DisplayModeAction::Set { mode, .. },
+                },
+        }) => assert_eq!(
+            mode,
+            Some(lg_monitor::DisplayMode {
+                width: 2560,
+                height: 1440,
+                refresh_hz: 240,
+            })
+        ),
+        _ => panic!("expected display mode set"),
+    }
+    assert!(Cli::try_parse_from(["lg-ultragear-dimming-fix", "display", "mode", "set"]).is_ok());
+    assert!(Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "display",
+        "mode",
+        "set",
+        "240hz"
+    ])
+    .is_err());
+}
+
 #[test]
 fn parse_hex_bytes_handles_common_separators() {
     assert_eq!(
@@ -41,6 +248,7 @@ fn parse_hex_bytes_handles_common_separators() {
     assert!(parse_hex_bytes("A").is_err());
 }
 
+#[cfg(feature = "ddc")]
 #[test]
 fn color_preset_name_maps_known_values() {
     assert_eq!(color_preset_name(1), "sRGB");
@@ -48,6 +256,15 @@ fn color_preset_name_maps_known_values() {
     assert_eq!(color_preset_name(999), "Unknown");
 }
 
+#[cfg(feature = "ddc")]
+#[test]
+fn ddc_watch_interval_enforces_minimum() {
+    assert_eq!(ddc_watch_interval(0).as_millis(), 100);
+    assert_eq!(ddc_watch_interval(50).as_millis(), 100);
+    assert_eq!(ddc_watch_interval(2500).as_millis(), 2500);
+}
+
+#[cfg(feature = "ddc")]
 #[test]
 fn is_risky_vcp_write_uses_configured_csv() {
     let cfg = app_state::AutomationConfig {
@@ -130,3 +347,580 @@ fn tuning_for_reader_keeps_manual_overlay_when_enabled() {
         "manual overlay should still apply on reader preset"
     );
 }
+
+#[cfg(feature = "service")]
+#[test]
+fn service_commands_need_admin() {
+    for command in ["install", "uninstall", "reinstall", "watch"] {
+        let cli = Cli::try_parse_from(["lg-ultragear-dimming-fix", command]).expect("parse");
+        assert!(
+            cli.command.as_ref().is_some_and(Commands::needs_admin),
+            "{}",
+            command
+        );
+    }
+}
+
+#[cfg(feature = "service")]
+#[test]
+fn portable_mode_rules_out_installing_the_service() {
+    let parse = |args: &[&str]| {
+        Cli::try_parse_from(["lg-ultragear-dimming-fix"].iter().chain(args)).expect("parse")
+    };
+    let cli = parse(&["watch", "--portable"]);
+    assert!(cli.portable);
+    assert!(!cli.command.as_ref().is_some_and(Commands::installs_service));
+
+    for args in [&["install"][..], &["reinstall"], &["service", "install"]] {
+        let cli = parse(args);
+        assert!(
+            cli.command.as_ref().is_some_and(Commands::installs_service),
+            "{:?}",
+            args
+        );
+    }
+    let profile_only = parse(&["--portable", "install", "--profile-only"]);
+    assert!(!profile_only
+        .command
+        .as_ref()
+        .is_some_and(Commands::installs_service));
+}
+
+#[cfg(feature = "service")]
+#[test]
+fn uninstall_feedback_needs_full_and_takes_an_optional_file() {
+    let parse = |args: &[&str]| {
+        Cli::try_parse_from(["lg-ultragear-dimming-fix", "uninstall"].iter().chain(args))
+    };
+    assert!(parse(&["--feedback"]).is_err());
+
+    match parse(&["--full", "--feedback"]).expect("parse").command {
+        Some(Commands::Uninstall { full, feedback, .. }) => {
+            assert!(full);
+            assert_eq!(feedback, Some(None));
+        }
+        _ => panic!("expected uninstall"),
+    }
+    match parse(&["--full", "--feedback", "C:\\temp\\lg.txt"])
+        .expect("parse")
+        .command
+    {
+        Some(Commands::Uninstall { feedback, .. }) => {
+            assert_eq!(feedback, Some(Some(PathBuf::from("C:\\temp\\lg.txt"))));
+        }
+        _ => panic!("expected uninstall"),
+    }
+    match parse(&["--full"]).expect("parse").command {
+        Some(Commands::Uninstall { feedback, .. }) => assert_eq!(feedback, None),
+        _ => panic!("expected uninstall"),
+    }
+}
+
+#[cfg(feature = "service")]
+#[test]
+fn install_plan_json_is_only_for_dry_run() {
+    let cli = Cli::try_parse_from(["lg-ultragear-dimming-fix", "install", "--dry-run", "--json"])
+        .expect("parse");
+    assert!(cli.dry_run);
+    match cli.command {
+        Some(Commands::Install { json, .. }) => {
+            assert!(json);
+            assert!(plan_json(json, cli.dry_run, Format::Human).expect("allowed"));
+        }
+        _ => panic!("expected install"),
+    }
+    assert!(plan_json(true, false, Format::Human).is_err());
+    assert!(!plan_json(false, false, Format::Human).expect("allowed"));
+}
+
+#[cfg(feature = "service")]
+#[test]
+fn plan_json_follows_the_output_format() {
+    // --format json is enough for a JSON plan; --json is then an alias.
+    assert!(plan_json(false, true, Format::Json).expect("allowed"));
+    assert!(plan_json(true, true, Format::Json).expect("allowed"));
+    assert!(!plan_json(false, false, Format::Json).expect("allowed"));
+    assert!(!plan_json(false, true, Format::Human).expect("allowed"));
+    // --quiet would drop the plan --json asked for.
+    assert!(plan_json(true, true, Format::Quiet).is_err());
+    assert!(!plan_json(false, true, Format::Quiet).expect("allowed"));
+}
+
+#[cfg(feature = "service")]
+#[test]
+fn for_packaging_skips_relaunch_and_rejects_config_writing_flags() {
+    let parse =
+        |args: &[&str]| Cli::try_parse_from(["lg-ultragear-dimming-fix"].iter().chain(args));
+    for args in [
+        &["install", "--for-packaging"][..],
+        &["install", "--for-packaging", "--service-only"],
+        &["uninstall", "--for-packaging", "--full"],
+    ] {
+        let cli = parse(args).expect("parse");
+        assert!(
+            cli.command.as_ref().is_some_and(Commands::for_packaging),
+            "{:?}",
+            args
+        );
+    }
+    assert!(!parse(&["install"])
+        .expect("parse")
+        .command
+        .as_ref()
+        .is_some_and(Commands::for_packaging));
+
+    for flag in [
+        "--pattern=LG",
+        "--regex",
+        "--per-user",
+        "--skip-hdr",
+        "--generic-default",
+    ] {
+        assert!(
+            parse(&["install", "--for-packaging", flag]).is_err(),
+            "{}",
+            flag
+        );
+    }
+    assert!(parse(&["uninstall", "--for-packaging", "--full", "--feedback"]).is_err());
+}
+
+#[cfg(feature = "service")]
+#[test]
+fn telemetry_status_is_read_only_but_toggles_need_admin() {
+    let parse = |args: &[&str]| {
+        Cli::try_parse_from(["lg-ultragear-dimming-fix", "telemetry"].iter().chain(args))
+            .expect("parse")
+            .command
+            .expect("command")
+    };
+    assert!(!parse(&["status"]).needs_admin());
+    assert!(parse(&["disable"]).needs_admin());
+    match parse(&["enable", "--endpoint", "https://example.invalid/ping"]) {
+        Commands::Telemetry {
+            action: TelemetryAction::Enable { endpoint },
+        } => assert_eq!(endpoint.as_deref(), Some("https://example.invalid/ping")),
+        _ => panic!("expected telemetry enable"),
+    }
+}
+
+#[test]
+fn apply_needs_admin_but_read_only_commands_do_not() {
+    let needs_admin = |command: &str| {
+        Cli::try_parse_from(["lg-ultragear-dimming-fix", command])
+            .expect("parse")
+            .command
+            .as_ref()
+            .is_some_and(Commands::needs_admin)
+    };
+    assert!(needs_admin("apply"));
+    assert!(!needs_admin("detect"));
+    assert!(!needs_admin("probe"));
+}
+
+#[test]
+fn apply_device_key_is_repeatable_and_replaces_the_pattern() {
+    let cli = Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "apply",
+        "--device-key",
+        r"DISPLAY\LGS\001",
+        "--device-key",
+        r"DISPLAY\GSM5BBF\5&1a2b3c&0&UID4352",
+    ])
+    .expect("parse");
+    match cli.command {
+        Some(Commands::Apply { device_key, .. }) => assert_eq!(
+            device_key,
+            vec![
+                r"DISPLAY\LGS\001".to_string(),
+                r"DISPLAY\GSM5BBF\5&1a2b3c&0&UID4352".to_string()
+            ]
+        ),
+        _ => panic!("expected apply"),
+    }
+    for conflicting in ["--regex", "--pattern=LG"] {
+        assert!(Cli::try_parse_from([
+            "lg-ultragear-dimming-fix",
+            "apply",
+            "--device-key",
+            r"DISPLAY\LGS\001",
+            conflicting,
+        ])
+        .is_err());
+    }
+}
+
+#[test]
+fn apply_profile_name_conflicts_with_profile_path() {
+    let cli = Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "apply",
+        "--profile-name",
+        "sRGB Color Space Profile",
+    ])
+    .expect("parse");
+    match cli.command {
+        Some(Commands::Apply { profile_name, .. }) => {
+            assert_eq!(profile_name.as_deref(), Some("sRGB Color Space Profile"))
+        }
+        _ => panic!("expected apply"),
+    }
+    assert!(Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "apply",
+        "--profile-name",
+        "a.icm",
+        "--profile-path",
+        r"C:\a.icm",
+    ])
+    .is_err());
+
+    let list = Cli::try_parse_from(["lg-ultragear-dimming-fix", "profile", "list"]).expect("parse");
+    assert!(!list.command.as_ref().is_some_and(Commands::needs_admin));
+}
+
+#[test]
+fn profile_generate_requires_a_kind_and_admin() {
+    let cli = Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "profile",
+        "generate",
+        "--identity",
+    ])
+    .expect("parse");
+    assert!(cli.command.as_ref().is_some_and(Commands::needs_admin));
+    assert!(Cli::try_parse_from(["lg-ultragear-dimming-fix", "profile", "generate"]).is_err());
+}
+
+#[test]
+fn profile_generate_variant_parses_white_points() {
+    let parse = |args: &[&str]| {
+        Cli::try_parse_from(
+            ["lg-ultragear-dimming-fix", "profile", "generate"]
+                .into_iter()
+                .chain(args.iter().copied()),
+        )
+    };
+    match parse(&["--variant", "5000K"]).expect("parse").command {
+        Some(Commands::Profile {
+            action: ProfileAction::Generate { identity, variant },
+        }) => {
+            assert!(!identity);
+            assert_eq!(variant, Some(lg_profile::WhitePointVariant::K5000));
+        }
+        _ => panic!("expected profile generate"),
+    }
+    assert!(parse(&["--variant", "7000k"]).is_err());
+    assert!(parse(&["--variant", "6500k", "--identity"]).is_err());
+}
+
+#[test]
+fn profile_install_scope_decides_elevation() {
+    let parse = |args: &[&str]| {
+        Cli::try_parse_from(
+            ["lg-ultragear-dimming-fix", "profile", "install", "cal.icm"]
+                .into_iter()
+                .chain(args.iter().copied()),
+        )
+    };
+    let system = parse(&[]).expect("parse");
+    match system.command.as_ref() {
+        Some(Commands::Profile {
+            action: ProfileAction::Install { file, scope },
+        }) => {
+            assert_eq!(file, &PathBuf::from("cal.icm"));
+            assert_eq!(*scope, colorstore::Scope::SystemWide);
+        }
+        _ => panic!("expected profile install"),
+    }
+    assert!(system.command.as_ref().is_some_and(Commands::needs_admin));
+    let user = parse(&["--scope", "user"]).expect("parse");
+    assert!(!user.command.as_ref().is_some_and(Commands::needs_admin));
+    assert!(parse(&["--scope", "machine"]).is_err());
+}
+
+#[test]
+fn profile_compare_defaults_to_stock_against_the_fix() {
+    let cli =
+        Cli::try_parse_from(["lg-ultragear-dimming-fix", "profile", "compare"]).expect("parse");
+    assert!(cli.command.as_ref().is_some_and(Commands::needs_admin));
+    match cli.command {
+        Some(Commands::Profile {
+            action: ProfileAction::Compare { a, b, interval, .. },
+        }) => {
+            assert_eq!(a, "stock");
+            assert_eq!(b, "fix");
+            assert_eq!(interval, 3);
+        }
+        _ => panic!("expected profile compare"),
+    }
+    assert!(Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "profile",
+        "compare",
+        "--interval",
+        "0",
+    ])
+    .is_err());
+}
+
+#[test]
+fn test_pattern_defaults_to_the_stock_profile_as_before() {
+    let cli = Cli::try_parse_from(["lg-ultragear-dimming-fix", "test", "pattern", "-p", "27GR"])
+        .expect("parse");
+    assert!(cli.command.as_ref().is_some_and(Commands::needs_admin));
+    match cli.command {
+        Some(Commands::Test {
+            action: TestAction::Pattern {
+                pattern, before, ..
+            },
+        }) => {
+            assert_eq!(pattern.as_deref(), Some("27GR"));
+            assert_eq!(before, "stock");
+        }
+        _ => panic!("expected test pattern"),
+    }
+}
+
+#[test]
+fn profile_adjust_accepts_signed_offsets() {
+    let cli = Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "profile",
+        "adjust",
+        "--brightness",
+        "+5",
+        "--contrast",
+        "-3",
+        "--output",
+        "custom.icm",
+    ])
+    .expect("parse");
+    match cli.command {
+        Some(Commands::Profile {
+            action:
+                ProfileAction::Adjust {
+                    input,
+                    output,
+                    brightness,
+                    contrast,
+                    gamma,
+                },
+        }) => {
+            assert!(input.is_none());
+            assert_eq!(output, "custom.icm");
+            assert_eq!(brightness, 5.0);
+            assert_eq!(contrast, -3.0);
+            assert_eq!(gamma, lg_profile::PRESET_GAMMA_22);
+        }
+        _ => panic!("expected profile adjust"),
+    }
+    assert!(!cli.command.as_ref().is_some_and(Commands::needs_admin));
+}
+
+#[cfg(feature = "service")]
+#[test]
+fn service_history_does_not_need_admin() {
+    let needs_admin = |args: &[&str]| {
+        Cli::try_parse_from(std::iter::once("lg-ultragear-dimming-fix").chain(args.iter().copied()))
+            .expect("parse")
+            .command
+            .as_ref()
+            .is_some_and(Commands::needs_admin)
+    };
+    assert!(needs_admin(&["service", "status"]));
+    assert!(!needs_admin(&["service", "history"]));
+    assert!(!needs_admin(&["service", "history", "-n", "5"]));
+    assert!(!needs_admin(&["service", "logs", "--follow"]));
+    assert!(!needs_admin(&["service", "counters", "-n", "1"]));
+}
+
+#[cfg(feature = "service")]
+#[test]
+fn service_counters_parses_interval_and_samples() {
+    let cli = Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "service",
+        "counters",
+        "--interval",
+        "5",
+        "-n",
+        "3",
+    ])
+    .expect("parse");
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Service {
+            action: ServiceAction::Counters {
+                interval: 5,
+                samples: Some(3),
+            }
+        })
+    ));
+    assert!(Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "service",
+        "counters",
+        "--interval",
+        "0"
+    ])
+    .is_err());
+}
+
+#[cfg(feature = "service")]
+#[test]
+fn service_logs_parses_limit_and_level() {
+    let cli = Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "service",
+        "logs",
+        "-n",
+        "20",
+        "--level",
+        "warn",
+    ])
+    .expect("parse");
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Service {
+            action: ServiceAction::Logs {
+                limit: 20,
+                level: lg_service::EventLevel::Warning,
+                follow: false,
+            }
+        })
+    ));
+    assert!(Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "service",
+        "logs",
+        "--level",
+        "debug"
+    ])
+    .is_err());
+}
+
+#[cfg(feature = "service")]
+#[test]
+fn service_status_takes_a_stats_flag() {
+    let cli = Cli::try_parse_from(["lg-ultragear-dimming-fix", "service", "status", "--stats"])
+        .expect("parse");
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Service {
+            action: ServiceAction::Status { stats: true }
+        })
+    ));
+}
+
+#[test]
+fn measure_add_takes_a_reading_and_does_not_need_admin() {
+    let cli = Cli::try_parse_from(["lg-ultragear-dimming-fix", "measure", "add", "118.4"])
+        .expect("parse");
+    assert!(!cli.command.as_ref().is_some_and(Commands::needs_admin));
+    match cli.command {
+        Some(Commands::Measure {
+            action: MeasureAction::Add { cd_m2 },
+        }) => assert_eq!(cd_m2, 118.4),
+        _ => panic!("expected measure add"),
+    }
+    assert!(Cli::try_parse_from(["lg-ultragear-dimming-fix", "measure", "add", "bright"]).is_err());
+}
+
+#[cfg(feature = "notify")]
+#[test]
+fn session_toast_helper_parses_and_does_not_need_admin() {
+    let cli = Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        lg_notify::SESSION_TOAST_COMMAND,
+        "--title",
+        "LG UltraGear",
+        "--body",
+        "Color profile reapplied",
+        "--quiet-hours",
+        "queue",
+    ])
+    .expect("parse");
+    assert!(!cli.command.as_ref().is_some_and(Commands::needs_admin));
+    match cli.command {
+        Some(Commands::SessionToast {
+            title,
+            body,
+            quiet_hours,
+        }) => {
+            assert_eq!(title, "LG UltraGear");
+            assert_eq!(body, "Color profile reapplied");
+            assert_eq!(quiet_hours, "queue");
+        }
+        _ => panic!("expected the session-toast helper"),
+    }
+}
+
+#[test]
+fn quirks_validate_takes_a_file() {
+    let cli = Cli::try_parse_from([
+        "lg-ultragear-dimming-fix",
+        "quirks",
+        "validate",
+        "pack.toml",
+    ])
+    .expect("parse");
+    match cli.command {
+        Some(Commands::Quirks {
+            action: QuirksAction::Validate { file },
+        }) => assert_eq!(file, PathBuf::from("pack.toml")),
+        _ => panic!("expected quirks validate"),
+    }
+    assert!(Cli::try_parse_from(["lg-ultragear-dimming-fix", "quirks", "validate"]).is_err());
+}
+
+#[test]
+fn watch_log_flags_default_to_file_only() {
+    let cli = Cli::try_parse_from(["lg-ultragear-dimming-fix", "watch"]).expect("parse");
+    match cli.command {
+        Some(Commands::Watch {
+            event_log,
+            no_log_file,
+            ..
+        }) => assert!(!event_log && !no_log_file),
+        _ => panic!("expected watch"),
+    }
+    let cli = Cli::try_parse_from(["lg-ultragear-dimming-fix", "watch", "--event-log", "-v"])
+        .expect("parse");
+    assert_eq!(cli.verbose, 1);
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Watch {
+            event_log: true,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn bench_is_hidden_and_bounded() {
+    use clap::CommandFactory;
+
+    let cli = Cli::try_parse_from(["lg-ultragear-dimming-fix", "bench"]).expect("parse");
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Bench { iterations: 10 })
+    ));
+    let cli = Cli::try_parse_from(["lg-ultragear-dimming-fix", "bench", "-n", "3"]).expect("parse");
+    assert!(cli.command.as_ref().is_some_and(Commands::needs_admin));
+    assert!(Cli::try_parse_from(["lg-ultragear-dimming-fix", "bench", "-n", "0"]).is_err());
+    let help = Cli::command().render_help().to_string();
+    assert!(!help.contains("bench"));
+}
+
+#[test]
+fn match_mode_label_recognizes_expressions() {
+    assert_eq!(match_mode_label("LG ULTRAGEAR", false), "substring");
+    assert_eq!(match_mode_label("LG.*GEAR", true), "regex");
+    assert_eq!(
+        match_mode_label("manufacturer:GSM AND product:5BBF", true),
+        "expression"
+    );
+    assert_eq!(match_mode_label("NOT serial=\"123\"", false), "expression");
+}
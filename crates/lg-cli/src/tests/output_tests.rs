@@ -0,0 +1,125 @@
+use super::*;
+
+fn verbosity(quiet: bool, verbose: u8) -> Verbosity {
+    Verbosity { quiet, verbose }
+}
+
+// ── Levels ───────────────────────────────────────────────────────
+
+#[test]
+fn each_v_raises_the_console_level() {
+    assert_eq!(verbosity(false, 0).level(), LevelFilter::Warn);
+    assert_eq!(verbosity(false, 1).level(), LevelFilter::Info);
+    assert_eq!(verbosity(false, 2).level(), LevelFilter::Debug);
+    assert_eq!(verbosity(false, 3).level(), LevelFilter::Trace);
+    assert_eq!(verbosity(false, 9).level(), LevelFilter::Trace);
+}
+
+#[test]
+fn quiet_shows_only_errors() {
+    let quiet = verbosity(true, 0);
+    assert_eq!(quiet.level(), LevelFilter::Error);
+    assert!(!quiet.is_verbose());
+}
+
+#[cfg(feature = "service")]
+#[test]
+fn log_file_and_event_log_keep_at_least_info() {
+    assert_eq!(verbosity(true, 0).sink_level(), LevelFilter::Info);
+    assert_eq!(verbosity(false, 0).sink_level(), LevelFilter::Info);
+    assert_eq!(verbosity(false, 1).sink_level(), LevelFilter::Info);
+    assert_eq!(verbosity(false, 2).sink_level(), LevelFilter::Debug);
+    assert_eq!(verbosity(false, 3).sink_level(), LevelFilter::Trace);
+}
+
+// ── Formats ──────────────────────────────────────────────────────
+
+#[test]
+fn format_names_parse_in_any_case() {
+    assert_eq!(Format::parse("human"), Some(Format::Human));
+    assert_eq!(Format::parse(" JSON "), Some(Format::Json));
+    assert_eq!(Format::parse("quiet"), Some(Format::Quiet));
+    assert_eq!(Format::parse("yaml"), None);
+}
+
+// ── Lines ────────────────────────────────────────────────────────
+
+#[test]
+fn tags_become_the_kind() {
+    assert_eq!(
+        Line::parse("[OK] Profile installed"),
+        Line {
+            kind: "ok".to_string(),
+            text: "Profile installed".to_string(),
+        }
+    );
+    assert_eq!(Line::parse("  [DRY RUN] Would install").kind, "dry_run");
+    assert_eq!(Line::parse("[WARN]").text, "");
+}
+
+#[test]
+fn untagged_lines_are_text_as_written() {
+    for text in [
+        "",
+        "  Verbose: true",
+        "[y/N] answer",
+        "[] empty",
+        "a [OK] b",
+    ] {
+        let line = Line::parse(text);
+        assert_eq!(line.kind, "text", "{:?}", text);
+        assert_eq!(line.text, text);
+    }
+}
+
+#[test]
+fn lines_are_one_json_object_each() {
+    assert_eq!(
+        Line::parse("[INFO] Path: C:\\color \"x\"\nnext").to_json(),
+        r#"{"kind":"info","text":"Path: C:\\color \"x\"\nnext"}"#
+    );
+}
+
+// ── Capture ──────────────────────────────────────────────────────
+
+#[cfg(feature = "tui")]
+#[test]
+fn capture_keeps_reported_and_recorded_lines() {
+    let (value, lines) = capture(|| {
+        say!("[OK] first");
+        record("  [WARN] second\n  third");
+        say!();
+        fail!("[FAIL] fourth");
+        42
+    });
+    assert_eq!(value, 42);
+    assert_eq!(
+        lines,
+        vec![
+            "[OK] first",
+            "  [WARN] second",
+            "  third",
+            "",
+            "[FAIL] fourth"
+        ]
+    );
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn nested_capture_reports_to_both() {
+    let ((_, inner), outer) = capture(|| {
+        say!("outer");
+        capture(|| say!("inner"))
+    });
+    assert_eq!(inner, vec!["inner"]);
+    assert_eq!(outer, vec!["outer", "inner"]);
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn nothing_is_kept_outside_a_capture() {
+    record("dropped");
+    let ((), lines) = capture(|| {});
+    assert!(lines.is_empty());
+}
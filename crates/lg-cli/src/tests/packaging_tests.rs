@@ -0,0 +1,48 @@
+use super::*;
+
+// ── Exit codes ───────────────────────────────────────────────────
+
+#[test]
+fn exit_code_is_zero_on_success() {
+    assert_eq!(exit_code(&Ok(())), EXIT_OK);
+}
+
+#[test]
+fn exit_code_follows_the_failed_step() {
+    for (step, code) in [
+        (Step::Profile, EXIT_PROFILE_FAILED),
+        (Step::Service, EXIT_SERVICE_FAILED),
+        (Step::ServiceStart, EXIT_SERVICE_START_FAILED),
+    ] {
+        let result = Err(failed_at(step)("boom".into()));
+        assert_eq!(exit_code(&result), code);
+    }
+}
+
+#[test]
+fn untagged_errors_exit_with_generic_failure() {
+    assert_eq!(exit_code(&Err("boom".into())), EXIT_FAILED);
+}
+
+#[test]
+fn tagged_error_displays_as_its_source() {
+    let err = failed_at(Step::Service)("access denied".into());
+    assert_eq!(err.to_string(), "access denied");
+    assert_eq!(err.source().unwrap().to_string(), "access denied");
+}
+
+#[test]
+fn exit_codes_are_distinct_and_skip_usage_error() {
+    let codes = [
+        EXIT_OK,
+        EXIT_FAILED,
+        EXIT_NOT_ELEVATED,
+        EXIT_PROFILE_FAILED,
+        EXIT_SERVICE_FAILED,
+        EXIT_SERVICE_START_FAILED,
+    ];
+    for (i, a) in codes.iter().enumerate() {
+        assert_ne!(*a, 2, "2 is clap's usage error");
+        assert!(codes[i + 1..].iter().all(|b| b != a));
+    }
+}
@@ -0,0 +1,66 @@
+use super::*;
+
+fn row(patches: &[Patch], top: i32) -> Vec<Patch> {
+    patches.iter().copied().filter(|p| p.top == top).collect()
+}
+
+// ── Layout ───────────────────────────────────────────────────────
+
+#[test]
+fn pattern_has_three_rows_above_the_caption() {
+    let patches = pattern_patches(2560, 1440);
+    assert_eq!(patches.len(), 256 + 16 + 16);
+    let bottom = patches.iter().map(|p| p.bottom).max().unwrap();
+    assert_eq!(bottom, 1440 - CAPTION_HEIGHT);
+    assert_eq!(row(&patches, 0).len(), 256);
+}
+
+#[test]
+fn every_row_spans_the_full_width_without_gaps() {
+    let patches = pattern_patches(1920, 1080);
+    let tops: Vec<i32> = vec![
+        0,
+        (1080 - CAPTION_HEIGHT) / 3,
+        (1080 - CAPTION_HEIGHT) * 2 / 3,
+    ];
+    for top in tops {
+        let row = row(&patches, top);
+        assert_eq!(row.first().unwrap().left, 0);
+        assert_eq!(row.last().unwrap().right, 1920);
+        for pair in row.windows(2) {
+            assert_eq!(pair[0].right, pair[1].left);
+        }
+    }
+}
+
+// ── Levels ───────────────────────────────────────────────────────
+
+#[test]
+fn ramps_run_from_black_to_white() {
+    let patches = pattern_patches(1920, 1080);
+    let smooth = &patches[..256];
+    assert!(smooth
+        .iter()
+        .enumerate()
+        .all(|(i, p)| p.level as usize == i));
+    let stepped = &patches[256..272];
+    assert_eq!(stepped.first().unwrap().level, 0);
+    assert_eq!(stepped.last().unwrap().level, 255);
+    assert!(stepped.windows(2).all(|w| w[0].level < w[1].level));
+}
+
+#[test]
+fn near_black_row_steps_by_two() {
+    let patches = pattern_patches(1920, 1080);
+    let levels: Vec<u8> = patches[272..].iter().map(|p| p.level).collect();
+    assert_eq!(levels, (0..16).map(|i| i * 2).collect::<Vec<u8>>());
+}
+
+#[test]
+fn tiny_windows_still_get_a_pattern() {
+    let patches = pattern_patches(10, 10);
+    assert_eq!(patches.len(), 288);
+    assert!(patches
+        .iter()
+        .all(|p| p.bottom >= p.top && p.right >= p.left));
+}
@@ -0,0 +1,33 @@
+use super::*;
+
+// ── Report formatting ────────────────────────────────────────────
+
+#[test]
+fn format_report_lists_phases_then_total() {
+    let report = format_report(
+        &[
+            ("config", Duration::from_micros(1_500)),
+            ("monitor scan", Duration::from_millis(42)),
+        ],
+        Duration::from_millis(50),
+    );
+    assert_eq!(
+        report,
+        "[TIMING] config             1.5 ms\n\
+         [TIMING] monitor scan      42.0 ms\n\
+         [TIMING] total             50.0 ms\n"
+    );
+}
+
+#[test]
+fn format_report_with_no_phases_shows_total() {
+    assert_eq!(
+        format_report(&[], Duration::ZERO),
+        "[TIMING] total       0.0 ms\n"
+    );
+}
+
+#[test]
+fn phase_returns_closure_result() {
+    assert_eq!(phase("test phase", || 7), 7);
+}
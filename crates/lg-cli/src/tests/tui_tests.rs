@@ -551,6 +551,10 @@ fn draw_maintenance2_contains_all_items() {
     assert!(output.contains("[C]"), "item C — read custom VCP");
     assert!(output.contains("[D]"), "item D — write custom VCP");
     assert!(output.contains("[E]"), "item E — guardrails");
+    assert!(output.contains("[F]"), "item F — read sharpness");
+    assert!(output.contains("[G]"), "item G — set sharpness");
+    assert!(output.contains("[H]"), "item H — read response time");
+    assert!(output.contains("[I]"), "item I — set response time");
     assert!(output.contains("[P]"), "prev page key");
     assert!(output.contains("[Z]"), "back key");
     assert!(output.contains("[Q]"), "quit key");
@@ -572,6 +576,7 @@ fn draw_maintenance2_sections() {
         render_to_string(|buf| draw_maintenance2(buf, &default_status(), &default_opts(), None));
     assert!(output.contains("READ"), "should have READ section");
     assert!(output.contains("WRITE"), "should have WRITE section");
+    assert!(output.contains("PICTURE"), "should have PICTURE section");
     assert!(output.contains("RESET"), "should have RESET section");
     assert!(output.contains("INFO"), "should have INFO section");
     assert!(output.contains("TARGET"), "should have TARGET section");
@@ -3006,6 +3011,169 @@ fn run_action_error_renders_err_tag() {
     );
 }
 
+// ── Result pane ──────────────────────────────────────────────
+
+fn lines(text: &[&str]) -> Vec<String> {
+    text.iter().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn result_rows_split_long_lines_and_keep_blank_ones() {
+    let long = format!("  {}", "x".repeat(INNER + 10));
+    let rows = result_rows(&lines(&["  [ OK ] done", "", &long]));
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[1], "");
+    assert_eq!(rows[2].chars().count(), INNER);
+    assert!(rows[2].starts_with("  x"));
+    assert_eq!(rows[3], "x".repeat(12));
+}
+
+#[test]
+fn scroll_result_stays_within_the_rows() {
+    assert_eq!(scroll_result(0, ResultKey::End, 50, 20), 30);
+    assert_eq!(scroll_result(30, ResultKey::PageDown, 50, 20), 30);
+    assert_eq!(scroll_result(25, ResultKey::PageDown, 50, 20), 30);
+    assert_eq!(scroll_result(30, ResultKey::PageUp, 50, 20), 10);
+    assert_eq!(scroll_result(10, ResultKey::PageUp, 50, 20), 0);
+    assert_eq!(scroll_result(0, ResultKey::Up, 50, 20), 0);
+    assert_eq!(scroll_result(5, ResultKey::Down, 50, 20), 6);
+    assert_eq!(scroll_result(12, ResultKey::Home, 50, 20), 0);
+    // Output shorter than the pane never scrolls.
+    assert_eq!(scroll_result(0, ResultKey::End, 5, 20), 0);
+    assert_eq!(scroll_result(0, ResultKey::Down, 5, 20), 0);
+}
+
+#[test]
+fn result_file_has_the_banner_then_the_output() {
+    let text = result_file_text(
+        "  Detecting monitors...  ",
+        &lines(&["  [INFO] one  ", "", "  [ERR ] two"]),
+    );
+    assert_eq!(
+        text,
+        "Detecting monitors...\n\n  [INFO] one\n\n  [ERR ] two\n"
+    );
+}
+
+#[test]
+fn draw_result_shows_the_window_and_position() {
+    let rows: Vec<String> = (1..=30).map(|i| format!("  row {}", i)).collect();
+    let output = render_to_string(|buf| draw_result(buf, "Banner", &rows, 10, 5, None));
+    assert!(output.contains("RESULT"));
+    assert!(output.contains("Banner"));
+    assert!(output.contains("row 11") && output.contains("row 15"));
+    assert!(!output.contains("row 10 ") && !output.contains("row 16"));
+    assert!(output.contains("Lines 11-15 of 30"));
+}
+
+#[test]
+fn draw_result_pads_short_output_and_shows_the_save_status() {
+    let rows = lines(&["  [ OK ] only line"]);
+    let output =
+        render_to_string(|buf| draw_result(buf, "Banner", &rows, 0, 5, Some("Saved to C:/x.txt")));
+    assert!(output.contains("only line"));
+    assert!(output.contains("1 line(s)"));
+    assert!(output.contains("Saved to C:/x.txt"));
+    let box_rows = output.matches('\u{2551}').count() / 2;
+    // banner + 5 rows + position + keys
+    assert_eq!(box_rows, 8);
+}
+
+// ── Brightness slider ────────────────────────────────────────
+
+#[test]
+fn slider_bar_fills_in_proportion() {
+    assert_eq!(slider_bar(0, 100, 10), "\u{2591}".repeat(10));
+    assert_eq!(slider_bar(100, 100, 10), "\u{2588}".repeat(10));
+    assert_eq!(
+        slider_bar(50, 100, 10),
+        format!("{}{}", "\u{2588}".repeat(5), "\u{2591}".repeat(5))
+    );
+    // Out-of-range values and a zero max still give a full-width bar.
+    assert_eq!(slider_bar(250, 100, 10).chars().count(), 10);
+    assert_eq!(slider_bar(5, 0, 10), "\u{2591}".repeat(10));
+}
+
+#[test]
+fn slider_starts_at_the_reading_or_midway() {
+    let read = SliderMonitor::new(0, "LG".to_string(), Some((30, 100)));
+    assert_eq!((read.value, read.max, read.read), (30, 100, Some(30)));
+    assert!(!read.pending);
+    let silent = SliderMonitor::new(1, "LG".to_string(), None);
+    assert_eq!((silent.value, silent.max, silent.read), (50, 100, None));
+    // A monitor reporting max 0 still gets a usable range.
+    assert_eq!(SliderMonitor::new(2, "LG".to_string(), Some((0, 0))).max, 1);
+}
+
+#[test]
+fn slider_nudge_clamps_and_marks_pending() {
+    let mut m = SliderMonitor::new(0, "LG".to_string(), Some((95, 100)));
+    m.nudge(10);
+    assert_eq!(m.value, 100);
+    assert_eq!(m.take_pending(), Some(100));
+    assert_eq!(m.take_pending(), None);
+    // Pushing against the end doesn't ask for a write.
+    m.nudge(1);
+    assert!(!m.pending);
+    m.nudge(-250);
+    assert_eq!(m.value, 0);
+    assert!(m.pending);
+}
+
+#[test]
+fn slider_confirm_reports_a_differing_read_back() {
+    let mut m = SliderMonitor::new(0, "LG".to_string(), Some((40, 100)));
+    m.confirm(60, Some(60));
+    assert_eq!(m.feedback, SliderFeedback::Written);
+    m.confirm(70, Some(68));
+    assert_eq!(m.feedback, SliderFeedback::Differs(68));
+    assert_eq!(m.read, Some(68));
+    m.confirm(75, None);
+    assert_eq!(m.feedback, SliderFeedback::Written);
+    assert_eq!(m.read, Some(75));
+}
+
+#[test]
+fn slider_reject_snaps_back_to_the_reading() {
+    let mut m = SliderMonitor::new(0, "LG".to_string(), Some((40, 100)));
+    m.nudge(-35);
+    m.reject("Guardrails blocked brightness 5".to_string());
+    assert_eq!(m.value, 40);
+    assert!(!m.pending);
+    assert!(matches!(m.feedback, SliderFeedback::Rejected(_)));
+}
+
+#[test]
+fn slider_reread_follows_the_monitor_unless_moved() {
+    let mut m = SliderMonitor::new(0, "LG".to_string(), Some((40, 100)));
+    m.reread(Some(55));
+    assert_eq!((m.value, m.read), (55, Some(55)));
+    m.nudge(5);
+    m.reread(Some(20));
+    assert_eq!((m.value, m.read), (60, Some(20)));
+    m.reread(None);
+    assert_eq!(m.read, Some(20));
+}
+
+#[test]
+fn draw_brightness_slider_shows_each_monitor() {
+    let mut first = SliderMonitor::new(0, "LG ULTRAGEAR".to_string(), Some((40, 100)));
+    first.confirm(40, Some(38));
+    let mut second = SliderMonitor::new(1, "LG HDR 4K".to_string(), None);
+    second.nudge(10);
+    let output =
+        render_to_string(|buf| draw_brightness_slider(buf, "All matching", &[first, second], 1));
+    assert!(output.contains("BRIGHTNESS"));
+    assert!(output.contains("All matching"));
+    assert!(output.contains("  LG ULTRAGEAR (#0)"));
+    assert!(output.contains("> LG HDR 4K (#1)"));
+    assert!(output.contains("read 38/100"));
+    assert!(output.contains("monitor reports 38"));
+    assert!(output.contains("read \u{2014}"));
+    assert!(output.contains("writes when the key is released"));
+    assert!(output.contains("Home/End"));
+}
+
 struct FileBackup {
     path: std::path::PathBuf,
     original: Option<Vec<u8>>,
@@ -0,0 +1,26 @@
+use super::*;
+
+// ── Log file ─────────────────────────────────────────────────────
+
+#[test]
+fn format_line_has_time_level_and_target() {
+    assert_eq!(
+        format_line(
+            "2026-01-02 03:04:05.678",
+            Level::Info,
+            "lg_service",
+            &"Reapply complete"
+        ),
+        "2026-01-02 03:04:05.678 INFO  lg_service: Reapply complete"
+    );
+}
+
+#[test]
+fn open_log_file_appends_and_creates_the_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state").join("watch.log");
+    writeln!(open_log_file(&path).unwrap(), "first").unwrap();
+    writeln!(open_log_file(&path).unwrap(), "second").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "first\nsecond\n");
+    assert!(!dir.path().join("state").join("watch.log.old").exists());
+}
@@ -0,0 +1,58 @@
+//! Phase timing for the `--timing` flag.
+//!
+//! Phases are always recorded (an `Instant` pair and a push), so timing the
+//! argument parse doesn't depend on having parsed `--timing` yet. The report
+//! is only printed when the flag is set.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static PHASES: Mutex<Vec<(&'static str, Duration)>> = Mutex::new(Vec::new());
+
+/// Run `f` and record its wall-clock cost under `name`.
+pub(crate) fn phase<T>(name: &'static str, f: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = f();
+    record(name, started.elapsed());
+    result
+}
+
+fn record(name: &'static str, elapsed: Duration) {
+    if let Ok(mut phases) = PHASES.lock() {
+        phases.push((name, elapsed));
+    }
+}
+
+/// Prints the report when dropped, so it also covers early returns and
+/// failed commands. Holds the process start time.
+pub(crate) struct ReportOnExit(pub(crate) Instant);
+
+impl Drop for ReportOnExit {
+    fn drop(&mut self) {
+        let phases = PHASES.lock().map(|p| p.clone()).unwrap_or_default();
+        eprint!("{}", format_report(&phases, self.0.elapsed()));
+    }
+}
+
+fn format_report(phases: &[(&str, Duration)], total: Duration) -> String {
+    let width = phases
+        .iter()
+        .map(|(name, _)| name.len())
+        .chain(std::iter::once("total".len()))
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for (name, elapsed) in phases.iter().copied().chain([("total", total)]) {
+        out.push_str(&format!(
+            "[TIMING] {:<width$}  {:>8.1} ms\n",
+            name,
+            elapsed.as_secs_f64() * 1000.0,
+            width = width
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+#[path = "tests/timing_tests.rs"]
+mod tests;
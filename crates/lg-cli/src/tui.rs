@@ -4,6 +4,7 @@
 //! installer's interactive experience: live status display, numbered
 //! actions, and toggle-based advanced settings.
 
+use crate::output::{self, say};
 use crossterm::{
     cursor,
     event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
@@ -13,64 +14,18 @@ use crossterm::{
 };
 use lg_core::{
     config::{self, Config},
+    i18n,
+    pipeline::{ModeProfiles, MonitorResult, ReapplyOptions, ReapplyPipeline, ReapplySteps},
+    progress::ProgressEvent,
+    shutdown::ShutdownToken,
     state as app_state,
+    toast_template::{self, ToastTally, ToastValues},
 };
 use std::io::{self, IsTerminal, Write};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
 
-// ── UTF-8 console support (Windows) ──────────────────────────────────────
-
-/// Ensure the Windows console uses UTF-8 for output so box-drawing and
-/// other Unicode characters render correctly, even in cmd.exe or legacy
-/// PowerShell hosts that default to an OEM code page.
-///
-/// This does three things:
-/// 1. Sets the input and output code pages to 65001 (UTF-8).
-/// 2. Switches the console font to Consolas (a TrueType font with full
-///    Unicode box-drawing support). The default "Raster Fonts" in cmd.exe
-///    cannot render ╔═╗║ etc.
-/// 3. Enables Virtual Terminal Processing so ANSI escape sequences (used by
-///    crossterm for colours and cursor movement) work correctly.
-pub fn enable_utf8_console() {
-    #[cfg(windows)]
-    {
-        use windows::Win32::System::Console::{
-            GetConsoleMode, GetStdHandle, SetConsoleCP, SetConsoleMode, SetConsoleOutputCP,
-            SetCurrentConsoleFontEx, CONSOLE_FONT_INFOEX, COORD, ENABLE_PROCESSED_OUTPUT,
-            ENABLE_VIRTUAL_TERMINAL_PROCESSING, STD_OUTPUT_HANDLE,
-        };
-
-        unsafe {
-            // 1. UTF-8 code pages
-            let _ = SetConsoleOutputCP(65001);
-            let _ = SetConsoleCP(65001);
-
-            let handle = match GetStdHandle(STD_OUTPUT_HANDLE) {
-                Ok(h) => h,
-                Err(_) => return,
-            };
-
-            // 2. TrueType font with Unicode support
-            let mut font = CONSOLE_FONT_INFOEX {
-                cbSize: std::mem::size_of::<CONSOLE_FONT_INFOEX>() as u32,
-                dwFontSize: COORD { X: 0, Y: 18 },
-                FontWeight: 400, // FW_NORMAL
-                ..Default::default()
-            };
-            let name: Vec<u16> = "Consolas\0".encode_utf16().collect();
-            font.FaceName[..name.len()].copy_from_slice(&name);
-            let _ = SetCurrentConsoleFontEx(handle, false, &font);
-
-            // 3. Enable VT processing (ANSI escape sequences)
-            let mut mode = Default::default();
-            if GetConsoleMode(handle, &mut mode).is_ok() {
-                let _ = SetConsoleMode(
-                    handle,
-                    mode | ENABLE_PROCESSED_OUTPUT | ENABLE_VIRTUAL_TERMINAL_PROCESSING,
-                );
-            }
-        }
-    }
-}
+// ── Console window size (Windows) ────────────────────────────────────────
 
 /// Resize the console window so the TUI fits without scrolling.
 /// Targets 45 rows × 80 columns — enough for the tallest menu page
@@ -180,6 +135,7 @@ pub(crate) enum Page {
 
 pub fn run() -> Result<(), Box<dyn std::error::Error>> {
     ensure_console_size();
+    lg_service::enable_windows_location();
     let mut out = io::stdout();
 
     // Enter the alternate screen buffer so TUI output never pollutes
@@ -501,25 +457,7 @@ fn run_inner(mut out: &mut impl Write) -> Result<(), Box<dyn std::error::Error>>
                 action_ddc_read_brightness(&ddc_target)
             })?,
             (Page::Maintenance2, 'b') => {
-                if let Some(value) =
-                    prompt_u8(&mut out, "SET BRIGHTNESS", "Enter brightness 0..100", 50)?
-                {
-                    if let Err(message) = validate_guarded_ddc_write(
-                        &ddc_guardrails,
-                        lg_monitor::ddc::VCP_BRIGHTNESS,
-                        value as u32,
-                    ) {
-                        run_action(&mut out, "DDC guardrails blocked write...", || {
-                            Err(message.into())
-                        })?;
-                        continue;
-                    }
-                    run_action(
-                        &mut out,
-                        &format!("Setting brightness to {}...", value),
-                        || action_ddc_set_brightness(&ddc_target, value as u32),
-                    )?;
-                }
+                run_brightness_slider(&mut out, &ddc_target, &ddc_guardrails)?
             }
             (Page::Maintenance2, 'c') => {
                 if let Some(code) = prompt_vcp_code(
@@ -568,6 +506,110 @@ fn run_inner(mut out: &mut impl Write) -> Result<(), Box<dyn std::error::Error>>
                     }
                 }
             }
+            (Page::Maintenance2, 'f') => run_action(&mut out, "Reading sharpness...", || {
+                action_ddc_read_sharpness(&ddc_target)
+            })?,
+            (Page::Maintenance2, 'g') => {
+                let cur = ddc_get_vcp(&ddc_target, lg_monitor::ddc::VCP_SHARPNESS)
+                    .map(|v| v.current)
+                    .unwrap_or(50);
+                if let Some(value) =
+                    prompt_u32(&mut out, "SET SHARPNESS", "Enter sharpness value", cur)?
+                {
+                    if let Err(message) = validate_guarded_ddc_write(
+                        &ddc_guardrails,
+                        lg_monitor::ddc::VCP_SHARPNESS,
+                        value,
+                    ) {
+                        run_action(&mut out, "DDC guardrails blocked write...", || {
+                            Err(message.into())
+                        })?;
+                        continue;
+                    }
+                    run_action(
+                        &mut out,
+                        &format!("Setting sharpness to {}...", value),
+                        || {
+                            ddc_set_vcp(&ddc_target, lg_monitor::ddc::VCP_SHARPNESS, value)?;
+                            log_ok(&format!("Sharpness set to {}", value));
+                            log_done("Sharpness updated.");
+                            Ok(())
+                        },
+                    )?;
+                }
+            }
+            (Page::Maintenance2, 'h') => run_action(&mut out, "Reading response time...", || {
+                action_ddc_read_response_time(&ddc_target)
+            })?,
+            (Page::Maintenance2, 'i') => match ddc_capability_entries(&ddc_target) {
+                Ok(entries) => {
+                    let Some(code) = prompt_response_time_code(&mut out, &entries)? else {
+                        continue;
+                    };
+                    let values = entries
+                        .into_iter()
+                        .find(|e| e.code == code)
+                        .map(|e| e.values)
+                        .unwrap_or_default();
+                    if values.is_empty() {
+                        run_action(&mut out, "Reading response time...", || {
+                            Err(format!(
+                                "VCP 0x{:02X} advertises no values in the capabilities string",
+                                code
+                            )
+                            .into())
+                        })?;
+                        continue;
+                    }
+                    let cur = ddc_get_vcp(&ddc_target, code).map(|v| v.current).ok();
+                    let keys = b"123456789abcdefghijklmnop";
+                    let count = values.len().min(keys.len());
+                    let labels: Vec<String> = values[..count]
+                        .iter()
+                        .map(|v| format!("Value {}", v))
+                        .collect();
+                    let items: Vec<(char, &str, bool)> = (0..count)
+                        .map(|i| {
+                            (
+                                keys[i] as char,
+                                labels[i].as_str(),
+                                cur == Some(values[i] as u32),
+                            )
+                        })
+                        .collect();
+
+                    if let Some(idx) = run_submenu(&mut out, " RESPONSE TIME ", &items)? {
+                        let value = values[idx] as u32;
+                        if !confirm_ddc_write_if_risky(
+                            &mut out,
+                            &ddc_guardrails,
+                            code,
+                            value,
+                            &format!("Set response time (VCP 0x{:02X}) to {}", code, value),
+                        )? {
+                            continue;
+                        }
+                        run_action(
+                            &mut out,
+                            &format!("Setting response time to {}...", value),
+                            || {
+                                ddc_set_vcp(&ddc_target, code, value)?;
+                                log_ok(&format!(
+                                    "Response time set to {} (VCP 0x{:02X})",
+                                    value, code
+                                ));
+                                log_done("Response time updated.");
+                                Ok(())
+                            },
+                        )?;
+                    }
+                }
+                Err(e) => {
+                    run_action(&mut out, "Reading response time...", || {
+                        Err(format!("Could not read monitor capabilities: {}", e).into())
+                    })?;
+                }
+            },
             (Page::Maintenance2, '9') => match lg_monitor::ddc::list_physical_monitors() {
                 Ok(monitors) if !monitors.is_empty() => {
                     let keys = b"123456789abcdefghijklmnop";
@@ -1215,13 +1257,14 @@ fn tuning_from_config(cfg: &Config) -> lg_profile::DynamicIccTuning {
 }
 
 fn effective_preset_for_mode(cfg: &Config, hdr_mode: bool) -> String {
-    lg_profile::select_effective_preset(
+    lg_profile::select_effective_preset_for_period(
         &cfg.icc_active_preset,
         &cfg.icc_sdr_preset,
         &cfg.icc_hdr_preset,
         &cfg.icc_schedule_day_preset,
         &cfg.icc_schedule_night_preset,
         hdr_mode,
+        lg_core::schedule::is_daytime_now(cfg),
     )
 }
 
@@ -1499,11 +1542,18 @@ pub(crate) fn draw_maintenance2(
     draw_section(out, "WRITE")?;
     draw_item(out, "3", "Cycle Color Preset (sRGB→6500K→9300K→User1)")?;
     draw_item(out, "5", "Set Display Mode (VCP 0xDC)")?;
-    draw_item(out, "B", "Set Brightness (VCP 0x10)")?;
+    draw_item(out, "B", "Set Brightness — Slider (VCP 0x10)")?;
     draw_item(out, "D", "Write Custom VCP (Any code/value)")?;
     draw_item(out, "E", "DDC Guardrails (Limits + confirmations)")?;
     draw_empty(out)?;
 
+    draw_section(out, "PICTURE")?;
+    draw_item(out, "F", "Read Sharpness (VCP 0x87)")?;
+    draw_item(out, "G", "Set Sharpness (VCP 0x87)")?;
+    draw_item(out, "H", "Read Response Time / Overdrive")?;
+    draw_item(out, "I", "Set Response Time / Overdrive")?;
+    draw_empty(out)?;
+
     draw_section(out, "RESET")?;
     draw_item(out, "6", "Reset Brightness + Contrast (VCP 0x06)")?;
     draw_item(out, "7", "Reset Color (VCP 0x0A)")?;
@@ -2469,6 +2519,7 @@ fn draw_status(out: &mut impl Write, label: &str, value: &str, color: Color) ->
 
 /// Print a log line with a colored tag prefix: `  [TAG] message`.
 fn log_tag(tag: &str, color: Color, msg: &str) {
+    output::record(&format!("  {} {}", tag, msg));
     let mut out = io::stdout();
     let _ = queue!(out, SetForegroundColor(color));
     let _ = write!(out, "  {}", tag);
@@ -2484,7 +2535,7 @@ fn log_dry(msg: &str) {
     log_tag("[DRY RUN]", Color::Cyan, msg);
 }
 fn log_done(msg: &str) {
-    println!(); // blank line before completion tag
+    say!(); // blank line before completion tag
     log_tag("[DONE]", Color::Green, msg);
 }
 fn log_info(msg: &str) {
@@ -2534,6 +2585,244 @@ fn log_service_binary_placement(after_failed_install: bool) {
     }
 }
 
+fn log_legacy_migration(migration: &Option<lg_service::LegacyMigration>) {
+    let Some(migration) = migration else {
+        return;
+    };
+    log_ok(&format!(
+        "Migrated legacy service (was {})",
+        migration.legacy_binary
+    ));
+    if !migration.imported.is_empty() {
+        log_ok(&format!("Imported: {}", migration.imported.join(", ")));
+    }
+    log_note("The old binary was left in place; delete it when no longer needed");
+}
+
+// ============================================================================
+// Cancellable operations — Esc and per-action timeouts
+// ============================================================================
+
+/// Spinner frames shown while a long operation runs.
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+
+/// Longest a service install may run before it is cancelled.
+const INSTALL_TIMEOUT: Duration = Duration::from_secs(120);
+/// Longest a service uninstall may run before it is cancelled.
+const UNINSTALL_TIMEOUT: Duration = Duration::from_secs(60);
+/// Longest a monitor detection (WMI) query may run before it is abandoned.
+const DETECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long a cancelled operation gets to reach a cancellation point before
+/// the TUI stops waiting for it.
+const CANCEL_GRACE: Duration = Duration::from_secs(5);
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Queue of the background thread that runs cancellable operations. One
+/// long-lived thread keeps its per-thread WMI connection between actions.
+static WORKER: Mutex<Option<mpsc::Sender<Job>>> = Mutex::new(None);
+
+/// Run `job` on the worker thread, starting one if there is none (first use,
+/// after a panic, or after [`abandon_worker`]).
+fn submit_job(job: Job) {
+    let mut worker = WORKER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let job = match worker.as_ref().map(|jobs| jobs.send(job)) {
+        Some(Ok(())) => return,
+        Some(Err(mpsc::SendError(job))) => job,
+        None => job,
+    };
+    let (jobs, queue) = mpsc::channel::<Job>();
+    std::thread::spawn(move || {
+        for job in queue {
+            job();
+        }
+    });
+    let _ = jobs.send(job);
+    *worker = Some(jobs);
+}
+
+/// Leave a hung worker behind; the next operation gets a fresh thread. The
+/// old one exits by itself if its call ever returns.
+fn abandon_worker() {
+    WORKER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .take();
+}
+
+/// A cancellable operation was stopped by Esc or its timeout.
+#[derive(Debug)]
+struct Interrupted(String);
+
+impl std::fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+enum Update<T> {
+    Progress(ProgressEvent),
+    Done(Result<T, String>),
+}
+
+/// Whether Esc was pressed since the last call (other keys are dropped).
+fn esc_pressed() -> bool {
+    let mut pressed = false;
+    while event::poll(Duration::ZERO).unwrap_or(false) {
+        if let Ok(Event::Key(KeyEvent {
+            code: KeyCode::Esc,
+            kind: KeyEventKind::Press,
+            ..
+        })) = event::read()
+        {
+            pressed = true;
+        }
+    }
+    pressed
+}
+
+/// Run a long operation on the worker thread, printing each step it reports
+/// as its own line with a spinner below, so the PROCESSING screen doesn't sit
+/// frozen while the service stops.
+///
+/// Esc, or running past `timeout`, cancels the token handed to `op`. If `op`
+/// doesn't return within [`CANCEL_GRACE`] after that (a hung SCM or WMI
+/// call), it is abandoned and the TUI carries on with an [`Interrupted`]
+/// error.
+fn run_cancellable<T, F>(timeout: Duration, op: F) -> Result<T, Box<dyn std::error::Error>>
+where
+    T: Send + 'static,
+    F: FnOnce(
+            &mut dyn FnMut(&ProgressEvent),
+            &ShutdownToken,
+        ) -> Result<T, Box<dyn std::error::Error>>
+        + Send
+        + 'static,
+{
+    let shutdown = ShutdownToken::new();
+    let (tx, rx) = mpsc::channel();
+    {
+        let shutdown = shutdown.clone();
+        submit_job(Box::new(move || {
+            let progress_tx = tx.clone();
+            let mut send = |event: &ProgressEvent| {
+                let _ = progress_tx.send(Update::Progress(event.clone()));
+            };
+            let result = op(&mut send, &shutdown).map_err(|e| e.to_string());
+            let _ = tx.send(Update::Done(result));
+        }));
+    }
+
+    let mut out = io::stdout();
+    let raw = terminal::enable_raw_mode().is_ok();
+    let started = Instant::now();
+    let mut interrupted: Option<(String, Instant)> = None;
+    let mut frame = 0;
+    let result = loop {
+        match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(Update::Progress(event)) => {
+                let color = match event {
+                    ProgressEvent::Step { .. } => Color::White,
+                    ProgressEvent::Detail(_) => Color::DarkGrey,
+                };
+                let _ = queue!(out, cursor::MoveToColumn(0), Clear(ClearType::CurrentLine));
+                let _ = queue!(out, SetForegroundColor(color));
+                let _ = write!(out, "  {}\r\n", event);
+                output::record(&format!("  {}", event));
+                let _ = queue!(out, ResetColor);
+            }
+            Ok(Update::Done(result)) => {
+                break result.map_err(|e| -> Box<dyn std::error::Error> {
+                    match &interrupted {
+                        Some((reason, _)) => Box::new(Interrupted(reason.clone())),
+                        None => e.into(),
+                    }
+                })
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                break Err("operation panicked".into());
+            }
+        }
+
+        match &interrupted {
+            None if esc_pressed() => {
+                interrupted = Some(("Cancelled (Esc)".to_string(), Instant::now()));
+                shutdown.cancel();
+            }
+            None if started.elapsed() >= timeout => {
+                interrupted = Some((
+                    format!("Timed out after {} s", timeout.as_secs()),
+                    Instant::now(),
+                ));
+                shutdown.cancel();
+            }
+            Some((reason, at)) if at.elapsed() >= CANCEL_GRACE => {
+                abandon_worker();
+                break Err(Box::new(Interrupted(format!(
+                    "{}; the operation did not stop and was left running in the background",
+                    reason
+                ))));
+            }
+            _ => {}
+        }
+
+        let hint = if interrupted.is_some() {
+            "cancelling..."
+        } else {
+            "Esc to cancel"
+        };
+        let _ = queue!(
+            out,
+            cursor::MoveToColumn(0),
+            SetForegroundColor(Color::Cyan)
+        );
+        let _ = write!(out, "  {}", SPINNER_FRAMES[frame % SPINNER_FRAMES.len()]);
+        let _ = queue!(out, SetForegroundColor(Color::DarkGrey));
+        let _ = write!(out, " {}", hint);
+        let _ = queue!(out, ResetColor, Clear(ClearType::UntilNewLine));
+        let _ = out.flush();
+        frame += 1;
+    };
+
+    let _ = queue!(out, cursor::MoveToColumn(0), Clear(ClearType::CurrentLine));
+    let _ = out.flush();
+    if raw {
+        let _ = terminal::disable_raw_mode();
+    }
+    result
+}
+
+/// Monitor detection for TUI actions, bounded by [`DETECT_TIMEOUT`].
+fn find_monitors(
+    pattern: &str,
+    mode: lg_monitor::MonitorMatchMode,
+) -> Result<Vec<lg_monitor::MatchedMonitor>, Box<dyn std::error::Error>> {
+    let pattern = pattern.to_string();
+    run_cancellable(DETECT_TIMEOUT, move |_, _| {
+        lg_monitor::find_matching_monitors_with_mode(&pattern, mode)
+    })
+}
+
+/// Install the service for TUI actions, bounded by [`INSTALL_TIMEOUT`].
+fn install_service(
+    monitor_match: &str,
+) -> Result<Option<lg_service::LegacyMigration>, Box<dyn std::error::Error>> {
+    let monitor_match = monitor_match.to_string();
+    run_cancellable(INSTALL_TIMEOUT, move |progress, shutdown| {
+        lg_service::install_with_progress(&monitor_match, progress, shutdown)
+    })
+}
+
+/// Uninstall the service for TUI actions, bounded by [`UNINSTALL_TIMEOUT`].
+fn uninstall_service() -> Result<(), Box<dyn std::error::Error>> {
+    run_cancellable(UNINSTALL_TIMEOUT, lg_service::uninstall_with_progress)
+}
+
 /// Write a colored error tag to an arbitrary `Write` sink (used by
 /// `run_action` which writes to `out` rather than stdout).
 fn write_err(out: &mut impl Write, msg: &str) -> io::Result<()> {
@@ -2567,13 +2856,15 @@ where
     writeln!(out)?;
     out.flush()?;
 
-    match action() {
-        Ok(()) => {}
-        Err(e) => {
-            write_err(out, &e.to_string())?;
-        }
+    let (result, mut lines) = output::capture(action);
+    if let Err(e) = result {
+        write_err(out, &e.to_string())?;
+        lines.push(format!("  [ERR ] {}", e));
     }
 
+    if !lines.is_empty() {
+        return show_result(out, banner, &lines);
+    }
     writeln!(out)?;
     queue!(out, SetForegroundColor(Color::DarkGrey))?;
     write!(out, "  Press any key to continue...")?;
@@ -2613,20 +2904,26 @@ where
     writeln!(out)?;
     out.flush()?;
 
-    let failed = match action() {
+    let (result, mut lines) = output::capture(action);
+    let failed = match result {
         Ok(()) => false,
         Err(e) => {
+            let tip = "  Tip: If Event Viewer or another MMC snap-in is open, close it and retry.";
             write_err(out, &e.to_string())?;
             queue!(out, SetForegroundColor(Color::DarkYellow))?;
-            writeln!(
-                out,
-                "  Tip: If Event Viewer or another MMC snap-in is open, close it and retry."
-            )?;
+            writeln!(out, "{}", tip)?;
             queue!(out, ResetColor)?;
+            lines.push(format!("  [ERR ] {}", e));
+            lines.push(tip.to_string());
             true
         }
     };
 
+    // A failure always has output, so the folder offer follows the pane.
+    let shown = !lines.is_empty();
+    if shown {
+        show_result(out, banner, &lines)?;
+    }
     writeln!(out)?;
     if failed && !folders.is_empty() {
         queue!(out, SetForegroundColor(Color::Yellow))?;
@@ -2649,7 +2946,7 @@ where
                 break;
             }
         }
-    } else {
+    } else if !shown {
         queue!(out, SetForegroundColor(Color::DarkGrey))?;
         write!(out, "  Press any key to continue...")?;
         queue!(out, ResetColor)?;
@@ -2659,6 +2956,191 @@ where
     Ok(())
 }
 
+// ============================================================================
+// Result pane — scrollable view of what an action reported
+// ============================================================================
+
+/// Keys of the result pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ResultKey {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Save,
+    Close,
+}
+
+fn read_result_key() -> io::Result<ResultKey> {
+    terminal::enable_raw_mode()?;
+    let key = loop {
+        if let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        {
+            break match code {
+                KeyCode::Up => ResultKey::Up,
+                KeyCode::Down => ResultKey::Down,
+                KeyCode::PageUp => ResultKey::PageUp,
+                KeyCode::PageDown => ResultKey::PageDown,
+                KeyCode::Home => ResultKey::Home,
+                KeyCode::End => ResultKey::End,
+                KeyCode::Char('s' | 'S') => ResultKey::Save,
+                _ => ResultKey::Close,
+            };
+        }
+    };
+    terminal::disable_raw_mode()?;
+    Ok(key)
+}
+
+/// `lines` cut into rows that fit inside the box, keeping indentation.
+pub(crate) fn result_rows(lines: &[String]) -> Vec<String> {
+    let mut rows = Vec::new();
+    for line in lines {
+        let chars: Vec<char> = line.trim_end().chars().collect();
+        if chars.is_empty() {
+            rows.push(String::new());
+        }
+        rows.extend(chars.chunks(INNER).map(|chunk| chunk.iter().collect()));
+    }
+    rows
+}
+
+/// First row shown after `key`, with `total` rows in a window `height`
+/// rows tall.
+pub(crate) fn scroll_result(offset: usize, key: ResultKey, total: usize, height: usize) -> usize {
+    let last = total.saturating_sub(height);
+    match key {
+        ResultKey::Up => offset.saturating_sub(1),
+        ResultKey::Down => (offset + 1).min(last),
+        ResultKey::PageUp => offset.saturating_sub(height),
+        ResultKey::PageDown => (offset + height).min(last),
+        ResultKey::Home => 0,
+        ResultKey::End => last,
+        ResultKey::Save | ResultKey::Close => offset,
+    }
+}
+
+/// Colour of a result row, by the tag in it.
+fn result_color(row: &str) -> Color {
+    let tag = row.trim_start();
+    if tag.starts_with("[ERR ]") || tag.starts_with("[FAIL]") {
+        Color::Red
+    } else if tag.starts_with("[WARN]") {
+        Color::Yellow
+    } else if tag.starts_with("[ OK ]") || tag.starts_with("[OK]") || tag.starts_with("[DONE]") {
+        Color::Green
+    } else if tag.starts_with("[INFO]") {
+        Color::Blue
+    } else if tag.starts_with("[DRY RUN]") {
+        Color::Cyan
+    } else if tag.starts_with("[NOTE]") || tag.starts_with("[SKIP]") {
+        Color::DarkGrey
+    } else {
+        Color::White
+    }
+}
+
+/// Contents of a saved result: the action's banner, then its output.
+pub(crate) fn result_file_text(banner: &str, lines: &[String]) -> String {
+    let mut text = format!("{}\n\n", banner.trim());
+    for line in lines {
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// Write the result to a new file in the state folder.
+fn save_result(banner: &str, lines: &[String]) -> io::Result<std::path::PathBuf> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let path = app_state::state_dir().join(format!("tui-output-{}.txt", stamp));
+    std::fs::create_dir_all(app_state::state_dir())?;
+    std::fs::write(&path, result_file_text(banner, lines))?;
+    Ok(path)
+}
+
+/// Rows of output that fit on screen under the pane's header and above
+/// its footer.
+fn result_height() -> usize {
+    let rows = terminal::size().map_or(30, |(_, rows)| rows as usize);
+    rows.saturating_sub(9).max(5)
+}
+
+pub(crate) fn draw_result(
+    out: &mut impl Write,
+    banner: &str,
+    rows: &[String],
+    offset: usize,
+    height: usize,
+    status: Option<&str>,
+) -> io::Result<()> {
+    queue!(
+        out,
+        Clear(ClearType::Purge),
+        Clear(ClearType::All),
+        cursor::MoveTo(0, 0)
+    )?;
+    draw_top(out, " RESULT ")?;
+    draw_line(out, banner, Color::Yellow)?;
+    draw_sep(out, "")?;
+    for row in rows.iter().skip(offset).take(height) {
+        draw_line(out, row, result_color(row))?;
+    }
+    for _ in rows.len().saturating_sub(offset)..height {
+        draw_empty(out)?;
+    }
+    draw_sep(out, "")?;
+    let position = if rows.len() > height {
+        format!(
+            "  Lines {}-{} of {}",
+            offset + 1,
+            (offset + height).min(rows.len()),
+            rows.len()
+        )
+    } else {
+        format!("  {} line(s)", rows.len())
+    };
+    draw_line(out, &position, Color::DarkGrey)?;
+    draw_line(
+        out,
+        "  PgUp/PgDn, Up/Down, Home/End scroll  [S] Save to file  Other: continue",
+        Color::DarkGrey,
+    )?;
+    draw_bottom(out)?;
+    if let Some(status) = status {
+        writeln!(out, "  {}", status)?;
+    }
+    out.flush()
+}
+
+/// Show what an action reported, starting at the end, until a key other
+/// than scrolling or Save is pressed.
+fn show_result(out: &mut impl Write, banner: &str, lines: &[String]) -> io::Result<()> {
+    let rows = result_rows(lines);
+    let height = result_height();
+    let mut offset = scroll_result(0, ResultKey::End, rows.len(), height);
+    let mut status = None;
+    loop {
+        draw_result(out, banner, &rows, offset, height, status.as_deref())?;
+        match read_result_key()? {
+            ResultKey::Close => return Ok(()),
+            ResultKey::Save => {
+                status = Some(match save_result(banner, lines) {
+                    Ok(path) => format!("Saved to {}", path.display()),
+                    Err(e) => format!("Could not save the output: {}", e),
+                });
+            }
+            key => offset = scroll_result(offset, key, rows.len(), height),
+        }
+    }
+}
+
 // ============================================================================
 // Sub-menu — interactive selection screen
 // ============================================================================
@@ -2894,6 +3376,34 @@ fn prompt_vcp_code(
     }
 }
 
+/// Ask for the response-time VCP code. It is model-specific, so the
+/// capabilities candidate is only shown, never used without being typed.
+fn prompt_response_time_code(
+    out: &mut impl Write,
+    entries: &[lg_monitor::ddc::CapabilityVcpEntry],
+) -> io::Result<Option<u8>> {
+    let candidate = match lg_monitor::ddc::response_time_candidate(entries) {
+        Some(code) => format!("candidate 0x{:02X} (unverified)", code),
+        None => "no candidate advertised".to_string(),
+    };
+    let Some(text) = prompt_text(
+        out,
+        "SET RESPONSE TIME",
+        "Enter the response time VCP code for this model (hex like E9 or 0xE9)",
+        &candidate,
+    )?
+    else {
+        return Ok(None);
+    };
+    match parse_vcp_code(&text) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => {
+            run_action(out, "Invalid VCP code...", || Err(e.into()))?;
+            Ok(None)
+        }
+    }
+}
+
 // ============================================================================
 // Actions — called from TUI menu selections
 // ============================================================================
@@ -3389,13 +3899,16 @@ fn run_snapshot_manager(
                         Config::write_config(cfg)?;
                         *dirty = false;
 
-                        let devices = lg_monitor::find_matching_monitors(&cfg.monitor_match)?;
+                        let devices = find_monitors(
+                            &cfg.monitor_match,
+                            lg_monitor::MonitorMatchMode::Substring,
+                        )?;
                         for device in &devices {
                             lg_profile::reapply_profile_with_mode_associations(
                                 &device.device_key,
                                 &profile_path,
                                 &profile_path,
-                                &profile_path,
+                                cfg.icc_hdr_association.then_some(profile_path.as_path()),
                                 cfg.toggle_delay_ms,
                                 opts.per_user,
                             )?;
@@ -3475,7 +3988,7 @@ fn action_toggle_ab_compare(
         return Ok(());
     }
 
-    let devices = lg_monitor::find_matching_monitors(&cfg.monitor_match)?;
+    let devices = find_monitors(&cfg.monitor_match, lg_monitor::MonitorMatchMode::Substring)?;
     if devices.is_empty() {
         return Err("No matching monitors found for A/B compare.".into());
     }
@@ -3530,7 +4043,7 @@ fn action_toggle_ab_compare(
             &device.device_key,
             &next_path,
             &next_path,
-            &next_path,
+            cfg.icc_hdr_association.then_some(next_path.as_path()),
             cfg.toggle_delay_ms,
             opts.per_user,
         )?;
@@ -3588,7 +4101,7 @@ fn action_safe_recovery(opts: &Options) -> Result<(), Box<dyn std::error::Error>
     cfg.icc_per_monitor_profiles = snapshot.per_monitor_profiles;
     Config::write_config(&cfg)?;
 
-    let devices = lg_monitor::find_matching_monitors(&cfg.monitor_match)?;
+    let devices = find_monitors(&cfg.monitor_match, lg_monitor::MonitorMatchMode::Substring)?;
     if devices.is_empty() {
         log_warn("No matching monitors found during recovery.");
     } else {
@@ -3597,7 +4110,7 @@ fn action_safe_recovery(opts: &Options) -> Result<(), Box<dyn std::error::Error>
                 &device.device_key,
                 &profile_path,
                 &profile_path,
-                &profile_path,
+                cfg.icc_hdr_association.then_some(profile_path.as_path()),
                 cfg.toggle_delay_ms,
                 opts.per_user,
             )?;
@@ -3660,8 +4173,9 @@ fn action_default_install(opts: &Options) -> Result<(), Box<dyn std::error::Erro
     }
 
     // Install service
-    match lg_service::install(&cfg.monitor_match) {
-        Ok(()) => {
+    match install_service(&cfg.monitor_match) {
+        Ok(migration) => {
+            log_legacy_migration(&migration);
             log_ok("Service installed");
             log_service_binary_placement(false);
         }
@@ -3715,8 +4229,9 @@ fn action_service_only(opts: &Options) -> Result<(), Box<dyn std::error::Error>>
         log_ok("Default config written");
     }
 
-    match lg_service::install(&cfg.monitor_match) {
-        Ok(()) => {
+    match install_service(&cfg.monitor_match) {
+        Ok(migration) => {
+            log_legacy_migration(&migration);
             log_ok("Service installed");
             log_service_binary_placement(false);
         }
@@ -3733,113 +4248,229 @@ fn action_service_only(opts: &Options) -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
-fn action_refresh(opts: &Options) -> Result<(), Box<dyn std::error::Error>> {
-    let started = std::time::Instant::now();
-    if opts.dry_run {
-        log_dry("Would re-apply SDR/HDR mode profiles to matching monitors");
-        return Ok(());
+/// Refresh side of the [`ReapplyPipeline`]: logs each step to the console.
+struct RefreshSteps<'a> {
+    cfg: &'a Config,
+    /// Show the toast (the on-screen badge follows its own setting).
+    toast: bool,
+    /// Device keys of every matching monitor.
+    connected: Vec<String>,
+    /// Toast placeholder values.
+    tally: ToastTally,
+}
+
+impl ReapplySteps for RefreshSteps<'_> {
+    type Monitor = lg_monitor::MatchedMonitor;
+
+    fn ensure_shared_profiles(
+        &mut self,
+    ) -> Result<Option<ModeProfiles>, Box<dyn std::error::Error>> {
+        if self.cfg.icc_per_monitor_profiles {
+            return Ok(None);
+        }
+        let (sdr, hdr) = ensure_shared_mode_profiles(self.cfg)?;
+        Ok(Some(ModeProfiles { sdr, hdr }))
     }
 
-    let cfg = Config::load();
-    let shared_mode_profiles = if cfg.icc_per_monitor_profiles {
-        None
-    } else {
-        Some(ensure_shared_mode_profiles(&cfg)?)
-    };
+    fn is_installed(&self, path: &std::path::Path) -> bool {
+        lg_profile::is_profile_installed(path)
+    }
 
-    let devices = lg_monitor::find_matching_monitors(&cfg.monitor_match)?;
-    let success = if devices.is_empty() {
-        log_skip("No matching monitors found.");
-        app_state::append_diagnostic_event("tui", "WARN", "apply_skip", "refresh: no monitors");
-        false
-    } else {
-        let mut last_applied_profile: Option<std::path::PathBuf> = None;
-        for device in &devices {
-            let (sdr_profile_path, hdr_profile_path) =
-                if let Some((sdr, hdr)) = &shared_mode_profiles {
-                    (sdr.clone(), hdr.clone())
-                } else {
-                    ensure_mode_profiles_for_monitor(&cfg, device)?
-                };
-            let active_profile_path = if opts.hdr {
-                &hdr_profile_path
-            } else {
-                &sdr_profile_path
-            };
-            log_info(&format!("Found: {}", device.name));
-            lg_profile::reapply_profile_with_mode_associations(
-                &device.device_key,
-                active_profile_path,
-                &sdr_profile_path,
-                &hdr_profile_path,
-                cfg.toggle_delay_ms,
-                opts.per_user,
-            )?;
-            last_applied_profile = Some(active_profile_path.clone());
-            log_ok(&format!("SDR/HDR profiles associated for {}", device.name));
-            if opts.generic_default {
-                lg_profile::set_generic_default(
-                    &device.device_key,
-                    active_profile_path,
-                    opts.per_user,
-                )?;
-                log_ok(&format!("Generic default set for {}", device.name));
-            }
+    fn find_monitors(
+        &mut self,
+    ) -> Result<Vec<lg_monitor::MatchedMonitor>, Box<dyn std::error::Error>> {
+        let match_mode = if self.cfg.monitor_match_regex {
+            lg_monitor::MonitorMatchMode::Regex
+        } else {
+            lg_monitor::MonitorMatchMode::Substring
+        };
+        let devices = find_monitors(&self.cfg.monitor_match, match_mode)?;
+        self.connected = devices.iter().map(|d| d.device_key.clone()).collect();
+        Ok(devices)
+    }
+
+    fn inherit_associations(&mut self, device: &lg_monitor::MatchedMonitor) {
+        log_info(&format!("Found: {}", device.name));
+        match lg_profile::inherit_previous_device_associations(&device.device_key, &self.connected)
+        {
+            Ok(Some(previous)) => log_info(&format!(
+                "Carried {} association(s) over from {}",
+                previous.profiles.len(),
+                previous.device_key
+            )),
+            Ok(None) => {}
+            Err(e) => log_warn(&format!("Could not check previous associations: {}", e)),
         }
-        lg_profile::refresh_display(false, cfg.refresh_broadcast_color, cfg.refresh_invalidate);
-        lg_profile::trigger_calibration_loader(cfg.refresh_calibration_loader);
+    }
 
-        // DDC/CI brightness (if enabled)
-        if cfg.ddc_brightness_on_reapply {
-            match lg_monitor::ddc::set_brightness_all(cfg.ddc_brightness_value) {
-                Ok(n) => log_ok(&format!(
-                    "DDC brightness set to {} on {} monitor(s)",
-                    cfg.ddc_brightness_value, n
-                )),
-                Err(e) => log_note(&format!("DDC brightness failed: {}", e)),
+    fn ensure_monitor_profiles(
+        &mut self,
+        device: &lg_monitor::MatchedMonitor,
+    ) -> Result<ModeProfiles, Box<dyn std::error::Error>> {
+        let (sdr, hdr) = ensure_mode_profiles_for_monitor(self.cfg, device)?;
+        Ok(ModeProfiles { sdr, hdr })
+    }
+
+    fn associate(
+        &mut self,
+        device: &lg_monitor::MatchedMonitor,
+        active: &std::path::Path,
+        sdr: &std::path::Path,
+        hdr: Option<&std::path::Path>,
+        per_user: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        lg_profile::reapply_profile_with_mode_associations(
+            &device.device_key,
+            active,
+            sdr,
+            hdr,
+            self.cfg.toggle_delay_ms,
+            per_user,
+        )?;
+        log_ok(&format!("SDR/HDR profiles associated for {}", device.name));
+        Ok(())
+    }
+
+    fn set_generic_default(
+        &mut self,
+        device: &lg_monitor::MatchedMonitor,
+        active: &std::path::Path,
+        per_user: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        lg_profile::set_generic_default(&device.device_key, active, per_user)?;
+        log_ok(&format!("Generic default set for {}", device.name));
+        Ok(())
+    }
+
+    fn refresh(&mut self) {
+        lg_profile::refresh_display(
+            false,
+            self.cfg.refresh_broadcast_color,
+            self.cfg.refresh_invalidate,
+        );
+        lg_profile::trigger_calibration_loader(self.cfg.refresh_calibration_loader);
+    }
+
+    fn set_ddc_brightness(&mut self, level: u32) -> Result<usize, Box<dyn std::error::Error>> {
+        lg_monitor::ddc::set_brightness_all(level)
+    }
+
+    fn toast(&mut self) {
+        let values = self.tally.values();
+        lg_notify::show_reapply_toast_quiet_aware(
+            self.toast,
+            &toast_template::render(&self.cfg.toast_title, &values),
+            &toast_template::render(i18n::toast_body(self.cfg), &values),
+            lg_notify::QuietHours::parse(&self.cfg.toast_quiet_hours),
+            self.cfg.verbose,
+        );
+        lg_notify::show_reapply_osd(
+            self.cfg.osd_enabled,
+            &self.cfg.osd_position,
+            self.cfg.osd_duration_ms,
+        );
+    }
+
+    fn monitor_done(&mut self, device: &lg_monitor::MatchedMonitor, result: &MonitorResult<'_>) {
+        match result.error {
+            Some(e) => log_err(&format!("{}: {}", device.name, e)),
+            None => {
+                if let Some(active) = result.active {
+                    self.tally.record(&device.name, active);
+                }
             }
         }
+    }
+}
+
+fn action_refresh(opts: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let started = std::time::Instant::now();
+    if opts.dry_run {
+        log_dry("Would re-apply SDR/HDR mode profiles to matching monitors");
+        return Ok(());
+    }
 
-        if opts.toast && cfg.toast_enabled {
-            lg_notify::show_reapply_toast(true, &cfg.toast_title, &cfg.toast_body, opts.verbose);
+    let cfg = Config::load();
+    let ddc_available = cfg.ddc_brightness_on_reapply && lg_monitor::ddc::is_supported();
+    let pipeline = ReapplyPipeline::new(ReapplyOptions {
+        per_user: opts.per_user,
+        hdr: cfg.icc_hdr_association,
+        hdr_active: opts.hdr,
+        generic_default: opts.generic_default,
+        // DDC/CI brightness (if enabled and available)
+        ddc_brightness: ddc_available.then_some(cfg.ddc_brightness_value),
+        toast: (opts.toast && cfg.toast_enabled) || cfg.osd_enabled,
+    });
+    let mut steps = RefreshSteps {
+        cfg: &cfg,
+        toast: opts.toast && cfg.toast_enabled,
+        connected: Vec::new(),
+        tally: ToastTally::new("refresh", started),
+    };
+    let outcome = pipeline.run(&mut steps)?;
+
+    let text = i18n::strings(&cfg);
+    let success = if outcome.found == 0 {
+        log_skip(text.no_monitors);
+        app_state::append_diagnostic_event("tui", "WARN", "apply_skip", "refresh: no monitors");
+        false
+    } else {
+        match &outcome.ddc {
+            Some(Ok(n)) => log_ok(&format!(
+                "DDC brightness set to {} on {} monitor(s)",
+                cfg.ddc_brightness_value.min(100),
+                n
+            )),
+            Some(Err(e)) => log_note(&format!("DDC brightness failed: {}", e)),
+            None => {}
         }
 
-        if let Some(profile_path) = last_applied_profile.as_ref() {
+        if let Some(profile_path) = outcome.last_applied.as_ref() {
             maybe_capture_last_good(
                 &cfg,
                 profile_path,
                 "Manual refresh apply",
-                if cfg.ddc_brightness_on_reapply {
-                    Some(cfg.ddc_brightness_value)
-                } else {
-                    None
-                },
+                pipeline.options().ddc_brightness,
             );
         }
-        app_state::append_diagnostic_event(
-            "tui",
-            "INFO",
-            "apply_success",
-            &format!("refresh applied to {} monitor(s)", devices.len()),
-        );
-
-        log_done(&format!(
-            "Profile refreshed for {} monitor(s).",
-            devices.len()
-        ));
-        true
+        if outcome.failed() == 0 {
+            app_state::append_diagnostic_event(
+                "tui",
+                "INFO",
+                "apply_success",
+                &format!("refresh applied to {} monitor(s)", outcome.applied),
+            );
+            log_done(&text.reapplied_count(outcome.applied));
+        } else {
+            app_state::append_diagnostic_event(
+                "tui",
+                "ERROR",
+                "apply_error",
+                &format!(
+                    "refresh failed for {} of {} monitor(s)",
+                    outcome.failed(),
+                    outcome.found
+                ),
+            );
+        }
+        outcome.failed() == 0
     };
 
     emit_apply_latency_tui(
         started,
         success,
-        &format!(
-            "action=refresh monitors={}",
-            if success { devices.len() } else { 0 }
-        ),
+        &format!("action=refresh monitors={}", outcome.applied),
     );
 
-    Ok(())
+    match &outcome.first_error {
+        Some(e) => Err(format!(
+            "{}: {}",
+            text.monitors_failed(outcome.failed(), outcome.found),
+            e
+        )
+        .into()),
+        None => Ok(()),
+    }
 }
 
 fn action_icc_generate_and_apply(
@@ -3864,9 +4495,9 @@ fn action_icc_generate_and_apply(
     } else {
         lg_monitor::MonitorMatchMode::Substring
     };
-    let devices = lg_monitor::find_matching_monitors_with_mode(&cfg.monitor_match, match_mode)?;
+    let devices = find_monitors(&cfg.monitor_match, match_mode)?;
     if devices.is_empty() {
-        log_skip("No matching monitors found.");
+        log_skip(i18n::strings(cfg).no_monitors);
         app_state::append_diagnostic_event(
             "tui",
             "WARN",
@@ -3878,6 +4509,7 @@ fn action_icc_generate_and_apply(
     }
 
     let mut last_applied_profile: Option<std::path::PathBuf> = None;
+    let mut tally = ToastTally::new("icc optimize", started);
     for device in &devices {
         let (sdr_profile_path, hdr_profile_path) = if let Some((sdr, hdr)) = &shared_mode_profiles {
             (sdr.clone(), hdr.clone())
@@ -3901,11 +4533,13 @@ fn action_icc_generate_and_apply(
             &device.device_key,
             active_profile_path,
             &sdr_profile_path,
-            &hdr_profile_path,
+            cfg.icc_hdr_association
+                .then_some(hdr_profile_path.as_path()),
             cfg.toggle_delay_ms,
             opts.per_user,
         )?;
         last_applied_profile = Some(active_profile_path.clone());
+        tally.record(&device.name, active_profile_path);
         if opts.generic_default {
             lg_profile::set_generic_default(
                 &device.device_key,
@@ -3931,8 +4565,16 @@ fn action_icc_generate_and_apply(
     }
 
     if opts.toast && cfg.toast_enabled {
-        lg_notify::show_reapply_toast(true, &cfg.toast_title, &cfg.toast_body, opts.verbose);
+        let values = tally.values();
+        lg_notify::show_reapply_toast_quiet_aware(
+            true,
+            &toast_template::render(&cfg.toast_title, &values),
+            &toast_template::render(i18n::toast_body(cfg), &values),
+            lg_notify::QuietHours::parse(&cfg.toast_quiet_hours),
+            opts.verbose,
+        );
     }
+    lg_notify::show_reapply_osd(cfg.osd_enabled, &cfg.osd_position, cfg.osd_duration_ms);
 
     let tuning = tuning_from_config(cfg);
     log_ok(&format!(
@@ -3984,8 +4626,9 @@ fn action_reinstall(opts: &Options) -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Best-effort uninstall first
-    match lg_service::uninstall() {
+    match uninstall_service() {
         Ok(()) => log_ok("Service uninstalled"),
+        Err(e) if e.is::<Interrupted>() => return Err(e),
         Err(e) => log_note(&format!("Service removal: {} (continuing)", e)),
     }
 
@@ -3995,28 +4638,28 @@ fn action_reinstall(opts: &Options) -> Result<(), Box<dyn std::error::Error>> {
 
 fn action_detect() -> Result<(), Box<dyn std::error::Error>> {
     let cfg = Config::load();
-    let devices = lg_monitor::find_matching_monitors(&cfg.monitor_match)?;
+    let devices = find_monitors(&cfg.monitor_match, lg_monitor::MonitorMatchMode::Substring)?;
 
     if devices.is_empty() {
-        println!(
+        say!(
             "  No matching monitors found for pattern \"{}\".",
             cfg.monitor_match
         );
     } else {
-        println!(
+        say!(
             "  Found {} monitor(s) matching \"{}\":\n",
             devices.len(),
             cfg.monitor_match
         );
         for (i, device) in devices.iter().enumerate() {
-            println!("    {}. {}", i + 1, device.name);
-            println!("       Device: {}", device.device_key);
+            say!("    {}. {}", i + 1, device.name);
+            say!("       Device: {}", device.device_key);
         }
     }
 
     let profile_path = resolve_active_profile_path(&cfg);
-    println!("\n  Profile: {}", profile_path.display());
-    println!(
+    say!("\n  Profile: {}", profile_path.display());
+    say!(
         "  Installed: {}",
         if lg_profile::is_profile_installed(&profile_path) {
             "yes"
@@ -4034,7 +4677,7 @@ fn action_remove_service(opts: &Options) -> Result<(), Box<dyn std::error::Error
         return Ok(());
     }
 
-    lg_service::uninstall()?;
+    uninstall_service()?;
     log_ok("Service uninstalled");
     log_note("ICC profile preserved in color store");
     Ok(())
@@ -4067,8 +4710,9 @@ fn action_full_uninstall(opts: &Options) -> Result<(), Box<dyn std::error::Error
     }
 
     // Remove service (best-effort)
-    match lg_service::uninstall() {
+    match uninstall_service() {
         Ok(()) => log_ok("Service uninstalled"),
+        Err(e) if e.is::<Interrupted>() => return Err(e),
         Err(e) => log_note(&format!("Service removal: {} (continuing)", e)),
     }
 
@@ -4111,8 +4755,8 @@ fn action_service_status() -> Result<(), Box<dyn std::error::Error>> {
     } else {
         log_warn("Service is NOT installed");
     }
-    println!();
-    lg_service::print_status()?;
+    say!();
+    lg_service::report_status(output::block)?;
     app_state::append_diagnostic_event(
         "tui",
         "INFO",
@@ -4147,7 +4791,7 @@ fn action_check_applicability() -> Result<(), Box<dyn std::error::Error>> {
     let mut conflict_hits = 0usize;
 
     // Check monitor
-    let devices = lg_monitor::find_matching_monitors(&cfg.monitor_match)?;
+    let devices = find_monitors(&cfg.monitor_match, lg_monitor::MonitorMatchMode::Substring)?;
     if devices.is_empty() {
         log_warn(&format!("No monitors matching \"{}\"", cfg.monitor_match));
     } else {
@@ -4157,7 +4801,7 @@ fn action_check_applicability() -> Result<(), Box<dyn std::error::Error>> {
             cfg.monitor_match
         ));
         for d in &devices {
-            println!("         - {}", d.name);
+            say!("         - {}", d.name);
         }
     }
 
@@ -4249,7 +4893,13 @@ fn action_check_applicability() -> Result<(), Box<dyn std::error::Error>> {
 fn action_test_toast(opts: &Options) -> Result<(), Box<dyn std::error::Error>> {
     let cfg = Config::load();
     log_info("Sending test toast notification...");
-    lg_notify::show_reapply_toast(true, &cfg.toast_title, &cfg.toast_body, opts.verbose);
+    let values = ToastValues::sample();
+    lg_notify::show_reapply_toast(
+        true,
+        &toast_template::render(&cfg.toast_title, &values),
+        &toast_template::render(i18n::toast_body(&cfg), &values),
+        opts.verbose,
+    );
     if opts.toast {
         log_ok("Toast notification sent (check your notification area)");
     } else {
@@ -4268,9 +4918,9 @@ fn action_force_refresh_profile(opts: &Options) -> Result<(), Box<dyn std::error
         Some(ensure_shared_mode_profiles(&cfg)?)
     };
 
-    let devices = lg_monitor::find_matching_monitors(&cfg.monitor_match)?;
+    let devices = find_monitors(&cfg.monitor_match, lg_monitor::MonitorMatchMode::Substring)?;
     let success = if devices.is_empty() {
-        log_skip("No matching monitors found.");
+        log_skip(i18n::strings(&cfg).no_monitors);
         app_state::append_diagnostic_event(
             "tui",
             "WARN",
@@ -4297,7 +4947,8 @@ fn action_force_refresh_profile(opts: &Options) -> Result<(), Box<dyn std::error
                 &device.device_key,
                 active_profile_path,
                 &sdr_profile_path,
-                &hdr_profile_path,
+                cfg.icc_hdr_association
+                    .then_some(hdr_profile_path.as_path()),
                 cfg.toggle_delay_ms,
                 opts.per_user,
             )?;
@@ -4679,21 +5330,349 @@ fn action_ddc_read_brightness(
     Ok(())
 }
 
-fn action_ddc_set_brightness(
+// ── Brightness slider (DDC/CI Studio) ────────────────────────────────────
+
+/// Width of the slider bar in characters.
+const SLIDER_WIDTH: usize = 40;
+/// Step for Shift+arrow and PgUp/PgDn.
+const SLIDER_BIG_STEP: i64 = 10;
+/// With no key-release events (some terminals), a value is written once
+/// the arrow keys have been left alone this long.
+const SLIDER_SETTLE: Duration = Duration::from_millis(400);
+/// How often the monitors are read again while the slider is idle.
+const SLIDER_REREAD: Duration = Duration::from_secs(2);
+
+/// How the last brightness write to a monitor went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SliderFeedback {
+    None,
+    Written,
+    /// Written, but the monitor reads back another value.
+    Differs(u32),
+    /// Blocked by the guardrails or refused by the monitor.
+    Rejected(String),
+}
+
+/// One monitor on the brightness slider.
+#[derive(Debug, Clone)]
+pub(crate) struct SliderMonitor {
+    pub index: usize,
+    pub name: String,
+    /// Brightness last read over DDC/CI; `None` when the monitor didn't
+    /// answer.
+    pub read: Option<u32>,
+    pub max: u32,
+    /// Slider position.
+    pub value: u32,
+    /// `value` was moved and not written yet.
+    pub pending: bool,
+    pub feedback: SliderFeedback,
+}
+
+impl SliderMonitor {
+    /// `read` is the `(current, max)` brightness, if the monitor answered.
+    pub fn new(index: usize, name: String, read: Option<(u32, u32)>) -> Self {
+        let max = read.map_or(100, |(_, max)| max).clamp(1, 100);
+        Self {
+            index,
+            name,
+            read: read.map(|(current, _)| current),
+            max,
+            value: read.map_or(max / 2, |(current, _)| current.min(max)),
+            pending: false,
+            feedback: SliderFeedback::None,
+        }
+    }
+
+    /// Move the slider by `delta`, within `0..=max`.
+    pub fn nudge(&mut self, delta: i64) {
+        let value = (self.value as i64 + delta).clamp(0, self.max as i64) as u32;
+        if value != self.value {
+            self.value = value;
+            self.pending = true;
+        }
+    }
+
+    /// The value to write, if the slider moved since the last write.
+    pub fn take_pending(&mut self) -> Option<u32> {
+        std::mem::take(&mut self.pending).then_some(self.value)
+    }
+
+    /// `written` went through; `read_back` is what the monitor now reports.
+    pub fn confirm(&mut self, written: u32, read_back: Option<u32>) {
+        self.feedback = match read_back {
+            Some(value) if value != written => SliderFeedback::Differs(value),
+            _ => SliderFeedback::Written,
+        };
+        self.read = read_back.or(Some(written));
+    }
+
+    /// The write didn't happen: back to the last value read.
+    pub fn reject(&mut self, reason: String) {
+        self.feedback = SliderFeedback::Rejected(reason);
+        if let Some(read) = self.read {
+            self.value = read.min(self.max);
+        }
+        self.pending = false;
+    }
+
+    /// A fresh reading. The slider follows it unless it was just moved.
+    pub fn reread(&mut self, current: Option<u32>) {
+        if let Some(current) = current {
+            self.read = Some(current);
+            if !self.pending {
+                self.value = current.min(self.max);
+            }
+        }
+    }
+}
+
+/// `value` of `max` as a bar `width` characters wide.
+pub(crate) fn slider_bar(value: u32, max: u32, width: usize) -> String {
+    let filled = if max == 0 {
+        0
+    } else {
+        (value.min(max) as usize * width + max as usize / 2) / max as usize
+    };
+    format!(
+        "{}{}",
+        "\u{2588}".repeat(filled),
+        "\u{2591}".repeat(width - filled)
+    )
+}
+
+pub(crate) fn draw_brightness_slider(
+    out: &mut impl Write,
+    target: &str,
+    monitors: &[SliderMonitor],
+    selected: usize,
+) -> io::Result<()> {
+    queue!(
+        out,
+        Clear(ClearType::Purge),
+        Clear(ClearType::All),
+        cursor::MoveTo(0, 0)
+    )?;
+    draw_top(out, " BRIGHTNESS (VCP 0x10) ")?;
+    draw_empty(out)?;
+    draw_line(out, &format!("  Target: {}", target), Color::Green)?;
+    for (i, monitor) in monitors.iter().enumerate() {
+        draw_empty(out)?;
+        let (marker, color) = if i == selected {
+            (">", Color::Yellow)
+        } else {
+            (" ", Color::White)
+        };
+        draw_line(
+            out,
+            &format!("{} {} (#{})", marker, monitor.name, monitor.index),
+            color,
+        )?;
+        let read = match monitor.read {
+            Some(read) => format!("read {}/{}", read, monitor.max),
+            None => "read —".to_string(),
+        };
+        draw_line(
+            out,
+            &format!(
+                "    {} {:>3}  {}",
+                slider_bar(monitor.value, monitor.max, SLIDER_WIDTH),
+                monitor.value,
+                read
+            ),
+            color,
+        )?;
+        let (feedback, feedback_color) = match &monitor.feedback {
+            _ if monitor.pending => (
+                "… writes when the key is released".to_string(),
+                Color::DarkGrey,
+            ),
+            SliderFeedback::None if monitor.read.is_none() => (
+                "No DDC/CI reply — check DDC/CI in the monitor's menu".to_string(),
+                Color::DarkYellow,
+            ),
+            SliderFeedback::None => (String::new(), Color::DarkGrey),
+            SliderFeedback::Written => ("\u{2713} Written".to_string(), Color::Green),
+            SliderFeedback::Differs(value) => (
+                format!("! Written, but the monitor reports {}", value),
+                Color::Yellow,
+            ),
+            SliderFeedback::Rejected(reason) => {
+                (format!("\u{2717} Rejected: {}", reason), Color::Red)
+            }
+        };
+        for line in wrap_text(&feedback, INNER.saturating_sub(4)) {
+            draw_line(out, &format!("    {}", line), feedback_color)?;
+        }
+    }
+    draw_empty(out)?;
+    draw_sep(out, "")?;
+    draw_line(
+        out,
+        "  \u{2190}/\u{2192} \u{00b1}1   Shift+\u{2190}/\u{2192}, PgDn/PgUp \u{00b1}10   Home/End min/max",
+        Color::DarkGrey,
+    )?;
+    draw_line(
+        out,
+        "  \u{2191}/\u{2193} choose monitor   Esc/Enter done",
+        Color::DarkGrey,
+    )?;
+    draw_bottom(out)?;
+    out.flush()
+}
+
+/// The monitors the slider works on: the DDC target, or every monitor
+/// matching the config pattern, each with its current brightness.
+fn slider_monitors(
     target: &Option<(usize, String)>,
-    value: u32,
-) -> Result<(), Box<dyn std::error::Error>> {
-    if value > 100 {
-        return Err("brightness must be 0..100".into());
+) -> Result<Vec<SliderMonitor>, Box<dyn std::error::Error>> {
+    let session = lg_monitor::ddc::DdcSession::open()?;
+    let indices = match target {
+        Some((index, _)) => vec![*index],
+        None => session.find_all(&Config::load().monitor_match)?,
+    };
+    let names = session.monitors();
+    Ok(indices
+        .into_iter()
+        .map(|index| {
+            let name = names
+                .iter()
+                .find(|(i, _)| *i == index)
+                .map_or_else(|| "Monitor".to_string(), |(_, name)| name.clone());
+            let read = session
+                .get_vcp(index, lg_monitor::ddc::VCP_BRIGHTNESS)
+                .ok()
+                .map(|v| (v.current, v.max));
+            SliderMonitor::new(index, name, read)
+        })
+        .collect())
+}
+
+/// Read every monitor's brightness again.
+fn reread_slider(monitors: &mut [SliderMonitor]) {
+    let Ok(session) = lg_monitor::ddc::DdcSession::open() else {
+        return;
+    };
+    for monitor in monitors {
+        let current = session
+            .get_vcp(monitor.index, lg_monitor::ddc::VCP_BRIGHTNESS)
+            .ok()
+            .map(|v| v.current);
+        monitor.reread(current);
     }
+}
 
-    log_info(&format!("Target: {}", ddc_target_label(target)));
-    log_info(&format!("Setting brightness to {}...", value));
+/// Write `monitor`'s pending value, if any, and read it back.
+fn write_slider(monitor: &mut SliderMonitor, guardrails: &app_state::DdcGuardrails) {
+    let Some(value) = monitor.take_pending() else {
+        return;
+    };
+    if let Err(message) =
+        validate_guarded_ddc_write(guardrails, lg_monitor::ddc::VCP_BRIGHTNESS, value)
+    {
+        monitor.reject(message);
+        return;
+    }
+    let result = lg_monitor::ddc::DdcSession::open().and_then(|session| {
+        session.set_vcp(monitor.index, lg_monitor::ddc::VCP_BRIGHTNESS, value)?;
+        Ok(session
+            .get_vcp(monitor.index, lg_monitor::ddc::VCP_BRIGHTNESS)
+            .ok()
+            .map(|v| v.current))
+    });
+    match result {
+        Ok(read_back) => {
+            monitor.confirm(value, read_back);
+            app_state::append_diagnostic_event(
+                "tui",
+                "INFO",
+                "ddc_brightness_slider",
+                &format!("monitor #{} brightness {}", monitor.index, value),
+            );
+        }
+        Err(e) => monitor.reject(e.to_string()),
+    }
+}
 
-    ddc_set_vcp(target, lg_monitor::ddc::VCP_BRIGHTNESS, value)?;
-    log_ok("Brightness set.");
-    log_done("Brightness write complete.");
-    Ok(())
+/// Interactive brightness slider for the DDC/CI Studio. Moving the slider
+/// only changes the bar; the value is written when the arrow key is
+/// released, then read back from the monitor.
+fn run_brightness_slider(
+    out: &mut impl Write,
+    target: &Option<(usize, String)>,
+    guardrails: &app_state::DdcGuardrails,
+) -> io::Result<()> {
+    let mut monitors = match slider_monitors(target) {
+        Ok(monitors) => monitors,
+        Err(e) => return run_action(out, "Reading brightness...", move || Err(e)),
+    };
+    let label = ddc_target_label(target);
+    let mut selected = 0;
+    let mut last_key = Instant::now();
+    let mut last_read = Instant::now();
+    loop {
+        draw_brightness_slider(out, &label, &monitors, selected)?;
+        terminal::enable_raw_mode()?;
+        let event = if event::poll(Duration::from_millis(100))? {
+            Some(event::read()?)
+        } else {
+            None
+        };
+        terminal::disable_raw_mode()?;
+
+        let monitor = &mut monitors[selected];
+        let Some(Event::Key(key)) = event else {
+            if monitor.pending && last_key.elapsed() >= SLIDER_SETTLE {
+                write_slider(monitor, guardrails);
+            } else if !monitor.pending && last_read.elapsed() >= SLIDER_REREAD {
+                reread_slider(&mut monitors);
+                last_read = Instant::now();
+            }
+            continue;
+        };
+        last_key = Instant::now();
+        let step = if key.modifiers.contains(KeyModifiers::SHIFT) {
+            SLIDER_BIG_STEP
+        } else {
+            1
+        };
+        if key.kind == KeyEventKind::Release {
+            if matches!(
+                key.code,
+                KeyCode::Left
+                    | KeyCode::Right
+                    | KeyCode::PageUp
+                    | KeyCode::PageDown
+                    | KeyCode::Home
+                    | KeyCode::End
+            ) {
+                write_slider(monitor, guardrails);
+                last_read = Instant::now();
+            }
+            continue;
+        }
+        match key.code {
+            KeyCode::Left => monitor.nudge(-step),
+            KeyCode::Right => monitor.nudge(step),
+            KeyCode::PageDown => monitor.nudge(-SLIDER_BIG_STEP),
+            KeyCode::PageUp => monitor.nudge(SLIDER_BIG_STEP),
+            KeyCode::Home => monitor.nudge(-(monitor.max as i64)),
+            KeyCode::End => monitor.nudge(monitor.max as i64),
+            KeyCode::Up | KeyCode::Down if key.kind == KeyEventKind::Press => {
+                write_slider(monitor, guardrails);
+                selected = if key.code == KeyCode::Up {
+                    selected.saturating_sub(1)
+                } else {
+                    (selected + 1).min(monitors.len() - 1)
+                };
+            }
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                write_slider(monitor, guardrails);
+                return Ok(());
+            }
+            _ => {}
+        }
+    }
 }
 
 fn action_ddc_read_custom_vcp(
@@ -4761,6 +5740,73 @@ fn action_ddc_reset_color(
     Ok(())
 }
 
+fn action_ddc_read_sharpness(
+    target: &Option<(usize, String)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    log_info(&format!("Target: {}", ddc_target_label(target)));
+
+    match ddc_get_vcp(target, lg_monitor::ddc::VCP_SHARPNESS) {
+        Ok(val) => log_ok(&format!(
+            "Sharpness: current={} max={}",
+            val.current, val.max
+        )),
+        Err(e) => log_note(&format!("Could not read sharpness: {}", e)),
+    }
+
+    log_done("Sharpness read complete.");
+    Ok(())
+}
+
+/// Parsed VCP entries from the target monitor's capabilities string.
+fn ddc_capability_entries(
+    target: &Option<(usize, String)>,
+) -> Result<Vec<lg_monitor::ddc::CapabilityVcpEntry>, Box<dyn std::error::Error>> {
+    let caps = match target {
+        Some((idx, _)) => lg_monitor::ddc::get_capabilities_by_index(*idx)?,
+        None => lg_monitor::ddc::get_capabilities_by_pattern(&Config::load().monitor_match)?,
+    };
+    Ok(lg_monitor::ddc::parse_capabilities_vcp(&caps))
+}
+
+/// Candidate response-time code and its advertised values, for display.
+fn ddc_response_time_candidate(
+    target: &Option<(usize, String)>,
+) -> Result<(u8, Vec<u8>), Box<dyn std::error::Error>> {
+    let entries = ddc_capability_entries(target)?;
+    let code = lg_monitor::ddc::response_time_candidate(&entries)
+        .ok_or("no response time candidate advertised in capabilities")?;
+    let values = entries
+        .into_iter()
+        .find(|e| e.code == code)
+        .map(|e| e.values)
+        .unwrap_or_default();
+    Ok((code, values))
+}
+
+fn action_ddc_read_response_time(
+    target: &Option<(usize, String)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    log_info(&format!("Target: {}", ddc_target_label(target)));
+
+    let (code, values) = ddc_response_time_candidate(target)?;
+    let advertised: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+    log_info(&format!(
+        "Response time candidate: VCP 0x{:02X} (values: {}, unverified)",
+        code,
+        advertised.join(", ")
+    ));
+    match ddc_get_vcp(target, code) {
+        Ok(val) => log_ok(&format!(
+            "Response Time: current={} max={}",
+            val.current, val.max
+        )),
+        Err(e) => log_note(&format!("Could not read response time: {}", e)),
+    }
+
+    log_done("Response time read complete.");
+    Ok(())
+}
+
 fn action_ddc_list_monitors() -> Result<(), Box<dyn std::error::Error>> {
     log_info("Enumerating physical monitors via DDC/CI...");
 
@@ -0,0 +1,132 @@
+//! Logging for foreground `watch`.
+//!
+//! The service logs to the Event Log; `watch` runs the same code in a
+//! console, where log records only reached the window and were lost with
+//! it. [`init`] sends every record to the console as before, appends it to
+//! `state\watch.log`, and with `--event-log` also writes it to the Event
+//! Log under the service's source. The file and Event Log get `info` and
+//! up (more with `-vv`/`-vvv`); the console keeps its usual level.
+
+use crate::output::{say, Verbosity};
+use chrono::Local;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Size at which `watch.log` is moved to `watch.log.old` on startup.
+const ROTATE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Event Log source, shared with the service.
+const EVENT_SOURCE: &str = "lg-ultragear-color-svc";
+
+struct TeeLogger {
+    console: env_logger::Logger,
+    file: Option<Mutex<File>>,
+    event_log: Option<winlog::WinLogger>,
+    /// Level for the file and Event Log.
+    level: LevelFilter,
+}
+
+impl Log for TeeLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level || self.console.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.console.matches(record) {
+            self.console.log(record);
+        }
+        if record.level() > self.level {
+            return;
+        }
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                let stamp = Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string();
+                let line = format_line(&stamp, record.level(), record.target(), record.args());
+                let _ = writeln!(file, "{}", line);
+            }
+        }
+        if let Some(event_log) = &self.event_log {
+            event_log.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+/// One `watch.log` line.
+fn format_line(stamp: &str, level: Level, target: &str, message: &dyn fmt::Display) -> String {
+    format!("{} {:<5} {}: {}", stamp, level, target, message)
+}
+
+/// Open `path` for appending, first moving a log past [`ROTATE_BYTES`] to
+/// `<name>.old` (replacing the previous one).
+fn open_log_file(path: &Path) -> std::io::Result<File> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    if fs::metadata(path).is_ok_and(|m| m.len() >= ROTATE_BYTES) {
+        let mut old = path.as_os_str().to_owned();
+        old.push(".old");
+        let _ = fs::rename(path, old);
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Install the `watch` logger. `log_file` is `None` with `--no-log-file`.
+/// A sink that can't be opened is reported and left out.
+pub(crate) fn init(verbosity: Verbosity, log_file: Option<&Path>, event_log: bool) {
+    let console_level = verbosity.level();
+    let level = verbosity.sink_level();
+    let console = env_logger::Builder::new()
+        .filter_level(console_level)
+        .format_timestamp(None)
+        .build();
+
+    let file = log_file.and_then(|path| match open_log_file(path) {
+        Ok(file) => {
+            say!("[WATCH] Logging to {}", path.display());
+            Some(Mutex::new(file))
+        }
+        Err(e) => {
+            say!("[WARN] Cannot open log file {}: {}", path.display(), e);
+            None
+        }
+    });
+    let event_log = event_log
+        .then(|| match winlog::WinLogger::try_new(EVENT_SOURCE) {
+            Ok(logger) => {
+                say!("[WATCH] Mirroring logs to the Event Log ({})", EVENT_SOURCE);
+                Some(logger)
+            }
+            Err(e) => {
+                say!("[WARN] Cannot open the Event Log: {}", e);
+                None
+            }
+        })
+        .flatten();
+
+    let logger = TeeLogger {
+        console,
+        file,
+        event_log,
+        level,
+    };
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(level.max(console_level));
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/watch_log_tests.rs"]
+mod tests;
@@ -302,6 +302,110 @@ fn config_help_shows_subcommands() {
     );
 }
 
+#[test]
+fn config_help_lists_named_config_subcommands() {
+    let (stdout, _, _) = run_binary(&["config", "--help"]);
+    assert!(
+        stdout.contains("list"),
+        "config --help should list list: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("use"),
+        "config --help should list use: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("--config-name"),
+        "config --help should show --config-name: {}",
+        stdout
+    );
+}
+
+#[test]
+fn config_validate_reports_line_anchored_errors() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.toml");
+    std::fs::write(&path, "verbose = true\nstabilise_delay_ms = 100\n").unwrap();
+    let (stdout, stderr, success) = run_binary(&["config", "validate", path.to_str().unwrap()]);
+    assert!(!success, "config validate should fail on an unknown key");
+    assert!(
+        stdout.contains("config.toml:2: error:") && stdout.contains("stabilize_delay_ms"),
+        "config validate should name the line and suggest the key: {}",
+        stdout
+    );
+    assert!(
+        stderr.contains("1 error(s)"),
+        "config validate should summarize the errors: {}",
+        stderr
+    );
+}
+
+#[test]
+fn config_validate_accepts_clean_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.toml");
+    std::fs::write(&path, "monitor_match = \"LG ULTRAGEAR\"\n").unwrap();
+    let (stdout, _, success) = run_binary(&["config", "validate", path.to_str().unwrap()]);
+    assert!(success, "config validate should pass: {}", stdout);
+    assert!(stdout.contains("[OK]"), "expected [OK]: {}", stdout);
+}
+
+#[test]
+fn quick_help_lists_button_commands() {
+    let (stdout, _, _) = run_binary(&["quick", "--help"]);
+    for cmd in ["reapply", "brightness", "preset"] {
+        assert!(
+            stdout.contains(cmd),
+            "quick --help should list {}: {}",
+            cmd,
+            stdout
+        );
+    }
+}
+
+#[test]
+fn dry_run_quick_brightness_accepts_negative_step() {
+    let (stdout, stderr, ok) = run_binary(&["--dry-run", "quick", "brightness", "-10"]);
+    assert!(ok, "quick brightness -10 should parse. stderr: {}", stderr);
+    assert!(
+        stdout.contains("brightness -10"),
+        "dry run should show the request: {}",
+        stdout
+    );
+}
+
+#[test]
+fn timing_flag_reports_phases_on_stderr() {
+    let (stdout, stderr, ok) = run_binary(&["--timing", "config", "path"]);
+    assert!(
+        ok,
+        "config path --timing should succeed. stderr: {}",
+        stderr
+    );
+    assert!(!stdout.contains("[TIMING]"), "timing goes to stderr");
+    assert!(stderr.contains("[TIMING] parse args"), "stderr: {}", stderr);
+    assert!(stderr.contains("[TIMING] total"), "stderr: {}", stderr);
+}
+
+#[test]
+fn no_timing_output_without_flag() {
+    let (_, stderr, ok) = run_binary(&["config", "path"]);
+    assert!(ok);
+    assert!(!stderr.contains("[TIMING]"), "stderr: {}", stderr);
+}
+
+#[test]
+fn invalid_config_name_is_rejected() {
+    let (_, stderr, ok) = run_binary(&["--config-name", "../evil", "config", "path"]);
+    assert!(!ok);
+    assert!(
+        stderr.contains("may only contain"),
+        "invalid --config-name should explain the rule: {}",
+        stderr
+    );
+}
+
 #[test]
 fn service_help_shows_subcommands() {
     let (stdout, _, _) = run_binary(&["service", "--help"]);
@@ -413,6 +517,56 @@ fn ddc_help_lists_map_subcommand() {
     );
 }
 
+#[test]
+fn ddc_help_lists_picture_subcommands() {
+    let (stdout, stderr, success) = run_binary(&["ddc", "--help"]);
+    assert!(success, "ddc --help should succeed. stderr: {}", stderr);
+    let lower = stdout.to_lowercase();
+    for sub in [
+        "sharpness",
+        "set-sharpness",
+        "response-time",
+        "capabilities",
+        "watch",
+    ] {
+        assert!(
+            lower.contains(sub),
+            "ddc --help should list {}: {}",
+            sub,
+            stdout
+        );
+    }
+}
+
+#[test]
+fn ddc_set_response_time_requires_code() {
+    let (_stdout, stderr, success) = run_binary(&["ddc", "set-response-time", "2"]);
+    assert!(!success, "set-response-time without --code should fail");
+    assert!(
+        stderr.contains("--code"),
+        "error should name the missing --code: {}",
+        stderr
+    );
+}
+
+#[test]
+fn ddc_watch_help_shows_polling_options() {
+    let (stdout, stderr, success) = run_binary(&["ddc", "watch", "--help"]);
+    assert!(
+        success,
+        "ddc watch --help should succeed. stderr: {}",
+        stderr
+    );
+    let lower = stdout.to_lowercase();
+    assert!(lower.contains("--codes"), "missing --codes: {}", stdout);
+    assert!(
+        lower.contains("--interval-ms"),
+        "missing --interval-ms: {}",
+        stdout
+    );
+    assert!(lower.contains("--samples"), "missing --samples: {}", stdout);
+}
+
 #[test]
 fn automation_help_lists_subcommands() {
     let (stdout, stderr, success) = run_binary(&["automation", "--help"]);
@@ -593,6 +747,94 @@ fn verbose_long_flag_accepted() {
     assert!(success, "detect with --verbose should succeed");
 }
 
+#[test]
+fn stacked_verbose_flags_accepted() {
+    let (_, _, success) = run_binary(&["-vvv", "detect"]);
+    assert!(success, "detect with -vvv should succeed");
+}
+
+#[test]
+fn quiet_detect_prints_nothing() {
+    let (stdout, stderr, success) = run_binary(&["--quiet", "detect"]);
+    assert!(
+        success,
+        "detect with --quiet should succeed. stderr: {}",
+        stderr
+    );
+    assert!(stdout.trim().is_empty(), "quiet detect printed: {}", stdout);
+}
+
+#[test]
+fn quiet_conflicts_with_verbose_and_format() {
+    let (_, _, success) = run_binary(&["-q", "-v", "detect"]);
+    assert!(!success, "--quiet with --verbose should be rejected");
+    let (_, _, success) = run_binary(&["-q", "--format", "json", "detect"]);
+    assert!(!success, "--quiet with --format should be rejected");
+}
+
+#[test]
+fn json_format_prints_one_object_per_line() {
+    let (stdout, stderr, success) = run_binary(&["--format", "json", "detect"]);
+    assert!(
+        success,
+        "detect with --format json should succeed. stderr: {}",
+        stderr
+    );
+    for line in stdout.lines() {
+        let value: serde_json::Value =
+            serde_json::from_str(line).unwrap_or_else(|e| panic!("not JSON ({}): {}", e, line));
+        assert!(
+            value["kind"].is_string() && value["text"].is_string(),
+            "{}",
+            line
+        );
+    }
+}
+
+#[test]
+fn dry_run_json_plan_is_printed_as_is() {
+    let (stdout, stderr, success) = run_binary(&["--dry-run", "uninstall", "--json"]);
+    assert!(
+        success,
+        "uninstall --dry-run --json should succeed. stderr: {}",
+        stderr
+    );
+    let plan: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("plan is not JSON ({}): {}", e, stdout));
+    assert_eq!(plan["command"], "uninstall");
+}
+
+#[test]
+fn json_format_prints_the_dry_run_plan_on_one_line() {
+    for args in [
+        &["--format", "json", "--dry-run", "uninstall"][..],
+        &["--format", "json", "--dry-run", "uninstall", "--json"][..],
+    ] {
+        let (stdout, stderr, success) = run_binary(args);
+        assert!(success, "{:?} should succeed. stderr: {}", args, stderr);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 1, "{:?} printed: {}", args, stdout);
+        let plan: serde_json::Value = serde_json::from_str(lines[0])
+            .unwrap_or_else(|e| panic!("plan is not JSON ({}): {}", e, lines[0]));
+        assert_eq!(plan["command"], "uninstall", "{}", lines[0]);
+    }
+}
+
+#[test]
+fn quiet_rejects_a_json_plan() {
+    let (stdout, stderr, success) = run_binary(&["--quiet", "--dry-run", "uninstall", "--json"]);
+    assert!(!success, "--quiet with --json should be rejected");
+    assert!(stdout.trim().is_empty(), "{}", stdout);
+    assert!(stderr.contains("--quiet"), "{}", stderr);
+}
+
+#[test]
+fn unknown_format_is_rejected() {
+    let (_, stderr, success) = run_binary(&["--format", "yaml", "detect"]);
+    assert!(!success);
+    assert!(stderr.contains("expected human or json"), "{}", stderr);
+}
+
 // ============================================================================
 // Detect command tests
 // ============================================================================
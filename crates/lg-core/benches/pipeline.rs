@@ -0,0 +1,190 @@
+//! Reapply pipeline benchmarks against an in-memory backend.
+//!
+//! `overhead` measures the sequence itself with steps that return at once.
+//! `simulated` gives each platform call a fixed latency in the range the
+//! real ones take, so changes to how monitors are worked through (say,
+//! reapplying them in parallel, or caching the WMI scan) show up as
+//! numbers. Run with `cargo bench -p lg-core`; `lg-ultragear-dimming-fix
+//! bench` measures the real calls on a live system.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use lg_core::bench::{PhaseTimings, TimedSteps};
+use lg_core::pipeline::{ModeProfiles, ReapplyOptions, ReapplyPipeline, ReapplySteps};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+/// Simulated cost of each platform call; zero for `overhead`.
+#[derive(Clone, Copy, Default)]
+struct Latency {
+    find_monitors: Duration,
+    ensure_profiles: Duration,
+    associate: Duration,
+    refresh: Duration,
+}
+
+impl Latency {
+    /// Rough shape of a warm reapply: a WMI query, a profile check, the
+    /// association toggle (which includes `toggle_delay_ms`) and the
+    /// refresh broadcast.
+    fn realistic() -> Self {
+        Self {
+            find_monitors: Duration::from_millis(8),
+            ensure_profiles: Duration::from_millis(1),
+            associate: Duration::from_millis(5),
+            refresh: Duration::from_millis(2),
+        }
+    }
+}
+
+fn wait(latency: Duration) {
+    if !latency.is_zero() {
+        thread::sleep(latency);
+    }
+}
+
+struct MockSteps {
+    monitors: usize,
+    per_monitor_profiles: bool,
+    latency: Latency,
+}
+
+impl ReapplySteps for MockSteps {
+    type Monitor = String;
+
+    fn ensure_shared_profiles(&mut self) -> Result<Option<ModeProfiles>, Box<dyn Error>> {
+        if self.per_monitor_profiles {
+            return Ok(None);
+        }
+        wait(self.latency.ensure_profiles);
+        Ok(Some(ModeProfiles {
+            sdr: PathBuf::from("lg-ultragear-full-cal.icm"),
+            hdr: PathBuf::from("lg-ultragear-full-cal-hdr.icm"),
+        }))
+    }
+
+    fn is_installed(&self, _path: &Path) -> bool {
+        true
+    }
+
+    fn find_monitors(&mut self) -> Result<Vec<String>, Box<dyn Error>> {
+        wait(self.latency.find_monitors);
+        Ok((0..self.monitors)
+            .map(|i| format!(r"DISPLAY\GSM5BBF\5&bench&0&UID{}", 4352 + i))
+            .collect())
+    }
+
+    fn ensure_monitor_profiles(
+        &mut self,
+        monitor: &String,
+    ) -> Result<ModeProfiles, Box<dyn Error>> {
+        wait(self.latency.ensure_profiles);
+        Ok(ModeProfiles::single(PathBuf::from(format!(
+            "lg-ultragear-{}.icm",
+            monitor.rsplit('&').next().unwrap_or_default()
+        ))))
+    }
+
+    fn associate(
+        &mut self,
+        _monitor: &String,
+        _active: &Path,
+        _sdr: &Path,
+        _hdr: Option<&Path>,
+        _per_user: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        wait(self.latency.associate);
+        Ok(())
+    }
+
+    fn set_generic_default(
+        &mut self,
+        _monitor: &String,
+        _active: &Path,
+        _per_user: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn refresh(&mut self) {
+        wait(self.latency.refresh);
+    }
+
+    fn set_ddc_brightness(&mut self, _level: u32) -> Result<usize, Box<dyn Error>> {
+        Ok(self.monitors)
+    }
+
+    fn toast(&mut self) {}
+}
+
+fn pipeline() -> ReapplyPipeline {
+    ReapplyPipeline::new(ReapplyOptions {
+        hdr: true,
+        generic_default: true,
+        ddc_brightness: Some(50),
+        toast: true,
+        ..ReapplyOptions::default()
+    })
+}
+
+fn overhead(c: &mut Criterion) {
+    let pipeline = pipeline();
+    let mut group = c.benchmark_group("overhead");
+    for monitors in [1, 2, 4, 8] {
+        for (label, per_monitor_profiles) in [("shared", false), ("per_monitor", true)] {
+            group.bench_with_input(BenchmarkId::new(label, monitors), &monitors, |b, &n| {
+                b.iter(|| {
+                    let mut steps = MockSteps {
+                        monitors: n,
+                        per_monitor_profiles,
+                        latency: Latency::default(),
+                    };
+                    black_box(pipeline.run(&mut steps).expect("reapply"))
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn timed_overhead(c: &mut Criterion) {
+    let pipeline = pipeline();
+    c.bench_function("overhead/timed_steps/4", |b| {
+        b.iter(|| {
+            let mut timings = PhaseTimings::new();
+            let mut steps = MockSteps {
+                monitors: 4,
+                per_monitor_profiles: false,
+                latency: Latency::default(),
+            };
+            black_box(
+                pipeline
+                    .run(&mut TimedSteps::new(&mut steps, &mut timings))
+                    .expect("reapply"),
+            )
+        })
+    });
+}
+
+fn simulated(c: &mut Criterion) {
+    let pipeline = pipeline();
+    let mut group = c.benchmark_group("simulated");
+    group.sample_size(10);
+    for monitors in [1, 2, 4] {
+        group.bench_with_input(BenchmarkId::from_parameter(monitors), &monitors, |b, &n| {
+            b.iter(|| {
+                let mut steps = MockSteps {
+                    monitors: n,
+                    per_monitor_profiles: false,
+                    latency: Latency::realistic(),
+                };
+                black_box(pipeline.run(&mut steps).expect("reapply"))
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, overhead, timed_overhead, simulated);
+criterion_main!(benches);
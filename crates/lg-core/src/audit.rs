@@ -0,0 +1,208 @@
+//! Append-only audit log of every system change.
+//!
+//! Each mutating call — profile file and registry writes, WCS association
+//! and default changes, service operations — appends one JSON line
+//! to `audit\audit.log` in the config directory: what was changed, by which
+//! process and account, the value before and after where it can be read,
+//! and whether it worked. Lines are never rewritten. Once the file passes
+//! [`ROTATE_BYTES`] it is renamed to `audit-<timestamp>.log` and a new one
+//! is started; the tool never deletes rotated files. `audit show` prints
+//! the most recent entries.
+
+use crate::config;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Size at which `audit.log` is rotated.
+pub const ROTATE_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Process role recorded with each entry (`cli` unless set).
+static SOURCE: OnceLock<&'static str> = OnceLock::new();
+
+pub fn audit_dir() -> PathBuf {
+    config::config_dir().join("audit")
+}
+
+/// `audit.log` in [`audit_dir`].
+///
+/// Test builds — this crate's, or any crate that enables the
+/// `test-audit-log` feature from its dev-dependencies — write to a
+/// per-process file in the temp directory instead of the real log.
+pub fn audit_log_path() -> PathBuf {
+    if cfg!(any(test, feature = "test-audit-log")) {
+        return std::env::temp_dir()
+            .join(format!("lg-test-audit-{}", std::process::id()))
+            .join("audit.log");
+    }
+    audit_dir().join("audit.log")
+}
+
+/// Set the role recorded with this process's entries (e.g. `service`).
+pub fn set_source(source: &'static str) {
+    let _ = SOURCE.set(source);
+}
+
+/// One recorded change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: String,
+    /// `cli` or `service`.
+    pub source: String,
+    pub pid: u32,
+    pub user: String,
+    /// `file`, `registry`, `wcs` or `service`.
+    pub category: String,
+    pub action: String,
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(category: &str, action: &str, target: impl Into<String>) -> Self {
+        Self {
+            timestamp: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            source: SOURCE.get().copied().unwrap_or("cli").to_string(),
+            pid: std::process::id(),
+            user: std::env::var("USERNAME").unwrap_or_default(),
+            category: category.to_string(),
+            action: action.to_string(),
+            target: target.into(),
+            before: None,
+            after: None,
+            ok: true,
+            error: None,
+        }
+    }
+
+    pub fn before(mut self, value: impl Into<String>) -> Self {
+        self.before = Some(value.into());
+        self
+    }
+
+    pub fn after(mut self, value: impl Into<String>) -> Self {
+        self.after = Some(value.into());
+        self
+    }
+
+    /// Take the outcome from `result`.
+    pub fn result<T, E: fmt::Display>(mut self, result: &Result<T, E>) -> Self {
+        if let Err(e) = result {
+            self.ok = false;
+            self.error = Some(e.to_string());
+        }
+        self
+    }
+
+    /// Append to the audit log. Failures to write are ignored; auditing
+    /// never blocks the change itself.
+    pub fn record(self) {
+        let _ = append_to(&audit_log_path(), &self, ROTATE_BYTES);
+    }
+}
+
+impl fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:<7} {} {}/{} {}",
+            self.timestamp,
+            self.source,
+            if self.ok { "OK  " } else { "FAIL" },
+            self.category,
+            self.action,
+            self.target
+        )?;
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => write!(f, " [{} -> {}]", before, after)?,
+            (Some(before), None) => write!(f, " [was {}]", before)?,
+            (None, Some(after)) => write!(f, " [-> {}]", after)?,
+            (None, None) => {}
+        }
+        if let Some(error) = &self.error {
+            write!(f, " ({})", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// `absent`, or the size and content hash of the file at `path`, for the
+/// before/after values of file writes.
+pub fn file_state(path: &Path) -> String {
+    match fs::read(path) {
+        Ok(bytes) => format!("{} bytes fnv1a:{:016x}", bytes.len(), fnv1a(&bytes)),
+        Err(_) => "absent".to_string(),
+    }
+}
+
+/// Run a write or removal of `path` and record it with [`file_state`]
+/// before and after.
+pub fn file_change<T>(
+    action: &str,
+    path: &Path,
+    change: impl FnOnce() -> std::io::Result<T>,
+) -> std::io::Result<T> {
+    let before = file_state(path);
+    let result = change();
+    AuditEntry::new("file", action, path.display().to_string())
+        .before(before)
+        .after(file_state(path))
+        .result(&result)
+        .record();
+    result
+}
+
+/// FNV-1a 64: stable across builds, unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn append_to(path: &Path, entry: &AuditEntry, rotate_bytes: u64) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    if fs::metadata(path).is_ok_and(|m| m.len() >= rotate_bytes) {
+        let stamp = Utc::now().format("%Y%m%dT%H%M%S%.3f");
+        let _ = fs::rename(path, path.with_file_name(format!("audit-{}.log", stamp)));
+    }
+    let line = serde_json::to_string(entry)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+fn read_recent_from(path: &Path, limit: usize) -> std::io::Result<Vec<AuditEntry>> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut entries: Vec<AuditEntry> = BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect();
+    entries.reverse();
+    entries.truncate(limit);
+    Ok(entries)
+}
+
+/// The newest `limit` entries of the current audit log, newest first.
+pub fn read_recent_entries(limit: usize) -> std::io::Result<Vec<AuditEntry>> {
+    read_recent_from(&audit_log_path(), limit)
+}
+
+#[cfg(test)]
+#[path = "tests/audit_tests.rs"]
+mod tests;
@@ -0,0 +1,223 @@
+//! Per-phase timings of a reapply, for the `bench` command.
+//!
+//! [`TimedSteps`] wraps a [`ReapplySteps`] implementation and records how
+//! long each step takes in [`PhaseTimings`], which summarizes the samples
+//! of each phase. The hidden `bench` command runs real reapplies through
+//! it; the criterion benches in `benches/pipeline.rs` drive the pipeline
+//! with an in-memory backend instead, so they measure the sequence itself.
+
+use crate::pipeline::{ModeProfiles, MonitorResult, ReapplySteps};
+use std::error::Error;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+pub const PHASE_SHARED_PROFILES: &str = "shared profiles";
+pub const PHASE_FIND_MONITORS: &str = "find monitors";
+pub const PHASE_INHERIT: &str = "inherit associations";
+pub const PHASE_MONITOR_PROFILES: &str = "monitor profiles";
+pub const PHASE_ASSOCIATE: &str = "associate";
+pub const PHASE_GENERIC_DEFAULT: &str = "generic default";
+pub const PHASE_REFRESH: &str = "refresh";
+pub const PHASE_DDC: &str = "ddc brightness";
+pub const PHASE_TOAST: &str = "toast";
+/// Whole reapply, recorded by the caller around [`ReapplyPipeline::run`](crate::pipeline::ReapplyPipeline::run).
+pub const PHASE_TOTAL: &str = "total";
+
+/// Samples per phase, in the order phases were first seen.
+#[derive(Debug, Clone, Default)]
+pub struct PhaseTimings {
+    phases: Vec<(&'static str, Vec<Duration>)>,
+}
+
+/// Spread of one phase's samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseSummary {
+    pub phase: &'static str,
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+impl PhaseTimings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, phase: &'static str, elapsed: Duration) {
+        match self.phases.iter_mut().find(|(name, _)| *name == phase) {
+            Some((_, samples)) => samples.push(elapsed),
+            None => self.phases.push((phase, vec![elapsed])),
+        }
+    }
+
+    /// Run `f` and record its wall-clock time under `phase`.
+    pub fn time<T>(&mut self, phase: &'static str, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = f();
+        self.record(phase, started.elapsed());
+        result
+    }
+
+    /// One summary per phase. Percentiles are nearest-rank, so every
+    /// figure is an actual sample.
+    pub fn summaries(&self) -> Vec<PhaseSummary> {
+        self.phases
+            .iter()
+            .map(|(phase, samples)| {
+                let mut sorted = samples.clone();
+                sorted.sort();
+                PhaseSummary {
+                    phase,
+                    samples: sorted.len(),
+                    min: sorted[0],
+                    median: nearest_rank(&sorted, 50),
+                    p95: nearest_rank(&sorted, 95),
+                    max: sorted[sorted.len() - 1],
+                }
+            })
+            .collect()
+    }
+
+    /// The summaries as a table in milliseconds.
+    pub fn format_table(&self) -> String {
+        let summaries = self.summaries();
+        let width = summaries
+            .iter()
+            .map(|s| s.phase.len())
+            .chain(std::iter::once("phase".len()))
+            .max()
+            .unwrap_or(0);
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let mut out = format!(
+            "{:<width$}  {:>5}  {:>9}  {:>9}  {:>9}  {:>9}\n",
+            "phase",
+            "n",
+            "min ms",
+            "median ms",
+            "p95 ms",
+            "max ms",
+            width = width
+        );
+        for s in &summaries {
+            out.push_str(&format!(
+                "{:<width$}  {:>5}  {:>9.2}  {:>9.2}  {:>9.2}  {:>9.2}\n",
+                s.phase,
+                s.samples,
+                ms(s.min),
+                ms(s.median),
+                ms(s.p95),
+                ms(s.max),
+                width = width
+            ));
+        }
+        out
+    }
+}
+
+/// `percent`th percentile of `sorted` (non-empty, ascending).
+fn nearest_rank(sorted: &[Duration], percent: usize) -> Duration {
+    let rank = (percent * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Times every step of the wrapped implementation. Per-monitor phases get
+/// one sample per monitor. `is_installed` is a plain file check and isn't
+/// timed.
+pub struct TimedSteps<'a, S> {
+    inner: &'a mut S,
+    timings: &'a mut PhaseTimings,
+}
+
+impl<'a, S: ReapplySteps> TimedSteps<'a, S> {
+    pub fn new(inner: &'a mut S, timings: &'a mut PhaseTimings) -> Self {
+        Self { inner, timings }
+    }
+}
+
+impl<S: ReapplySteps> ReapplySteps for TimedSteps<'_, S> {
+    type Monitor = S::Monitor;
+
+    fn ensure_shared_profiles(&mut self) -> Result<Option<ModeProfiles>, Box<dyn Error>> {
+        let inner = &mut *self.inner;
+        self.timings
+            .time(PHASE_SHARED_PROFILES, || inner.ensure_shared_profiles())
+    }
+
+    fn is_installed(&self, path: &Path) -> bool {
+        self.inner.is_installed(path)
+    }
+
+    fn find_monitors(&mut self) -> Result<Vec<Self::Monitor>, Box<dyn Error>> {
+        let inner = &mut *self.inner;
+        self.timings
+            .time(PHASE_FIND_MONITORS, || inner.find_monitors())
+    }
+
+    fn inherit_associations(&mut self, monitor: &Self::Monitor) {
+        let inner = &mut *self.inner;
+        self.timings
+            .time(PHASE_INHERIT, || inner.inherit_associations(monitor))
+    }
+
+    fn ensure_monitor_profiles(
+        &mut self,
+        monitor: &Self::Monitor,
+    ) -> Result<ModeProfiles, Box<dyn Error>> {
+        let inner = &mut *self.inner;
+        self.timings.time(PHASE_MONITOR_PROFILES, || {
+            inner.ensure_monitor_profiles(monitor)
+        })
+    }
+
+    fn associate(
+        &mut self,
+        monitor: &Self::Monitor,
+        active: &Path,
+        sdr: &Path,
+        hdr: Option<&Path>,
+        per_user: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let inner = &mut *self.inner;
+        self.timings.time(PHASE_ASSOCIATE, || {
+            inner.associate(monitor, active, sdr, hdr, per_user)
+        })
+    }
+
+    fn set_generic_default(
+        &mut self,
+        monitor: &Self::Monitor,
+        active: &Path,
+        per_user: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let inner = &mut *self.inner;
+        self.timings.time(PHASE_GENERIC_DEFAULT, || {
+            inner.set_generic_default(monitor, active, per_user)
+        })
+    }
+
+    fn refresh(&mut self) {
+        let inner = &mut *self.inner;
+        self.timings.time(PHASE_REFRESH, || inner.refresh())
+    }
+
+    fn set_ddc_brightness(&mut self, level: u32) -> Result<usize, Box<dyn Error>> {
+        let inner = &mut *self.inner;
+        self.timings
+            .time(PHASE_DDC, || inner.set_ddc_brightness(level))
+    }
+
+    fn toast(&mut self) {
+        let inner = &mut *self.inner;
+        self.timings.time(PHASE_TOAST, || inner.toast())
+    }
+
+    fn monitor_done(&mut self, monitor: &Self::Monitor, result: &MonitorResult<'_>) {
+        self.inner.monitor_done(monitor, result)
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/bench_tests.rs"]
+mod tests;
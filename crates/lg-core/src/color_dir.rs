@@ -0,0 +1,158 @@
+//! Where the Windows color store lives.
+//!
+//! Profiles are installed into the directory `GetColorDirectoryW` reports,
+//! which follows a spool directory moved off `%WINDIR%` (some imaged and
+//! server machines do that). The answer is looked up once per process; the
+//! usual `%WINDIR%\System32\spool\drivers\color` is only a fallback for when
+//! the call fails. The `color_directory` config key replaces the lookup
+//! with a directory of the user's choosing (for example a per-user store),
+//! with `%VAR%` references expanded. [`Config::load`](crate::config::Config::load)
+//! records that setting here so code without a config at hand, like the
+//! profile installer, sees the same directory.
+//!
+//! `profile install --scope user` needs a store the user can write to: the
+//! configured directory, or [`default_user_directory`] when none is set.
+
+use crate::colorstore::Scope;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+/// Directory reported by the system, looked up on first use.
+static DISCOVERED: OnceLock<PathBuf> = OnceLock::new();
+
+/// `color_directory` from the most recently loaded config.
+static CONFIGURED: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// The standard location under `%WINDIR%`.
+pub fn default_directory() -> PathBuf {
+    let windir = std::env::var("WINDIR").unwrap_or_else(|_| r"C:\Windows".to_string());
+    PathBuf::from(windir)
+        .join("System32")
+        .join("spool")
+        .join("drivers")
+        .join("color")
+}
+
+/// Ask the system for its color directory.
+#[cfg(windows)]
+fn discover() -> Option<PathBuf> {
+    use windows::core::{PCWSTR, PWSTR};
+    use windows::Win32::UI::ColorSystem::GetColorDirectoryW;
+
+    let mut buffer = [0u16; 260];
+    let mut size = (buffer.len() * 2) as u32;
+    let ok = unsafe {
+        GetColorDirectoryW(PCWSTR::null(), PWSTR(buffer.as_mut_ptr()), &mut size).as_bool()
+    };
+    if !ok {
+        log::warn!(
+            "GetColorDirectoryW failed: {}",
+            std::io::Error::last_os_error()
+        );
+        return None;
+    }
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    (len > 0).then(|| PathBuf::from(String::from_utf16_lossy(&buffer[..len])))
+}
+
+#[cfg(not(windows))]
+fn discover() -> Option<PathBuf> {
+    None
+}
+
+/// Per-user store used when no `color_directory` is configured.
+pub fn default_user_directory() -> PathBuf {
+    let local = std::env::var("LOCALAPPDATA").unwrap_or_else(|_| {
+        let profile =
+            std::env::var("USERPROFILE").unwrap_or_else(|_| r"C:\Users\Default".to_string());
+        format!(r"{}\AppData\Local", profile)
+    });
+    PathBuf::from(local)
+        .join("LG-UltraGear-Monitor")
+        .join("color")
+}
+
+/// The system color directory, falling back to [`default_directory`].
+pub fn system_directory() -> PathBuf {
+    DISCOVERED
+        .get_or_init(|| discover().unwrap_or_else(default_directory))
+        .clone()
+}
+
+/// Expand `%VAR%` references with `lookup`. Unknown variables and a lone
+/// `%` are kept as written.
+pub fn expand_env(value: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('%') {
+            Some(end) if end > 0 => {
+                let name = &after[..end];
+                match lookup(name) {
+                    Some(expanded) => out.push_str(&expanded),
+                    None => {
+                        out.push('%');
+                        out.push_str(name);
+                        out.push('%');
+                    }
+                }
+                rest = &after[end + 1..];
+            }
+            _ => {
+                out.push('%');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The color directory for a `color_directory` setting: the setting with
+/// `%VAR%` expanded, or the system directory when it is empty.
+pub fn resolve(configured: &str) -> PathBuf {
+    let configured = configured.trim();
+    if configured.is_empty() {
+        return system_directory();
+    }
+    PathBuf::from(expand_env(configured, |name| std::env::var(name).ok()))
+}
+
+/// Record the loaded config's `color_directory` for [`directory`].
+pub fn set_configured(configured: &str) {
+    let configured = configured.trim();
+    let value = (!configured.is_empty()).then(|| resolve(configured));
+    *CONFIGURED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = value;
+}
+
+/// The color directory in effect: the configured one if any, otherwise the
+/// system's.
+pub fn directory() -> PathBuf {
+    CONFIGURED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+        .unwrap_or_else(system_directory)
+}
+
+/// Store a profile installed for `scope` goes to: the system directory for
+/// [`Scope::SystemWide`]; the configured directory, or
+/// [`default_user_directory`], for [`Scope::CurrentUser`].
+pub fn directory_for(scope: Scope) -> PathBuf {
+    match scope {
+        Scope::SystemWide => system_directory(),
+        Scope::CurrentUser => CONFIGURED
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+            .unwrap_or_else(default_user_directory),
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/color_dir_tests.rs"]
+mod tests;
@@ -0,0 +1,293 @@
+//! Backup and restore of the Windows color store state.
+//!
+//! `colorstore backup <file>` captures every display's profile associations
+//! and defaults, system-wide and per-user, as JSON. `colorstore restore
+//! <file>` compares that against the live state with [`restore_plan`] and
+//! puts back exactly what was recorded. The profile files themselves are
+//! not part of the backup; a restored association whose file has since
+//! been removed fails and is reported.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Bumped when a field changes meaning or is removed (additions are fine).
+pub const BACKUP_SCHEMA_VERSION: u32 = 1;
+
+/// WCS profile management scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    SystemWide,
+    CurrentUser,
+}
+
+impl Scope {
+    /// `system` or `user`, as [`label`](Self::label) prints them.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "system" => Some(Scope::SystemWide),
+            "user" => Some(Scope::CurrentUser),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Scope::SystemWide => "system",
+            Scope::CurrentUser => "user",
+        }
+    }
+}
+
+/// Which default a [`RestoreChange::SetDefault`] restores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefaultKind {
+    /// Legacy `Wcs*` device default.
+    Device,
+    /// `ColorProfile*` SDR display default.
+    Sdr,
+    /// `ColorProfile*` advanced-color (HDR) display default.
+    Hdr,
+}
+
+impl DefaultKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            DefaultKind::Device => "default",
+            DefaultKind::Sdr => "SDR default",
+            DefaultKind::Hdr => "HDR default",
+        }
+    }
+}
+
+/// Associations and defaults of one display in one scope.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScopeColorState {
+    /// Associated profile file names, in registry order.
+    pub profiles: Vec<String>,
+    /// Legacy `Wcs*` default profile.
+    pub default: Option<String>,
+    /// SDR display default (active displays on Windows 10 1703+ only).
+    pub sdr_default: Option<String>,
+    /// HDR display default (active displays on Windows 10 1703+ only).
+    pub hdr_default: Option<String>,
+}
+
+impl ScopeColorState {
+    fn default_of(&self, kind: DefaultKind) -> &Option<String> {
+        match kind {
+            DefaultKind::Device => &self.default,
+            DefaultKind::Sdr => &self.sdr_default,
+            DefaultKind::Hdr => &self.hdr_default,
+        }
+    }
+}
+
+/// Color store state of one display device.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceColorState {
+    /// `DISPLAY\<model>\<instance>`.
+    pub device_key: String,
+    pub use_per_user_profiles: bool,
+    pub system_wide: ScopeColorState,
+    pub current_user: ScopeColorState,
+}
+
+impl DeviceColorState {
+    pub fn scope(&self, scope: Scope) -> &ScopeColorState {
+        match scope {
+            Scope::SystemWide => &self.system_wide,
+            Scope::CurrentUser => &self.current_user,
+        }
+    }
+}
+
+/// A full color store snapshot, as written by `colorstore backup`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColorStoreBackup {
+    pub schema_version: u32,
+    pub created_at: String,
+    pub tool_version: String,
+    pub devices: Vec<DeviceColorState>,
+}
+
+impl ColorStoreBackup {
+    pub fn new(devices: Vec<DeviceColorState>) -> Self {
+        Self {
+            schema_version: BACKUP_SCHEMA_VERSION,
+            created_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            devices,
+        }
+    }
+
+    pub fn device(&self, device_key: &str) -> Option<&DeviceColorState> {
+        self.devices
+            .iter()
+            .find(|d| d.device_key.eq_ignore_ascii_case(device_key))
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Parse a backup, refusing one written by a newer schema.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let backup: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if backup.schema_version > BACKUP_SCHEMA_VERSION {
+            return Err(format!(
+                "backup schema {} is newer than this build understands ({})",
+                backup.schema_version, BACKUP_SCHEMA_VERSION
+            ));
+        }
+        Ok(backup)
+    }
+}
+
+/// One step of putting a backup back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestoreChange {
+    /// Replace a device's association list in `scope`.
+    SetProfiles {
+        device_key: String,
+        scope: Scope,
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+    /// Turn per-user profiles on or off for a device.
+    SetUsePerUser { device_key: String, enabled: bool },
+    /// Make `profile` a device default again.
+    SetDefault {
+        device_key: String,
+        scope: Scope,
+        kind: DefaultKind,
+        profile: String,
+    },
+}
+
+impl fmt::Display for RestoreChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |profiles: &[String]| {
+            if profiles.is_empty() {
+                "(none)".to_string()
+            } else {
+                profiles.join(", ")
+            }
+        };
+        match self {
+            RestoreChange::SetProfiles {
+                device_key,
+                scope,
+                from,
+                to,
+            } => write!(
+                f,
+                "{} {} associations: {} -> {}",
+                device_key,
+                scope.label(),
+                list(from),
+                list(to)
+            ),
+            RestoreChange::SetUsePerUser {
+                device_key,
+                enabled,
+            } => write!(
+                f,
+                "{} per-user profiles {}",
+                device_key,
+                if *enabled { "on" } else { "off" }
+            ),
+            RestoreChange::SetDefault {
+                device_key,
+                scope,
+                kind,
+                profile,
+            } => write!(
+                f,
+                "{} {} {} -> {}",
+                device_key,
+                scope.label(),
+                kind.label(),
+                profile
+            ),
+        }
+    }
+}
+
+/// What it takes to turn `current` into `backup`: association lists first,
+/// then the per-user switch, then defaults. Devices missing from the backup
+/// lose their associations; a default the backup doesn't record is left to
+/// Windows.
+pub fn restore_plan(current: &ColorStoreBackup, backup: &ColorStoreBackup) -> Vec<RestoreChange> {
+    const SCOPES: [Scope; 2] = [Scope::SystemWide, Scope::CurrentUser];
+    let empty = DeviceColorState::default();
+    let mut lists = Vec::new();
+    let mut switches = Vec::new();
+    let mut defaults = Vec::new();
+
+    for wanted in &backup.devices {
+        let live = current.device(&wanted.device_key).unwrap_or(&empty);
+        for scope in SCOPES {
+            let (from, to) = (&live.scope(scope).profiles, &wanted.scope(scope).profiles);
+            if from != to {
+                lists.push(RestoreChange::SetProfiles {
+                    device_key: wanted.device_key.clone(),
+                    scope,
+                    from: from.clone(),
+                    to: to.clone(),
+                });
+            }
+            for kind in [DefaultKind::Device, DefaultKind::Sdr, DefaultKind::Hdr] {
+                let target = wanted.scope(scope).default_of(kind);
+                let Some(profile) = target else { continue };
+                if !live
+                    .scope(scope)
+                    .default_of(kind)
+                    .as_ref()
+                    .is_some_and(|p| p.eq_ignore_ascii_case(profile))
+                {
+                    defaults.push(RestoreChange::SetDefault {
+                        device_key: wanted.device_key.clone(),
+                        scope,
+                        kind,
+                        profile: profile.clone(),
+                    });
+                }
+            }
+        }
+        if live.use_per_user_profiles != wanted.use_per_user_profiles {
+            switches.push(RestoreChange::SetUsePerUser {
+                device_key: wanted.device_key.clone(),
+                enabled: wanted.use_per_user_profiles,
+            });
+        }
+    }
+
+    for live in &current.devices {
+        if backup.device(&live.device_key).is_some() {
+            continue;
+        }
+        for scope in SCOPES {
+            let from = &live.scope(scope).profiles;
+            if !from.is_empty() {
+                lists.push(RestoreChange::SetProfiles {
+                    device_key: live.device_key.clone(),
+                    scope,
+                    from: from.clone(),
+                    to: Vec::new(),
+                });
+            }
+        }
+    }
+
+    lists.extend(switches);
+    lists.extend(defaults);
+    lists
+}
+
+#[cfg(test)]
+#[path = "tests/colorstore_tests.rs"]
+mod tests;
@@ -2,21 +2,189 @@
 //!
 //! Config file location: `%ProgramData%\LG-UltraGear-Monitor\config.toml`
 //! Falls back to compiled-in defaults if the file is missing or malformed.
-
+//! With `--portable` ([`enable_portable`]) the config, state, logs and
+//! reports live next to the executable instead, and nothing is written under
+//! `%ProgramData%`.
+//!
+//! Saves are atomic (temp file + rename), so a power loss mid-write leaves
+//! either the old or the new file, never a truncated one. The previous file
+//! is kept as `config.toml.bak` when it still parses, and a config that fails
+//! to parse is replaced by that backup (with a warning) before falling back
+//! to defaults.
+
+use crate::audit;
+use crate::color_dir;
+use crate::i18n;
+use crate::plan::Plan;
 use log::{info, warn};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Name that refers to the base `config.toml`.
+pub const DEFAULT_CONFIG_NAME: &str = "default";
+
+/// Shortest stabilize window with `dock_mode` on, in ms.
+pub const DOCK_MIN_STABILIZE_DELAY_MS: u64 = 5000;
+
+/// Shortest initialization wait with `dock_mode` on, in ms.
+pub const DOCK_MIN_REAPPLY_DELAY_MS: u64 = 20000;
+
+/// Per-process config name set by `--config-name`, overriding the pointer file.
+static CONFIG_NAME_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// Directory of a portable run, set by [`enable_portable`].
+static PORTABLE_DIR: OnceLock<PathBuf> = OnceLock::new();
 
 /// Path to the config directory.
 pub fn config_dir() -> PathBuf {
+    if let Some(dir) = PORTABLE_DIR.get() {
+        return dir.clone();
+    }
     let program_data =
         std::env::var("ProgramData").unwrap_or_else(|_| r"C:\ProgramData".to_string());
     PathBuf::from(program_data).join("LG-UltraGear-Monitor")
 }
 
-/// Full path to the config file.
+/// Directory a portable copy of `exe` keeps its files in: its own.
+pub fn portable_dir_for(exe: &Path) -> Result<PathBuf, String> {
+    exe.parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map(Path::to_path_buf)
+        .ok_or_else(|| format!("cannot tell the directory of {}", exe.display()))
+}
+
+/// Keep every file of this process next to the running executable
+/// (`--portable`).
+pub fn enable_portable() -> Result<(), String> {
+    let exe =
+        std::env::current_exe().map_err(|e| format!("cannot locate the executable: {}", e))?;
+    let dir = portable_dir_for(&exe)?;
+    PORTABLE_DIR
+        .set(dir)
+        .map_err(|_| "portable mode already set".to_string())
+}
+
+/// True when this process runs in portable mode.
+pub fn is_portable() -> bool {
+    PORTABLE_DIR.get().is_some()
+}
+
+/// Full path to the active config file.
+///
+/// This is `config.toml` unless a named config is selected, either for this
+/// process via [`set_config_name_override`] or persistently via the pointer
+/// file written by [`set_active_config_name`].
 pub fn config_path() -> PathBuf {
-    config_dir().join("config.toml")
+    named_config_path(&active_config_name())
+}
+
+/// Directory holding named configs (`<name>.toml`).
+pub fn named_configs_dir() -> PathBuf {
+    config_dir().join("configs")
+}
+
+/// Pointer file recording which named config the service should read.
+pub fn active_config_pointer_path() -> PathBuf {
+    config_dir().join("active-config")
+}
+
+/// Path of the config called `name` (`default` is the base `config.toml`).
+pub fn named_config_path(name: &str) -> PathBuf {
+    if name.eq_ignore_ascii_case(DEFAULT_CONFIG_NAME) {
+        config_dir().join("config.toml")
+    } else {
+        named_configs_dir().join(format!("{}.toml", name))
+    }
+}
+
+/// Check that `name` is usable as a config name (and therefore a file stem).
+pub fn validate_config_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 64 {
+        return Err(format!(
+            "config name must be 1-64 characters, got \"{}\"",
+            name
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "config name \"{}\" may only contain letters, digits, '-' and '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Select a named config for the rest of this process (`--config-name`).
+pub fn set_config_name_override(name: &str) -> Result<(), String> {
+    validate_config_name(name)?;
+    CONFIG_NAME_OVERRIDE
+        .set(name.to_string())
+        .map_err(|_| "config name override already set".to_string())
+}
+
+/// Name of the config currently in effect.
+pub fn active_config_name() -> String {
+    if let Some(name) = CONFIG_NAME_OVERRIDE.get() {
+        return name.clone();
+    }
+    std::fs::read_to_string(active_config_pointer_path())
+        .ok()
+        .and_then(|raw| parse_config_pointer(&raw))
+        .unwrap_or_else(|| DEFAULT_CONFIG_NAME.to_string())
+}
+
+/// Parse the pointer file contents; invalid names fall back to the default.
+fn parse_config_pointer(raw: &str) -> Option<String> {
+    let name = raw.trim();
+    validate_config_name(name).ok()?;
+    Some(name.to_string())
+}
+
+/// Persist `name` as the active config (`default` removes the pointer file).
+pub fn set_active_config_name(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    validate_config_name(name)?;
+    let pointer = active_config_pointer_path();
+    if name.eq_ignore_ascii_case(DEFAULT_CONFIG_NAME) {
+        if pointer.exists() {
+            std::fs::remove_file(&pointer)?;
+        }
+    } else {
+        std::fs::create_dir_all(config_dir())?;
+        std::fs::write(&pointer, format!("{}\n", name))?;
+    }
+    info!("Active config set to \"{}\"", name);
+    Ok(())
+}
+
+/// Names of all configs on disk, `default` first.
+pub fn list_config_names() -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(named_configs_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("toml"))
+            {
+                path.file_stem().map(|s| s.to_string_lossy().to_string())
+            } else {
+                None
+            }
+        })
+        .filter(|name| validate_config_name(name).is_ok())
+        .filter(|name| !name.eq_ignore_ascii_case(DEFAULT_CONFIG_NAME))
+        .collect();
+    names.sort();
+    names.insert(0, DEFAULT_CONFIG_NAME.to_string());
+    names
 }
 
 /// Full path to the installed service binary.
@@ -37,6 +205,10 @@ pub struct Config {
     /// ICC profile filename (looked up in Windows color store).
     pub profile_name: String,
 
+    /// Color store directory. Empty uses the one Windows reports; `%VAR%`
+    /// references are expanded.
+    pub color_directory: String,
+
     /// Gamma value used to generate the dynamic ICC transfer curves.
     /// Lower values brighten shadows/midtones; higher values darken them.
     pub icc_gamma: f64,
@@ -137,6 +309,9 @@ pub struct Config {
     /// Generate separate ICC files per matched monitor and embed identity metadata.
     pub icc_per_monitor_profiles: bool,
 
+    /// Associate a profile for HDR/advanced color mode as well as SDR.
+    pub icc_hdr_association: bool,
+
     /// Preferred preset when HDR/advanced color mode is active.
     pub icc_hdr_preset: String,
 
@@ -149,6 +324,12 @@ pub struct Config {
     /// Optional night preset (used when both day and night presets are set).
     pub icc_schedule_night_preset: String,
 
+    /// Local time (`HH:MM`) the day preset takes over.
+    pub icc_schedule_day_start: String,
+
+    /// Local time (`HH:MM`) the night preset takes over.
+    pub icc_schedule_night_start: String,
+
     /// Automatically regenerate and apply optimized ICC after each ICC Studio
     /// parameter edit. Intended for rapid testing/tuning workflows.
     pub icc_auto_apply_on_change: bool,
@@ -159,9 +340,46 @@ pub struct Config {
     /// Toast title text.
     pub toast_title: String,
 
-    /// Toast body text.
+    /// Toast body text. Left at the English default, it shows in the
+    /// display language (see [`crate::i18n`]).
     pub toast_body: String,
 
+    /// What to do with a toast while Focus Assist (do-not-disturb) is on:
+    /// `show`, `suppress`, or `queue` (shown once quiet hours end).
+    pub toast_quiet_hours: String,
+
+    /// Language of the default toast text and the reapply summary lines:
+    /// a code such as `de` or `pt-BR`, or empty for the Windows display
+    /// language.
+    pub language: String,
+
+    /// Flash an on-screen badge after each successful reapply. Drawn in the
+    /// user's session, so it shows up from `watch` and the tray even where
+    /// toasts are blocked; the service itself (Session 0) cannot draw one.
+    pub osd_enabled: bool,
+
+    /// Screen corner of the badge: `top-left`, `top-right`, `bottom-left`
+    /// or `bottom-right`.
+    pub osd_position: String,
+
+    /// How long the badge stays up, in milliseconds.
+    pub osd_duration_ms: u64,
+
+    /// Command run after each successful reapply to measure screen
+    /// luminance (e.g. ArgyllCMS `spotread -x -O`); its reading is logged
+    /// for `service status --stats`. Empty = no measurement.
+    pub measure_command: String,
+
+    /// Milliseconds `measure_command` may run before it is killed.
+    pub measure_timeout_ms: u64,
+
+    /// Opt-in weekly usage ping from the service: version, Windows build,
+    /// matched monitor model codes and reapply success rate. Off by default.
+    pub telemetry_enabled: bool,
+
+    /// HTTPS URL the usage ping is POSTed to. Empty = nothing is sent.
+    pub telemetry_endpoint: String,
+
     /// Milliseconds to wait after a display/session event before reapplying.
     /// Gives the display time to stabilize after connect/wake.
     pub stabilize_delay_ms: u64,
@@ -175,6 +393,37 @@ pub struct Config {
     /// scaler sync, color pipeline). Default 12000 (12 seconds).
     pub reapply_delay_ms: u64,
 
+    /// Hold background reapplies (device changes, automation polls, the
+    /// day/night schedule) while the user is active in a fullscreen app.
+    pub defer_reapply: bool,
+
+    /// Input idle time (ms) after which a deferred reapply goes ahead.
+    pub defer_reapply_idle_ms: u64,
+
+    /// Longest a reapply may be deferred (ms) before it is applied anyway.
+    pub defer_reapply_max_ms: u64,
+
+    /// Treat a monitor that returns within seconds of its removal as a KVM
+    /// switch: reapply straight away, without the stabilize and
+    /// initialization waits, the monitor check, or a toast.
+    pub kvm_mode: bool,
+
+    /// Docking-station preset: raise the stabilize and initialization waits
+    /// to at least [`DOCK_MIN_STABILIZE_DELAY_MS`] and
+    /// [`DOCK_MIN_REAPPLY_DELAY_MS`], let device bursts extend the settle
+    /// window, and require two monitor checks in a row before reapplying.
+    pub dock_mode: bool,
+
+    /// Skip a reapply while fewer monitors than this match. 0 = no minimum.
+    pub min_matching_monitors: u32,
+
+    /// Only reapply while exactly this many displays are active. 0 = any.
+    pub only_when_monitor_count: u32,
+
+    /// Skip a reapply while the laptop's built-in panel is off (lid closed)
+    /// and a matching monitor is the only display.
+    pub skip_reapply_lid_closed: bool,
+
     /// Whether to call `ChangeDisplaySettingsExW` as part of the refresh.
     pub refresh_display_settings: bool,
 
@@ -187,6 +436,10 @@ pub struct Config {
     /// Whether to trigger the Windows Calibration Loader scheduled task.
     pub refresh_calibration_loader: bool,
 
+    /// Hold the display refresh (not the profile association) while a
+    /// fullscreen app has the foreground, and run it once the app leaves.
+    pub postpone_refresh_in_fullscreen: bool,
+
     /// Automatically set DDC/CI brightness after each profile reapply.
     pub ddc_brightness_on_reapply: bool,
 
@@ -194,8 +447,117 @@ pub struct Config {
     /// is enabled.  Also used by the TUI "Set DDC Brightness" maintenance action.
     pub ddc_brightness_value: u32,
 
+    /// SDR content brightness (the "SDR content brightness" slider, 80–480
+    /// nits) to set on matched monitors with HDR on after each reapply.
+    /// 0 leaves the Windows setting alone.
+    pub sdr_white_level_nits: u32,
+
+    /// Record each matched display's resolution and refresh rate and put
+    /// it back during reapply when it has changed (e.g. 60 Hz after sleep).
+    pub display_mode_guard: bool,
+
+    /// Watch DDC/CI brightness (VCP 0x10) on matched monitors and rewrite
+    /// `ddc_brightness_value` whenever the firmware lowers it.
+    pub brightness_guard: bool,
+
+    /// How far (in brightness steps) the value may drop below the target
+    /// before the guard restores it.  Acts as hysteresis against jitter.
+    pub brightness_guard_threshold: u32,
+
+    /// Milliseconds between brightness guard polls.
+    pub brightness_guard_interval_ms: u64,
+
+    /// Maximum restores per monitor per minute (rate limit; 0 = unlimited).
+    pub brightness_guard_max_per_minute: u32,
+
+    /// Reapply the profile when a fullscreen application loses the
+    /// foreground (exits or alt-tabs), since games often reset the gamma
+    /// ramp without any device event firing.
+    pub fullscreen_exit_reassert: bool,
+
+    /// Comma-separated process names (e.g. `"game.exe, other"`) whose
+    /// fullscreen exit triggers a reassert.  Empty = any fullscreen app.
+    pub fullscreen_exit_apps: String,
+
+    /// React when another program replaces our default profile:
+    /// `"off"`, `"log"`, `"notify"` (toast) or `"reassert"` (put it back).
+    pub tamper_watch: String,
+
+    /// React when a GPU driver or control panel resets the gamma ramp:
+    /// `"off"`, `"log"`, `"notify"` (toast) or `"reassert"` (reapply).
+    pub gamma_watch: String,
+
+    /// Milliseconds between gamma ramp readbacks for `gamma_watch`.
+    pub gamma_watch_interval_ms: u64,
+
+    /// Minutes between service self-checks (message window, notifications,
+    /// WMI, association). 0 = off.
+    pub watchdog_interval_minutes: u64,
+
+    /// Minutes between resource samples of the service process (handle
+    /// count and private bytes), for tracking down leaks. 0 = off.
+    pub resource_audit_interval_minutes: u64,
+
+    /// Register global hotkeys in the watcher's message window.
+    pub hotkeys_enabled: bool,
+
+    /// Hotkey that reapplies the profile immediately (e.g. `"Ctrl+Alt+F9"`).
+    /// Empty = unbound.
+    pub hotkey_reapply: String,
+
+    /// Hotkey that raises DDC brightness by `hotkey_brightness_step`.
+    pub hotkey_brightness_up: String,
+
+    /// Hotkey that lowers DDC brightness by `hotkey_brightness_step`.
+    pub hotkey_brightness_down: String,
+
+    /// Brightness change per hotkey press (percentage points).
+    pub hotkey_brightness_step: u32,
+
     /// Enable logging of every event (useful for debugging).
     pub verbose: bool,
+
+    /// Write a JSON report (paths, device keys, API results) to
+    /// `reports\last-<action>.json` after each install/apply.
+    pub install_report: bool,
+
+    /// Location and day/night mode shared by the time-of-day features.
+    pub schedule: ScheduleConfig,
+
+    /// Per-application overrides: process name → profile filename in the
+    /// color store, or `"none"` to remove the dimming-fix profile while that
+    /// process is focused.
+    pub app_overrides: BTreeMap<String, String>,
+}
+
+/// `[schedule]` section: how day and night are determined.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    /// `"fixed"` uses the configured clock times; `"solar"` uses local
+    /// sunrise/sunset at the configured (or detected) location.
+    pub mode: String,
+
+    /// Latitude in degrees, north positive.
+    pub latitude: f64,
+
+    /// Longitude in degrees, east positive.
+    pub longitude: f64,
+
+    /// Ask the Windows Location API for the position when in solar mode,
+    /// falling back to `latitude`/`longitude` if location access is off.
+    pub use_windows_location: bool,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            mode: "fixed".to_string(),
+            latitude: 0.0,
+            longitude: 0.0,
+            use_windows_location: false,
+        }
+    }
 }
 
 impl Default for Config {
@@ -204,6 +566,7 @@ impl Default for Config {
             monitor_match: "LG ULTRAGEAR".to_string(),
             monitor_match_regex: false,
             profile_name: "lg-ultragear-dynamic-cmx.icm".to_string(),
+            color_directory: String::new(),
             icc_gamma: 2.05,
             icc_active_preset: "gamma22".to_string(),
             icc_generate_specialized_profiles: true,
@@ -238,46 +601,140 @@ impl Default for Config {
             icc_include_viewing_conditions: true,
             icc_include_spectral_scaffold: false,
             icc_per_monitor_profiles: true,
+            icc_hdr_association: true,
             icc_hdr_preset: "gamma22".to_string(),
             icc_sdr_preset: "gamma22".to_string(),
             icc_schedule_day_preset: "".to_string(),
             icc_schedule_night_preset: "".to_string(),
+            icc_schedule_day_start: "07:00".to_string(),
+            icc_schedule_night_start: "19:00".to_string(),
             icc_auto_apply_on_change: false,
             toast_enabled: true,
             toast_title: "LG UltraGear".to_string(),
-            toast_body: "Color profile reapplied ✓".to_string(),
+            toast_body: i18n::ENGLISH.toast_body.to_string(),
+            toast_quiet_hours: "suppress".to_string(),
+            language: String::new(),
+            osd_enabled: false,
+            osd_position: "top-right".to_string(),
+            osd_duration_ms: 2000,
+            measure_command: String::new(),
+            measure_timeout_ms: 60_000,
+            telemetry_enabled: false,
+            telemetry_endpoint: String::new(),
             stabilize_delay_ms: 1500,
             toggle_delay_ms: 100,
             reapply_delay_ms: 12000,
+            defer_reapply: false,
+            defer_reapply_idle_ms: 60000,
+            defer_reapply_max_ms: 600000,
+            kvm_mode: false,
+            dock_mode: false,
+            min_matching_monitors: 0,
+            only_when_monitor_count: 0,
+            skip_reapply_lid_closed: false,
             refresh_display_settings: false,
             refresh_broadcast_color: true,
             refresh_invalidate: false,
             refresh_calibration_loader: true,
+            postpone_refresh_in_fullscreen: true,
             ddc_brightness_on_reapply: false,
             ddc_brightness_value: 50,
+            sdr_white_level_nits: 0,
+            display_mode_guard: false,
+            brightness_guard: false,
+            brightness_guard_threshold: 3,
+            brightness_guard_interval_ms: 2000,
+            brightness_guard_max_per_minute: 6,
+            fullscreen_exit_reassert: false,
+            fullscreen_exit_apps: "".to_string(),
+            tamper_watch: "off".to_string(),
+            gamma_watch: "off".to_string(),
+            gamma_watch_interval_ms: 5000,
+            watchdog_interval_minutes: 5,
+            resource_audit_interval_minutes: 0,
+            hotkeys_enabled: false,
+            hotkey_reapply: "Ctrl+Alt+F9".to_string(),
+            hotkey_brightness_up: "Ctrl+Alt+Up".to_string(),
+            hotkey_brightness_down: "Ctrl+Alt+Down".to_string(),
+            hotkey_brightness_step: 10,
             verbose: false,
+            install_report: false,
+            schedule: ScheduleConfig::default(),
+            app_overrides: BTreeMap::new(),
         }
     }
 }
 
+/// Backup of the last config at `path` that parsed cleanly (`<path>.bak`).
+pub fn backup_path(path: &Path) -> PathBuf {
+    sibling_with_suffix(path, ".bak")
+}
+
+fn sibling_with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// [`replace_atomic`], recorded in the audit log.
+fn write_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    audit::file_change("write_config", path, || replace_atomic(path, contents))
+}
+
+/// Replace `path` with `contents` atomically: write and flush a sibling temp
+/// file, then rename it over the target. The file being replaced is copied
+/// to [`backup_path`] first if it still parses.
+fn replace_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        if !dir.exists() {
+            std::fs::create_dir_all(dir)?;
+        }
+    }
+
+    let tmp = sibling_with_suffix(path, ".tmp");
+    let written = std::fs::File::create(&tmp).and_then(|mut file| {
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()
+    });
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+
+    let previous_is_good = std::fs::read_to_string(path)
+        .is_ok_and(|existing| toml::from_str::<Config>(&existing).is_ok());
+    if previous_is_good {
+        if let Err(e) = std::fs::copy(path, backup_path(path)) {
+            warn!("Could not back up {}: {}", path.display(), e);
+        }
+    }
+
+    std::fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
 impl Config {
     /// Load config from the TOML file, falling back to defaults.
+    ///
+    /// Also records its `color_directory` as the color store for this
+    /// process (see [`color_dir`]).
     pub fn load() -> Self {
-        let path = config_path();
-        match std::fs::read_to_string(&path) {
+        let cfg = Self::load_from(&config_path());
+        color_dir::set_configured(&cfg.color_directory);
+        cfg
+    }
+
+    /// Load config from `path`. A file that fails to parse is replaced by its
+    /// `.bak` backup when that parses, otherwise by the defaults.
+    pub fn load_from(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
             Ok(contents) => match toml::from_str::<Config>(&contents) {
                 Ok(cfg) => {
                     info!("Config loaded from {}", path.display());
                     cfg
                 }
-                Err(e) => {
-                    warn!(
-                        "Config parse error in {}: {} — using defaults",
-                        path.display(),
-                        e
-                    );
-                    Self::default()
-                }
+                Err(e) => Self::recover(path, &e),
             },
             Err(_) => {
                 info!("No config file at {} — using defaults", path.display());
@@ -286,38 +743,69 @@ impl Config {
         }
     }
 
+    fn recover(path: &Path, error: &toml::de::Error) -> Self {
+        let backup = backup_path(path);
+        let from_backup = std::fs::read_to_string(&backup)
+            .ok()
+            .and_then(|raw| toml::from_str::<Config>(&raw).ok());
+        match from_backup {
+            Some(cfg) => {
+                warn!(
+                    "Config {} is corrupt ({}) — loaded the last good copy from {}. \
+                     Fix or re-save the config to clear this warning.",
+                    path.display(),
+                    error.message(),
+                    backup.display()
+                );
+                cfg
+            }
+            None => {
+                warn!(
+                    "Config {} is corrupt ({}) and no usable backup exists — using defaults",
+                    path.display(),
+                    error.message()
+                );
+                Self::default()
+            }
+        }
+    }
+
     /// Write the default config to disk (creates directory if needed).
     /// Used by `install` to bootstrap the config file.
     pub fn write_default() -> Result<(), Box<dyn std::error::Error>> {
-        let dir = config_dir();
-        if !dir.exists() {
-            std::fs::create_dir_all(&dir)?;
-        }
-
         let path = config_path();
         let cfg = Self::default();
         let toml_str = Self::to_toml_commented(&cfg);
-        std::fs::write(&path, toml_str)?;
+        write_atomic(&path, &toml_str)?;
         info!("Default config written to {}", path.display());
         Ok(())
     }
 
     /// Write a specific config to disk.
     pub fn write_config(cfg: &Config) -> Result<(), Box<dyn std::error::Error>> {
-        let dir = config_dir();
-        if !dir.exists() {
-            std::fs::create_dir_all(&dir)?;
-        }
+        Self::write_config_to(cfg, &config_path())
+    }
 
-        let path = config_path();
+    /// Plan counterpart of [`Config::write_config_to`]: a `write_config`
+    /// operation unless `path` already holds exactly this config.
+    pub fn plan_write_to(plan: &mut Plan, cfg: &Config, path: &Path) -> bool {
+        plan.write_file(
+            "write_config",
+            path,
+            Self::to_toml_commented(cfg).as_bytes(),
+        )
+    }
+
+    /// Write a specific config to `path` (creates the parent directory if needed).
+    pub fn write_config_to(cfg: &Config, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
         let toml_str = Self::to_toml_commented(cfg);
-        std::fs::write(&path, toml_str)?;
+        write_atomic(path, &toml_str)?;
         info!("Config written to {}", path.display());
         Ok(())
     }
 
     /// Serialize config to a TOML string with helpful comments.
-    fn to_toml_commented(cfg: &Config) -> String {
+    pub(crate) fn to_toml_commented(cfg: &Config) -> String {
         format!(
             r##"# LG UltraGear Color Profile Tool — Configuration
 # Location: %ProgramData%\LG-UltraGear-Monitor\config.toml
@@ -327,12 +815,21 @@ impl Config {
 # Match against monitor friendly names.
 # - monitor_match_regex = false: case-insensitive substring
 # - monitor_match_regex = true: case-insensitive regex
+# Or write a match expression, in either mode, e.g.
+#   'name~"ULTRAGEAR" AND NOT serial="123"' or "manufacturer:GSM AND product:5BBF"
+# (fields: name, model, serial, manufacturer, product, devicekey; `detect`
+# shows them).
 monitor_match = "{monitor_match}"
 monitor_match_regex = {monitor_match_regex}
 
-# ICC profile filename (must be in %WINDIR%\System32\spool\drivers\color\).
+# ICC profile filename (must be in the color store directory).
 profile_name = "{profile_name}"
 
+# Color store directory. Leave empty to use the one Windows reports
+# (normally %WINDIR%\System32\spool\drivers\color\). %VAR% references
+# are expanded, e.g. for a per-user store.
+color_directory = "{color_directory}"
+
 # Dynamic ICC gamma tuning (recommended range: 1.2–3.0).
 # Lower gamma can offset aggressive dimming by lifting shadows.
 icc_gamma = {icc_gamma}
@@ -416,6 +913,10 @@ icc_include_spectral_scaffold = {icc_include_spectral_scaffold}
 # Generate and use monitor-scoped ICC files with serial/device identity in tags.
 icc_per_monitor_profiles = {icc_per_monitor_profiles}
 
+# Associate a profile for HDR/advanced color mode too. Off skips the HDR
+# association (`install --skip-hdr`).
+icc_hdr_association = {icc_hdr_association}
+
 # HDR/SDR preferred presets.
 icc_hdr_preset = "{icc_hdr_preset}"
 icc_sdr_preset = "{icc_sdr_preset}"
@@ -424,16 +925,57 @@ icc_sdr_preset = "{icc_sdr_preset}"
 icc_schedule_day_preset = "{icc_schedule_day_preset}"
 icc_schedule_night_preset = "{icc_schedule_night_preset}"
 
+# Day/night switch times (local, 24h HH:MM). With [schedule] mode = "solar"
+# the switch follows sunrise/sunset instead (falling back to these times
+# during polar day/night).
+icc_schedule_day_start = "{icc_schedule_day_start}"
+icc_schedule_night_start = "{icc_schedule_night_start}"
+
 # ICC Studio behavior.
 # When enabled, every parameter edit auto-runs optimized ICC generate+apply.
 icc_auto_apply_on_change = {icc_auto_apply_on_change}
 
 # ─── Toast Notifications ─────────────────────────────────────────────
 # Show a Windows notification after each successful profile reapply.
+# Title and body can use {{monitors}}, {{count}}, {{profile}}, {{trigger}},
+# {{duration_ms}} and {{time}}, e.g. "Reapplied to {{monitors}} after {{trigger}}
+# ({{duration_ms}} ms)"; write {{{{ and }}}} for literal braces.
 toast_enabled = {toast_enabled}
 toast_title = "{toast_title}"
 toast_body = "{toast_body}"
 
+# While Focus Assist / do-not-disturb is on: "show" anyway, "suppress",
+# or "queue" the latest toast until quiet hours end.
+toast_quiet_hours = "{toast_quiet_hours}"
+
+# Language of the default toast text and the end-of-reapply summary:
+# "en", "de", "fr", "es", "it", "pt", "pl", "ru", "ja", "ko" or "zh".
+# Empty follows the Windows display language. Logs stay in English.
+language = "{language}"
+
+# ─── On-screen Badge ─────────────────────────────────────────────────
+# Flash a small "reapplied" badge in a screen corner instead of (or as
+# well as) the toast. Shown by `watch` and the tray in your session; the
+# service runs in Session 0 and cannot draw it.
+osd_enabled = {osd_enabled}
+# "top-left", "top-right", "bottom-left" or "bottom-right".
+osd_position = "{osd_position}"
+osd_duration_ms = {osd_duration_ms}
+
+# ─── Luminance Measurement ───────────────────────────────────────────
+# Command run after each successful reapply to read screen luminance,
+# e.g. "spotread -x -O" from ArgyllCMS with the meter on the screen.
+# Readings are kept for `service status --stats`. Empty = off.
+measure_command = "{measure_command}"
+measure_timeout_ms = {measure_timeout_ms}
+
+# ─── Usage Ping ──────────────────────────────────────────────────────
+# Opt-in. Once a week the service POSTs version, Windows build, matched
+# monitor model codes and reapply success rate to telemetry_endpoint.
+# `telemetry status` shows the exact payload.
+telemetry_enabled = {telemetry_enabled}
+telemetry_endpoint = "{telemetry_endpoint}"
+
 # ─── Timing ──────────────────────────────────────────────────────────
 # Delay after display/session event before reapplying (ms).
 # Increase if the profile isn't sticking on slow displays.
@@ -448,6 +990,37 @@ toggle_delay_ms = {toggle_delay_ms}
 # 12000 = 12 seconds. Increase to 15000 for slow-wake monitors.
 reapply_delay_ms = {reapply_delay_ms}
 
+# Hold background reapplies (device changes, polls, the day/night schedule)
+# while you are active in a fullscreen app such as a video call, so the
+# refresh flash doesn't land mid-call. They go ahead once input has been idle
+# for defer_reapply_idle_ms, the fullscreen app leaves the foreground, or
+# defer_reapply_max_ms passes. Needs `watch` running in your session.
+defer_reapply = {defer_reapply}
+defer_reapply_idle_ms = {defer_reapply_idle_ms}
+defer_reapply_max_ms = {defer_reapply_max_ms}
+
+# Input-switch (KVM) helper: when a monitor comes back within a few seconds
+# of disappearing, reapply right away and quietly instead of waiting out
+# stabilize_delay_ms and reapply_delay_ms.
+kvm_mode = {kvm_mode}
+
+# Docking-station helper for displays behind a Thunderbolt/USB-C dock that
+# enumerate twice: waits at least 5 s for the dock's devices to go quiet and
+# 20 s for the displays, and only reapplies once the monitor check has found
+# the monitors twice in a row.
+dock_mode = {dock_mode}
+
+# Display-count conditions checked before each reapply; a reapply that fails
+# one is skipped (and logged), avoiding useless work and flashes while a
+# dock or clamshell setup is still switching.
+#   min_matching_monitors: at least this many monitors must match (0 = off)
+#   only_when_monitor_count: exactly this many displays active (0 = off)
+#   skip_reapply_lid_closed: skip while the laptop panel is off and the
+#     matching monitor is the only display
+min_matching_monitors = {min_matching_monitors}
+only_when_monitor_count = {only_when_monitor_count}
+skip_reapply_lid_closed = {skip_reapply_lid_closed}
+
 # ─── Refresh Methods ─────────────────────────────────────────────────
 # Which display refresh methods to use after toggling the profile.
 # Defaults favor no-flicker apply (soft refresh).
@@ -457,6 +1030,12 @@ refresh_broadcast_color = {refresh_broadcast_color}     # WM_SETTINGCHANGE "Colo
 refresh_invalidate = {refresh_invalidate}          # InvalidateRect repaint nudge (soft)
 refresh_calibration_loader = {refresh_calibration_loader} # Trigger Calibration Loader task (ICC reload)
 
+# Postpone the refresh above while a fullscreen (exclusive or borderless) app
+# has the foreground, since the broadcast can make games stutter or drop out
+# of fullscreen. The profile is still associated right away; the refresh
+# runs once the app leaves the foreground.
+postpone_refresh_in_fullscreen = {postpone_refresh_in_fullscreen}
+
 # ─── DDC/CI Brightness ───────────────────────────────────────────────
 # Automatically set monitor brightness via DDC/CI after each profile reapply.
 # Requires DDC/CI support on your monitor (most LG UltraGears support it).
@@ -466,13 +1045,114 @@ ddc_brightness_on_reapply = {ddc_brightness_on_reapply}
 # Only used when ddc_brightness_on_reapply is enabled.
             ddc_brightness_value = {ddc_brightness_value}
 
+# ─── SDR White Level ─────────────────────────────────────────────────
+# With HDR on, part of the perceived dimming is the Windows "SDR content
+# brightness" slider. Set it (80–480 nits) on matched monitors after each
+# reapply. 0 = leave the Windows setting alone.
+sdr_white_level_nits = {sdr_white_level_nits}
+
+# ─── Display Mode Guard ──────────────────────────────────────────────
+# Remember each matched display's resolution and refresh rate and restore
+# it during reapply when the monitor comes back from sleep at a lower one.
+# Pin a mode with `display mode set`.
+display_mode_guard = {display_mode_guard}
+
+# ─── Brightness Guard ────────────────────────────────────────────────
+# Watch DDC/CI brightness and immediately restore ddc_brightness_value
+# when the monitor firmware lowers it on its own (auto-dimming).
+brightness_guard = {brightness_guard}
+
+# Drop (in brightness steps) tolerated below the target before restoring.
+brightness_guard_threshold = {brightness_guard_threshold}
+
+# Polling interval (ms).
+brightness_guard_interval_ms = {brightness_guard_interval_ms}
+
+# Maximum restores per monitor per minute (0 = unlimited).
+brightness_guard_max_per_minute = {brightness_guard_max_per_minute}
+
+# ─── Fullscreen Exit Reassert ────────────────────────────────────────
+# Reapply the profile when a fullscreen app (usually a game that reset the
+# gamma ramp) leaves the foreground. Only observes the desktop of the
+# session the watcher runs in (use `watch` / the tray in your session).
+fullscreen_exit_reassert = {fullscreen_exit_reassert}
+
+# Comma-separated process names that trigger it (empty = any fullscreen app).
+fullscreen_exit_apps = "{fullscreen_exit_apps}"
+
+# ─── Tamper Watch ────────────────────────────────────────────────────
+# Watch the WCS association registry keys and react when another program
+# changes the default profile of a matched monitor:
+# "off", "log", "notify" (toast) or "reassert" (put ours back, rate-limited).
+tamper_watch = "{tamper_watch}"
+
+# ─── GPU Gamma Reset Watch ───────────────────────────────────────────
+# Read the gamma ramp back periodically and react when a GPU driver or
+# control panel (NVIDIA / AMD / Intel) resets it, logging the likely culprit:
+# "off", "log", "notify" (toast) or "reassert" (reapply, rate-limited).
+# Works best with `watch` running in your session.
+gamma_watch = "{gamma_watch}"
+
+# Readback interval (ms).
+gamma_watch_interval_ms = {gamma_watch_interval_ms}
+
+# ─── Watchdog ────────────────────────────────────────────────────────
+# Every N minutes the service checks that its message window is alive,
+# device/session notifications are registered, WMI answers and the profile
+# is still associated, repairs what it can, and records a heartbeat shown
+# by `service status`. 0 = off.
+watchdog_interval_minutes = {watchdog_interval_minutes}
+
+# Debugging aid: every N minutes the service logs its handle count and
+# private bytes, and warns when they keep growing. 0 = off.
+resource_audit_interval_minutes = {resource_audit_interval_minutes}
+
+# ─── Hotkeys ─────────────────────────────────────────────────────────
+# Global hotkeys handled by the watcher (`watch` / the tray in your
+# session). Combine Ctrl, Alt, Shift, Win with a key (F1-F24, A-Z, 0-9,
+# Up, Down, Left, Right, PageUp, PageDown, Home, End, Insert, Delete).
+# Leave a binding empty to disable it.
+hotkeys_enabled = {hotkeys_enabled}
+hotkey_reapply = "{hotkey_reapply}"
+hotkey_brightness_up = "{hotkey_brightness_up}"
+hotkey_brightness_down = "{hotkey_brightness_down}"
+
+# DDC/CI brightness change per press (percentage points).
+hotkey_brightness_step = {hotkey_brightness_step}
+
 # ─── Debug ───────────────────────────────────────────────────────────
 # Log every event and action (useful for troubleshooting).
 verbose = {verbose}
+
+# Write a machine-readable JSON report after each install/apply to
+# reports\last-install.json / reports\last-apply.json next to this file,
+# for cross-checking with DisplayCAL / ArgyllCMS tooling.
+install_report = {install_report}
+
+# ─── Schedule ────────────────────────────────────────────────────────
+# How day and night are determined for the time-of-day features.
+#   mode = "fixed"  → use the configured clock times
+#   mode = "solar"  → use local sunrise/sunset at latitude/longitude
+# use_windows_location asks the Windows Location API for the position
+# (requires location access); the coordinates below are the fallback.
+[schedule]
+mode = "{schedule_mode}"
+latitude = {schedule_latitude:?}
+longitude = {schedule_longitude:?}
+use_windows_location = {schedule_use_windows_location}
+
+# ─── Per-Application Overrides ───────────────────────────────────────
+# While one of these processes is focused, swap the dimming-fix profile:
+#   "none"         → remove the fix (monitor falls back to its factory profile)
+#   "<file>.icm"   → use that profile from the Windows color store instead
+# The fix is reapplied as soon as the app loses focus or exits.
+[app_overrides]
+{app_overrides}
 "##,
             monitor_match = escape_toml_string(&cfg.monitor_match),
             monitor_match_regex = cfg.monitor_match_regex,
             profile_name = escape_toml_string(&cfg.profile_name),
+            color_directory = escape_toml_string(&cfg.color_directory),
             icc_gamma = cfg.icc_gamma,
             icc_active_preset = escape_toml_string(&cfg.icc_active_preset),
             icc_generate_specialized_profiles = cfg.icc_generate_specialized_profiles,
@@ -507,36 +1187,93 @@ verbose = {verbose}
             icc_include_viewing_conditions = cfg.icc_include_viewing_conditions,
             icc_include_spectral_scaffold = cfg.icc_include_spectral_scaffold,
             icc_per_monitor_profiles = cfg.icc_per_monitor_profiles,
+            icc_hdr_association = cfg.icc_hdr_association,
             icc_hdr_preset = escape_toml_string(&cfg.icc_hdr_preset),
             icc_sdr_preset = escape_toml_string(&cfg.icc_sdr_preset),
             icc_schedule_day_preset = escape_toml_string(&cfg.icc_schedule_day_preset),
             icc_schedule_night_preset = escape_toml_string(&cfg.icc_schedule_night_preset),
+            icc_schedule_day_start = escape_toml_string(&cfg.icc_schedule_day_start),
+            icc_schedule_night_start = escape_toml_string(&cfg.icc_schedule_night_start),
+            schedule_mode = escape_toml_string(&cfg.schedule.mode),
+            schedule_latitude = cfg.schedule.latitude,
+            schedule_longitude = cfg.schedule.longitude,
+            schedule_use_windows_location = cfg.schedule.use_windows_location,
             icc_auto_apply_on_change = cfg.icc_auto_apply_on_change,
             toast_enabled = cfg.toast_enabled,
             toast_title = escape_toml_string(&cfg.toast_title),
             toast_body = escape_toml_string(&cfg.toast_body),
+            toast_quiet_hours = escape_toml_string(&cfg.toast_quiet_hours),
+            language = escape_toml_string(&cfg.language),
+            osd_enabled = cfg.osd_enabled,
+            osd_position = escape_toml_string(&cfg.osd_position),
+            osd_duration_ms = cfg.osd_duration_ms,
+            measure_command = escape_toml_string(&cfg.measure_command),
+            measure_timeout_ms = cfg.measure_timeout_ms,
+            telemetry_enabled = cfg.telemetry_enabled,
+            telemetry_endpoint = escape_toml_string(&cfg.telemetry_endpoint),
             stabilize_delay_ms = cfg.stabilize_delay_ms,
             toggle_delay_ms = cfg.toggle_delay_ms,
             reapply_delay_ms = cfg.reapply_delay_ms,
+            defer_reapply = cfg.defer_reapply,
+            defer_reapply_idle_ms = cfg.defer_reapply_idle_ms,
+            defer_reapply_max_ms = cfg.defer_reapply_max_ms,
+            kvm_mode = cfg.kvm_mode,
+            dock_mode = cfg.dock_mode,
+            min_matching_monitors = cfg.min_matching_monitors,
+            only_when_monitor_count = cfg.only_when_monitor_count,
+            skip_reapply_lid_closed = cfg.skip_reapply_lid_closed,
             refresh_display_settings = cfg.refresh_display_settings,
             refresh_broadcast_color = cfg.refresh_broadcast_color,
             refresh_invalidate = cfg.refresh_invalidate,
             refresh_calibration_loader = cfg.refresh_calibration_loader,
+            postpone_refresh_in_fullscreen = cfg.postpone_refresh_in_fullscreen,
             ddc_brightness_on_reapply = cfg.ddc_brightness_on_reapply,
             ddc_brightness_value = cfg.ddc_brightness_value,
+            sdr_white_level_nits = cfg.sdr_white_level_nits,
+            display_mode_guard = cfg.display_mode_guard,
+            brightness_guard = cfg.brightness_guard,
+            brightness_guard_threshold = cfg.brightness_guard_threshold,
+            brightness_guard_interval_ms = cfg.brightness_guard_interval_ms,
+            brightness_guard_max_per_minute = cfg.brightness_guard_max_per_minute,
+            fullscreen_exit_reassert = cfg.fullscreen_exit_reassert,
+            fullscreen_exit_apps = escape_toml_string(&cfg.fullscreen_exit_apps),
+            tamper_watch = escape_toml_string(&cfg.tamper_watch),
+            gamma_watch = escape_toml_string(&cfg.gamma_watch),
+            gamma_watch_interval_ms = cfg.gamma_watch_interval_ms,
+            watchdog_interval_minutes = cfg.watchdog_interval_minutes,
+            resource_audit_interval_minutes = cfg.resource_audit_interval_minutes,
+            hotkeys_enabled = cfg.hotkeys_enabled,
+            hotkey_reapply = escape_toml_string(&cfg.hotkey_reapply),
+            hotkey_brightness_up = escape_toml_string(&cfg.hotkey_brightness_up),
+            hotkey_brightness_down = escape_toml_string(&cfg.hotkey_brightness_down),
+            hotkey_brightness_step = cfg.hotkey_brightness_step,
             verbose = cfg.verbose,
+            install_report = cfg.install_report,
+            app_overrides = app_overrides_toml(&cfg.app_overrides),
         )
     }
 
+    /// `stabilize_delay_ms`, raised by `dock_mode`.
+    pub fn effective_stabilize_delay_ms(&self) -> u64 {
+        if self.dock_mode {
+            self.stabilize_delay_ms.max(DOCK_MIN_STABILIZE_DELAY_MS)
+        } else {
+            self.stabilize_delay_ms
+        }
+    }
+
+    /// `reapply_delay_ms`, raised by `dock_mode`.
+    pub fn effective_reapply_delay_ms(&self) -> u64 {
+        if self.dock_mode {
+            self.reapply_delay_ms.max(DOCK_MIN_REAPPLY_DELAY_MS)
+        } else {
+            self.reapply_delay_ms
+        }
+    }
+
     /// Get the full path to the ICC profile in the Windows color store.
     pub fn profile_path(&self) -> PathBuf {
-        let windir = std::env::var("WINDIR").unwrap_or_else(|_| r"C:\Windows".to_string());
-        PathBuf::from(windir)
-            .join("System32")
-            .join("spool")
-            .join("drivers")
-            .join("color")
-            .join(&self.profile_name)
+        color_dir::resolve(&self.color_directory).join(&self.profile_name)
     }
 }
 
@@ -544,6 +1281,24 @@ verbose = {verbose}
 ///
 /// Handles backslashes, double-quotes, and common control characters that
 /// would otherwise break the TOML output from `to_toml_commented()`.
+/// Render `[app_overrides]` entries, or a commented example when empty.
+fn app_overrides_toml(overrides: &BTreeMap<String, String>) -> String {
+    if overrides.is_empty() {
+        return "# \"photoshop.exe\" = \"none\"".to_string();
+    }
+    overrides
+        .iter()
+        .map(|(process, profile)| {
+            format!(
+                "\"{}\" = \"{}\"",
+                escape_toml_string(process),
+                escape_toml_string(profile)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 fn escape_toml_string(s: &str) -> String {
     let mut out = String::with_capacity(s.len());
     for ch in s.chars() {
@@ -0,0 +1,152 @@
+//! Service performance counters for monitoring tools.
+//!
+//! The service keeps running totals — reapplies, failures, duration of the
+//! last apply — in `state\counters.toml`, rewritten after every reapply.
+//! `service counters` samples that file and prints it the way `typeperf`
+//! prints Windows performance counters (PDH-CSV 4.0: a header row of
+//! `\\HOST\Object\Counter` paths, then one quoted row per sample), so
+//! monitoring agents that already scrape `typeperf` output can collect it
+//! unchanged. Reapplies/sec is computed between samples, like a PDH rate
+//! counter.
+
+use crate::state;
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Object name in the counter paths.
+pub const COUNTER_OBJECT: &str = "LG UltraGear Service";
+
+/// Counter names, in column order.
+pub const COUNTER_NAMES: [&str; 4] = ["Reapplies/sec", "Reapplies", "Failures", "Last Apply ms"];
+
+pub fn counters_path() -> PathBuf {
+    state::state_dir().join("counters.toml")
+}
+
+/// Totals since the service process started.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct ServiceCounters {
+    /// When the service started; totals restart from zero with it.
+    pub started_at: String,
+    pub updated_at: String,
+    pub reapplies: u64,
+    pub failures: u64,
+    pub last_apply_ms: u64,
+}
+
+impl ServiceCounters {
+    /// Zeroed counters for a service that starts now.
+    pub fn new() -> Self {
+        let now = Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        Self {
+            started_at: now.clone(),
+            updated_at: now,
+            ..Self::default()
+        }
+    }
+
+    /// Count one reapply that took `ms`.
+    pub fn record_apply(&mut self, ms: u64, success: bool) {
+        self.reapplies += 1;
+        if !success {
+            self.failures += 1;
+        }
+        self.last_apply_ms = ms;
+        self.updated_at = Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    }
+}
+
+pub fn load_counters() -> Option<ServiceCounters> {
+    load_from(&counters_path())
+}
+
+fn load_from(path: &Path) -> Option<ServiceCounters> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|text| toml::from_str(&text).ok())
+}
+
+/// Write the counters. The file is replaced in one rename so a reader
+/// never sees half of it.
+pub fn save_counters(counters: &ServiceCounters) -> Result<(), Box<dyn std::error::Error>> {
+    save_to(&counters_path(), counters)
+}
+
+fn save_to(path: &Path, counters: &ServiceCounters) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("toml.tmp");
+    fs::write(&tmp, toml::to_string_pretty(counters)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Counters as read at one moment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CounterSample {
+    pub at: DateTime<Local>,
+    pub counters: ServiceCounters,
+}
+
+/// `typeperf` header row for `host`.
+pub fn csv_header(host: &str) -> String {
+    let mut row = vec![quote("(PDH-CSV 4.0)")];
+    row.extend(
+        COUNTER_NAMES
+            .iter()
+            .map(|name| quote(&format!(r"\\{}\{}\{}", host, COUNTER_OBJECT, name))),
+    );
+    row.join(",")
+}
+
+/// `typeperf` data row for `current`. The rate is taken against `previous`,
+/// or since the service started when there is no earlier sample of the same
+/// service run. Missing counters (service never started) print as blanks,
+/// as PDH does for an unavailable instance.
+pub fn csv_row(previous: Option<&CounterSample>, current: &CounterSample) -> String {
+    let mut row = vec![quote(
+        &current.at.format("%m/%d/%Y %H:%M:%S%.3f").to_string(),
+    )];
+    let counters = &current.counters;
+    if counters.started_at.is_empty() {
+        row.extend(COUNTER_NAMES.iter().map(|_| quote(" ")));
+        return row.join(",");
+    }
+    row.push(quote(&format!("{:.6}", reapply_rate(previous, current))));
+    row.push(quote(&counters.reapplies.to_string()));
+    row.push(quote(&counters.failures.to_string()));
+    row.push(quote(&counters.last_apply_ms.to_string()));
+    row.join(",")
+}
+
+/// Reapplies per second between two samples of the same service run.
+fn reapply_rate(previous: Option<&CounterSample>, current: &CounterSample) -> f64 {
+    let baseline = previous.filter(|p| {
+        p.counters.started_at == current.counters.started_at
+            && p.counters.reapplies <= current.counters.reapplies
+    });
+    let (since, reapplies) = match baseline {
+        Some(p) => (p.at.with_timezone(&Utc), p.counters.reapplies),
+        None => match DateTime::parse_from_rfc3339(&current.counters.started_at) {
+            Ok(started) => (started.with_timezone(&Utc), 0),
+            Err(_) => return 0.0,
+        },
+    };
+    let seconds = (current.at.with_timezone(&Utc) - since).num_milliseconds() as f64 / 1000.0;
+    if seconds <= 0.0 {
+        return 0.0;
+    }
+    (current.counters.reapplies - reapplies) as f64 / seconds
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+#[path = "tests/counters_tests.rs"]
+mod tests;
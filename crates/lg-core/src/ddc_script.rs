@@ -0,0 +1,260 @@
+//! DDC/CI scripts: a shareable list of VCP operations run in order.
+//!
+//! `ddc script <file>` runs a TOML file of `set` / `get` / `sleep` /
+//! `assert` steps against one monitor, so a model-specific sequence (say,
+//! the writes that switch off a panel's auto-dimming) can be passed around
+//! as data:
+//!
+//! ```toml
+//! name = "27GN950: disable auto dimming"
+//! monitor_match = "27GN950"
+//!
+//! [[step]]
+//! op = "set"
+//! code = 0xDC
+//! value = 5
+//!
+//! [[step]]
+//! op = "sleep"
+//! ms = 200
+//!
+//! [[step]]
+//! op = "assert"
+//! code = 0xDC
+//! equals = 5
+//! ```
+//!
+//! [`run`] stops at the first failing step; the platform calls come from a
+//! [`VcpPort`] so the runner stays free of Windows code.
+
+use serde::Deserialize;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+/// Longest single `sleep` step; anything longer is almost certainly a typo.
+pub const MAX_SLEEP_MS: u64 = 60_000;
+
+/// One operation.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ScriptStep {
+    /// Write `value` to VCP `code`.
+    Set { code: u8, value: u32 },
+    /// Read VCP `code` and report it.
+    Get { code: u8 },
+    /// Wait before the next step (monitors need time after some writes).
+    Sleep { ms: u64 },
+    /// Read VCP `code` and fail unless it meets every given bound.
+    Assert {
+        code: u8,
+        #[serde(default)]
+        equals: Option<u32>,
+        #[serde(default)]
+        min: Option<u32>,
+        #[serde(default)]
+        max: Option<u32>,
+    },
+}
+
+impl ScriptStep {
+    /// Why `current` fails this assert, or `None` when it holds (and for
+    /// every other kind of step).
+    pub fn assert_failure(&self, current: u32) -> Option<String> {
+        let ScriptStep::Assert {
+            equals, min, max, ..
+        } = self
+        else {
+            return None;
+        };
+        if let Some(want) = *equals {
+            if current != want {
+                return Some(format!("expected {}, read {}", want, current));
+            }
+        }
+        if min.is_some_and(|low| current < low) || max.is_some_and(|high| current > high) {
+            return Some(format!(
+                "expected {}..{}, read {}",
+                min.map(|v| v.to_string()).unwrap_or_default(),
+                max.map(|v| v.to_string()).unwrap_or_default(),
+                current
+            ));
+        }
+        None
+    }
+}
+
+impl fmt::Display for ScriptStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptStep::Set { code, value } => write!(f, "set 0x{:02X} = {}", code, value),
+            ScriptStep::Get { code } => write!(f, "get 0x{:02X}", code),
+            ScriptStep::Sleep { ms } => write!(f, "sleep {}ms", ms),
+            ScriptStep::Assert {
+                code,
+                equals,
+                min,
+                max,
+            } => {
+                write!(f, "assert 0x{:02X}", code)?;
+                if let Some(v) = equals {
+                    write!(f, " == {}", v)?;
+                }
+                if let Some(v) = min {
+                    write!(f, " >= {}", v)?;
+                }
+                if let Some(v) = max {
+                    write!(f, " <= {}", v)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A parsed script file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DdcScript {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Monitor pattern used when `--pattern` isn't given.
+    #[serde(default)]
+    pub monitor_match: Option<String>,
+    #[serde(rename = "step", default)]
+    pub steps: Vec<ScriptStep>,
+}
+
+impl DdcScript {
+    /// Parse and check a script.
+    pub fn from_toml(source: &str) -> Result<Self, String> {
+        let script: Self = toml::from_str(source).map_err(|e| e.message().to_string())?;
+        script.validate()?;
+        Ok(script)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.steps.is_empty() {
+            return Err("script has no [[step]] entries".to_string());
+        }
+        validate_steps(&self.steps)
+    }
+
+    /// Every `(code, value)` the script writes, for guardrail checks before
+    /// anything runs.
+    pub fn writes(&self) -> impl Iterator<Item = (u8, u32)> + '_ {
+        self.steps.iter().filter_map(|step| match step {
+            ScriptStep::Set { code, value } => Some((*code, *value)),
+            _ => None,
+        })
+    }
+}
+
+/// Check each step on its own (sleep cap, assert bounds). Errors name the
+/// 1-based step.
+pub fn validate_steps(steps: &[ScriptStep]) -> Result<(), String> {
+    for (i, step) in steps.iter().enumerate() {
+        let problem = match step {
+            ScriptStep::Sleep { ms } if *ms > MAX_SLEEP_MS => {
+                Some(format!("sleep is capped at {}ms", MAX_SLEEP_MS))
+            }
+            ScriptStep::Assert {
+                equals: None,
+                min: None,
+                max: None,
+                ..
+            } => Some("assert needs equals, min or max".to_string()),
+            ScriptStep::Assert {
+                min: Some(min),
+                max: Some(max),
+                ..
+            } if min > max => Some(format!("assert min {} is above max {}", min, max)),
+            _ => None,
+        };
+        if let Some(problem) = problem {
+            return Err(format!("step {}: {}", i + 1, problem));
+        }
+    }
+    Ok(())
+}
+
+/// Platform side of a script run, called by [`run`].
+pub trait VcpPort {
+    /// Current and maximum value of VCP `code`.
+    fn get_vcp(&mut self, code: u8) -> Result<(u32, u32), Box<dyn Error>>;
+
+    fn set_vcp(&mut self, code: u8, value: u32) -> Result<(), Box<dyn Error>>;
+
+    fn sleep(&mut self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// How one step went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepResult {
+    /// 1-based step number.
+    pub number: usize,
+    pub step: ScriptStep,
+    pub ok: bool,
+    /// Value read, or the error.
+    pub detail: String,
+}
+
+impl fmt::Display for StepResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>3}. {:<28} {}",
+            self.number,
+            self.step.to_string(),
+            if self.ok { "ok" } else { "FAILED" }
+        )?;
+        if !self.detail.is_empty() {
+            write!(f, "  ({})", self.detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// Run `script` through `port` in order, stopping after the first step
+/// that fails. Steps after it are not attempted and not returned.
+pub fn run(script: &DdcScript, port: &mut impl VcpPort) -> Vec<StepResult> {
+    let mut results = Vec::with_capacity(script.steps.len());
+    for (i, step) in script.steps.iter().enumerate() {
+        let outcome = match step {
+            ScriptStep::Set { code, value } => port.set_vcp(*code, *value).map(|()| String::new()),
+            ScriptStep::Get { code } => port
+                .get_vcp(*code)
+                .map(|(current, max)| format!("current={} max={}", current, max)),
+            ScriptStep::Sleep { ms } => {
+                port.sleep(Duration::from_millis(*ms));
+                Ok(String::new())
+            }
+            ScriptStep::Assert { code, .. } => match port.get_vcp(*code) {
+                Ok((current, _)) => match step.assert_failure(current) {
+                    Some(failure) => Err(failure.into()),
+                    None => Ok(format!("read {}", current)),
+                },
+                Err(e) => Err(e),
+            },
+        };
+        let ok = outcome.is_ok();
+        results.push(StepResult {
+            number: i + 1,
+            step: step.clone(),
+            ok,
+            detail: outcome.unwrap_or_else(|e| e.to_string()),
+        });
+        if !ok {
+            break;
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+#[path = "tests/ddc_script_tests.rs"]
+mod tests;
@@ -0,0 +1,95 @@
+//! Display-count conditions checked before a reapply.
+//!
+//! Docking and clamshell transitions enumerate displays several times: the
+//! laptop panel goes off, the external monitor comes up alone, a second
+//! monitor follows. A reapply in the middle of that is wasted work and a
+//! visible flash. `min_matching_monitors`, `only_when_monitor_count` and
+//! `skip_reapply_lid_closed` describe the setups worth reapplying in; the
+//! platform layer counts the displays into a [`DisplayCounts`] and
+//! [`skip_reason`] says whether to go ahead.
+//!
+//! The lid position comes from the service's power notifications when it
+//! has one; otherwise a built-in panel that is present but off stands in
+//! for a closed lid.
+
+use crate::config::Config;
+
+/// Laptop lid position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LidState {
+    Open,
+    Closed,
+}
+
+impl LidState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Closed => "closed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "open" => Some(Self::Open),
+            "closed" => Some(Self::Closed),
+            _ => None,
+        }
+    }
+}
+
+/// Displays at the moment of a reapply.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DisplayCounts {
+    /// Monitors matching `monitor_match`.
+    pub matching: usize,
+    /// Active displays of any kind.
+    pub active: usize,
+    /// The built-in panel: `None` without one, otherwise whether it is on.
+    pub internal_panel: Option<bool>,
+    /// Lid position, when reported.
+    pub lid: Option<LidState>,
+}
+
+impl DisplayCounts {
+    /// The lid is closed (or, without a reported position, the built-in
+    /// panel is off) and a matching monitor is the only display left.
+    pub fn lid_closed_single(&self) -> bool {
+        let closed = match self.lid {
+            Some(lid) => lid == LidState::Closed,
+            None => self.internal_panel == Some(false),
+        };
+        closed && self.active == 1 && self.matching == 1
+    }
+}
+
+/// True when any condition is configured, so the displays need counting.
+pub fn is_enabled(cfg: &Config) -> bool {
+    cfg.min_matching_monitors > 0 || cfg.only_when_monitor_count > 0 || cfg.skip_reapply_lid_closed
+}
+
+/// Why a reapply should be skipped with `counts`, or `None` to go ahead.
+pub fn skip_reason(cfg: &Config, counts: &DisplayCounts) -> Option<String> {
+    let min = cfg.min_matching_monitors as usize;
+    if counts.matching < min {
+        return Some(format!(
+            "{} matching monitor(s), min_matching_monitors = {}",
+            counts.matching, min
+        ));
+    }
+    let wanted = cfg.only_when_monitor_count as usize;
+    if wanted > 0 && counts.active != wanted {
+        return Some(format!(
+            "{} active display(s), only_when_monitor_count = {}",
+            counts.active, wanted
+        ));
+    }
+    if cfg.skip_reapply_lid_closed && counts.lid_closed_single() {
+        return Some("lid closed with the matching monitor as the only display".to_string());
+    }
+    None
+}
+
+#[cfg(test)]
+#[path = "tests/display_gate_tests.rs"]
+mod tests;
@@ -0,0 +1,290 @@
+//! Translated default texts: the toast body and the summary lines `apply`
+//! and the TUI print at the end of a reapply.
+//!
+//! The language is `language` from the config when set, otherwise the
+//! Windows display language, falling back to English for anything without
+//! a table here. A `toast_body` left at the English default shows in the
+//! chosen language; any other text is the user's and is shown as written.
+//! Logs, diagnostics and install reports stay in English so they can be
+//! searched and compared.
+
+use crate::config::Config;
+use std::sync::OnceLock;
+
+/// A language with its own [`Strings`] table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    German,
+    French,
+    Spanish,
+    Italian,
+    Portuguese,
+    Polish,
+    Russian,
+    Japanese,
+    Korean,
+    /// Simplified Chinese.
+    Chinese,
+}
+
+impl Language {
+    pub const ALL: [Language; 11] = [
+        Language::English,
+        Language::German,
+        Language::French,
+        Language::Spanish,
+        Language::Italian,
+        Language::Portuguese,
+        Language::Polish,
+        Language::Russian,
+        Language::Japanese,
+        Language::Korean,
+        Language::Chinese,
+    ];
+
+    /// ISO 639-1 code, as written in the `language` setting.
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::French => "fr",
+            Language::Spanish => "es",
+            Language::Italian => "it",
+            Language::Portuguese => "pt",
+            Language::Polish => "pl",
+            Language::Russian => "ru",
+            Language::Japanese => "ja",
+            Language::Korean => "ko",
+            Language::Chinese => "zh",
+        }
+    }
+
+    /// Parse a language code or locale name (`de`, `de-AT`, `pt_BR`, any
+    /// case). Traditional Chinese locales have no table and give `None`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase().replace('_', "-");
+        if matches!(
+            value.as_str(),
+            "zh-tw" | "zh-hk" | "zh-mo" | "zh-hant" | "zh-hant-tw" | "zh-hant-hk"
+        ) {
+            return None;
+        }
+        let primary = value.split('-').next().unwrap_or_default();
+        Self::ALL.into_iter().find(|lang| lang.code() == primary)
+    }
+
+    /// Language of a Windows `LANGID`, by its primary language.
+    pub fn from_langid(langid: u16) -> Option<Self> {
+        const SUBLANG_CHINESE_SIMPLIFIED: u16 = 0x02;
+        const SUBLANG_CHINESE_SINGAPORE: u16 = 0x04;
+        match langid & 0x3ff {
+            0x09 => Some(Language::English),
+            0x07 => Some(Language::German),
+            0x0c => Some(Language::French),
+            0x0a => Some(Language::Spanish),
+            0x10 => Some(Language::Italian),
+            0x16 => Some(Language::Portuguese),
+            0x15 => Some(Language::Polish),
+            0x19 => Some(Language::Russian),
+            0x11 => Some(Language::Japanese),
+            0x12 => Some(Language::Korean),
+            0x04 if matches!(
+                langid >> 10,
+                SUBLANG_CHINESE_SIMPLIFIED | SUBLANG_CHINESE_SINGAPORE
+            ) =>
+            {
+                Some(Language::Chinese)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn strings(self) -> &'static Strings {
+        match self {
+            Language::English => &ENGLISH,
+            Language::German => &GERMAN,
+            Language::French => &FRENCH,
+            Language::Spanish => &SPANISH,
+            Language::Italian => &ITALIAN,
+            Language::Portuguese => &PORTUGUESE,
+            Language::Polish => &POLISH,
+            Language::Russian => &RUSSIAN,
+            Language::Japanese => &JAPANESE,
+            Language::Korean => &KOREAN,
+            Language::Chinese => &CHINESE,
+        }
+    }
+}
+
+/// Texts of one language. `{count}`, `{failed}` and `{found}` are filled
+/// in by the methods that use them.
+#[derive(Debug)]
+pub struct Strings {
+    /// Default `toast_body`.
+    pub toast_body: &'static str,
+    pub no_monitors: &'static str,
+    pub all_reapplied: &'static str,
+    reapplied_count: &'static str,
+    monitors_failed: &'static str,
+}
+
+impl Strings {
+    /// "Profile refreshed for 2 monitor(s)."
+    pub fn reapplied_count(&self, count: usize) -> String {
+        self.reapplied_count.replace("{count}", &count.to_string())
+    }
+
+    /// "1 of 2 monitor(s) failed", without the error.
+    pub fn monitors_failed(&self, failed: usize, found: usize) -> String {
+        self.monitors_failed
+            .replace("{failed}", &failed.to_string())
+            .replace("{found}", &found.to_string())
+    }
+}
+
+pub const ENGLISH: Strings = Strings {
+    toast_body: "Color profile reapplied ✓",
+    no_monitors: "No matching monitors found.",
+    all_reapplied: "All profiles reapplied.",
+    reapplied_count: "Profile refreshed for {count} monitor(s).",
+    monitors_failed: "{failed} of {found} monitor(s) failed",
+};
+
+const GERMAN: Strings = Strings {
+    toast_body: "Farbprofil erneut angewendet ✓",
+    no_monitors: "Keine passenden Monitore gefunden.",
+    all_reapplied: "Alle Profile erneut angewendet.",
+    reapplied_count: "Profil für {count} Monitor(e) aktualisiert.",
+    monitors_failed: "{failed} von {found} Monitor(en) fehlgeschlagen",
+};
+
+const FRENCH: Strings = Strings {
+    toast_body: "Profil de couleur réappliqué ✓",
+    no_monitors: "Aucun moniteur correspondant trouvé.",
+    all_reapplied: "Tous les profils ont été réappliqués.",
+    reapplied_count: "Profil actualisé pour {count} moniteur(s).",
+    monitors_failed: "Échec sur {failed} moniteur(s) sur {found}",
+};
+
+const SPANISH: Strings = Strings {
+    toast_body: "Perfil de color reaplicado ✓",
+    no_monitors: "No se encontraron monitores coincidentes.",
+    all_reapplied: "Todos los perfiles se han reaplicado.",
+    reapplied_count: "Perfil actualizado en {count} monitor(es).",
+    monitors_failed: "Fallaron {failed} de {found} monitor(es)",
+};
+
+const ITALIAN: Strings = Strings {
+    toast_body: "Profilo colore riapplicato ✓",
+    no_monitors: "Nessun monitor corrispondente trovato.",
+    all_reapplied: "Tutti i profili sono stati riapplicati.",
+    reapplied_count: "Profilo aggiornato per {count} monitor.",
+    monitors_failed: "{failed} monitor su {found} non riusciti",
+};
+
+const PORTUGUESE: Strings = Strings {
+    toast_body: "Perfil de cor reaplicado ✓",
+    no_monitors: "Nenhum monitor correspondente encontrado.",
+    all_reapplied: "Todos os perfis foram reaplicados.",
+    reapplied_count: "Perfil atualizado em {count} monitor(es).",
+    monitors_failed: "Falha em {failed} de {found} monitor(es)",
+};
+
+const POLISH: Strings = Strings {
+    toast_body: "Profil kolorów zastosowany ponownie ✓",
+    no_monitors: "Nie znaleziono pasujących monitorów.",
+    all_reapplied: "Wszystkie profile zastosowano ponownie.",
+    reapplied_count: "Odświeżono profil na monitorach: {count}.",
+    monitors_failed: "Niepowodzenie na {failed} z {found} monitorów",
+};
+
+const RUSSIAN: Strings = Strings {
+    toast_body: "Цветовой профиль применён повторно ✓",
+    no_monitors: "Подходящие мониторы не найдены.",
+    all_reapplied: "Все профили применены повторно.",
+    reapplied_count: "Профиль обновлён на мониторах: {count}.",
+    monitors_failed: "Сбой на {failed} из {found} мониторов",
+};
+
+const JAPANESE: Strings = Strings {
+    toast_body: "カラープロファイルを再適用しました ✓",
+    no_monitors: "一致するモニターが見つかりません。",
+    all_reapplied: "すべてのプロファイルを再適用しました。",
+    reapplied_count: "{count} 台のモニターでプロファイルを更新しました。",
+    monitors_failed: "{found} 台中 {failed} 台のモニターで失敗しました",
+};
+
+const KOREAN: Strings = Strings {
+    toast_body: "색 프로필을 다시 적용했습니다 ✓",
+    no_monitors: "일치하는 모니터를 찾을 수 없습니다.",
+    all_reapplied: "모든 프로필을 다시 적용했습니다.",
+    reapplied_count: "모니터 {count}대의 프로필을 새로 고쳤습니다.",
+    monitors_failed: "모니터 {found}대 중 {failed}대 실패",
+};
+
+const CHINESE: Strings = Strings {
+    toast_body: "已重新应用颜色配置文件 ✓",
+    no_monitors: "未找到匹配的显示器。",
+    all_reapplied: "已重新应用所有配置文件。",
+    reapplied_count: "已为 {count} 台显示器刷新配置文件。",
+    monitors_failed: "{found} 台显示器中有 {failed} 台失败",
+};
+
+/// Language for `cfg`: the `language` setting, else the system's.
+pub fn language(cfg: &Config) -> Language {
+    resolve(&cfg.language, system_language())
+}
+
+/// The `language` setting when it names a known language, `system` when
+/// it is empty, English otherwise.
+pub fn resolve(setting: &str, system: Option<Language>) -> Language {
+    if setting.trim().is_empty() {
+        system.unwrap_or(Language::English)
+    } else {
+        Language::parse(setting).unwrap_or(Language::English)
+    }
+}
+
+/// Texts for `cfg`'s language.
+pub fn strings(cfg: &Config) -> &'static Strings {
+    language(cfg).strings()
+}
+
+/// The toast body to show: the translated default while `toast_body` is
+/// the English default, the configured text otherwise.
+pub fn toast_body(cfg: &Config) -> &str {
+    if cfg.toast_body == ENGLISH.toast_body {
+        strings(cfg).toast_body
+    } else {
+        &cfg.toast_body
+    }
+}
+
+/// Windows display language of the current user, looked up once. For the
+/// service that is the system's default.
+#[cfg(windows)]
+pub fn system_language() -> Option<Language> {
+    static SYSTEM: OnceLock<Option<Language>> = OnceLock::new();
+    *SYSTEM.get_or_init(|| {
+        let langid = unsafe { windows::Win32::Globalization::GetUserDefaultUILanguage() };
+        Language::from_langid(langid)
+    })
+}
+
+/// `LANG`-style environment locale where there is no Windows API.
+#[cfg(not(windows))]
+pub fn system_language() -> Option<Language> {
+    static SYSTEM: OnceLock<Option<Language>> = OnceLock::new();
+    *SYSTEM.get_or_init(|| {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|key| std::env::var(key).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Language::parse(value.split('.').next().unwrap_or_default()))
+    })
+}
+
+#[cfg(test)]
+#[path = "tests/i18n_tests.rs"]
+mod tests;
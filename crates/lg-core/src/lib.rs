@@ -1,4 +1,26 @@
 //! Core types and configuration for the LG UltraGear color profile tool.
 
+pub mod audit;
+pub mod bench;
+pub mod color_dir;
+pub mod colorstore;
 pub mod config;
+pub mod counters;
+pub mod ddc_script;
+pub mod display_gate;
+pub mod i18n;
+pub mod measure;
+pub mod pipeline;
+pub mod plan;
+pub mod preset;
+pub mod progress;
+pub mod quirks;
+pub mod report;
+pub mod resource_audit;
+pub mod schedule;
+pub mod shutdown;
 pub mod state;
+pub mod telemetry;
+pub mod toast_template;
+pub mod validate;
+pub mod wide;
@@ -0,0 +1,276 @@
+//! Screen luminance readings taken after a reapply.
+//!
+//! With `measure_command` set (for example ArgyllCMS `spotread -x -O`), the
+//! service and `apply` run it after each successful reapply and parse the
+//! luminance from its output; `measure add` records a value read off any
+//! other meter. Readings are appended to `state\luminance.jsonl` and
+//! summarized per day by `service status --stats`, so a panel that still
+//! dims shows up as a falling minimum over time.
+
+use crate::config::Config;
+use crate::state;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Reading source for values parsed from `measure_command`.
+pub const SOURCE_COMMAND: &str = "command";
+/// Reading source for values entered by the user.
+pub const SOURCE_MANUAL: &str = "manual";
+
+/// How often a running measurement command is checked for exit.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// One luminance reading.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LuminanceReading {
+    /// RFC 3339 time of the reading.
+    pub at: String,
+    /// Luminance in cd/m².
+    pub cd_m2: f64,
+    /// [`SOURCE_COMMAND`] or [`SOURCE_MANUAL`].
+    pub source: String,
+    /// What the reading followed (`apply`, a service trigger, ...).
+    #[serde(default)]
+    pub trigger: String,
+}
+
+impl LuminanceReading {
+    /// A reading taken now.
+    pub fn now(cd_m2: f64, source: &str, trigger: &str) -> Self {
+        Self {
+            at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            cd_m2,
+            source: source.to_string(),
+            trigger: trigger.to_string(),
+        }
+    }
+
+    /// `YYYY-MM-DD` part of [`Self::at`].
+    pub fn day(&self) -> &str {
+        self.at.get(..10).unwrap_or(&self.at)
+    }
+}
+
+/// Lowest and highest reading of one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyRange {
+    pub day: String,
+    pub min: f64,
+    pub max: f64,
+    pub samples: usize,
+}
+
+pub fn luminance_log_path() -> PathBuf {
+    state::state_dir().join("luminance.jsonl")
+}
+
+/// Luminance from a measurement tool's output: `Y` of a `Yxy:` triple
+/// (`spotread -x`), else `Y` of an `XYZ:` triple (plain `spotread`), else
+/// the first number, for scripts that just print a value.
+pub fn parse_luminance_output(output: &str) -> Option<f64> {
+    let numbers_after = |label: &str| {
+        output
+            .rfind(label)
+            .map(|at| numbers(&output[at + label.len()..]))
+    };
+    let value = if let Some(yxy) = numbers_after("Yxy:") {
+        yxy.first().copied()
+    } else if let Some(xyz) = numbers_after("XYZ:") {
+        xyz.get(1).copied()
+    } else {
+        numbers(output).first().copied()
+    };
+    value.filter(|v| v.is_finite() && *v >= 0.0)
+}
+
+/// Numbers in `text`, in order; separators are anything that can't be
+/// part of a number.
+fn numbers(text: &str) -> Vec<f64> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .filter_map(|token| token.parse::<f64>().ok())
+        .collect()
+}
+
+/// Split a command line into program and arguments. Double quotes group
+/// words with spaces; there is no other escaping, so Windows paths keep
+/// their backslashes.
+pub fn split_command_line(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut in_word = false;
+    for c in command.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_word = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(current);
+    }
+    words
+}
+
+/// Run `command` and parse the luminance from its standard output. The
+/// command is killed when it runs longer than `timeout`.
+pub fn run_measure_command(command: &str, timeout: Duration) -> Result<f64, Box<dyn Error>> {
+    let words = split_command_line(command);
+    let (program, args) = words.split_first().ok_or("measure_command is empty")?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("cannot run {}: {}", program, e))?;
+
+    // Read on a thread so a chatty tool can't fill the pipe and stall.
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or("no stdout from measure_command")?;
+    let reader = thread::spawn(move || {
+        let mut text = String::new();
+        let _ = stdout.read_to_string(&mut text);
+        text
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!("{} timed out after {} ms", program, timeout.as_millis()).into());
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+    let output = reader.join().unwrap_or_default();
+    if !status.success() {
+        return Err(format!("{} exited with {}", program, status).into());
+    }
+    parse_luminance_output(&output)
+        .ok_or_else(|| format!("no luminance value in the output of {}", program).into())
+}
+
+/// Run `cfg.measure_command` after a reapply and log the reading under
+/// `trigger`. `None` when no command is configured.
+pub fn measure_after_reapply(cfg: &Config, trigger: &str) -> Option<Result<f64, Box<dyn Error>>> {
+    if cfg.measure_command.trim().is_empty() {
+        return None;
+    }
+    let timeout = Duration::from_millis(cfg.measure_timeout_ms);
+    Some(
+        run_measure_command(&cfg.measure_command, timeout).and_then(|cd_m2| {
+            append_reading(&LuminanceReading::now(cd_m2, SOURCE_COMMAND, trigger))?;
+            Ok(cd_m2)
+        }),
+    )
+}
+
+/// Append `reading` to [`luminance_log_path`].
+pub fn append_reading(reading: &LuminanceReading) -> Result<(), Box<dyn Error>> {
+    append_reading_to(&luminance_log_path(), reading)
+}
+
+/// Append `reading` as one JSON line to `path` (creates the parent
+/// directory).
+pub fn append_reading_to(path: &Path, reading: &LuminanceReading) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(reading)?)?;
+    Ok(())
+}
+
+/// All readings in [`luminance_log_path`], oldest first.
+pub fn read_readings() -> Result<Vec<LuminanceReading>, Box<dyn Error>> {
+    read_readings_from(&luminance_log_path())
+}
+
+/// Readings in `path`, oldest first; a missing file has none and lines
+/// that don't parse are skipped.
+pub fn read_readings_from(path: &Path) -> Result<Vec<LuminanceReading>, Box<dyn Error>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let reader = BufReader::new(fs::File::open(path)?);
+    Ok(reader
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect())
+}
+
+/// Min/max per day of `readings`, oldest day first.
+pub fn daily_ranges(readings: &[LuminanceReading]) -> Vec<DailyRange> {
+    let mut ranges: Vec<DailyRange> = Vec::new();
+    for reading in readings {
+        match ranges.iter_mut().find(|r| r.day == reading.day()) {
+            Some(range) => {
+                range.min = range.min.min(reading.cd_m2);
+                range.max = range.max.max(reading.cd_m2);
+                range.samples += 1;
+            }
+            None => ranges.push(DailyRange {
+                day: reading.day().to_string(),
+                min: reading.cd_m2,
+                max: reading.cd_m2,
+                samples: 1,
+            }),
+        }
+    }
+    ranges.sort_by(|a, b| a.day.cmp(&b.day));
+    ranges
+}
+
+/// A `width`-column bar marking `range` on a scale from the lowest to the
+/// highest value across `all`.
+pub fn range_bar(range: &DailyRange, all: &[DailyRange], width: usize) -> String {
+    let width = width.max(1);
+    let lo = all.iter().map(|r| r.min).fold(range.min, f64::min);
+    let hi = all.iter().map(|r| r.max).fold(range.max, f64::max);
+    let column = |value: f64| {
+        if hi > lo {
+            (((value - lo) / (hi - lo)) * (width - 1) as f64).round() as usize
+        } else {
+            (width - 1) / 2
+        }
+    };
+    let (start, end) = (column(range.min), column(range.max));
+    (0..width)
+        .map(|i| {
+            if (start..=end).contains(&i) {
+                '█'
+            } else {
+                '·'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "tests/measure_tests.rs"]
+mod tests;
@@ -0,0 +1,331 @@
+//! The reapply sequence shared by the service, `apply` and the TUI.
+//!
+//! A reapply always runs the same steps: ensure the ICC profiles, find the
+//! matching monitors, associate the SDR/HDR profiles with each one (the
+//! toggle that makes Windows reload them), refresh the display, set DDC/CI
+//! brightness and show the toast. [`ReapplyPipeline`] owns that order and
+//! the rules between the steps; the platform calls come from a
+//! [`ReapplySteps`] implementation, so this crate stays free of Windows
+//! code and the sequence can be tested with a fake.
+//!
+//! Per-monitor failures don't stop the run: the remaining monitors are
+//! still done, and refresh, DDC and toast follow as long as at least one
+//! monitor was reapplied. Each caller reports results its own way from
+//! [`ReapplySteps::monitor_done`] and the returned [`ReapplyOutcome`].
+
+use crate::report::StepResult;
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Report step name for generating a monitor's own profiles.
+pub const STEP_MONITOR_PROFILES: &str = "monitor_profiles";
+/// Report step name for the SDR/HDR association toggle.
+pub const STEP_MODE_ASSOCIATIONS: &str = "mode_associations";
+/// Report step name for setting the generic default profile.
+pub const STEP_GENERIC_DEFAULT: &str = "generic_default";
+
+/// SDR and HDR profile paths, shared or for one monitor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModeProfiles {
+    pub sdr: PathBuf,
+    pub hdr: PathBuf,
+}
+
+impl ModeProfiles {
+    /// The same profile for both modes.
+    pub fn single(path: PathBuf) -> Self {
+        Self {
+            hdr: path.clone(),
+            sdr: path,
+        }
+    }
+
+    /// The profile for the current mode.
+    pub fn active(&self, hdr_active: bool) -> &Path {
+        if hdr_active {
+            &self.hdr
+        } else {
+            &self.sdr
+        }
+    }
+}
+
+/// What a reapply does besides associating profiles.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReapplyOptions {
+    /// Associate in the current user's scope instead of system-wide.
+    pub per_user: bool,
+    /// Associate the HDR profile for HDR mode; off skips the HDR
+    /// association and makes the SDR profile current in both modes.
+    pub hdr: bool,
+    /// HDR is on right now, so the HDR profile is made current.
+    pub hdr_active: bool,
+    /// Also make the current profile each monitor's generic default.
+    pub generic_default: bool,
+    /// DDC/CI brightness to set after the refresh.
+    pub ddc_brightness: Option<u32>,
+    /// Show the reapply toast.
+    pub toast: bool,
+}
+
+/// Platform side of a reapply, called by [`ReapplyPipeline::run`].
+pub trait ReapplySteps {
+    type Monitor;
+
+    /// Generate and install the profiles every monitor shares. `Ok(None)`
+    /// when profiles are generated per monitor instead.
+    fn ensure_shared_profiles(&mut self) -> Result<Option<ModeProfiles>, Box<dyn Error>>;
+
+    /// True when `path` is registered in the color store.
+    fn is_installed(&self, path: &Path) -> bool;
+
+    /// Monitors to reapply to.
+    fn find_monitors(&mut self) -> Result<Vec<Self::Monitor>, Box<dyn Error>>;
+
+    /// Carry associations over from an older instance of `monitor` (a new
+    /// device key after a driver update). Failures are the caller's to log.
+    fn inherit_associations(&mut self, _monitor: &Self::Monitor) {}
+
+    /// Generate and install `monitor`'s own profiles.
+    fn ensure_monitor_profiles(
+        &mut self,
+        monitor: &Self::Monitor,
+    ) -> Result<ModeProfiles, Box<dyn Error>>;
+
+    /// Associate `sdr` (and `hdr` for HDR mode, when given) with `monitor`
+    /// and toggle `active` so Windows reloads it.
+    fn associate(
+        &mut self,
+        monitor: &Self::Monitor,
+        active: &Path,
+        sdr: &Path,
+        hdr: Option<&Path>,
+        per_user: bool,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Make `active` the generic default for `monitor`.
+    fn set_generic_default(
+        &mut self,
+        monitor: &Self::Monitor,
+        active: &Path,
+        per_user: bool,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Tell Windows and the calibration loader the profiles changed.
+    fn refresh(&mut self);
+
+    /// Set DDC/CI brightness on every monitor; returns how many took it.
+    fn set_ddc_brightness(&mut self, level: u32) -> Result<usize, Box<dyn Error>>;
+
+    /// Show the reapply toast.
+    fn toast(&mut self);
+
+    /// `monitor` is done, successfully or not.
+    fn monitor_done(&mut self, _monitor: &Self::Monitor, _result: &MonitorResult<'_>) {}
+}
+
+/// How one monitor's reapply went.
+#[derive(Debug)]
+pub struct MonitorResult<'a> {
+    /// Profiles used; `None` when generating them failed.
+    pub profiles: Option<&'a ModeProfiles>,
+    /// The profile made current.
+    pub active: Option<&'a Path>,
+    /// Steps that ran, in order, for the install report.
+    pub steps: Vec<StepResult>,
+    /// Error of the step that failed.
+    pub error: Option<&'a (dyn Error + 'static)>,
+}
+
+impl MonitorResult<'_> {
+    pub fn ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// What a finished run did.
+#[derive(Debug, Default)]
+pub struct ReapplyOutcome {
+    /// Monitors [`ReapplySteps::find_monitors`] returned.
+    pub found: usize,
+    /// Monitors reapplied without error.
+    pub applied: usize,
+    /// Current profile of the last monitor reapplied.
+    pub last_applied: Option<PathBuf>,
+    /// Message of the first per-monitor error.
+    pub first_error: Option<String>,
+    /// DDC/CI result; `None` when it wasn't attempted.
+    pub ddc: Option<Result<usize, Box<dyn Error>>>,
+    /// Whether the toast was shown.
+    pub toasted: bool,
+}
+
+impl ReapplyOutcome {
+    /// Monitors that were found but not reapplied.
+    pub fn failed(&self) -> usize {
+        self.found - self.applied
+    }
+
+    fn note_failure(&mut self, e: &dyn Error) {
+        if self.first_error.is_none() {
+            self.first_error = Some(e.to_string());
+        }
+    }
+}
+
+/// A reapply that stopped before reaching any monitor.
+#[derive(Debug)]
+pub enum ReapplyError {
+    /// Generating or installing the shared profiles failed.
+    Profiles(Box<dyn Error>),
+    /// The shared profiles were written but aren't in the color store.
+    NotInstalled(ModeProfiles),
+    /// Enumerating monitors failed.
+    Monitors(Box<dyn Error>),
+}
+
+impl fmt::Display for ReapplyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Profiles(e) => write!(f, "failed to generate active profile: {}", e),
+            Self::NotInstalled(p) => write!(
+                f,
+                "ICC mode profile not found (sdr={}, hdr={})",
+                p.sdr.display(),
+                p.hdr.display()
+            ),
+            Self::Monitors(e) => write!(f, "monitor enumeration failed: {}", e),
+        }
+    }
+}
+
+impl Error for ReapplyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Profiles(e) | Self::Monitors(e) => Some(e.as_ref()),
+            Self::NotInstalled(_) => None,
+        }
+    }
+}
+
+/// Ensure → match → associate → refresh → DDC → toast.
+#[derive(Debug, Clone, Default)]
+pub struct ReapplyPipeline {
+    options: ReapplyOptions,
+}
+
+impl ReapplyPipeline {
+    pub fn new(options: ReapplyOptions) -> Self {
+        Self { options }
+    }
+
+    pub fn options(&self) -> &ReapplyOptions {
+        &self.options
+    }
+
+    /// Run the sequence. Finding no monitors is not an error: the outcome
+    /// has `found == 0` and nothing after the match ran.
+    pub fn run<S: ReapplySteps>(&self, steps: &mut S) -> Result<ReapplyOutcome, ReapplyError> {
+        let shared = steps
+            .ensure_shared_profiles()
+            .map_err(ReapplyError::Profiles)?
+            .map(|p| self.mode_profiles(p));
+        if let Some(p) = &shared {
+            if !steps.is_installed(&p.sdr) || !steps.is_installed(&p.hdr) {
+                return Err(ReapplyError::NotInstalled(p.clone()));
+            }
+        }
+
+        let monitors = steps.find_monitors().map_err(ReapplyError::Monitors)?;
+        let mut outcome = ReapplyOutcome {
+            found: monitors.len(),
+            ..ReapplyOutcome::default()
+        };
+        if monitors.is_empty() {
+            return Ok(outcome);
+        }
+
+        for monitor in &monitors {
+            steps.inherit_associations(monitor);
+            let profiles = match &shared {
+                Some(p) => p.clone(),
+                None => match steps.ensure_monitor_profiles(monitor) {
+                    Ok(p) => self.mode_profiles(p),
+                    Err(e) => {
+                        let result = MonitorResult {
+                            profiles: None,
+                            active: None,
+                            steps: vec![StepResult::from_result(
+                                STEP_MONITOR_PROFILES,
+                                &Err::<(), _>(&e),
+                            )],
+                            error: Some(e.as_ref()),
+                        };
+                        outcome.note_failure(e.as_ref());
+                        steps.monitor_done(monitor, &result);
+                        continue;
+                    }
+                },
+            };
+            let active = profiles.active(self.options.hdr_active);
+
+            let mut done = Vec::new();
+            let hdr = Some(profiles.hdr.as_path()).filter(|_| self.options.hdr);
+            let associated =
+                steps.associate(monitor, active, &profiles.sdr, hdr, self.options.per_user);
+            done.push(StepResult::from_result(STEP_MODE_ASSOCIATIONS, &associated));
+            let failure = match associated {
+                Err(e) => Some(e),
+                Ok(()) if self.options.generic_default => {
+                    let set = steps.set_generic_default(monitor, active, self.options.per_user);
+                    done.push(StepResult::from_result(STEP_GENERIC_DEFAULT, &set));
+                    set.err()
+                }
+                Ok(()) => None,
+            };
+
+            match &failure {
+                Some(e) => outcome.note_failure(e.as_ref()),
+                None => {
+                    outcome.applied += 1;
+                    outcome.last_applied = Some(active.to_path_buf());
+                }
+            }
+            let result = MonitorResult {
+                profiles: Some(&profiles),
+                active: Some(active),
+                steps: done,
+                error: failure.as_deref(),
+            };
+            steps.monitor_done(monitor, &result);
+        }
+
+        if outcome.applied == 0 {
+            return Ok(outcome);
+        }
+        steps.refresh();
+        if let Some(level) = self.options.ddc_brightness {
+            outcome.ddc = Some(steps.set_ddc_brightness(level.min(100)));
+        }
+        if self.options.toast {
+            steps.toast();
+            outcome.toasted = true;
+        }
+        Ok(outcome)
+    }
+
+    /// `profiles` with the SDR profile standing in for HDR when HDR
+    /// association is off, so the report shows what is current.
+    fn mode_profiles(&self, profiles: ModeProfiles) -> ModeProfiles {
+        if self.options.hdr {
+            profiles
+        } else {
+            ModeProfiles::single(profiles.sdr)
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/pipeline_tests.rs"]
+mod tests;
@@ -0,0 +1,183 @@
+//! Read-only operation plans for `install` / `uninstall` / `reinstall`
+//! `--dry-run`.
+//!
+//! A [`Plan`] is the ordered list of operations a command would perform on
+//! this machine — file writes and removals, registry writes, WCS calls,
+//! service control — built by planning counterparts of the real install
+//! and uninstall steps without changing anything. File operations go
+//! through a virtual overlay, so a profile the plan has already written is
+//! seen as up to date by later steps, exactly as it would be on disk.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// What an operation touches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpKind {
+    File,
+    Registry,
+    Wcs,
+    /// GDI display calls (`SetICMProfileW`, gamma ramps).
+    Display,
+    Service,
+}
+
+impl OpKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            OpKind::File => "file",
+            OpKind::Registry => "registry",
+            OpKind::Wcs => "wcs",
+            OpKind::Display => "display",
+            OpKind::Service => "service",
+        }
+    }
+}
+
+/// One planned operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlannedOp {
+    pub kind: OpKind,
+    pub action: String,
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl PlannedOp {
+    /// Attach a detail (value written, parameters, why).
+    pub fn detail(&mut self, detail: impl Into<String>) -> &mut Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+impl fmt::Display for PlannedOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<8} {:<22} {}",
+            self.kind.label(),
+            self.action,
+            self.target
+        )?;
+        if let Some(detail) = &self.detail {
+            write!(f, "  ({})", detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// Ordered operations of one command, plus notes on what it depends on.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Plan {
+    pub command: String,
+    pub operations: Vec<PlannedOp>,
+    pub notes: Vec<String>,
+    /// Planned file contents (`None` = removed), layered over the disk.
+    #[serde(skip)]
+    files: BTreeMap<PathBuf, Option<Vec<u8>>>,
+}
+
+impl Plan {
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        kind: OpKind,
+        action: &str,
+        target: impl Into<String>,
+    ) -> &mut PlannedOp {
+        self.operations.push(PlannedOp {
+            kind,
+            action: action.to_string(),
+            target: target.into(),
+            detail: None,
+        });
+        self.operations.last_mut().expect("just pushed")
+    }
+
+    pub fn note(&mut self, note: impl Into<String>) {
+        self.notes.push(note.into());
+    }
+
+    /// Contents of `path` once the operations so far have run.
+    pub fn file_contents(&self, path: &Path) -> Option<Vec<u8>> {
+        match self.files.get(path) {
+            Some(planned) => planned.clone(),
+            None => std::fs::read(path).ok(),
+        }
+    }
+
+    pub fn file_exists(&self, path: &Path) -> bool {
+        match self.files.get(path) {
+            Some(planned) => planned.is_some(),
+            None => path.exists(),
+        }
+    }
+
+    /// Plan writing `contents` to `path` under `action`, unless it already
+    /// holds exactly that. Returns whether a write was planned.
+    pub fn write_file(&mut self, action: &str, path: &Path, contents: &[u8]) -> bool {
+        let detail = match self.file_contents(path) {
+            Some(existing) if existing == contents => return false,
+            Some(existing) => format!("replace {} -> {} bytes", existing.len(), contents.len()),
+            None => format!("new, {} bytes", contents.len()),
+        };
+        self.push(OpKind::File, action, path.display().to_string())
+            .detail(detail);
+        self.files
+            .insert(path.to_path_buf(), Some(contents.to_vec()));
+        true
+    }
+
+    /// Plan removing `path` under `action` if it exists. Returns whether a
+    /// removal was planned.
+    pub fn remove_file(&mut self, action: &str, path: &Path) -> bool {
+        if !self.file_exists(path) {
+            return false;
+        }
+        self.push(OpKind::File, action, path.display().to_string());
+        self.files.insert(path.to_path_buf(), None);
+        true
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Numbered text listing, as `--dry-run` prints it.
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "Plan for `{}` ({} operation(s)):\n",
+            self.command,
+            self.operations.len()
+        );
+        if self.operations.is_empty() {
+            out.push_str("  (nothing to do)\n");
+        }
+        let width = self.operations.len().to_string().len();
+        for (i, op) in self.operations.iter().enumerate() {
+            out.push_str(&format!("  {:>width$}. {}\n", i + 1, op, width = width));
+        }
+        if !self.notes.is_empty() {
+            out.push_str("Notes:\n");
+            for note in &self.notes {
+                out.push_str(&format!("  - {}\n", note));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/plan_tests.rs"]
+mod tests;
@@ -0,0 +1,123 @@
+//! Config presets: named bundles of settings applied in one step.
+//!
+//! Each preset is a small TOML file under `presets/` embedded at build
+//! time. Applying one overlays its keys on the current config and leaves
+//! every other setting alone; `default` puts back everything the other
+//! presets touch. `config preset diff <name>` shows what would change.
+
+use crate::config::Config;
+use std::fmt;
+
+/// One embedded preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Preset {
+    pub name: &'static str,
+    pub summary: &'static str,
+    /// The preset TOML, a flat table of config keys.
+    pub source: &'static str,
+}
+
+/// Every preset, in the order `config preset list` prints them.
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "kvm",
+        summary: "Quick, quiet reapply after a KVM input switch",
+        source: include_str!("../presets/kvm.toml"),
+    },
+    Preset {
+        name: "dock",
+        summary: "Longer waits and a double monitor check for docking stations",
+        source: include_str!("../presets/dock.toml"),
+    },
+    Preset {
+        name: "aggressive",
+        summary: "Reassert the profile and gamma ramp whenever anything resets them",
+        source: include_str!("../presets/aggressive.toml"),
+    },
+    Preset {
+        name: "default",
+        summary: "Put every setting the other presets change back to its default",
+        source: include_str!("../presets/default.toml"),
+    },
+];
+
+/// Look up a preset by name (case-insensitive).
+pub fn find(name: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+}
+
+/// Comma-separated preset names, for error messages.
+pub fn names() -> String {
+    PRESETS
+        .iter()
+        .map(|p| p.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// One setting a preset would change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresetChange {
+    pub key: String,
+    /// Current value, as TOML.
+    pub from: String,
+    /// Value after applying the preset, as TOML.
+    pub to: String,
+}
+
+impl fmt::Display for PresetChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {} -> {}", self.key, self.from, self.to)
+    }
+}
+
+impl Preset {
+    /// The preset's keys and values.
+    pub fn table(&self) -> Result<toml::Table, String> {
+        toml::from_str(self.source).map_err(|e| format!("preset {}: {}", self.name, e.message()))
+    }
+
+    /// Settings in `cfg` that applying this preset would change.
+    pub fn diff(&self, cfg: &Config) -> Result<Vec<PresetChange>, String> {
+        let current = config_table(cfg)?;
+        Ok(self
+            .table()?
+            .into_iter()
+            .filter_map(|(key, to)| {
+                let from = current.get(&key)?;
+                (*from != to).then(|| PresetChange {
+                    key,
+                    from: from.to_string(),
+                    to: to.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    /// `cfg` with this preset's settings applied.
+    pub fn apply(&self, cfg: &Config) -> Result<Config, String> {
+        let mut table = config_table(cfg)?;
+        for (key, value) in self.table()? {
+            if !table.contains_key(&key) {
+                return Err(format!("preset {}: unknown key `{}`", self.name, key));
+            }
+            table.insert(key, value);
+        }
+        toml::Value::Table(table)
+            .try_into()
+            .map_err(|e: toml::de::Error| format!("preset {}: {}", self.name, e.message()))
+    }
+}
+
+/// `cfg` as a TOML table, keyed by setting name.
+fn config_table(cfg: &Config) -> Result<toml::Table, String> {
+    match toml::Value::try_from(cfg) {
+        Ok(toml::Value::Table(table)) => Ok(table),
+        Ok(_) => Err("config did not serialize to a table".to_string()),
+        Err(e) => Err(format!("cannot serialize config: {}", e)),
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/preset_tests.rs"]
+mod tests;
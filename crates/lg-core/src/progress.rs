@@ -0,0 +1,77 @@
+//! Step-by-step progress of long operations.
+//!
+//! Install and uninstall can take several seconds (waiting for the old
+//! service to stop, retrying a locked binary). The operation reports each
+//! step it starts through a [`Progress`], which forwards [`ProgressEvent`]s
+//! to a caller-supplied callback: the CLI prints them as
+//! `[1/6] Stopping existing service...`, the TUI as lines with a spinner.
+
+use std::fmt;
+
+/// Something worth telling the user while an operation runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// Step `index` (1-based) of `total` has started.
+    Step {
+        index: usize,
+        total: usize,
+        label: String,
+    },
+    /// Extra information about the current step (a wait, a retry).
+    Detail(String),
+}
+
+impl fmt::Display for ProgressEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProgressEvent::Step {
+                index,
+                total,
+                label,
+            } => write!(f, "[{}/{}] {}...", index, total, label),
+            ProgressEvent::Detail(message) => write!(f, "      {}", message),
+        }
+    }
+}
+
+/// Numbers the steps of one operation and hands them to a callback.
+pub struct Progress<'a> {
+    callback: &'a mut dyn FnMut(&ProgressEvent),
+    index: usize,
+    total: usize,
+}
+
+impl<'a> Progress<'a> {
+    /// An operation of `total` steps reporting to `callback`.
+    pub fn new(total: usize, callback: &'a mut dyn FnMut(&ProgressEvent)) -> Self {
+        Self {
+            callback,
+            index: 0,
+            total,
+        }
+    }
+
+    /// Start the next step.
+    pub fn step(&mut self, label: &str) {
+        self.index += 1;
+        // Never report "[7/6]" if an operation grows a step.
+        self.total = self.total.max(self.index);
+        (self.callback)(&ProgressEvent::Step {
+            index: self.index,
+            total: self.total,
+            label: label.to_string(),
+        });
+    }
+
+    /// Report something about the current step.
+    pub fn detail(&mut self, message: impl Into<String>) {
+        (self.callback)(&ProgressEvent::Detail(message.into()));
+    }
+}
+
+/// Callback for callers that don't show progress.
+pub fn ignore(_: &ProgressEvent) {}
+
+#[cfg(test)]
+#[path = "tests/progress_tests.rs"]
+mod tests;
@@ -0,0 +1,332 @@
+//! Model quirk database.
+//!
+//! Per-model knowledge the tool acts on, kept out of the code paths that
+//! use it. The built-in entries live in `quirks/builtin.toml`, embedded at
+//! build time. Each quirk names the monitors it applies to and the factory
+//! profiles known to bring the dimming back, which `install` looks for in
+//! the existing associations, and can recommend delays, a profile and a
+//! DDC/CI fix sequence for those models. A quirk with a `warning` is shown
+//! at install and apply time on its models — for monitors where this
+//! tool's profile is known not to help, or where another community
+//! profile is the right fix.
+//!
+//! Community quirk packs — `*.toml` files of the same `[[quirk]]` tables in
+//! `quirks\` under the config directory — are loaded on first use and
+//! merged over the built-ins: a pack quirk with a built-in's name replaces
+//! it, any other is added. A pack that fails validation is skipped as a
+//! whole and reported by `quirks list`, so new models can be supported
+//! without a release.
+
+use crate::config;
+use crate::ddc_script::{self, ScriptStep};
+use crate::validate;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// [`ModelQuirk::source`] of the quirks shipped with the binary.
+pub const BUILTIN_SOURCE: &str = "built-in";
+
+/// One entry of the quirk database.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModelQuirk {
+    pub name: String,
+    /// EDID model codes (`GSM5BBF`) or monitor name substrings,
+    /// case-insensitive. Empty = every matched monitor.
+    pub models: Vec<String>,
+    /// Profile file name patterns; `*` matches any run of characters.
+    pub known_bad_profiles: Vec<String>,
+    /// Why the profiles are a problem, shown next to each finding.
+    pub note: String,
+    /// Recommended `stabilize_delay_ms` for these models.
+    pub stabilize_delay_ms: Option<u64>,
+    /// Recommended `reapply_delay_ms` for these models.
+    pub reapply_delay_ms: Option<u64>,
+    /// Recommended `profile_name`.
+    pub profile: Option<String>,
+    /// Shown at install and apply on these models.
+    pub warning: String,
+    /// Where to go instead: a quirk pack, community profile or issue URL.
+    pub more_info: String,
+    /// DDC/CI steps that fix these models, as `[[quirk.step]]` tables in
+    /// the `ddc script` format.
+    #[serde(rename = "step")]
+    pub ddc_steps: Vec<ScriptStep>,
+    /// [`BUILTIN_SOURCE`] or the pack file name.
+    #[serde(skip)]
+    pub source: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct QuirkFile {
+    #[serde(default, rename = "quirk")]
+    quirks: Vec<ModelQuirk>,
+}
+
+/// Parse a quirk TOML (`[[quirk]]` tables).
+pub fn parse_quirks(source: &str) -> Result<Vec<ModelQuirk>, String> {
+    toml::from_str::<QuirkFile>(source)
+        .map(|f| f.quirks)
+        .map_err(|e| e.message().to_string())
+}
+
+/// The quirks shipped with the binary.
+pub fn builtin() -> &'static [ModelQuirk] {
+    static BUILTIN: OnceLock<Vec<ModelQuirk>> = OnceLock::new();
+    BUILTIN.get_or_init(|| {
+        let mut quirks = parse_quirks(include_str!("../quirks/builtin.toml"))
+            .expect("built-in quirks/builtin.toml must parse");
+        for quirk in &mut quirks {
+            quirk.source = BUILTIN_SOURCE.to_string();
+        }
+        quirks
+    })
+}
+
+/// Directory community quirk packs are loaded from.
+pub fn quirks_dir() -> PathBuf {
+    config::config_dir().join("quirks")
+}
+
+/// A quirk pack that was skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackError {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// Parse and validate one pack, tagging each quirk with the file name.
+pub fn load_pack(path: &Path) -> Result<Vec<ModelQuirk>, String> {
+    let source = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut quirks = parse_quirks(&source)?;
+    if quirks.is_empty() {
+        return Err("no [[quirk]] entries".to_string());
+    }
+    let file = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    for quirk in &mut quirks {
+        quirk
+            .validate()
+            .map_err(|e| format!("quirk \"{}\": {}", quirk.name, e))?;
+        quirk.source = file.clone();
+    }
+    Ok(quirks)
+}
+
+/// Every `*.toml` pack in `dir`, in file name order. A missing directory
+/// is no packs.
+pub fn load_packs(dir: &Path) -> (Vec<ModelQuirk>, Vec<PackError>) {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+        })
+        .collect();
+    paths.sort();
+
+    let mut quirks = Vec::new();
+    let mut errors = Vec::new();
+    for path in paths {
+        match load_pack(&path) {
+            Ok(mut pack) => quirks.append(&mut pack),
+            Err(error) => errors.push(PackError { path, error }),
+        }
+    }
+    (quirks, errors)
+}
+
+/// `base` with `packs` merged over it: a pack quirk replaces the quirk of
+/// the same name (case-insensitive), any other is added at the end.
+pub fn merge(base: &[ModelQuirk], packs: Vec<ModelQuirk>) -> Vec<ModelQuirk> {
+    let mut merged = base.to_vec();
+    for quirk in packs {
+        match merged
+            .iter_mut()
+            .find(|q| q.name.eq_ignore_ascii_case(&quirk.name))
+        {
+            Some(existing) => *existing = quirk,
+            None => merged.push(quirk),
+        }
+    }
+    merged
+}
+
+/// The merged quirk database and the packs that were skipped.
+#[derive(Debug, Default)]
+pub struct QuirkDatabase {
+    pub quirks: Vec<ModelQuirk>,
+    pub errors: Vec<PackError>,
+}
+
+/// Built-in quirks merged with the packs in [`quirks_dir`], loaded once per
+/// process.
+pub fn database() -> &'static QuirkDatabase {
+    static DATABASE: OnceLock<QuirkDatabase> = OnceLock::new();
+    DATABASE.get_or_init(|| {
+        let (packs, errors) = load_packs(&quirks_dir());
+        for e in &errors {
+            log::warn!("Skipping quirk pack {}: {}", e.path.display(), e.error);
+        }
+        QuirkDatabase {
+            quirks: merge(builtin(), packs),
+            errors,
+        }
+    })
+}
+
+/// Every quirk in effect: built-ins plus community packs.
+pub fn all() -> &'static [ModelQuirk] {
+    &database().quirks
+}
+
+impl ModelQuirk {
+    /// Check a quirk from a pack: a name, no blank patterns, delays in the
+    /// range the config accepts, and valid DDC steps.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("name is required".to_string());
+        }
+        if self.models.iter().any(|m| m.trim().is_empty()) {
+            return Err("models has an empty entry".to_string());
+        }
+        if self.known_bad_profiles.iter().any(|p| p.trim().is_empty()) {
+            return Err("known_bad_profiles has an empty entry".to_string());
+        }
+        for (key, value) in [
+            ("stabilize_delay_ms", self.stabilize_delay_ms),
+            ("reapply_delay_ms", self.reapply_delay_ms),
+        ] {
+            let (Some(value), Some((min, max))) = (value, validate::delay_range(key)) else {
+                continue;
+            };
+            if !(min..=max).contains(&value) {
+                return Err(format!("{} {} is outside {}..{}", key, value, min, max));
+            }
+        }
+        if self.profile.as_deref().is_some_and(|p| p.trim().is_empty()) {
+            return Err("profile is empty".to_string());
+        }
+        if !self.warning.trim().is_empty() && self.models.is_empty() {
+            return Err("a warning needs models; it would show for every monitor".to_string());
+        }
+        ddc_script::validate_steps(&self.ddc_steps)
+    }
+
+    /// Whether this quirk applies to a monitor, by friendly name or EDID
+    /// model code.
+    pub fn applies_to(&self, monitor_name: &str, model_code: Option<&str>) -> bool {
+        let name = monitor_name.to_lowercase();
+        self.models.is_empty()
+            || self.models.iter().any(|m| {
+                let m = m.trim().to_lowercase();
+                !m.is_empty()
+                    && (model_code.is_some_and(|c| c.eq_ignore_ascii_case(&m)) || name.contains(&m))
+            })
+    }
+
+    /// Whether `profile` (a file name) is one of this quirk's known-bad
+    /// profiles.
+    pub fn flags_profile(&self, profile: &str) -> bool {
+        self.known_bad_profiles
+            .iter()
+            .any(|pattern| wildcard_match(pattern, profile))
+    }
+}
+
+/// A known-bad profile associated with a monitor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownBadProfile {
+    pub profile: String,
+    pub quirk: String,
+    pub note: String,
+}
+
+/// The profiles in `associated` that a quirk applying to this monitor
+/// flags as known-bad, each reported once.
+pub fn find_known_bad_profiles(
+    quirks: &[ModelQuirk],
+    monitor_name: &str,
+    model_code: Option<&str>,
+    associated: &[String],
+) -> Vec<KnownBadProfile> {
+    let applicable: Vec<&ModelQuirk> = quirks
+        .iter()
+        .filter(|q| q.applies_to(monitor_name, model_code))
+        .collect();
+    associated
+        .iter()
+        .filter_map(|profile| {
+            let quirk = applicable.iter().find(|q| q.flags_profile(profile))?;
+            Some(KnownBadProfile {
+                profile: profile.clone(),
+                quirk: quirk.name.clone(),
+                note: quirk.note.clone(),
+            })
+        })
+        .collect()
+}
+
+/// A model warning for a connected monitor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelWarning {
+    pub quirk: String,
+    pub warning: String,
+    pub profile: Option<String>,
+    pub more_info: String,
+}
+
+/// Warnings of the quirks that name this monitor's model. Quirks without
+/// `models` never warn, so a catch-all quirk can't show on every monitor.
+pub fn model_warnings(
+    quirks: &[ModelQuirk],
+    monitor_name: &str,
+    model_code: Option<&str>,
+) -> Vec<ModelWarning> {
+    quirks
+        .iter()
+        .filter(|q| !q.warning.trim().is_empty() && !q.models.is_empty())
+        .filter(|q| q.applies_to(monitor_name, model_code))
+        .map(|q| ModelWarning {
+            quirk: q.name.clone(),
+            warning: q.warning.trim().to_string(),
+            profile: q.profile.clone(),
+            more_info: q.more_info.trim().to_string(),
+        })
+        .collect()
+}
+
+/// Case-insensitive match where `*` in `pattern` matches any run of
+/// characters (including none).
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    let text = text.trim().to_lowercase();
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+#[path = "tests/quirks_tests.rs"]
+mod tests;
@@ -0,0 +1,247 @@
+//! Machine-readable install/apply reports.
+//!
+//! With `install_report` on, each `install`, `apply` and service reapply
+//! writes a JSON description of what it did to `reports\last-<action>.json`
+//! in the config directory: profile paths and ICC profile IDs, the registry
+//! scope, device keys, and the result of each association step. DisplayCAL
+//! and ArgyllCMS users can diff that against `dispwin`/`iccdump` output
+//! instead of trusting the console.
+//!
+//! The service also keeps its most recent reapply attempts in memory
+//! ([`ReapplyHistory`]) and hands them to `service history` over the
+//! control pipe.
+
+use crate::config;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+/// Bumped when a field changes meaning or is removed (additions are fine).
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// WCS scope the profile associations are written to.
+pub const SCOPE_SYSTEM_WIDE: &str = "system_wide";
+
+/// Offset and length of the Profile ID (MD5) in the ICC header.
+const ICC_PROFILE_ID: std::ops::Range<usize> = 84..100;
+
+/// One install or apply run.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct InstallReport {
+    pub schema_version: u32,
+    pub generated_at: String,
+    pub tool_version: String,
+    /// `install` or `apply`.
+    pub action: String,
+    /// `cli` or `service`.
+    pub source: String,
+    pub config_path: String,
+    pub color_directory: String,
+    /// WCS association scope (`system_wide`).
+    pub registry_scope: String,
+    /// Association API family in use (modern `ColorProfile*` or legacy `Wcs*`).
+    pub association_api: String,
+    pub per_user_requested: bool,
+    pub hdr_mode_active: bool,
+    pub profiles: Vec<ProfileEntry>,
+    pub devices: Vec<DeviceEntry>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// A profile file the run installed or associated.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ProfileEntry {
+    /// `sdr`, `hdr`, `active`, or `custom`.
+    pub role: String,
+    pub path: String,
+    pub file_name: String,
+    pub exists: bool,
+    pub size_bytes: Option<u64>,
+    /// ICC header Profile ID (MD5) as hex; `None` when the field is zero.
+    pub icc_profile_id: Option<String>,
+}
+
+/// A monitor the run touched.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeviceEntry {
+    pub name: String,
+    pub device_key: String,
+    pub active_profile: String,
+    pub sdr_profile: String,
+    pub hdr_profile: String,
+    pub steps: Vec<StepResult>,
+}
+
+/// Outcome of one API step for a device.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepResult {
+    pub step: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+impl StepResult {
+    pub fn from_result<T, E: std::fmt::Display>(step: &str, result: &Result<T, E>) -> Self {
+        Self {
+            step: step.to_string(),
+            ok: result.is_ok(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        }
+    }
+}
+
+impl InstallReport {
+    /// Empty report for `action` run from `source`, stamped now.
+    pub fn new(action: &str, source: &str) -> Self {
+        Self {
+            schema_version: REPORT_SCHEMA_VERSION,
+            generated_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            action: action.to_string(),
+            source: source.to_string(),
+            config_path: config::config_path().display().to_string(),
+            color_directory: String::new(),
+            registry_scope: SCOPE_SYSTEM_WIDE.to_string(),
+            association_api: String::new(),
+            per_user_requested: false,
+            hdr_mode_active: false,
+            profiles: Vec::new(),
+            devices: Vec::new(),
+            success: false,
+            error: None,
+        }
+    }
+
+    /// Add the profile at `path` under `role`, once per role and path.
+    pub fn add_profile(&mut self, role: &str, path: &Path) {
+        let display = path.display().to_string();
+        if !self
+            .profiles
+            .iter()
+            .any(|p| p.role == role && p.path == display)
+        {
+            self.profiles.push(ProfileEntry::from_path(role, path));
+        }
+    }
+}
+
+impl ProfileEntry {
+    /// Describe the profile at `path` from its contents on disk.
+    pub fn from_path(role: &str, path: &Path) -> Self {
+        let contents = std::fs::read(path).ok();
+        Self {
+            role: role.to_string(),
+            path: path.display().to_string(),
+            file_name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            exists: contents.is_some(),
+            size_bytes: contents.as_ref().map(|c| c.len() as u64),
+            icc_profile_id: contents.as_deref().and_then(icc_profile_id),
+        }
+    }
+}
+
+/// One service reapply attempt, as kept in [`ReapplyHistory`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReapplyRecord {
+    /// RFC 3339 start time.
+    pub at: String,
+    /// Trigger name (`startup`, `event`, `hotkey`, ...).
+    pub trigger: String,
+    /// Names of the event flags that were coalesced into this attempt.
+    pub events: Vec<String>,
+    pub duration_ms: u64,
+    pub success: bool,
+    /// Why nothing was applied (`no matching monitors`, ...), if so.
+    pub skipped: Option<String>,
+    pub devices: Vec<DeviceEntry>,
+    /// Screen luminance measured afterwards (cd/m²), when `measure_command`
+    /// is set and the reading succeeded.
+    #[serde(default)]
+    pub luminance_cd_m2: Option<f64>,
+}
+
+/// Fixed-size buffer of the most recent reapply attempts, oldest first.
+#[derive(Debug, Clone)]
+pub struct ReapplyHistory {
+    capacity: usize,
+    records: VecDeque<ReapplyRecord>,
+}
+
+impl ReapplyHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            records: VecDeque::new(),
+        }
+    }
+
+    /// Add `record`, dropping the oldest one when full.
+    pub fn push(&mut self, record: ReapplyRecord) {
+        while self.records.len() >= self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    /// The last `limit` records (all with `None`), oldest first.
+    pub fn recent(&self, limit: Option<usize>) -> Vec<ReapplyRecord> {
+        let skip = limit.map_or(0, |n| self.records.len().saturating_sub(n));
+        self.records.iter().skip(skip).cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+/// Profile ID from an ICC header as lowercase hex, or `None` when the file
+/// is too short or the ID was never computed (all zero).
+pub fn icc_profile_id(contents: &[u8]) -> Option<String> {
+    let id = contents.get(ICC_PROFILE_ID)?;
+    if id.iter().all(|&b| b == 0) {
+        return None;
+    }
+    Some(id.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+pub fn reports_dir() -> PathBuf {
+    config::config_dir().join("reports")
+}
+
+/// `reports\last-<action>.json` in the config directory.
+pub fn report_path(action: &str) -> PathBuf {
+    reports_dir().join(format!("last-{}.json", action))
+}
+
+/// Write `report` to [`report_path`] for its action.
+pub fn write_install_report(report: &InstallReport) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = report_path(&report.action);
+    write_install_report_to(report, &path)?;
+    Ok(path)
+}
+
+/// Write `report` as pretty JSON to `path` (creates the parent directory).
+pub fn write_install_report_to(
+    report: &InstallReport,
+    path: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(report)?;
+    std::fs::write(path, json + "\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "tests/report_tests.rs"]
+mod tests;
@@ -0,0 +1,175 @@
+//! Resource audit: spot handle and memory leaks in a long-running process.
+//!
+//! The service is expected to run for months, so a reapply that leaks one
+//! handle or a few kilobytes adds up. With `resource_audit_interval_minutes`
+//! set, the service takes a [`sample`] of its handle count and private
+//! bytes at that interval and feeds it to a [`ResourceAudit`], which reports
+//! growth that goes beyond normal fluctuation. The soak test in
+//! `tests/soak.rs` uses the same pieces to run thousands of simulated
+//! reapplies and fail on growth.
+
+use std::fmt;
+
+/// Handle count increase that counts as growth.
+pub const HANDLE_GROWTH_STEP: u32 = 64;
+
+/// Private bytes increase that counts as growth.
+pub const PRIVATE_BYTES_GROWTH_STEP: u64 = 16 * 1024 * 1024;
+
+/// Resource use of the process at one point in time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceSample {
+    /// Open kernel handles.
+    pub handles: u32,
+    /// Memory committed to this process alone.
+    pub private_bytes: u64,
+}
+
+/// Take a sample of the current process. `None` where the counters aren't
+/// available.
+#[cfg(windows)]
+pub fn sample() -> Option<ResourceSample> {
+    use windows::Win32::System::ProcessStatus::{
+        K32GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS, PROCESS_MEMORY_COUNTERS_EX,
+    };
+    use windows::Win32::System::Threading::{GetCurrentProcess, GetProcessHandleCount};
+
+    let process = unsafe { GetCurrentProcess() };
+    let mut handles = 0u32;
+    unsafe { GetProcessHandleCount(process, &mut handles) }.ok()?;
+
+    let mut counters = PROCESS_MEMORY_COUNTERS_EX {
+        cb: std::mem::size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32,
+        ..Default::default()
+    };
+    let ok = unsafe {
+        K32GetProcessMemoryInfo(
+            process,
+            &mut counters as *mut PROCESS_MEMORY_COUNTERS_EX as *mut PROCESS_MEMORY_COUNTERS,
+            counters.cb,
+        )
+    };
+    ok.as_bool().then_some(ResourceSample {
+        handles,
+        private_bytes: counters.PrivateUsage as u64,
+    })
+}
+
+#[cfg(not(windows))]
+pub fn sample() -> Option<ResourceSample> {
+    None
+}
+
+/// Growth reported by [`ResourceAudit::record`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceGrowth {
+    /// The first sample.
+    pub baseline: ResourceSample,
+    /// The sample that showed the growth.
+    pub current: ResourceSample,
+    /// Samples taken so far, including both of the above.
+    pub samples: u64,
+}
+
+impl ResourceGrowth {
+    pub fn handles_delta(&self) -> i64 {
+        i64::from(self.current.handles) - i64::from(self.baseline.handles)
+    }
+
+    pub fn private_bytes_delta(&self) -> i64 {
+        self.current.private_bytes as i64 - self.baseline.private_bytes as i64
+    }
+}
+
+impl fmt::Display for ResourceGrowth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "handles {} -> {} ({:+}), private bytes {} -> {} ({:+.1} MiB) over {} samples",
+            self.baseline.handles,
+            self.current.handles,
+            self.handles_delta(),
+            mib(self.baseline.private_bytes),
+            mib(self.current.private_bytes),
+            self.private_bytes_delta() as f64 / MIB,
+            self.samples
+        )
+    }
+}
+
+const MIB: f64 = 1024.0 * 1024.0;
+
+fn mib(bytes: u64) -> String {
+    format!("{:.1} MiB", bytes as f64 / MIB)
+}
+
+/// Tracks samples against the first one and reports growth.
+///
+/// A report is made when either figure rises a full step above the highest
+/// level reported so far, so a steady leak shows up once per step instead
+/// of on every sample, and usage that goes up and down doesn't show up at
+/// all.
+#[derive(Debug, Clone)]
+pub struct ResourceAudit {
+    handle_step: u32,
+    private_bytes_step: u64,
+    baseline: Option<ResourceSample>,
+    high_water: ResourceSample,
+    samples: u64,
+}
+
+impl Default for ResourceAudit {
+    fn default() -> Self {
+        Self::new(HANDLE_GROWTH_STEP, PRIVATE_BYTES_GROWTH_STEP)
+    }
+}
+
+impl ResourceAudit {
+    pub fn new(handle_step: u32, private_bytes_step: u64) -> Self {
+        Self {
+            handle_step: handle_step.max(1),
+            private_bytes_step: private_bytes_step.max(1),
+            baseline: None,
+            high_water: ResourceSample::default(),
+            samples: 0,
+        }
+    }
+
+    /// The first sample, once there is one.
+    pub fn baseline(&self) -> Option<ResourceSample> {
+        self.baseline
+    }
+
+    /// Add a sample; returns the growth since the first sample when it
+    /// crossed another step.
+    pub fn record(&mut self, sample: ResourceSample) -> Option<ResourceGrowth> {
+        self.samples += 1;
+        let Some(baseline) = self.baseline else {
+            self.baseline = Some(sample);
+            self.high_water = sample;
+            return None;
+        };
+        let grew = sample.handles >= self.high_water.handles.saturating_add(self.handle_step)
+            || sample.private_bytes
+                >= self
+                    .high_water
+                    .private_bytes
+                    .saturating_add(self.private_bytes_step);
+        if !grew {
+            return None;
+        }
+        self.high_water = ResourceSample {
+            handles: self.high_water.handles.max(sample.handles),
+            private_bytes: self.high_water.private_bytes.max(sample.private_bytes),
+        };
+        Some(ResourceGrowth {
+            baseline,
+            current: sample,
+            samples: self.samples,
+        })
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/resource_audit_tests.rs"]
+mod tests;
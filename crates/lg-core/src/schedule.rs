@@ -0,0 +1,292 @@
+//! Day/night schedule shared by the time-of-day features.
+//!
+//! The boundaries come either from fixed `HH:MM` times in the config or, with
+//! `[schedule] mode = "solar"`, from local sunrise/sunset at the configured
+//! location. The location can also come from the Windows Location API: the
+//! platform layer hands it over via [`set_detected_location`] or registers a
+//! lookup with [`set_location_lookup`], since this crate stays OS-agnostic.
+//! The lookup only runs the first time solar times need it, so commands that
+//! never consult the schedule don't pay for it. Solar times
+//! fall back to the fixed times on days the sun never rises or sets (polar
+//! day/night).
+
+use crate::config::Config;
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, Offset};
+use std::sync::{Mutex, OnceLock};
+
+/// Day start used when `icc_schedule_day_start` is empty or malformed.
+pub const DEFAULT_DAY_START: &str = "07:00";
+
+/// Night start used when `icc_schedule_night_start` is empty or malformed.
+pub const DEFAULT_NIGHT_START: &str = "19:00";
+
+/// `[schedule] mode` using the configured clock times.
+pub const MODE_FIXED: &str = "fixed";
+
+/// `[schedule] mode` using local sunrise/sunset.
+pub const MODE_SOLAR: &str = "solar";
+
+/// Official sunrise/sunset zenith (degrees), including refraction.
+const SUN_ZENITH_DEG: f64 = 90.833;
+
+static DETECTED_LOCATION: OnceLock<Mutex<Option<(f64, f64)>>> = OnceLock::new();
+
+/// OS lookup returning `(latitude, longitude)` in degrees.
+pub type LocationLookup = fn() -> Option<(f64, f64)>;
+
+static LOCATION_LOOKUP: OnceLock<LocationLookup> = OnceLock::new();
+
+/// Which half of the schedule is in effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DayPeriod {
+    Day,
+    Night,
+}
+
+impl DayPeriod {
+    pub fn label(self) -> &'static str {
+        match self {
+            DayPeriod::Day => "day",
+            DayPeriod::Night => "night",
+        }
+    }
+}
+
+/// Resolved boundaries for a single calendar day (local time).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DaySchedule {
+    pub day_start: NaiveTime,
+    pub night_start: NaiveTime,
+}
+
+impl DaySchedule {
+    /// Period in effect at `time`. Handles schedules where night starts
+    /// before day on the clock (e.g. day 20:00, night 08:00).
+    pub fn period_at(&self, time: NaiveTime) -> DayPeriod {
+        let daytime = if self.day_start <= self.night_start {
+            time >= self.day_start && time < self.night_start
+        } else {
+            !(time >= self.night_start && time < self.day_start)
+        };
+        if daytime {
+            DayPeriod::Day
+        } else {
+            DayPeriod::Night
+        }
+    }
+}
+
+/// True when both day and night presets are configured.
+pub fn is_enabled(cfg: &Config) -> bool {
+    !cfg.icc_schedule_day_preset.trim().is_empty()
+        && !cfg.icc_schedule_night_preset.trim().is_empty()
+}
+
+/// True when `[schedule] mode = "solar"`.
+pub fn is_solar(cfg: &Config) -> bool {
+    cfg.schedule.mode.trim().eq_ignore_ascii_case(MODE_SOLAR)
+}
+
+/// Record a position reported by the OS location service (degrees).
+pub fn set_detected_location(latitude: f64, longitude: f64) {
+    let slot = DETECTED_LOCATION.get_or_init(|| Mutex::new(None));
+    if let Ok(mut loc) = slot.lock() {
+        *loc = Some((latitude, longitude));
+    }
+}
+
+/// Register the OS location lookup. It runs at most once, the first time a
+/// solar schedule with `use_windows_location` needs a position, and only if
+/// no position was recorded with [`set_detected_location`] before that.
+pub fn set_location_lookup(lookup: LocationLookup) {
+    let _ = LOCATION_LOOKUP.set(lookup);
+}
+
+fn detected_location() -> Option<(f64, f64)> {
+    DETECTED_LOCATION
+        .get_or_init(|| Mutex::new(LOCATION_LOOKUP.get().and_then(|lookup| lookup())))
+        .lock()
+        .ok()
+        .and_then(|loc| *loc)
+}
+
+fn valid_location(latitude: f64, longitude: f64) -> bool {
+    latitude.is_finite()
+        && longitude.is_finite()
+        && latitude.abs() <= 90.0
+        && longitude.abs() <= 180.0
+}
+
+/// Location used for solar times: the detected position when
+/// `use_windows_location` is on and one was recorded, else the configured
+/// coordinates. `None` if neither is usable.
+pub fn location(cfg: &Config) -> Option<(f64, f64)> {
+    let detected = cfg
+        .schedule
+        .use_windows_location
+        .then(detected_location)
+        .flatten();
+    detected
+        .into_iter()
+        .chain(std::iter::once((
+            cfg.schedule.latitude,
+            cfg.schedule.longitude,
+        )))
+        .find(|&(lat, lon)| valid_location(lat, lon))
+}
+
+/// Local sunrise/sunset for `date` when the schedule is in solar mode.
+pub fn solar_times(
+    cfg: &Config,
+    date: NaiveDate,
+    utc_offset_minutes: i32,
+) -> Option<(NaiveTime, NaiveTime)> {
+    if !is_solar(cfg) {
+        return None;
+    }
+    let (latitude, longitude) = location(cfg)?;
+    sun_times(date, latitude, longitude, utc_offset_minutes)
+}
+
+/// Parse a 24h `HH:MM` clock time.
+pub fn parse_clock_time(value: &str) -> Option<NaiveTime> {
+    let (h, m) = value.trim().split_once(':')?;
+    NaiveTime::from_hms_opt(h.trim().parse().ok()?, m.trim().parse().ok()?, 0)
+}
+
+fn clock_or_default(value: &str, default: &str) -> NaiveTime {
+    parse_clock_time(value)
+        .or_else(|| parse_clock_time(default))
+        .unwrap_or(NaiveTime::MIN)
+}
+
+/// Sunrise and sunset for `date` at `latitude`/`longitude`, converted to
+/// local time using `utc_offset_minutes`. `None` when the sun does not rise
+/// or set that day.
+///
+/// Uses the NOAA almanac approximation, accurate to a couple of minutes
+/// outside the polar regions.
+pub fn sun_times(
+    date: NaiveDate,
+    latitude: f64,
+    longitude: f64,
+    utc_offset_minutes: i32,
+) -> Option<(NaiveTime, NaiveTime)> {
+    if !valid_location(latitude, longitude) {
+        return None;
+    }
+    let day_of_year = date.ordinal() as f64;
+    let offset_hours = utc_offset_minutes as f64 / 60.0;
+    let to_local = |utc_hours: f64| {
+        let secs = ((utc_hours + offset_hours).rem_euclid(24.0) * 3600.0).round() as u32;
+        NaiveTime::from_num_seconds_from_midnight_opt(secs % 86_400, 0)
+    };
+    let sunrise = sun_event_utc_hours(day_of_year, latitude, longitude, true)?;
+    let sunset = sun_event_utc_hours(day_of_year, latitude, longitude, false)?;
+    Some((to_local(sunrise)?, to_local(sunset)?))
+}
+
+fn sun_event_utc_hours(
+    day_of_year: f64,
+    latitude: f64,
+    longitude: f64,
+    rising: bool,
+) -> Option<f64> {
+    let lng_hour = longitude / 15.0;
+    let t = day_of_year + ((if rising { 6.0 } else { 18.0 }) - lng_hour) / 24.0;
+
+    // Sun's mean anomaly and true longitude.
+    let m = 0.9856 * t - 3.289;
+    let l = (m + 1.916 * m.to_radians().sin() + 0.020 * (2.0 * m).to_radians().sin() + 282.634)
+        .rem_euclid(360.0);
+
+    // Right ascension, moved into the same quadrant as L, in hours.
+    let mut ra = (0.91764 * l.to_radians().tan())
+        .atan()
+        .to_degrees()
+        .rem_euclid(360.0);
+    ra += (l / 90.0).floor() * 90.0 - (ra / 90.0).floor() * 90.0;
+    ra /= 15.0;
+
+    let sin_dec = 0.39782 * l.to_radians().sin();
+    let cos_dec = sin_dec.asin().cos();
+    let cos_h = (SUN_ZENITH_DEG.to_radians().cos() - sin_dec * latitude.to_radians().sin())
+        / (cos_dec * latitude.to_radians().cos());
+    if !(-1.0..=1.0).contains(&cos_h) {
+        return None;
+    }
+
+    let h = if rising {
+        360.0 - cos_h.acos().to_degrees()
+    } else {
+        cos_h.acos().to_degrees()
+    } / 15.0;
+    let local_mean = h + ra - 0.06571 * t - 6.622;
+    Some((local_mean - lng_hour).rem_euclid(24.0))
+}
+
+/// Resolve the schedule boundaries for `date`.
+pub fn schedule_for_date(cfg: &Config, date: NaiveDate, utc_offset_minutes: i32) -> DaySchedule {
+    if let Some((sunrise, sunset)) = solar_times(cfg, date, utc_offset_minutes) {
+        return DaySchedule {
+            day_start: sunrise,
+            night_start: sunset,
+        };
+    }
+    DaySchedule {
+        day_start: clock_or_default(&cfg.icc_schedule_day_start, DEFAULT_DAY_START),
+        night_start: clock_or_default(&cfg.icc_schedule_night_start, DEFAULT_NIGHT_START),
+    }
+}
+
+/// Period in effect at local time `now`.
+pub fn period_at(cfg: &Config, now: NaiveDateTime, utc_offset_minutes: i32) -> DayPeriod {
+    schedule_for_date(cfg, now.date(), utc_offset_minutes).period_at(now.time())
+}
+
+/// The next schedule boundary strictly after `now` (local time).
+pub fn next_transition_after(
+    cfg: &Config,
+    now: NaiveDateTime,
+    utc_offset_minutes: i32,
+) -> NaiveDateTime {
+    let today = now.date();
+    let tomorrow = today.succ_opt().unwrap_or(today);
+    [today, tomorrow]
+        .into_iter()
+        .flat_map(|date| {
+            let s = schedule_for_date(cfg, date, utc_offset_minutes);
+            [date.and_time(s.day_start), date.and_time(s.night_start)]
+        })
+        .filter(|at| *at > now)
+        .min()
+        .unwrap_or_else(|| tomorrow.and_time(NaiveTime::MIN))
+}
+
+fn local_now() -> (NaiveDateTime, i32) {
+    let now = Local::now();
+    (now.naive_local(), now.offset().fix().local_minus_utc() / 60)
+}
+
+/// Period in effect right now.
+pub fn current_period(cfg: &Config) -> DayPeriod {
+    let (now, offset) = local_now();
+    period_at(cfg, now, offset)
+}
+
+/// True when the day preset should be used right now.
+pub fn is_daytime_now(cfg: &Config) -> bool {
+    current_period(cfg) == DayPeriod::Day
+}
+
+/// Time remaining until the next schedule boundary.
+pub fn until_next_transition(cfg: &Config) -> std::time::Duration {
+    let (now, offset) = local_now();
+    (next_transition_after(cfg, now, offset) - now)
+        .to_std()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+#[path = "tests/schedule_tests.rs"]
+mod tests;
@@ -0,0 +1,161 @@
+//! Process-wide shutdown signal.
+//!
+//! One [`ShutdownToken`] is created per run and cloned into every worker.
+//! Whoever decides to stop (the service control handler, the Ctrl+C handler
+//! in `watch`) calls [`ShutdownToken::cancel`]; workers check
+//! [`is_cancelled`](ShutdownToken::is_cancelled) and do their waiting in
+//! [`sleep`](ShutdownToken::sleep), which returns as soon as the token is
+//! cancelled instead of at the end of the interval. Threads parked in a call
+//! the token can't interrupt (a message pump, a blocking pipe accept)
+//! register an [`on_cancel`](ShutdownToken::on_cancel) hook that unblocks them.
+//!
+//! The same token cancels one-off operations: the TUI hands a fresh token to
+//! an install or uninstall and cancels it on Esc or timeout; the operation
+//! calls [`check`](ShutdownToken::check) between steps and stops with
+//! [`Cancelled`].
+
+use std::error::Error;
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+type Hook = Box<dyn FnOnce() + Send>;
+
+#[derive(Default)]
+struct Inner {
+    cancelled: Mutex<bool>,
+    changed: Condvar,
+    hooks: Mutex<Vec<Hook>>,
+}
+
+/// Cloneable cancellation signal; every clone observes the same state.
+#[derive(Clone, Default)]
+pub struct ShutdownToken {
+    inner: Arc<Inner>,
+}
+
+impl ShutdownToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn flag(&self) -> MutexGuard<'_, bool> {
+        // A panic while holding the lock can't leave a bool half-written.
+        self.inner
+            .cancelled
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Request shutdown: wake every sleeper and run the registered hooks.
+    /// Only the first call has an effect.
+    pub fn cancel(&self) {
+        {
+            let mut cancelled = self.flag();
+            if *cancelled {
+                return;
+            }
+            *cancelled = true;
+        }
+        self.inner.changed.notify_all();
+        let hooks = std::mem::take(
+            &mut *self
+                .inner
+                .hooks
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        );
+        for hook in hooks {
+            hook();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.flag()
+    }
+
+    /// `Err(Cancelled)` once cancelled, for `?` at an operation's
+    /// cancellation points.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sleep for `duration` or until cancelled. Returns `true` when the
+    /// full duration elapsed, `false` when shutdown was requested.
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let deadline = Instant::now() + duration;
+        let mut cancelled = self.flag();
+        while !*cancelled {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return true;
+            }
+            cancelled = self
+                .inner
+                .changed
+                .wait_timeout(cancelled, remaining)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        }
+        false
+    }
+
+    /// Block until cancelled.
+    pub fn wait(&self) {
+        let mut cancelled = self.flag();
+        while !*cancelled {
+            cancelled = self
+                .inner
+                .changed
+                .wait(cancelled)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    /// Run `hook` when the token is cancelled, or right away if it already
+    /// is. Hooks run on the thread that calls [`cancel`](Self::cancel).
+    pub fn on_cancel(&self, hook: impl FnOnce() + Send + 'static) {
+        {
+            // Holding the flag lock orders this against `cancel`, so the hook
+            // is either queued before the drain or run here, never lost.
+            let cancelled = self.flag();
+            if !*cancelled {
+                self.inner
+                    .hooks
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .push(Box::new(hook));
+                return;
+            }
+        }
+        hook();
+    }
+}
+
+impl fmt::Debug for ShutdownToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShutdownToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// An operation stopped early because its token was cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("cancelled")
+    }
+}
+
+impl Error for Cancelled {}
+
+#[cfg(test)]
+#[path = "tests/shutdown_tests.rs"]
+mod tests;
@@ -3,6 +3,7 @@
 use crate::config::{self, Config};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::{self, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
@@ -21,6 +22,11 @@ pub fn diagnostics_log_path() -> PathBuf {
     state_dir().join("diagnostics.log")
 }
 
+/// Log of foreground `watch` runs.
+pub fn watch_log_path() -> PathBuf {
+    state_dir().join("watch.log")
+}
+
 pub fn guardrails_path() -> PathBuf {
     state_dir().join("ddc_guardrails.toml")
 }
@@ -37,6 +43,18 @@ pub fn automation_config_path() -> PathBuf {
     state_dir().join("automation.toml")
 }
 
+pub fn display_drivers_path() -> PathBuf {
+    state_dir().join("display_drivers.toml")
+}
+
+pub fn heartbeat_path() -> PathBuf {
+    state_dir().join("heartbeat.toml")
+}
+
+pub fn display_modes_path() -> PathBuf {
+    state_dir().join("display_modes.toml")
+}
+
 fn now_iso() -> String {
     Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
 }
@@ -55,12 +73,7 @@ fn sanitize_log_field(value: &str) -> String {
 }
 
 pub fn windows_color_directory() -> PathBuf {
-    let windir = std::env::var("WINDIR").unwrap_or_else(|_| r"C:\Windows".to_string());
-    PathBuf::from(windir)
-        .join("System32")
-        .join("spool")
-        .join("drivers")
-        .join("color")
+    crate::color_dir::directory()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -799,6 +812,467 @@ pub fn compute_apply_latency_metrics(window: usize) -> ApplyLatencyMetrics {
     }
 }
 
+/// Counts from the diagnostics log for the `uninstall --feedback` file.
+/// Only event names, sources and triggers are kept — no device names,
+/// paths or error messages.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsageSummary {
+    pub first_event: Option<String>,
+    pub last_event: Option<String>,
+    /// Reapply attempts (`apply_latency` events).
+    pub reapplies: usize,
+    pub failures: usize,
+    /// Reapplies that were skipped (`apply_skip`).
+    pub skipped: usize,
+    /// Reapplies per source (`service`, `cli`, `tui`).
+    pub by_source: BTreeMap<String, usize>,
+    /// Service reapplies per trigger.
+    pub triggers: BTreeMap<String, usize>,
+    /// Every event name in the log with its count.
+    pub events: BTreeMap<String, usize>,
+}
+
+impl UsageSummary {
+    /// Tally `events` (any order).
+    pub fn from_events(events: &[DiagnosticEvent]) -> Self {
+        let mut summary = Self::default();
+        for event in events {
+            if summary
+                .first_event
+                .as_ref()
+                .is_none_or(|first| event.timestamp < *first)
+            {
+                summary.first_event = Some(event.timestamp.clone());
+            }
+            if summary
+                .last_event
+                .as_ref()
+                .is_none_or(|last| event.timestamp > *last)
+            {
+                summary.last_event = Some(event.timestamp.clone());
+            }
+            *summary.events.entry(event.event.clone()).or_default() += 1;
+            match event.event.as_str() {
+                "apply_latency" => {
+                    summary.reapplies += 1;
+                    *summary.by_source.entry(event.source.clone()).or_default() += 1;
+                    for token in event.details.split_whitespace() {
+                        if let Some(v) = token.strip_prefix("success=") {
+                            if !matches!(v, "1" | "true" | "yes") {
+                                summary.failures += 1;
+                            }
+                        } else if let Some(v) = token.strip_prefix("trigger=") {
+                            *summary.triggers.entry(v.to_string()).or_default() += 1;
+                        }
+                    }
+                }
+                "apply_skip" => summary.skipped += 1,
+                _ => {}
+            }
+        }
+        summary
+    }
+
+    /// The summary as the plain-text feedback file.
+    pub fn render(&self, version: &str) -> String {
+        let mut out = String::new();
+        out.push_str("LG UltraGear dimming fix - usage summary\n");
+        out.push_str(
+            "Written locally by `uninstall --full --feedback`; nothing was sent anywhere.\n\
+             Attach it to an issue if you like. It holds counts only: no monitor names,\n\
+             paths or error messages.\n\n",
+        );
+        out.push_str(&format!("Version:    {}\n", version));
+        out.push_str(&format!(
+            "Period:     {} .. {}\n",
+            self.first_event.as_deref().unwrap_or("-"),
+            self.last_event.as_deref().unwrap_or("-")
+        ));
+        out.push_str(&format!(
+            "Reapplies:  {} ({} failed, {} skipped)\n",
+            self.reapplies, self.failures, self.skipped
+        ));
+        for (title, counts) in [
+            ("Reapplies by source", &self.by_source),
+            ("Service triggers", &self.triggers),
+            ("Events", &self.events),
+        ] {
+            out.push_str(&format!("\n{}:\n", title));
+            if counts.is_empty() {
+                out.push_str("  (none)\n");
+            }
+            for (name, count) in counts {
+                out.push_str(&format!("  {:<28} {}\n", name, count));
+            }
+        }
+        out
+    }
+}
+
+/// [`UsageSummary`] of the whole diagnostics log.
+pub fn summarize_usage() -> Result<UsageSummary, Box<dyn std::error::Error>> {
+    Ok(UsageSummary::from_events(&read_recent_diagnostic_events(
+        usize::MAX,
+    )?))
+}
+
+/// Display driver seen for one monitor the last time the service looked.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct DisplayDriverRecord {
+    pub device_key: String,
+    pub adapter_name: String,
+    /// PnP instance ID of the GPU (`PCI\VEN_10DE&DEV_…`); tells two
+    /// adapters of a hybrid graphics laptop apart.
+    pub adapter_instance: String,
+    pub driver_version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct DisplayDriverState {
+    pub updated_at: String,
+    pub displays: Vec<DisplayDriverRecord>,
+}
+
+/// Evidence that the display driver was updated or reinstalled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DisplayDriverChange {
+    /// Same monitor instance, different driver version.
+    Updated {
+        device_key: String,
+        from: String,
+        to: String,
+    },
+    /// A monitor of the same model came back under a new instance ID.
+    Reenumerated {
+        device_key: String,
+        previous_key: String,
+    },
+    /// The monitor is now driven by a different GPU (hybrid graphics), and
+    /// usually has a new instance ID under it. `from` and `to` are adapter
+    /// names.
+    AdapterChanged {
+        device_key: String,
+        previous_key: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl DisplayDriverChange {
+    /// Instance ID the monitor had before, when it changed.
+    pub fn previous_key(&self) -> Option<&str> {
+        match self {
+            DisplayDriverChange::Updated { .. } => None,
+            DisplayDriverChange::Reenumerated { previous_key, .. }
+            | DisplayDriverChange::AdapterChanged { previous_key, .. } => Some(previous_key),
+        }
+    }
+}
+
+pub fn load_display_driver_state() -> DisplayDriverState {
+    match fs::read_to_string(display_drivers_path()) {
+        Ok(text) => toml::from_str::<DisplayDriverState>(&text).unwrap_or_default(),
+        Err(_) => DisplayDriverState::default(),
+    }
+}
+
+pub fn save_display_driver_state(
+    state: &DisplayDriverState,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = display_drivers_path();
+    ensure_parent(&path)?;
+    let text = toml::to_string_pretty(state)?;
+    fs::write(path, text)?;
+    Ok(())
+}
+
+/// Model segment of a device key (`GSM5BBF` in `DISPLAY\GSM5BBF\5&1a2b&0&UID4352`).
+fn device_key_model(device_key: &str) -> Option<String> {
+    device_key
+        .split('\\')
+        .nth(1)
+        .filter(|model| !model.is_empty())
+        .map(|model| model.to_ascii_uppercase())
+}
+
+/// True when both records name a GPU and the GPUs differ. The adapter
+/// instance decides when both have one; older records only have the name.
+fn adapter_differs(before: &DisplayDriverRecord, now: &DisplayDriverRecord) -> bool {
+    if !before.adapter_instance.is_empty() && !now.adapter_instance.is_empty() {
+        return !before
+            .adapter_instance
+            .eq_ignore_ascii_case(&now.adapter_instance);
+    }
+    !before.adapter_name.is_empty()
+        && !now.adapter_name.is_empty()
+        && before.adapter_name != now.adapter_name
+}
+
+fn adapter_changed(before: &DisplayDriverRecord, now: &DisplayDriverRecord) -> DisplayDriverChange {
+    DisplayDriverChange::AdapterChanged {
+        device_key: now.device_key.clone(),
+        previous_key: before.device_key.clone(),
+        from: before.adapter_name.clone(),
+        to: now.adapter_name.clone(),
+    }
+}
+
+/// Compare the drivers recorded last time with the ones seen now. The
+/// first run (nothing recorded) reports no changes, and an unknown driver
+/// version on either side is not treated as an update. A monitor that moved
+/// to another GPU is reported as such rather than as an update or a
+/// re-enumeration.
+pub fn detect_display_driver_changes(
+    previous: &[DisplayDriverRecord],
+    current: &[DisplayDriverRecord],
+) -> Vec<DisplayDriverChange> {
+    let same_key = |a: &DisplayDriverRecord, b: &DisplayDriverRecord| {
+        a.device_key.eq_ignore_ascii_case(&b.device_key)
+    };
+    let mut changes = Vec::new();
+    let mut claimed: Vec<&str> = Vec::new();
+    for now in current {
+        if let Some(before) = previous.iter().find(|p| same_key(p, now)) {
+            if adapter_differs(before, now) {
+                changes.push(adapter_changed(before, now));
+            } else if !before.driver_version.is_empty()
+                && !now.driver_version.is_empty()
+                && before.driver_version != now.driver_version
+            {
+                changes.push(DisplayDriverChange::Updated {
+                    device_key: now.device_key.clone(),
+                    from: before.driver_version.clone(),
+                    to: now.driver_version.clone(),
+                });
+            }
+            continue;
+        }
+        let Some(model) = device_key_model(&now.device_key) else {
+            continue;
+        };
+        let vanished = previous.iter().find(|p| {
+            device_key_model(&p.device_key).as_deref() == Some(model.as_str())
+                && !current.iter().any(|c| same_key(c, p))
+                && !claimed.contains(&p.device_key.as_str())
+        });
+        if let Some(before) = vanished {
+            claimed.push(&before.device_key);
+            changes.push(if adapter_differs(before, now) {
+                adapter_changed(before, now)
+            } else {
+                DisplayDriverChange::Reenumerated {
+                    device_key: now.device_key.clone(),
+                    previous_key: before.device_key.clone(),
+                }
+            });
+        }
+    }
+    changes
+}
+
+/// Records to store after a check: everything seen now, plus earlier
+/// monitors that are merely disconnected. Instances replaced by a new one
+/// are dropped.
+pub fn merge_display_driver_records(
+    previous: &[DisplayDriverRecord],
+    current: &[DisplayDriverRecord],
+    changes: &[DisplayDriverChange],
+) -> Vec<DisplayDriverRecord> {
+    let mut merged = current.to_vec();
+    for before in previous {
+        let seen = current
+            .iter()
+            .any(|c| c.device_key.eq_ignore_ascii_case(&before.device_key));
+        let replaced = changes.iter().any(|c| {
+            c.previous_key()
+                .is_some_and(|key| key.eq_ignore_ascii_case(&before.device_key))
+        });
+        if !seen && !replaced {
+            merged.push(before.clone());
+        }
+    }
+    merged
+}
+
+/// Result of the service watchdog's last self-check.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct ServiceHeartbeat {
+    pub at: String,
+    pub interval_minutes: u64,
+    pub window_ok: bool,
+    pub notifications_ok: bool,
+    pub wmi_ok: bool,
+    pub association_ok: bool,
+    /// Repairs attempted during this check.
+    pub repairs: Vec<String>,
+    /// Background workers as seen by the supervisor.
+    pub workers: Vec<WorkerHealth>,
+}
+
+impl ServiceHeartbeat {
+    pub fn healthy(&self) -> bool {
+        self.window_ok
+            && self.notifications_ok
+            && self.wmi_ok
+            && self.association_ok
+            && !self.workers.iter().any(WorkerHealth::failed)
+    }
+
+    /// True when no check has been recorded for more than two intervals,
+    /// i.e. the watchdog itself has stopped.
+    pub fn is_overdue(&self, now: chrono::DateTime<Utc>) -> bool {
+        let Ok(at) = chrono::DateTime::parse_from_rfc3339(&self.at) else {
+            return true;
+        };
+        let limit = chrono::Duration::minutes((self.interval_minutes.max(1) * 2) as i64);
+        now.signed_duration_since(at) > limit
+    }
+}
+
+/// State of one supervised service worker.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct WorkerHealth {
+    pub name: String,
+    /// `running`, `restarting`, `stopped` or `failed` (gave up restarting).
+    pub state: String,
+    /// Restarts after a panic since the service started.
+    pub restarts: u32,
+    /// Message of the most recent panic.
+    pub last_panic: Option<String>,
+    /// When the most recent panic happened.
+    pub last_panic_at: Option<String>,
+}
+
+impl WorkerHealth {
+    pub fn failed(&self) -> bool {
+        self.state == "failed"
+    }
+}
+
+pub fn load_service_heartbeat() -> Option<ServiceHeartbeat> {
+    fs::read_to_string(heartbeat_path())
+        .ok()
+        .and_then(|text| toml::from_str::<ServiceHeartbeat>(&text).ok())
+}
+
+pub fn save_service_heartbeat(
+    heartbeat: &ServiceHeartbeat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = heartbeat_path();
+    ensure_parent(&path)?;
+    let text = toml::to_string_pretty(heartbeat)?;
+    fs::write(path, text)?;
+    Ok(())
+}
+
+/// Heartbeat stamped now.
+pub fn new_service_heartbeat(interval_minutes: u64) -> ServiceHeartbeat {
+    ServiceHeartbeat {
+        at: now_iso(),
+        interval_minutes,
+        ..ServiceHeartbeat::default()
+    }
+}
+
+/// Resolution and refresh rate a display is expected to run at.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct DisplayModeRecord {
+    pub device_key: String,
+    pub width: u32,
+    pub height: u32,
+    pub refresh_hz: u32,
+    /// Set by `display mode set`; the guard never overwrites a pinned mode.
+    pub pinned: bool,
+}
+
+impl DisplayModeRecord {
+    pub fn label(&self) -> String {
+        format!("{}x{} @ {} Hz", self.width, self.height, self.refresh_hz)
+    }
+
+    /// Same resolution and refresh rate as `other`.
+    pub fn same_mode(&self, other: &DisplayModeRecord) -> bool {
+        (self.width, self.height, self.refresh_hz) == (other.width, other.height, other.refresh_hz)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct DisplayModeState {
+    pub displays: Vec<DisplayModeRecord>,
+}
+
+impl DisplayModeState {
+    /// Expected mode recorded for `device_key`.
+    pub fn expected(&self, device_key: &str) -> Option<&DisplayModeRecord> {
+        self.displays
+            .iter()
+            .find(|d| d.device_key.eq_ignore_ascii_case(device_key))
+    }
+
+    /// Record `record` as the expected mode, replacing any earlier one for
+    /// the same display.
+    pub fn record(&mut self, record: DisplayModeRecord) {
+        match self
+            .displays
+            .iter_mut()
+            .find(|d| d.device_key.eq_ignore_ascii_case(&record.device_key))
+        {
+            Some(existing) => *existing = record,
+            None => self.displays.push(record),
+        }
+    }
+}
+
+/// What the display mode guard does about one display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayModeAction {
+    /// Mode matches what was recorded.
+    Keep,
+    /// Nothing usable recorded yet; take the current mode as expected.
+    Record,
+    /// The display drifted; switch it back to the recorded mode.
+    Restore,
+}
+
+/// Decide what to do with a display now running at `current`. A pinned
+/// mode is always restored. An unpinned one is only restored when the
+/// refresh rate dropped, the usual after-sleep symptom; any other change
+/// is taken as the user's and recorded.
+pub fn display_mode_action(
+    expected: Option<&DisplayModeRecord>,
+    current: &DisplayModeRecord,
+) -> DisplayModeAction {
+    match expected {
+        None => DisplayModeAction::Record,
+        Some(expected) if expected.same_mode(current) => DisplayModeAction::Keep,
+        Some(expected) if expected.pinned || current.refresh_hz < expected.refresh_hz => {
+            DisplayModeAction::Restore
+        }
+        Some(_) => DisplayModeAction::Record,
+    }
+}
+
+pub fn load_display_mode_state() -> DisplayModeState {
+    match fs::read_to_string(display_modes_path()) {
+        Ok(text) => toml::from_str::<DisplayModeState>(&text).unwrap_or_default(),
+        Err(_) => DisplayModeState::default(),
+    }
+}
+
+pub fn save_display_mode_state(state: &DisplayModeState) -> Result<(), Box<dyn std::error::Error>> {
+    let path = display_modes_path();
+    ensure_parent(&path)?;
+    let text = toml::to_string_pretty(state)?;
+    fs::write(path, text)?;
+    Ok(())
+}
+
 #[cfg(test)]
 #[path = "tests/state_tests.rs"]
 mod tests;
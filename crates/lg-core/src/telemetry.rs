@@ -0,0 +1,170 @@
+//! Opt-in anonymous usage ping.
+//!
+//! With `telemetry_enabled` on and `telemetry_endpoint` set, the service
+//! POSTs one small JSON document a week so maintainers can see which
+//! monitor models still misbehave. It holds the app version, the Windows
+//! build, the EDID model codes of the matched monitors (`GSM5BBF`) and the
+//! reapply success rate — no names, serials, paths or user data.
+//! `telemetry status` prints exactly what would be sent. The outcome of
+//! the last attempt is kept in `state\telemetry.toml`.
+
+use crate::state::{self, ApplyLatencyMetrics};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+/// Days between two successful pings.
+pub const PING_INTERVAL_DAYS: i64 = 7;
+
+/// Hours to wait after a failed ping before trying again.
+pub const RETRY_AFTER_HOURS: i64 = 24;
+
+/// The whole ping, exactly as it is sent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TelemetryPayload {
+    pub version: String,
+    /// `CurrentBuildNumber.UBR`, e.g. `22631.4317`.
+    pub windows_build: String,
+    /// Sorted, de-duplicated EDID model codes of the matched monitors.
+    pub monitor_models: Vec<String>,
+    /// Reapplies in the metrics window.
+    pub reapplies: usize,
+    /// Share of those reapplies that succeeded (0–1); `None` without any.
+    pub reapply_success_rate: Option<f64>,
+}
+
+impl TelemetryPayload {
+    pub fn new(
+        version: &str,
+        windows_build: &str,
+        models: impl IntoIterator<Item = String>,
+        metrics: &ApplyLatencyMetrics,
+    ) -> Self {
+        let mut monitor_models: Vec<String> = models.into_iter().collect();
+        monitor_models.sort();
+        monitor_models.dedup();
+        let reapply_success_rate = (metrics.samples > 0).then(|| {
+            let rate = metrics.success_count as f64 / metrics.samples as f64;
+            (rate * 1000.0).round() / 1000.0
+        });
+        Self {
+            version: version.to_string(),
+            windows_build: windows_build.to_string(),
+            monitor_models,
+            reapplies: metrics.samples,
+            reapply_success_rate,
+        }
+    }
+
+    /// The request body.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Model code from the EDID manufacturer ID and product code reported by
+/// WMI (`GSM` + `5BBF`). `None` unless both look like EDID values, so a
+/// friendly name can never end up in the payload.
+pub fn model_code(manufacturer_id: &str, product_code: &str) -> Option<String> {
+    let manufacturer = manufacturer_id.trim();
+    let product = product_code.trim();
+    let manufacturer_ok =
+        manufacturer.len() == 3 && manufacturer.chars().all(|c| c.is_ascii_alphabetic());
+    let product_ok =
+        (1..=4).contains(&product.len()) && product.chars().all(|c| c.is_ascii_hexdigit());
+    (manufacturer_ok && product_ok).then(|| {
+        format!(
+            "{}{}",
+            manufacturer.to_ascii_uppercase(),
+            product.to_ascii_uppercase()
+        )
+    })
+}
+
+/// Host, port and path of an `https://` URL; `None` for anything else.
+pub fn split_https_url(url: &str) -> Option<(String, u16, String)> {
+    let url = url.trim();
+    if !url.get(..8)?.eq_ignore_ascii_case("https://") {
+        return None;
+    }
+    let rest = &url[8..];
+    let (authority, path) = match rest.find('/') {
+        Some(at) => (&rest[..at], &rest[at..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse().ok()?),
+        None => (authority, 443),
+    };
+    if host.is_empty() || host.contains('@') {
+        return None;
+    }
+    Some((host.to_string(), port, path.to_string()))
+}
+
+/// Outcome of the last ping attempts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TelemetryState {
+    /// RFC 3339 time of the last attempt.
+    pub last_attempt: Option<String>,
+    /// RFC 3339 time of the last accepted ping.
+    pub last_sent: Option<String>,
+    /// Why the last attempt failed; empty after a success.
+    pub last_error: String,
+}
+
+impl TelemetryState {
+    /// Whether a ping is due at `now`: a week after the last success and
+    /// not within [`RETRY_AFTER_HOURS`] of a failed attempt.
+    pub fn ping_due(&self, now: DateTime<Utc>) -> bool {
+        let older_than = |stamp: &Option<String>, age: Duration| {
+            stamp
+                .as_deref()
+                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                .is_none_or(|at| now - at.with_timezone(&Utc) >= age)
+        };
+        older_than(&self.last_sent, Duration::days(PING_INTERVAL_DAYS))
+            && (self.last_error.is_empty()
+                || older_than(&self.last_attempt, Duration::hours(RETRY_AFTER_HOURS)))
+    }
+
+    /// Record an attempt made at `now`.
+    pub fn record(&mut self, now: DateTime<Utc>, result: Result<(), String>) {
+        let stamp = now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        self.last_attempt = Some(stamp.clone());
+        match result {
+            Ok(()) => {
+                self.last_sent = Some(stamp);
+                self.last_error.clear();
+            }
+            Err(e) => self.last_error = e,
+        }
+    }
+}
+
+pub fn telemetry_state_path() -> PathBuf {
+    state::state_dir().join("telemetry.toml")
+}
+
+pub fn load_telemetry_state() -> TelemetryState {
+    fs::read_to_string(telemetry_state_path())
+        .ok()
+        .and_then(|text| toml::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_telemetry_state(telemetry: &TelemetryState) -> Result<(), Box<dyn Error>> {
+    let path = telemetry_state_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, toml::to_string_pretty(telemetry)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "tests/telemetry_tests.rs"]
+mod tests;
@@ -0,0 +1,90 @@
+use super::*;
+
+fn entry(action: &str) -> AuditEntry {
+    AuditEntry::new("registry", action, r"HKLM\SOFTWARE\Test")
+}
+
+// ── Entries ──────────────────────────────────────────────────────
+
+#[test]
+fn result_records_failure_and_display_shows_values() {
+    let failed: Result<(), String> = Err("access denied".to_string());
+    let e = entry("set_value").before("1").after("2").result(&failed);
+    assert!(!e.ok);
+    assert_eq!(e.error.as_deref(), Some("access denied"));
+    let text = e.to_string();
+    assert!(text.contains("FAIL registry/set_value"), "{}", text);
+    assert!(
+        text.ends_with(r"HKLM\SOFTWARE\Test [1 -> 2] (access denied)"),
+        "{}",
+        text
+    );
+
+    let ok: Result<(), String> = Ok(());
+    assert!(entry("delete_value").result(&ok).ok);
+}
+
+#[test]
+fn json_omits_missing_values() {
+    let json = serde_json::to_string(&entry("create_key").after("created")).unwrap();
+    assert!(!json.contains("\"before\""));
+    assert!(!json.contains("\"error\""));
+    assert!(json.contains("\"after\":\"created\""));
+}
+
+#[test]
+fn file_state_hashes_content_stably() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("profile.icm");
+    assert_eq!(file_state(&path), "absent");
+    std::fs::write(&path, b"abc").unwrap();
+    assert_eq!(file_state(&path), "3 bytes fnv1a:e71fa2190541574b");
+}
+
+// ── Log file ─────────────────────────────────────────────────────
+
+#[test]
+fn append_and_read_back_newest_first() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit").join("audit.log");
+    assert!(read_recent_from(&path, 10).unwrap().is_empty());
+
+    for action in ["first", "second", "third"] {
+        append_to(&path, &entry(action), ROTATE_BYTES).unwrap();
+    }
+    let recent = read_recent_from(&path, 2).unwrap();
+    let actions: Vec<_> = recent.iter().map(|e| e.action.as_str()).collect();
+    assert_eq!(actions, ["third", "second"]);
+}
+
+#[test]
+fn append_rotates_full_log_without_deleting_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.log");
+    append_to(&path, &entry("old"), 1).unwrap();
+    append_to(&path, &entry("new"), 1).unwrap();
+
+    let recent = read_recent_from(&path, 10).unwrap();
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0].action, "new");
+    let rotated: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|n| n.starts_with("audit-"))
+        .collect();
+    assert_eq!(rotated.len(), 1);
+}
+
+#[test]
+fn record_appends_to_a_per_process_log_in_tests() {
+    let path = audit_log_path();
+    assert!(path.starts_with(std::env::temp_dir()), "{}", path.display());
+    assert!(!path.starts_with(audit_dir()), "{}", path.display());
+    let marker = format!("record-test-{}", std::process::id());
+    entry("set_value").after(marker.as_str()).record();
+    let entries = read_recent_entries(usize::MAX).unwrap();
+    assert!(entries
+        .iter()
+        .any(|e| e.after.as_deref() == Some(marker.as_str())));
+}
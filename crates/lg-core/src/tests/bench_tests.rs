@@ -0,0 +1,124 @@
+use super::*;
+use crate::pipeline::{ReapplyOptions, ReapplyPipeline};
+use std::path::PathBuf;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+// ── Summaries ────────────────────────────────────────────────────
+
+#[test]
+fn percentiles_are_nearest_rank() {
+    let sorted: Vec<Duration> = (1..=20).map(ms).collect();
+    assert_eq!(nearest_rank(&sorted, 50), ms(10));
+    assert_eq!(nearest_rank(&sorted, 95), ms(19));
+    assert_eq!(nearest_rank(&sorted[..1], 95), ms(1));
+}
+
+#[test]
+fn summaries_keep_first_seen_order() {
+    let mut timings = PhaseTimings::new();
+    for (phase, n) in [("b", 30), ("a", 5), ("b", 10), ("b", 20)] {
+        timings.record(phase, ms(n));
+    }
+    let summaries = timings.summaries();
+    assert_eq!(
+        summaries
+            .iter()
+            .map(|s| (s.phase, s.samples))
+            .collect::<Vec<_>>(),
+        vec![("b", 3), ("a", 1)]
+    );
+    assert_eq!(summaries[0].min, ms(10));
+    assert_eq!(summaries[0].median, ms(20));
+    assert_eq!(summaries[0].max, ms(30));
+}
+
+#[test]
+fn table_lists_each_phase() {
+    let mut timings = PhaseTimings::new();
+    timings.record(PHASE_ASSOCIATE, ms(12));
+    let table = timings.format_table();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("phase "));
+    assert!(lines[1].starts_with("associate "));
+    assert!(lines[1].ends_with("12.00"));
+}
+
+// ── Timed steps ──────────────────────────────────────────────────
+
+struct TwoMonitors;
+
+impl ReapplySteps for TwoMonitors {
+    type Monitor = u8;
+
+    fn ensure_shared_profiles(&mut self) -> Result<Option<ModeProfiles>, Box<dyn Error>> {
+        Ok(Some(ModeProfiles::single(PathBuf::from("shared.icm"))))
+    }
+
+    fn is_installed(&self, _path: &Path) -> bool {
+        true
+    }
+
+    fn find_monitors(&mut self) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(vec![1, 2])
+    }
+
+    fn ensure_monitor_profiles(&mut self, _monitor: &u8) -> Result<ModeProfiles, Box<dyn Error>> {
+        unreachable!("profiles are shared")
+    }
+
+    fn associate(
+        &mut self,
+        _monitor: &u8,
+        _active: &Path,
+        _sdr: &Path,
+        _hdr: Option<&Path>,
+        _per_user: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn set_generic_default(
+        &mut self,
+        _monitor: &u8,
+        _active: &Path,
+        _per_user: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn refresh(&mut self) {}
+
+    fn set_ddc_brightness(&mut self, _level: u32) -> Result<usize, Box<dyn Error>> {
+        Ok(2)
+    }
+
+    fn toast(&mut self) {}
+}
+
+#[test]
+fn timed_steps_record_every_phase_that_ran() {
+    let mut timings = PhaseTimings::new();
+    let mut steps = TwoMonitors;
+    let outcome = ReapplyPipeline::new(ReapplyOptions::default())
+        .run(&mut TimedSteps::new(&mut steps, &mut timings))
+        .expect("reapply");
+    assert_eq!(outcome.applied, 2);
+    assert_eq!(
+        timings
+            .summaries()
+            .iter()
+            .map(|s| (s.phase, s.samples))
+            .collect::<Vec<_>>(),
+        vec![
+            (PHASE_SHARED_PROFILES, 1),
+            (PHASE_FIND_MONITORS, 1),
+            (PHASE_INHERIT, 2),
+            (PHASE_ASSOCIATE, 2),
+            (PHASE_REFRESH, 1),
+        ]
+    );
+}
@@ -0,0 +1,49 @@
+use super::*;
+
+fn lookup(name: &str) -> Option<String> {
+    match name {
+        "LOCALAPPDATA" => Some(r"C:\Users\me\AppData\Local".to_string()),
+        "SystemRoot" => Some(r"D:\Windows".to_string()),
+        _ => None,
+    }
+}
+
+// ── Expansion ────────────────────────────────────────────────────
+
+#[test]
+fn expand_env_replaces_known_variables() {
+    assert_eq!(
+        expand_env(r"%LOCALAPPDATA%\Color", lookup),
+        r"C:\Users\me\AppData\Local\Color"
+    );
+    assert_eq!(
+        expand_env(r"%SystemRoot%\System32\spool\drivers\color", lookup),
+        r"D:\Windows\System32\spool\drivers\color"
+    );
+}
+
+#[test]
+fn expand_env_keeps_unknown_variables_and_stray_percent() {
+    assert_eq!(expand_env(r"%NOPE%\Color", lookup), r"%NOPE%\Color");
+    assert_eq!(expand_env("100% color", lookup), "100% color");
+    assert_eq!(expand_env("%%", lookup), "%%");
+}
+
+// ── Resolution ───────────────────────────────────────────────────
+
+#[test]
+fn resolve_uses_the_setting_or_the_system_directory() {
+    assert_eq!(resolve(r" E:\Profiles "), PathBuf::from(r"E:\Profiles"));
+    assert_eq!(resolve(""), system_directory());
+}
+
+#[test]
+fn default_directory_is_under_the_spool_tree() {
+    assert!(default_directory().ends_with(PathBuf::from("spool").join("drivers").join("color")));
+}
+
+#[test]
+fn system_scope_uses_the_system_directory() {
+    assert_eq!(directory_for(Scope::SystemWide), system_directory());
+    assert!(default_user_directory().ends_with(PathBuf::from("LG-UltraGear-Monitor").join("color")));
+}
@@ -0,0 +1,113 @@
+use super::*;
+
+fn device(key: &str, system: &[&str], user: &[&str]) -> DeviceColorState {
+    DeviceColorState {
+        device_key: key.to_string(),
+        use_per_user_profiles: false,
+        system_wide: ScopeColorState {
+            profiles: system.iter().map(|p| p.to_string()).collect(),
+            ..Default::default()
+        },
+        current_user: ScopeColorState {
+            profiles: user.iter().map(|p| p.to_string()).collect(),
+            ..Default::default()
+        },
+    }
+}
+
+fn snapshot(devices: Vec<DeviceColorState>) -> ColorStoreBackup {
+    ColorStoreBackup::new(devices)
+}
+
+// ── Scope ────────────────────────────────────────────────────────
+
+#[test]
+fn scope_parses_its_labels() {
+    for scope in [Scope::SystemWide, Scope::CurrentUser] {
+        assert_eq!(Scope::parse(scope.label()), Some(scope));
+    }
+    assert_eq!(Scope::parse(" User "), Some(Scope::CurrentUser));
+    assert_eq!(Scope::parse("machine"), None);
+}
+
+// ── JSON ─────────────────────────────────────────────────────────
+
+#[test]
+fn backup_roundtrips_through_json() {
+    let mut dev = device(r"DISPLAY\GSM5BBF\5&1", &["a.icm", "b.icm"], &["c.icm"]);
+    dev.use_per_user_profiles = true;
+    dev.system_wide.sdr_default = Some("b.icm".to_string());
+    let backup = snapshot(vec![dev]);
+
+    let json = backup.to_json().unwrap();
+    assert!(json.contains("\"current_user\""));
+    assert_eq!(ColorStoreBackup::from_json(&json).unwrap(), backup);
+}
+
+#[test]
+fn from_json_rejects_newer_schema_and_garbage() {
+    let mut backup = snapshot(Vec::new());
+    backup.schema_version = BACKUP_SCHEMA_VERSION + 1;
+    let err = ColorStoreBackup::from_json(&backup.to_json().unwrap()).unwrap_err();
+    assert!(err.contains("newer"), "{}", err);
+    assert!(ColorStoreBackup::from_json("not json").is_err());
+}
+
+// ── Restore plan ─────────────────────────────────────────────────
+
+#[test]
+fn identical_state_needs_no_changes() {
+    let state = snapshot(vec![device(r"DISPLAY\GSM5BBF\5&1", &["a.icm"], &[])]);
+    assert!(restore_plan(&state, &state.clone()).is_empty());
+}
+
+#[test]
+fn plan_restores_lists_then_switches_then_defaults() {
+    let key = r"DISPLAY\GSM5BBF\5&1";
+    let current = snapshot(vec![device(key, &["a.icm", "lg-fix.icm"], &[])]);
+    let mut wanted = device(key, &["a.icm"], &["u.icm"]);
+    wanted.use_per_user_profiles = true;
+    wanted.system_wide.default = Some("a.icm".to_string());
+    wanted.current_user.hdr_default = Some("u.icm".to_string());
+    let backup = snapshot(vec![wanted]);
+
+    let plan = restore_plan(&current, &backup);
+    let text: Vec<String> = plan.iter().map(|c| c.to_string()).collect();
+    assert_eq!(
+        text,
+        [
+            r"DISPLAY\GSM5BBF\5&1 system associations: a.icm, lg-fix.icm -> a.icm",
+            r"DISPLAY\GSM5BBF\5&1 user associations: (none) -> u.icm",
+            r"DISPLAY\GSM5BBF\5&1 per-user profiles on",
+            r"DISPLAY\GSM5BBF\5&1 system default -> a.icm",
+            r"DISPLAY\GSM5BBF\5&1 user HDR default -> u.icm",
+        ]
+    );
+}
+
+#[test]
+fn plan_clears_devices_missing_from_backup() {
+    let current = snapshot(vec![
+        device(r"DISPLAY\GSM5BBF\5&1", &["a.icm"], &[]),
+        device(r"DISPLAY\GSM7714\5&2", &["lg-fix.icm"], &["lg-fix.icm"]),
+    ]);
+    let backup = snapshot(vec![device(r"display\gsm5bbf\5&1", &["a.icm"], &[])]);
+
+    let plan = restore_plan(&current, &backup);
+    assert_eq!(plan.len(), 2);
+    assert!(plan.iter().all(|c| matches!(
+        c,
+        RestoreChange::SetProfiles { device_key, to, .. }
+            if device_key == r"DISPLAY\GSM7714\5&2" && to.is_empty()
+    )));
+}
+
+#[test]
+fn plan_skips_matching_defaults_case_insensitively() {
+    let key = r"DISPLAY\GSM5BBF\5&1";
+    let mut live = device(key, &["a.icm"], &[]);
+    live.system_wide.default = Some("A.ICM".to_string());
+    let mut wanted = device(key, &["a.icm"], &[]);
+    wanted.system_wide.default = Some("a.icm".to_string());
+    assert!(restore_plan(&snapshot(vec![live]), &snapshot(vec![wanted])).is_empty());
+}
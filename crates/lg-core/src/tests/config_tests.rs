@@ -64,6 +64,36 @@ fn default_config_toast_body() {
     assert_eq!(cfg.toast_body, "Color profile reapplied ✓");
 }
 
+#[test]
+fn default_config_toast_quiet_hours_suppresses() {
+    let cfg = Config::default();
+    assert_eq!(cfg.toast_quiet_hours, "suppress");
+}
+
+#[test]
+fn default_config_tamper_watch_is_off() {
+    let cfg = Config::default();
+    assert_eq!(cfg.tamper_watch, "off");
+}
+
+#[test]
+fn default_config_gamma_watch_is_off() {
+    let cfg = Config::default();
+    assert_eq!(cfg.gamma_watch, "off");
+    assert_eq!(cfg.gamma_watch_interval_ms, 5000);
+}
+
+#[test]
+fn default_config_watchdog_every_five_minutes() {
+    let cfg = Config::default();
+    assert_eq!(cfg.watchdog_interval_minutes, 5);
+}
+
+#[test]
+fn default_config_resource_audit_off() {
+    assert_eq!(Config::default().resource_audit_interval_minutes, 0);
+}
+
 #[test]
 fn default_config_stabilize_delay() {
     let cfg = Config::default();
@@ -80,6 +110,31 @@ fn default_config_toggle_delay() {
 fn default_config_reapply_delay() {
     let cfg = Config::default();
     assert_eq!(cfg.reapply_delay_ms, 12000);
+    assert!(!cfg.defer_reapply);
+    assert_eq!(cfg.defer_reapply_idle_ms, 60000);
+    assert_eq!(cfg.defer_reapply_max_ms, 600000);
+    assert!(!cfg.kvm_mode);
+    assert!(!cfg.dock_mode);
+    assert_eq!(cfg.min_matching_monitors, 0);
+    assert_eq!(cfg.only_when_monitor_count, 0);
+    assert!(!cfg.skip_reapply_lid_closed);
+}
+
+#[test]
+fn dock_mode_raises_short_delays_only() {
+    let mut cfg = Config {
+        stabilize_delay_ms: 1500,
+        reapply_delay_ms: 30000,
+        ..Config::default()
+    };
+    assert_eq!(cfg.effective_stabilize_delay_ms(), 1500);
+    assert_eq!(cfg.effective_reapply_delay_ms(), 30000);
+    cfg.dock_mode = true;
+    assert_eq!(
+        cfg.effective_stabilize_delay_ms(),
+        DOCK_MIN_STABILIZE_DELAY_MS
+    );
+    assert_eq!(cfg.effective_reapply_delay_ms(), 30000);
 }
 
 #[test]
@@ -89,6 +144,7 @@ fn default_config_uses_soft_refresh_defaults() {
     assert!(cfg.refresh_broadcast_color);
     assert!(!cfg.refresh_invalidate);
     assert!(cfg.refresh_calibration_loader);
+    assert!(cfg.postpone_refresh_in_fullscreen);
 }
 
 #[test]
@@ -97,6 +153,12 @@ fn default_config_verbose_is_false() {
     assert!(!cfg.verbose);
 }
 
+#[test]
+fn default_config_install_report_is_off() {
+    let cfg = Config::default();
+    assert!(!cfg.install_report);
+}
+
 #[test]
 fn default_config_ddc_brightness_off() {
     let cfg = Config::default();
@@ -220,6 +282,7 @@ fn serialize_roundtrip() {
         monitor_match: "TestMonitor".to_string(),
         monitor_match_regex: true,
         profile_name: "test.icm".to_string(),
+        color_directory: r"%LOCALAPPDATA%\Color".to_string(),
         icc_gamma: 2.05,
         icc_active_preset: "gamma24".to_string(),
         icc_generate_specialized_profiles: true,
@@ -254,24 +317,71 @@ fn serialize_roundtrip() {
         icc_include_viewing_conditions: true,
         icc_include_spectral_scaffold: true,
         icc_per_monitor_profiles: true,
+        icc_hdr_association: false,
         icc_hdr_preset: "gamma24".to_string(),
         icc_sdr_preset: "gamma22".to_string(),
         icc_schedule_day_preset: "gamma22".to_string(),
         icc_schedule_night_preset: "gamma24".to_string(),
+        icc_schedule_day_start: "06:30".to_string(),
+        icc_schedule_night_start: "20:15".to_string(),
         icc_auto_apply_on_change: true,
         toast_enabled: false,
         toast_title: "T".to_string(),
         toast_body: "B".to_string(),
+        toast_quiet_hours: "queue".to_string(),
+        language: "de".to_string(),
+        osd_enabled: true,
+        osd_position: "bottom-left".to_string(),
+        osd_duration_ms: 3500,
+        measure_command: r#""C:\Argyll\bin\spotread.exe" -x -O"#.to_string(),
+        measure_timeout_ms: 45_000,
+        telemetry_enabled: true,
+        telemetry_endpoint: "https://example.invalid/ping".to_string(),
         stabilize_delay_ms: 999,
         toggle_delay_ms: 50,
         reapply_delay_ms: 8000,
+        defer_reapply: true,
+        defer_reapply_idle_ms: 30000,
+        defer_reapply_max_ms: 120000,
+        kvm_mode: true,
+        dock_mode: true,
+        min_matching_monitors: 2,
+        only_when_monitor_count: 3,
+        skip_reapply_lid_closed: true,
         refresh_display_settings: false,
         refresh_broadcast_color: true,
         refresh_invalidate: false,
         refresh_calibration_loader: true,
+        postpone_refresh_in_fullscreen: false,
         ddc_brightness_on_reapply: true,
         ddc_brightness_value: 75,
+        sdr_white_level_nits: 240,
+        display_mode_guard: true,
+        brightness_guard: true,
+        brightness_guard_threshold: 5,
+        brightness_guard_interval_ms: 1500,
+        brightness_guard_max_per_minute: 4,
+        fullscreen_exit_reassert: true,
+        fullscreen_exit_apps: "game.exe".to_string(),
+        tamper_watch: "reassert".to_string(),
+        gamma_watch: "log".to_string(),
+        gamma_watch_interval_ms: 2500,
+        watchdog_interval_minutes: 15,
+        resource_audit_interval_minutes: 60,
+        hotkeys_enabled: true,
+        hotkey_reapply: "Ctrl+Shift+R".to_string(),
+        hotkey_brightness_up: "".to_string(),
+        hotkey_brightness_down: "Win+PageDown".to_string(),
+        hotkey_brightness_step: 5,
         verbose: true,
+        install_report: true,
+        schedule: ScheduleConfig {
+            mode: "solar".to_string(),
+            latitude: 51.5074,
+            longitude: -0.1278,
+            use_windows_location: true,
+        },
+        app_overrides: std::collections::BTreeMap::new(),
     };
 
     let toml_str = toml::to_string(&original).unwrap();
@@ -281,6 +391,7 @@ fn serialize_roundtrip() {
     assert_eq!(parsed.monitor_match_regex, original.monitor_match_regex);
     assert_eq!(parsed.monitor_match_regex, original.monitor_match_regex);
     assert_eq!(parsed.profile_name, original.profile_name);
+    assert_eq!(parsed.color_directory, original.color_directory);
     assert_eq!(parsed.icc_gamma, original.icc_gamma);
     assert_eq!(parsed.icc_active_preset, original.icc_active_preset);
     assert_eq!(
@@ -333,6 +444,14 @@ fn serialize_roundtrip() {
         parsed.icc_per_monitor_profiles,
         original.icc_per_monitor_profiles
     );
+    assert_eq!(parsed.icc_hdr_association, original.icc_hdr_association);
+    assert_eq!(parsed.osd_enabled, original.osd_enabled);
+    assert_eq!(parsed.osd_position, original.osd_position);
+    assert_eq!(parsed.osd_duration_ms, original.osd_duration_ms);
+    assert_eq!(parsed.measure_command, original.measure_command);
+    assert_eq!(parsed.measure_timeout_ms, original.measure_timeout_ms);
+    assert_eq!(parsed.telemetry_enabled, original.telemetry_enabled);
+    assert_eq!(parsed.telemetry_endpoint, original.telemetry_endpoint);
     assert_eq!(
         parsed.icc_include_media_black_point,
         original.icc_include_media_black_point
@@ -425,6 +544,15 @@ fn serialize_roundtrip() {
         parsed.icc_schedule_night_preset,
         original.icc_schedule_night_preset
     );
+    assert_eq!(
+        parsed.icc_schedule_day_start,
+        original.icc_schedule_day_start
+    );
+    assert_eq!(
+        parsed.icc_schedule_night_start,
+        original.icc_schedule_night_start
+    );
+    assert_eq!(parsed.schedule, original.schedule);
     assert_eq!(
         parsed.icc_auto_apply_on_change,
         original.icc_auto_apply_on_change
@@ -432,8 +560,24 @@ fn serialize_roundtrip() {
     assert_eq!(parsed.toast_enabled, original.toast_enabled);
     assert_eq!(parsed.toast_title, original.toast_title);
     assert_eq!(parsed.toast_body, original.toast_body);
+    assert_eq!(parsed.toast_quiet_hours, original.toast_quiet_hours);
+    assert_eq!(parsed.language, original.language);
     assert_eq!(parsed.stabilize_delay_ms, original.stabilize_delay_ms);
     assert_eq!(parsed.toggle_delay_ms, original.toggle_delay_ms);
+    assert_eq!(parsed.defer_reapply, original.defer_reapply);
+    assert_eq!(parsed.defer_reapply_idle_ms, original.defer_reapply_idle_ms);
+    assert_eq!(parsed.defer_reapply_max_ms, original.defer_reapply_max_ms);
+    assert_eq!(parsed.kvm_mode, original.kvm_mode);
+    assert_eq!(parsed.dock_mode, original.dock_mode);
+    assert_eq!(parsed.min_matching_monitors, original.min_matching_monitors);
+    assert_eq!(
+        parsed.only_when_monitor_count,
+        original.only_when_monitor_count
+    );
+    assert_eq!(
+        parsed.skip_reapply_lid_closed,
+        original.skip_reapply_lid_closed
+    );
     assert_eq!(
         parsed.refresh_display_settings,
         original.refresh_display_settings
@@ -447,12 +591,50 @@ fn serialize_roundtrip() {
         parsed.refresh_calibration_loader,
         original.refresh_calibration_loader
     );
+    assert_eq!(
+        parsed.postpone_refresh_in_fullscreen,
+        original.postpone_refresh_in_fullscreen
+    );
     assert_eq!(
         parsed.ddc_brightness_on_reapply,
         original.ddc_brightness_on_reapply
     );
     assert_eq!(parsed.ddc_brightness_value, original.ddc_brightness_value);
+    assert_eq!(parsed.sdr_white_level_nits, original.sdr_white_level_nits);
+    assert_eq!(parsed.display_mode_guard, original.display_mode_guard);
+    assert_eq!(parsed.brightness_guard, original.brightness_guard);
+    assert_eq!(parsed.fullscreen_exit_apps, original.fullscreen_exit_apps);
+    assert_eq!(parsed.tamper_watch, original.tamper_watch);
+    assert_eq!(parsed.gamma_watch, original.gamma_watch);
+    assert_eq!(
+        parsed.gamma_watch_interval_ms,
+        original.gamma_watch_interval_ms
+    );
+    assert_eq!(
+        parsed.watchdog_interval_minutes,
+        original.watchdog_interval_minutes
+    );
+    assert_eq!(
+        parsed.resource_audit_interval_minutes,
+        original.resource_audit_interval_minutes
+    );
+    assert_eq!(parsed.hotkeys_enabled, original.hotkeys_enabled);
+    assert_eq!(parsed.hotkey_reapply, original.hotkey_reapply);
+    assert_eq!(parsed.hotkey_brightness_up, original.hotkey_brightness_up);
+    assert_eq!(
+        parsed.hotkey_brightness_down,
+        original.hotkey_brightness_down
+    );
+    assert_eq!(
+        parsed.hotkey_brightness_step,
+        original.hotkey_brightness_step
+    );
+    assert_eq!(
+        parsed.brightness_guard_threshold,
+        original.brightness_guard_threshold
+    );
     assert_eq!(parsed.verbose, original.verbose);
+    assert_eq!(parsed.install_report, original.install_report);
 }
 
 // ── to_toml_commented ────────────────────────────────────────────
@@ -562,10 +744,30 @@ fn to_toml_commented_contains_all_field_values() {
         output.contains("icc_per_monitor_profiles = true"),
         "should contain icc_per_monitor_profiles"
     );
+    assert!(
+        output.contains("icc_hdr_association = true"),
+        "should contain icc_hdr_association"
+    );
     assert!(
         output.contains("toast_enabled = true"),
         "should contain toast_enabled"
     );
+    assert!(
+        output.contains("osd_enabled = false"),
+        "should contain osd_enabled"
+    );
+    assert!(
+        output.contains("osd_position = \"top-right\""),
+        "should contain osd_position"
+    );
+    assert!(
+        output.contains("measure_command = \"\""),
+        "should contain measure_command"
+    );
+    assert!(
+        output.contains("telemetry_enabled = false"),
+        "should contain telemetry_enabled"
+    );
     assert!(
         output.contains("stabilize_delay_ms = 1500"),
         "should contain stabilize_delay_ms"
@@ -607,6 +809,7 @@ fn to_toml_commented_roundtrip_preserves_values() {
         monitor_match: "Custom Monitor".to_string(),
         monitor_match_regex: false,
         profile_name: "custom.icm".to_string(),
+        color_directory: r"D:\Spool\color".to_string(),
         icc_gamma: 2.05,
         icc_active_preset: "custom".to_string(),
         icc_generate_specialized_profiles: false,
@@ -641,24 +844,71 @@ fn to_toml_commented_roundtrip_preserves_values() {
         icc_include_viewing_conditions: true,
         icc_include_spectral_scaffold: false,
         icc_per_monitor_profiles: true,
+        icc_hdr_association: true,
         icc_hdr_preset: "gamma22".to_string(),
         icc_sdr_preset: "custom".to_string(),
         icc_schedule_day_preset: "".to_string(),
         icc_schedule_night_preset: "".to_string(),
+        icc_schedule_day_start: "07:00".to_string(),
+        icc_schedule_night_start: "19:00".to_string(),
         icc_auto_apply_on_change: true,
         toast_enabled: false,
         toast_title: "Custom".to_string(),
         toast_body: "Applied".to_string(),
+        toast_quiet_hours: "show".to_string(),
+        language: String::new(),
+        osd_enabled: false,
+        osd_position: "top-right".to_string(),
+        osd_duration_ms: 2000,
+        measure_command: String::new(),
+        measure_timeout_ms: 60_000,
+        telemetry_enabled: false,
+        telemetry_endpoint: String::new(),
         stabilize_delay_ms: 5000,
         toggle_delay_ms: 250,
         reapply_delay_ms: 15000,
+        defer_reapply: false,
+        defer_reapply_idle_ms: 45000,
+        defer_reapply_max_ms: 900000,
+        kvm_mode: false,
+        dock_mode: false,
+        min_matching_monitors: 1,
+        only_when_monitor_count: 0,
+        skip_reapply_lid_closed: false,
         refresh_display_settings: false,
         refresh_broadcast_color: false,
         refresh_invalidate: true,
         refresh_calibration_loader: false,
+        postpone_refresh_in_fullscreen: true,
         ddc_brightness_on_reapply: true,
         ddc_brightness_value: 80,
+        sdr_white_level_nits: 200,
+        display_mode_guard: false,
+        brightness_guard: true,
+        brightness_guard_threshold: 2,
+        brightness_guard_interval_ms: 750,
+        brightness_guard_max_per_minute: 0,
+        fullscreen_exit_reassert: true,
+        fullscreen_exit_apps: "a.exe, \"b\"".to_string(),
+        tamper_watch: "notify".to_string(),
+        gamma_watch: "reassert".to_string(),
+        gamma_watch_interval_ms: 10000,
+        watchdog_interval_minutes: 0,
+        resource_audit_interval_minutes: 0,
+        hotkeys_enabled: false,
+        hotkey_reapply: "Ctrl+Alt+F9".to_string(),
+        hotkey_brightness_up: "Ctrl+Alt+Up".to_string(),
+        hotkey_brightness_down: "Ctrl+Alt+Down".to_string(),
+        hotkey_brightness_step: 10,
         verbose: true,
+        install_report: true,
+        schedule: ScheduleConfig {
+            mode: "solar".to_string(),
+            latitude: 51.5074,
+            longitude: -0.1278,
+            use_windows_location: true,
+        },
+        app_overrides: std::collections::BTreeMap::new(),
     };
 
     let commented = Config::to_toml_commented(&original);
@@ -666,6 +916,7 @@ fn to_toml_commented_roundtrip_preserves_values() {
 
     assert_eq!(parsed.monitor_match, original.monitor_match);
     assert_eq!(parsed.profile_name, original.profile_name);
+    assert_eq!(parsed.color_directory, original.color_directory);
     assert_eq!(parsed.icc_gamma, original.icc_gamma);
     assert_eq!(parsed.icc_active_preset, original.icc_active_preset);
     assert_eq!(
@@ -700,6 +951,15 @@ fn to_toml_commented_roundtrip_preserves_values() {
         parsed.icc_schedule_night_preset,
         original.icc_schedule_night_preset
     );
+    assert_eq!(
+        parsed.icc_schedule_day_start,
+        original.icc_schedule_day_start
+    );
+    assert_eq!(
+        parsed.icc_schedule_night_start,
+        original.icc_schedule_night_start
+    );
+    assert_eq!(parsed.schedule, original.schedule);
     assert_eq!(
         parsed.icc_auto_apply_on_change,
         original.icc_auto_apply_on_change
@@ -708,6 +968,7 @@ fn to_toml_commented_roundtrip_preserves_values() {
     assert_eq!(parsed.stabilize_delay_ms, original.stabilize_delay_ms);
     assert_eq!(parsed.toggle_delay_ms, original.toggle_delay_ms);
     assert_eq!(parsed.verbose, original.verbose);
+    assert_eq!(parsed.install_report, original.install_report);
 }
 
 // ── profile_path ─────────────────────────────────────────────────
@@ -732,6 +993,18 @@ fn profile_path_ends_with_profile_name() {
     assert!(path.ends_with("my-custom-profile.icm"));
 }
 
+#[test]
+fn profile_path_uses_configured_color_directory() {
+    let cfg = Config {
+        color_directory: r"E:\Profiles".to_string(),
+        ..Config::default()
+    };
+    assert_eq!(
+        cfg.profile_path(),
+        PathBuf::from(r"E:\Profiles").join(&cfg.profile_name)
+    );
+}
+
 #[test]
 fn profile_path_uses_windir_env() {
     let cfg = Config::default();
@@ -766,6 +1039,95 @@ fn config_path_is_inside_config_dir() {
     assert!(path.starts_with(&dir));
 }
 
+#[test]
+fn portable_dir_is_the_executable_directory() {
+    let exe = Path::new("E:/tools/lg/lg-ultragear-dimming-fix.exe");
+    assert_eq!(portable_dir_for(exe).unwrap(), PathBuf::from("E:/tools/lg"));
+    assert!(portable_dir_for(Path::new("lg-ultragear-dimming-fix.exe")).is_err());
+}
+
+#[test]
+fn portable_mode_is_off_unless_enabled() {
+    assert!(!is_portable());
+}
+
+// ── Named configs ────────────────────────────────────────────────
+
+#[test]
+fn named_config_path_default_is_base_config() {
+    assert_eq!(
+        named_config_path(DEFAULT_CONFIG_NAME),
+        config_dir().join("config.toml")
+    );
+    assert_eq!(
+        named_config_path("Default"),
+        config_dir().join("config.toml")
+    );
+}
+
+#[test]
+fn named_config_path_lives_in_configs_dir() {
+    let path = named_config_path("gaming");
+    assert!(path.starts_with(named_configs_dir()));
+    assert_eq!(path.file_name().unwrap(), "gaming.toml");
+}
+
+#[test]
+fn validate_config_name_accepts_simple_names() {
+    assert!(validate_config_name("work").is_ok());
+    assert!(validate_config_name("vivid_gaming-2").is_ok());
+}
+
+#[test]
+fn validate_config_name_rejects_paths_and_empty() {
+    assert!(validate_config_name("").is_err());
+    assert!(validate_config_name("../evil").is_err());
+    assert!(validate_config_name("a b").is_err());
+    assert!(validate_config_name(&"x".repeat(65)).is_err());
+}
+
+#[test]
+fn parse_config_pointer_trims_and_validates() {
+    assert_eq!(
+        parse_config_pointer("gaming\r\n"),
+        Some("gaming".to_string())
+    );
+    assert_eq!(parse_config_pointer("  "), None);
+    assert_eq!(parse_config_pointer("..\\x"), None);
+}
+
+#[test]
+fn list_config_names_starts_with_default() {
+    assert_eq!(list_config_names()[0], DEFAULT_CONFIG_NAME);
+}
+
+#[test]
+fn write_config_to_creates_parent_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("configs").join("work.toml");
+    Config::write_config_to(&Config::default(), &path).unwrap();
+    let parsed: Config = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(parsed, Config::default());
+}
+
+#[test]
+fn plan_write_to_matches_what_write_config_to_writes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    let mut plan = crate::plan::Plan::new("install");
+    assert!(Config::plan_write_to(&mut plan, &Config::default(), &path));
+    assert!(!path.exists());
+
+    Config::write_config_to(&Config::default(), &path).unwrap();
+    let mut plan = crate::plan::Plan::new("install");
+    assert!(!Config::plan_write_to(&mut plan, &Config::default(), &path));
+    let changed = Config {
+        monitor_match: "27GR95QE".to_string(),
+        ..Config::default()
+    };
+    assert!(Config::plan_write_to(&mut plan, &changed, &path));
+}
+
 // ── install_path ─────────────────────────────────────────────────
 
 #[test]
@@ -832,6 +1194,94 @@ fn write_and_read_config_roundtrip() {
     assert!(parsed.verbose);
 }
 
+// ── Atomic writes and recovery ───────────────────────────────────
+
+#[test]
+fn write_config_to_keeps_previous_good_file_as_backup() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.toml");
+    let first = Config {
+        monitor_match: "First".to_string(),
+        ..Config::default()
+    };
+    let second = Config {
+        monitor_match: "Second".to_string(),
+        ..Config::default()
+    };
+
+    Config::write_config_to(&first, &path).unwrap();
+    assert!(!backup_path(&path).exists(), "nothing to back up yet");
+    Config::write_config_to(&second, &path).unwrap();
+
+    assert_eq!(Config::load_from(&path).monitor_match, "Second");
+    assert_eq!(
+        Config::load_from(&backup_path(&path)).monitor_match,
+        "First"
+    );
+    assert!(
+        !tmp.path().join("config.toml.tmp").exists(),
+        "temp file should be renamed away"
+    );
+}
+
+#[test]
+fn write_config_to_does_not_back_up_corrupt_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.toml");
+    let good = Config {
+        monitor_match: "Good".to_string(),
+        ..Config::default()
+    };
+    Config::write_config_to(&good, &path).unwrap();
+    Config::write_config_to(&good, &path).unwrap();
+    fs::write(&path, "monitor_match = \"trunc").unwrap();
+
+    Config::write_config_to(&Config::default(), &path).unwrap();
+
+    assert_eq!(Config::load_from(&backup_path(&path)).monitor_match, "Good");
+}
+
+#[test]
+fn load_from_corrupt_file_uses_backup() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.toml");
+    let good = Config {
+        monitor_match: "Backup".to_string(),
+        ..Config::default()
+    };
+    fs::write(backup_path(&path), Config::to_toml_commented(&good)).unwrap();
+    fs::write(&path, "monitor_match = \"trunc").unwrap();
+
+    assert_eq!(Config::load_from(&path).monitor_match, "Backup");
+}
+
+#[test]
+fn load_from_corrupt_file_without_backup_uses_defaults() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("config.toml");
+    fs::write(&path, "[[[").unwrap();
+    fs::write(backup_path(&path), "also broken = ").unwrap();
+
+    assert_eq!(Config::load_from(&path), Config::default());
+}
+
+#[test]
+fn load_from_missing_file_uses_defaults() {
+    let tmp = tempfile::tempdir().unwrap();
+    assert_eq!(
+        Config::load_from(&tmp.path().join("missing.toml")),
+        Config::default()
+    );
+}
+
+#[test]
+fn backup_path_appends_bak() {
+    assert_eq!(
+        backup_path(Path::new("dir/config.toml")),
+        PathBuf::from("dir/config.toml.bak")
+    );
+}
+
 // ── Edge cases ───────────────────────────────────────────────────
 
 #[test]
@@ -1056,6 +1506,86 @@ fn parse_toml_ddc_brightness_defaults_when_omitted() {
     assert_eq!(cfg.ddc_brightness_value, 50);
 }
 
+#[test]
+fn parse_toml_brightness_guard_defaults_when_omitted() {
+    let cfg: Config = toml::from_str("monitor_match = \"TEST\"").unwrap();
+    assert!(!cfg.brightness_guard);
+    assert_eq!(cfg.brightness_guard_threshold, 3);
+    assert_eq!(cfg.brightness_guard_interval_ms, 2000);
+    assert_eq!(cfg.brightness_guard_max_per_minute, 6);
+}
+
+#[test]
+fn to_toml_commented_roundtrips_brightness_guard() {
+    let cfg = Config {
+        brightness_guard: true,
+        brightness_guard_threshold: 7,
+        brightness_guard_interval_ms: 500,
+        brightness_guard_max_per_minute: 2,
+        ..Config::default()
+    };
+    let parsed: Config = toml::from_str(&Config::to_toml_commented(&cfg)).unwrap();
+    assert!(parsed.brightness_guard);
+    assert_eq!(parsed.brightness_guard_threshold, 7);
+    assert_eq!(parsed.brightness_guard_interval_ms, 500);
+    assert_eq!(parsed.brightness_guard_max_per_minute, 2);
+}
+
+#[test]
+fn to_toml_commented_roundtrips_fullscreen_exit_settings() {
+    let cfg = Config {
+        fullscreen_exit_reassert: true,
+        fullscreen_exit_apps: "eldenring.exe, \"cs2\"".to_string(),
+        ..Config::default()
+    };
+    let parsed: Config = toml::from_str(&Config::to_toml_commented(&cfg)).unwrap();
+    assert!(parsed.fullscreen_exit_reassert);
+    assert_eq!(parsed.fullscreen_exit_apps, cfg.fullscreen_exit_apps);
+}
+
+#[test]
+fn default_config_hotkeys_disabled_with_bindings() {
+    let cfg = Config::default();
+    assert!(!cfg.hotkeys_enabled);
+    assert_eq!(cfg.hotkey_reapply, "Ctrl+Alt+F9");
+    assert_eq!(cfg.hotkey_brightness_step, 10);
+}
+
+#[test]
+fn to_toml_commented_roundtrips_app_overrides() {
+    let mut cfg = Config::default();
+    cfg.app_overrides
+        .insert("Photoshop.exe".to_string(), "none".to_string());
+    cfg.app_overrides
+        .insert("resolve.exe".to_string(), "sRGB \"ref\".icm".to_string());
+    let parsed: Config = toml::from_str(&Config::to_toml_commented(&cfg)).unwrap();
+    assert_eq!(parsed.app_overrides, cfg.app_overrides);
+}
+
+#[test]
+fn to_toml_commented_empty_app_overrides_parses_empty() {
+    let cfg = Config::default();
+    let output = Config::to_toml_commented(&cfg);
+    assert!(output.contains("[app_overrides]"));
+    let parsed: Config = toml::from_str(&output).unwrap();
+    assert!(parsed.app_overrides.is_empty());
+}
+
+#[test]
+fn parse_toml_app_overrides_table() {
+    let toml_str = r#"
+        monitor_match = "LG"
+
+        [app_overrides]
+        "photoshop.exe" = "none"
+    "#;
+    let cfg: Config = toml::from_str(toml_str).unwrap();
+    assert_eq!(
+        cfg.app_overrides.get("photoshop.exe").map(String::as_str),
+        Some("none")
+    );
+}
+
 #[test]
 fn to_toml_commented_contains_ddc_section() {
     let cfg = Config::default();
@@ -1073,3 +1603,34 @@ fn to_toml_commented_contains_ddc_section() {
         "should contain ddc value"
     );
 }
+
+#[test]
+fn default_config_has_fixed_schedule_times() {
+    let cfg = Config::default();
+    assert_eq!(cfg.icc_schedule_day_start, "07:00");
+    assert_eq!(cfg.icc_schedule_night_start, "19:00");
+}
+
+#[test]
+fn default_schedule_is_fixed_without_location() {
+    let cfg = Config::default();
+    assert_eq!(cfg.schedule.mode, "fixed");
+    assert!(!cfg.schedule.use_windows_location);
+}
+
+#[test]
+fn parse_toml_schedule_section() {
+    let cfg: Config = toml::from_str(
+        r#"
+[schedule]
+mode = "solar"
+latitude = 40.4168
+longitude = -3.7038
+"#,
+    )
+    .unwrap();
+    assert_eq!(cfg.schedule.mode, "solar");
+    assert_eq!(cfg.schedule.latitude, 40.4168);
+    assert_eq!(cfg.schedule.longitude, -3.7038);
+    assert!(!cfg.schedule.use_windows_location);
+}
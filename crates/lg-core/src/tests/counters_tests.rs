@@ -0,0 +1,72 @@
+use super::*;
+use chrono::TimeZone;
+
+fn sample(seconds: u32, reapplies: u64) -> CounterSample {
+    CounterSample {
+        at: Utc
+            .with_ymd_and_hms(2026, 10, 16, 12, 0, seconds)
+            .unwrap()
+            .with_timezone(&Local),
+        counters: ServiceCounters {
+            started_at: "2026-10-16T12:00:00.000Z".to_string(),
+            reapplies,
+            failures: 1,
+            last_apply_ms: 250,
+            ..ServiceCounters::default()
+        },
+    }
+}
+
+// ── Counting ─────────────────────────────────────────────────────
+
+#[test]
+fn record_apply_counts_failures_and_keeps_last_duration() {
+    let mut counters = ServiceCounters::new();
+    counters.record_apply(120, true);
+    counters.record_apply(900, false);
+    assert_eq!(counters.reapplies, 2);
+    assert_eq!(counters.failures, 1);
+    assert_eq!(counters.last_apply_ms, 900);
+}
+
+#[test]
+fn save_and_load_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state").join("counters.toml");
+    assert!(load_from(&path).is_none());
+    let mut counters = ServiceCounters::new();
+    counters.record_apply(42, true);
+    save_to(&path, &counters).unwrap();
+    assert_eq!(load_from(&path), Some(counters));
+}
+
+// ── typeperf CSV ─────────────────────────────────────────────────
+
+#[test]
+fn header_uses_pdh_counter_paths() {
+    assert_eq!(
+        csv_header("PC"),
+        r#""(PDH-CSV 4.0)","\\PC\LG UltraGear Service\Reapplies/sec","\\PC\LG UltraGear Service\Reapplies","\\PC\LG UltraGear Service\Failures","\\PC\LG UltraGear Service\Last Apply ms""#
+    );
+}
+
+#[test]
+fn row_rates_against_previous_sample_or_service_start() {
+    let first = sample(10, 5);
+    let row = csv_row(None, &first);
+    assert!(row.ends_with(r#","0.500000","5","1","250""#), "{}", row);
+
+    let second = sample(20, 9);
+    assert!(csv_row(Some(&first), &second).contains(r#","0.400000","9","#));
+
+    // A restarted service (counts went down) rates from its own start.
+    let restarted = sample(20, 2);
+    assert!(csv_row(Some(&first), &restarted).contains(r#","0.100000","2","#));
+}
+
+#[test]
+fn row_is_blank_without_counters() {
+    let mut missing = sample(0, 0);
+    missing.counters = ServiceCounters::default();
+    assert!(csv_row(None, &missing).ends_with(r#"," "," "," "," ""#));
+}
@@ -0,0 +1,151 @@
+use super::*;
+
+/// A monitor with a few VCP values; records every call.
+#[derive(Default)]
+struct Fake {
+    values: Vec<(u8, u32)>,
+    failing: Vec<u8>,
+    calls: Vec<String>,
+}
+
+impl VcpPort for Fake {
+    fn get_vcp(&mut self, code: u8) -> Result<(u32, u32), Box<dyn Error>> {
+        self.calls.push(format!("get {:02X}", code));
+        if self.failing.contains(&code) {
+            return Err("monitor did not respond".into());
+        }
+        let current = self
+            .values
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map_or(0, |(_, v)| *v);
+        Ok((current, 100))
+    }
+
+    fn set_vcp(&mut self, code: u8, value: u32) -> Result<(), Box<dyn Error>> {
+        self.calls.push(format!("set {:02X}={}", code, value));
+        self.values.retain(|(c, _)| *c != code);
+        self.values.push((code, value));
+        Ok(())
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        self.calls.push(format!("sleep {}", duration.as_millis()));
+    }
+}
+
+const DIMMING_OFF: &str = r#"
+name = "disable auto dimming"
+monitor_match = "27GN950"
+
+[[step]]
+op = "set"
+code = 0xDC
+value = 5
+
+[[step]]
+op = "sleep"
+ms = 200
+
+[[step]]
+op = "assert"
+code = 0xDC
+equals = 5
+
+[[step]]
+op = "get"
+code = 0x10
+"#;
+
+// ── Parsing ──────────────────────────────────────────────────────
+
+#[test]
+fn parses_steps_with_hex_codes() {
+    let script = DdcScript::from_toml(DIMMING_OFF).unwrap();
+    assert_eq!(script.monitor_match.as_deref(), Some("27GN950"));
+    assert_eq!(
+        script.steps[0],
+        ScriptStep::Set {
+            code: 0xDC,
+            value: 5
+        }
+    );
+    assert_eq!(script.steps[2].to_string(), "assert 0xDC == 5");
+    assert_eq!(script.writes().collect::<Vec<_>>(), [(0xDC, 5)]);
+}
+
+#[test]
+fn rejects_unknown_ops_fields_and_empty_scripts() {
+    assert!(DdcScript::from_toml("[[step]]\nop = \"reset\"\ncode = 4").is_err());
+    assert!(DdcScript::from_toml("[[step]]\nop = \"get\"\ncode = 16\nvalue = 1").is_err());
+    assert!(DdcScript::from_toml("[[step]]\nop = \"get\"\ncode = 256").is_err());
+    let err = DdcScript::from_toml("name = \"empty\"").unwrap_err();
+    assert!(err.contains("no [[step]]"), "{}", err);
+}
+
+#[test]
+fn validation_names_the_bad_step() {
+    let err = DdcScript::from_toml(
+        "[[step]]\nop = \"get\"\ncode = 16\n[[step]]\nop = \"assert\"\ncode = 16",
+    )
+    .unwrap_err();
+    assert_eq!(err, "step 2: assert needs equals, min or max");
+    let err = DdcScript::from_toml("[[step]]\nop = \"sleep\"\nms = 600000").unwrap_err();
+    assert!(err.starts_with("step 1: sleep is capped"), "{}", err);
+    let err =
+        DdcScript::from_toml("[[step]]\nop = \"assert\"\ncode = 16\nmin = 9\nmax = 3").unwrap_err();
+    assert!(err.contains("min 9 is above max 3"), "{}", err);
+}
+
+#[test]
+fn assert_checks_every_bound() {
+    let step = ScriptStep::Assert {
+        code: 0x10,
+        equals: None,
+        min: Some(20),
+        max: Some(80),
+    };
+    assert_eq!(step.assert_failure(50), None);
+    assert_eq!(
+        step.assert_failure(90).as_deref(),
+        Some("expected 20..80, read 90")
+    );
+    assert_eq!(ScriptStep::Get { code: 0x10 }.assert_failure(0), None);
+}
+
+// ── Running ──────────────────────────────────────────────────────
+
+#[test]
+fn run_executes_steps_in_order_and_reports_each() {
+    let script = DdcScript::from_toml(DIMMING_OFF).unwrap();
+    let mut fake = Fake {
+        values: vec![(0x10, 70)],
+        ..Fake::default()
+    };
+    let results = run(&script, &mut fake);
+
+    assert_eq!(fake.calls, ["set DC=5", "sleep 200", "get DC", "get 10"]);
+    assert!(results.iter().all(|r| r.ok));
+    assert_eq!(results[2].detail, "read 5");
+    assert_eq!(results[3].detail, "current=70 max=100");
+    assert!(results[3].to_string().starts_with("  4. get 0x10"));
+}
+
+#[test]
+fn run_stops_at_the_first_failure() {
+    let script = DdcScript::from_toml(DIMMING_OFF).unwrap();
+    let mut fake = Fake {
+        failing: vec![0xDC],
+        ..Fake::default()
+    };
+    let results = run(&script, &mut fake);
+    assert_eq!(results.len(), 3);
+    assert!(!results[2].ok);
+    assert_eq!(results[2].detail, "monitor did not respond");
+    assert!(!fake.calls.contains(&"get 10".to_string()));
+
+    let mut fake = Fake::default();
+    let script = DdcScript::from_toml("[[step]]\nop = \"assert\"\ncode = 16\nequals = 80").unwrap();
+    let results = run(&script, &mut fake);
+    assert_eq!(results[0].detail, "expected 80, read 0");
+}
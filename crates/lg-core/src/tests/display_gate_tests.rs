@@ -0,0 +1,85 @@
+use super::*;
+
+fn counts(matching: usize, active: usize, internal_panel: Option<bool>) -> DisplayCounts {
+    DisplayCounts {
+        matching,
+        active,
+        internal_panel,
+        lid: None,
+    }
+}
+
+// ── Conditions ───────────────────────────────────────────────────
+
+#[test]
+fn defaults_never_skip() {
+    let cfg = Config::default();
+    assert!(!is_enabled(&cfg));
+    assert_eq!(skip_reason(&cfg, &counts(0, 0, Some(false))), None);
+    assert_eq!(skip_reason(&cfg, &counts(1, 1, Some(false))), None);
+}
+
+#[test]
+fn min_matching_monitors_skips_below_the_minimum() {
+    let cfg = Config {
+        min_matching_monitors: 2,
+        ..Config::default()
+    };
+    assert!(is_enabled(&cfg));
+    let reason = skip_reason(&cfg, &counts(1, 2, None)).expect("skipped");
+    assert!(reason.contains("min_matching_monitors = 2"), "{}", reason);
+    assert_eq!(skip_reason(&cfg, &counts(2, 3, None)), None);
+}
+
+#[test]
+fn only_when_monitor_count_needs_the_exact_count() {
+    let cfg = Config {
+        only_when_monitor_count: 2,
+        ..Config::default()
+    };
+    assert!(skip_reason(&cfg, &counts(1, 1, None)).is_some());
+    assert!(skip_reason(&cfg, &counts(1, 3, None)).is_some());
+    assert_eq!(skip_reason(&cfg, &counts(1, 2, None)), None);
+}
+
+#[test]
+fn lid_closed_skips_only_when_the_lg_is_alone() {
+    let cfg = Config {
+        skip_reapply_lid_closed: true,
+        ..Config::default()
+    };
+    assert!(skip_reason(&cfg, &counts(1, 1, Some(false))).is_some());
+    // Lid open, a second external display, or a desktop without a panel.
+    assert_eq!(skip_reason(&cfg, &counts(1, 2, Some(true))), None);
+    assert_eq!(skip_reason(&cfg, &counts(1, 2, Some(false))), None);
+    assert_eq!(skip_reason(&cfg, &counts(1, 1, None)), None);
+}
+
+#[test]
+fn reported_lid_state_wins_over_the_panel() {
+    let cfg = Config {
+        skip_reapply_lid_closed: true,
+        ..Config::default()
+    };
+    let closed = DisplayCounts {
+        lid: Some(LidState::Closed),
+        ..counts(1, 1, None)
+    };
+    assert!(skip_reason(&cfg, &closed).is_some());
+    let open = DisplayCounts {
+        lid: Some(LidState::Open),
+        ..counts(1, 1, Some(false))
+    };
+    assert_eq!(skip_reason(&cfg, &open), None);
+}
+
+// ── Lid state ────────────────────────────────────────────────────
+
+#[test]
+fn lid_state_round_trips_through_its_name() {
+    for lid in [LidState::Open, LidState::Closed] {
+        assert_eq!(LidState::parse(lid.as_str()), Some(lid));
+    }
+    assert_eq!(LidState::parse(" Closed\n"), Some(LidState::Closed));
+    assert_eq!(LidState::parse("unknown"), None);
+}
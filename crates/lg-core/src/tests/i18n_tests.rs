@@ -0,0 +1,87 @@
+use super::*;
+
+// ── Language selection ───────────────────────────────────────────
+
+#[test]
+fn codes_and_locale_names_parse() {
+    assert_eq!(Language::parse("de"), Some(Language::German));
+    assert_eq!(Language::parse(" pt_BR "), Some(Language::Portuguese));
+    assert_eq!(Language::parse("FR-ca"), Some(Language::French));
+    assert_eq!(Language::parse("zh-CN"), Some(Language::Chinese));
+    assert_eq!(Language::parse("zh-TW"), None);
+    assert_eq!(Language::parse("nl"), None);
+    assert_eq!(Language::parse(""), None);
+}
+
+#[test]
+fn codes_round_trip() {
+    for lang in Language::ALL {
+        assert_eq!(Language::parse(lang.code()), Some(lang));
+    }
+}
+
+#[test]
+fn langids_map_by_primary_language() {
+    assert_eq!(Language::from_langid(0x0409), Some(Language::English));
+    assert_eq!(Language::from_langid(0x0c07), Some(Language::German));
+    assert_eq!(Language::from_langid(0x0416), Some(Language::Portuguese));
+    assert_eq!(Language::from_langid(0x0411), Some(Language::Japanese));
+    assert_eq!(Language::from_langid(0x0804), Some(Language::Chinese));
+    assert_eq!(Language::from_langid(0x0404), None, "zh-TW");
+    assert_eq!(Language::from_langid(0x0413), None, "nl-NL");
+}
+
+#[test]
+fn setting_wins_over_the_system_language() {
+    assert_eq!(
+        resolve("", Some(Language::Korean)),
+        Language::Korean,
+        "empty follows Windows"
+    );
+    assert_eq!(resolve("", None), Language::English);
+    assert_eq!(resolve("es", Some(Language::Korean)), Language::Spanish);
+    assert_eq!(
+        resolve("klingon", Some(Language::Korean)),
+        Language::English
+    );
+}
+
+// ── Texts ────────────────────────────────────────────────────────
+
+#[test]
+fn every_table_fills_its_counts() {
+    for lang in Language::ALL {
+        let strings = lang.strings();
+        assert!(strings.toast_body.ends_with('✓'), "{:?}", lang);
+        assert!(!strings.no_monitors.is_empty(), "{:?}", lang);
+        assert!(!strings.all_reapplied.is_empty(), "{:?}", lang);
+        let count = strings.reapplied_count(7);
+        assert!(count.contains('7') && !count.contains('{'), "{:?}", lang);
+        let failed = strings.monitors_failed(3, 5);
+        assert!(failed.contains('3') && failed.contains('5'), "{:?}", lang);
+        assert!(!failed.contains('{'), "{:?}", lang);
+    }
+}
+
+#[test]
+fn english_lines_are_unchanged() {
+    assert_eq!(
+        ENGLISH.reapplied_count(2),
+        "Profile refreshed for 2 monitor(s)."
+    );
+    assert_eq!(ENGLISH.monitors_failed(1, 2), "1 of 2 monitor(s) failed");
+}
+
+#[test]
+fn only_the_default_toast_body_is_translated() {
+    let mut cfg = Config {
+        language: "de".to_string(),
+        ..Config::default()
+    };
+    assert_eq!(toast_body(&cfg), "Farbprofil erneut angewendet ✓");
+    cfg.toast_body = "{monitors} ok".to_string();
+    assert_eq!(toast_body(&cfg), "{monitors} ok");
+    cfg.language = "en".to_string();
+    cfg.toast_body = Config::default().toast_body;
+    assert_eq!(toast_body(&cfg), "Color profile reapplied ✓");
+}
@@ -0,0 +1,124 @@
+use super::*;
+
+fn reading(at: &str, cd_m2: f64) -> LuminanceReading {
+    LuminanceReading {
+        at: at.to_string(),
+        cd_m2,
+        source: SOURCE_MANUAL.to_string(),
+        trigger: "apply".to_string(),
+    }
+}
+
+// ── Output parsing ───────────────────────────────────────────────
+
+#[test]
+fn spotread_yxy_output_gives_y() {
+    let output = "Place instrument on spot to be measured,\n\
+                  Result is Y: 118.402356, Yxy: 118.402356 0.312700 0.329000\n";
+    assert_eq!(parse_luminance_output(output), Some(118.402356));
+}
+
+#[test]
+fn spotread_xyz_output_gives_the_middle_value() {
+    let output = " Result is XYZ: 112.531 118.402 128.930, D50 Lab: 100.0 0.1 -0.2\n";
+    assert_eq!(parse_luminance_output(output), Some(118.402));
+}
+
+#[test]
+fn plain_number_output_is_accepted() {
+    assert_eq!(parse_luminance_output("  243.5\r\n"), Some(243.5));
+    assert_eq!(parse_luminance_output("luminance=97 cd/m2"), Some(97.0));
+}
+
+#[test]
+fn output_without_a_usable_value_is_none() {
+    assert_eq!(parse_luminance_output("Instrument not found"), None);
+    assert_eq!(parse_luminance_output("-5"), None);
+    assert_eq!(parse_luminance_output(""), None);
+}
+
+// ── Command line splitting ───────────────────────────────────────
+
+#[test]
+fn command_line_splits_on_spaces_outside_quotes() {
+    assert_eq!(
+        split_command_line(r#""C:\Program Files\Argyll\bin\spotread.exe" -x  -O"#),
+        vec![
+            r"C:\Program Files\Argyll\bin\spotread.exe".to_string(),
+            "-x".to_string(),
+            "-O".to_string()
+        ]
+    );
+    assert_eq!(split_command_line(r#"tool """#), vec!["tool", ""]);
+    assert!(split_command_line("   ").is_empty());
+}
+
+#[test]
+fn empty_measure_command_is_an_error() {
+    let err = run_measure_command("  ", Duration::from_secs(1)).unwrap_err();
+    assert_eq!(err.to_string(), "measure_command is empty");
+}
+
+// ── Log file ─────────────────────────────────────────────────────
+
+#[test]
+fn readings_round_trip_through_the_log() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state").join("luminance.jsonl");
+    assert!(read_readings_from(&path).unwrap().is_empty());
+
+    let first = reading("2026-10-14T08:00:00Z", 120.5);
+    let second = reading("2026-10-14T20:00:00Z", 96.0);
+    append_reading_to(&path, &first).unwrap();
+    append_reading_to(&path, &second).unwrap();
+    let mut text = fs::read_to_string(&path).unwrap();
+    text.push_str("not json\n");
+    fs::write(&path, text).unwrap();
+
+    assert_eq!(read_readings_from(&path).unwrap(), vec![first, second]);
+}
+
+// ── Daily ranges ─────────────────────────────────────────────────
+
+#[test]
+fn readings_are_grouped_by_day() {
+    let ranges = daily_ranges(&[
+        reading("2026-10-15T09:00:00Z", 110.0),
+        reading("2026-10-14T08:00:00Z", 120.0),
+        reading("2026-10-14T20:00:00Z", 96.0),
+    ]);
+    assert_eq!(
+        ranges,
+        vec![
+            DailyRange {
+                day: "2026-10-14".to_string(),
+                min: 96.0,
+                max: 120.0,
+                samples: 2,
+            },
+            DailyRange {
+                day: "2026-10-15".to_string(),
+                min: 110.0,
+                max: 110.0,
+                samples: 1,
+            },
+        ]
+    );
+}
+
+#[test]
+fn range_bar_marks_the_span_on_a_shared_scale() {
+    let ranges = daily_ranges(&[
+        reading("2026-10-14T08:00:00Z", 100.0),
+        reading("2026-10-14T20:00:00Z", 200.0),
+        reading("2026-10-15T09:00:00Z", 150.0),
+    ]);
+    assert_eq!(range_bar(&ranges[0], &ranges, 5), "█████");
+    assert_eq!(range_bar(&ranges[1], &ranges, 5), "··█··");
+}
+
+#[test]
+fn range_bar_of_a_single_value_sits_in_the_middle() {
+    let ranges = daily_ranges(&[reading("2026-10-14T08:00:00Z", 100.0)]);
+    assert_eq!(range_bar(&ranges[0], &ranges, 5), "··█··");
+}
@@ -0,0 +1,397 @@
+use super::*;
+
+/// Records every call; monitors are plain names.
+#[derive(Default)]
+struct Fake {
+    shared: Option<ModeProfiles>,
+    shared_error: bool,
+    missing: Vec<PathBuf>,
+    monitors: Vec<&'static str>,
+    failing: Vec<&'static str>,
+    failing_profiles: Vec<&'static str>,
+    failing_default: Vec<&'static str>,
+    ddc_error: bool,
+    calls: Vec<String>,
+    done: Vec<(String, bool, Vec<String>)>,
+}
+
+impl Fake {
+    fn shared(monitors: &[&'static str]) -> Self {
+        Self {
+            shared: Some(profiles("shared")),
+            monitors: monitors.to_vec(),
+            ..Self::default()
+        }
+    }
+
+    fn per_monitor(monitors: &[&'static str]) -> Self {
+        Self {
+            monitors: monitors.to_vec(),
+            ..Self::default()
+        }
+    }
+}
+
+fn profiles(stem: &str) -> ModeProfiles {
+    ModeProfiles {
+        sdr: PathBuf::from(format!("{}-sdr.icm", stem)),
+        hdr: PathBuf::from(format!("{}-hdr.icm", stem)),
+    }
+}
+
+impl ReapplySteps for Fake {
+    type Monitor = &'static str;
+
+    fn ensure_shared_profiles(&mut self) -> Result<Option<ModeProfiles>, Box<dyn Error>> {
+        self.calls.push("ensure_shared".to_string());
+        if self.shared_error {
+            return Err("disk full".into());
+        }
+        Ok(self.shared.clone())
+    }
+
+    fn is_installed(&self, path: &Path) -> bool {
+        !self.missing.iter().any(|m| m == path)
+    }
+
+    fn find_monitors(&mut self) -> Result<Vec<&'static str>, Box<dyn Error>> {
+        self.calls.push("find".to_string());
+        Ok(self.monitors.clone())
+    }
+
+    fn inherit_associations(&mut self, monitor: &&'static str) {
+        self.calls.push(format!("inherit {}", monitor));
+    }
+
+    fn ensure_monitor_profiles(
+        &mut self,
+        monitor: &&'static str,
+    ) -> Result<ModeProfiles, Box<dyn Error>> {
+        self.calls.push(format!("ensure {}", monitor));
+        if self.failing_profiles.contains(monitor) {
+            return Err("generation failed".into());
+        }
+        Ok(profiles(monitor))
+    }
+
+    fn associate(
+        &mut self,
+        monitor: &&'static str,
+        active: &Path,
+        sdr: &Path,
+        hdr: Option<&Path>,
+        per_user: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        self.calls.push(format!(
+            "associate {} sdr={} hdr={} active={} per_user={}",
+            monitor,
+            sdr.display(),
+            hdr.map(|p| p.display().to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            active.display(),
+            per_user
+        ));
+        if self.failing.contains(monitor) {
+            return Err("access denied".into());
+        }
+        Ok(())
+    }
+
+    fn set_generic_default(
+        &mut self,
+        monitor: &&'static str,
+        active: &Path,
+        _per_user: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        self.calls
+            .push(format!("default {} {}", monitor, active.display()));
+        if self.failing_default.contains(monitor) {
+            return Err("default rejected".into());
+        }
+        Ok(())
+    }
+
+    fn refresh(&mut self) {
+        self.calls.push("refresh".to_string());
+    }
+
+    fn set_ddc_brightness(&mut self, level: u32) -> Result<usize, Box<dyn Error>> {
+        self.calls.push(format!("ddc {}", level));
+        if self.ddc_error {
+            return Err("no DDC".into());
+        }
+        Ok(self.monitors.len())
+    }
+
+    fn toast(&mut self) {
+        self.calls.push("toast".to_string());
+    }
+
+    fn monitor_done(&mut self, monitor: &&'static str, result: &MonitorResult<'_>) {
+        self.done.push((
+            monitor.to_string(),
+            result.ok(),
+            result.steps.iter().map(|s| s.step.clone()).collect(),
+        ));
+    }
+}
+
+fn options() -> ReapplyOptions {
+    ReapplyOptions {
+        hdr: true,
+        ..ReapplyOptions::default()
+    }
+}
+
+// ── Order ────────────────────────────────────────────────────────
+
+#[test]
+fn runs_every_step_in_order() {
+    let mut fake = Fake::shared(&["a", "b"]);
+    let outcome = ReapplyPipeline::new(ReapplyOptions {
+        ddc_brightness: Some(40),
+        toast: true,
+        ..options()
+    })
+    .run(&mut fake)
+    .unwrap();
+
+    assert_eq!(
+        fake.calls,
+        vec![
+            "ensure_shared",
+            "find",
+            "inherit a",
+            "associate a sdr=shared-sdr.icm hdr=shared-hdr.icm active=shared-sdr.icm per_user=false",
+            "inherit b",
+            "associate b sdr=shared-sdr.icm hdr=shared-hdr.icm active=shared-sdr.icm per_user=false",
+            "refresh",
+            "ddc 40",
+            "toast",
+        ]
+    );
+    assert_eq!(outcome.found, 2);
+    assert_eq!(outcome.applied, 2);
+    assert_eq!(outcome.failed(), 0);
+    assert_eq!(outcome.last_applied, Some(PathBuf::from("shared-sdr.icm")));
+    assert!(matches!(outcome.ddc, Some(Ok(2))));
+    assert!(outcome.toasted);
+}
+
+#[test]
+fn ddc_and_toast_are_skipped_unless_asked_for() {
+    let mut fake = Fake::shared(&["a"]);
+    let outcome = ReapplyPipeline::new(options()).run(&mut fake).unwrap();
+    assert_eq!(fake.calls.last().map(String::as_str), Some("refresh"));
+    assert!(outcome.ddc.is_none());
+    assert!(!outcome.toasted);
+}
+
+#[test]
+fn ddc_level_is_capped_and_its_failure_is_reported() {
+    let mut fake = Fake {
+        ddc_error: true,
+        ..Fake::shared(&["a"])
+    };
+    let outcome = ReapplyPipeline::new(ReapplyOptions {
+        ddc_brightness: Some(250),
+        ..options()
+    })
+    .run(&mut fake)
+    .unwrap();
+    assert!(fake.calls.contains(&"ddc 100".to_string()));
+    assert!(matches!(outcome.ddc, Some(Err(_))));
+    assert_eq!(outcome.applied, 1);
+}
+
+// ── Profiles ─────────────────────────────────────────────────────
+
+#[test]
+fn hdr_active_makes_the_hdr_profile_current() {
+    let mut fake = Fake::shared(&["a"]);
+    let outcome = ReapplyPipeline::new(ReapplyOptions {
+        hdr_active: true,
+        per_user: true,
+        ..options()
+    })
+    .run(&mut fake)
+    .unwrap();
+    assert!(fake.calls.contains(
+        &"associate a sdr=shared-sdr.icm hdr=shared-hdr.icm active=shared-hdr.icm per_user=true"
+            .to_string()
+    ));
+    assert_eq!(outcome.last_applied, Some(PathBuf::from("shared-hdr.icm")));
+}
+
+#[test]
+fn hdr_association_off_skips_the_hdr_profile() {
+    let mut fake = Fake::per_monitor(&["a"]);
+    ReapplyPipeline::new(ReapplyOptions {
+        hdr: false,
+        hdr_active: true,
+        ..ReapplyOptions::default()
+    })
+    .run(&mut fake)
+    .unwrap();
+    assert!(fake
+        .calls
+        .contains(&"associate a sdr=a-sdr.icm hdr=- active=a-sdr.icm per_user=false".to_string()));
+}
+
+#[test]
+fn per_monitor_profiles_are_generated_for_each_monitor() {
+    let mut fake = Fake::per_monitor(&["a", "b"]);
+    ReapplyPipeline::new(options()).run(&mut fake).unwrap();
+    assert!(fake.calls.contains(&"ensure a".to_string()));
+    assert!(fake.calls.contains(&"ensure b".to_string()));
+    assert!(fake.calls.contains(
+        &"associate b sdr=b-sdr.icm hdr=b-hdr.icm active=b-sdr.icm per_user=false".to_string()
+    ));
+}
+
+#[test]
+fn shared_profile_failure_stops_before_matching() {
+    let mut fake = Fake {
+        shared_error: true,
+        ..Fake::shared(&["a"])
+    };
+    let err = ReapplyPipeline::new(options()).run(&mut fake).unwrap_err();
+    assert!(matches!(err, ReapplyError::Profiles(_)));
+    assert_eq!(
+        err.to_string(),
+        "failed to generate active profile: disk full"
+    );
+    assert_eq!(fake.calls, vec!["ensure_shared"]);
+}
+
+#[test]
+fn uninstalled_shared_profile_stops_before_matching() {
+    let mut fake = Fake {
+        missing: vec![PathBuf::from("shared-hdr.icm")],
+        ..Fake::shared(&["a"])
+    };
+    let err = ReapplyPipeline::new(options()).run(&mut fake).unwrap_err();
+    assert!(matches!(err, ReapplyError::NotInstalled(_)));
+    assert!(err.to_string().starts_with("ICC mode profile not found"));
+    assert_eq!(fake.calls, vec!["ensure_shared"]);
+}
+
+// ── Failures ─────────────────────────────────────────────────────
+
+#[test]
+fn no_monitors_runs_nothing_after_the_match() {
+    let mut fake = Fake::shared(&[]);
+    let outcome = ReapplyPipeline::new(ReapplyOptions {
+        toast: true,
+        ddc_brightness: Some(50),
+        ..options()
+    })
+    .run(&mut fake)
+    .unwrap();
+    assert_eq!(outcome.found, 0);
+    assert_eq!(fake.calls, vec!["ensure_shared", "find"]);
+}
+
+#[test]
+fn a_failing_monitor_does_not_stop_the_others() {
+    let mut fake = Fake {
+        failing: vec!["a"],
+        ..Fake::shared(&["a", "b"])
+    };
+    let outcome = ReapplyPipeline::new(ReapplyOptions {
+        toast: true,
+        ..options()
+    })
+    .run(&mut fake)
+    .unwrap();
+    assert_eq!(outcome.applied, 1);
+    assert_eq!(outcome.failed(), 1);
+    assert_eq!(outcome.first_error.as_deref(), Some("access denied"));
+    assert!(outcome.toasted);
+    assert_eq!(
+        fake.done,
+        vec![
+            (
+                "a".to_string(),
+                false,
+                vec!["mode_associations".to_string()]
+            ),
+            ("b".to_string(), true, vec!["mode_associations".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn monitor_profile_failure_skips_that_monitor() {
+    let mut fake = Fake {
+        failing_profiles: vec!["a"],
+        ..Fake::per_monitor(&["a", "b"])
+    };
+    let outcome = ReapplyPipeline::new(options()).run(&mut fake).unwrap();
+    assert!(!fake.calls.iter().any(|c| c.starts_with("associate a")));
+    assert_eq!(outcome.applied, 1);
+    assert_eq!(
+        fake.done[0],
+        ("a".to_string(), false, vec!["monitor_profiles".to_string()])
+    );
+}
+
+#[test]
+fn nothing_applied_skips_refresh_ddc_and_toast() {
+    let mut fake = Fake {
+        failing: vec!["a"],
+        ..Fake::shared(&["a"])
+    };
+    let outcome = ReapplyPipeline::new(ReapplyOptions {
+        toast: true,
+        ddc_brightness: Some(50),
+        ..options()
+    })
+    .run(&mut fake)
+    .unwrap();
+    assert_eq!(outcome.applied, 0);
+    assert!(!fake.calls.contains(&"refresh".to_string()));
+    assert!(outcome.ddc.is_none());
+    assert!(!outcome.toasted);
+}
+
+// ── Generic default ──────────────────────────────────────────────
+
+#[test]
+fn generic_default_follows_a_successful_association() {
+    let mut fake = Fake {
+        failing: vec!["a"],
+        ..Fake::shared(&["a", "b"])
+    };
+    ReapplyPipeline::new(ReapplyOptions {
+        generic_default: true,
+        ..options()
+    })
+    .run(&mut fake)
+    .unwrap();
+    assert!(!fake.calls.contains(&"default a shared-sdr.icm".to_string()));
+    assert!(fake.calls.contains(&"default b shared-sdr.icm".to_string()));
+    assert_eq!(
+        fake.done[1].2,
+        vec![
+            "mode_associations".to_string(),
+            "generic_default".to_string()
+        ]
+    );
+}
+
+#[test]
+fn generic_default_failure_fails_the_monitor() {
+    let mut fake = Fake {
+        failing_default: vec!["a"],
+        ..Fake::shared(&["a"])
+    };
+    let outcome = ReapplyPipeline::new(ReapplyOptions {
+        generic_default: true,
+        ..options()
+    })
+    .run(&mut fake)
+    .unwrap();
+    assert_eq!(outcome.applied, 0);
+    assert_eq!(outcome.first_error.as_deref(), Some("default rejected"));
+}
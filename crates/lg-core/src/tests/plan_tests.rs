@@ -0,0 +1,72 @@
+use super::*;
+
+// ── Virtual files ────────────────────────────────────────────────
+
+#[test]
+fn write_file_skips_identical_content_on_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("profile.icm");
+    std::fs::write(&path, b"same").unwrap();
+
+    let mut plan = Plan::new("install");
+    assert!(!plan.write_file("write_profile", &path, b"same"));
+    assert!(plan.write_file("write_profile", &path, b"different"));
+    assert_eq!(
+        plan.operations[0].detail.as_deref(),
+        Some("replace 4 -> 9 bytes")
+    );
+    // The disk is untouched.
+    assert_eq!(std::fs::read(&path).unwrap(), b"same");
+}
+
+#[test]
+fn later_steps_see_earlier_planned_writes_and_removals() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("profile.icm");
+
+    let mut plan = Plan::new("install");
+    assert!(!plan.remove_file("remove_profile", &path));
+    assert!(plan.write_file("write_profile", &path, b"abc"));
+    assert!(!plan.write_file("write_profile", &path, b"abc"));
+    assert!(plan.file_exists(&path));
+    assert!(plan.remove_file("remove_profile", &path));
+    assert!(!plan.file_exists(&path));
+    assert!(!path.exists());
+
+    let actions: Vec<_> = plan.operations.iter().map(|o| o.action.as_str()).collect();
+    assert_eq!(actions, ["write_profile", "remove_profile"]);
+    assert_eq!(plan.operations[0].detail.as_deref(), Some("new, 3 bytes"));
+}
+
+// ── Output ───────────────────────────────────────────────────────
+
+#[test]
+fn render_numbers_operations_and_lists_notes() {
+    let mut plan = Plan::new("uninstall");
+    assert!(plan.render().contains("(nothing to do)"));
+
+    plan.push(OpKind::Service, "stop", "lg-ultragear-color-svc");
+    plan.push(OpKind::Registry, "delete_key", r"HKLM\SOFTWARE\Test")
+        .detail("event log source");
+    plan.note("service is running");
+    let text = plan.render();
+    assert!(text.starts_with("Plan for `uninstall` (2 operation(s)):"));
+    assert!(text.contains("  1. service  stop"), "{}", text);
+    assert!(text.contains(r"HKLM\SOFTWARE\Test  (event log source)"));
+    assert!(text.ends_with("Notes:\n  - service is running\n"));
+}
+
+#[test]
+fn json_lists_operations_in_order_without_file_overlay() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut plan = Plan::new("install");
+    plan.write_file("write_config", &dir.path().join("config.toml"), b"x = 1");
+    plan.push(OpKind::Wcs, "install_profile", "a.icm");
+
+    let json: serde_json::Value = serde_json::from_str(&plan.to_json().unwrap()).unwrap();
+    assert_eq!(json["command"], "install");
+    assert_eq!(json["operations"][0]["kind"], "file");
+    assert_eq!(json["operations"][1]["kind"], "wcs");
+    assert!(json["operations"][1].get("detail").is_none());
+    assert!(json.get("files").is_none());
+}
@@ -0,0 +1,81 @@
+use super::*;
+
+// ── Lookup ───────────────────────────────────────────────────────
+
+#[test]
+fn find_is_case_insensitive() {
+    assert_eq!(find("KVM").map(|p| p.name), Some("kvm"));
+    assert_eq!(find("Dock").map(|p| p.name), Some("dock"));
+    assert!(find("gaming").is_none());
+    assert_eq!(names(), "kvm, dock, aggressive, default");
+}
+
+// ── Embedded presets ─────────────────────────────────────────────
+
+#[test]
+fn every_preset_parses_and_applies_to_defaults() {
+    for preset in PRESETS {
+        let table = preset.table().expect(preset.name);
+        assert!(!table.is_empty(), "{} is empty", preset.name);
+        preset.apply(&Config::default()).expect(preset.name);
+    }
+}
+
+#[test]
+fn default_preset_covers_every_key_at_its_default() {
+    let default = find("default").unwrap().table().unwrap();
+    for preset in PRESETS {
+        for key in preset.table().unwrap().keys() {
+            assert!(
+                default.contains_key(key),
+                "default preset misses `{}` from {}",
+                key,
+                preset.name
+            );
+        }
+    }
+    let diff = find("default").unwrap().diff(&Config::default()).unwrap();
+    assert!(diff.is_empty(), "default preset differs: {:?}", diff);
+}
+
+// ── Diff / apply ─────────────────────────────────────────────────
+
+#[test]
+fn apply_overlays_preset_and_keeps_other_settings() {
+    let cfg = Config {
+        monitor_match: "27GR95QE".to_string(),
+        kvm_mode: true,
+        ..Config::default()
+    };
+    let applied = find("dock").unwrap().apply(&cfg).unwrap();
+    assert!(applied.dock_mode);
+    assert!(!applied.kvm_mode);
+    assert_eq!(applied.stabilize_delay_ms, 5000);
+    assert_eq!(applied.reapply_delay_ms, 20000);
+    assert_eq!(applied.monitor_match, "27GR95QE");
+}
+
+#[test]
+fn diff_lists_only_changed_keys() {
+    let cfg = Config {
+        kvm_mode: true,
+        ..Config::default()
+    };
+    assert!(find("kvm").unwrap().diff(&cfg).unwrap().is_empty());
+
+    let diff = find("aggressive").unwrap().diff(&cfg).unwrap();
+    let tamper = diff.iter().find(|c| c.key == "tamper_watch").unwrap();
+    assert_eq!(tamper.to_string(), "tamper_watch = \"off\" -> \"reassert\"");
+    assert!(diff.iter().all(|c| c.key != "kvm_mode"));
+}
+
+#[test]
+fn apply_rejects_unknown_keys() {
+    let preset = Preset {
+        name: "typo",
+        summary: "",
+        source: "stabilise_delay_ms = 10",
+    };
+    let err = preset.apply(&Config::default()).unwrap_err();
+    assert!(err.contains("stabilise_delay_ms"), "{}", err);
+}
@@ -0,0 +1,31 @@
+use super::*;
+
+// ── Progress ─────────────────────────────────────────────────────
+
+#[test]
+fn steps_are_numbered_against_the_total() {
+    let mut events = Vec::new();
+    let mut record = |e: &ProgressEvent| events.push(e.to_string());
+    let mut progress = Progress::new(2, &mut record);
+    progress.step("Stopping existing service");
+    progress.detail("waiting for the service to stop");
+    progress.step("Copying binary");
+    progress.step("Registering service");
+    assert_eq!(
+        events,
+        [
+            "[1/2] Stopping existing service...",
+            "      waiting for the service to stop",
+            "[2/2] Copying binary...",
+            "[3/3] Registering service...",
+        ]
+    );
+}
+
+#[test]
+fn ignore_accepts_every_event() {
+    let mut callback = ignore;
+    let mut progress = Progress::new(1, &mut callback);
+    progress.step("Copying binary");
+    progress.detail("done");
+}
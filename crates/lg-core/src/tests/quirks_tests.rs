@@ -0,0 +1,203 @@
+use super::*;
+
+// ── Built-in database ────────────────────────────────────────────
+
+#[test]
+fn builtin_quirks_parse_and_flag_lg_factory_profile() {
+    let quirks = builtin();
+    assert!(!quirks.is_empty());
+    assert!(quirks.iter().all(|q| !q.name.is_empty()));
+    assert!(quirks
+        .iter()
+        .any(|q| q.flags_profile("LG ULTRAGEAR 27GR95QE.icm")));
+}
+
+#[test]
+fn builtin_quirks_never_flag_our_own_profiles() {
+    for name in [
+        "lg-ultragear-gamma22-cmx.icm",
+        "lg-ultragear-identity.icm",
+        "sRGB Color Space Profile.icm",
+    ] {
+        assert!(
+            builtin().iter().all(|q| !q.flags_profile(name)),
+            "{} flagged",
+            name
+        );
+    }
+}
+
+// ── Matching ─────────────────────────────────────────────────────
+
+#[test]
+fn wildcard_match_handles_prefix_middle_and_suffix() {
+    assert!(wildcard_match("LG*.icm", "lg 27gr95qe.ICM"));
+    assert!(wildcard_match("*gr95*", "LG 27GR95QE.icm"));
+    assert!(wildcard_match("exact.icm", "Exact.icm"));
+    assert!(!wildcard_match("exact.icm", "exact.icm.bak"));
+    assert!(!wildcard_match("a*b*a", "ab"));
+    assert!(wildcard_match("a*b*a", "aba"));
+    assert!(wildcard_match("a*", "a"));
+}
+
+#[test]
+fn applies_to_matches_model_code_or_name() {
+    let quirk = ModelQuirk {
+        name: "q".into(),
+        models: vec!["GSM5BBF".into(), "27GN950".into()],
+        ..Default::default()
+    };
+    assert!(quirk.applies_to("LG ULTRAGEAR", Some("gsm5bbf")));
+    assert!(quirk.applies_to("LG 27GN950-B", None));
+    assert!(!quirk.applies_to("LG ULTRAGEAR", Some("GSM7714")));
+    assert!(ModelQuirk::default().applies_to("anything", None));
+}
+
+#[test]
+fn find_known_bad_profiles_reports_each_profile_once() {
+    let quirks = parse_quirks(
+        r#"
+[[quirk]]
+name = "a"
+models = ["27GR95QE"]
+known_bad_profiles = ["LG*"]
+note = "first"
+
+[[quirk]]
+name = "b"
+known_bad_profiles = ["lg factory*", "vendor.icm"]
+note = "second"
+"#,
+    )
+    .unwrap();
+    let associated = vec![
+        "LG Factory.icm".to_string(),
+        "vendor.icm".to_string(),
+        "sRGB Color Space Profile.icm".to_string(),
+    ];
+
+    let found = find_known_bad_profiles(&quirks, "LG 27GR95QE", None, &associated);
+    let names: Vec<_> = found
+        .iter()
+        .map(|f| (f.profile.as_str(), f.quirk.as_str()))
+        .collect();
+    assert_eq!(names, [("LG Factory.icm", "a"), ("vendor.icm", "b")]);
+
+    let found = find_known_bad_profiles(&quirks, "LG 32GS95UE", None, &associated);
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].note, "second");
+}
+
+#[test]
+fn parse_quirks_rejects_bad_toml() {
+    assert!(parse_quirks("[[quirk]]\nmodels = 3").is_err());
+    assert!(parse_quirks("").unwrap().is_empty());
+}
+
+#[test]
+fn model_warnings_only_come_from_quirks_naming_the_model() {
+    let quirks = parse_quirks(
+        r#"
+[[quirk]]
+name = "oled-abl"
+models = ["27GR95QE"]
+warning = "ABL dimming is in the panel; a profile can't stop it"
+more_info = "https://example.invalid/oled-abl"
+
+[[quirk]]
+name = "catch-all"
+warning = "never shown"
+"#,
+    )
+    .unwrap();
+    let found = model_warnings(&quirks, "LG 27GR95QE", None);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].quirk, "oled-abl");
+    assert_eq!(found[0].more_info, "https://example.invalid/oled-abl");
+    assert!(model_warnings(&quirks, "LG 27GN950", None).is_empty());
+
+    let err = quirks[1].validate().unwrap_err();
+    assert!(err.contains("needs models"), "{}", err);
+}
+
+// ── Community packs ──────────────────────────────────────────────
+
+const PACK: &str = r#"
+[[quirk]]
+name = "27gn950-local-dimming"
+models = ["27GN950"]
+reapply_delay_ms = 20000
+profile = "lg-ultragear-gamma22-cmx.icm"
+
+[[quirk.step]]
+op = "set"
+code = 0xDC
+value = 5
+"#;
+
+#[test]
+fn load_packs_tags_sources_and_skips_invalid_packs() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("b-27gn950.toml"), PACK).unwrap();
+    std::fs::write(
+        dir.path().join("a-broken.toml"),
+        "[[quirk]]\nname = \"x\"\nreapply_delay_ms = 999999",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("readme.txt"), "not a pack").unwrap();
+
+    let (quirks, errors) = load_packs(dir.path());
+    assert_eq!(quirks.len(), 1);
+    assert_eq!(quirks[0].source, "b-27gn950.toml");
+    assert_eq!(quirks[0].reapply_delay_ms, Some(20000));
+    assert_eq!(
+        quirks[0].ddc_steps,
+        [ScriptStep::Set {
+            code: 0xDC,
+            value: 5
+        }]
+    );
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].path.ends_with("a-broken.toml"));
+    assert!(
+        errors[0].error.contains("reapply_delay_ms 999999"),
+        "{}",
+        errors[0].error
+    );
+
+    let (quirks, errors) = load_packs(&dir.path().join("missing"));
+    assert!(quirks.is_empty() && errors.is_empty());
+}
+
+#[test]
+fn merge_replaces_builtins_by_name_and_appends_new_quirks() {
+    let replacement = ModelQuirk {
+        name: "LG-FACTORY-PROFILE".into(),
+        note: "pack".into(),
+        ..Default::default()
+    };
+    let added = ModelQuirk {
+        name: "new".into(),
+        ..Default::default()
+    };
+    let merged = merge(builtin(), vec![replacement, added]);
+    assert_eq!(merged.len(), builtin().len() + 1);
+    assert_eq!(merged[0].note, "pack");
+    assert_eq!(merged.last().unwrap().name, "new");
+    assert!(builtin().iter().all(|q| q.source == BUILTIN_SOURCE));
+}
+
+#[test]
+fn validate_rejects_unusable_quirks() {
+    let err = |source: &str| parse_quirks(source).unwrap()[0].validate().unwrap_err();
+    assert_eq!(err("[[quirk]]\nmodels = [\"A\"]"), "name is required");
+    assert_eq!(
+        err("[[quirk]]\nname = \"q\"\nmodels = [\" \"]"),
+        "models has an empty entry"
+    );
+    assert!(
+        err("[[quirk]]\nname = \"q\"\n[[quirk.step]]\nop = \"sleep\"\nms = 90000")
+            .starts_with("step 1:")
+    );
+    assert!(parse_quirks("[[quirk]]\nname = \"q\"\nmodel = [\"A\"]").is_err());
+}
@@ -0,0 +1,185 @@
+use super::*;
+use std::fs;
+
+// ── ICC profile ID ───────────────────────────────────────────────
+
+#[test]
+fn icc_profile_id_reads_header_bytes() {
+    let mut header = vec![0u8; 128];
+    for (i, b) in header[84..100].iter_mut().enumerate() {
+        *b = i as u8 + 0xa0;
+    }
+    assert_eq!(
+        icc_profile_id(&header).as_deref(),
+        Some("a0a1a2a3a4a5a6a7a8a9aaabacadaeaf")
+    );
+}
+
+#[test]
+fn icc_profile_id_none_when_zero_or_short() {
+    assert_eq!(icc_profile_id(&[0u8; 128]), None);
+    assert_eq!(icc_profile_id(&[1u8; 90]), None);
+}
+
+// ── Entries ──────────────────────────────────────────────────────
+
+#[test]
+fn profile_entry_for_missing_file() {
+    let entry = ProfileEntry::from_path("sdr", Path::new("/nonexistent/x.icm"));
+    assert_eq!(entry.file_name, "x.icm");
+    assert!(!entry.exists);
+    assert_eq!(entry.size_bytes, None);
+    assert_eq!(entry.icc_profile_id, None);
+}
+
+#[test]
+fn profile_entry_for_existing_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("p.icm");
+    fs::write(&path, [7u8; 132]).unwrap();
+    let entry = ProfileEntry::from_path("active", &path);
+    assert!(entry.exists);
+    assert_eq!(entry.size_bytes, Some(132));
+    assert_eq!(entry.icc_profile_id.as_deref(), Some(&"07".repeat(16)[..]));
+}
+
+#[test]
+fn add_profile_skips_duplicates() {
+    let mut report = InstallReport::new("apply", "cli");
+    report.add_profile("sdr", Path::new("a.icm"));
+    report.add_profile("sdr", Path::new("a.icm"));
+    report.add_profile("hdr", Path::new("a.icm"));
+    assert_eq!(report.profiles.len(), 2);
+}
+
+#[test]
+fn step_result_captures_error_text() {
+    let ok: Result<(), String> = Ok(());
+    let err: Result<(), String> = Err("access denied".to_string());
+    assert_eq!(
+        StepResult::from_result("associate", &ok),
+        StepResult {
+            step: "associate".to_string(),
+            ok: true,
+            error: None,
+        }
+    );
+    assert_eq!(
+        StepResult::from_result("associate", &err).error.as_deref(),
+        Some("access denied")
+    );
+}
+
+// ── Writing ──────────────────────────────────────────────────────
+
+#[test]
+fn new_report_defaults() {
+    let report = InstallReport::new("apply", "cli");
+    assert_eq!(report.schema_version, REPORT_SCHEMA_VERSION);
+    assert_eq!(report.registry_scope, SCOPE_SYSTEM_WIDE);
+    assert!(!report.success);
+    assert!(report.generated_at.ends_with('Z'));
+}
+
+#[test]
+fn report_path_is_per_action() {
+    assert!(report_path("install").ends_with(Path::new("reports").join("last-install.json")));
+}
+
+#[test]
+fn write_report_produces_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nested").join("last-apply.json");
+    let mut report = InstallReport::new("apply", "service");
+    report.devices.push(DeviceEntry {
+        name: "LG ULTRAGEAR".to_string(),
+        device_key: r"DISPLAY\GSM5BBF\5&1234&0&UID4353".to_string(),
+        active_profile: "a.icm".to_string(),
+        sdr_profile: "a.icm".to_string(),
+        hdr_profile: "b.icm".to_string(),
+        steps: vec![],
+    });
+    report.success = true;
+    write_install_report_to(&report, &path).unwrap();
+
+    let raw = fs::read_to_string(&path).unwrap();
+    assert!(raw.contains("\"action\": \"apply\""));
+    assert!(raw.contains(r#""device_key": "DISPLAY\\GSM5BBF\\5&1234&0&UID4353""#));
+    assert!(raw.contains("\"error\": null"));
+}
+
+// ── Reapply history ──────────────────────────────────────────────
+
+fn record(trigger: &str) -> ReapplyRecord {
+    ReapplyRecord {
+        at: "2026-01-01T00:00:00Z".to_string(),
+        trigger: trigger.to_string(),
+        events: vec!["device_arrival".to_string()],
+        duration_ms: 120,
+        success: true,
+        skipped: None,
+        devices: vec![DeviceEntry {
+            name: "LG ULTRAGEAR".to_string(),
+            device_key: r"DISPLAY\GSM5BBF\5&1234&0&UID4353".to_string(),
+            active_profile: "a.icm".to_string(),
+            sdr_profile: "a.icm".to_string(),
+            hdr_profile: "b.icm".to_string(),
+            steps: vec![StepResult::from_result::<(), _>(
+                "mode_associations",
+                &Err("access denied"),
+            )],
+        }],
+        luminance_cd_m2: None,
+    }
+}
+
+#[test]
+fn history_drops_oldest_when_full() {
+    let mut history = ReapplyHistory::new(2);
+    history.push(record("startup"));
+    history.push(record("event"));
+    history.push(record("hotkey"));
+    let triggers: Vec<String> = history
+        .recent(None)
+        .into_iter()
+        .map(|r| r.trigger)
+        .collect();
+    assert_eq!(triggers, ["event", "hotkey"]);
+}
+
+#[test]
+fn history_recent_returns_newest_oldest_first() {
+    let mut history = ReapplyHistory::new(10);
+    for trigger in ["startup", "event", "hotkey"] {
+        history.push(record(trigger));
+    }
+    let triggers: Vec<String> = history
+        .recent(Some(2))
+        .into_iter()
+        .map(|r| r.trigger)
+        .collect();
+    assert_eq!(triggers, ["event", "hotkey"]);
+    assert_eq!(history.recent(Some(10)).len(), 3);
+    assert!(ReapplyHistory::new(0).recent(None).is_empty());
+}
+
+#[test]
+fn history_record_roundtrips_through_json() {
+    let original = record("remote");
+    let json = serde_json::to_string(&original).unwrap();
+    assert!(!json.contains('\n'));
+    let parsed: ReapplyRecord = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, original);
+    assert_eq!(
+        parsed.devices[0].steps[0].error.as_deref(),
+        Some("access denied")
+    );
+}
+
+#[test]
+fn history_record_without_luminance_still_parses() {
+    let mut value = serde_json::to_value(record("event")).unwrap();
+    value.as_object_mut().unwrap().remove("luminance_cd_m2");
+    let parsed: ReapplyRecord = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed.luminance_cd_m2, None);
+}
@@ -0,0 +1,51 @@
+use super::*;
+
+const MB: u64 = 1024 * 1024;
+
+fn at(handles: u32, private_mb: u64) -> ResourceSample {
+    ResourceSample {
+        handles,
+        private_bytes: private_mb * MB,
+    }
+}
+
+// ── Growth detection ─────────────────────────────────────────────
+
+#[test]
+fn first_sample_is_the_baseline() {
+    let mut audit = ResourceAudit::default();
+    assert_eq!(audit.record(at(200, 20)), None);
+    assert_eq!(audit.baseline(), Some(at(200, 20)));
+}
+
+#[test]
+fn fluctuation_below_a_step_is_not_reported() {
+    let mut audit = ResourceAudit::new(10, 4 * MB);
+    audit.record(at(200, 20));
+    for sample in [at(209, 23), at(195, 18), at(205, 22)] {
+        assert_eq!(audit.record(sample), None);
+    }
+}
+
+#[test]
+fn steady_leak_is_reported_once_per_step() {
+    let mut audit = ResourceAudit::new(10, 4 * MB);
+    audit.record(at(200, 20));
+    let reports: Vec<u64> = (1..=25)
+        .filter_map(|i| audit.record(at(200 + i, 20)).map(|g| g.samples))
+        .collect();
+    assert_eq!(reports, vec![11, 21]);
+}
+
+#[test]
+fn memory_growth_is_reported_with_deltas() {
+    let mut audit = ResourceAudit::new(10, 4 * MB);
+    audit.record(at(200, 20));
+    let growth = audit.record(at(203, 26)).expect("growth");
+    assert_eq!(growth.handles_delta(), 3);
+    assert_eq!(growth.private_bytes_delta(), (6 * MB) as i64);
+    assert_eq!(
+        growth.to_string(),
+        "handles 200 -> 203 (+3), private bytes 20.0 MiB -> 26.0 MiB (+6.0 MiB) over 2 samples"
+    );
+}
@@ -0,0 +1,169 @@
+use super::*;
+use crate::config::ScheduleConfig;
+
+fn at(date: NaiveDate, h: u32, m: u32) -> NaiveDateTime {
+    date.and_hms_opt(h, m, 0).unwrap()
+}
+
+fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn minutes(t: NaiveTime) -> i64 {
+    (t - NaiveTime::MIN).num_minutes()
+}
+
+fn scheduled(day: &str, night: &str) -> Config {
+    Config {
+        icc_schedule_day_preset: "gamma22".into(),
+        icc_schedule_night_preset: "reader".into(),
+        icc_schedule_day_start: day.into(),
+        icc_schedule_night_start: night.into(),
+        ..Config::default()
+    }
+}
+
+fn solar(latitude: f64, longitude: f64) -> ScheduleConfig {
+    ScheduleConfig {
+        mode: MODE_SOLAR.into(),
+        latitude,
+        longitude,
+        use_windows_location: false,
+    }
+}
+
+// ── Clock parsing ────────────────────────────────────────────────
+
+#[test]
+fn parse_clock_time_accepts_hh_mm() {
+    assert_eq!(parse_clock_time("06:30"), NaiveTime::from_hms_opt(6, 30, 0));
+    assert_eq!(parse_clock_time(" 7:05 "), NaiveTime::from_hms_opt(7, 5, 0));
+}
+
+#[test]
+fn parse_clock_time_rejects_invalid_values() {
+    assert_eq!(parse_clock_time(""), None);
+    assert_eq!(parse_clock_time("25:00"), None);
+    assert_eq!(parse_clock_time("12:60"), None);
+    assert_eq!(parse_clock_time("noon"), None);
+}
+
+#[test]
+fn is_enabled_requires_both_presets() {
+    assert!(!is_enabled(&Config::default()));
+    assert!(is_enabled(&scheduled("07:00", "19:00")));
+}
+
+// ── Fixed schedule ───────────────────────────────────────────────
+
+#[test]
+fn period_follows_fixed_times() {
+    let cfg = scheduled("07:00", "19:00");
+    let d = ymd(2024, 3, 1);
+    assert_eq!(period_at(&cfg, at(d, 6, 59), 0), DayPeriod::Night);
+    assert_eq!(period_at(&cfg, at(d, 7, 0), 0), DayPeriod::Day);
+    assert_eq!(period_at(&cfg, at(d, 18, 59), 0), DayPeriod::Day);
+    assert_eq!(period_at(&cfg, at(d, 19, 0), 0), DayPeriod::Night);
+}
+
+#[test]
+fn period_handles_wrapped_schedule() {
+    let cfg = scheduled("20:00", "08:00");
+    let d = ymd(2024, 3, 1);
+    assert_eq!(period_at(&cfg, at(d, 23, 0), 0), DayPeriod::Day);
+    assert_eq!(period_at(&cfg, at(d, 3, 0), 0), DayPeriod::Day);
+    assert_eq!(period_at(&cfg, at(d, 12, 0), 0), DayPeriod::Night);
+}
+
+#[test]
+fn malformed_times_fall_back_to_defaults() {
+    let cfg = scheduled("soon", "");
+    let s = schedule_for_date(&cfg, ymd(2024, 3, 1), 0);
+    assert_eq!(s.day_start, parse_clock_time(DEFAULT_DAY_START).unwrap());
+    assert_eq!(
+        s.night_start,
+        parse_clock_time(DEFAULT_NIGHT_START).unwrap()
+    );
+}
+
+#[test]
+fn next_transition_picks_next_boundary() {
+    let cfg = scheduled("07:00", "19:00");
+    let d = ymd(2024, 3, 1);
+    assert_eq!(next_transition_after(&cfg, at(d, 12, 0), 0), at(d, 19, 0));
+    assert_eq!(
+        next_transition_after(&cfg, at(d, 19, 0), 0),
+        at(ymd(2024, 3, 2), 7, 0)
+    );
+    assert_eq!(next_transition_after(&cfg, at(d, 2, 0), 0), at(d, 7, 0));
+}
+
+// ── Sunrise / sunset ─────────────────────────────────────────────
+
+#[test]
+fn sun_times_match_london_midsummer() {
+    // 2024-06-21, BST (UTC+1): sunrise ~04:43, sunset ~21:21.
+    let (rise, set) = sun_times(ymd(2024, 6, 21), 51.5074, -0.1278, 60).unwrap();
+    assert!((minutes(rise) - (4 * 60 + 43)).abs() <= 5, "sunrise {rise}");
+    assert!((minutes(set) - (21 * 60 + 21)).abs() <= 5, "sunset {set}");
+}
+
+#[test]
+fn sun_times_none_during_polar_day() {
+    assert_eq!(sun_times(ymd(2024, 6, 21), 78.22, 15.65, 120), None);
+}
+
+#[test]
+fn sun_schedule_falls_back_to_fixed_times_in_polar_day() {
+    let cfg = Config {
+        schedule: solar(78.22, 15.65),
+        ..scheduled("08:00", "18:00")
+    };
+    let s = schedule_for_date(&cfg, ymd(2024, 6, 21), 120);
+    assert_eq!(s.day_start, parse_clock_time("08:00").unwrap());
+    assert_eq!(s.night_start, parse_clock_time("18:00").unwrap());
+}
+
+#[test]
+fn sun_schedule_uses_sunrise_and_sunset() {
+    let cfg = Config {
+        schedule: solar(51.5074, -0.1278),
+        ..scheduled("07:00", "19:00")
+    };
+    let d = ymd(2024, 6, 21);
+    assert_eq!(period_at(&cfg, at(d, 5, 30), 60), DayPeriod::Day);
+    assert_eq!(period_at(&cfg, at(d, 20, 0), 60), DayPeriod::Day);
+    assert_eq!(period_at(&cfg, at(d, 22, 0), 60), DayPeriod::Night);
+}
+
+#[test]
+fn solar_times_require_solar_mode() {
+    let mut cfg = scheduled("07:00", "19:00");
+    cfg.schedule.latitude = 51.5074;
+    assert_eq!(solar_times(&cfg, ymd(2024, 6, 21), 60), None);
+    cfg.schedule.mode = "Solar".into();
+    assert!(solar_times(&cfg, ymd(2024, 6, 21), 60).is_some());
+}
+
+// ── Location ─────────────────────────────────────────────────────
+
+#[test]
+fn location_rejects_out_of_range_coordinates() {
+    let cfg = Config {
+        schedule: solar(95.0, 10.0),
+        ..Config::default()
+    };
+    assert_eq!(location(&cfg), None);
+}
+
+#[test]
+fn location_prefers_detected_position_when_opted_in() {
+    set_detected_location(40.4168, -3.7038);
+    let mut cfg = Config {
+        schedule: solar(51.5074, -0.1278),
+        ..Config::default()
+    };
+    assert_eq!(location(&cfg), Some((51.5074, -0.1278)));
+    cfg.schedule.use_windows_location = true;
+    assert_eq!(location(&cfg), Some((40.4168, -3.7038)));
+}
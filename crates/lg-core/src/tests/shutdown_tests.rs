@@ -0,0 +1,85 @@
+use super::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+// ── Cancellation ─────────────────────────────────────────────────
+
+#[test]
+fn clones_share_cancellation() {
+    let token = ShutdownToken::new();
+    let clone = token.clone();
+    assert!(!clone.is_cancelled());
+    token.cancel();
+    assert!(clone.is_cancelled());
+}
+
+#[test]
+fn sleep_runs_to_the_end_when_not_cancelled() {
+    let token = ShutdownToken::new();
+    let started = Instant::now();
+    assert!(token.sleep(Duration::from_millis(20)));
+    assert!(started.elapsed() >= Duration::from_millis(20));
+}
+
+#[test]
+fn cancel_cuts_a_long_sleep_short() {
+    let token = ShutdownToken::new();
+    let sleeper = {
+        let token = token.clone();
+        thread::spawn(move || token.sleep(Duration::from_secs(60)))
+    };
+    let started = Instant::now();
+    thread::sleep(Duration::from_millis(20));
+    token.cancel();
+    assert!(!sleeper.join().unwrap());
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn sleep_after_cancel_returns_immediately() {
+    let token = ShutdownToken::new();
+    token.cancel();
+    assert!(!token.sleep(Duration::from_secs(60)));
+    token.wait();
+}
+
+#[test]
+fn check_reports_cancelled_as_an_error() {
+    let token = ShutdownToken::new();
+    assert_eq!(token.check(), Ok(()));
+    token.cancel();
+    let err: Box<dyn Error> = token.check().unwrap_err().into();
+    assert_eq!(err.to_string(), "cancelled");
+}
+
+// ── Hooks ────────────────────────────────────────────────────────
+
+#[test]
+fn hooks_run_once_on_cancel() {
+    let token = ShutdownToken::new();
+    let calls = Arc::new(AtomicUsize::new(0));
+    {
+        let calls = calls.clone();
+        token.on_cancel(move || {
+            calls.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    token.cancel();
+    token.cancel();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn hook_registered_after_cancel_runs_immediately() {
+    let token = ShutdownToken::new();
+    token.cancel();
+    let calls = Arc::new(AtomicUsize::new(0));
+    {
+        let calls = calls.clone();
+        token.on_cancel(move || {
+            calls.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
@@ -188,3 +188,356 @@ fn compute_apply_latency_metrics_parses_window_and_percentile() {
     assert!(metrics.avg_ms > 100.0);
     assert!(metrics.p95_ms >= 120);
 }
+
+// ── Display driver changes ───────────────────────────────────────
+
+fn driver(device_key: &str, version: &str) -> DisplayDriverRecord {
+    DisplayDriverRecord {
+        device_key: device_key.to_string(),
+        adapter_name: "NVIDIA GeForce RTX 4080".to_string(),
+        adapter_instance: r"PCI\VEN_10DE&DEV_2704\4&1&0&0008".to_string(),
+        driver_version: version.to_string(),
+    }
+}
+
+fn on_igpu(mut record: DisplayDriverRecord) -> DisplayDriverRecord {
+    record.adapter_name = "Intel(R) UHD Graphics".to_string();
+    record.adapter_instance = r"PCI\VEN_8086&DEV_A788\3&2&0&0010".to_string();
+    record.driver_version = "31.0.101.5333".to_string();
+    record
+}
+
+#[test]
+fn first_run_reports_no_driver_changes() {
+    let current = [driver(r"DISPLAY\GSM5BBF\5&a&0&UID4352", "32.0.15.6094")];
+    assert!(detect_display_driver_changes(&[], &current).is_empty());
+}
+
+#[test]
+fn driver_version_change_is_an_update() {
+    let previous = [driver(r"DISPLAY\GSM5BBF\5&a&0&UID4352", "32.0.15.6094")];
+    let current = [driver(r"display\gsm5bbf\5&a&0&uid4352", "32.0.15.6603")];
+    assert_eq!(
+        detect_display_driver_changes(&previous, &current),
+        vec![DisplayDriverChange::Updated {
+            device_key: r"display\gsm5bbf\5&a&0&uid4352".to_string(),
+            from: "32.0.15.6094".to_string(),
+            to: "32.0.15.6603".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn unknown_driver_version_is_not_an_update() {
+    let previous = [driver(r"DISPLAY\GSM5BBF\5&a&0&UID4352", "")];
+    let current = [driver(r"DISPLAY\GSM5BBF\5&a&0&UID4352", "32.0.15.6603")];
+    assert!(detect_display_driver_changes(&previous, &current).is_empty());
+}
+
+#[test]
+fn new_instance_of_vanished_model_is_reenumerated() {
+    let previous = [
+        driver(r"DISPLAY\GSM5BBF\5&a&0&UID4352", "32.0.15.6094"),
+        driver(r"DISPLAY\DEL4321\5&b&0&UID4353", "32.0.15.6094"),
+    ];
+    let current = [
+        driver(r"DISPLAY\GSM5BBF\7&c&0&UID4352", "32.0.15.6094"),
+        driver(r"DISPLAY\DEL4321\5&b&0&UID4353", "32.0.15.6094"),
+        driver(r"DISPLAY\ACR0001\7&d&0&UID4354", "32.0.15.6094"),
+    ];
+    assert_eq!(
+        detect_display_driver_changes(&previous, &current),
+        vec![DisplayDriverChange::Reenumerated {
+            device_key: r"DISPLAY\GSM5BBF\7&c&0&UID4352".to_string(),
+            previous_key: r"DISPLAY\GSM5BBF\5&a&0&UID4352".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn monitor_moving_to_other_gpu_is_an_adapter_change() {
+    let previous = [driver(r"DISPLAY\GSM5BBF\5&a&0&UID4352", "32.0.15.6094")];
+    let current = [on_igpu(driver(r"DISPLAY\GSM5BBF\4&e&0&UID8388688", ""))];
+    assert_eq!(
+        detect_display_driver_changes(&previous, &current),
+        vec![DisplayDriverChange::AdapterChanged {
+            device_key: r"DISPLAY\GSM5BBF\4&e&0&UID8388688".to_string(),
+            previous_key: r"DISPLAY\GSM5BBF\5&a&0&UID4352".to_string(),
+            from: "NVIDIA GeForce RTX 4080".to_string(),
+            to: "Intel(R) UHD Graphics".to_string(),
+        }]
+    );
+    let changes = detect_display_driver_changes(&previous, &current);
+    let merged = merge_display_driver_records(&previous, &current, &changes);
+    assert_eq!(merged, current.to_vec());
+}
+
+#[test]
+fn adapter_change_under_same_key_is_not_a_driver_update() {
+    let previous = [driver(r"DISPLAY\GSM5BBF\5&a&0&UID4352", "32.0.15.6094")];
+    let current = [on_igpu(driver(r"DISPLAY\GSM5BBF\5&a&0&UID4352", ""))];
+    let changes = detect_display_driver_changes(&previous, &current);
+    assert_eq!(changes.len(), 1);
+    assert!(matches!(
+        &changes[0],
+        DisplayDriverChange::AdapterChanged { previous_key, .. }
+            if previous_key == r"DISPLAY\GSM5BBF\5&a&0&UID4352"
+    ));
+}
+
+#[test]
+fn records_without_adapter_instance_compare_by_name() {
+    let mut before = driver(r"DISPLAY\GSM5BBF\5&a&0&UID4352", "1");
+    before.adapter_instance.clear();
+    let now = driver(r"DISPLAY\GSM5BBF\5&a&0&UID4352", "1");
+    assert!(detect_display_driver_changes(&[before], &[now]).is_empty());
+}
+
+#[test]
+fn merge_keeps_disconnected_and_drops_replaced_instances() {
+    let previous = [
+        driver(r"DISPLAY\GSM5BBF\5&a&0&UID4352", "1"),
+        driver(r"DISPLAY\DEL4321\5&b&0&UID4353", "1"),
+    ];
+    let current = [driver(r"DISPLAY\GSM5BBF\7&c&0&UID4352", "1")];
+    let changes = detect_display_driver_changes(&previous, &current);
+    let merged = merge_display_driver_records(&previous, &current, &changes);
+    let keys: Vec<&str> = merged.iter().map(|r| r.device_key.as_str()).collect();
+    assert_eq!(
+        keys,
+        vec![
+            r"DISPLAY\GSM5BBF\7&c&0&UID4352",
+            r"DISPLAY\DEL4321\5&b&0&UID4353"
+        ]
+    );
+}
+
+// ── Service heartbeat ────────────────────────────────────────────
+
+fn heartbeat_at(at: &str, interval_minutes: u64) -> ServiceHeartbeat {
+    ServiceHeartbeat {
+        at: at.to_string(),
+        interval_minutes,
+        ..ServiceHeartbeat::default()
+    }
+}
+
+#[test]
+fn heartbeat_is_overdue_after_two_intervals() {
+    let now = chrono::DateTime::parse_from_rfc3339("2026-01-01T00:20:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    assert!(!heartbeat_at("2026-01-01T00:11:00Z", 5).is_overdue(now));
+    assert!(heartbeat_at("2026-01-01T00:09:00Z", 5).is_overdue(now));
+}
+
+#[test]
+fn unparseable_heartbeat_is_overdue() {
+    assert!(heartbeat_at("", 5).is_overdue(Utc::now()));
+}
+
+#[test]
+fn heartbeat_is_healthy_only_when_every_check_passed() {
+    let mut heartbeat = ServiceHeartbeat {
+        window_ok: true,
+        notifications_ok: true,
+        wmi_ok: true,
+        association_ok: true,
+        ..new_service_heartbeat(5)
+    };
+    assert!(heartbeat.healthy());
+    heartbeat.wmi_ok = false;
+    assert!(!heartbeat.healthy());
+}
+
+#[test]
+fn heartbeat_with_a_failed_worker_is_degraded() {
+    let mut heartbeat = ServiceHeartbeat {
+        window_ok: true,
+        notifications_ok: true,
+        wmi_ok: true,
+        association_ok: true,
+        workers: vec![WorkerHealth {
+            name: "debounce-worker".to_string(),
+            state: "restarting".to_string(),
+            restarts: 1,
+            ..WorkerHealth::default()
+        }],
+        ..new_service_heartbeat(5)
+    };
+    assert!(heartbeat.healthy());
+    heartbeat.workers[0].state = "failed".to_string();
+    assert!(!heartbeat.healthy());
+}
+
+#[test]
+fn save_and_load_service_heartbeat_roundtrip() {
+    let _guard = test_lock().lock().expect("lock");
+    let path = heartbeat_path();
+    let _backup = FileBackup::capture(path);
+    let mut heartbeat = new_service_heartbeat(5);
+    heartbeat.window_ok = true;
+    heartbeat.repairs = vec!["wmi_reconnect".to_string()];
+    save_service_heartbeat(&heartbeat).expect("save heartbeat");
+    assert_eq!(load_service_heartbeat(), Some(heartbeat));
+}
+
+// ── Usage summary ────────────────────────────────────────────────
+
+fn event(timestamp: &str, source: &str, event: &str, details: &str) -> DiagnosticEvent {
+    DiagnosticEvent {
+        timestamp: timestamp.to_string(),
+        source: source.to_string(),
+        level: "info".to_string(),
+        event: event.to_string(),
+        details: details.to_string(),
+    }
+}
+
+#[test]
+fn usage_summary_counts_reapplies_failures_and_triggers() {
+    let summary = UsageSummary::from_events(&[
+        event(
+            "2026-10-15T09:00:00Z",
+            "service",
+            "apply_latency",
+            "ms=120 success=1 trigger=wake preset=default source=event",
+        ),
+        event(
+            "2026-10-14T08:00:00Z",
+            "service",
+            "apply_latency",
+            "ms=900 success=0 trigger=device_arrival preset=default source=event",
+        ),
+        event(
+            "2026-10-14T10:00:00Z",
+            "cli",
+            "apply_latency",
+            "ms=80 success=1",
+        ),
+        event(
+            "2026-10-14T11:00:00Z",
+            "service",
+            "apply_skip",
+            "no monitors",
+        ),
+        event("2026-10-16T07:00:00Z", "service", "wake", "resume"),
+    ]);
+    assert_eq!(summary.reapplies, 3);
+    assert_eq!(summary.failures, 1);
+    assert_eq!(summary.skipped, 1);
+    assert_eq!(summary.first_event.as_deref(), Some("2026-10-14T08:00:00Z"));
+    assert_eq!(summary.last_event.as_deref(), Some("2026-10-16T07:00:00Z"));
+    assert_eq!(summary.by_source.get("service"), Some(&2));
+    assert_eq!(summary.by_source.get("cli"), Some(&1));
+    assert_eq!(summary.triggers.get("wake"), Some(&1));
+    assert_eq!(summary.triggers.get("device_arrival"), Some(&1));
+    assert_eq!(summary.events.get("apply_latency"), Some(&3));
+    assert_eq!(summary.events.get("wake"), Some(&1));
+}
+
+#[test]
+fn usage_summary_render_keeps_details_out() {
+    let summary = UsageSummary::from_events(&[event(
+        "2026-10-14T08:00:00Z",
+        "service",
+        "apply_skip",
+        r"\\?\DISPLAY#GSM5BBF#secret",
+    )]);
+    let text = summary.render("9.9.9");
+    assert!(text.contains("Version:    9.9.9"));
+    assert!(text.contains("nothing was sent anywhere"));
+    assert!(text.contains("Reapplies:  0 (0 failed, 1 skipped)"));
+    assert!(!text.contains("GSM5BBF"));
+}
+
+#[test]
+fn empty_usage_summary_renders_placeholders() {
+    let text = UsageSummary::default().render("1.0.0");
+    assert!(text.contains("Period:     - .. -"));
+    assert!(text.contains("  (none)"));
+}
+
+// ── Display modes ────────────────────────────────────────────────
+
+fn mode(device_key: &str, refresh_hz: u32) -> DisplayModeRecord {
+    DisplayModeRecord {
+        device_key: device_key.to_string(),
+        width: 2560,
+        height: 1440,
+        refresh_hz,
+        pinned: false,
+    }
+}
+
+#[test]
+fn display_mode_record_replaces_same_display_case_insensitively() {
+    let mut state = DisplayModeState::default();
+    state.record(mode(r"DISPLAY\GSM5BBF\1", 240));
+    state.record(mode(r"DISPLAY\GSM5BBF\2", 144));
+    state.record(mode(r"display\gsm5bbf\1", 165));
+    assert_eq!(state.displays.len(), 2);
+    assert_eq!(
+        state.expected(r"DISPLAY\GSM5BBF\1").unwrap().refresh_hz,
+        165
+    );
+    assert!(state.expected(r"DISPLAY\GSM5BBF\3").is_none());
+}
+
+#[test]
+fn display_mode_same_mode_ignores_key_and_pin() {
+    let mut pinned = mode("b", 240);
+    pinned.pinned = true;
+    assert!(mode("a", 240).same_mode(&pinned));
+    assert!(!mode("a", 240).same_mode(&mode("a", 60)));
+    assert_eq!(mode("a", 60).label(), "2560x1440 @ 60 Hz");
+}
+
+#[test]
+fn display_mode_state_roundtrips_through_toml() {
+    let mut state = DisplayModeState::default();
+    state.record(mode(r"DISPLAY\GSM5BBF\1", 240));
+    let text = toml::to_string_pretty(&state).unwrap();
+    assert_eq!(toml::from_str::<DisplayModeState>(&text).unwrap(), state);
+}
+
+#[test]
+fn display_mode_action_records_first_sighting_and_keeps_matches() {
+    assert_eq!(
+        display_mode_action(None, &mode("a", 240)),
+        DisplayModeAction::Record
+    );
+    assert_eq!(
+        display_mode_action(Some(&mode("a", 240)), &mode("a", 240)),
+        DisplayModeAction::Keep
+    );
+}
+
+#[test]
+fn display_mode_action_restores_refresh_drops_only_when_unpinned() {
+    assert_eq!(
+        display_mode_action(Some(&mode("a", 240)), &mode("a", 60)),
+        DisplayModeAction::Restore
+    );
+    assert_eq!(
+        display_mode_action(Some(&mode("a", 60)), &mode("a", 240)),
+        DisplayModeAction::Record
+    );
+    let mut smaller = mode("a", 240);
+    smaller.width = 1920;
+    smaller.height = 1080;
+    assert_eq!(
+        display_mode_action(Some(&mode("a", 240)), &smaller),
+        DisplayModeAction::Record
+    );
+}
+
+#[test]
+fn display_mode_action_always_restores_pinned_mode() {
+    let mut pinned = mode("a", 144);
+    pinned.pinned = true;
+    assert_eq!(
+        display_mode_action(Some(&pinned), &mode("a", 240)),
+        DisplayModeAction::Restore
+    );
+}
@@ -0,0 +1,112 @@
+use super::*;
+
+fn metrics(samples: usize, success_count: usize) -> ApplyLatencyMetrics {
+    ApplyLatencyMetrics {
+        samples,
+        success_count,
+        failure_count: samples - success_count,
+        ..ApplyLatencyMetrics::default()
+    }
+}
+
+fn at(stamp: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(stamp)
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+// ── Payload ──────────────────────────────────────────────────────
+
+#[test]
+fn payload_sorts_models_and_rounds_the_success_rate() {
+    let payload = TelemetryPayload::new(
+        "1.2.3",
+        "22631.4317",
+        [
+            "GSM5BBF".to_string(),
+            "GSM5B7F".to_string(),
+            "GSM5BBF".to_string(),
+        ],
+        &metrics(3, 2),
+    );
+    assert_eq!(payload.monitor_models, vec!["GSM5B7F", "GSM5BBF"]);
+    assert_eq!(payload.reapplies, 3);
+    assert_eq!(payload.reapply_success_rate, Some(0.667));
+}
+
+#[test]
+fn payload_without_reapplies_has_no_rate() {
+    let payload = TelemetryPayload::new("1.2.3", "19045.5011", Vec::new(), &metrics(0, 0));
+    assert_eq!(payload.reapply_success_rate, None);
+    let json = payload.to_json();
+    assert!(json.contains("\"reapply_success_rate\": null"));
+    assert!(json.contains("\"windows_build\": \"19045.5011\""));
+}
+
+#[test]
+fn model_code_only_accepts_edid_values() {
+    assert_eq!(model_code("GSM", "5bbf"), Some("GSM5BBF".to_string()));
+    assert_eq!(model_code(" gsm ", "7714"), Some("GSM7714".to_string()));
+    assert_eq!(model_code("LG ULTRAGEAR", "5BBF"), None);
+    assert_eq!(model_code("GSM", "27GP950"), None);
+    assert_eq!(model_code("", ""), None);
+}
+
+// ── Endpoint ─────────────────────────────────────────────────────
+
+#[test]
+fn https_urls_are_split_into_host_port_and_path() {
+    assert_eq!(
+        split_https_url("https://stats.example.invalid/v1/ping"),
+        Some((
+            "stats.example.invalid".to_string(),
+            443,
+            "/v1/ping".to_string()
+        ))
+    );
+    assert_eq!(
+        split_https_url("HTTPS://localhost:8443"),
+        Some(("localhost".to_string(), 8443, "/".to_string()))
+    );
+    assert_eq!(split_https_url("http://example.invalid/ping"), None);
+    assert_eq!(split_https_url("https://user@example.invalid/"), None);
+    assert_eq!(split_https_url("https://example.invalid:x/"), None);
+    assert_eq!(split_https_url(""), None);
+}
+
+// ── Schedule ─────────────────────────────────────────────────────
+
+#[test]
+fn ping_is_due_weekly_after_a_success() {
+    let mut telemetry = TelemetryState::default();
+    assert!(telemetry.ping_due(at("2026-10-01T00:00:00Z")));
+    telemetry.record(at("2026-10-01T00:00:00Z"), Ok(()));
+    assert!(!telemetry.ping_due(at("2026-10-07T23:59:59Z")));
+    assert!(telemetry.ping_due(at("2026-10-08T00:00:00Z")));
+}
+
+#[test]
+fn failed_ping_is_retried_after_a_day() {
+    let mut telemetry = TelemetryState::default();
+    telemetry.record(at("2026-10-01T00:00:00Z"), Err("HTTP 503".to_string()));
+    assert_eq!(telemetry.last_sent, None);
+    assert_eq!(telemetry.last_error, "HTTP 503");
+    assert!(!telemetry.ping_due(at("2026-10-01T12:00:00Z")));
+    assert!(telemetry.ping_due(at("2026-10-02T00:00:00Z")));
+
+    telemetry.record(at("2026-10-02T00:00:00Z"), Ok(()));
+    assert!(telemetry.last_error.is_empty());
+    assert!(!telemetry.ping_due(at("2026-10-03T00:00:00Z")));
+}
+
+#[test]
+fn telemetry_state_roundtrips_through_toml() {
+    let mut telemetry = TelemetryState::default();
+    telemetry.record(at("2026-10-01T00:00:00Z"), Err("timed out".to_string()));
+    let text = toml::to_string_pretty(&telemetry).unwrap();
+    assert_eq!(toml::from_str::<TelemetryState>(&text).unwrap(), telemetry);
+    assert_eq!(
+        toml::from_str::<TelemetryState>("").unwrap(),
+        TelemetryState::default()
+    );
+}
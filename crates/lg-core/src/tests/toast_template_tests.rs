@@ -0,0 +1,114 @@
+use super::*;
+use std::time::Duration;
+
+fn values() -> ToastValues {
+    ToastValues {
+        monitors: vec!["LG ULTRAGEAR".to_string(), "27GP850".to_string()],
+        profiles: vec!["lg-ultragear-full-cal.icm".to_string()],
+        trigger: "unlock".to_string(),
+        duration_ms: 142,
+        time: "21:05".to_string(),
+    }
+}
+
+// ── Rendering ────────────────────────────────────────────────────
+
+#[test]
+fn placeholders_are_filled() {
+    assert_eq!(
+        render("{monitor}: {count} reapplied at {time}", &values()),
+        "LG ULTRAGEAR, 27GP850: 2 reapplied at 21:05"
+    );
+    assert_eq!(
+        render(
+            "{profile} reapplied to {monitors} after {trigger} ({duration_ms} ms)",
+            &values()
+        ),
+        "lg-ultragear-full-cal.icm reapplied to LG ULTRAGEAR, 27GP850 after unlock (142 ms)"
+    );
+}
+
+#[test]
+fn text_without_placeholders_is_unchanged() {
+    for template in ["", "Color profile reapplied ✓", "a < b & c > d", "$(calc)"] {
+        assert_eq!(render(template, &values()), template);
+    }
+}
+
+#[test]
+fn doubled_braces_are_literal() {
+    assert_eq!(render("{{monitor}} }}{{", &values()), "{monitor} }{");
+}
+
+#[test]
+fn unknown_and_unclosed_placeholders_are_kept() {
+    assert_eq!(
+        render("{Monitor} {count {} } {", &values()),
+        "{Monitor} {count {} } {"
+    );
+    assert_eq!(render("{{count}", &values()), "{count}");
+}
+
+#[test]
+fn values_are_not_expanded_again() {
+    let hostile = ToastValues {
+        monitors: vec!["{count}{{time}}".to_string()],
+        ..values()
+    };
+    assert_eq!(render("{monitor}", &hostile), "{count}{{time}}");
+}
+
+#[test]
+fn control_characters_in_values_are_dropped() {
+    let hostile = ToastValues {
+        monitors: vec!["LG\r\nULTRA\u{0}GEAR\u{1b}[2J".to_string()],
+        ..values()
+    };
+    assert_eq!(render("[{monitor}]", &hostile), "[LGULTRAGEAR[2J]");
+}
+
+#[test]
+fn markup_in_values_is_left_for_the_toast_layer() {
+    let hostile = ToastValues {
+        monitors: vec!["</text><image src=\"x\"/> & $(calc)".to_string()],
+        ..values()
+    };
+    assert_eq!(
+        render("{monitor}", &hostile),
+        "</text><image src=\"x\"/> & $(calc)"
+    );
+}
+
+// ── Tally ────────────────────────────────────────────────────────
+
+#[test]
+fn tally_collects_monitors_and_distinct_profiles() {
+    let started = Instant::now() - Duration::from_millis(50);
+    let mut tally = ToastTally::new("hotkey", started);
+    tally.record("LG ULTRAGEAR", Path::new("C:/color/lg-a.icm"));
+    tally.record("27GP850", Path::new("C:/color/lg-b.icm"));
+    tally.record("27GP850 #2", Path::new("C:/color/lg-a.icm"));
+    let values = tally.values();
+    assert_eq!(values.monitors.len(), 3);
+    assert_eq!(render("{profile}", &values), "lg-a.icm, lg-b.icm");
+    assert_eq!(values.trigger, "hotkey");
+    assert!(values.duration_ms >= 50);
+    assert_eq!(values.time.len(), 5);
+}
+
+#[test]
+fn empty_tally_renders_empty_values() {
+    let values = ToastTally::new("", Instant::now()).values();
+    assert_eq!(render("[{monitor}|{count}|{profile}]", &values), "[|0|]");
+}
+
+// ── Validation ───────────────────────────────────────────────────
+
+#[test]
+fn unknown_placeholders_are_listed_in_order() {
+    assert_eq!(
+        unknown_placeholders("{monitor} {Count} {{name}} {serial} {time"),
+        vec!["Count", "serial"]
+    );
+    assert!(unknown_placeholders("{monitor} at {time} ({count})").is_empty());
+}
@@ -0,0 +1,324 @@
+use super::*;
+use std::fs;
+
+fn errors(raw: &str) -> Vec<Issue> {
+    validate_str(raw)
+        .into_iter()
+        .filter(|i| i.severity == Severity::Error)
+        .collect()
+}
+
+// ── Clean configs ────────────────────────────────────────────────
+
+#[test]
+fn default_template_is_clean() {
+    let raw = Config::to_toml_commented(&Config::default());
+    assert_eq!(validate_str(&raw), Vec::new());
+}
+
+#[test]
+fn empty_file_is_clean() {
+    assert_eq!(validate_str(""), Vec::new());
+}
+
+// ── Syntax and types ─────────────────────────────────────────────
+
+#[test]
+fn syntax_error_reports_line() {
+    let issues = validate_str("verbose = true\nmonitor_match = \"LG\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].severity, Severity::Error);
+    assert_eq!(issues[0].line, Some(2));
+}
+
+#[test]
+fn wrong_type_reports_line() {
+    let issues = errors("verbose = true\n\nstabilize_delay_ms = \"soon\"\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(3));
+}
+
+// ── Unknown keys ─────────────────────────────────────────────────
+
+#[test]
+fn unknown_key_is_reported_with_suggestion() {
+    let issues = errors("verbose = true\nstabilise_delay_ms = 100\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(2));
+    assert!(issues[0].message.contains("`stabilise_delay_ms`"));
+    assert!(issues[0]
+        .message
+        .contains("did you mean `stabilize_delay_ms`"));
+}
+
+#[test]
+fn unknown_key_without_close_match_has_no_suggestion() {
+    let issues = errors("completely_unrelated_option = 1\n");
+    assert_eq!(issues.len(), 1);
+    assert!(!issues[0].message.contains("did you mean"));
+}
+
+#[test]
+fn unknown_schedule_key_is_reported() {
+    let issues = errors("[schedule]\nmode = \"solar\"\nlatitud = 40.0\nlongitude = -3.7\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(3));
+    assert!(issues[0].message.contains("`schedule.latitud`"));
+    assert!(issues[0].message.contains("`latitude`"));
+}
+
+#[test]
+fn app_override_names_are_free_form() {
+    assert_eq!(
+        validate_str("[app_overrides]\n\"anything.exe\" = \"none\"\n"),
+        Vec::new()
+    );
+}
+
+// ── Values ───────────────────────────────────────────────────────
+
+#[test]
+fn out_of_range_delay_is_reported() {
+    let issues = errors("toggle_delay_ms = 60000\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(1));
+    assert!(issues[0].message.contains("0..=10000"));
+}
+
+#[test]
+fn guard_interval_has_a_floor() {
+    assert_eq!(errors("brightness_guard_interval_ms = 10\n").len(), 1);
+}
+
+#[test]
+fn gamma_watch_interval_has_a_floor() {
+    assert_eq!(errors("gamma_watch_interval_ms = 100\n").len(), 1);
+    assert!(errors("gamma_watch_interval_ms = 1000\n").is_empty());
+}
+
+#[test]
+fn watchdog_interval_is_bounded() {
+    assert_eq!(errors("watchdog_interval_minutes = 5000\n").len(), 1);
+    assert!(errors("watchdog_interval_minutes = 0\n").is_empty());
+    assert_eq!(errors("resource_audit_interval_minutes = 2000\n").len(), 1);
+}
+
+#[test]
+fn empty_pattern_is_an_error() {
+    let issues = errors("monitor_match = \"  \"\n");
+    assert_eq!(issues.len(), 1);
+    assert!(issues[0].message.contains("monitor_match is empty"));
+}
+
+#[test]
+fn invalid_regex_is_an_error_only_in_regex_mode() {
+    assert!(errors("monitor_match = \"LG (\"\n").is_empty());
+    let issues = errors("monitor_match = \"LG (\"\nmonitor_match_regex = true\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(1));
+    assert!(issues[0].message.contains("not a valid regex"));
+}
+
+#[test]
+fn registered_pattern_check_reports_bad_expressions() {
+    set_pattern_check(|pattern| {
+        if pattern.contains("~~") {
+            Err("invalid match expression: doubled operator at column 5".to_string())
+        } else {
+            Ok(())
+        }
+    });
+    assert!(errors("monitor_match = \"name~GEAR\"\n").is_empty());
+    let issues = errors("verbose = true\nmonitor_match = \"name~~GEAR\"\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(2));
+    assert!(issues[0].message.contains("doubled operator at column 5"));
+}
+
+#[test]
+fn profile_name_must_be_a_file_name() {
+    let issues = errors("profile_name = 'C:\\temp\\x.icm'\n");
+    assert_eq!(issues.len(), 1);
+    assert!(issues[0].message.contains("not a path"));
+    assert_eq!(errors("profile_name = \"\"\n").len(), 1);
+}
+
+#[test]
+fn profile_name_without_extension_warns() {
+    let issues = validate_str("profile_name = \"my-profile\"\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].severity, Severity::Warning);
+}
+
+#[test]
+fn app_override_paths_are_rejected() {
+    let issues = errors("\n[app_overrides]\n\"game.exe\" = \"../x.icm\"\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(3));
+}
+
+#[test]
+fn bad_clock_time_and_mode_are_errors() {
+    let issues = errors("icc_schedule_day_start = \"25:00\"\n[schedule]\nmode = \"lunar\"\n");
+    assert_eq!(issues.len(), 2);
+    assert_eq!(issues[0].line, Some(1));
+    assert_eq!(issues[1].line, Some(3));
+}
+
+#[test]
+fn unknown_toast_quiet_hours_is_an_error() {
+    assert!(errors("toast_quiet_hours = \"Queue\"\n").is_empty());
+    let issues = errors("toast_quiet_hours = \"mute\"\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(1));
+}
+
+#[test]
+fn unknown_language_is_a_warning() {
+    assert!(validate_str("language = \"pt-BR\"\n").is_empty());
+    let issues = validate_str("language = \"klingon\"\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].severity, Severity::Warning);
+    assert_eq!(issues[0].line, Some(1));
+}
+
+#[test]
+fn unknown_toast_placeholders_are_warnings() {
+    assert!(validate_str("toast_body = \"{monitor} at {time} ({count}) {{x}}\"\n").is_empty());
+    let issues = validate_str("toast_title = \"LG\"\ntoast_body = \"{Monitor} {serial}\"\n");
+    assert_eq!(issues.len(), 2);
+    assert!(issues
+        .iter()
+        .all(|i| i.severity == Severity::Warning && i.line == Some(2)));
+    assert!(issues[0].message.contains("{Monitor}"), "{}", issues[0]);
+}
+
+#[test]
+fn unknown_osd_position_is_an_error() {
+    assert!(errors("osd_position = \"Bottom-Left\"\n").is_empty());
+    let issues = errors("osd_position = \"center\"\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(1));
+    let warnings = validate_str("osd_duration_ms = 50\n");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].severity, Severity::Warning);
+}
+
+#[test]
+fn measure_timeout_is_only_checked_with_a_command() {
+    assert!(validate_str("measure_timeout_ms = 10\n").is_empty());
+    let warnings = validate_str("measure_command = \"spotread -x -O\"\nmeasure_timeout_ms = 10\n");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].severity, Severity::Warning);
+    assert_eq!(warnings[0].line, Some(2));
+}
+
+#[test]
+fn telemetry_endpoint_must_be_https() {
+    assert!(validate_str("telemetry_endpoint = \"https://example.invalid/ping\"\n").is_empty());
+    let issues = errors("telemetry_endpoint = \"http://example.invalid/ping\"\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(1));
+    let warnings = validate_str("telemetry_enabled = true\n");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].severity, Severity::Warning);
+}
+
+#[test]
+fn sdr_white_level_is_zero_or_in_slider_range() {
+    assert!(validate_str("sdr_white_level_nits = 0\n").is_empty());
+    assert!(validate_str("sdr_white_level_nits = 240\n").is_empty());
+    assert_eq!(errors("sdr_white_level_nits = 40\n").len(), 1);
+    assert_eq!(errors("sdr_white_level_nits = 1000\n").len(), 1);
+}
+
+#[test]
+fn unknown_tamper_watch_is_an_error() {
+    assert!(errors("tamper_watch = \"Reassert\"\n").is_empty());
+    let issues = errors("\ntamper_watch = \"fix\"\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(2));
+    assert_eq!(errors("gamma_watch = \"restore\"\n").len(), 1);
+}
+
+// ── Conflicts ────────────────────────────────────────────────────
+
+#[test]
+fn half_configured_schedule_warns() {
+    let issues = validate_str("icc_schedule_day_preset = \"gamma22\"\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].severity, Severity::Warning);
+    assert!(issues[0].message.contains("icc_schedule_night_preset"));
+}
+
+#[test]
+fn identical_schedule_times_are_an_error() {
+    let raw = "icc_schedule_day_preset = \"gamma22\"\n\
+               icc_schedule_night_preset = \"reader\"\n\
+               icc_schedule_day_start = \"08:00\"\n\
+               icc_schedule_night_start = \"8:00\"\n";
+    let issues = errors(raw);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(4));
+}
+
+#[test]
+fn solar_mode_without_location_warns() {
+    let issues = validate_str("[schedule]\nmode = \"solar\"\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].severity, Severity::Warning);
+    assert_eq!(issues[0].line, Some(2));
+}
+
+#[test]
+fn duplicate_hotkeys_are_an_error() {
+    let raw = "hotkeys_enabled = true\n\
+               hotkey_reapply = \"Ctrl+Alt+F9\"\n\
+               hotkey_brightness_up = \"alt + ctrl + f9\"\n";
+    let issues = errors(raw);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(3));
+    assert!(issues[0].message.contains("hotkey_reapply"));
+}
+
+#[test]
+fn duplicate_hotkeys_are_fine_when_hotkeys_are_off() {
+    let raw = "hotkeys_enabled = false\n\
+               hotkey_reapply = \"Ctrl+Alt+F9\"\n\
+               hotkey_brightness_up = \"Ctrl+Alt+F9\"\n";
+    assert!(errors(raw).is_empty());
+}
+
+#[test]
+fn issues_are_sorted_by_line() {
+    let raw = "toggle_delay_ms = 99999\nverbos = true\nmonitor_match = \"\"\n";
+    let lines: Vec<_> = validate_str(raw).iter().map(|i| i.line).collect();
+    assert_eq!(lines, vec![Some(1), Some(2), Some(3)]);
+}
+
+// ── Display and files ────────────────────────────────────────────
+
+#[test]
+fn issue_display_includes_line_and_severity() {
+    let issue = Issue {
+        severity: Severity::Warning,
+        line: Some(7),
+        message: "something".to_string(),
+    };
+    assert_eq!(issue.to_string(), "line 7: warning: something");
+    let issue = Issue {
+        line: None,
+        ..issue
+    };
+    assert_eq!(issue.to_string(), "warning: something");
+}
+
+#[test]
+fn validate_file_reads_from_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, "verbos = true\n").unwrap();
+    let issues = validate_file(&path).unwrap();
+    assert!(has_errors(&issues));
+    assert!(validate_file(&dir.path().join("missing.toml")).is_err());
+}
@@ -0,0 +1,39 @@
+use super::*;
+use std::path::Path;
+
+// ── Encoding ─────────────────────────────────────────────────────
+
+#[test]
+fn wide_string_is_nul_terminated_utf16() {
+    let wide = WideString::new("LG ✓");
+    assert_eq!(wide.as_slice(), &[0x4C, 0x47, 0x20, 0x2713]);
+    assert_eq!(wide.as_slice_with_nul().last(), Some(&0));
+    assert_eq!(wide.len(), 4);
+    assert_eq!(wide.to_string(), "LG ✓");
+}
+
+#[test]
+fn empty_wide_string_is_just_the_terminator() {
+    let wide = WideString::new("");
+    assert!(wide.is_empty());
+    assert_eq!(wide.as_slice_with_nul(), &[0]);
+    assert_eq!(WideString::default(), wide);
+}
+
+#[test]
+fn paths_and_strings_convert_alike() {
+    let path = Path::new("C:/color/lg.icm");
+    assert_eq!(WideString::from(path), WideString::new("C:/color/lg.icm"));
+    assert_eq!(
+        WideString::from(&"C:/color/lg.icm".to_string()),
+        WideString::from("C:/color/lg.icm")
+    );
+}
+
+#[test]
+fn pointer_points_at_the_owned_buffer() {
+    let wide = WideString::new("DISPLAY");
+    let ptr = wide.as_ptr();
+    let read = unsafe { std::slice::from_raw_parts(ptr, wide.len() + 1) };
+    assert_eq!(read, wide.as_slice_with_nul());
+}
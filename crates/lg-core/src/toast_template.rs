@@ -0,0 +1,189 @@
+//! Placeholders in `toast_title` and `toast_body`.
+//!
+//! | Placeholder | Value |
+//! |---|---|
+//! | `{monitor}`, `{monitors}` | names of the reapplied monitors, joined with `, ` |
+//! | `{count}` | how many monitors were reapplied |
+//! | `{profile}` | file name of the profile made current (`, `-joined if they differ) |
+//! | `{trigger}` | what caused the reapply, e.g. `unlock` or `hotkey` |
+//! | `{duration_ms}` | milliseconds from the start of the reapply to the toast |
+//! | `{time}` | local time as `HH:MM` |
+//!
+//! `{{` and `}}` are literal braces, and anything else in braces is kept as
+//! written (`config validate` warns about it). The reapply steps collect
+//! the values in a [`ToastTally`] as monitors finish.
+//!
+//! Values go in as plain text in a single pass: a monitor name containing
+//! `{count}` stays `{count}`, and control characters in values are dropped.
+//! Escaping for the toast XML and the helper's command line happens later,
+//! in `lg-notify`, on the finished text.
+
+use chrono::Local;
+use std::path::Path;
+use std::time::Instant;
+
+/// Placeholder names [`render`] fills in.
+pub const PLACEHOLDERS: [&str; 7] = [
+    "monitor",
+    "monitors",
+    "count",
+    "profile",
+    "trigger",
+    "duration_ms",
+    "time",
+];
+
+/// What the placeholders stand for in one toast.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToastValues {
+    pub monitors: Vec<String>,
+    pub profiles: Vec<String>,
+    pub trigger: String,
+    pub duration_ms: u64,
+    pub time: String,
+}
+
+impl ToastValues {
+    /// Stand-in values for `test toast`, so a template shows filled in.
+    pub fn sample() -> Self {
+        Self {
+            monitors: vec!["LG ULTRAGEAR".to_string()],
+            profiles: vec!["lg-ultragear-full-cal.icm".to_string()],
+            trigger: "test".to_string(),
+            duration_ms: 0,
+            time: local_time(),
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        match name {
+            "monitor" | "monitors" => Some(self.monitors.join(", ")),
+            "count" => Some(self.monitors.len().to_string()),
+            "profile" => Some(self.profiles.join(", ")),
+            "trigger" => Some(self.trigger.clone()),
+            "duration_ms" => Some(self.duration_ms.to_string()),
+            "time" => Some(self.time.clone()),
+            _ => None,
+        }
+    }
+}
+
+fn local_time() -> String {
+    Local::now().format("%H:%M").to_string()
+}
+
+/// Placeholder values gathered during one reapply.
+#[derive(Debug, Clone)]
+pub struct ToastTally {
+    started: Instant,
+    trigger: String,
+    monitors: Vec<String>,
+    profiles: Vec<String>,
+}
+
+impl ToastTally {
+    /// Tally for a reapply caused by `trigger` that began at `started`.
+    pub fn new(trigger: &str, started: Instant) -> Self {
+        Self {
+            started,
+            trigger: trigger.to_string(),
+            monitors: Vec::new(),
+            profiles: Vec::new(),
+        }
+    }
+
+    /// `monitor` was reapplied with `active` as its current profile.
+    pub fn record(&mut self, monitor: &str, active: &Path) {
+        self.monitors.push(monitor.to_string());
+        let profile = active
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if !self.profiles.contains(&profile) {
+            self.profiles.push(profile);
+        }
+    }
+
+    /// Values as of now.
+    pub fn values(&self) -> ToastValues {
+        ToastValues {
+            monitors: self.monitors.clone(),
+            profiles: self.profiles.clone(),
+            trigger: self.trigger.clone(),
+            duration_ms: self.started.elapsed().as_millis() as u64,
+            time: local_time(),
+        }
+    }
+}
+
+/// A piece of a template.
+#[derive(Debug, PartialEq, Eq)]
+enum Part<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Split `template` into text and `{name}` placeholders. `{{`/`}}` become
+/// single braces; a `{` without a closing `}` is text.
+fn parts(template: &str) -> Vec<Part<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(at) = rest.find(['{', '}']) {
+        parts.push(Part::Text(&rest[..at]));
+        let tail = &rest[at..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            parts.push(Part::Text(&tail[..1]));
+            rest = &tail[2..];
+        } else if let Some(name) = tail.strip_prefix('{').and_then(placeholder_name) {
+            parts.push(Part::Placeholder(name));
+            rest = &tail[name.len() + 2..];
+        } else {
+            parts.push(Part::Text(&tail[..1]));
+            rest = &tail[1..];
+        }
+    }
+    parts.push(Part::Text(rest));
+    parts
+}
+
+/// The name of a placeholder whose `{` came just before `s`: everything up
+/// to a `}`, provided no other brace comes first.
+fn placeholder_name(s: &str) -> Option<&str> {
+    s.find(['{', '}'])
+        .filter(|&end| end > 0 && s[end..].starts_with('}'))
+        .map(|end| &s[..end])
+}
+
+/// Fill the placeholders in `template`.
+pub fn render(template: &str, values: &ToastValues) -> String {
+    let mut out = String::with_capacity(template.len());
+    for part in parts(template) {
+        match part {
+            Part::Text(text) => out.push_str(text),
+            Part::Placeholder(name) => match values.get(name) {
+                Some(value) => out.extend(value.chars().filter(|c| !c.is_control())),
+                None => {
+                    out.push('{');
+                    out.push_str(name);
+                    out.push('}');
+                }
+            },
+        }
+    }
+    out
+}
+
+/// Placeholders in `template` that [`render`] leaves alone, in order.
+pub fn unknown_placeholders(template: &str) -> Vec<&str> {
+    parts(template)
+        .into_iter()
+        .filter_map(|part| match part {
+            Part::Placeholder(name) if !PLACEHOLDERS.contains(&name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "tests/toast_template_tests.rs"]
+mod tests;
@@ -0,0 +1,678 @@
+//! Config validation for `config validate` and service start.
+//!
+//! `Config::load` is deliberately forgiving: unknown keys are ignored and
+//! out-of-range values are clamped or replaced further down the line. That
+//! keeps the service running, but hides typos. The checks here report those
+//! problems against the line of the TOML file they came from.
+
+use crate::config::Config;
+use crate::i18n;
+use crate::schedule::{self, MODE_FIXED, MODE_SOLAR};
+use crate::toast_template;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::OnceLock;
+use toml::Spanned;
+
+/// Parse check for `monitor_match`, returning the error of a malformed
+/// match expression. The parser lives in `lg-monitor`, which registers it
+/// with [`set_pattern_check`], since this crate stays OS-agnostic.
+pub type PatternCheck = fn(&str) -> Result<(), String>;
+
+static PATTERN_CHECK: OnceLock<PatternCheck> = OnceLock::new();
+
+/// Register the `monitor_match` expression check. Without one, only the
+/// regex syntax is checked.
+pub fn set_pattern_check(check: PatternCheck) {
+    let _ = PATTERN_CHECK.set(check);
+}
+
+/// How serious a validation finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The setting is ignored or cannot work as written.
+    Error,
+    /// The setting works, but probably not as intended.
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        })
+    }
+}
+
+/// One validation finding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub severity: Severity,
+    /// 1-based line in the config file, when the finding maps to one.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}: {}", line, self.severity, self.message),
+            None => write!(f, "{}: {}", self.severity, self.message),
+        }
+    }
+}
+
+/// True when any finding is an error.
+pub fn has_errors(issues: &[Issue]) -> bool {
+    issues.iter().any(|i| i.severity == Severity::Error)
+}
+
+/// Delay settings and their accepted range in milliseconds.
+const DELAY_RANGES: &[(&str, u64, u64)] = &[
+    ("stabilize_delay_ms", 0, 120_000),
+    ("toggle_delay_ms", 0, 10_000),
+    ("reapply_delay_ms", 0, 300_000),
+    ("brightness_guard_interval_ms", 250, 600_000),
+    ("defer_reapply_idle_ms", 1_000, 3_600_000),
+    ("defer_reapply_max_ms", 0, 3_600_000),
+    ("gamma_watch_interval_ms", 1_000, 600_000),
+];
+
+/// Accepted range of a delay setting, for values set outside the config
+/// (quirk recommendations).
+pub(crate) fn delay_range(key: &str) -> Option<(u64, u64)> {
+    DELAY_RANGES
+        .iter()
+        .find(|(k, _, _)| *k == key)
+        .map(|&(_, min, max)| (min, max))
+}
+
+type SpannedTable = BTreeMap<Spanned<String>, toml::Value>;
+
+/// Key positions inside the sections that have known keys or file names.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Sections {
+    schedule: SpannedTable,
+    app_overrides: SpannedTable,
+}
+
+/// Byte offsets of every key in the file, keyed by dotted path.
+struct KeyLines<'a> {
+    raw: &'a str,
+    offsets: BTreeMap<String, usize>,
+}
+
+impl KeyLines<'_> {
+    fn line(&self, key: &str) -> Option<usize> {
+        self.offsets.get(key).map(|&at| line_of(self.raw, at))
+    }
+}
+
+fn line_of(raw: &str, offset: usize) -> usize {
+    raw[..offset.min(raw.len())].matches('\n').count() + 1
+}
+
+/// Validate a config file on disk. Adds checks that depend on the machine
+/// (the color store directory) to [`validate_str`].
+pub fn validate_file(path: &Path) -> std::io::Result<Vec<Issue>> {
+    let raw = std::fs::read_to_string(path)?;
+    let mut issues = validate_str(&raw);
+    if !has_errors(&issues) {
+        if let Ok(cfg) = toml::from_str::<Config>(&raw) {
+            let profile = cfg.profile_path();
+            if let Some(dir) = profile.parent().filter(|dir| !dir.is_dir()) {
+                issues.push(Issue {
+                    severity: Severity::Warning,
+                    line: None,
+                    message: format!(
+                        "color store {} does not exist — the profile cannot be installed there",
+                        dir.display()
+                    ),
+                });
+            }
+        }
+    }
+    Ok(issues)
+}
+
+/// Validate config TOML text. Returns findings in file order; an empty list
+/// means the config is clean.
+pub fn validate_str(raw: &str) -> Vec<Issue> {
+    let top = match toml::from_str::<SpannedTable>(raw) {
+        Ok(top) => top,
+        Err(e) => return vec![parse_issue(raw, &e)],
+    };
+    let sections = toml::from_str::<Sections>(raw).unwrap_or_default();
+
+    let mut offsets = BTreeMap::new();
+    for key in top.keys() {
+        offsets.insert(key.get_ref().clone(), key.span().start);
+    }
+    for (section, table) in [
+        ("schedule", &sections.schedule),
+        ("app_overrides", &sections.app_overrides),
+    ] {
+        for key in table.keys() {
+            offsets.insert(format!("{}.{}", section, key.get_ref()), key.span().start);
+        }
+    }
+    let lines = KeyLines { raw, offsets };
+
+    let mut issues = unknown_keys(&top, &sections, &lines);
+    match toml::from_str::<Config>(raw) {
+        Ok(cfg) => issues.extend(check_values(&cfg, &lines)),
+        Err(e) => issues.push(parse_issue(raw, &e)),
+    }
+    issues.sort_by_key(|i| i.line.unwrap_or(usize::MAX));
+    issues
+}
+
+fn parse_issue(raw: &str, error: &toml::de::Error) -> Issue {
+    Issue {
+        severity: Severity::Error,
+        line: error.span().map(|span| line_of(raw, span.start)),
+        message: error.message().trim().to_string(),
+    }
+}
+
+fn unknown_keys(top: &SpannedTable, sections: &Sections, lines: &KeyLines) -> Vec<Issue> {
+    let known = match toml::Value::try_from(Config::default()) {
+        Ok(toml::Value::Table(table)) => table,
+        _ => return Vec::new(),
+    };
+    let known_schedule: Vec<&str> = match known.get("schedule") {
+        Some(toml::Value::Table(table)) => table.keys().map(String::as_str).collect(),
+        _ => Vec::new(),
+    };
+    let known_top: Vec<&str> = known.keys().map(String::as_str).collect();
+
+    let mut issues = Vec::new();
+    let mut check = |key: &str, path: String, candidates: &[&str]| {
+        if candidates.contains(&key) {
+            return;
+        }
+        let mut message = format!("unknown key `{}` is ignored", path);
+        if let Some(close) = closest(key, candidates) {
+            message.push_str(&format!(" — did you mean `{}`?", close));
+        }
+        issues.push(Issue {
+            severity: Severity::Error,
+            line: lines.line(&path),
+            message,
+        });
+    };
+    for key in top.keys() {
+        let key = key.get_ref();
+        check(key, key.clone(), &known_top);
+    }
+    for key in sections.schedule.keys() {
+        let key = key.get_ref();
+        check(key, format!("schedule.{}", key), &known_schedule);
+    }
+    issues
+}
+
+/// Closest candidate within a small edit distance, for typo suggestions.
+fn closest<'a>(key: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|&c| (edit_distance(key, c), c))
+        .filter(|&(d, _)| d <= 3)
+        .min_by_key(|&(d, _)| d)
+        .map(|(_, c)| c)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = prev + usize::from(ca != cb);
+            prev = row[j + 1];
+            row[j + 1] = substitute.min(prev + 1).min(row[j] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn check_values(cfg: &Config, lines: &KeyLines) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut push = |severity, key: &str, message: String| {
+        issues.push(Issue {
+            severity,
+            line: lines.line(key),
+            message,
+        });
+    };
+
+    // ── Monitor pattern ──────────────────────────────────────────
+    if cfg.monitor_match.trim().is_empty() {
+        push(
+            Severity::Error,
+            "monitor_match",
+            "monitor_match is empty — set it to part of the monitor name, e.g. \"LG ULTRAGEAR\""
+                .to_string(),
+        );
+    } else if let Some(Err(e)) = PATTERN_CHECK.get().map(|check| check(&cfg.monitor_match)) {
+        push(
+            Severity::Error,
+            "monitor_match",
+            format!("monitor_match: {}", e),
+        );
+    } else if cfg.monitor_match_regex {
+        if let Err(e) = regex::Regex::new(&cfg.monitor_match) {
+            let detail = e.to_string();
+            let detail = detail.lines().last().unwrap_or_default().trim();
+            push(
+                Severity::Error,
+                "monitor_match",
+                format!(
+                    "monitor_match is not a valid regex ({}) — fix it or set monitor_match_regex = false",
+                    detail
+                ),
+            );
+        }
+    }
+
+    // ── Profile paths ────────────────────────────────────────────
+    let profile = cfg.profile_name.trim();
+    if profile.is_empty() {
+        push(
+            Severity::Error,
+            "profile_name",
+            "profile_name is empty — set it to an .icm file name".to_string(),
+        );
+    } else if !is_plain_file_name(profile) {
+        push(
+            Severity::Error,
+            "profile_name",
+            format!(
+                "profile_name \"{}\" must be a file name in the color store, not a path",
+                profile
+            ),
+        );
+    } else if !has_profile_extension(profile) {
+        push(
+            Severity::Warning,
+            "profile_name",
+            format!(
+                "profile_name \"{}\" has no .icm/.icc extension — Windows may not list it",
+                profile
+            ),
+        );
+    }
+    for (app, target) in &cfg.app_overrides {
+        let target = target.trim();
+        if !target.eq_ignore_ascii_case("none") && !is_plain_file_name(target) {
+            push(
+                Severity::Error,
+                &format!("app_overrides.{}", app),
+                format!(
+                    "app_overrides.\"{}\" = \"{}\" must be a profile file name or \"none\"",
+                    app, target
+                ),
+            );
+        }
+    }
+
+    // ── Delays and ranges ────────────────────────────────────────
+    for &(key, min, max) in DELAY_RANGES {
+        let value = delay_value(cfg, key);
+        if value < min || value > max {
+            push(
+                Severity::Error,
+                key,
+                format!(
+                    "{} = {} is out of range — use {}..={} ms",
+                    key, value, min, max
+                ),
+            );
+        }
+    }
+    if cfg.ddc_brightness_value > 100 {
+        push(
+            Severity::Error,
+            "ddc_brightness_value",
+            format!(
+                "ddc_brightness_value = {} is out of range — use 0..=100",
+                cfg.ddc_brightness_value
+            ),
+        );
+    }
+    if cfg.sdr_white_level_nits != 0 && !(80..=480).contains(&cfg.sdr_white_level_nits) {
+        push(
+            Severity::Error,
+            "sdr_white_level_nits",
+            format!(
+                "sdr_white_level_nits = {} is out of range — use 80..=480, or 0 to leave it alone",
+                cfg.sdr_white_level_nits
+            ),
+        );
+    }
+    if !(1..=100).contains(&cfg.hotkey_brightness_step) {
+        push(
+            Severity::Error,
+            "hotkey_brightness_step",
+            format!(
+                "hotkey_brightness_step = {} is out of range — use 1..=100",
+                cfg.hotkey_brightness_step
+            ),
+        );
+    }
+    if cfg.watchdog_interval_minutes > 1440 {
+        push(
+            Severity::Error,
+            "watchdog_interval_minutes",
+            format!(
+                "watchdog_interval_minutes = {} is out of range — use 0..=1440 (0 = off)",
+                cfg.watchdog_interval_minutes
+            ),
+        );
+    }
+    if cfg.resource_audit_interval_minutes > 1440 {
+        push(
+            Severity::Error,
+            "resource_audit_interval_minutes",
+            format!(
+                "resource_audit_interval_minutes = {} is out of range — use 0..=1440 (0 = off)",
+                cfg.resource_audit_interval_minutes
+            ),
+        );
+    }
+
+    // ── Toasts ───────────────────────────────────────────────────
+    if !["show", "suppress", "queue"]
+        .contains(&cfg.toast_quiet_hours.trim().to_ascii_lowercase().as_str())
+    {
+        push(
+            Severity::Error,
+            "toast_quiet_hours",
+            format!(
+                "toast_quiet_hours = \"{}\" is not recognised — use \"show\", \"suppress\" or \"queue\"",
+                cfg.toast_quiet_hours
+            ),
+        );
+    }
+    if !cfg.language.trim().is_empty() && i18n::Language::parse(&cfg.language).is_none() {
+        let known: Vec<&str> = i18n::Language::ALL.iter().map(|l| l.code()).collect();
+        push(
+            Severity::Warning,
+            "language",
+            format!(
+                "language = \"{}\" has no translation, English is used — use one of {} or leave it empty",
+                cfg.language,
+                known.join(", ")
+            ),
+        );
+    }
+    for (key, text) in [
+        ("toast_title", &cfg.toast_title),
+        ("toast_body", &cfg.toast_body),
+    ] {
+        for name in toast_template::unknown_placeholders(text) {
+            let known: Vec<String> = toast_template::PLACEHOLDERS
+                .iter()
+                .map(|p| format!("{{{}}}", p))
+                .collect();
+            push(
+                Severity::Warning,
+                key,
+                format!(
+                    "{} has an unknown placeholder {{{}}}, shown as written — use {}, or {{{{ }}}} for literal braces",
+                    key,
+                    name,
+                    known.join(", ")
+                ),
+            );
+        }
+    }
+
+    if !["top-left", "top-right", "bottom-left", "bottom-right"]
+        .contains(&cfg.osd_position.trim().to_ascii_lowercase().as_str())
+    {
+        push(
+            Severity::Error,
+            "osd_position",
+            format!(
+                "osd_position = \"{}\" is not recognised — use \"top-left\", \"top-right\", \"bottom-left\" or \"bottom-right\"",
+                cfg.osd_position
+            ),
+        );
+    }
+    if !(250..=30_000).contains(&cfg.osd_duration_ms) {
+        push(
+            Severity::Warning,
+            "osd_duration_ms",
+            format!(
+                "osd_duration_ms = {} is out of range — use 250..=30000",
+                cfg.osd_duration_ms
+            ),
+        );
+    }
+
+    if !cfg.measure_command.trim().is_empty()
+        && !(1_000..=600_000).contains(&cfg.measure_timeout_ms)
+    {
+        push(
+            Severity::Warning,
+            "measure_timeout_ms",
+            format!(
+                "measure_timeout_ms = {} is out of range — use 1000..=600000",
+                cfg.measure_timeout_ms
+            ),
+        );
+    }
+
+    let endpoint = cfg.telemetry_endpoint.trim();
+    if !endpoint.is_empty() && !endpoint.to_ascii_lowercase().starts_with("https://") {
+        push(
+            Severity::Error,
+            "telemetry_endpoint",
+            format!("telemetry_endpoint \"{}\" is not an https:// URL", endpoint),
+        );
+    } else if cfg.telemetry_enabled && endpoint.is_empty() {
+        push(
+            Severity::Warning,
+            "telemetry_enabled",
+            "telemetry_enabled is on but telemetry_endpoint is empty — nothing is sent".to_string(),
+        );
+    }
+
+    // ── Tamper / gamma watch ─────────────────────────────────────
+    for (key, value) in [
+        ("tamper_watch", &cfg.tamper_watch),
+        ("gamma_watch", &cfg.gamma_watch),
+    ] {
+        if !["off", "log", "notify", "reassert"]
+            .contains(&value.trim().to_ascii_lowercase().as_str())
+        {
+            push(
+                Severity::Error,
+                key,
+                format!(
+                    "{} = \"{}\" is not recognised — use \"off\", \"log\", \"notify\" or \"reassert\"",
+                    key, value
+                ),
+            );
+        }
+    }
+
+    // ── Schedule ─────────────────────────────────────────────────
+    for (key, value) in [
+        ("icc_schedule_day_start", &cfg.icc_schedule_day_start),
+        ("icc_schedule_night_start", &cfg.icc_schedule_night_start),
+    ] {
+        if !value.trim().is_empty() && schedule::parse_clock_time(value).is_none() {
+            push(
+                Severity::Error,
+                key,
+                format!("{} = \"{}\" is not a 24h HH:MM time", key, value),
+            );
+        }
+    }
+    let day_set = !cfg.icc_schedule_day_preset.trim().is_empty();
+    let night_set = !cfg.icc_schedule_night_preset.trim().is_empty();
+    if day_set != night_set {
+        let (set, unset) = if day_set {
+            ("icc_schedule_day_preset", "icc_schedule_night_preset")
+        } else {
+            ("icc_schedule_night_preset", "icc_schedule_day_preset")
+        };
+        push(
+            Severity::Warning,
+            set,
+            format!(
+                "{} is set but {} is empty — the day/night schedule needs both",
+                set, unset
+            ),
+        );
+    }
+    let mode = cfg.schedule.mode.trim();
+    if !mode.eq_ignore_ascii_case(MODE_FIXED) && !mode.eq_ignore_ascii_case(MODE_SOLAR) {
+        push(
+            Severity::Error,
+            "schedule.mode",
+            format!(
+                "schedule.mode = \"{}\" is not recognised — use \"{}\" or \"{}\"",
+                cfg.schedule.mode, MODE_FIXED, MODE_SOLAR
+            ),
+        );
+    }
+    if cfg.schedule.latitude.abs() > 90.0 || !cfg.schedule.latitude.is_finite() {
+        push(
+            Severity::Error,
+            "schedule.latitude",
+            format!(
+                "schedule.latitude = {} is out of range — use -90..=90",
+                cfg.schedule.latitude
+            ),
+        );
+    }
+    if cfg.schedule.longitude.abs() > 180.0 || !cfg.schedule.longitude.is_finite() {
+        push(
+            Severity::Error,
+            "schedule.longitude",
+            format!(
+                "schedule.longitude = {} is out of range — use -180..=180",
+                cfg.schedule.longitude
+            ),
+        );
+    }
+
+    // ── Conflicting options ──────────────────────────────────────
+    if schedule::is_enabled(cfg)
+        && !schedule::is_solar(cfg)
+        && schedule::parse_clock_time(&cfg.icc_schedule_day_start).is_some()
+        && schedule::parse_clock_time(&cfg.icc_schedule_day_start)
+            == schedule::parse_clock_time(&cfg.icc_schedule_night_start)
+    {
+        push(
+            Severity::Error,
+            "icc_schedule_night_start",
+            "icc_schedule_day_start and icc_schedule_night_start are the same time — \
+             the night preset would never apply"
+                .to_string(),
+        );
+    }
+    if schedule::is_solar(cfg)
+        && !cfg.schedule.use_windows_location
+        && cfg.schedule.latitude == 0.0
+        && cfg.schedule.longitude == 0.0
+    {
+        push(
+            Severity::Warning,
+            "schedule.mode",
+            "solar mode with no location — set schedule.latitude/longitude \
+             or schedule.use_windows_location = true"
+                .to_string(),
+        );
+    }
+    if cfg.hotkeys_enabled {
+        let bindings = [
+            ("hotkey_reapply", &cfg.hotkey_reapply),
+            ("hotkey_brightness_up", &cfg.hotkey_brightness_up),
+            ("hotkey_brightness_down", &cfg.hotkey_brightness_down),
+        ];
+        if bindings.iter().all(|(_, b)| b.trim().is_empty()) {
+            push(
+                Severity::Warning,
+                "hotkeys_enabled",
+                "hotkeys_enabled = true but no hotkey is bound".to_string(),
+            );
+        }
+        for (i, (key, binding)) in bindings.iter().enumerate() {
+            let normalized = normalize_binding(binding);
+            if normalized.is_empty() {
+                continue;
+            }
+            if let Some((other, _)) = bindings[..i]
+                .iter()
+                .find(|(_, b)| normalize_binding(b) == normalized)
+            {
+                push(
+                    Severity::Error,
+                    key,
+                    format!(
+                        "{} = \"{}\" is already bound to {}",
+                        key,
+                        binding.trim(),
+                        other
+                    ),
+                );
+            }
+        }
+    }
+    if !cfg.fullscreen_exit_reassert && !cfg.fullscreen_exit_apps.trim().is_empty() {
+        push(
+            Severity::Warning,
+            "fullscreen_exit_apps",
+            "fullscreen_exit_apps is set but fullscreen_exit_reassert = false — the list is ignored"
+                .to_string(),
+        );
+    }
+
+    issues
+}
+
+fn delay_value(cfg: &Config, key: &str) -> u64 {
+    match key {
+        "stabilize_delay_ms" => cfg.stabilize_delay_ms,
+        "toggle_delay_ms" => cfg.toggle_delay_ms,
+        "reapply_delay_ms" => cfg.reapply_delay_ms,
+        "brightness_guard_interval_ms" => cfg.brightness_guard_interval_ms,
+        "defer_reapply_idle_ms" => cfg.defer_reapply_idle_ms,
+        "defer_reapply_max_ms" => cfg.defer_reapply_max_ms,
+        "gamma_watch_interval_ms" => cfg.gamma_watch_interval_ms,
+        _ => 0,
+    }
+}
+
+fn is_plain_file_name(name: &str) -> bool {
+    !name.contains(['/', '\\', ':']) && name != "." && name != ".."
+}
+
+fn has_profile_extension(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    lower.ends_with(".icm") || lower.ends_with(".icc")
+}
+
+/// Modifier order and spacing don't matter to the hotkey parser.
+fn normalize_binding(binding: &str) -> Vec<String> {
+    let mut parts: Vec<String> = binding
+        .split('+')
+        .map(|p| p.trim().to_ascii_lowercase())
+        .filter(|p| !p.is_empty())
+        .collect();
+    parts.sort();
+    parts
+}
+
+#[cfg(test)]
+#[path = "tests/validate_tests.rs"]
+mod tests;
@@ -0,0 +1,92 @@
+//! Owned, NUL-terminated UTF-16 strings for Win32 calls.
+//!
+//! `PCWSTR(to_wide(s).as_ptr())` compiles fine and hands Windows a pointer
+//! into a buffer that is freed at the end of the statement. A
+//! [`WideString`] is bound to a name first and only lends out pointers
+//! that borrow from it, so the buffer visibly outlives the call.
+
+use std::ffi::OsStr;
+use std::fmt;
+
+/// A UTF-16 string with a trailing NUL.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct WideString(Vec<u16>);
+
+impl WideString {
+    /// Encode `text`. Paths keep unpaired surrogates on Windows.
+    pub fn new(text: impl AsRef<OsStr>) -> Self {
+        let mut units = encode(text.as_ref());
+        units.push(0);
+        Self(units)
+    }
+
+    /// Pointer to the first unit, valid while `self` is alive. Windows
+    /// reads up to the first NUL, so text after an embedded NUL is ignored.
+    pub fn as_ptr(&self) -> *const u16 {
+        self.0.as_ptr()
+    }
+
+    /// The string as a `PCWSTR`, valid while `self` is alive.
+    #[cfg(windows)]
+    pub fn as_pcwstr(&self) -> windows::core::PCWSTR {
+        windows::core::PCWSTR(self.as_ptr())
+    }
+
+    /// The units without the trailing NUL.
+    pub fn as_slice(&self) -> &[u16] {
+        &self.0[..self.0.len() - 1]
+    }
+
+    /// The units including the trailing NUL.
+    pub fn as_slice_with_nul(&self) -> &[u16] {
+        &self.0
+    }
+
+    /// Number of units, not counting the trailing NUL.
+    pub fn len(&self) -> usize {
+        self.0.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for WideString {
+    fn default() -> Self {
+        Self(vec![0])
+    }
+}
+
+impl<T: AsRef<OsStr> + ?Sized> From<&T> for WideString {
+    fn from(text: &T) -> Self {
+        Self::new(text)
+    }
+}
+
+impl fmt::Display for WideString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from_utf16_lossy(self.as_slice()))
+    }
+}
+
+impl fmt::Debug for WideString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "WideString({:?})", self.to_string())
+    }
+}
+
+#[cfg(windows)]
+fn encode(text: &OsStr) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+    text.encode_wide().collect()
+}
+
+#[cfg(not(windows))]
+fn encode(text: &OsStr) -> Vec<u16> {
+    text.to_string_lossy().encode_utf16().collect()
+}
+
+#[cfg(test)]
+#[path = "tests/wide_tests.rs"]
+mod tests;
@@ -0,0 +1,149 @@
+//! Soak test: thousands of simulated reapplies must not leak.
+//!
+//! Runs the reapply pipeline against an in-memory backend, with one monitor
+//! failing at a rotating step each round so the error paths are exercised
+//! too, and samples the process between batches. This test binary runs on
+//! its own, so the samples aren't disturbed by other tests. Where the
+//! counters aren't available the reapplies still run, unmeasured.
+
+use lg_core::pipeline::{ModeProfiles, ReapplyOptions, ReapplyPipeline, ReapplySteps};
+use lg_core::resource_audit::{self, ResourceAudit};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// Reapplies before the baseline sample, so anything created lazily on
+/// first use is already in place.
+const WARMUP: usize = 200;
+/// Reapplies measured.
+const ROUNDS: usize = 5_000;
+/// Reapplies between samples.
+const BATCH: usize = 500;
+
+/// In-memory backend with three monitors.
+struct SoakSteps {
+    round: usize,
+    monitors: Vec<String>,
+}
+
+impl SoakSteps {
+    fn new(round: usize) -> Self {
+        Self {
+            round,
+            monitors: (0..3)
+                .map(|i| format!(r"DISPLAY\GSM5BBF\5&soak&0&UID{}", 4352 + i))
+                .collect(),
+        }
+    }
+
+    /// Whether `monitor` fails at `step` this round.
+    fn fails(&self, monitor: &str, step: usize) -> bool {
+        self.round % 4 == step && monitor.ends_with(&(4352 + self.round % 3).to_string())
+    }
+}
+
+impl ReapplySteps for SoakSteps {
+    type Monitor = String;
+
+    fn ensure_shared_profiles(&mut self) -> Result<Option<ModeProfiles>, Box<dyn Error>> {
+        Ok(self.round.is_multiple_of(2).then(|| ModeProfiles {
+            sdr: PathBuf::from("lg-ultragear-full-cal.icm"),
+            hdr: PathBuf::from("lg-ultragear-full-cal-hdr.icm"),
+        }))
+    }
+
+    fn is_installed(&self, _path: &Path) -> bool {
+        true
+    }
+
+    fn find_monitors(&mut self) -> Result<Vec<String>, Box<dyn Error>> {
+        Ok(self.monitors.clone())
+    }
+
+    fn ensure_monitor_profiles(
+        &mut self,
+        monitor: &String,
+    ) -> Result<ModeProfiles, Box<dyn Error>> {
+        if self.fails(monitor, 0) {
+            return Err(format!("cannot write profile for {}", monitor).into());
+        }
+        Ok(ModeProfiles::single(PathBuf::from(format!(
+            "lg-ultragear-{}.icm",
+            monitor.rsplit('&').next().unwrap_or_default()
+        ))))
+    }
+
+    fn associate(
+        &mut self,
+        monitor: &String,
+        _active: &Path,
+        _sdr: &Path,
+        _hdr: Option<&Path>,
+        _per_user: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.fails(monitor, 1) {
+            return Err(format!("access denied for {}", monitor).into());
+        }
+        Ok(())
+    }
+
+    fn set_generic_default(
+        &mut self,
+        monitor: &String,
+        _active: &Path,
+        _per_user: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.fails(monitor, 2) {
+            return Err("default rejected".into());
+        }
+        Ok(())
+    }
+
+    fn refresh(&mut self) {}
+
+    fn set_ddc_brightness(&mut self, _level: u32) -> Result<usize, Box<dyn Error>> {
+        if self.round % 4 == 3 {
+            return Err("no DDC".into());
+        }
+        Ok(self.monitors.len())
+    }
+
+    fn toast(&mut self) {}
+}
+
+fn reapply(pipeline: &ReapplyPipeline, round: usize) {
+    let outcome = pipeline
+        .run(&mut SoakSteps::new(round))
+        .expect("simulated reapply");
+    assert_eq!(outcome.found, 3);
+    assert!(outcome.applied >= 2);
+}
+
+#[test]
+fn thousands_of_reapplies_do_not_leak() {
+    let pipeline = ReapplyPipeline::new(ReapplyOptions {
+        hdr: true,
+        generic_default: true,
+        ddc_brightness: Some(50),
+        toast: true,
+        ..ReapplyOptions::default()
+    });
+    for round in 0..WARMUP {
+        reapply(&pipeline, round);
+    }
+
+    let mut audit = ResourceAudit::new(16, 4 * 1024 * 1024);
+    if let Some(sample) = resource_audit::sample() {
+        audit.record(sample);
+    }
+    for batch in 0..ROUNDS / BATCH {
+        for round in 0..BATCH {
+            reapply(&pipeline, WARMUP + batch * BATCH + round);
+        }
+        if let Some(growth) = resource_audit::sample().and_then(|s| audit.record(s)) {
+            panic!("resources grew during simulated reapplies: {}", growth);
+        }
+    }
+    if audit.baseline().is_none() {
+        eprintln!("resource counters unavailable; reapplies ran unmeasured");
+    }
+}
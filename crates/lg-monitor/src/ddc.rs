@@ -5,14 +5,50 @@
 //!
 //! All functions are safe to call without admin rights — DDC/CI only needs
 //! access to the display adapter (which every interactive user has).
+//!
+//! `dxva2.dll` is loaded at runtime; on systems without it every call returns
+//! [`DDC_UNSUPPORTED_MESSAGE`] and [`is_supported`] reports `false`.
+//!
+//! Failed calls return a [`DdcError`] whose [`DdcErrorKind`] names the
+//! likely cause (DDC/CI switched off in the monitor's menu, a KVM in the
+//! path, an unsupported code) and whose message says what to do about it.
+//!
+//! Every public call that uses physical monitor handles holds a named
+//! cross-process mutex while it does, so a `ddc` command run during the
+//! service's reapply queues behind it instead of interleaving commands on
+//! the same monitor.
+//!
+//! Physical monitor handles are opened once and cached until the display
+//! configuration changes ([`invalidate_handle_cache`], or a different set of
+//! HMONITORs). A [`DdcSession`] runs several calls against the cache under
+//! one lock, for pollers such as the brightness guard.
 
 use log::{info, warn};
 use std::error::Error;
 use std::io;
 use std::ptr;
-
-use windows::Win32::Foundation::{BOOL, LPARAM, RECT};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::Duration;
+
+use windows::core::{s, w, PCWSTR};
+use windows::Win32::Foundation::{
+    LocalFree, BOOL, HANDLE as Win32Handle, HLOCAL, LPARAM, RECT, WAIT_ABANDONED, WAIT_OBJECT_0,
+    WAIT_TIMEOUT, WPARAM,
+};
 use windows::Win32::Graphics::Gdi::{EnumDisplayMonitors, HDC, HMONITOR};
+use windows::Win32::Security::Authorization::{
+    ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+};
+use windows::Win32::Security::{PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES};
+use windows::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
+use windows::Win32::System::Threading::{
+    CreateMutexExW, ReleaseMutex, WaitForSingleObject, MUTEX_MODIFY_STATE,
+    SYNCHRONIZATION_SYNCHRONIZE,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    PostMessageW, HWND_BROADCAST, SC_MONITORPOWER, WM_SYSCOMMAND,
+};
 
 // ============================================================================
 // DDC/CI FFI — dxva2.dll
@@ -29,27 +65,149 @@ struct PhysicalMonitor {
     description: [u16; 128],
 }
 
-#[link(name = "dxva2")]
-extern "system" {
-    fn GetNumberOfPhysicalMonitorsFromHMONITOR(h_monitor: isize, num_monitors: *mut u32) -> BOOL;
+/// Runtime-resolved `dxva2.dll` entry points.
+///
+/// Stripped-down Windows editions (N/KN, some VM images) ship without the
+/// Monitor Configuration API, and a static import would keep the whole binary
+/// from loading there. Resolving at first use lets every DDC call fail with
+/// [`DDC_UNSUPPORTED_MESSAGE`] instead.
+#[allow(non_snake_case)]
+struct Dxva2Api {
+    GetNumberOfPhysicalMonitorsFromHMONITOR: unsafe extern "system" fn(isize, *mut u32) -> BOOL,
+    GetPhysicalMonitorsFromHMONITOR:
+        unsafe extern "system" fn(isize, u32, *mut PhysicalMonitor) -> BOOL,
+    DestroyPhysicalMonitor: unsafe extern "system" fn(HANDLE) -> BOOL,
+    SetVCPFeature: unsafe extern "system" fn(HANDLE, u8, u32) -> BOOL,
+    GetVCPFeatureAndVCPFeatureReply:
+        unsafe extern "system" fn(HANDLE, u8, *mut u32, *mut u32, *mut u32) -> BOOL,
+    GetCapabilitiesStringLength: unsafe extern "system" fn(HANDLE, *mut u32) -> BOOL,
+    CapabilitiesRequestAndCapabilitiesReply:
+        unsafe extern "system" fn(HANDLE, *mut u8, u32) -> BOOL,
+}
+
+static DXVA2_API: OnceLock<Option<Dxva2Api>> = OnceLock::new();
+
+/// Error returned by every DDC function when `dxva2.dll` is unavailable.
+pub const DDC_UNSUPPORTED_MESSAGE: &str =
+    "DDC/CI is not supported on this system (dxva2.dll Monitor Configuration API unavailable)";
+
+fn dxva2_api() -> Option<&'static Dxva2Api> {
+    DXVA2_API
+        .get_or_init(|| {
+            let api = unsafe { load_dxva2_api() };
+            if api.is_none() {
+                warn!("{}", DDC_UNSUPPORTED_MESSAGE);
+            }
+            api
+        })
+        .as_ref()
+}
+
+unsafe fn load_dxva2_api() -> Option<Dxva2Api> {
+    let module = LoadLibraryW(w!("dxva2.dll")).ok()?;
+    macro_rules! resolve {
+        ($name:literal) => {
+            std::mem::transmute(GetProcAddress(module, s!($name))?)
+        };
+    }
+    Some(Dxva2Api {
+        GetNumberOfPhysicalMonitorsFromHMONITOR: resolve!(
+            "GetNumberOfPhysicalMonitorsFromHMONITOR"
+        ),
+        GetPhysicalMonitorsFromHMONITOR: resolve!("GetPhysicalMonitorsFromHMONITOR"),
+        DestroyPhysicalMonitor: resolve!("DestroyPhysicalMonitor"),
+        SetVCPFeature: resolve!("SetVCPFeature"),
+        GetVCPFeatureAndVCPFeatureReply: resolve!("GetVCPFeatureAndVCPFeatureReply"),
+        GetCapabilitiesStringLength: resolve!("GetCapabilitiesStringLength"),
+        CapabilitiesRequestAndCapabilitiesReply: resolve!(
+            "CapabilitiesRequestAndCapabilitiesReply"
+        ),
+    })
+}
+
+/// True if the DDC/CI API is available on this system.
+///
+/// Resolved once; callers such as the reapply pipeline use this to skip DDC
+/// steps silently rather than logging a failure on every cycle.
+pub fn is_supported() -> bool {
+    dxva2_api().is_some()
+}
 
-    fn GetPhysicalMonitorsFromHMONITOR(
-        h_monitor: isize,
-        array_size: u32,
-        physical_monitors: *mut PhysicalMonitor,
-    ) -> BOOL;
+fn ensure_supported() -> Result<&'static Dxva2Api, Box<dyn Error>> {
+    dxva2_api().ok_or_else(|| DDC_UNSUPPORTED_MESSAGE.into())
+}
 
-    fn DestroyPhysicalMonitor(h_monitor: HANDLE) -> BOOL;
+// Thin shims with the Win32 names so call sites read like the SDK. They are
+// only reached after `enumerate_hmonitors` has checked `ensure_supported`.
 
-    fn SetVCPFeature(h_monitor: HANDLE, vcp_code: u8, new_value: u32) -> BOOL;
+#[allow(non_snake_case)]
+unsafe fn GetNumberOfPhysicalMonitorsFromHMONITOR(
+    h_monitor: isize,
+    num_monitors: *mut u32,
+) -> BOOL {
+    dxva2_api().map_or(BOOL(0), |api| {
+        (api.GetNumberOfPhysicalMonitorsFromHMONITOR)(h_monitor, num_monitors)
+    })
+}
 
-    fn GetVCPFeatureAndVCPFeatureReply(
-        h_monitor: HANDLE,
-        vcp_code: u8,
-        vcp_type: *mut u32,
-        current_value: *mut u32,
-        maximum_value: *mut u32,
-    ) -> BOOL;
+#[allow(non_snake_case)]
+unsafe fn GetPhysicalMonitorsFromHMONITOR(
+    h_monitor: isize,
+    array_size: u32,
+    physical_monitors: *mut PhysicalMonitor,
+) -> BOOL {
+    dxva2_api().map_or(BOOL(0), |api| {
+        (api.GetPhysicalMonitorsFromHMONITOR)(h_monitor, array_size, physical_monitors)
+    })
+}
+
+#[allow(non_snake_case)]
+unsafe fn DestroyPhysicalMonitor(h_monitor: HANDLE) -> BOOL {
+    dxva2_api().map_or(BOOL(0), |api| (api.DestroyPhysicalMonitor)(h_monitor))
+}
+
+#[allow(non_snake_case)]
+unsafe fn SetVCPFeature(h_monitor: HANDLE, vcp_code: u8, new_value: u32) -> BOOL {
+    dxva2_api().map_or(BOOL(0), |api| {
+        (api.SetVCPFeature)(h_monitor, vcp_code, new_value)
+    })
+}
+
+#[allow(non_snake_case)]
+unsafe fn GetVCPFeatureAndVCPFeatureReply(
+    h_monitor: HANDLE,
+    vcp_code: u8,
+    vcp_type: *mut u32,
+    current_value: *mut u32,
+    maximum_value: *mut u32,
+) -> BOOL {
+    dxva2_api().map_or(BOOL(0), |api| {
+        (api.GetVCPFeatureAndVCPFeatureReply)(
+            h_monitor,
+            vcp_code,
+            vcp_type,
+            current_value,
+            maximum_value,
+        )
+    })
+}
+
+#[allow(non_snake_case)]
+unsafe fn GetCapabilitiesStringLength(h_monitor: HANDLE, length: *mut u32) -> BOOL {
+    dxva2_api().map_or(BOOL(0), |api| {
+        (api.GetCapabilitiesStringLength)(h_monitor, length)
+    })
+}
+
+#[allow(non_snake_case)]
+unsafe fn CapabilitiesRequestAndCapabilitiesReply(
+    h_monitor: HANDLE,
+    capabilities: *mut u8,
+    length: u32,
+) -> BOOL {
+    dxva2_api().map_or(BOOL(0), |api| {
+        (api.CapabilitiesRequestAndCapabilitiesReply)(h_monitor, capabilities, length)
+    })
 }
 
 // ============================================================================
@@ -75,6 +233,16 @@ pub const VCP_GREEN_GAIN: u8 = 0x18;
 /// VCP code for Video Gain (Drive) — Blue.  Range 0–100.
 pub const VCP_BLUE_GAIN: u8 = 0x1A;
 
+/// VCP code for Sharpness.  Range is model-specific (UltraGear: 0–100).
+pub const VCP_SHARPNESS: u8 = 0x87;
+
+/// First VCP code of the MCCS manufacturer-specific range (0xE0–0xFF).
+///
+/// LG exposes Response Time (overdrive) somewhere in this range, but the
+/// exact code varies per model, so writes take the code from the user;
+/// [`response_time_candidate`] only suggests one.
+pub const VCP_MANUFACTURER_SPECIFIC_START: u8 = 0xE0;
+
 /// VCP code for Input Source Select.
 /// Values: 1=VGA, 3=DVI, 15=DisplayPort, 17=HDMI1, 18=HDMI2.
 pub const VCP_INPUT_SOURCE: u8 = 0x60;
@@ -86,7 +254,7 @@ pub const VCP_VOLUME: u8 = 0x62;
 pub const VCP_DISPLAY_MODE: u8 = 0xDC;
 
 /// VCP code for Power Mode.
-/// Values: 1=On, 2=Standby, 4=Suspend, 5=Off.
+/// Values: 1=On, 2=Standby, 3=Suspend, 4=Off (DPM), 5=Off (power button).
 pub const VCP_POWER_MODE: u8 = 0xD6;
 
 /// VCP code for VCP Version (read-only).
@@ -101,6 +269,548 @@ pub const VCP_RESET_BRIGHTNESS_CONTRAST: u8 = 0x06;
 /// VCP code: Restore Factory Color Defaults.  Write 1 to trigger.
 pub const VCP_RESET_COLOR: u8 = 0x0A;
 
+// ============================================================================
+// Error classification
+// ============================================================================
+
+const ERROR_TIMEOUT: i32 = 1460;
+const ERROR_GRAPHICS_I2C_NOT_SUPPORTED: u32 = 0xC026_2580;
+const ERROR_GRAPHICS_I2C_DEVICE_DOES_NOT_EXIST: u32 = 0xC026_2581;
+const ERROR_GRAPHICS_I2C_ERROR_TRANSMITTING_DATA: u32 = 0xC026_2582;
+const ERROR_GRAPHICS_I2C_ERROR_RECEIVING_DATA: u32 = 0xC026_2583;
+const ERROR_GRAPHICS_DDCCI_VCP_NOT_SUPPORTED: u32 = 0xC026_2584;
+const ERROR_GRAPHICS_DDCCI_INVALID_DATA: u32 = 0xC026_2585;
+const ERROR_GRAPHICS_DDCCI_MONITOR_RETURNED_INVALID_TIMING_STATUS_BYTE: u32 = 0xC026_2586;
+const ERROR_GRAPHICS_MCA_INVALID_CAPABILITIES_STRING: u32 = 0xC026_2587;
+const ERROR_GRAPHICS_DDCCI_INVALID_MESSAGE_COMMAND: u32 = 0xC026_2589;
+const ERROR_GRAPHICS_DDCCI_INVALID_MESSAGE_LENGTH: u32 = 0xC026_258A;
+const ERROR_GRAPHICS_DDCCI_INVALID_MESSAGE_CHECKSUM: u32 = 0xC026_258B;
+const ERROR_GRAPHICS_INVALID_PHYSICAL_MONITOR_HANDLE: u32 = 0xC026_258C;
+const ERROR_GRAPHICS_MONITOR_NO_LONGER_EXISTS: u32 = 0xC026_258D;
+
+/// Likely cause of a failed DDC/CI call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DdcErrorKind {
+    /// `dxva2.dll` is missing ([`DDC_UNSUPPORTED_MESSAGE`]).
+    Unsupported,
+    /// Nothing answered on the DDC bus: DDC/CI is switched off in the
+    /// monitor's on-screen menu, or a KVM switch, dock or adapter does not
+    /// pass it through.
+    NoResponse,
+    /// The monitor answered with corrupted data, typically through a KVM
+    /// switch, dock or long cable.
+    BadReply,
+    /// The monitor does not implement the VCP code.
+    CodeNotSupported,
+    /// The monitor was disconnected or went to sleep mid-call.
+    MonitorGone,
+    /// Another process (usually the service mid-reapply) kept DDC/CI busy
+    /// for longer than [`DDC_LOCK_TIMEOUT`].
+    Busy,
+    /// Anything else; the raw Win32 error is shown.
+    Other,
+}
+
+impl DdcErrorKind {
+    /// Classify a Win32 error code from a dxva2 call.
+    pub fn from_os_error(code: i32) -> Self {
+        if code == ERROR_TIMEOUT {
+            return Self::NoResponse;
+        }
+        match code as u32 {
+            ERROR_GRAPHICS_I2C_NOT_SUPPORTED
+            | ERROR_GRAPHICS_I2C_DEVICE_DOES_NOT_EXIST
+            | ERROR_GRAPHICS_I2C_ERROR_TRANSMITTING_DATA
+            | ERROR_GRAPHICS_I2C_ERROR_RECEIVING_DATA
+            | ERROR_GRAPHICS_DDCCI_MONITOR_RETURNED_INVALID_TIMING_STATUS_BYTE => Self::NoResponse,
+            ERROR_GRAPHICS_DDCCI_INVALID_DATA
+            | ERROR_GRAPHICS_MCA_INVALID_CAPABILITIES_STRING
+            | ERROR_GRAPHICS_DDCCI_INVALID_MESSAGE_COMMAND
+            | ERROR_GRAPHICS_DDCCI_INVALID_MESSAGE_LENGTH
+            | ERROR_GRAPHICS_DDCCI_INVALID_MESSAGE_CHECKSUM => Self::BadReply,
+            ERROR_GRAPHICS_DDCCI_VCP_NOT_SUPPORTED => Self::CodeNotSupported,
+            ERROR_GRAPHICS_INVALID_PHYSICAL_MONITOR_HANDLE
+            | ERROR_GRAPHICS_MONITOR_NO_LONGER_EXISTS => Self::MonitorGone,
+            _ => Self::Other,
+        }
+    }
+
+    /// What the user should do about it, or `None` for [`Self::Other`].
+    pub fn guidance(self) -> Option<&'static str> {
+        match self {
+            Self::Unsupported => Some(DDC_UNSUPPORTED_MESSAGE),
+            Self::NoResponse => Some(
+                "the monitor did not answer over DDC/CI. Turn DDC/CI on in the monitor's \
+                 on-screen menu, and if it is connected through a KVM switch, dock or adapter, \
+                 try a direct cable",
+            ),
+            Self::BadReply => Some(
+                "the monitor's DDC/CI replies were corrupted. This usually means a KVM switch, \
+                 dock, adapter or long cable in between; try a direct connection",
+            ),
+            Self::CodeNotSupported => Some("the monitor does not support this setting over DDC/CI"),
+            Self::MonitorGone => {
+                Some("the monitor was disconnected or went to sleep; try again once it is on")
+            }
+            Self::Busy => Some(
+                "another process (usually the service reapplying the profile) is still using \
+                 DDC/CI; try again in a moment",
+            ),
+            Self::Other => None,
+        }
+    }
+
+    /// True for failures that point at the monitor's DDC/CI setting or the
+    /// connection rather than a single setting.
+    pub fn is_link_problem(self) -> bool {
+        matches!(self, Self::NoResponse | Self::BadReply)
+    }
+}
+
+/// A failed DDC/CI call, classified.
+#[derive(Debug)]
+pub struct DdcError {
+    operation: String,
+    kind: DdcErrorKind,
+    os_error: Option<i32>,
+}
+
+impl DdcError {
+    pub fn new(operation: impl Into<String>, kind: DdcErrorKind) -> Self {
+        Self {
+            operation: operation.into(),
+            kind,
+            os_error: None,
+        }
+    }
+
+    /// Classify the calling thread's last Win32 error for `operation`.
+    fn last_os_error(operation: impl Into<String>) -> Self {
+        let code = io::Error::last_os_error().raw_os_error();
+        Self {
+            operation: operation.into(),
+            kind: code.map_or(DdcErrorKind::Other, DdcErrorKind::from_os_error),
+            os_error: code,
+        }
+    }
+
+    pub fn kind(&self) -> DdcErrorKind {
+        self.kind
+    }
+
+    /// Raw Win32 error, when the failure came from one.
+    pub fn os_error(&self) -> Option<i32> {
+        self.os_error
+    }
+}
+
+impl std::fmt::Display for DdcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.kind.guidance(), self.os_error) {
+            (Some(guidance), _) => write!(f, "{} failed: {}", self.operation, guidance),
+            (None, Some(code)) => write!(
+                f,
+                "{} failed: {}",
+                self.operation,
+                io::Error::from_raw_os_error(code)
+            ),
+            (None, None) => write!(f, "{} failed", self.operation),
+        }
+    }
+}
+
+impl Error for DdcError {}
+
+/// Kind of a DDC error returned by this module, or `None` for errors that
+/// did not come from a DDC call (e.g. no monitor matched the pattern).
+pub fn error_kind(err: &(dyn Error + 'static)) -> Option<DdcErrorKind> {
+    if let Some(ddc) = err.downcast_ref::<DdcError>() {
+        return Some(ddc.kind());
+    }
+    (err.to_string() == DDC_UNSUPPORTED_MESSAGE).then_some(DdcErrorKind::Unsupported)
+}
+
+/// The cause shared by every failure in `kinds`, when one call per monitor
+/// failed the same way. A mix of silent and garbled replies still points at
+/// the connection, reported as [`DdcErrorKind::BadReply`].
+pub fn common_failure(kinds: &[DdcErrorKind]) -> Option<DdcErrorKind> {
+    let first = *kinds.first()?;
+    if kinds.iter().all(|k| *k == first) {
+        return Some(first);
+    }
+    kinds
+        .iter()
+        .all(|k| k.is_link_problem())
+        .then_some(DdcErrorKind::BadReply)
+}
+
+// ============================================================================
+// Cross-process lock
+// ============================================================================
+
+/// Named mutex held while physical monitor handles are open. `Global\` so
+/// the service in session 0 and commands run from a desktop share it.
+const DDC_MUTEX_NAME: &str = r"Global\lg-ultragear-ddc";
+
+/// SYSTEM and administrators get full access; any signed-in user may wait
+/// on and release the mutex, whichever process created it.
+const DDC_MUTEX_SDDL: &str = "D:(A;;GA;;;SY)(A;;GA;;;BA)(A;;0x100001;;;AU)";
+
+/// How long a call queues behind another process before failing with
+/// [`DdcErrorKind::Busy`]. A full reapply with DDC brightness takes a few
+/// seconds at most.
+pub const DDC_LOCK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Process-wide mutex handle (as `isize`, since handles aren't `Sync`), or
+/// `None` when it can't be created here.
+static DDC_MUTEX: OnceLock<Option<isize>> = OnceLock::new();
+
+fn ddc_mutex() -> Option<Win32Handle> {
+    DDC_MUTEX
+        .get_or_init(|| unsafe { create_ddc_mutex() }.map(|h| h.0 as isize))
+        .map(|h| Win32Handle(h as *mut std::ffi::c_void))
+}
+
+unsafe fn create_ddc_mutex() -> Option<Win32Handle> {
+    let name: Vec<u16> = DDC_MUTEX_NAME.encode_utf16().chain(Some(0)).collect();
+    let sddl: Vec<u16> = DDC_MUTEX_SDDL.encode_utf16().chain(Some(0)).collect();
+    let mut descriptor = PSECURITY_DESCRIPTOR::default();
+    let have_descriptor = ConvertStringSecurityDescriptorToSecurityDescriptorW(
+        PCWSTR(sddl.as_ptr()),
+        SDDL_REVISION_1,
+        &mut descriptor,
+        None,
+    )
+    .is_ok();
+    let attributes = SECURITY_ATTRIBUTES {
+        nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+        lpSecurityDescriptor: descriptor.0,
+        bInheritHandle: BOOL(0),
+    };
+    // Opening with just wait/release rights lets a standard user use the
+    // service's mutex; creating one in `Global\` needs SeCreateGlobalPrivilege.
+    let result = CreateMutexExW(
+        have_descriptor.then_some(&attributes as *const _),
+        PCWSTR(name.as_ptr()),
+        0,
+        (SYNCHRONIZATION_SYNCHRONIZE | MUTEX_MODIFY_STATE).0,
+    );
+    if have_descriptor {
+        let _ = LocalFree(HLOCAL(descriptor.0));
+    }
+    match result {
+        Ok(handle) => Some(handle),
+        Err(e) => {
+            info!(
+                "DDC/CI lock {} unavailable ({}); calls are not serialized with other processes",
+                DDC_MUTEX_NAME, e
+            );
+            None
+        }
+    }
+}
+
+/// Holds the DDC mutex until dropped. Reentrant on the same thread, so a
+/// public call may use another one.
+struct DdcLock(Option<Win32Handle>);
+
+impl Drop for DdcLock {
+    fn drop(&mut self) {
+        if let Some(handle) = self.0 {
+            unsafe {
+                let _ = ReleaseMutex(handle);
+            }
+        }
+    }
+}
+
+/// Wait for exclusive DDC access, queueing behind another process for up to
+/// [`DDC_LOCK_TIMEOUT`].
+fn lock_ddc() -> Result<DdcLock, Box<dyn Error>> {
+    let Some(handle) = ddc_mutex() else {
+        return Ok(DdcLock(None));
+    };
+    let mut wait = unsafe { WaitForSingleObject(handle, 0) };
+    if wait == WAIT_TIMEOUT {
+        info!(
+            "DDC/CI is in use by another process; waiting up to {}s",
+            DDC_LOCK_TIMEOUT.as_secs()
+        );
+        wait = unsafe { WaitForSingleObject(handle, DDC_LOCK_TIMEOUT.as_millis() as u32) };
+    }
+    match wait {
+        WAIT_OBJECT_0 => Ok(DdcLock(Some(handle))),
+        WAIT_ABANDONED => {
+            warn!("A process exited while holding the DDC/CI lock; continuing");
+            Ok(DdcLock(Some(handle)))
+        }
+        WAIT_TIMEOUT => Err(DdcError::new("waiting for DDC/CI access", DdcErrorKind::Busy).into()),
+        _ => {
+            warn!(
+                "DDC/CI lock wait failed ({}); continuing unlocked",
+                io::Error::last_os_error()
+            );
+            Ok(DdcLock(None))
+        }
+    }
+}
+
+// ============================================================================
+// Physical monitor handle cache
+// ============================================================================
+
+/// An open physical monitor handle, owned by the handle cache.
+#[derive(Debug)]
+struct MonitorHandle {
+    handle: HANDLE,
+    description: String,
+    hmonitor: isize,
+    /// [`resolve_display_name`], looked up when the handle was opened.
+    name: String,
+}
+
+/// Physical monitor handles reused across calls, with the HMONITORs they
+/// were opened under (in enumeration order).
+struct HandleCache {
+    hmonitors: Vec<isize>,
+    monitors: Vec<MonitorHandle>,
+}
+
+// The raw handles are only used while the cache mutex is held.
+unsafe impl Send for HandleCache {}
+
+impl HandleCache {
+    fn destroy(self) {
+        for mh in &self.monitors {
+            unsafe {
+                let _ = DestroyPhysicalMonitor(mh.handle);
+            };
+        }
+    }
+}
+
+static HANDLE_CACHE: Mutex<Option<HandleCache>> = Mutex::new(None);
+
+/// Set by [`invalidate_handle_cache`]; checked when the next session opens.
+/// A flag rather than clearing the cache directly, so a window procedure
+/// never blocks behind a session that is mid-call.
+static CACHE_INVALIDATED: AtomicBool = AtomicBool::new(false);
+
+/// Drop the cached physical monitor handles; the next DDC call opens them
+/// again. Call on monitor arrival/removal (`WM_DEVICECHANGE`) and
+/// `WM_DISPLAYCHANGE`. A changed HMONITOR list is picked up without this;
+/// a monitor replugged under the same HMONITOR is not.
+pub fn invalidate_handle_cache() {
+    CACHE_INVALIDATED.store(true, Ordering::SeqCst);
+}
+
+/// Exclusive access to every physical monitor for a batch of DDC calls.
+///
+/// Opening one takes the cross-process DDC lock and reuses the cached
+/// handles, re-enumerating only when the display configuration changed.
+/// Indices match [`list_physical_monitors`]. Other processes wait while a
+/// session is open, so hold one for a batch rather than for the life of a
+/// UI page, and don't call this module's free functions on the same thread
+/// while it is open.
+pub struct DdcSession {
+    cache: MutexGuard<'static, Option<HandleCache>>,
+    _lock: DdcLock,
+}
+
+impl DdcSession {
+    pub fn open() -> Result<Self, Box<dyn Error>> {
+        let lock = lock_ddc()?;
+        let mut cache = HANDLE_CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+        let hmonitors = enumerate_hmonitors()?;
+        let invalidated = CACHE_INVALIDATED.swap(false, Ordering::SeqCst);
+        if invalidated || !cache.as_ref().is_some_and(|c| c.hmonitors == hmonitors) {
+            if let Some(stale) = cache.take() {
+                stale.destroy();
+            }
+            let monitors = open_physical_monitors(&hmonitors);
+            info!("DDC: opened {} physical monitor handle(s)", monitors.len());
+            *cache = Some(HandleCache {
+                hmonitors,
+                monitors,
+            });
+        }
+        Ok(Self { cache, _lock: lock })
+    }
+
+    fn handles(&self) -> &[MonitorHandle] {
+        self.cache.as_ref().map_or(&[], |c| c.monitors.as_slice())
+    }
+
+    fn handle(&self, index: usize) -> Result<&MonitorHandle, Box<dyn Error>> {
+        let handles = self.handles();
+        handles.get(index).ok_or_else(|| {
+            format!(
+                "Monitor index {} out of range (found {} monitors)",
+                index,
+                handles.len()
+            )
+            .into()
+        })
+    }
+
+    /// Pass `result` through, dropping the cache when it says the monitor
+    /// behind a handle is gone.
+    fn checked<T>(&self, result: Result<T, Box<dyn Error>>) -> Result<T, Box<dyn Error>> {
+        if let Err(e) = &result {
+            if error_kind(e.as_ref()) == Some(DdcErrorKind::MonitorGone) {
+                invalidate_handle_cache();
+            }
+        }
+        result
+    }
+
+    /// Number of physical monitors.
+    pub fn len(&self) -> usize {
+        self.handles().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handles().is_empty()
+    }
+
+    /// `(index, display name)` of every physical monitor.
+    pub fn monitors(&self) -> Vec<(usize, String)> {
+        self.handles()
+            .iter()
+            .enumerate()
+            .map(|(i, mh)| (i, mh.name.clone()))
+            .collect()
+    }
+
+    /// Index of the first monitor whose description matches `pattern`
+    /// (case-insensitive contains; empty = the first monitor). A field
+    /// expression picks the first handle of [`expression_matches`](Self::expression_matches).
+    ///
+    /// Falls back to the GDI device string, which has the real product name
+    /// for LG monitors that dxva2 lists as "Generic PnP Monitor".
+    pub fn find(&self, pattern: &str) -> Result<usize, Box<dyn Error>> {
+        let handles = self.handles();
+        if handles.is_empty() {
+            return Err("No physical monitors found via DDC/CI".into());
+        }
+        if pattern.is_empty() {
+            return Ok(0);
+        }
+        if crate::match_expr::is_expression(pattern) {
+            return Ok(self.expression_matches(pattern)?[0]);
+        }
+        let pat = pattern.to_uppercase();
+
+        if let Some(index) = handles
+            .iter()
+            .position(|mh| mh.description.to_uppercase().contains(&pat))
+        {
+            info!(
+                "DDC: matched monitor by description: {}",
+                handles[index].description
+            );
+            return Ok(index);
+        }
+        for (index, mh) in handles.iter().enumerate() {
+            if let Some(name) =
+                get_gdi_device_name(mh.hmonitor).filter(|n| n.to_uppercase().contains(&pat))
+            {
+                info!(
+                    "DDC: matched monitor by GDI device name: {} (DDC desc: {})",
+                    name, mh.description
+                );
+                return Ok(index);
+            }
+        }
+
+        let names: Vec<&str> = handles.iter().map(|mh| mh.name.as_str()).collect();
+        Err(format!(
+            "No DDC/CI monitor matched pattern '{}'. Found: {}",
+            pattern,
+            names.join(", ")
+        )
+        .into())
+    }
+
+    /// Indices of every monitor matching `pattern`; every monitor for an
+    /// empty pattern.
+    ///
+    /// A name pattern matches like [`find`](Self::find), by description or
+    /// GDI device name; an expression through
+    /// [`expression_matches`](Self::expression_matches).
+    pub fn find_all(&self, pattern: &str) -> Result<Vec<usize>, Box<dyn Error>> {
+        let handles = self.handles();
+        if handles.is_empty() {
+            return Err("No physical monitors found via DDC/CI".into());
+        }
+        if pattern.is_empty() {
+            return Ok((0..handles.len()).collect());
+        }
+        if crate::match_expr::is_expression(pattern) {
+            return self.expression_matches(pattern);
+        }
+        let pat = pattern.to_uppercase();
+        let matches: Vec<usize> = handles
+            .iter()
+            .enumerate()
+            .filter(|(_, mh)| {
+                mh.description.to_uppercase().contains(&pat)
+                    || get_gdi_device_name(mh.hmonitor)
+                        .is_some_and(|name| name.to_uppercase().contains(&pat))
+            })
+            .map(|(index, _)| index)
+            .collect();
+        if matches.is_empty() {
+            let names: Vec<&str> = handles.iter().map(|mh| mh.name.as_str()).collect();
+            return Err(format!(
+                "No DDC/CI monitor matched pattern '{}'. Found: {}",
+                pattern,
+                names.join(", ")
+            )
+            .into());
+        }
+        Ok(matches)
+    }
+
+    /// Handles of the monitors a [`crate::match_expr`] expression matches.
+    ///
+    /// dxva2 only knows monitor names, which two of the same model share,
+    /// so the expression is resolved with [`crate::find_matching_monitors`]
+    /// and each match is mapped to its handle by device key. Monitors the
+    /// expression excludes are never picked because they share a name; a
+    /// match whose handle can't be told apart from another monitor's
+    /// (mirrored displays) is an error.
+    fn expression_matches(&self, pattern: &str) -> Result<Vec<usize>, Box<dyn Error>> {
+        let matched = crate::find_matching_monitors(pattern)?;
+        if matched.is_empty() {
+            return Err(format!("No monitor matched '{}'", pattern).into());
+        }
+        let keys: Vec<String> = matched.into_iter().map(|m| m.device_key).collect();
+        let handle_keys: Vec<Vec<String>> = self
+            .handles()
+            .iter()
+            .map(|mh| hmonitor_device_keys(mh.hmonitor))
+            .collect();
+        handles_for_device_keys(&keys, &handle_keys)
+    }
+
+    pub fn get_vcp(&self, index: usize, vcp_code: u8) -> Result<VcpValue, Box<dyn Error>> {
+        let mh = self.handle(index)?;
+        self.checked(get_vcp_raw(mh.handle, vcp_code))
+    }
+
+    pub fn set_vcp(&self, index: usize, vcp_code: u8, value: u32) -> Result<(), Box<dyn Error>> {
+        let mh = self.handle(index)?;
+        self.checked(set_vcp_raw(mh.handle, vcp_code, value))
+    }
+
+    /// Raw MCCS capabilities string of the monitor at `index`.
+    pub fn capabilities(&self, index: usize) -> Result<String, Box<dyn Error>> {
+        let mh = self.handle(index)?;
+        self.checked(get_capabilities_raw(mh.handle))
+    }
+}
+
 // ============================================================================
 // Public API
 // ============================================================================
@@ -118,23 +828,30 @@ pub struct BrightnessInfo {
 
 /// Set DDC/CI brightness on all connected monitors.
 ///
-/// Enumerates all HMONITOR handles via `EnumDisplayMonitors`, resolves each
-/// to physical monitors, and calls `SetVCPFeature(0x10, value)`.
+/// Calls `SetVCPFeature(0x10, value)` on every physical monitor.
 ///
-/// Returns the number of physical monitors that were successfully set.
+/// Returns the number of physical monitors that were successfully set, or a
+/// [`DdcError`] naming the shared cause when every monitor failed.
 pub fn set_brightness_all(value: u32) -> Result<usize, Box<dyn Error>> {
-    let hmonitors = enumerate_hmonitors()?;
+    let session = DdcSession::open()?;
     let mut count = 0usize;
-
-    for hmon in hmonitors {
-        match set_brightness_for_hmonitor(hmon, value) {
-            Ok(n) => count += n,
-            Err(e) => warn!("DDC set brightness failed for a display: {}", e),
+    let mut failures = Vec::new();
+
+    for index in 0..session.len() {
+        match session.set_vcp(index, VCP_BRIGHTNESS, value) {
+            Ok(()) => count += 1,
+            Err(e) => {
+                warn!("{}", e);
+                failures.push(error_kind(e.as_ref()).unwrap_or(DdcErrorKind::Other));
+            }
         }
     }
 
     if count == 0 {
         warn!("No physical monitors responded to DDC brightness set");
+        if let Some(kind) = common_failure(&failures) {
+            return Err(DdcError::new(format!("SetVCPFeature(0x10, {})", value), kind).into());
+        }
     } else {
         info!("DDC brightness set to {} on {} monitor(s)", value, count);
     }
@@ -147,13 +864,17 @@ pub fn set_brightness_all(value: u32) -> Result<usize, Box<dyn Error>> {
 /// Returns a `BrightnessInfo` for each physical monitor that supports
 /// the brightness VCP code.
 pub fn get_brightness_all() -> Result<Vec<BrightnessInfo>, Box<dyn Error>> {
-    let hmonitors = enumerate_hmonitors()?;
+    let session = DdcSession::open()?;
     let mut results = Vec::new();
 
-    for hmon in hmonitors {
-        match get_brightness_for_hmonitor(hmon) {
-            Ok(mut infos) => results.append(&mut infos),
-            Err(e) => warn!("DDC get brightness failed for a display: {}", e),
+    for (index, name) in session.monitors() {
+        match session.get_vcp(index, VCP_BRIGHTNESS) {
+            Ok(v) => results.push(BrightnessInfo {
+                current: v.current,
+                max: v.max,
+                description: name,
+            }),
+            Err(e) => warn!("{}", e),
         }
     }
 
@@ -163,40 +884,11 @@ pub fn get_brightness_all() -> Result<Vec<BrightnessInfo>, Box<dyn Error>> {
 /// Set DDC/CI brightness on a specific physical monitor by index (0-based).
 /// Useful for multi-monitor setups where you only want to target one display.
 pub fn set_brightness_by_index(index: usize, value: u32) -> Result<(), Box<dyn Error>> {
-    let physicals = get_all_physical_monitors()?;
-    if index >= physicals.len() {
-        return Err(format!(
-            "Monitor index {} out of range (found {} monitors)",
-            index,
-            physicals.len()
-        )
-        .into());
-    }
-
-    let pm = &physicals[index];
-    let ok = unsafe { SetVCPFeature(pm.handle, VCP_BRIGHTNESS, value) };
-    if !ok.as_bool() {
-        let err = io::Error::last_os_error();
-        // Clean up all handles
-        for p in &physicals {
-            unsafe {
-                let _ = DestroyPhysicalMonitor(p.handle);
-            };
-        }
-        return Err(format!("SetVCPFeature(0x10, {}) failed: {}", value, err).into());
-    }
-
+    DdcSession::open()?.set_vcp(index, VCP_BRIGHTNESS, value)?;
     info!(
         "DDC brightness set to {} for monitor index {}",
         value, index
     );
-
-    // Clean up all handles
-    for p in &physicals {
-        unsafe {
-            let _ = DestroyPhysicalMonitor(p.handle);
-        };
-    }
     Ok(())
 }
 
@@ -237,103 +929,48 @@ pub struct MonitorCapabilityMap {
     pub capabilities: Vec<VcpCapability>,
 }
 
-/// Information about a physical monitor handle with its description.
-#[derive(Debug)]
-struct MonitorHandle {
-    handle: HANDLE,
-    description: String,
-    hmonitor: isize,
-}
-
-/// Read a VCP feature from a specific physical monitor identified by
-/// matching its description against `pattern` (case-insensitive contains).
+/// Read a VCP feature from the physical monitor [`DdcSession::find`] picks for
+/// `pattern`: by description (case-insensitive contains) or, for a match
+/// expression, by device key.
 ///
 /// If `pattern` is empty, uses the first physical monitor found.
 pub fn get_vcp_by_pattern(pattern: &str, vcp_code: u8) -> Result<VcpValue, Box<dyn Error>> {
-    let handle = find_monitor_by_pattern(pattern)?;
-    let result = get_vcp_raw(handle.handle, vcp_code);
-    unsafe {
-        let _ = DestroyPhysicalMonitor(handle.handle);
-    };
-    result
+    let session = DdcSession::open()?;
+    session.get_vcp(session.find(pattern)?, vcp_code)
 }
 
-/// Write a VCP feature to a specific physical monitor identified by
-/// matching its description against `pattern` (case-insensitive contains).
+/// Write a VCP feature to the physical monitor [`DdcSession::find`] picks for
+/// `pattern`: by description (case-insensitive contains) or, for a match
+/// expression, by device key.
 ///
 /// If `pattern` is empty, uses the first physical monitor found.
 pub fn set_vcp_by_pattern(pattern: &str, vcp_code: u8, value: u32) -> Result<(), Box<dyn Error>> {
-    let handle = find_monitor_by_pattern(pattern)?;
-    let result = set_vcp_raw(handle.handle, vcp_code, value);
-    unsafe {
-        let _ = DestroyPhysicalMonitor(handle.handle);
-    };
-    result
+    let session = DdcSession::open()?;
+    session.set_vcp(session.find(pattern)?, vcp_code, value)
 }
 
 /// Read a VCP feature from a specific physical monitor by 0-based index.
 ///
 /// The index corresponds to the order returned by `list_physical_monitors()`.
 pub fn get_vcp_by_index(index: usize, vcp_code: u8) -> Result<VcpValue, Box<dyn Error>> {
-    let handles = get_all_monitor_handles()?;
-    if index >= handles.len() {
-        for mh in &handles {
-            unsafe {
-                let _ = DestroyPhysicalMonitor(mh.handle);
-            };
-        }
-        return Err(format!(
-            "Monitor index {} out of range (found {} monitors)",
-            index,
-            handles.len()
-        )
-        .into());
-    }
-    let result = get_vcp_raw(handles[index].handle, vcp_code);
-    for mh in &handles {
-        unsafe {
-            let _ = DestroyPhysicalMonitor(mh.handle);
-        };
-    }
-    result
+    DdcSession::open()?.get_vcp(index, vcp_code)
 }
 
 /// Write a VCP feature to a specific physical monitor by 0-based index.
 ///
 /// The index corresponds to the order returned by `list_physical_monitors()`.
 pub fn set_vcp_by_index(index: usize, vcp_code: u8, value: u32) -> Result<(), Box<dyn Error>> {
-    let handles = get_all_monitor_handles()?;
-    if index >= handles.len() {
-        for mh in &handles {
-            unsafe {
-                let _ = DestroyPhysicalMonitor(mh.handle);
-            };
-        }
-        return Err(format!(
-            "Monitor index {} out of range (found {} monitors)",
-            index,
-            handles.len()
-        )
-        .into());
-    }
-    let result = set_vcp_raw(handles[index].handle, vcp_code, value);
-    for mh in &handles {
-        unsafe {
-            let _ = DestroyPhysicalMonitor(mh.handle);
-        };
-    }
-    result
+    DdcSession::open()?.set_vcp(index, vcp_code, value)
 }
 
 /// Read a VCP feature from all physical monitors, returning results
 /// paired with their descriptions.
 pub fn get_vcp_all(vcp_code: u8) -> Result<Vec<(String, VcpValue)>, Box<dyn Error>> {
-    let handles = get_all_monitor_handles()?;
+    let session = DdcSession::open()?;
     let mut results = Vec::new();
 
-    for mh in &handles {
-        let name = resolve_display_name(&mh.description, mh.hmonitor);
-        match get_vcp_raw(mh.handle, vcp_code) {
+    for (index, name) in session.monitors() {
+        match session.get_vcp(index, vcp_code) {
             Ok(val) => results.push((name, val)),
             Err(e) => warn!(
                 "VCP 0x{:02X} read failed for {}: {}",
@@ -344,14 +981,155 @@ pub fn get_vcp_all(vcp_code: u8) -> Result<Vec<(String, VcpValue)>, Box<dyn Erro
         }
     }
 
-    // Cleanup
-    for mh in &handles {
-        unsafe {
-            let _ = DestroyPhysicalMonitor(mh.handle);
-        };
+    Ok(results)
+}
+
+// ============================================================================
+// Power control
+// ============================================================================
+
+/// Monitor power state set by [`set_power_by_pattern`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerMode {
+    On,
+    Standby,
+    Off,
+}
+
+impl PowerMode {
+    /// Parse `on`, `standby` or `off` (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "on" => Some(Self::On),
+            "standby" => Some(Self::Standby),
+            "off" => Some(Self::Off),
+            _ => None,
+        }
     }
 
-    Ok(results)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::On => "on",
+            Self::Standby => "standby",
+            Self::Off => "off",
+        }
+    }
+
+    /// Value written to VCP 0xD6. `Off` is the DPM off state (4), not the
+    /// power-button off (5): the monitor keeps listening on DDC/CI, so
+    /// `On` can wake it again.
+    pub fn vcp_value(self) -> u32 {
+        match self {
+            Self::On => 1,
+            Self::Standby => 2,
+            Self::Off => 4,
+        }
+    }
+
+    /// `lParam` of `WM_SYSCOMMAND` / `SC_MONITORPOWER`.
+    fn monitor_power_lparam(self) -> isize {
+        match self {
+            Self::On => -1,
+            Self::Standby => 1,
+            Self::Off => 2,
+        }
+    }
+}
+
+/// Outcome of [`set_power_by_pattern`].
+#[derive(Debug, Clone, Default)]
+pub struct PowerResult {
+    /// Monitors that accepted the VCP 0xD6 write.
+    pub set: Vec<String>,
+    /// Matched monitors that rejected it, with the likely cause.
+    pub failed: Vec<(String, DdcErrorKind)>,
+    /// DDC/CI reached no monitor, so Windows was asked to change the power
+    /// state of every display (`SC_MONITORPOWER`).
+    pub broadcast: bool,
+}
+
+/// Set the power state of every physical monitor whose description or
+/// display name contains `pattern` (case-insensitive; empty = all), or
+/// that a [`crate::match_expr`] expression matches.
+///
+/// Uses VCP 0xD6. When DDC/CI is unavailable or every matched monitor
+/// rejects the write, falls back to broadcasting `SC_MONITORPOWER`, which
+/// applies to all displays and can't target one. Windows doesn't reliably
+/// wake displays that way; moving the mouse does.
+pub fn set_power_by_pattern(pattern: &str, mode: PowerMode) -> Result<PowerResult, Box<dyn Error>> {
+    // Taken first so a busy lock fails here instead of falling back to the
+    // broadcast below.
+    let _lock = lock_ddc()?;
+    let session = DdcSession::open()
+        .inspect_err(|e| warn!("DDC power: cannot enumerate monitors: {}", e))
+        .ok();
+    let handles = session.as_ref().map_or(&[][..], |s| s.handles());
+    let targets: Vec<usize> = match &session {
+        Some(s) if !handles.is_empty() && crate::match_expr::is_expression(pattern) => {
+            s.expression_matches(pattern)?
+        }
+        _ => {
+            let pat = pattern.to_uppercase();
+            (0..handles.len())
+                .filter(|&index| {
+                    let mh = &handles[index];
+                    pat.is_empty()
+                        || mh.description.to_uppercase().contains(&pat)
+                        || mh.name.to_uppercase().contains(&pat)
+                })
+                .collect()
+        }
+    };
+    let mut result = PowerResult::default();
+    let matched = targets.len();
+
+    for index in targets {
+        let mh = &handles[index];
+        let name = mh.name.clone();
+        let written = session.as_ref().map_or(Ok(()), |s| {
+            s.set_vcp(index, VCP_POWER_MODE, mode.vcp_value())
+        });
+        match written {
+            Ok(()) => result.set.push(name),
+            Err(e) => {
+                warn!("DDC power {} failed for {}: {}", mode.as_str(), name, e);
+                let kind = error_kind(e.as_ref()).unwrap_or(DdcErrorKind::Other);
+                result.failed.push((name, kind));
+            }
+        }
+    }
+
+    if matched == 0 && !handles.is_empty() {
+        return Err(format!("No physical monitor matching \"{}\"", pattern).into());
+    }
+    if result.set.is_empty() {
+        broadcast_monitor_power(mode)?;
+        result.broadcast = true;
+    }
+    info!(
+        "DDC power {}: {} set, {} failed, broadcast={}",
+        mode.as_str(),
+        result.set.len(),
+        result.failed.len(),
+        result.broadcast
+    );
+    Ok(result)
+}
+
+/// Ask Windows to change the power state of all displays.
+fn broadcast_monitor_power(mode: PowerMode) -> Result<(), Box<dyn Error>> {
+    // Posted rather than sent: a hung top-level window would block a
+    // broadcast SendMessage indefinitely.
+    unsafe {
+        PostMessageW(
+            HWND_BROADCAST,
+            WM_SYSCOMMAND,
+            WPARAM(SC_MONITORPOWER as usize),
+            LPARAM(mode.monitor_power_lparam()),
+        )
+    }
+    .map_err(|e| format!("SC_MONITORPOWER broadcast failed: {}", e))?;
+    Ok(())
 }
 
 /// List all physical monitors with their descriptions and HMONITOR index.
@@ -360,21 +1138,7 @@ pub fn get_vcp_all(vcp_code: u8) -> Result<Vec<(String, VcpValue)>, Box<dyn Erro
 /// If the DDC description is "Generic PnP Monitor", the GDI device string
 /// is used instead so the real product name is shown (e.g. "LG ULTRAGEAR").
 pub fn list_physical_monitors() -> Result<Vec<(usize, String)>, Box<dyn Error>> {
-    let handles = get_all_monitor_handles()?;
-    let result: Vec<(usize, String)> = handles
-        .iter()
-        .enumerate()
-        .map(|(i, mh)| (i, resolve_display_name(&mh.description, mh.hmonitor)))
-        .collect();
-
-    // Cleanup
-    for mh in &handles {
-        unsafe {
-            let _ = DestroyPhysicalMonitor(mh.handle);
-        };
-    }
-
-    Ok(result)
+    Ok(DdcSession::open()?.monitors())
 }
 
 /// Return known VCP codes with labels and a default risk marker.
@@ -386,6 +1150,7 @@ pub fn known_vcp_codes() -> &'static [(u8, &'static str, bool)] {
         (VCP_RED_GAIN, "Red Gain", false),
         (VCP_GREEN_GAIN, "Green Gain", false),
         (VCP_BLUE_GAIN, "Blue Gain", false),
+        (VCP_SHARPNESS, "Sharpness", false),
         (VCP_INPUT_SOURCE, "Input Source", true),
         (VCP_VOLUME, "Volume", false),
         (VCP_POWER_MODE, "Power Mode", true),
@@ -403,13 +1168,13 @@ pub fn known_vcp_codes() -> &'static [(u8, &'static str, bool)] {
 
 /// Probe each monitor for support of common VCP codes and return a capability map.
 pub fn probe_monitor_capabilities() -> Result<Vec<MonitorCapabilityMap>, Box<dyn Error>> {
-    let handles = get_all_monitor_handles()?;
+    let session = DdcSession::open()?;
     let mut maps = Vec::new();
 
-    for (idx, mh) in handles.iter().enumerate() {
+    for (idx, name) in session.monitors() {
         let mut capabilities = Vec::with_capacity(known_vcp_codes().len());
         for &(code, label, risky) in known_vcp_codes() {
-            match get_vcp_raw(mh.handle, code) {
+            match session.get_vcp(idx, code) {
                 Ok(v) => capabilities.push(VcpCapability {
                     code,
                     label,
@@ -433,18 +1198,104 @@ pub fn probe_monitor_capabilities() -> Result<Vec<MonitorCapabilityMap>, Box<dyn
 
         maps.push(MonitorCapabilityMap {
             index: idx,
-            name: resolve_display_name(&mh.description, mh.hmonitor),
+            name,
             capabilities,
         });
     }
 
-    for mh in &handles {
-        unsafe {
-            let _ = DestroyPhysicalMonitor(mh.handle);
-        };
+    Ok(maps)
+}
+
+// ============================================================================
+// Capabilities string
+// ============================================================================
+
+/// One `vcp(...)` entry from an MCCS capabilities string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilityVcpEntry {
+    /// The VCP code advertised by the monitor.
+    pub code: u8,
+    /// Discrete values advertised for the code (empty for continuous controls).
+    pub values: Vec<u8>,
+}
+
+/// Read the raw MCCS capabilities string from a monitor matching `pattern`.
+///
+/// If `pattern` is empty, uses the first physical monitor found.
+pub fn get_capabilities_by_pattern(pattern: &str) -> Result<String, Box<dyn Error>> {
+    let session = DdcSession::open()?;
+    session.capabilities(session.find(pattern)?)
+}
+
+/// Read the raw MCCS capabilities string from a monitor by 0-based index.
+pub fn get_capabilities_by_index(index: usize) -> Result<String, Box<dyn Error>> {
+    DdcSession::open()?.capabilities(index)
+}
+
+/// Parse the `vcp(...)` section of an MCCS capabilities string.
+///
+/// Example input: `(prot(monitor)type(lcd)vcp(02 10 12 14(05 06 08 0B) 87 E9(00 01 02)))`.
+/// Tokens that are not valid hex bytes are skipped.
+pub fn parse_capabilities_vcp(caps: &str) -> Vec<CapabilityVcpEntry> {
+    let lower = caps.to_ascii_lowercase();
+    let Some(start) = lower.find("vcp(") else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<CapabilityVcpEntry> = Vec::new();
+    let mut depth = 0usize;
+    let mut token = String::new();
+
+    for ch in caps[start + 4..].chars() {
+        match ch {
+            '(' => {
+                flush_capability_token(&mut token, depth, &mut entries);
+                depth += 1;
+            }
+            ')' => {
+                flush_capability_token(&mut token, depth, &mut entries);
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            }
+            c if c.is_ascii_whitespace() => flush_capability_token(&mut token, depth, &mut entries),
+            c => token.push(c),
+        }
     }
 
-    Ok(maps)
+    entries
+}
+
+fn flush_capability_token(token: &mut String, depth: usize, entries: &mut Vec<CapabilityVcpEntry>) {
+    if token.is_empty() {
+        return;
+    }
+    if let Ok(byte) = u8::from_str_radix(token, 16) {
+        if depth == 0 {
+            entries.push(CapabilityVcpEntry {
+                code: byte,
+                values: Vec::new(),
+            });
+        } else if let Some(last) = entries.last_mut() {
+            last.values.push(byte);
+        }
+    }
+    token.clear();
+}
+
+/// Guess the Response Time (overdrive) code from parsed capabilities.
+///
+/// UltraGear firmware advertises overdrive as a small enumerated control
+/// (Off/Normal/Fast/Faster) in the manufacturer-specific range. The first
+/// such code with 2–5 discrete values is returned. Other manufacturer
+/// controls look the same, so this is for display only — never write to
+/// the candidate without the user naming the code.
+pub fn response_time_candidate(entries: &[CapabilityVcpEntry]) -> Option<u8> {
+    entries
+        .iter()
+        .find(|e| e.code >= VCP_MANUFACTURER_SPECIFIC_START && (2..=5).contains(&e.values.len()))
+        .map(|e| e.code)
 }
 
 // ============================================================================
@@ -461,11 +1312,10 @@ fn get_vcp_raw(handle: HANDLE, vcp_code: u8) -> Result<VcpValue, Box<dyn Error>>
         GetVCPFeatureAndVCPFeatureReply(handle, vcp_code, &mut vcp_type, &mut current, &mut maximum)
     };
     if !ok.as_bool() {
-        let err = io::Error::last_os_error();
-        return Err(format!(
-            "GetVCPFeatureAndVCPFeatureReply(0x{:02X}) failed: {}",
-            vcp_code, err
-        )
+        return Err(DdcError::last_os_error(format!(
+            "GetVCPFeatureAndVCPFeatureReply(0x{:02X})",
+            vcp_code
+        ))
         .into());
     }
 
@@ -477,129 +1327,43 @@ fn get_vcp_raw(handle: HANDLE, vcp_code: u8) -> Result<VcpValue, Box<dyn Error>>
     })
 }
 
+/// Read the capabilities string from a raw physical monitor handle.  Does NOT destroy it.
+fn get_capabilities_raw(handle: HANDLE) -> Result<String, Box<dyn Error>> {
+    let mut length: u32 = 0;
+    let ok = unsafe { GetCapabilitiesStringLength(handle, &mut length) };
+    if !ok.as_bool() || length == 0 {
+        return Err(DdcError::last_os_error("GetCapabilitiesStringLength").into());
+    }
+
+    let mut buf = vec![0u8; length as usize];
+    let ok = unsafe { CapabilitiesRequestAndCapabilitiesReply(handle, buf.as_mut_ptr(), length) };
+    if !ok.as_bool() {
+        return Err(DdcError::last_os_error("CapabilitiesRequestAndCapabilitiesReply").into());
+    }
+
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    Ok(String::from_utf8_lossy(&buf[..end]).into_owned())
+}
+
 /// Write a VCP code to a raw physical monitor handle.  Does NOT destroy it.
 fn set_vcp_raw(handle: HANDLE, vcp_code: u8, value: u32) -> Result<(), Box<dyn Error>> {
     let ok = unsafe { SetVCPFeature(handle, vcp_code, value) };
     if !ok.as_bool() {
-        let err = io::Error::last_os_error();
-        return Err(format!(
-            "SetVCPFeature(0x{:02X}, {}) failed: {}",
-            vcp_code, value, err
-        )
+        return Err(DdcError::last_os_error(format!(
+            "SetVCPFeature(0x{:02X}, {})",
+            vcp_code, value
+        ))
         .into());
     }
     Ok(())
 }
 
-/// Find a single physical monitor whose description matches `pattern`.
-///
-/// Uses `EnumDisplayDevices` to get the GDI device string for each HMONITOR,
-/// then checks both the physical monitor description (from `dxva2`) and the
-/// GDI device string for a case-insensitive contains match.
-///
-/// This handles LG monitors that show up as "Generic PnP Monitor" in the
-/// physical monitor description but have "LG" in the GDI display adapter info.
-fn find_monitor_by_pattern(pattern: &str) -> Result<MonitorHandle, Box<dyn Error>> {
-    let handles = get_all_monitor_handles()?;
-
-    if handles.is_empty() {
-        return Err("No physical monitors found via DDC/CI".into());
-    }
-
-    // If pattern is empty, return the first monitor
-    if pattern.is_empty() {
-        // Destroy all except first
-        for mh in handles.iter().skip(1) {
-            unsafe {
-                let _ = DestroyPhysicalMonitor(mh.handle);
-            };
-        }
-        let first = handles.into_iter().next().unwrap();
-        return Ok(first);
-    }
-
-    let pat = pattern.to_uppercase();
-
-    // First pass: match by DDC physical monitor description
-    for mh in &handles {
-        if mh.description.to_uppercase().contains(&pat) {
-            let matched_handle = mh.handle;
-            let matched_desc = mh.description.clone();
-            let matched_hmon = mh.hmonitor;
-            // Destroy all OTHER handles
-            for other in &handles {
-                if !std::ptr::eq(other.handle, matched_handle) {
-                    unsafe {
-                        let _ = DestroyPhysicalMonitor(other.handle);
-                    };
-                }
-            }
-            info!("DDC: matched monitor by description: {}", matched_desc);
-            return Ok(MonitorHandle {
-                handle: matched_handle,
-                description: matched_desc,
-                hmonitor: matched_hmon,
-            });
-        }
-    }
-
-    // Second pass: match by GDI device name (EnumDisplayDevices)
-    // This catches monitors listed as "Generic PnP Monitor" by dxva2 but
-    // with the real name in the GDI device string.
-    for mh in &handles {
-        let gdi_name = get_gdi_device_name(mh.hmonitor);
-        if let Some(ref name) = gdi_name {
-            if name.to_uppercase().contains(&pat) {
-                let matched_handle = mh.handle;
-                let matched_hmon = mh.hmonitor;
-                // Prefer the GDI name so callers see the real product name
-                let resolved = name.clone();
-                for other in &handles {
-                    if !std::ptr::eq(other.handle, matched_handle) {
-                        unsafe {
-                            let _ = DestroyPhysicalMonitor(other.handle);
-                        };
-                    }
-                }
-                info!(
-                    "DDC: matched monitor by GDI device name: {} (DDC desc: {})",
-                    name, mh.description
-                );
-                return Ok(MonitorHandle {
-                    handle: matched_handle,
-                    description: resolved,
-                    hmonitor: matched_hmon,
-                });
-            }
-        }
-    }
-
-    // No match — clean up all handles
-    for mh in &handles {
-        unsafe {
-            let _ = DestroyPhysicalMonitor(mh.handle);
-        };
-    }
-
-    let names: Vec<String> = handles
-        .iter()
-        .map(|m| resolve_display_name(&m.description, m.hmonitor))
-        .collect();
-    Err(format!(
-        "No DDC/CI monitor matched pattern '{}'. Found: {}",
-        pattern,
-        names.join(", ")
-    )
-    .into())
-}
-
-/// Get all physical monitors with their handles and descriptions.
-/// Caller is responsible for calling `DestroyPhysicalMonitor` on each handle.
-fn get_all_monitor_handles() -> Result<Vec<MonitorHandle>, Box<dyn Error>> {
-    let hmonitors = enumerate_hmonitors()?;
+/// Open the physical monitors behind each of `hmonitors`, in order. The
+/// caller owns the handles (see [`HandleCache::destroy`]).
+fn open_physical_monitors(hmonitors: &[isize]) -> Vec<MonitorHandle> {
     let mut all = Vec::new();
 
-    for hmon in hmonitors {
+    for &hmon in hmonitors {
         let mut count: u32 = 0;
         let ok = unsafe { GetNumberOfPhysicalMonitorsFromHMONITOR(hmon, &mut count) };
         if !ok.as_bool() || count == 0 {
@@ -617,16 +1381,18 @@ fn get_all_monitor_handles() -> Result<Vec<MonitorHandle>, Box<dyn Error>> {
         let ok = unsafe { GetPhysicalMonitorsFromHMONITOR(hmon, count, monitors.as_mut_ptr()) };
         if ok.as_bool() {
             for pm in monitors {
+                let description = decode_description(&pm.description);
                 all.push(MonitorHandle {
                     handle: pm.handle,
-                    description: decode_description(&pm.description),
+                    name: resolve_display_name(&description, hmon),
+                    description,
                     hmonitor: hmon,
                 });
             }
         }
     }
 
-    Ok(all)
+    all
 }
 
 /// Try to get the GDI display device name for an HMONITOR.
@@ -689,8 +1455,108 @@ fn get_gdi_device_name(hmon: isize) -> Option<String> {
     }
 }
 
+/// Device keys (see [`crate::device_key_from_interface_path`]) of the
+/// active monitors shown on `hmon`: one, or several when mirrored.
+fn hmonitor_device_keys(hmon: isize) -> Vec<String> {
+    use windows::Win32::Graphics::Gdi::{
+        EnumDisplayDevicesA, GetMonitorInfoA, DISPLAY_DEVICEA, DISPLAY_DEVICE_ACTIVE,
+        MONITORINFOEXA,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::EDD_GET_DEVICE_INTERFACE_NAME;
+
+    let mut mi = MONITORINFOEXA::default();
+    mi.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXA>() as u32;
+    let ok = unsafe {
+        GetMonitorInfoA(
+            HMONITOR(hmon as *mut std::ffi::c_void),
+            &mut mi as *mut MONITORINFOEXA as *mut _,
+        )
+    };
+    if !ok.as_bool() {
+        return Vec::new();
+    }
+    let device_cstr: Vec<u8> = mi
+        .szDevice
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| c as u8)
+        .chain(std::iter::once(0))
+        .collect();
+    let device_pcstr = windows::core::PCSTR::from_raw(device_cstr.as_ptr());
+
+    let mut keys = Vec::new();
+    for index in 0.. {
+        let mut dd = DISPLAY_DEVICEA {
+            cb: std::mem::size_of::<DISPLAY_DEVICEA>() as u32,
+            ..Default::default()
+        };
+        let ok = unsafe {
+            EnumDisplayDevicesA(device_pcstr, index, &mut dd, EDD_GET_DEVICE_INTERFACE_NAME)
+        };
+        if !ok.as_bool() {
+            break;
+        }
+        if dd.StateFlags & DISPLAY_DEVICE_ACTIVE == 0 {
+            continue;
+        }
+        let path: String = dd
+            .DeviceID
+            .iter()
+            .take_while(|&&c| c != 0)
+            .map(|&c| c as u8 as char)
+            .collect();
+        let key = crate::device_key_from_interface_path(&path);
+        if !key.is_empty() {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+/// Handle indices for the monitors with `device_keys`, given the device
+/// keys behind each handle's HMONITOR. Matched monitors without a handle
+/// are skipped; a monitor whose HMONITOR also shows another one, or that
+/// several handles share, can't be told apart and is an error rather than
+/// a write to every candidate.
+fn handles_for_device_keys(
+    device_keys: &[String],
+    handle_keys: &[Vec<String>],
+) -> Result<Vec<usize>, Box<dyn Error>> {
+    let mut indices = Vec::new();
+    for key in device_keys {
+        let hits: Vec<usize> = handle_keys
+            .iter()
+            .enumerate()
+            .filter(|(_, keys)| keys.iter().any(|k| k.eq_ignore_ascii_case(key)))
+            .map(|(index, _)| index)
+            .collect();
+        match hits.as_slice() {
+            [] => {}
+            [index] if handle_keys[*index].len() == 1 => {
+                if !indices.contains(index) {
+                    indices.push(*index);
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "Can't tell which DDC/CI handle drives {} (mirrored displays?); \
+                     pick the monitor by index instead",
+                    key
+                )
+                .into())
+            }
+        }
+    }
+    if indices.is_empty() {
+        return Err("None of the matched monitors has a DDC/CI handle".into());
+    }
+    indices.sort_unstable();
+    Ok(indices)
+}
+
 /// Enumerate all HMONITOR handles on the system.
 fn enumerate_hmonitors() -> Result<Vec<isize>, Box<dyn Error>> {
+    ensure_supported()?;
     let mut handles: Vec<isize> = Vec::new();
 
     unsafe extern "system" fn callback(
@@ -720,137 +1586,6 @@ fn enumerate_hmonitors() -> Result<Vec<isize>, Box<dyn Error>> {
     Ok(handles)
 }
 
-/// Get all physical monitors across all HMONITOR handles.
-fn get_all_physical_monitors() -> Result<Vec<PhysicalMonitor>, Box<dyn Error>> {
-    let hmonitors = enumerate_hmonitors()?;
-    let mut all = Vec::new();
-
-    for hmon in hmonitors {
-        let mut count: u32 = 0;
-        let ok = unsafe { GetNumberOfPhysicalMonitorsFromHMONITOR(hmon, &mut count) };
-        if !ok.as_bool() || count == 0 {
-            continue;
-        }
-
-        let mut monitors = Vec::with_capacity(count as usize);
-        for _ in 0..count {
-            monitors.push(PhysicalMonitor {
-                handle: ptr::null_mut(),
-                description: [0u16; 128],
-            });
-        }
-
-        let ok = unsafe { GetPhysicalMonitorsFromHMONITOR(hmon, count, monitors.as_mut_ptr()) };
-        if ok.as_bool() {
-            all.append(&mut monitors);
-        }
-    }
-
-    Ok(all)
-}
-
-/// Set brightness for all physical monitors behind a given HMONITOR.
-fn set_brightness_for_hmonitor(hmon: isize, value: u32) -> Result<usize, Box<dyn Error>> {
-    let mut count: u32 = 0;
-    let ok = unsafe { GetNumberOfPhysicalMonitorsFromHMONITOR(hmon, &mut count) };
-    if !ok.as_bool() || count == 0 {
-        return Ok(0);
-    }
-
-    let mut monitors = Vec::with_capacity(count as usize);
-    for _ in 0..count {
-        monitors.push(PhysicalMonitor {
-            handle: ptr::null_mut(),
-            description: [0u16; 128],
-        });
-    }
-
-    let ok = unsafe { GetPhysicalMonitorsFromHMONITOR(hmon, count, monitors.as_mut_ptr()) };
-    if !ok.as_bool() {
-        return Err("GetPhysicalMonitorsFromHMONITOR failed".into());
-    }
-
-    let mut success_count = 0usize;
-    for pm in &monitors {
-        let ok = unsafe { SetVCPFeature(pm.handle, VCP_BRIGHTNESS, value) };
-        if ok.as_bool() {
-            success_count += 1;
-        } else {
-            let err = io::Error::last_os_error();
-            warn!("SetVCPFeature(0x10, {}) failed: {}", value, err);
-        }
-    }
-
-    // Cleanup
-    for pm in &monitors {
-        unsafe {
-            let _ = DestroyPhysicalMonitor(pm.handle);
-        };
-    }
-
-    Ok(success_count)
-}
-
-/// Get brightness for all physical monitors behind a given HMONITOR.
-fn get_brightness_for_hmonitor(hmon: isize) -> Result<Vec<BrightnessInfo>, Box<dyn Error>> {
-    let mut count: u32 = 0;
-    let ok = unsafe { GetNumberOfPhysicalMonitorsFromHMONITOR(hmon, &mut count) };
-    if !ok.as_bool() || count == 0 {
-        return Ok(Vec::new());
-    }
-
-    let mut monitors = Vec::with_capacity(count as usize);
-    for _ in 0..count {
-        monitors.push(PhysicalMonitor {
-            handle: ptr::null_mut(),
-            description: [0u16; 128],
-        });
-    }
-
-    let ok = unsafe { GetPhysicalMonitorsFromHMONITOR(hmon, count, monitors.as_mut_ptr()) };
-    if !ok.as_bool() {
-        return Err("GetPhysicalMonitorsFromHMONITOR failed".into());
-    }
-
-    let mut results = Vec::new();
-    for pm in &monitors {
-        let mut vcp_type: u32 = 0;
-        let mut current: u32 = 0;
-        let mut maximum: u32 = 0;
-
-        let ok = unsafe {
-            GetVCPFeatureAndVCPFeatureReply(
-                pm.handle,
-                VCP_BRIGHTNESS,
-                &mut vcp_type,
-                &mut current,
-                &mut maximum,
-            )
-        };
-        if ok.as_bool() {
-            let desc = decode_description(&pm.description);
-            let display = resolve_display_name(&desc, hmon);
-            results.push(BrightnessInfo {
-                current,
-                max: maximum,
-                description: display,
-            });
-        } else {
-            let err = io::Error::last_os_error();
-            warn!("GetVCPFeatureAndVCPFeatureReply(0x10) failed: {}", err);
-        }
-    }
-
-    // Cleanup
-    for pm in &monitors {
-        unsafe {
-            let _ = DestroyPhysicalMonitor(pm.handle);
-        };
-    }
-
-    Ok(results)
-}
-
 /// Return the best human-readable name for a physical monitor.
 ///
 /// If the DDC description is the unhelpful "Generic PnP Monitor", we fall
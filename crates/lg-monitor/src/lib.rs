@@ -3,27 +3,120 @@
 //! Enumerates connected monitors using `WmiMonitorID` and matches against
 //! a user-configured friendly name pattern (e.g. "LG ULTRAGEAR").
 //!
-//! The [`ddc`] module provides DDC/CI brightness reading and control via
-//! the Windows Monitor Configuration API (`dxva2.dll`).
+//! The [`ddc`] module (`ddc` feature, on by default) provides DDC/CI
+//! brightness reading and control via the Windows Monitor Configuration API
+//! (`dxva2.dll`).
+//!
+//! COM is initialized lazily on the first WMI query, and the apartment and
+//! each namespace connection are then reused for the rest of the thread's
+//! life, so commands that touch WMI more than once only connect once.
 
+#[cfg(feature = "ddc")]
 pub mod ddc;
+pub mod match_expr;
 
+use log::debug;
+use match_expr::{Field, MatchExpr};
 use regex::RegexBuilder;
 use serde::Deserialize;
+use std::cell::RefCell;
 use std::error::Error;
+use winreg::enums::{HKEY_LOCAL_MACHINE, KEY_READ};
+use winreg::RegKey;
 use wmi::{COMLibrary, WMIConnection};
 
+use windows::core::PCWSTR;
 use windows::Win32::Devices::Display::{
-    DisplayConfigGetDeviceInfo, GetDisplayConfigBufferSizes, QueryDisplayConfig,
-    DISPLAYCONFIG_DEVICE_INFO_GET_ADVANCED_COLOR_INFO, DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO,
-    DISPLAYCONFIG_MODE_INFO, DISPLAYCONFIG_PATH_INFO, QDC_ONLY_ACTIVE_PATHS,
+    DisplayConfigGetDeviceInfo, DisplayConfigSetDeviceInfo, GetDisplayConfigBufferSizes,
+    QueryDisplayConfig, DISPLAYCONFIG_ADAPTER_NAME, DISPLAYCONFIG_DEVICE_INFO_GET_ADAPTER_NAME,
+    DISPLAYCONFIG_DEVICE_INFO_GET_ADVANCED_COLOR_INFO,
+    DISPLAYCONFIG_DEVICE_INFO_GET_SDR_WHITE_LEVEL, DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME,
+    DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME, DISPLAYCONFIG_DEVICE_INFO_HEADER,
+    DISPLAYCONFIG_DEVICE_INFO_SET_ADVANCED_COLOR_STATE, DISPLAYCONFIG_DEVICE_INFO_TYPE,
+    DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO, DISPLAYCONFIG_MODE_INFO,
+    DISPLAYCONFIG_MODE_INFO_TYPE_SOURCE, DISPLAYCONFIG_PATH_INFO, DISPLAYCONFIG_SDR_WHITE_LEVEL,
+    DISPLAYCONFIG_SET_ADVANCED_COLOR_STATE, DISPLAYCONFIG_SOURCE_DEVICE_NAME,
+    DISPLAYCONFIG_TARGET_DEVICE_NAME, QDC_ALL_PATHS, QDC_ONLY_ACTIVE_PATHS,
+    QUERY_DISPLAY_CONFIG_FLAGS,
+};
+use windows::Win32::Foundation::{ERROR_INSUFFICIENT_BUFFER, ERROR_SUCCESS, HWND};
+use windows::Win32::Graphics::Gdi::{
+    ChangeDisplaySettingsExW, EnumDisplaySettingsW, CDS_UPDATEREGISTRY, DEVMODEW,
+    DISP_CHANGE_SUCCESSFUL, DM_DISPLAYFREQUENCY, DM_PELSHEIGHT, DM_PELSWIDTH,
+    ENUM_CURRENT_SETTINGS,
 };
-use windows::Win32::Foundation::{ERROR_INSUFFICIENT_BUFFER, ERROR_SUCCESS};
 
 const ADVANCED_COLOR_SUPPORTED_MASK: u32 = 0b0001;
 const ADVANCED_COLOR_ENABLED_MASK: u32 = 0b0010;
 const DISPLAY_CONFIG_QUERY_RETRIES: usize = 3;
 
+/// Lowest value of the Windows "SDR content brightness" slider, in nits.
+pub const SDR_WHITE_LEVEL_MIN_NITS: u32 = 80;
+/// Highest value of the Windows "SDR content brightness" slider, in nits.
+pub const SDR_WHITE_LEVEL_MAX_NITS: u32 = 480;
+
+/// Undocumented `DisplayConfigSetDeviceInfo` request behind the SDR content
+/// brightness slider (`0xFFFFFFEE`).
+const DISPLAYCONFIG_DEVICE_INFO_SET_SDR_WHITE_LEVEL: DISPLAYCONFIG_DEVICE_INFO_TYPE =
+    DISPLAYCONFIG_DEVICE_INFO_TYPE(-18);
+
+/// Packet for [`DISPLAYCONFIG_DEVICE_INFO_SET_SDR_WHITE_LEVEL`].
+#[repr(C)]
+struct DisplayConfigSetSdrWhiteLevel {
+    header: DISPLAYCONFIG_DEVICE_INFO_HEADER,
+    /// Same scale as `DISPLAYCONFIG_SDR_WHITE_LEVEL::SDRWhiteLevel`.
+    sdr_white_level: u32,
+    /// 1 to persist the value, as the Settings slider does on release.
+    final_value: u8,
+}
+
+const WMI_NAMESPACE: &str = "root\\wmi";
+const CIMV2_NAMESPACE: &str = "root\\cimv2";
+
+/// COM apartment and WMI connections opened on the current thread.
+#[derive(Default)]
+struct WmiSession {
+    com: Option<COMLibrary>,
+    connections: Vec<(&'static str, WMIConnection)>,
+}
+
+thread_local! {
+    static WMI_SESSION: RefCell<WmiSession> = RefCell::new(WmiSession::default());
+}
+
+/// Run `query` against `namespace`, initializing COM and connecting on first
+/// use. A failed query drops the cached connection so the next call
+/// reconnects (e.g. after the WMI service restarts).
+fn with_wmi<T>(
+    namespace: &'static str,
+    query: impl FnOnce(&WMIConnection) -> Result<T, Box<dyn Error>>,
+) -> Result<T, Box<dyn Error>> {
+    WMI_SESSION.with(|session| {
+        let mut session = session.borrow_mut();
+        let index = match session
+            .connections
+            .iter()
+            .position(|(ns, _)| *ns == namespace)
+        {
+            Some(index) => index,
+            None => {
+                let com = match session.com {
+                    Some(com) => com,
+                    None => *session.com.insert(COMLibrary::new()?),
+                };
+                let connection = WMIConnection::with_namespace_path(namespace, com)?;
+                session.connections.push((namespace, connection));
+                session.connections.len() - 1
+            }
+        };
+        let result = query(&session.connections[index].1);
+        if result.is_err() {
+            session.connections.swap_remove(index);
+        }
+        result
+    })
+}
+
 /// A matched monitor with its friendly name and device instance path.
 #[derive(Debug, Clone)]
 pub struct MatchedMonitor {
@@ -34,6 +127,23 @@ pub struct MatchedMonitor {
     pub product_code: String,
 }
 
+impl MatchedMonitor {
+    /// A [`match_expr`] expression that matches this monitor, for
+    /// `monitor_match` (`manufacturer:GSM AND product:5BBF`). Empty when
+    /// WMI reported neither field.
+    pub fn field_expression(&self) -> String {
+        [
+            (Field::Manufacturer, &self.manufacturer_id),
+            (Field::Product, &self.product_code),
+        ]
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(field, value)| format!("{}:{}", field.as_str(), value))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+    }
+}
+
 /// Pattern matching mode for monitor discovery.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MonitorMatchMode {
@@ -55,6 +165,68 @@ impl AdvancedColorState {
     }
 }
 
+/// Active displays and the built-in panel, for the reapply display-count
+/// conditions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DisplayTopology {
+    pub active_displays: usize,
+    /// `None` without a built-in panel, otherwise whether it is active
+    /// (off with the lid closed).
+    pub internal_panel: Option<bool>,
+}
+
+/// GPU/adapter details for one active display path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DisplayAdapterInfo {
+    /// Monitor instance ID in the same form as [`MatchedMonitor::device_key`].
+    pub monitor_instance: String,
+    /// GPU name as reported by `Win32_VideoController` (empty if unknown).
+    pub adapter_name: String,
+    /// PnP instance ID of the GPU driving the path (empty if unknown).
+    pub adapter_instance: String,
+    /// Display driver version (empty if unknown).
+    pub driver_version: String,
+    /// Physical connector, e.g. "HDMI" or "DisplayPort".
+    pub connector: String,
+}
+
+/// Where a monitor sits on the virtual desktop, in pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DesktopRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Resolution and refresh rate of a display.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DisplayMode {
+    pub width: u32,
+    pub height: u32,
+    pub refresh_hz: u32,
+}
+
+impl DisplayMode {
+    /// Parse `WIDTHxHEIGHT@HZ`, e.g. `2560x1440@240`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (size, hz) = s.trim().split_once('@')?;
+        let (width, height) = size.trim().split_once(['x', 'X'])?;
+        let mode = Self {
+            width: width.trim().parse().ok()?,
+            height: height.trim().parse().ok()?,
+            refresh_hz: hz.trim().trim_end_matches("Hz").trim().parse().ok()?,
+        };
+        (mode.width > 0 && mode.height > 0 && mode.refresh_hz > 0).then_some(mode)
+    }
+}
+
+impl std::fmt::Display for DisplayMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{} @ {} Hz", self.width, self.height, self.refresh_hz)
+    }
+}
+
 /// Raw WMI result from `WmiMonitorID`.
 #[derive(Deserialize, Debug)]
 #[serde(rename = "WmiMonitorID")]
@@ -67,6 +239,59 @@ struct WmiMonitorId {
     product_code_id: Option<Vec<u16>>,
 }
 
+/// Raw WMI result from `Win32_VideoController`.
+#[derive(Deserialize, Debug)]
+#[serde(rename = "Win32_VideoController")]
+#[serde(rename_all = "PascalCase")]
+struct WmiVideoController {
+    name: Option<String>,
+    driver_version: Option<String>,
+    #[serde(rename = "PNPDeviceID")]
+    pnp_device_id: Option<String>,
+}
+
+/// Raw WMI result from `Win32_OperatingSystem`, used only as a probe.
+#[derive(Deserialize, Debug)]
+#[serde(rename = "Win32_OperatingSystem")]
+#[serde(rename_all = "PascalCase")]
+struct WmiOperatingSystem {
+    #[allow(dead_code)]
+    caption: Option<String>,
+}
+
+/// Device key for a `WmiMonitorID` instance name: the PnP instance ID
+/// without the `_N` suffix WMI appends. The suffix is usually `_0`, but
+/// some drivers report `_1` or higher after a reinstall.
+pub fn device_key_from_instance_name(instance_name: &str) -> String {
+    let trimmed = instance_name.trim();
+    match trimmed.rsplit_once('_') {
+        Some((key, suffix))
+            if !key.is_empty()
+                && !suffix.is_empty()
+                && suffix.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            key.to_string()
+        }
+        _ => trimmed.to_string(),
+    }
+}
+
+/// Device key for a monitor device interface path, as delivered with
+/// `DBT_DEVICEARRIVAL` (`\\?\DISPLAY#GSM5BBF#5&abc&0&UID4352#{e6f07b5f-…}`):
+/// the PnP instance ID with the interface class GUID removed.
+pub fn device_key_from_interface_path(path: &str) -> String {
+    let trimmed = path.trim();
+    let trimmed = trimmed
+        .strip_prefix(r"\\?\")
+        .or_else(|| trimmed.strip_prefix(r"\\.\"))
+        .unwrap_or(trimmed);
+    let instance = match trimmed.rsplit_once('#') {
+        Some((instance, class)) if class.starts_with('{') => instance,
+        _ => trimmed,
+    };
+    instance.replace('#', "\\")
+}
+
 /// Find all connected monitors whose friendly name contains `pattern` (case-insensitive).
 pub fn find_matching_monitors(pattern: &str) -> Result<Vec<MatchedMonitor>, Box<dyn Error>> {
     find_matching_monitors_with_mode(pattern, MonitorMatchMode::Substring)
@@ -77,57 +302,157 @@ pub fn find_matching_monitors_regex(pattern: &str) -> Result<Vec<MatchedMonitor>
     find_matching_monitors_with_mode(pattern, MonitorMatchMode::Regex)
 }
 
-/// Find monitors by pattern using either substring or regex mode.
+/// Find monitors by pattern using either substring or regex mode. A
+/// [`match_expr`] expression is recognized in either mode and matched on
+/// the monitor's fields instead of the name alone.
 pub fn find_matching_monitors_with_mode(
     pattern: &str,
     mode: MonitorMatchMode,
 ) -> Result<Vec<MatchedMonitor>, Box<dyn Error>> {
-    let com = COMLibrary::new()?;
-    let wmi = WMIConnection::with_namespace_path("root\\wmi", com)?;
-
-    let monitors: Vec<WmiMonitorId> = wmi.raw_query(
-        "SELECT UserFriendlyName, InstanceName, SerialNumberID, ManufacturerName, ProductCodeID \
-         FROM WmiMonitorID",
-    )?;
+    let expr = MatchExpr::parse(pattern)
+        .map_err(|e| format!("invalid match expression \"{}\": {}", pattern, e))?;
+    let monitors: Vec<WmiMonitorId> = with_wmi(WMI_NAMESPACE, |wmi| {
+        Ok(wmi.raw_query(
+            "SELECT UserFriendlyName, InstanceName, SerialNumberID, ManufacturerName, \
+             ProductCodeID FROM WmiMonitorID",
+        )?)
+    })?;
     let mut matched = Vec::with_capacity(2);
 
-    let compiled_regex = if matches!(mode, MonitorMatchMode::Regex) && !pattern.is_empty() {
-        Some(
-            RegexBuilder::new(pattern)
-                .case_insensitive(true)
-                .build()
-                .map_err(|e| format!("invalid regex pattern \"{}\": {}", pattern, e))?,
-        )
-    } else {
-        None
-    };
+    let compiled_regex =
+        if matches!(mode, MonitorMatchMode::Regex) && expr.is_none() && !pattern.is_empty() {
+            Some(
+                RegexBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| format!("invalid regex pattern \"{}\": {}", pattern, e))?,
+            )
+        } else {
+            None
+        };
 
     for mon in monitors {
-        let name = decode_friendly_name(&mon.user_friendly_name);
-        if monitor_name_matches(&name, pattern, mode, compiled_regex.as_ref()) {
-            // Strip trailing "_0" from instance name to get the device key
-            let device_key = mon
-                .instance_name
-                .as_deref()
-                .unwrap_or("")
-                .trim_end_matches("_0")
-                .to_string();
-
-            if !device_key.is_empty() {
-                matched.push(MatchedMonitor {
-                    name,
-                    device_key,
-                    serial: decode_wmi_u16_text(&mon.serial_number_id),
-                    manufacturer_id: decode_wmi_u16_text(&mon.manufacturer_name),
-                    product_code: decode_wmi_u16_text(&mon.product_code_id),
-                });
-            }
+        let device_key = device_key_from_instance_name(mon.instance_name.as_deref().unwrap_or(""));
+        if device_key.is_empty() {
+            continue;
+        }
+        let candidate = matched_monitor(&mon, monitor_friendly_name(&mon), device_key);
+        let is_match = match &expr {
+            Some(expr) => expr.matches(&candidate),
+            None => monitor_name_matches(&candidate.name, pattern, mode, compiled_regex.as_ref()),
+        };
+        if is_match {
+            matched.push(candidate);
+        }
+    }
+
+    if matched.len() > 1 {
+        // Without the display paths every instance is kept, as before.
+        if let Ok(active) = active_monitor_instances() {
+            matched = prefer_active_instances(matched, &active);
         }
     }
 
     Ok(matched)
 }
 
+/// Check `pattern` the way monitor discovery parses it: `Err` with the
+/// parse error when it is a malformed [`match_expr`] expression.
+pub fn check_match_pattern(pattern: &str) -> Result<(), String> {
+    MatchExpr::parse(pattern)
+        .map(|_| ())
+        .map_err(|e| format!("invalid match expression: {}", e))
+}
+
+/// Drop the stale twins of a monitor listed more than once.
+///
+/// On hybrid graphics laptops (Optimus, MUX switches) a monitor gets one
+/// device instance per GPU it has been connected through, and `WmiMonitorID`
+/// can list all of them. Entries with the same manufacturer, product code
+/// and serial are the same physical monitor; when one of them is on an
+/// active display path (`active` holds those instance IDs), the others are
+/// dropped so the profile is associated on the instance actually in use.
+/// Monitors without a serial are never merged, since two of the same model
+/// could not be told apart.
+pub fn prefer_active_instances(
+    monitors: Vec<MatchedMonitor>,
+    active: &[String],
+) -> Vec<MatchedMonitor> {
+    let is_active =
+        |m: &MatchedMonitor| active.iter().any(|a| a.eq_ignore_ascii_case(&m.device_key));
+    let same_monitor = |a: &MatchedMonitor, b: &MatchedMonitor| {
+        !a.serial.is_empty()
+            && a.serial == b.serial
+            && a.manufacturer_id == b.manufacturer_id
+            && a.product_code == b.product_code
+    };
+    monitors
+        .iter()
+        .filter(|m| is_active(m) || !monitors.iter().any(|o| same_monitor(o, m) && is_active(o)))
+        .cloned()
+        .collect()
+}
+
+/// Instance IDs of the monitors on active display paths, in the same form
+/// as [`MatchedMonitor::device_key`].
+pub fn active_monitor_instances() -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(query_active_display_paths()?
+        .iter()
+        .filter_map(path_monitor_instance)
+        .collect())
+}
+
+/// Resolve a device key (see [`device_key_from_interface_path`]) to its
+/// `WmiMonitorID` instance, whatever its name. `Ok(None)` while WMI doesn't
+/// list the monitor yet, which can take a moment after it arrives.
+pub fn find_monitor_by_device_key(
+    device_key: &str,
+) -> Result<Option<MatchedMonitor>, Box<dyn Error>> {
+    let query = format!(
+        "SELECT UserFriendlyName, InstanceName, SerialNumberID, ManufacturerName, \
+         ProductCodeID FROM WmiMonitorID WHERE InstanceName LIKE '{}'",
+        wql_like_prefix(device_key)
+    );
+    let monitors: Vec<WmiMonitorId> = with_wmi(WMI_NAMESPACE, |wmi| Ok(wmi.raw_query(&query)?))?;
+    Ok(monitors.into_iter().find_map(|mon| {
+        let key = device_key_from_instance_name(mon.instance_name.as_deref().unwrap_or(""));
+        key.eq_ignore_ascii_case(device_key.trim()).then(|| {
+            let name = monitor_friendly_name(&mon);
+            matched_monitor(&mon, name, key)
+        })
+    }))
+}
+
+/// WQL `LIKE` pattern for values starting with `prefix`: backslashes and
+/// quotes escaped for the string literal, wildcard characters bracketed.
+fn wql_like_prefix(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 8);
+    for c in prefix.trim().chars() {
+        match c {
+            '\\' => pattern.push_str(r"\\"),
+            '\'' => pattern.push_str(r"\'"),
+            '%' | '_' | '[' => {
+                pattern.push('[');
+                pattern.push(c);
+                pattern.push(']');
+            }
+            _ => pattern.push(c),
+        }
+    }
+    pattern.push('%');
+    pattern
+}
+
+fn matched_monitor(mon: &WmiMonitorId, name: String, device_key: String) -> MatchedMonitor {
+    MatchedMonitor {
+        name,
+        device_key,
+        serial: decode_wmi_u16_text(&mon.serial_number_id),
+        manufacturer_id: decode_wmi_u16_text(&mon.manufacturer_name),
+        product_code: decode_wmi_u16_text(&mon.product_code_id),
+    }
+}
+
 fn monitor_name_matches(
     name: &str,
     pattern: &str,
@@ -180,19 +505,484 @@ pub fn is_any_display_hdr_enabled() -> Result<bool, Box<dyn Error>> {
     Ok(query_advanced_color_state()?.any_enabled())
 }
 
+/// Count the active displays and find out whether the built-in panel is on.
+pub fn query_display_topology() -> Result<DisplayTopology, Box<dyn Error>> {
+    let is_internal = |path: &DISPLAYCONFIG_PATH_INFO| {
+        is_internal_technology(path.targetInfo.outputTechnology.0 as u32)
+    };
+    let active = query_active_display_paths()?;
+    let internal_panel = if active.iter().any(is_internal) {
+        Some(true)
+    } else if query_display_config(QDC_ALL_PATHS)?
+        .0
+        .iter()
+        .any(is_internal)
+    {
+        Some(false)
+    } else {
+        None
+    };
+    Ok(DisplayTopology {
+        active_displays: active.len(),
+        internal_panel,
+    })
+}
+
+/// Query GPU name, driver version, and connector type for every active display path.
+///
+/// Connector and adapter path come from `QueryDisplayConfig`; the GPU name and
+/// driver version are joined in from `Win32_VideoController` by PnP device ID.
+pub fn query_display_adapters() -> Result<Vec<DisplayAdapterInfo>, Box<dyn Error>> {
+    let paths = query_active_display_paths()?;
+    let controllers = query_video_controllers().unwrap_or_default();
+    let mut adapters = Vec::with_capacity(paths.len());
+
+    for path in paths {
+        let Some(target) = path_target_name(&path) else {
+            continue;
+        };
+
+        let mut adapter = DISPLAYCONFIG_ADAPTER_NAME::default();
+        adapter.header.r#type = DISPLAYCONFIG_DEVICE_INFO_GET_ADAPTER_NAME;
+        adapter.header.size = std::mem::size_of::<DISPLAYCONFIG_ADAPTER_NAME>() as u32;
+        adapter.header.adapterId = path.targetInfo.adapterId;
+        let adapter_instance = if unsafe { DisplayConfigGetDeviceInfo(&mut adapter.header) }
+            == ERROR_SUCCESS.0 as i32
+        {
+            device_path_to_instance_id(&decode_wide(&adapter.adapterDevicePath))
+        } else {
+            String::new()
+        };
+
+        let controller = controllers.iter().find(|c| {
+            c.pnp_device_id.as_deref().is_some_and(|id| {
+                !adapter_instance.is_empty() && id.eq_ignore_ascii_case(&adapter_instance)
+            })
+        });
+
+        adapters.push(DisplayAdapterInfo {
+            monitor_instance: device_path_to_instance_id(&decode_wide(&target.monitorDevicePath)),
+            adapter_name: controller
+                .and_then(|c| c.name.clone())
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            adapter_instance,
+            driver_version: controller
+                .and_then(|c| c.driver_version.clone())
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            connector: output_technology_label(target.outputTechnology.0 as u32).to_string(),
+        });
+    }
+
+    Ok(adapters)
+}
+
+/// Find the adapter entry that drives `monitor` (matched by instance ID).
+pub fn adapter_for_monitor<'a>(
+    adapters: &'a [DisplayAdapterInfo],
+    monitor: &MatchedMonitor,
+) -> Option<&'a DisplayAdapterInfo> {
+    adapters
+        .iter()
+        .find(|a| a.monitor_instance.eq_ignore_ascii_case(&monitor.device_key))
+}
+
+/// Desktop area of the monitor with `device_key` (see
+/// [`MatchedMonitor::device_key`]), from its active display path's source
+/// mode. `Ok(None)` when the monitor isn't an active part of the desktop.
+pub fn monitor_desktop_rect(device_key: &str) -> Result<Option<DesktopRect>, Box<dyn Error>> {
+    let (paths, modes) = query_active_display_config()?;
+    for path in paths {
+        if !path_is_device(&path, device_key) {
+            continue;
+        }
+
+        let index = unsafe { path.sourceInfo.Anonymous.modeInfoIdx } as usize;
+        return Ok(modes
+            .get(index)
+            .filter(|mode| mode.infoType == DISPLAYCONFIG_MODE_INFO_TYPE_SOURCE)
+            .map(|mode| {
+                let source = unsafe { mode.Anonymous.sourceMode };
+                DesktopRect {
+                    x: source.position.x,
+                    y: source.position.y,
+                    width: source.width,
+                    height: source.height,
+                }
+            }));
+    }
+    Ok(None)
+}
+
+/// Current resolution and refresh rate of the monitor with `device_key`.
+/// `Ok(None)` when the monitor isn't an active part of the desktop.
+pub fn display_mode(device_key: &str) -> Result<Option<DisplayMode>, Box<dyn Error>> {
+    let Some(name) = active_path_for_device(device_key)?.and_then(|p| gdi_device_name(&p)) else {
+        return Ok(None);
+    };
+    let mut devmode = DEVMODEW {
+        dmSize: std::mem::size_of::<DEVMODEW>() as u16,
+        ..Default::default()
+    };
+    if !unsafe { EnumDisplaySettingsW(PCWSTR(name.as_ptr()), ENUM_CURRENT_SETTINGS, &mut devmode) }
+        .as_bool()
+    {
+        return Err(format!("reading the display mode of {} failed", decode_wide(&name)).into());
+    }
+    Ok(Some(DisplayMode {
+        width: devmode.dmPelsWidth,
+        height: devmode.dmPelsHeight,
+        refresh_hz: devmode.dmDisplayFrequency,
+    }))
+}
+
+/// Switch the monitor with `device_key` to `mode` and keep it in the
+/// registry. Returns `Ok(false)` when the monitor isn't active.
+///
+/// Display settings belong to the interactive session, so this has no
+/// effect from the session-0 service.
+pub fn set_display_mode(device_key: &str, mode: DisplayMode) -> Result<bool, Box<dyn Error>> {
+    let Some(name) = active_path_for_device(device_key)?.and_then(|p| gdi_device_name(&p)) else {
+        return Ok(false);
+    };
+    let devmode = DEVMODEW {
+        dmSize: std::mem::size_of::<DEVMODEW>() as u16,
+        dmFields: DM_PELSWIDTH | DM_PELSHEIGHT | DM_DISPLAYFREQUENCY,
+        dmPelsWidth: mode.width,
+        dmPelsHeight: mode.height,
+        dmDisplayFrequency: mode.refresh_hz,
+        ..Default::default()
+    };
+    let result = unsafe {
+        ChangeDisplaySettingsExW(
+            PCWSTR(name.as_ptr()),
+            Some(&devmode),
+            HWND::default(),
+            CDS_UPDATEREGISTRY,
+            None,
+        )
+    };
+    if result != DISP_CHANGE_SUCCESSFUL {
+        return Err(format!(
+            "switching {} to {} failed: DISP_CHANGE {}",
+            decode_wide(&name),
+            mode,
+            result.0
+        )
+        .into());
+    }
+    Ok(true)
+}
+
+/// GDI device name (`\\.\DISPLAY1`) of the source `path` is shown on,
+/// NUL-terminated.
+fn gdi_device_name(path: &DISPLAYCONFIG_PATH_INFO) -> Option<[u16; 32]> {
+    let mut source = DISPLAYCONFIG_SOURCE_DEVICE_NAME::default();
+    source.header.r#type = DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME;
+    source.header.size = std::mem::size_of::<DISPLAYCONFIG_SOURCE_DEVICE_NAME>() as u32;
+    source.header.adapterId = path.sourceInfo.adapterId;
+    source.header.id = path.sourceInfo.id;
+    let status = unsafe { DisplayConfigGetDeviceInfo(&mut source.header) };
+    (status == ERROR_SUCCESS.0 as i32).then_some(source.viewGdiDeviceName)
+}
+
+/// SDR content brightness of one monitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SdrWhiteLevel {
+    /// Current level in nits.
+    pub nits: u32,
+    /// Whether HDR is on for the monitor; the level only applies then.
+    pub hdr_enabled: bool,
+}
+
+/// Read the SDR content brightness of the monitor with `device_key`.
+/// `Ok(None)` when the monitor isn't an active part of the desktop.
+pub fn sdr_white_level(device_key: &str) -> Result<Option<SdrWhiteLevel>, Box<dyn Error>> {
+    let Some(path) = active_path_for_device(device_key)? else {
+        return Ok(None);
+    };
+    let mut level = DISPLAYCONFIG_SDR_WHITE_LEVEL::default();
+    level.header.r#type = DISPLAYCONFIG_DEVICE_INFO_GET_SDR_WHITE_LEVEL;
+    level.header.size = std::mem::size_of::<DISPLAYCONFIG_SDR_WHITE_LEVEL>() as u32;
+    level.header.adapterId = path.targetInfo.adapterId;
+    level.header.id = path.targetInfo.id;
+    let status = unsafe { DisplayConfigGetDeviceInfo(&mut level.header) };
+    if status != ERROR_SUCCESS.0 as i32 {
+        return Err(format!("reading the SDR white level failed: {}", status).into());
+    }
+    Ok(Some(SdrWhiteLevel {
+        nits: sdr_white_level_to_nits(level.SDRWhiteLevel),
+        hdr_enabled: path_hdr_enabled(&path),
+    }))
+}
+
+/// Set the SDR content brightness of the monitor with `device_key` to
+/// `nits` (see [`SDR_WHITE_LEVEL_MIN_NITS`]..=[`SDR_WHITE_LEVEL_MAX_NITS`]).
+/// Returns `Ok(false)` without changing anything when the monitor isn't
+/// active or has HDR off, since Windows ignores the level in SDR.
+pub fn set_sdr_white_level(device_key: &str, nits: u32) -> Result<bool, Box<dyn Error>> {
+    if !(SDR_WHITE_LEVEL_MIN_NITS..=SDR_WHITE_LEVEL_MAX_NITS).contains(&nits) {
+        return Err(format!(
+            "SDR white level {} nits is out of range — use {}..={}",
+            nits, SDR_WHITE_LEVEL_MIN_NITS, SDR_WHITE_LEVEL_MAX_NITS
+        )
+        .into());
+    }
+    let Some(path) = active_path_for_device(device_key)? else {
+        return Ok(false);
+    };
+    if !path_hdr_enabled(&path) {
+        return Ok(false);
+    }
+    let packet = DisplayConfigSetSdrWhiteLevel {
+        header: DISPLAYCONFIG_DEVICE_INFO_HEADER {
+            r#type: DISPLAYCONFIG_DEVICE_INFO_SET_SDR_WHITE_LEVEL,
+            size: std::mem::size_of::<DisplayConfigSetSdrWhiteLevel>() as u32,
+            adapterId: path.targetInfo.adapterId,
+            id: path.targetInfo.id,
+        },
+        sdr_white_level: nits_to_sdr_white_level(nits),
+        final_value: 1,
+    };
+    let status = unsafe { DisplayConfigSetDeviceInfo(&packet.header) };
+    if status != ERROR_SUCCESS.0 as i32 {
+        return Err(format!("setting the SDR white level failed: {}", status).into());
+    }
+    Ok(true)
+}
+
+/// Requested HDR state for [`set_hdr`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HdrSwitch {
+    On,
+    Off,
+    Toggle,
+}
+
+impl HdrSwitch {
+    /// Parse `on`, `off` or `toggle` (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "on" => Some(Self::On),
+            "off" => Some(Self::Off),
+            "toggle" => Some(Self::Toggle),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::On => "on",
+            Self::Off => "off",
+            Self::Toggle => "toggle",
+        }
+    }
+
+    /// HDR state to end up in, given the current one.
+    pub fn target(self, enabled: bool) -> bool {
+        match self {
+            Self::On => true,
+            Self::Off => false,
+            Self::Toggle => !enabled,
+        }
+    }
+}
+
+/// HDR state of a monitor before and after [`set_hdr`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HdrChange {
+    pub was_enabled: bool,
+    pub enabled: bool,
+}
+
+impl HdrChange {
+    pub fn changed(&self) -> bool {
+        self.was_enabled != self.enabled
+    }
+}
+
+/// Switch HDR (advanced color) on the monitor with `device_key`.
+/// `Ok(None)` when the monitor isn't active or doesn't support HDR; a
+/// monitor already in the requested state is left untouched.
+pub fn set_hdr(device_key: &str, switch: HdrSwitch) -> Result<Option<HdrChange>, Box<dyn Error>> {
+    let Some(path) = active_path_for_device(device_key)? else {
+        return Ok(None);
+    };
+    let Some(flags) = path_advanced_color_flags(&path) else {
+        return Ok(None);
+    };
+    if !advanced_color_supported(flags) {
+        return Ok(None);
+    }
+    let was_enabled = advanced_color_enabled(flags);
+    let enabled = switch.target(was_enabled);
+    if enabled != was_enabled {
+        let mut state = DISPLAYCONFIG_SET_ADVANCED_COLOR_STATE::default();
+        state.header.r#type = DISPLAYCONFIG_DEVICE_INFO_SET_ADVANCED_COLOR_STATE;
+        state.header.size = std::mem::size_of::<DISPLAYCONFIG_SET_ADVANCED_COLOR_STATE>() as u32;
+        state.header.adapterId = path.targetInfo.adapterId;
+        state.header.id = path.targetInfo.id;
+        // Bit 0 is `enableAdvancedColor`.
+        state.Anonymous.value = u32::from(enabled);
+        let status = unsafe { DisplayConfigSetDeviceInfo(&state.header) };
+        if status != ERROR_SUCCESS.0 as i32 {
+            return Err(format!("switching HDR {} failed: {}", switch.as_str(), status).into());
+        }
+    }
+    Ok(Some(HdrChange {
+        was_enabled,
+        enabled,
+    }))
+}
+
+/// Nits for a raw `SDRWhiteLevel` value (1000 = 80 nits).
+pub fn sdr_white_level_to_nits(raw: u32) -> u32 {
+    ((u64::from(raw) * 80 + 500) / 1000) as u32
+}
+
+/// Raw `SDRWhiteLevel` value for `nits`.
+pub fn nits_to_sdr_white_level(nits: u32) -> u32 {
+    ((u64::from(nits) * 1000 + 40) / 80) as u32
+}
+
+/// Active display path of the monitor with `device_key`.
+fn active_path_for_device(
+    device_key: &str,
+) -> Result<Option<DISPLAYCONFIG_PATH_INFO>, Box<dyn Error>> {
+    Ok(query_active_display_paths()?
+        .into_iter()
+        .find(|path| path_is_device(path, device_key)))
+}
+
+/// Whether `path` drives the monitor with `device_key`.
+fn path_is_device(path: &DISPLAYCONFIG_PATH_INFO, device_key: &str) -> bool {
+    path_monitor_instance(path).is_some_and(|key| key.eq_ignore_ascii_case(device_key.trim()))
+}
+
+/// Target (monitor) name of `path`, `None` if the query fails.
+fn path_target_name(path: &DISPLAYCONFIG_PATH_INFO) -> Option<DISPLAYCONFIG_TARGET_DEVICE_NAME> {
+    let mut target = DISPLAYCONFIG_TARGET_DEVICE_NAME::default();
+    target.header.r#type = DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME;
+    target.header.size = std::mem::size_of::<DISPLAYCONFIG_TARGET_DEVICE_NAME>() as u32;
+    target.header.adapterId = path.targetInfo.adapterId;
+    target.header.id = path.targetInfo.id;
+    (unsafe { DisplayConfigGetDeviceInfo(&mut target.header) } == ERROR_SUCCESS.0 as i32)
+        .then_some(target)
+}
+
+/// Instance ID of the monitor `path` drives.
+fn path_monitor_instance(path: &DISPLAYCONFIG_PATH_INFO) -> Option<String> {
+    path_target_name(path)
+        .map(|target| device_path_to_instance_id(&decode_wide(&target.monitorDevicePath)))
+}
+
+/// Whether advanced color (HDR) is on for `path`.
+fn path_hdr_enabled(path: &DISPLAYCONFIG_PATH_INFO) -> bool {
+    path_advanced_color_flags(path).is_some_and(advanced_color_enabled)
+}
+
+/// Raw advanced-color flags of `path`, `None` if the query fails.
+fn path_advanced_color_flags(path: &DISPLAYCONFIG_PATH_INFO) -> Option<u32> {
+    let mut info = DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO::default();
+    info.header.r#type = DISPLAYCONFIG_DEVICE_INFO_GET_ADVANCED_COLOR_INFO;
+    info.header.size = std::mem::size_of::<DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO>() as u32;
+    info.header.adapterId = path.targetInfo.adapterId;
+    info.header.id = path.targetInfo.id;
+    let status = unsafe { DisplayConfigGetDeviceInfo(&mut info.header) };
+    (status == ERROR_SUCCESS.0 as i32).then(|| unsafe { info.Anonymous.value })
+}
+
+fn query_video_controllers() -> Result<Vec<WmiVideoController>, Box<dyn Error>> {
+    with_wmi(CIMV2_NAMESPACE, |wmi| {
+        Ok(wmi.raw_query("SELECT Name, DriverVersion, PNPDeviceID FROM Win32_VideoController")?)
+    })
+}
+
+/// Whether WMI answers a trivial query. At boot the WMI service can report
+/// running before its providers accept queries; the service waits for this
+/// before its first apply.
+pub fn wmi_responds() -> Result<(), Box<dyn Error>> {
+    with_wmi(CIMV2_NAMESPACE, |wmi| {
+        let _: Vec<WmiOperatingSystem> =
+            wmi.raw_query("SELECT Caption FROM Win32_OperatingSystem")?;
+        Ok(())
+    })
+}
+
+/// Convert a device interface path (`\\?\DISPLAY#GSM5BBF#5&1a2b&0&UID4352#{guid}`)
+/// into a PnP instance ID (`DISPLAY\GSM5BBF\5&1a2b&0&UID4352`).
+fn device_path_to_instance_id(path: &str) -> String {
+    let trimmed = path.strip_prefix(r"\\?\").unwrap_or(path);
+    let without_guid = match trimmed.rfind("#{") {
+        Some(idx) => &trimmed[..idx],
+        None => trimmed,
+    };
+    without_guid.replace('#', "\\")
+}
+
+/// Human-readable label for a `DISPLAYCONFIG_VIDEO_OUTPUT_TECHNOLOGY` value.
+fn output_technology_label(tech: u32) -> &'static str {
+    match tech {
+        0 => "VGA",
+        1 => "S-Video",
+        2 => "Composite",
+        3 => "Component",
+        4 => "DVI",
+        5 => "HDMI",
+        6 => "LVDS",
+        8 => "D-Jpn",
+        9 => "SDI",
+        10 => "DisplayPort",
+        11 => "DisplayPort (embedded)",
+        12 => "UDI",
+        13 => "UDI (embedded)",
+        14 => "SDTV dongle",
+        15 => "Miracast",
+        16 => "Indirect (wired)",
+        17 => "Indirect (virtual)",
+        0x8000_0000 => "Internal",
+        _ => "Other",
+    }
+}
+
+/// True for the output technologies of a laptop's built-in panel.
+fn is_internal_technology(tech: u32) -> bool {
+    matches!(tech, 11 | 13 | 0x8000_0000)
+}
+
+fn decode_wide(buf: &[u16]) -> String {
+    let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+    String::from_utf16_lossy(&buf[..len])
+}
+
 fn query_active_display_paths() -> Result<Vec<DISPLAYCONFIG_PATH_INFO>, Box<dyn Error>> {
+    Ok(query_active_display_config()?.0)
+}
+
+/// Active display paths and the mode table their mode indexes point into.
+fn query_active_display_config(
+) -> Result<(Vec<DISPLAYCONFIG_PATH_INFO>, Vec<DISPLAYCONFIG_MODE_INFO>), Box<dyn Error>> {
+    query_display_config(QDC_ONLY_ACTIVE_PATHS)
+}
+
+/// Display paths selected by `flags` and their mode table.
+fn query_display_config(
+    flags: QUERY_DISPLAY_CONFIG_FLAGS,
+) -> Result<(Vec<DISPLAYCONFIG_PATH_INFO>, Vec<DISPLAYCONFIG_MODE_INFO>), Box<dyn Error>> {
     for _ in 0..DISPLAY_CONFIG_QUERY_RETRIES {
         let mut path_count = 0u32;
         let mut mode_count = 0u32;
 
-        let size_status = unsafe {
-            GetDisplayConfigBufferSizes(QDC_ONLY_ACTIVE_PATHS, &mut path_count, &mut mode_count)
-        };
+        let size_status =
+            unsafe { GetDisplayConfigBufferSizes(flags, &mut path_count, &mut mode_count) };
         if size_status != ERROR_SUCCESS {
             return Err(format!("GetDisplayConfigBufferSizes failed: {}", size_status.0).into());
         }
         if path_count == 0 {
-            return Ok(Vec::new());
+            return Ok((Vec::new(), Vec::new()));
         }
 
         let mut paths = vec![DISPLAYCONFIG_PATH_INFO::default(); path_count as usize];
@@ -202,7 +992,7 @@ fn query_active_display_paths() -> Result<Vec<DISPLAYCONFIG_PATH_INFO>, Box<dyn
 
         let query_status = unsafe {
             QueryDisplayConfig(
-                QDC_ONLY_ACTIVE_PATHS,
+                flags,
                 &mut queried_paths,
                 paths.as_mut_ptr(),
                 &mut queried_modes,
@@ -213,7 +1003,8 @@ fn query_active_display_paths() -> Result<Vec<DISPLAYCONFIG_PATH_INFO>, Box<dyn
 
         if query_status == ERROR_SUCCESS {
             paths.truncate(queried_paths as usize);
-            return Ok(paths);
+            modes.truncate(queried_modes as usize);
+            return Ok((paths, modes));
         }
 
         if query_status != ERROR_INSUFFICIENT_BUFFER {
@@ -232,23 +1023,104 @@ fn advanced_color_enabled(flags: u32) -> bool {
     flags & ADVANCED_COLOR_ENABLED_MASK != 0
 }
 
-/// Decode the `UserFriendlyName` field from WMI (array of u16 code points, null-terminated).
-fn decode_friendly_name(raw: &Option<Vec<u16>>) -> String {
-    match raw {
-        Some(chars) => chars
-            .iter()
-            .take_while(|&&c| c != 0)
-            .filter_map(|&c| char::from_u32(c as u32))
-            .collect(),
-        None => String::new(),
+/// Display name of a `WmiMonitorID` entry. Falls back to the display name
+/// descriptor of the monitor's EDID when WMI's name decodes to nothing.
+fn monitor_friendly_name(mon: &WmiMonitorId) -> String {
+    let instance = mon.instance_name.as_deref().unwrap_or("");
+    let name = decode_friendly_name(&mon.user_friendly_name);
+    if let Some(units) = &mon.user_friendly_name {
+        let end = units.iter().position(|&c| c == 0).unwrap_or(units.len());
+        if String::from_utf16(&units[..end]).ok().as_deref() != Some(name.as_str()) {
+            debug!(
+                "{}: UserFriendlyName {:04X?} decoded as \"{}\"",
+                instance, units, name
+            );
+        }
+    }
+    if !name.is_empty() {
+        return name;
+    }
+
+    let device_key = device_key_from_instance_name(instance);
+    match read_registry_edid(&device_key).and_then(|edid| edid_display_name(&edid)) {
+        Some(edid_name) => {
+            debug!(
+                "{}: no name from WMI, using EDID name \"{}\"",
+                instance, edid_name
+            );
+            edid_name
+        }
+        None => name,
     }
 }
 
-fn decode_wmi_u16_text(raw: &Option<Vec<u16>>) -> String {
-    let decoded = decode_friendly_name(raw);
+/// Decode the `UserFriendlyName` field from WMI (UTF-16 code units,
+/// null-terminated). Unpaired surrogates become U+FFFD rather than
+/// vanishing, control characters are dropped (EDID names end in a line
+/// feed) and surrounding whitespace is trimmed.
+fn decode_friendly_name(raw: &Option<Vec<u16>>) -> String {
+    let Some(units) = raw else {
+        return String::new();
+    };
+    let decoded: String = char::decode_utf16(units.iter().copied().take_while(|&c| c != 0))
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .filter(|c| !c.is_control())
+        .collect();
     decoded.trim().to_string()
 }
 
+/// The other `WmiMonitorID` text fields (serial, manufacturer, product
+/// code) are encoded the same way.
+fn decode_wmi_u16_text(raw: &Option<Vec<u16>>) -> String {
+    decode_friendly_name(raw)
+}
+
+const EDID_HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+/// Offsets of the four 18-byte descriptors in a base EDID block.
+const EDID_DESCRIPTOR_OFFSETS: [usize; 4] = [54, 72, 90, 108];
+const EDID_DESCRIPTOR_LEN: usize = 18;
+const EDID_DISPLAY_NAME_TAG: u8 = 0xFC;
+
+/// Base EDID block Windows keeps for a monitor instance, under
+/// `HKLM\SYSTEM\CurrentControlSet\Enum\<device key>\Device Parameters`.
+fn read_registry_edid(device_key: &str) -> Option<Vec<u8>> {
+    if device_key.is_empty() {
+        return None;
+    }
+    let path = format!(
+        r"SYSTEM\CurrentControlSet\Enum\{}\Device Parameters",
+        device_key
+    );
+    let key = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey_with_flags(path, KEY_READ)
+        .ok()?;
+    key.get_raw_value("EDID").ok().map(|value| value.bytes)
+}
+
+/// Text of the display name descriptor (tag `0xFC`) in a base EDID block:
+/// up to 13 bytes, ended by a line feed and padded with spaces. `None` for
+/// anything that isn't an EDID block or has no non-blank name.
+fn edid_display_name(edid: &[u8]) -> Option<String> {
+    if edid.get(..EDID_HEADER.len())? != EDID_HEADER {
+        return None;
+    }
+    EDID_DESCRIPTOR_OFFSETS.iter().find_map(|&offset| {
+        let descriptor = edid.get(offset..offset + EDID_DESCRIPTOR_LEN)?;
+        if descriptor[..3] != [0, 0, 0] || descriptor[3] != EDID_DISPLAY_NAME_TAG {
+            return None;
+        }
+        let text = &descriptor[5..];
+        let end = text.iter().position(|&b| b == b'\n').unwrap_or(text.len());
+        let name: String = text[..end]
+            .iter()
+            .map(|&b| char::from(b))
+            .filter(|c| !c.is_control())
+            .collect();
+        let name = name.trim();
+        (!name.is_empty()).then(|| name.to_string())
+    })
+}
+
 #[cfg(test)]
 #[path = "tests/monitor_tests.rs"]
 mod tests;
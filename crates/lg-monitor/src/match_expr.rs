@@ -0,0 +1,370 @@
+//! Match expressions for `monitor_match` and `--pattern`.
+//!
+//! `name~"ULTRAGEAR" AND NOT serial="123"`: comparisons combined with
+//! `NOT`, `AND` and `OR` (binding in that order, keywords in any case) and
+//! grouped with parentheses. A comparison is a field, an operator and a
+//! value:
+//!
+//! - fields: `name`, `model` (`GSM5BBF`), `serial`, `manufacturer`,
+//!   `product` and `devicekey`
+//! - `~` contains, `=` equals, both case-insensitive; `:` is contains for
+//!   `name` and equals for the other fields
+//! - values are bare words or double-quoted strings, in which `\"` and
+//!   `\\` are escapes
+//!
+//! Only a pattern that starts with a comparison (after any `NOT` or `(`) is
+//! an expression. Anything else is a plain name pattern, so existing
+//! configs keep matching the way they did.
+
+use crate::MatchedMonitor;
+use std::fmt;
+
+/// Monitor property a comparison tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Name,
+    /// EDID manufacturer ID and product code together (`GSM5BBF`).
+    Model,
+    Serial,
+    /// EDID manufacturer ID (`GSM`).
+    Manufacturer,
+    /// EDID product code (`5BBF`).
+    Product,
+    DeviceKey,
+}
+
+impl Field {
+    const ALL: [Field; 6] = [
+        Field::Name,
+        Field::Model,
+        Field::Serial,
+        Field::Manufacturer,
+        Field::Product,
+        Field::DeviceKey,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Field::Name => "name",
+            Field::Model => "model",
+            Field::Serial => "serial",
+            Field::Manufacturer => "manufacturer",
+            Field::Product => "product",
+            Field::DeviceKey => "devicekey",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|field| field.as_str().eq_ignore_ascii_case(s))
+    }
+
+    fn value(self, monitor: &MatchedMonitor) -> String {
+        match self {
+            Field::Name => monitor.name.clone(),
+            Field::Model => format!("{}{}", monitor.manufacturer_id, monitor.product_code),
+            Field::Serial => monitor.serial.clone(),
+            Field::Manufacturer => monitor.manufacturer_id.clone(),
+            Field::Product => monitor.product_code.clone(),
+            Field::DeviceKey => monitor.device_key.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Contains,
+    Equals,
+}
+
+/// A parsed match expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchExpr {
+    Compare { field: Field, op: Op, value: String },
+    Not(Box<MatchExpr>),
+    And(Box<MatchExpr>, Box<MatchExpr>),
+    Or(Box<MatchExpr>, Box<MatchExpr>),
+}
+
+/// Why an expression didn't parse, with the 1-based column it refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at column {}", self.message, self.column)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl MatchExpr {
+    /// Parse `pattern`. `Ok(None)` when it isn't an expression at all and
+    /// should be matched as a name.
+    pub fn parse(pattern: &str) -> Result<Option<Self>, ParseError> {
+        if !is_expression(pattern) {
+            return Ok(None);
+        }
+        let mut parser = Parser::new(pattern);
+        let expr = parser.or()?;
+        parser.skip_whitespace();
+        if let Some(c) = parser.peek() {
+            return Err(parser.error(if c == ')' {
+                "unmatched ')'".to_string()
+            } else {
+                format!(
+                    "unexpected '{}' (quote values that contain spaces, e.g. name~\"LG ULTRAGEAR\")",
+                    parser.rest_of_word()
+                )
+            }));
+        }
+        Ok(Some(expr))
+    }
+
+    pub fn matches(&self, monitor: &MatchedMonitor) -> bool {
+        match self {
+            MatchExpr::Compare { field, op, value } => {
+                let actual = field.value(monitor).to_uppercase();
+                let wanted = value.to_uppercase();
+                match op {
+                    Op::Contains => actual.contains(&wanted),
+                    Op::Equals => actual == wanted,
+                }
+            }
+            MatchExpr::Not(inner) => !inner.matches(monitor),
+            MatchExpr::And(a, b) => a.matches(monitor) && b.matches(monitor),
+            MatchExpr::Or(a, b) => a.matches(monitor) || b.matches(monitor),
+        }
+    }
+}
+
+/// Whether `pattern` is meant as an expression: after any leading `NOT`s
+/// and parentheses comes a known field and an operator, or any word
+/// followed by `~` or `=` (so a misspelled field is reported rather than
+/// matched as a name).
+pub fn is_expression(pattern: &str) -> bool {
+    let mut parser = Parser::new(pattern);
+    loop {
+        parser.skip_whitespace();
+        if parser.eat('(') {
+            continue;
+        }
+        if parser.keyword("NOT") {
+            continue;
+        }
+        break;
+    }
+    let word = parser.identifier();
+    if word.is_empty() {
+        return false;
+    }
+    parser.skip_whitespace();
+    match parser.peek() {
+        Some('~' | '=') => true,
+        Some(':') => Field::parse(&word).is_some(),
+        _ => false,
+    }
+}
+
+/// Recursive-descent parser over the pattern's characters.
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(pattern: &str) -> Self {
+        Self {
+            chars: pattern.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn error(&self, message: String) -> ParseError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: String) -> ParseError {
+        ParseError {
+            column: pos + 1,
+            message,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consume `word` (any case) if it comes next as a whole word.
+    fn keyword(&mut self, word: &str) -> bool {
+        self.skip_whitespace();
+        let end = self.pos + word.len();
+        let Some(candidate) = self.chars.get(self.pos..end) else {
+            return false;
+        };
+        let matches = candidate
+            .iter()
+            .zip(word.chars())
+            .all(|(a, b)| a.eq_ignore_ascii_case(&b));
+        let boundary = self
+            .chars
+            .get(end)
+            .is_none_or(|c| c.is_whitespace() || matches!(c, '(' | ')' | '"'));
+        if matches && boundary {
+            self.pos = end;
+        }
+        matches && boundary
+    }
+
+    fn identifier(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// The run of non-space characters at the cursor, for messages.
+    fn rest_of_word(&self) -> String {
+        self.chars[self.pos..]
+            .iter()
+            .take_while(|c| !c.is_whitespace())
+            .collect()
+    }
+
+    fn or(&mut self) -> Result<MatchExpr, ParseError> {
+        let mut left = self.and()?;
+        while self.keyword("OR") {
+            let right = self.and()?;
+            left = MatchExpr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<MatchExpr, ParseError> {
+        let mut left = self.not()?;
+        while self.keyword("AND") {
+            let right = self.not()?;
+            left = MatchExpr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<MatchExpr, ParseError> {
+        if self.keyword("NOT") {
+            return Ok(MatchExpr::Not(Box::new(self.not()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<MatchExpr, ParseError> {
+        self.skip_whitespace();
+        let open = self.pos;
+        if self.eat('(') {
+            let expr = self.or()?;
+            self.skip_whitespace();
+            if !self.eat(')') {
+                return Err(self.error_at(open, "unclosed '('".to_string()));
+            }
+            return Ok(expr);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<MatchExpr, ParseError> {
+        let start = self.pos;
+        let name = self.identifier();
+        if name.is_empty() {
+            return Err(self.error(match self.peek() {
+                Some(_) => format!("expected a field, found '{}'", self.rest_of_word()),
+                None => "expected a field, found the end of the pattern".to_string(),
+            }));
+        }
+        let Some(field) = Field::parse(&name) else {
+            let known: Vec<&str> = Field::ALL.iter().map(|f| f.as_str()).collect();
+            return Err(self.error_at(
+                start,
+                format!(
+                    "unknown field '{}' (expected one of {})",
+                    name,
+                    known.join(", ")
+                ),
+            ));
+        };
+        self.skip_whitespace();
+        let op = match self.peek() {
+            Some('~') => Op::Contains,
+            Some('=') => Op::Equals,
+            Some(':') if field == Field::Name => Op::Contains,
+            Some(':') => Op::Equals,
+            _ => {
+                return Err(self.error(format!("expected ~, = or : after '{}'", name)));
+            }
+        };
+        self.pos += 1;
+        self.skip_whitespace();
+        let value = self.value()?;
+        if value.is_empty() {
+            return Err(self.error(format!("expected a value after '{}'", name)));
+        }
+        Ok(MatchExpr::Compare { field, op, value })
+    }
+
+    fn value(&mut self) -> Result<String, ParseError> {
+        let open = self.pos;
+        if !self.eat('"') {
+            let start = self.pos;
+            while self
+                .peek()
+                .is_some_and(|c| !c.is_whitespace() && !matches!(c, '(' | ')' | '"'))
+            {
+                self.pos += 1;
+            }
+            return Ok(self.chars[start..self.pos].iter().collect());
+        }
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error_at(open, "unclosed '\"'".to_string())),
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(value);
+                }
+                Some('\\') if matches!(self.chars.get(self.pos + 1), Some('"' | '\\')) => {
+                    value.push(self.chars[self.pos + 1]);
+                    self.pos += 2;
+                }
+                Some(c) => {
+                    value.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/match_expr_tests.rs"]
+mod tests;
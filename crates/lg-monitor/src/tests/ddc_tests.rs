@@ -97,6 +97,7 @@ fn vcp_constants_are_correct() {
     assert_eq!(VCP_FACTORY_RESET, 0x04);
     assert_eq!(VCP_RESET_BRIGHTNESS_CONTRAST, 0x06);
     assert_eq!(VCP_RESET_COLOR, 0x0A);
+    assert_eq!(VCP_SHARPNESS, 0x87);
 }
 
 // ── VcpValue struct ──────────────────────────────────────────
@@ -127,6 +128,22 @@ fn vcp_value_clone() {
     assert_eq!(cloned.current, 0x0202);
 }
 
+// ── dxva2 availability ───────────────────────────────────────
+
+#[test]
+fn is_supported_matches_ensure_supported() {
+    assert_eq!(is_supported(), ensure_supported().is_ok());
+}
+
+#[test]
+fn unsupported_error_is_user_readable() {
+    assert!(DDC_UNSUPPORTED_MESSAGE.contains("not supported on this system"));
+    if !is_supported() {
+        let err = list_physical_monitors().unwrap_err();
+        assert_eq!(err.to_string(), DDC_UNSUPPORTED_MESSAGE);
+    }
+}
+
 // ── list_physical_monitors ───────────────────────────────────
 
 #[test]
@@ -135,6 +152,135 @@ fn list_physical_monitors_does_not_panic() {
     assert!(result.is_ok());
 }
 
+// ── Handle cache / DdcSession ────────────────────────────────
+
+#[test]
+fn session_lists_the_same_monitors_as_the_free_function() {
+    let listed = list_physical_monitors();
+    let Ok(session) = DdcSession::open() else {
+        assert!(listed.is_err());
+        return;
+    };
+    assert_eq!(session.len(), listed.unwrap().len());
+    assert_eq!(session.is_empty(), session.monitors().is_empty());
+    let err = session.get_vcp(session.len(), VCP_BRIGHTNESS).unwrap_err();
+    assert!(err.to_string().contains("out of range"), "{}", err);
+}
+
+#[test]
+fn invalidation_is_consumed_by_the_next_session() {
+    invalidate_handle_cache();
+    if DdcSession::open().is_ok() {
+        assert!(!CACHE_INVALIDATED.load(Ordering::SeqCst));
+    }
+}
+
+// ── Expression → handle mapping ──────────────────────────────
+
+fn panel(serial: &str, device_key: &str) -> crate::MatchedMonitor {
+    crate::MatchedMonitor {
+        name: "LG ULTRAGEAR".to_string(),
+        device_key: device_key.to_string(),
+        serial: serial.to_string(),
+        manufacturer_id: "GSM".to_string(),
+        product_code: "5BBF".to_string(),
+    }
+}
+
+fn keys(keys: &[&str]) -> Vec<String> {
+    keys.iter().map(|k| k.to_string()).collect()
+}
+
+#[test]
+fn expression_excludes_a_same_name_monitor_by_serial() {
+    let expr = crate::match_expr::MatchExpr::parse(r#"name~"ULTRAGEAR" AND NOT serial="123""#)
+        .expect("parse")
+        .expect("expression");
+    let monitors = [
+        panel("123", r"DISPLAY\GSM5BBF\5&abc&0&UID4352"),
+        panel("456", r"DISPLAY\GSM5BBF\5&abc&0&UID4353"),
+    ];
+    let matched: Vec<String> = monitors
+        .iter()
+        .filter(|m| expr.matches(m))
+        .map(|m| m.device_key.clone())
+        .collect();
+    let handle_keys = [
+        keys(&[r"DISPLAY\GSM5BBF\5&abc&0&UID4352"]),
+        keys(&[r"display\gsm5bbf\5&abc&0&uid4353"]),
+    ];
+    assert_eq!(
+        handles_for_device_keys(&matched, &handle_keys).unwrap(),
+        vec![1]
+    );
+}
+
+/// Handles `pattern` picks among `monitors`, whose handles are in the
+/// same order, the way [`DdcSession::expression_matches`] maps them.
+fn expression_handles(pattern: &str, monitors: &[crate::MatchedMonitor]) -> Vec<usize> {
+    let expr = crate::match_expr::MatchExpr::parse(pattern)
+        .expect("parse")
+        .expect("expression");
+    let matched: Vec<String> = monitors
+        .iter()
+        .filter(|m| expr.matches(m))
+        .map(|m| m.device_key.clone())
+        .collect();
+    let handle_keys: Vec<Vec<String>> = monitors
+        .iter()
+        .map(|m| vec![m.device_key.clone()])
+        .collect();
+    handles_for_device_keys(&matched, &handle_keys).expect("mapped")
+}
+
+#[test]
+fn serial_expression_picks_the_same_name_monitor_it_names() {
+    // Same model and name, only the serial differs; A's handle comes first.
+    let monitors = [
+        panel("A", r"DISPLAY\GSM5BBF\5&abc&0&UID4352"),
+        panel("B", r"DISPLAY\GSM5BBF\5&abc&0&UID4353"),
+    ];
+    assert_eq!(expression_handles("serial=B", &monitors), vec![1]);
+    assert_eq!(expression_handles("serial=A", &monitors), vec![0]);
+    // A field both share still matches both.
+    assert_eq!(expression_handles("product:5BBF", &monitors), vec![0, 1]);
+}
+
+#[test]
+fn excluded_monitor_sorting_first_is_never_the_target() {
+    // The excluded panel owns handle 0, so a name round-trip or a
+    // first-name-wins lookup would land on it.
+    let monitors = [
+        panel("123", r"DISPLAY\GSM5BBF\5&abc&0&UID4352"),
+        panel("456", r"DISPLAY\GSM5BBF\5&abc&0&UID4353"),
+        panel("789", r"DISPLAY\GSM5BBF\5&abc&0&UID4354"),
+    ];
+    let found = expression_handles(r#"name~"ULTRAGEAR" AND NOT serial="123""#, &monitors);
+    assert_eq!(found, vec![1, 2]);
+    // Single-monitor commands (`find`) take the first entry.
+    assert_eq!(found[0], 1);
+}
+
+#[test]
+fn device_keys_skip_monitors_without_a_handle() {
+    let handle_keys = [keys(&[r"DISPLAY\A\1"]), keys(&[r"DISPLAY\B\2"])];
+    let found = handles_for_device_keys(&keys(&[r"DISPLAY\B\2", r"DISPLAY\C\3"]), &handle_keys);
+    assert_eq!(found.unwrap(), vec![1]);
+    assert!(handles_for_device_keys(&keys(&[r"DISPLAY\C\3"]), &handle_keys).is_err());
+}
+
+#[test]
+fn device_keys_on_a_mirrored_hmonitor_are_ambiguous() {
+    // Two panels cloned onto one HMONITOR: dxva2 gives two handles that
+    // can't be told apart, so excluding one of them must not write both.
+    let mirrored = keys(&[r"DISPLAY\A\1", r"DISPLAY\A\2"]);
+    let handle_keys = [mirrored.clone(), mirrored];
+    assert!(handles_for_device_keys(&keys(&[r"DISPLAY\A\2"]), &handle_keys).is_err());
+    // One handle for a mirrored HMONITOR is just as ambiguous.
+    let handle_keys = [keys(&[r"DISPLAY\A\1", r"DISPLAY\A\2"])];
+    assert!(handles_for_device_keys(&keys(&[r"DISPLAY\A\1"]), &handle_keys).is_err());
+}
+
 // ── resolve_display_name ─────────────────────────────────────
 
 #[test]
@@ -181,6 +327,7 @@ fn known_vcp_codes_contains_core_entries() {
     assert!(known.iter().any(|(code, _, _)| *code == VCP_BRIGHTNESS));
     assert!(known.iter().any(|(code, _, _)| *code == VCP_COLOR_PRESET));
     assert!(known.iter().any(|(code, _, _)| *code == VCP_DISPLAY_MODE));
+    assert!(known.iter().any(|(code, _, _)| *code == VCP_SHARPNESS));
 }
 
 #[test]
@@ -203,3 +350,163 @@ fn probe_monitor_capabilities_does_not_panic() {
     let result = probe_monitor_capabilities();
     assert!(result.is_ok());
 }
+
+// ── Capabilities string ──────────────────────────────────────
+
+const SAMPLE_CAPS: &str = "(prot(monitor)type(lcd)model(27GN950)cmds(01 02 03 0C E3 F3)\
+vcp(02 04 10 12 14(05 08 0B) 60(0F 11 12) 87 D6(01 04) DF E9(00 01 02))mccs_ver(2.1))";
+
+#[test]
+fn parse_capabilities_vcp_reads_codes_and_values() {
+    let entries = parse_capabilities_vcp(SAMPLE_CAPS);
+    let codes: Vec<u8> = entries.iter().map(|e| e.code).collect();
+    assert_eq!(
+        codes,
+        vec![0x02, 0x04, 0x10, 0x12, 0x14, 0x60, 0x87, 0xD6, 0xDF, 0xE9]
+    );
+    let preset = entries.iter().find(|e| e.code == 0x14).unwrap();
+    assert_eq!(preset.values, vec![0x05, 0x08, 0x0B]);
+    let sharpness = entries.iter().find(|e| e.code == VCP_SHARPNESS).unwrap();
+    assert!(sharpness.values.is_empty());
+}
+
+#[test]
+fn parse_capabilities_vcp_ignores_cmds_section() {
+    let entries = parse_capabilities_vcp(SAMPLE_CAPS);
+    assert!(!entries.iter().any(|e| e.code == 0xE3));
+}
+
+#[test]
+fn parse_capabilities_vcp_without_vcp_section_is_empty() {
+    assert!(parse_capabilities_vcp("(prot(monitor)type(lcd))").is_empty());
+    assert!(parse_capabilities_vcp("").is_empty());
+}
+
+#[test]
+fn response_time_candidate_picks_enumerated_manufacturer_code() {
+    let entries = parse_capabilities_vcp(SAMPLE_CAPS);
+    assert_eq!(response_time_candidate(&entries), Some(0xE9));
+}
+
+#[test]
+fn response_time_candidate_none_without_manufacturer_codes() {
+    let entries = parse_capabilities_vcp("vcp(10 12 14(05 06))");
+    assert_eq!(response_time_candidate(&entries), None);
+}
+
+// ── Power control ────────────────────────────────────────────
+
+#[test]
+fn power_mode_parses_command_names() {
+    assert_eq!(PowerMode::parse("on"), Some(PowerMode::On));
+    assert_eq!(PowerMode::parse(" Standby "), Some(PowerMode::Standby));
+    assert_eq!(PowerMode::parse("OFF"), Some(PowerMode::Off));
+    assert_eq!(PowerMode::parse("suspend"), None);
+    for mode in [PowerMode::On, PowerMode::Standby, PowerMode::Off] {
+        assert_eq!(PowerMode::parse(mode.as_str()), Some(mode));
+    }
+}
+
+#[test]
+fn power_off_keeps_ddc_listening() {
+    assert_eq!(PowerMode::On.vcp_value(), 1);
+    assert_eq!(PowerMode::Standby.vcp_value(), 2);
+    assert_eq!(PowerMode::Off.vcp_value(), 4);
+}
+
+#[test]
+fn power_modes_map_to_monitor_power_lparam() {
+    assert_eq!(PowerMode::On.monitor_power_lparam(), -1);
+    assert_eq!(PowerMode::Standby.monitor_power_lparam(), 1);
+    assert_eq!(PowerMode::Off.monitor_power_lparam(), 2);
+}
+
+// ── Error classification ─────────────────────────────────────
+
+#[test]
+fn i2c_errors_mean_no_response() {
+    for code in [0xC026_2581u32, 0xC026_2582, 0xC026_2583] {
+        assert_eq!(
+            DdcErrorKind::from_os_error(code as i32),
+            DdcErrorKind::NoResponse
+        );
+    }
+    assert_eq!(DdcErrorKind::from_os_error(1460), DdcErrorKind::NoResponse);
+}
+
+#[test]
+fn checksum_and_unsupported_code_are_distinguished() {
+    assert_eq!(
+        DdcErrorKind::from_os_error(0xC026_258Bu32 as i32),
+        DdcErrorKind::BadReply
+    );
+    assert_eq!(
+        DdcErrorKind::from_os_error(0xC026_2584u32 as i32),
+        DdcErrorKind::CodeNotSupported
+    );
+    assert_eq!(DdcErrorKind::from_os_error(5), DdcErrorKind::Other);
+}
+
+#[test]
+fn ddc_error_message_gives_guidance_instead_of_code() {
+    let err = DdcError::new("SetVCPFeature(0x10, 50)", DdcErrorKind::NoResponse);
+    let message = err.to_string();
+    assert!(message.starts_with("SetVCPFeature(0x10, 50) failed: "));
+    assert!(message.contains("on-screen menu"));
+    assert!(message.contains("KVM"));
+    assert!(!message.contains("0xC026"));
+}
+
+#[test]
+fn error_kind_reads_boxed_ddc_errors() {
+    let boxed: Box<dyn Error> = DdcError::new("probe", DdcErrorKind::BadReply).into();
+    assert_eq!(error_kind(boxed.as_ref()), Some(DdcErrorKind::BadReply));
+    let unsupported: Box<dyn Error> = DDC_UNSUPPORTED_MESSAGE.into();
+    assert_eq!(
+        error_kind(unsupported.as_ref()),
+        Some(DdcErrorKind::Unsupported)
+    );
+    let other: Box<dyn Error> = "No physical monitors found via DDC/CI".into();
+    assert_eq!(error_kind(other.as_ref()), None);
+}
+
+#[test]
+fn common_failure_requires_every_monitor_to_agree() {
+    use DdcErrorKind::*;
+    assert_eq!(common_failure(&[]), None);
+    assert_eq!(common_failure(&[NoResponse, NoResponse]), Some(NoResponse));
+    assert_eq!(common_failure(&[NoResponse, BadReply]), Some(BadReply));
+    assert_eq!(common_failure(&[NoResponse, CodeNotSupported]), None);
+}
+
+#[test]
+fn busy_error_points_at_the_other_process() {
+    let message = DdcError::new("waiting for DDC/CI access", DdcErrorKind::Busy).to_string();
+    assert!(message.contains("another process"), "{}", message);
+    assert!(!DdcErrorKind::Busy.is_link_problem());
+}
+
+// ── Cross-process lock ───────────────────────────────────────
+
+#[test]
+fn ddc_lock_is_reentrant_and_queues_other_threads() {
+    if ddc_mutex().is_none() {
+        return; // Global\ objects need SeCreateGlobalPrivilege
+    }
+    let outer = lock_ddc().expect("lock");
+    drop(lock_ddc().expect("reentrant on the same thread"));
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let waiter = std::thread::spawn(move || {
+        let _lock = lock_ddc().expect("lock after release");
+        tx.send(()).unwrap();
+    });
+    assert!(
+        rx.recv_timeout(Duration::from_millis(200)).is_err(),
+        "second thread must wait"
+    );
+    drop(outer);
+    rx.recv_timeout(Duration::from_secs(5))
+        .expect("acquired once released");
+    waiter.join().unwrap();
+}
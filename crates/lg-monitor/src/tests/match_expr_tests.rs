@@ -0,0 +1,159 @@
+use super::*;
+
+fn ultragear(serial: &str) -> MatchedMonitor {
+    MatchedMonitor {
+        name: "LG ULTRAGEAR".to_string(),
+        device_key: r"DISPLAY\GSM5BBF\5&abc&0&UID4352".to_string(),
+        serial: serial.to_string(),
+        manufacturer_id: "GSM".to_string(),
+        product_code: "5BBF".to_string(),
+    }
+}
+
+fn parse(pattern: &str) -> MatchExpr {
+    MatchExpr::parse(pattern)
+        .expect("valid expression")
+        .expect("an expression, not a name")
+}
+
+fn parse_error(pattern: &str) -> ParseError {
+    MatchExpr::parse(pattern).expect_err("invalid expression")
+}
+
+// ── Recognition ──────────────────────────────────────────────────
+
+#[test]
+fn plain_names_are_not_expressions() {
+    for pattern in [
+        "",
+        "LG ULTRAGEAR",
+        "27GP950",
+        "LG: ULTRAGEAR",
+        "NOTEBOOK PANEL",
+        "(LG)",
+        "NOT",
+    ] {
+        assert_eq!(MatchExpr::parse(pattern), Ok(None), "{:?}", pattern);
+    }
+}
+
+#[test]
+fn expressions_are_recognized_after_not_and_parens() {
+    assert!(is_expression("name~ULTRAGEAR"));
+    assert!(is_expression("  ( NOT serial=1"));
+    assert!(is_expression("model:GSM5BBF"));
+    // A misspelled field with `~` or `=` is an expression with an error.
+    assert!(is_expression("nmae~ULTRAGEAR"));
+}
+
+// ── Parsing ──────────────────────────────────────────────────────
+
+#[test]
+fn not_binds_tighter_than_and_which_binds_tighter_than_or() {
+    let compare = |field, op, value: &str| MatchExpr::Compare {
+        field,
+        op,
+        value: value.to_string(),
+    };
+    assert_eq!(
+        parse("name~a or NOT serial=b AND model=c"),
+        MatchExpr::Or(
+            Box::new(compare(Field::Name, Op::Contains, "a")),
+            Box::new(MatchExpr::And(
+                Box::new(MatchExpr::Not(Box::new(compare(
+                    Field::Serial,
+                    Op::Equals,
+                    "b"
+                )))),
+                Box::new(compare(Field::Model, Op::Equals, "c")),
+            )),
+        )
+    );
+}
+
+#[test]
+fn quoted_values_keep_spaces_and_escapes() {
+    assert_eq!(
+        parse(r#"name ~ "LG \"ULTRA\" GEAR \\ 27""#),
+        MatchExpr::Compare {
+            field: Field::Name,
+            op: Op::Contains,
+            value: r#"LG "ULTRA" GEAR \ 27"#.to_string(),
+        }
+    );
+}
+
+#[test]
+fn colon_is_contains_for_names_only() {
+    assert!(matches!(
+        parse("name:GEAR"),
+        MatchExpr::Compare {
+            op: Op::Contains,
+            ..
+        }
+    ));
+    assert!(matches!(
+        parse("product:5BBF"),
+        MatchExpr::Compare { op: Op::Equals, .. }
+    ));
+}
+
+#[test]
+fn errors_point_at_the_problem() {
+    let e = parse_error("name~LG ULTRAGEAR");
+    assert_eq!(e.column, 9);
+    assert!(e.message.contains("quote values"), "{}", e);
+
+    let e = parse_error("nmae~ULTRAGEAR");
+    assert_eq!(e.column, 1);
+    assert!(e.message.contains("unknown field 'nmae'"), "{}", e);
+
+    let e = parse_error("name~a AND");
+    assert!(e.message.contains("end of the pattern"), "{}", e);
+
+    let e = parse_error("(name~a OR serial=1");
+    assert_eq!(e.column, 1);
+    assert!(e.message.contains("unclosed '('"), "{}", e);
+
+    let e = parse_error(r#"name~"ULTRA"#);
+    assert_eq!(e.column, 6);
+    assert!(e.message.contains("unclosed '\"'"), "{}", e);
+
+    let e = parse_error("name~a)");
+    assert!(e.message.contains("unmatched ')'"), "{}", e);
+
+    let e = parse_error("serial=");
+    assert!(e.message.contains("expected a value"), "{}", e);
+
+    assert_eq!(
+        parse_error("name~a AND serial 1").to_string(),
+        "expected ~, = or : after 'serial' at column 19"
+    );
+}
+
+// ── Matching ─────────────────────────────────────────────────────
+
+#[test]
+fn matching_is_case_insensitive() {
+    let monitor = ultragear("203NTXR1A234");
+    assert!(parse("name~ultragear").matches(&monitor));
+    assert!(!parse("name=ultra").matches(&monitor));
+    assert!(parse("name=\"lg ultragear\"").matches(&monitor));
+    assert!(parse("model=gsm5bbf AND manufacturer=GSM AND product=5bbf").matches(&monitor));
+    assert!(parse(r"devicekey~UID4352").matches(&monitor));
+}
+
+#[test]
+fn not_excludes_one_serial() {
+    let expr = parse(r#"name~"ULTRAGEAR" AND NOT serial="123""#);
+    assert!(expr.matches(&ultragear("456")));
+    assert!(!expr.matches(&ultragear("123")));
+}
+
+#[test]
+fn or_and_parentheses() {
+    let expr = parse("(serial=1 OR serial=2) AND model=GSM5BBF");
+    assert!(expr.matches(&ultragear("1")));
+    assert!(expr.matches(&ultragear("2")));
+    assert!(!expr.matches(&ultragear("3")));
+}
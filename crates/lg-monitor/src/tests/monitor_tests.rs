@@ -76,6 +76,82 @@ fn decode_friendly_name_all_nulls() {
     assert_eq!(result, "");
 }
 
+#[test]
+fn decode_friendly_name_keeps_surrogate_pairs() {
+    let input: Vec<u16> = "LG \u{1F5B5}".encode_utf16().collect();
+    assert_eq!(decode_friendly_name(&Some(input)), "LG \u{1F5B5}");
+}
+
+#[test]
+fn decode_friendly_name_replaces_unpaired_surrogates() {
+    let input = vec![76, 71, 0xD800, 32, 85, 0xDC00];
+    assert_eq!(decode_friendly_name(&Some(input)), "LG\u{FFFD} U\u{FFFD}");
+}
+
+#[test]
+fn decode_friendly_name_strips_edid_padding_and_controls() {
+    let input: Vec<u16> = "  LG ULTRAGEAR\n   ".encode_utf16().collect();
+    assert_eq!(decode_friendly_name(&Some(input)), "LG ULTRAGEAR");
+    assert_eq!(decode_friendly_name(&Some(vec![0x1B, 76, 71, 7])), "LG");
+}
+
+#[test]
+fn decode_friendly_name_blank_is_empty() {
+    assert_eq!(decode_friendly_name(&Some(vec![32, 10, 32, 0])), "");
+}
+
+// ── EDID display name ────────────────────────────────────────────
+
+/// Base EDID block with `name` (up to 13 bytes) in descriptor `slot`.
+fn edid_with_name(slot: usize, name: &[u8]) -> Vec<u8> {
+    let mut edid = vec![0u8; 128];
+    edid[..8].copy_from_slice(&EDID_HEADER);
+    // Every descriptor is a detailed timing unless it's the name.
+    for offset in EDID_DESCRIPTOR_OFFSETS {
+        edid[offset] = 0x3A;
+    }
+    let offset = EDID_DESCRIPTOR_OFFSETS[slot];
+    edid[offset..offset + 5].copy_from_slice(&[0, 0, 0, EDID_DISPLAY_NAME_TAG, 0]);
+    let text = &mut edid[offset + 5..offset + EDID_DESCRIPTOR_LEN];
+    text.fill(b' ');
+    text[..name.len()].copy_from_slice(name);
+    if name.len() < text.len() {
+        text[name.len()] = b'\n';
+    }
+    edid
+}
+
+#[test]
+fn edid_display_name_reads_the_name_descriptor() {
+    assert_eq!(
+        edid_display_name(&edid_with_name(1, b"LG ULTRAGEAR")).as_deref(),
+        Some("LG ULTRAGEAR")
+    );
+    assert_eq!(
+        edid_display_name(&edid_with_name(3, b"27GP950")).as_deref(),
+        Some("27GP950")
+    );
+    // 13 characters fill the descriptor with no line feed.
+    assert_eq!(
+        edid_display_name(&edid_with_name(0, b"ABCDEFGHIJKLM")).as_deref(),
+        Some("ABCDEFGHIJKLM")
+    );
+}
+
+#[test]
+fn edid_display_name_rejects_garbage() {
+    assert_eq!(edid_display_name(&[]), None);
+    assert_eq!(edid_display_name(&[0xFF; 128]), None);
+    // Truncated before the descriptor.
+    assert_eq!(edid_display_name(&edid_with_name(2, b"LG")[..95]), None);
+    // Blank name.
+    assert_eq!(edid_display_name(&edid_with_name(1, b"   ")), None);
+    // No name descriptor at all.
+    let mut edid = edid_with_name(1, b"LG");
+    edid[EDID_DESCRIPTOR_OFFSETS[1] + 3] = 0xFF;
+    assert_eq!(edid_display_name(&edid), None);
+}
+
 // ── MatchedMonitor struct ────────────────────────────────────────
 
 #[test]
@@ -111,6 +187,37 @@ fn matched_monitor_debug_format() {
     assert!(debug.contains("S"));
 }
 
+// ── Field expression ─────────────────────────────────────────────
+
+fn ultragear() -> MatchedMonitor {
+    MatchedMonitor {
+        name: "LG ULTRAGEAR".to_string(),
+        device_key: r"DISPLAY\GSM5BBF\5&abc&0&UID4352".to_string(),
+        serial: "203NTXR1A234".to_string(),
+        manufacturer_id: "GSM".to_string(),
+        product_code: "5BBF".to_string(),
+    }
+}
+
+#[test]
+fn field_expression_round_trips() {
+    let monitor = ultragear();
+    assert_eq!(
+        monitor.field_expression(),
+        "manufacturer:GSM AND product:5BBF"
+    );
+    assert!(MatchExpr::parse(&monitor.field_expression())
+        .unwrap()
+        .unwrap()
+        .matches(&monitor));
+    let unknown = MatchedMonitor {
+        manufacturer_id: String::new(),
+        product_code: String::new(),
+        ..monitor
+    };
+    assert_eq!(unknown.field_expression(), "");
+}
+
 // ── WmiMonitorId deserialization ─────────────────────────────────
 
 #[test]
@@ -265,3 +372,253 @@ fn advanced_color_state_any_enabled_depends_on_enabled_paths() {
     assert!(!off.any_enabled());
     assert!(on.any_enabled());
 }
+
+// ── SDR white level ──────────────────────────────────────────────
+
+#[test]
+fn sdr_white_level_scale_is_1000_per_80_nits() {
+    assert_eq!(sdr_white_level_to_nits(1000), 80);
+    assert_eq!(sdr_white_level_to_nits(6000), 480);
+    assert_eq!(nits_to_sdr_white_level(80), 1000);
+    assert_eq!(nits_to_sdr_white_level(240), 3000);
+}
+
+#[test]
+fn sdr_white_level_roundtrips_every_slider_value() {
+    for nits in SDR_WHITE_LEVEL_MIN_NITS..=SDR_WHITE_LEVEL_MAX_NITS {
+        assert_eq!(sdr_white_level_to_nits(nits_to_sdr_white_level(nits)), nits);
+    }
+}
+
+#[test]
+fn sdr_white_level_to_nits_rounds_to_nearest() {
+    assert_eq!(sdr_white_level_to_nits(1006), 80);
+    assert_eq!(sdr_white_level_to_nits(1007), 81);
+}
+
+// ── Display mode ─────────────────────────────────────────────────
+
+#[test]
+fn display_mode_parses_size_and_refresh() {
+    let expected = DisplayMode {
+        width: 2560,
+        height: 1440,
+        refresh_hz: 240,
+    };
+    assert_eq!(DisplayMode::parse("2560x1440@240"), Some(expected));
+    assert_eq!(DisplayMode::parse(" 2560 X 1440 @ 240Hz "), Some(expected));
+    assert_eq!(expected.to_string(), "2560x1440 @ 240 Hz");
+}
+
+#[test]
+fn display_mode_rejects_partial_or_zero_values() {
+    assert_eq!(DisplayMode::parse("2560x1440"), None);
+    assert_eq!(DisplayMode::parse("@240"), None);
+    assert_eq!(DisplayMode::parse("2560x0@240"), None);
+    assert_eq!(DisplayMode::parse("wide x tall @ fast"), None);
+}
+
+// ── HDR switch ───────────────────────────────────────────────────
+
+#[test]
+fn hdr_switch_parses_case_insensitively() {
+    assert_eq!(HdrSwitch::parse("ON"), Some(HdrSwitch::On));
+    assert_eq!(HdrSwitch::parse(" off "), Some(HdrSwitch::Off));
+    assert_eq!(HdrSwitch::parse("Toggle"), Some(HdrSwitch::Toggle));
+    assert_eq!(HdrSwitch::parse("auto"), None);
+}
+
+#[test]
+fn hdr_switch_target_follows_current_state_only_for_toggle() {
+    assert!(HdrSwitch::On.target(false));
+    assert!(HdrSwitch::On.target(true));
+    assert!(!HdrSwitch::Off.target(true));
+    assert!(!HdrSwitch::Off.target(false));
+    assert!(HdrSwitch::Toggle.target(false));
+    assert!(!HdrSwitch::Toggle.target(true));
+}
+
+// ── Display adapter info ─────────────────────────────────────────
+
+#[test]
+fn device_path_to_instance_id_matches_wmi_device_key() {
+    assert_eq!(
+        device_path_to_instance_id(
+            r"\\?\DISPLAY#GSM5BBF#5&1a2b3c&0&UID4352#{e6f07b5f-ee97-4a90-b076-33f57bf4eaa7}"
+        ),
+        r"DISPLAY\GSM5BBF\5&1a2b3c&0&UID4352"
+    );
+}
+
+#[test]
+fn device_path_to_instance_id_handles_pci_adapter_path() {
+    assert_eq!(
+        device_path_to_instance_id(
+            r"\\?\PCI#VEN_10DE&DEV_2684&SUBSYS_16F310DE&REV_A1#4&2b8260c3&0&0008#{5b45201d-f2f2-4f3b-85bb-30ff1f953599}"
+        ),
+        r"PCI\VEN_10DE&DEV_2684&SUBSYS_16F310DE&REV_A1\4&2b8260c3&0&0008"
+    );
+}
+
+#[test]
+fn device_path_to_instance_id_passes_plain_ids_through() {
+    assert_eq!(device_path_to_instance_id(""), "");
+    assert_eq!(
+        device_path_to_instance_id(r"DISPLAY\GSM5BBF\1"),
+        r"DISPLAY\GSM5BBF\1"
+    );
+}
+
+#[test]
+fn output_technology_label_known_connectors() {
+    assert_eq!(output_technology_label(5), "HDMI");
+    assert_eq!(output_technology_label(10), "DisplayPort");
+    assert_eq!(output_technology_label(0x8000_0000), "Internal");
+    assert_eq!(output_technology_label(0xFFFF_FFFF), "Other");
+}
+
+#[test]
+fn internal_technology_covers_built_in_panels_only() {
+    assert!(is_internal_technology(0x8000_0000));
+    assert!(is_internal_technology(11));
+    assert!(!is_internal_technology(10));
+    assert!(!is_internal_technology(5));
+}
+
+#[test]
+fn adapter_for_monitor_matches_case_insensitively() {
+    let adapters = vec![DisplayAdapterInfo {
+        monitor_instance: r"DISPLAY\GSM5BBF\5&ABC&0&UID1".into(),
+        adapter_name: "NVIDIA GeForce RTX 4080".into(),
+        adapter_instance: r"PCI\VEN_10DE&DEV_2704\4&1&0&0008".into(),
+        driver_version: "32.0.15.6094".into(),
+        connector: "DisplayPort".into(),
+    }];
+    let monitor = MatchedMonitor {
+        name: "LG ULTRAGEAR".into(),
+        device_key: r"DISPLAY\GSM5BBF\5&abc&0&UID1".into(),
+        serial: String::new(),
+        manufacturer_id: String::new(),
+        product_code: String::new(),
+    };
+    assert_eq!(
+        adapter_for_monitor(&adapters, &monitor).map(|a| a.connector.as_str()),
+        Some("DisplayPort")
+    );
+}
+
+// ── device_key_from_instance_name ────────────────────────────────
+
+#[test]
+fn device_key_strips_any_numeric_wmi_suffix() {
+    assert_eq!(
+        device_key_from_instance_name(r"DISPLAY\GSM5BBF\5&abc&0&UID4352_0"),
+        r"DISPLAY\GSM5BBF\5&abc&0&UID4352"
+    );
+    assert_eq!(
+        device_key_from_instance_name(r"DISPLAY\GSM5BBF\5&abc&0&UID4352_1 "),
+        r"DISPLAY\GSM5BBF\5&abc&0&UID4352"
+    );
+}
+
+#[test]
+fn device_key_keeps_non_numeric_tail() {
+    assert_eq!(
+        device_key_from_instance_name(r"DISPLAY\GSM5BBF\5&abc&0&UID4352"),
+        r"DISPLAY\GSM5BBF\5&abc&0&UID4352"
+    );
+    assert_eq!(
+        device_key_from_instance_name(r"DISPLAY\LG_X"),
+        r"DISPLAY\LG_X"
+    );
+    assert_eq!(device_key_from_instance_name(""), "");
+}
+
+// ── device_key_from_interface_path ───────────────────────────────
+
+#[test]
+fn interface_path_maps_to_the_instance_device_key() {
+    assert_eq!(
+        device_key_from_interface_path(
+            r"\\?\DISPLAY#GSM5BBF#5&abc&0&UID4352#{e6f07b5f-ee97-4a90-b076-33f57bf4eaa7}"
+        ),
+        r"DISPLAY\GSM5BBF\5&abc&0&UID4352"
+    );
+}
+
+#[test]
+fn interface_path_without_class_guid_is_kept() {
+    assert_eq!(
+        device_key_from_interface_path(r"\\?\DISPLAY#GSM5BBF#5&abc&0&UID4352"),
+        r"DISPLAY\GSM5BBF\5&abc&0&UID4352"
+    );
+    assert_eq!(device_key_from_interface_path(""), "");
+}
+
+// ── wql_like_prefix ──────────────────────────────────────────────
+
+#[test]
+fn wql_like_prefix_escapes_backslashes_and_ends_with_a_wildcard() {
+    assert_eq!(
+        wql_like_prefix(r"DISPLAY\GSM5BBF\5&abc&0&UID4352"),
+        r"DISPLAY\\GSM5BBF\\5&abc&0&UID4352%"
+    );
+}
+
+#[test]
+fn wql_like_prefix_brackets_wildcards_and_escapes_quotes() {
+    assert_eq!(wql_like_prefix("A_B%C[D'E"), r"A[_]B[%]C[[]D\'E%");
+}
+
+// ── Hybrid graphics ──────────────────────────────────────────────
+
+fn lg_instance(device_key: &str, serial: &str) -> MatchedMonitor {
+    MatchedMonitor {
+        name: "LG ULTRAGEAR".into(),
+        device_key: device_key.into(),
+        serial: serial.into(),
+        manufacturer_id: "GSM".into(),
+        product_code: "5BBF".into(),
+    }
+}
+
+fn keys(monitors: &[MatchedMonitor]) -> Vec<&str> {
+    monitors.iter().map(|m| m.device_key.as_str()).collect()
+}
+
+#[test]
+fn stale_instance_on_other_gpu_is_dropped() {
+    let monitors = vec![
+        lg_instance(r"DISPLAY\GSM5BBF\5&a&0&UID4352", "203NTAB1C234"),
+        lg_instance(r"DISPLAY\GSM5BBF\4&e&0&UID8388688", "203NTAB1C234"),
+    ];
+    let active = vec![r"display\gsm5bbf\4&e&0&uid8388688".to_string()];
+    assert_eq!(
+        keys(&prefer_active_instances(monitors, &active)),
+        vec![r"DISPLAY\GSM5BBF\4&e&0&UID8388688"]
+    );
+}
+
+#[test]
+fn instances_are_kept_when_none_is_active() {
+    let monitors = vec![
+        lg_instance(r"DISPLAY\GSM5BBF\5&a&0&UID4352", "203NTAB1C234"),
+        lg_instance(r"DISPLAY\GSM5BBF\4&e&0&UID8388688", "203NTAB1C234"),
+    ];
+    assert_eq!(prefer_active_instances(monitors, &[]).len(), 2);
+}
+
+#[test]
+fn different_or_missing_serials_are_not_merged() {
+    let monitors = vec![
+        lg_instance(r"DISPLAY\GSM5BBF\5&a&0&UID4352", "203NTAB1C234"),
+        lg_instance(r"DISPLAY\GSM5BBF\5&a&0&UID4353", "203NTAB9Z999"),
+        lg_instance(r"DISPLAY\GSM5BBF\5&a&0&UID4354", ""),
+        lg_instance(r"DISPLAY\GSM5BBF\5&a&0&UID4355", ""),
+    ];
+    let active = vec![
+        r"DISPLAY\GSM5BBF\5&a&0&UID4352".to_string(),
+        r"DISPLAY\GSM5BBF\5&a&0&UID4354".to_string(),
+    ];
+    assert_eq!(prefer_active_instances(monitors, &active).len(), 4);
+}
@@ -5,25 +5,103 @@
 //! (no PowerShell, no schtasks).
 //!
 //! In Session 0 (service context running as SYSTEM), the WinRT
-//! notification infrastructure is unavailable — the attempt will
-//! fail gracefully and the event is logged to the Windows Event Log.
+//! notification infrastructure is unavailable, so toasts are handed to a
+//! helper process started in the active console session (see
+//! [`SESSION_TOAST_COMMAND`]). When nobody is logged on at the console the
+//! toast is dropped and the event is still logged to the Windows Event Log.
+//!
+//! Reapply toasts respect Focus Assist (do-not-disturb): see [`QuietHours`].
+//!
+//! Where toasts are disabled or blocked, [`show_osd`] flashes a small
+//! on-screen badge instead (user session only).
 //!
 //! All functions take raw parameters (no Config dependency) so this crate
 //! can be used independently.
 
+mod osd;
+mod session;
+
+pub use osd::{show_osd, show_reapply_osd, OsdPosition, REAPPLY_BADGE_TEXT};
+pub use session::{remove_leftover_toast_tasks, SESSION_TOAST_COMMAND};
+
 use log::{info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
 use windows::core::HSTRING;
 use windows::Data::Xml::Dom::XmlDocument;
+use windows::Win32::UI::Shell::{
+    SHQueryUserNotificationState, QUERY_USER_NOTIFICATION_STATE, QUNS_BUSY, QUNS_PRESENTATION_MODE,
+    QUNS_QUIET_TIME, QUNS_RUNNING_D3D_FULL_SCREEN,
+};
 use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
 
+/// WNF state holding the active Focus Assist profile (0 = off,
+/// 1 = priority only, 2 = alarms only). Undocumented but stable since 1803.
+const WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED: u64 = 0x0D83_063E_A3BF_1C75;
+
+/// How often a queued toast checks whether quiet hours have ended.
+const QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+// FFI for the WNF query (ntdll, not covered by the windows crate)
+#[link(name = "ntdll")]
+extern "system" {
+    fn NtQueryWnfStateData(
+        state_name: *const u64,
+        type_id: *const core::ffi::c_void,
+        explicit_scope: *const core::ffi::c_void,
+        change_stamp: *mut u32,
+        buffer: *mut core::ffi::c_void,
+        buffer_size: *mut u32,
+    ) -> i32;
+}
+
+/// Latest toast waiting for quiet hours to end (only the newest is kept).
+static QUEUED: Mutex<Option<(String, String)>> = Mutex::new(None);
+
+/// Set while a waiter thread is holding [`QUEUED`].
+static QUEUE_WAITER: AtomicBool = AtomicBool::new(false);
+
+/// What to do with a reapply toast while Focus Assist is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuietHours {
+    /// Show it anyway.
+    Show,
+    /// Drop it.
+    Suppress,
+    /// Hold the latest one and show it once quiet hours end.
+    Queue,
+}
+
+impl QuietHours {
+    /// Parse the `toast_quiet_hours` setting. Unknown values suppress, so a
+    /// typo never punches through quiet hours.
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "show" => Self::Show,
+            "queue" => Self::Queue,
+            _ => Self::Suppress,
+        }
+    }
+
+    /// The setting value that parses back to `self`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Show => "show",
+            Self::Suppress => "suppress",
+            Self::Queue => "queue",
+        }
+    }
+}
+
 /// Show a Windows toast notification.
 ///
 /// If `enabled` is false, returns immediately (useful for testing and
 /// callers that want a single call site regardless of config).
 ///
-/// Uses WinRT toast APIs directly. In Session 0 (service mode), the
-/// notification infrastructure is unavailable and the call fails
-/// gracefully — the event is still logged to the Windows Event Log.
+/// Uses WinRT toast APIs directly. In Session 0 (service mode) the toast
+/// is routed to the user at the console instead.
 ///
 /// # Arguments
 /// * `enabled` — Whether to actually show the toast (false = no-op)
@@ -34,6 +112,10 @@ pub fn show_reapply_toast(enabled: bool, title: &str, body: &str, verbose: bool)
     if !enabled {
         return;
     }
+    if session::in_service_session() {
+        show_in_console_session(title, body, QuietHours::Show, verbose);
+        return;
+    }
 
     match show_toast_native(title, body) {
         Ok(()) => {
@@ -52,18 +134,135 @@ pub fn show_reapply_toast(enabled: bool, title: &str, body: &str, verbose: bool)
     }
 }
 
-/// Show a toast notification using the WinRT `ToastNotificationManager` API.
-fn show_toast_native(title: &str, body: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let title_escaped = escape_xml(title);
-    let body_escaped = escape_xml(body);
+/// Show a reapply toast unless Focus Assist is on, in which case `quiet`
+/// decides whether it is shown, dropped or queued until quiet hours end.
+pub fn show_reapply_toast_quiet_aware(
+    enabled: bool,
+    title: &str,
+    body: &str,
+    quiet: QuietHours,
+    verbose: bool,
+) {
+    if !enabled {
+        return;
+    }
+    // Focus Assist belongs to the user's session; the helper checks it there.
+    if session::in_service_session() {
+        show_in_console_session(title, body, quiet, verbose);
+        return;
+    }
+    let reason = match quiet {
+        QuietHours::Show => None,
+        QuietHours::Suppress | QuietHours::Queue => quiet_reason(),
+    };
+    let Some(reason) = reason else {
+        show_reapply_toast(true, title, body, verbose);
+        return;
+    };
 
-    let toast_xml = format!(
-        r#"<toast><visual><binding template="ToastGeneric"><text>{}</text><text>{}</text></binding></visual></toast>"#,
-        title_escaped, body_escaped
-    );
+    if quiet == QuietHours::Suppress {
+        info!("Toast suppressed: Focus Assist is on ({})", reason);
+        return;
+    }
+    if let Ok(mut queued) = QUEUED.lock() {
+        *queued = Some((title.to_string(), body.to_string()));
+    }
+    info!("Toast queued until Focus Assist ends ({})", reason);
+    if QUEUE_WAITER.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    thread::spawn(move || {
+        while quiet_reason().is_some() {
+            thread::sleep(QUEUE_POLL_INTERVAL);
+        }
+        QUEUE_WAITER.store(false, Ordering::SeqCst);
+        let queued = QUEUED.lock().ok().and_then(|mut q| q.take());
+        if let Some((title, body)) = queued {
+            show_reapply_toast(true, &title, &body, verbose);
+        }
+    });
+}
+
+/// Entry point of the [`SESSION_TOAST_COMMAND`] helper. Runs in the
+/// user's session, so Focus Assist is checked there; with
+/// [`QuietHours::Queue`] the helper process itself waits for quiet hours
+/// to end.
+pub fn run_session_toast(title: &str, body: &str, quiet: QuietHours) {
+    if quiet == QuietHours::Queue {
+        while quiet_reason().is_some() {
+            thread::sleep(QUEUE_POLL_INTERVAL);
+        }
+    }
+    let quiet = match quiet {
+        QuietHours::Queue => QuietHours::Show,
+        other => other,
+    };
+    show_reapply_toast_quiet_aware(true, title, body, quiet, false);
+}
 
+fn show_in_console_session(title: &str, body: &str, quiet: QuietHours, verbose: bool) {
+    match session::show_in_console_session(title, body, quiet) {
+        Ok(id) => info!("Toast notification sent to console session {}", id),
+        Err(e) => {
+            if verbose {
+                warn!("Toast notification unavailable: {}", e);
+            }
+        }
+    }
+}
+
+/// Why notifications are currently unwelcome, if they are: a manual or
+/// scheduled Focus Assist profile, or one of the shell's automatic quiet
+/// states (fullscreen game, presentation, quiet time after an upgrade).
+pub fn quiet_reason() -> Option<&'static str> {
+    focus_assist_profile()
+        .and_then(focus_assist_kind)
+        .or_else(|| {
+            unsafe { SHQueryUserNotificationState() }
+                .ok()
+                .and_then(notification_state_kind)
+        })
+}
+
+/// Focus Assist profile from WNF, or `None` if the query fails.
+fn focus_assist_profile() -> Option<u32> {
+    let mut change_stamp = 0u32;
+    let mut profile = 0u32;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let status = unsafe {
+        NtQueryWnfStateData(
+            &WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED,
+            std::ptr::null(),
+            std::ptr::null(),
+            &mut change_stamp,
+            &mut profile as *mut u32 as *mut core::ffi::c_void,
+            &mut size,
+        )
+    };
+    (status >= 0 && size as usize == std::mem::size_of::<u32>()).then_some(profile)
+}
+
+fn focus_assist_kind(profile: u32) -> Option<&'static str> {
+    match profile {
+        1 => Some("priority only"),
+        2 => Some("alarms only"),
+        _ => None,
+    }
+}
+
+fn notification_state_kind(state: QUERY_USER_NOTIFICATION_STATE) -> Option<&'static str> {
+    match state {
+        QUNS_BUSY | QUNS_RUNNING_D3D_FULL_SCREEN => Some("fullscreen app"),
+        QUNS_PRESENTATION_MODE => Some("presentation mode"),
+        QUNS_QUIET_TIME => Some("quiet time"),
+        _ => None,
+    }
+}
+
+/// Show a toast notification using the WinRT `ToastNotificationManager` API.
+fn show_toast_native(title: &str, body: &str) -> Result<(), Box<dyn std::error::Error>> {
     let xml = XmlDocument::new()?;
-    xml.LoadXml(&HSTRING::from(toast_xml.as_str()))?;
+    xml.LoadXml(&HSTRING::from(toast_xml(title, body).as_str()))?;
 
     let toast = ToastNotification::CreateToastNotification(&xml)?;
 
@@ -78,13 +277,33 @@ fn show_toast_native(title: &str, body: &str) -> Result<(), Box<dyn std::error::
     Ok(())
 }
 
-/// Escape XML special characters for safe inclusion in toast XML.
+/// Toast document with `title` and `body` as its two text lines.
+fn toast_xml(title: &str, body: &str) -> String {
+    format!(
+        r#"<toast><visual><binding template="ToastGeneric"><text>{}</text><text>{}</text></binding></visual></toast>"#,
+        escape_xml(title),
+        escape_xml(body)
+    )
+}
+
+/// Escape XML special characters for safe inclusion in toast XML, and drop
+/// characters XML 1.0 doesn't allow at all (control characters other than
+/// tab and newlines, U+FFFE/U+FFFF), which would make `LoadXml` fail.
 fn escape_xml(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-        .replace('\'', "&apos;")
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 #[cfg(test)]
@@ -0,0 +1,241 @@
+//! On-screen badge shown after a reapply.
+//!
+//! A small borderless, topmost, click-through window drawn with GDI that
+//! closes itself after a moment. Unlike a toast it needs no notification
+//! platform, so it also shows where toasts are disabled or blocked — as
+//! long as this process can see a desktop (`watch` and the tray in the
+//! user's session). Session 0 has no visible desktop, so the service
+//! skips it.
+
+use super::session;
+use log::{info, warn};
+use std::thread;
+use std::time::Duration;
+use windows::core::{w, PCWSTR};
+use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    BeginPaint, CreateFontW, CreateSolidBrush, DeleteObject, DrawTextW, EndPaint, FillRect,
+    SelectObject, SetBkMode, SetTextColor, CLEARTYPE_QUALITY, DEFAULT_CHARSET, DT_CENTER,
+    DT_END_ELLIPSIS, DT_SINGLELINE, DT_VCENTER, FW_SEMIBOLD, PAINTSTRUCT, TRANSPARENT,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetClientRect, GetMessageW,
+    GetWindowTextW, PostQuitMessage, RegisterClassW, SetLayeredWindowAttributes, SetTimer,
+    ShowWindow, SystemParametersInfoW, TranslateMessage, LWA_ALPHA, MSG, SPI_GETWORKAREA,
+    SW_SHOWNOACTIVATE, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS, WM_DESTROY, WM_PAINT, WM_TIMER,
+    WNDCLASSW, WS_EX_LAYERED, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT,
+    WS_POPUP,
+};
+
+const BADGE_WIDTH: i32 = 280;
+const BADGE_HEIGHT: i32 = 52;
+/// Gap between the badge and the edges of the work area.
+const BADGE_MARGIN: i32 = 24;
+const BADGE_ALPHA: u8 = 230;
+/// Background and text colours (`0x00BBGGRR`).
+const BADGE_BACKGROUND: u32 = 0x0030_2820;
+const BADGE_TEXT: u32 = 0x00FF_FFFF;
+const CLOSE_TIMER_ID: usize = 1;
+
+/// Badge text after a successful reapply.
+pub const REAPPLY_BADGE_TEXT: &str = "Profile reapplied \u{2713}";
+
+/// Screen corner the badge is drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsdPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl OsdPosition {
+    /// Parse the `osd_position` setting. Unknown values fall back to the
+    /// top-right corner.
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "top-left" => Self::TopLeft,
+            "bottom-left" => Self::BottomLeft,
+            "bottom-right" => Self::BottomRight,
+            _ => Self::TopRight,
+        }
+    }
+
+    /// The setting value that parses back to `self`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::TopLeft => "top-left",
+            Self::TopRight => "top-right",
+            Self::BottomLeft => "bottom-left",
+            Self::BottomRight => "bottom-right",
+        }
+    }
+}
+
+/// Top-left corner of a `size` badge placed in `position` of `work_area`.
+fn badge_origin(work_area: RECT, size: (i32, i32), position: OsdPosition) -> (i32, i32) {
+    let left = work_area.left + BADGE_MARGIN;
+    let right = work_area.right - BADGE_MARGIN - size.0;
+    let top = work_area.top + BADGE_MARGIN;
+    let bottom = work_area.bottom - BADGE_MARGIN - size.1;
+    match position {
+        OsdPosition::TopLeft => (left, top),
+        OsdPosition::TopRight => (right, top),
+        OsdPosition::BottomLeft => (left, bottom),
+        OsdPosition::BottomRight => (right, bottom),
+    }
+}
+
+/// Flash `text` in a corner of the primary monitor for `duration`.
+///
+/// Returns immediately; the badge runs on its own thread. A no-op if
+/// `enabled` is false or in Session 0.
+pub fn show_osd(enabled: bool, text: &str, position: OsdPosition, duration: Duration) {
+    if !enabled {
+        return;
+    }
+    if session::in_service_session() {
+        info!("On-screen badge skipped: no visible desktop in Session 0");
+        return;
+    }
+    let text = text.to_string();
+    let spawned = thread::Builder::new()
+        .name("osd-badge".to_string())
+        .spawn(move || {
+            if let Err(e) = run_badge(&text, position, duration) {
+                warn!("On-screen badge unavailable: {}", e);
+            }
+        });
+    if let Err(e) = spawned {
+        warn!("On-screen badge thread failed to start: {}", e);
+    }
+}
+
+/// Flash [`REAPPLY_BADGE_TEXT`] using the raw `osd_position` and
+/// `osd_duration_ms` settings.
+pub fn show_reapply_osd(enabled: bool, position: &str, duration_ms: u64) {
+    show_osd(
+        enabled,
+        REAPPLY_BADGE_TEXT,
+        OsdPosition::parse(position),
+        Duration::from_millis(duration_ms),
+    );
+}
+
+/// Create the badge window and pump its messages until it closes.
+fn run_badge(text: &str, position: OsdPosition, duration: Duration) -> windows::core::Result<()> {
+    let class_name = w!("LGUltraGearOsdBadge");
+    let title: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+    unsafe {
+        let instance = GetModuleHandleW(PCWSTR::null())?;
+        let wc = WNDCLASSW {
+            lpfnWndProc: Some(badge_proc),
+            hInstance: instance.into(),
+            lpszClassName: class_name,
+            ..Default::default()
+        };
+        // Zero when a previous badge already registered the class.
+        RegisterClassW(&wc);
+
+        let mut work_area = RECT::default();
+        SystemParametersInfoW(
+            SPI_GETWORKAREA,
+            0,
+            Some(&mut work_area as *mut RECT as *mut core::ffi::c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )?;
+        let (x, y) = badge_origin(work_area, (BADGE_WIDTH, BADGE_HEIGHT), position);
+
+        let hwnd = CreateWindowExW(
+            WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_NOACTIVATE,
+            class_name,
+            PCWSTR(title.as_ptr()),
+            WS_POPUP,
+            x,
+            y,
+            BADGE_WIDTH,
+            BADGE_HEIGHT,
+            None,
+            None,
+            wc.hInstance,
+            None,
+        )?;
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), BADGE_ALPHA, LWA_ALPHA)?;
+        let _ = ShowWindow(hwnd, SW_SHOWNOACTIVATE);
+        let millis = duration.as_millis().clamp(1, u32::MAX as u128) as u32;
+        SetTimer(hwnd, CLOSE_TIMER_ID, millis, None);
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+    Ok(())
+}
+
+unsafe extern "system" fn badge_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+            let mut rect = RECT::default();
+            let _ = GetClientRect(hwnd, &mut rect);
+
+            let brush = CreateSolidBrush(COLORREF(BADGE_BACKGROUND));
+            FillRect(hdc, &rect, brush);
+            let _ = DeleteObject(brush);
+
+            let font = CreateFontW(
+                -20,
+                0,
+                0,
+                0,
+                FW_SEMIBOLD.0 as i32,
+                0,
+                0,
+                0,
+                DEFAULT_CHARSET.0 as u32,
+                0,
+                0,
+                CLEARTYPE_QUALITY.0 as u32,
+                0,
+                w!("Segoe UI"),
+            );
+            let previous = SelectObject(hdc, font);
+            SetBkMode(hdc, TRANSPARENT);
+            SetTextColor(hdc, COLORREF(BADGE_TEXT));
+            let mut text = [0u16; 256];
+            let len = GetWindowTextW(hwnd, &mut text).max(0) as usize;
+            DrawTextW(
+                hdc,
+                &mut text[..len],
+                &mut rect,
+                DT_CENTER | DT_VCENTER | DT_SINGLELINE | DT_END_ELLIPSIS,
+            );
+            SelectObject(hdc, previous);
+            let _ = DeleteObject(font);
+            let _ = EndPaint(hwnd, &ps);
+            LRESULT(0)
+        }
+        WM_TIMER => {
+            let _ = DestroyWindow(hwnd);
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            PostQuitMessage(0);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/osd_tests.rs"]
+mod tests;
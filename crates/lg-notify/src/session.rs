@@ -0,0 +1,229 @@
+//! Routing toasts from Session 0 to the user at the console.
+//!
+//! The service runs as SYSTEM in Session 0, where WinRT toasts can't be
+//! shown. Instead it starts its own executable with the
+//! [`SESSION_TOAST_COMMAND`] subcommand in the active console session,
+//! under that user's token (`WTSQueryUserToken` + `CreateProcessAsUserW`).
+//! On a shared PC that is the user actually sitting at the monitor, not
+//! whoever else is logged on through fast user switching or Remote Desktop.
+//!
+//! Older releases got there through a temporary scheduled task instead,
+//! created and deleted around each toast. A service killed in between left
+//! the task behind for good; [`remove_leftover_toast_tasks`] clears those
+//! out at service start.
+
+use super::QuietHours;
+use log::warn;
+use std::error::Error;
+use std::ffi::c_void;
+use std::path::Path;
+
+use windows::core::{BSTR, PCWSTR, PWSTR, VARIANT};
+use windows::Win32::Foundation::{CloseHandle, BOOL, HANDLE};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
+};
+use windows::Win32::System::Environment::{CreateEnvironmentBlock, DestroyEnvironmentBlock};
+use windows::Win32::System::RemoteDesktop::{
+    ProcessIdToSessionId, WTSGetActiveConsoleSessionId, WTSQueryUserToken,
+};
+use windows::Win32::System::TaskScheduler::{ITaskService, TaskScheduler, TASK_ENUM_HIDDEN};
+use windows::Win32::System::Threading::{
+    CreateProcessAsUserW, GetCurrentProcessId, CREATE_NO_WINDOW, CREATE_UNICODE_ENVIRONMENT,
+    PROCESS_INFORMATION, STARTUPINFOW,
+};
+
+/// Hidden subcommand the executable must handle by calling
+/// [`run_session_toast`](super::run_session_toast).
+pub const SESSION_TOAST_COMMAND: &str = "session-toast";
+
+/// Returned by `WTSGetActiveConsoleSessionId` while the console is
+/// switching sessions or detached.
+const NO_CONSOLE_SESSION: u32 = 0xFFFF_FFFF;
+
+/// Interactive desktop of the target session.
+const INTERACTIVE_DESKTOP: &str = r"winsta0\default";
+
+/// Name prefix of the temporary toast tasks older releases registered in
+/// the Task Scheduler root folder (later with a per-toast suffix).
+const LEGACY_TOAST_TASK_PREFIX: &str = "LG-UltraGear-Toast-Temp";
+
+/// Session this process runs in, or `None` if the query fails.
+fn current_session_id() -> Option<u32> {
+    let mut session = 0u32;
+    unsafe { ProcessIdToSessionId(GetCurrentProcessId(), &mut session) }
+        .ok()
+        .map(|_| session)
+}
+
+/// True in the service's Session 0, where toasts have to be routed.
+pub(crate) fn in_service_session() -> bool {
+    current_session_id() == Some(0)
+}
+
+/// Session attached to the physical console, if it has one.
+fn active_console_session() -> Option<u32> {
+    let session = unsafe { WTSGetActiveConsoleSessionId() };
+    (session != NO_CONSOLE_SESSION && session != 0).then_some(session)
+}
+
+/// Quote one argument the way `CommandLineToArgvW` splits it back.
+pub(crate) fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '\n', '\u{0b}', '"']) {
+        return arg.to_string();
+    }
+    let mut quoted = String::from('"');
+    let mut backslashes = 0usize;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // Escape the pending backslashes and the quote itself.
+                quoted.extend(std::iter::repeat('\\').take(backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                quoted.extend(std::iter::repeat('\\').take(backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    // Backslashes before the closing quote must not escape it.
+    quoted.extend(std::iter::repeat('\\').take(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// Command line that shows `title`/`body` through the helper subcommand.
+pub(crate) fn session_toast_command_line(
+    exe: &Path,
+    title: &str,
+    body: &str,
+    quiet: QuietHours,
+) -> String {
+    format!(
+        "{} {} --title {} --body {} --quiet-hours {}",
+        quote_arg(&exe.display().to_string()),
+        SESSION_TOAST_COMMAND,
+        quote_arg(title),
+        quote_arg(body),
+        quiet.as_str()
+    )
+}
+
+/// Show a toast in the active console session by starting the helper as
+/// the user logged on there. Returns the session it was sent to.
+pub(crate) fn show_in_console_session(
+    title: &str,
+    body: &str,
+    quiet: QuietHours,
+) -> Result<u32, Box<dyn Error>> {
+    let session = active_console_session().ok_or("no user is logged on at the console")?;
+    let exe = std::env::current_exe()?;
+    let mut command_line: Vec<u16> = session_toast_command_line(&exe, title, body, quiet)
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut desktop: Vec<u16> = INTERACTIVE_DESKTOP
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let mut token = HANDLE::default();
+    unsafe { WTSQueryUserToken(session, &mut token) }
+        .map_err(|e| format!("cannot get the token of session {}: {}", session, e))?;
+
+    let mut environment: *mut c_void = std::ptr::null_mut();
+    let have_environment =
+        unsafe { CreateEnvironmentBlock(&mut environment, token, BOOL(0)).is_ok() };
+
+    let startup = STARTUPINFOW {
+        cb: std::mem::size_of::<STARTUPINFOW>() as u32,
+        lpDesktop: PWSTR(desktop.as_mut_ptr()),
+        ..Default::default()
+    };
+    let mut process = PROCESS_INFORMATION::default();
+    let result = unsafe {
+        CreateProcessAsUserW(
+            token,
+            PCWSTR::null(),
+            PWSTR(command_line.as_mut_ptr()),
+            None,
+            None,
+            BOOL(0),
+            CREATE_NO_WINDOW | CREATE_UNICODE_ENVIRONMENT,
+            have_environment.then_some(environment as *const c_void),
+            PCWSTR::null(),
+            &startup,
+            &mut process,
+        )
+    };
+
+    unsafe {
+        if have_environment {
+            let _ = DestroyEnvironmentBlock(environment);
+        }
+        let _ = CloseHandle(token);
+    }
+    result.map_err(|e| format!("cannot start toast helper in session {}: {}", session, e))?;
+    unsafe {
+        let _ = CloseHandle(process.hThread);
+        let _ = CloseHandle(process.hProcess);
+    }
+    Ok(session)
+}
+
+/// True for a temporary toast task left behind by an older release.
+pub(crate) fn is_leftover_toast_task(name: &str) -> bool {
+    name.get(..LEGACY_TOAST_TASK_PREFIX.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(LEGACY_TOAST_TASK_PREFIX))
+}
+
+/// Delete the temporary toast tasks older releases could leave in the Task
+/// Scheduler root folder. Returns the names removed.
+pub fn remove_leftover_toast_tasks() -> Result<Vec<String>, Box<dyn Error>> {
+    // Balanced with CoUninitialize below; S_FALSE (already initialized)
+    // is fine.
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED).ok();
+    }
+
+    let result = (|| -> Result<Vec<String>, Box<dyn Error>> {
+        let service: ITaskService =
+            unsafe { CoCreateInstance(&TaskScheduler, None, CLSCTX_INPROC_SERVER)? };
+        let empty = VARIANT::default();
+        unsafe { service.Connect(&empty, &empty, &empty, &empty)? };
+        let folder = unsafe { service.GetFolder(&BSTR::from("\\"))? };
+
+        // Collect first: deleting shifts the collection's indexes.
+        let tasks = unsafe { folder.GetTasks(TASK_ENUM_HIDDEN.0)? };
+        let mut leftovers = Vec::new();
+        for index in 1..=unsafe { tasks.Count()? } {
+            let name = unsafe { tasks.get_Item(&VARIANT::from(index))?.Name()? }.to_string();
+            if is_leftover_toast_task(&name) {
+                leftovers.push(name);
+            }
+        }
+
+        let mut removed = Vec::with_capacity(leftovers.len());
+        for name in leftovers {
+            match unsafe { folder.DeleteTask(&BSTR::from(name.as_str()), 0) } {
+                Ok(()) => removed.push(name),
+                Err(e) => warn!("Could not delete leftover task {}: {}", name, e),
+            }
+        }
+        Ok(removed)
+    })();
+
+    unsafe {
+        CoUninitialize();
+    }
+
+    result
+}
+
+#[cfg(test)]
+#[path = "tests/session_tests.rs"]
+mod tests;
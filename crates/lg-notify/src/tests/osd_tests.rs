@@ -0,0 +1,68 @@
+use super::*;
+
+fn work_area() -> RECT {
+    RECT {
+        left: 0,
+        top: 0,
+        right: 1920,
+        bottom: 1040,
+    }
+}
+
+// ── Position setting ─────────────────────────────────────────────
+
+#[test]
+fn positions_round_trip_through_their_setting_value() {
+    for position in [
+        OsdPosition::TopLeft,
+        OsdPosition::TopRight,
+        OsdPosition::BottomLeft,
+        OsdPosition::BottomRight,
+    ] {
+        assert_eq!(OsdPosition::parse(position.as_str()), position);
+    }
+}
+
+#[test]
+fn position_parsing_ignores_case_and_falls_back_to_top_right() {
+    assert_eq!(OsdPosition::parse(" Bottom-Left "), OsdPosition::BottomLeft);
+    assert_eq!(OsdPosition::parse("center"), OsdPosition::TopRight);
+    assert_eq!(OsdPosition::parse(""), OsdPosition::TopRight);
+}
+
+// ── Placement ────────────────────────────────────────────────────
+
+#[test]
+fn badge_sits_inside_the_margin_of_each_corner() {
+    let size = (200, 50);
+    assert_eq!(
+        badge_origin(work_area(), size, OsdPosition::TopLeft),
+        (24, 24)
+    );
+    assert_eq!(
+        badge_origin(work_area(), size, OsdPosition::TopRight),
+        (1696, 24)
+    );
+    assert_eq!(
+        badge_origin(work_area(), size, OsdPosition::BottomLeft),
+        (24, 966)
+    );
+    assert_eq!(
+        badge_origin(work_area(), size, OsdPosition::BottomRight),
+        (1696, 966)
+    );
+}
+
+#[test]
+fn badge_follows_an_offset_work_area() {
+    let area = RECT {
+        left: 0,
+        top: 40,
+        right: 1920,
+        bottom: 1080,
+    };
+    assert_eq!(
+        badge_origin(area, (200, 50), OsdPosition::TopLeft),
+        (24, 64)
+    );
+}
@@ -0,0 +1,136 @@
+use super::*;
+
+// ── Argument quoting ─────────────────────────────────────────────
+
+#[test]
+fn plain_arguments_are_not_quoted() {
+    assert_eq!(quote_arg("LG"), "LG");
+    assert_eq!(quote_arg(r"C:\Tools\fix.exe"), r"C:\Tools\fix.exe");
+}
+
+#[test]
+fn spaces_and_empty_arguments_are_quoted() {
+    assert_eq!(quote_arg("Profile reapplied"), "\"Profile reapplied\"");
+    assert_eq!(quote_arg(""), "\"\"");
+}
+
+#[test]
+fn embedded_quotes_are_escaped() {
+    assert_eq!(quote_arg(r#"say "hi""#), r#""say \"hi\"""#);
+    assert_eq!(quote_arg(r#"a\"b"#), r#""a\\\"b""#);
+}
+
+#[test]
+fn trailing_backslashes_do_not_escape_the_closing_quote() {
+    assert_eq!(
+        quote_arg(r"C:\Program Files\LG\"),
+        r#""C:\Program Files\LG\\""#
+    );
+}
+
+/// Split a command line the way `CommandLineToArgvW` does for arguments
+/// after the program name.
+fn split_args(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+        if chars.peek().is_none() {
+            return args;
+        }
+        let mut arg = String::new();
+        let mut quoted = false;
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    let mut backslashes = 1;
+                    while chars.next_if_eq(&'\\').is_some() {
+                        backslashes += 1;
+                    }
+                    if chars.peek() == Some(&'"') {
+                        arg.extend(std::iter::repeat('\\').take(backslashes / 2));
+                        if backslashes % 2 == 1 {
+                            arg.push('"');
+                            chars.next();
+                        }
+                    } else {
+                        arg.extend(std::iter::repeat('\\').take(backslashes));
+                    }
+                }
+                '"' => quoted = !quoted,
+                ' ' | '\t' if !quoted => break,
+                c => arg.push(c),
+            }
+        }
+        args.push(arg);
+    }
+}
+
+#[test]
+fn hostile_arguments_survive_the_round_trip() {
+    for arg in [
+        "$(calc)",
+        "a & b | c ^ d %PATH% !x! `whoami`",
+        r#"x" --quiet-hours show --title "y"#,
+        r#"C:\Temp\"#,
+        r#"\\"\\\""#,
+        "line 1\nline 2",
+        "\t",
+        "",
+    ] {
+        assert_eq!(split_args(&quote_arg(arg)), vec![arg], "{:?}", arg);
+    }
+}
+
+// ── Helper command line ──────────────────────────────────────────
+
+#[test]
+fn session_toast_command_line_carries_text_and_quiet_mode() {
+    let line = session_toast_command_line(
+        Path::new(r"C:\Program Files\LG\lg-ultragear-dimming-fix.exe"),
+        "LG UltraGear",
+        "Color profile reapplied ✓",
+        QuietHours::Queue,
+    );
+    assert_eq!(
+        line,
+        "\"C:\\Program Files\\LG\\lg-ultragear-dimming-fix.exe\" session-toast \
+         --title \"LG UltraGear\" --body \"Color profile reapplied ✓\" --quiet-hours queue"
+    );
+}
+
+#[test]
+fn hostile_title_and_body_cannot_add_options() {
+    let title = r#"LG" --quiet-hours show --body "x"#;
+    let body = "$(calc) & \\\" --quiet-hours show";
+    let line = session_toast_command_line(
+        Path::new(r"C:\Tools\fix.exe"),
+        title,
+        body,
+        QuietHours::Suppress,
+    );
+    assert_eq!(
+        split_args(&line),
+        vec![
+            r"C:\Tools\fix.exe",
+            SESSION_TOAST_COMMAND,
+            "--title",
+            title,
+            "--body",
+            body,
+            "--quiet-hours",
+            "suppress",
+        ]
+    );
+}
+
+// ── Leftover toast tasks ─────────────────────────────────────────
+
+#[test]
+fn leftover_toast_tasks_are_matched_by_prefix() {
+    assert!(is_leftover_toast_task("LG-UltraGear-Toast-Temp"));
+    assert!(is_leftover_toast_task("lg-ultragear-toast-temp-4242"));
+    assert!(!is_leftover_toast_task("LG-UltraGear-Toast"));
+    assert!(!is_leftover_toast_task("Calibration Loader"));
+    assert!(!is_leftover_toast_task("LG-UltraGear-Toast-Tem\u{e9}"));
+}
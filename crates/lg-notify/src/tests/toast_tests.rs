@@ -107,3 +107,81 @@ fn escape_xml_combined() {
 fn escape_xml_preserves_unicode() {
     assert_eq!(escape_xml("Color profile ✓"), "Color profile ✓");
 }
+
+#[test]
+fn escape_xml_drops_characters_xml_forbids() {
+    assert_eq!(escape_xml("a\u{0}b\u{1b}[2Jc\u{fffe}d"), "ab[2Jcd");
+    assert_eq!(escape_xml("line 1\r\n\tline 2"), "line 1\r\n\tline 2");
+}
+
+// ── toast_xml ────────────────────────────────────────────────────
+
+#[test]
+fn toast_xml_keeps_hostile_text_inside_its_element() {
+    let xml = toast_xml(
+        "</text><image src=\"file:///C:/x.png\"/><text>",
+        "]]><!ENTITY x SYSTEM \"file:///\"> &x; $(calc) '",
+    );
+    assert_eq!(xml.matches("<text>").count(), 2);
+    assert_eq!(xml.matches("</text>").count(), 2);
+    assert!(!xml.contains("<image"));
+    assert!(!xml.contains("<!ENTITY"));
+    assert!(!xml.contains("&x;"));
+    assert!(xml.contains("$(calc)"), "{}", xml);
+}
+
+#[test]
+fn toast_xml_places_title_before_body() {
+    assert_eq!(
+        toast_xml("LG & co", "<done>"),
+        r#"<toast><visual><binding template="ToastGeneric"><text>LG &amp; co</text><text>&lt;done&gt;</text></binding></visual></toast>"#
+    );
+}
+
+// ── Quiet hours ──────────────────────────────────────────────────
+
+#[test]
+fn quiet_hours_parse_known_values() {
+    assert_eq!(QuietHours::parse("show"), QuietHours::Show);
+    assert_eq!(QuietHours::parse(" Queue "), QuietHours::Queue);
+    assert_eq!(QuietHours::parse("suppress"), QuietHours::Suppress);
+}
+
+#[test]
+fn quiet_hours_parse_unknown_suppresses() {
+    assert_eq!(QuietHours::parse(""), QuietHours::Suppress);
+    assert_eq!(QuietHours::parse("loud"), QuietHours::Suppress);
+}
+
+#[test]
+fn quiet_hours_as_str_roundtrips() {
+    for quiet in [QuietHours::Show, QuietHours::Suppress, QuietHours::Queue] {
+        assert_eq!(QuietHours::parse(quiet.as_str()), quiet);
+    }
+}
+
+#[test]
+fn focus_assist_kind_maps_profiles() {
+    assert_eq!(focus_assist_kind(0), None);
+    assert_eq!(focus_assist_kind(1), Some("priority only"));
+    assert_eq!(focus_assist_kind(2), Some("alarms only"));
+}
+
+#[test]
+fn notification_state_kind_maps_quiet_states() {
+    assert_eq!(notification_state_kind(QUNS_QUIET_TIME), Some("quiet time"));
+    assert_eq!(
+        notification_state_kind(QUNS_RUNNING_D3D_FULL_SCREEN),
+        Some("fullscreen app")
+    );
+    assert_eq!(
+        notification_state_kind(QUERY_USER_NOTIFICATION_STATE(5)),
+        None,
+        "QUNS_ACCEPTS_NOTIFICATIONS"
+    );
+}
+
+#[test]
+fn quiet_aware_toast_disabled_is_noop() {
+    show_reapply_toast_quiet_aware(false, "Title", "Body", QuietHours::Queue, false);
+}
@@ -19,17 +19,22 @@ use cmx::tag::tags::{
 };
 use cmx::tag::RenderingIntent;
 use cmx::tag::TagSignature;
+use lg_core::audit::{self, AuditEntry};
+use lg_core::colorstore::{
+    ColorStoreBackup, DefaultKind, DeviceColorState, RestoreChange, Scope, ScopeColorState,
+};
+use lg_core::plan::{OpKind, Plan};
+use lg_core::wide::WideString;
 use log::{info, warn};
 use std::collections::HashMap;
 use std::error::Error;
-use std::ffi::OsStr;
 use std::hash::{Hash, Hasher};
 use std::io;
-use std::os::windows::ffi::OsStrExt;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
 use std::{ptr, thread, time::Duration};
-use windows::core::{BSTR, HSTRING, PCWSTR, PWSTR};
+use windows::core::{s, w, BSTR, HRESULT, HSTRING, PCSTR, PCWSTR, PWSTR};
 use windows::Win32::Devices::Display::{
     DisplayConfigGetDeviceInfo, GetDisplayConfigBufferSizes, QueryDisplayConfig, SetDisplayConfig,
     DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME, DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME,
@@ -39,7 +44,7 @@ use windows::Win32::Devices::Display::{
     SET_DISPLAY_CONFIG_FLAGS,
 };
 use windows::Win32::Foundation::{
-    LocalFree, BOOL, ERROR_INSUFFICIENT_BUFFER, ERROR_SUCCESS, HWND, LPARAM, WPARAM,
+    LocalFree, BOOL, ERROR_INSUFFICIENT_BUFFER, ERROR_SUCCESS, HWND, LPARAM, LUID, WPARAM,
 };
 use windows::Win32::Graphics::Gdi::{
     ChangeDisplaySettingsExW, CreateDCW, DeleteDC, InvalidateRect,
@@ -47,16 +52,17 @@ use windows::Win32::Graphics::Gdi::{
 use windows::Win32::System::Com::{
     CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
 };
+use windows::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
 use windows::Win32::System::TaskScheduler::{ITaskService, TaskScheduler};
 use windows::Win32::UI::ColorSystem::{
-    AssociateColorProfileWithDeviceW, ColorProfileAddDisplayAssociation,
-    ColorProfileGetDisplayDefault, ColorProfileSetDisplayDefaultAssociation, GetICMProfileW,
-    InstallColorProfileW, SetDeviceGammaRamp, SetICMProfileW, WcsAssociateColorProfileWithDevice,
+    AssociateColorProfileWithDeviceW, GetDeviceGammaRamp, GetICMProfileW, InstallColorProfileW,
+    SetDeviceGammaRamp, SetICMProfileW, WcsAssociateColorProfileWithDevice,
     WcsDisassociateColorProfileFromDevice, WcsGetDefaultColorProfile,
     WcsGetDefaultColorProfileSize, WcsGetUsePerUserProfiles, WcsSetCalibrationManagementState,
-    WcsSetDefaultColorProfile, WcsSetUsePerUserProfiles, CPST_EXTENDED_DISPLAY_COLOR_MODE,
-    CPST_NONE, CPST_STANDARD_DISPLAY_COLOR_MODE, CPT_ICC, WCS_PROFILE_MANAGEMENT_SCOPE,
-    WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER, WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
+    WcsSetDefaultColorProfile, WcsSetUsePerUserProfiles, COLORPROFILESUBTYPE, COLORPROFILETYPE,
+    CPST_EXTENDED_DISPLAY_COLOR_MODE, CPST_NONE, CPST_STANDARD_DISPLAY_COLOR_MODE, CPT_ICC,
+    WCS_PROFILE_MANAGEMENT_SCOPE, WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
+    WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
     SendMessageTimeoutW, HWND_BROADCAST, SMTO_ABORTIFHUNG, WM_SETTINGCHANGE,
@@ -93,6 +99,8 @@ pub const MAX_DYNAMIC_LUMINANCE_CD_M2: f64 = 600.0;
 pub const GAMMA22_PROFILE_NAME: &str = "lg-ultragear-gamma22-cmx.icm";
 pub const GAMMA24_PROFILE_NAME: &str = "lg-ultragear-gamma24-cmx.icm";
 pub const READER_PROFILE_NAME: &str = "lg-ultragear-reader-cmx.icm";
+/// Neutral profile for A/B testing, see [`generate_identity_profile_bytes`].
+pub const IDENTITY_PROFILE_NAME: &str = "lg-ultragear-identity.icm";
 
 /// Curve table size used for generated ICC TRCs/VCGT LUTs.
 const CURVE_TABLE_SIZE: usize = 256;
@@ -101,6 +109,9 @@ const ICC_TAG_COUNT_OFFSET: usize = ICC_HEADER_SIZE;
 const ICC_TAG_RECORD_SIZE: usize = 12;
 const ICC_MIN_SIZE: usize = ICC_HEADER_SIZE + 4;
 const ICC_ACSP_OFFSET: usize = 36;
+/// Real profiles carry a few dozen tags. The overlap check is quadratic
+/// in the tag count, so a corrupted count is rejected before it runs.
+const ICC_MAX_TAG_COUNT: usize = 1024;
 const TAG_SIG_SDIN: u32 = 0x7364_696E; // "sdin"
 const TAG_SIG_SWPT: u32 = 0x7377_7074; // "swpt"
 const TAG_SIG_SVCN: u32 = 0x7376_636E; // "svcn"
@@ -753,6 +764,9 @@ pub fn resolve_dynamic_icc_tuning(
 /// 1) day/night schedule presets when both are configured
 /// 2) HDR/SDR preset based on `hdr_mode`
 /// 3) fallback `active_preset`
+///
+/// Day/night uses the built-in 07:00–19:00 window; callers with a configured
+/// schedule should use [`select_effective_preset_for_period`].
 pub fn select_effective_preset(
     active_preset: &str,
     sdr_preset: &str,
@@ -760,15 +774,34 @@ pub fn select_effective_preset(
     schedule_day_preset: &str,
     schedule_night_preset: &str,
     hdr_mode: bool,
+) -> String {
+    let hour = chrono::Local::now().hour();
+    select_effective_preset_for_period(
+        active_preset,
+        sdr_preset,
+        hdr_preset,
+        schedule_day_preset,
+        schedule_night_preset,
+        hdr_mode,
+        (DAY_PRESET_START_HOUR..NIGHT_PRESET_START_HOUR).contains(&hour),
+    )
+}
+
+/// Same as [`select_effective_preset`], with the day/night period decided by
+/// the caller (`daytime` picks the day preset).
+pub fn select_effective_preset_for_period(
+    active_preset: &str,
+    sdr_preset: &str,
+    hdr_preset: &str,
+    schedule_day_preset: &str,
+    schedule_night_preset: &str,
+    hdr_mode: bool,
+    daytime: bool,
 ) -> String {
     let day = schedule_day_preset.trim();
     let night = schedule_night_preset.trim();
     if !day.is_empty() && !night.is_empty() {
-        let hour = chrono::Local::now().hour();
-        if (DAY_PRESET_START_HOUR..NIGHT_PRESET_START_HOUR).contains(&hour) {
-            return day.to_string();
-        }
-        return night.to_string();
+        return if daytime { day } else { night }.to_string();
     }
 
     let mode_preset = if hdr_mode {
@@ -1014,15 +1047,24 @@ fn build_trc_curve_points(
     out
 }
 
+fn identity_curve_points() -> [u16; CURVE_TABLE_SIZE] {
+    let mut out = [0u16; CURVE_TABLE_SIZE];
+    for (i, slot) in out.iter_mut().enumerate() {
+        *slot = ((i as f64 / (CURVE_TABLE_SIZE - 1) as f64) * 65535.0).round() as u16;
+    }
+    out
+}
+
 fn blend_curve_with_identity(
     curve: &[u16; CURVE_TABLE_SIZE],
     strength: f64,
 ) -> [u16; CURVE_TABLE_SIZE] {
     let strength = sanitize_vcgt_strength(strength);
+    let identity = identity_curve_points();
     let mut out = [0u16; CURVE_TABLE_SIZE];
     for (i, slot) in out.iter_mut().enumerate() {
-        let identity = ((i as f64 / (CURVE_TABLE_SIZE - 1) as f64) * 65535.0).round() as u16;
-        let blended = (identity as f64) + ((curve[i] as f64) - (identity as f64)) * strength;
+        let identity = identity[i] as f64;
+        let blended = identity + ((curve[i] as f64) - identity) * strength;
         *slot = blended.round().clamp(0.0, 65535.0) as u16;
     }
     out
@@ -1440,6 +1482,67 @@ pub fn generate_dynamic_profile_bytes_with_luminance_tuning_identity_and_extra_t
     profile.to_bytes()
 }
 
+/// Generate a neutral profile: the stock sRGB display description with a
+/// linear `vcgt`, so associating it loads a straight calibration ramp and
+/// changes no tone curve. Swapping it in for the generated profile shows
+/// whether the curves or just the association toggle stop the dimming.
+pub fn generate_identity_profile_bytes() -> Result<Vec<u8>, Box<dyn Error>> {
+    let created = chrono::Utc
+        .with_ymd_and_hms(2026, 1, 1, 0, 0, 0)
+        .single()
+        .ok_or("invalid fixed ICC creation date")?;
+    let linear = identity_curve_points();
+    let vcgt = build_vcgt_table_payload(&linear, &linear, &linear, 1.0);
+    let profile = DisplayProfile::cmx_srgb(RenderingIntent::RelativeColorimetric)
+        .with_creation_date(created)
+        .with_tag(ProfileDescriptionTag)
+        .as_text_description(|text| text.set_ascii("LG UltraGear Identity (linear vcgt)"))
+        .with_tag(VcgtTag)
+        .as_raw(|raw| raw.set_bytes(&vcgt))
+        .with_profile_id();
+    profile.to_bytes()
+}
+
+/// Write [`IDENTITY_PROFILE_NAME`] to `color_dir` and register it.
+/// Returns the path and whether the file was (re)written.
+pub fn ensure_identity_profile_installed(
+    color_dir: &Path,
+) -> Result<(PathBuf, bool), Box<dyn Error>> {
+    let path = color_dir.join(IDENTITY_PROFILE_NAME);
+    let written = install_generated_profile(&path, &generate_identity_profile_bytes()?)?;
+    Ok((path, written))
+}
+
+/// Write the `variant` white point version of `source` (usually the
+/// active generated profile) to `color_dir` and register it. Returns the
+/// path and whether the file was (re)written.
+pub fn ensure_white_point_variant_installed(
+    color_dir: &Path,
+    source: &Path,
+    variant: WhitePointVariant,
+) -> Result<(PathBuf, bool), Box<dyn Error>> {
+    let bytes =
+        std::fs::read(source).map_err(|e| format!("cannot read {}: {}", source.display(), e))?;
+    let path = color_dir.join(variant.profile_name());
+    let written =
+        install_generated_profile(&path, &generate_white_point_variant_bytes(&bytes, variant)?)?;
+    Ok((path, written))
+}
+
+/// Write `bytes` to `path` unless it already holds them, then register it.
+fn install_generated_profile(path: &Path, bytes: &[u8]) -> Result<bool, Box<dyn Error>> {
+    let written = !matches!(std::fs::read(path), Ok(existing) if existing == bytes);
+    if written {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        audit::file_change("write_profile", path, || std::fs::write(path, bytes))?;
+        info!("ICC profile written to {}", path.display());
+    }
+    register_color_profile(path)?;
+    Ok(written)
+}
+
 /// Default generated ICC profile size in bytes.
 pub fn dynamic_profile_size() -> Result<usize, Box<dyn Error>> {
     Ok(generate_dynamic_profile_bytes(DEFAULT_DYNAMIC_GAMMA)?.len())
@@ -1493,6 +1596,13 @@ pub fn validate_icc_profile_bytes(profile_bytes: &[u8]) -> IccValidationReport {
         return report;
     };
     report.tag_count = Some(tag_count);
+    if tag_count as usize > ICC_MAX_TAG_COUNT {
+        report.errors.push(format!(
+            "tag count {} exceeds the limit of {}",
+            tag_count, ICC_MAX_TAG_COUNT
+        ));
+        return report;
+    }
 
     let Some(tag_table_bytes) = (tag_count as usize).checked_mul(ICC_TAG_RECORD_SIZE) else {
         report.errors.push(format!(
@@ -1635,7 +1745,7 @@ pub fn validate_icc_profile_bytes(profile_bytes: &[u8]) -> IccValidationReport {
         }
     }
 
-    match RawProfile::from_bytes(profile_bytes) {
+    match parse_raw_profile(profile_bytes) {
         Ok(raw) => validate_rgb_display_profile_semantics(&raw, &mut report),
         Err(e) => report
             .errors
@@ -1949,7 +2059,7 @@ pub struct IccInspectionReport {
 pub fn inspect_icc_profile_bytes(
     profile_bytes: &[u8],
 ) -> Result<IccInspectionReport, Box<dyn Error>> {
-    let raw = RawProfile::from_bytes(profile_bytes)?;
+    let raw = parse_raw_profile(profile_bytes)?;
     let tag_details = collect_icc_tag_details(&raw);
     let known_tag_count = tag_details.iter().filter(|tag| tag.known_signature).count();
     let unknown_tag_count = tag_details.len() - known_tag_count;
@@ -1973,7 +2083,7 @@ pub fn inspect_icc_profile_bytes(
 
 /// Parse and reserialize an ICC profile to normalize layout/ordering.
 pub fn normalize_icc_profile_bytes(profile_bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
-    let raw = RawProfile::from_bytes(profile_bytes)?;
+    let raw = parse_raw_profile(profile_bytes)?;
     raw.into_bytes()
 }
 
@@ -1983,7 +2093,7 @@ pub fn patch_icc_profile_bytes(
     set_tags: &[ExtraRawTag],
     remove_signatures: &[u32],
 ) -> Result<Vec<u8>, Box<dyn Error>> {
-    let mut raw = RawProfile::from_bytes(profile_bytes)?;
+    let mut raw = parse_raw_profile(profile_bytes)?;
 
     if !remove_signatures.is_empty() {
         raw.tags
@@ -2006,6 +2116,201 @@ pub fn build_icc_data_type_payload(bytes: &[u8]) -> Vec<u8> {
     build_data_type_payload(bytes)
 }
 
+/// Curve changes made by [`adjust_vcgt_profile_bytes`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VcgtAdjustment {
+    /// Offset added to every entry, in percent of full scale (-50..=50).
+    pub brightness: f64,
+    /// Slope change around mid grey, in percent (-50..=50).
+    pub contrast: f64,
+    /// Gamma the display should end up at, assuming it tracks 2.2 with the
+    /// current curve (1.2..=3.0). 2.2 leaves the curve as it is.
+    pub gamma: f64,
+}
+
+impl Default for VcgtAdjustment {
+    fn default() -> Self {
+        Self {
+            brightness: 0.0,
+            contrast: 0.0,
+            gamma: PRESET_GAMMA_22,
+        }
+    }
+}
+
+impl VcgtAdjustment {
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if !(-50.0..=50.0).contains(&self.brightness) {
+            return Err(format!("brightness {} is outside -50..50", self.brightness).into());
+        }
+        if !(-50.0..=50.0).contains(&self.contrast) {
+            return Err(format!("contrast {} is outside -50..50", self.contrast).into());
+        }
+        if !(MIN_DYNAMIC_GAMMA..=MAX_DYNAMIC_GAMMA).contains(&self.gamma) {
+            return Err(format!(
+                "gamma {} is outside {}..{}",
+                self.gamma, MIN_DYNAMIC_GAMMA, MAX_DYNAMIC_GAMMA
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Map one normalized curve entry.
+    fn apply(&self, value: f64) -> f64 {
+        let mut y = value.clamp(0.0, 1.0).powf(self.gamma / PRESET_GAMMA_22);
+        y = (y - 0.5) * (1.0 + self.contrast / 100.0) + 0.5;
+        y += self.brightness / 100.0;
+        y.clamp(0.0, 1.0)
+    }
+}
+
+/// Rewrite the `vcgt` curve of an ICC profile with `adjustment` and
+/// recompute the profile ID. A profile without `vcgt` starts from a linear
+/// curve, so the result always carries one.
+pub fn adjust_vcgt_profile_bytes(
+    profile_bytes: &[u8],
+    adjustment: VcgtAdjustment,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    adjustment.validate()?;
+    map_vcgt_profile_bytes(profile_bytes, |_, value| adjustment.apply(value))
+}
+
+/// Rewrite every `vcgt` entry with `map(channel, value)` (normalized
+/// values, channels in R, G, B order) and recompute the profile ID. A
+/// profile without `vcgt` starts from a linear curve.
+fn map_vcgt_profile_bytes(
+    profile_bytes: &[u8],
+    map: impl Fn(usize, f64) -> f64,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let raw = parse_raw_profile(profile_bytes)?;
+    let ramp = match raw.tags.get(&TagSignature::Vcgt) {
+        Some(record) => parse_vcgt_gamma_ramp(record.tag.as_slice())?,
+        None => {
+            let linear = identity_curve_points();
+            let mut ramp = [0u16; CURVE_TABLE_SIZE * 3];
+            for channel in ramp.chunks_mut(CURVE_TABLE_SIZE) {
+                channel.copy_from_slice(&linear);
+            }
+            ramp
+        }
+    };
+
+    let mut channels = [[0u16; CURVE_TABLE_SIZE]; 3];
+    for (index, (channel, source)) in channels
+        .iter_mut()
+        .zip(ramp.chunks(CURVE_TABLE_SIZE))
+        .enumerate()
+    {
+        for (slot, value) in channel.iter_mut().zip(source) {
+            let y = map(index, *value as f64 / 65535.0).clamp(0.0, 1.0);
+            *slot = (y * 65535.0).round() as u16;
+        }
+    }
+    let [red, green, blue] = channels;
+    let payload = build_vcgt_table_payload(&red, &green, &blue, 1.0);
+    raw.with_tag(TagSignature::Vcgt)
+        .as_raw(|raw| raw.set_bytes(&payload))
+        .with_profile_id()
+        .into_bytes()
+}
+
+/// White points `profile generate --variant` offers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhitePointVariant {
+    K5000,
+    K6500,
+    K9300,
+}
+
+impl WhitePointVariant {
+    /// Parse `5000k`, `6500k` or `9300k` (case-insensitive, `k` optional).
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        match s.strip_suffix('k').unwrap_or(&s) {
+            "5000" => Some(Self::K5000),
+            "6500" => Some(Self::K6500),
+            "9300" => Some(Self::K9300),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::K5000 => "5000k",
+            Self::K6500 => "6500k",
+            Self::K9300 => "9300k",
+        }
+    }
+
+    pub fn kelvin(self) -> f64 {
+        match self {
+            Self::K5000 => 5000.0,
+            Self::K6500 => 6500.0,
+            Self::K9300 => 9300.0,
+        }
+    }
+
+    /// File name of the generated variant in the color store.
+    pub fn profile_name(self) -> String {
+        format!("lg-ultragear-{}.icm", self.as_str())
+    }
+}
+
+/// Approximate sRGB of a black body at `kelvin` (Tanner Helland's fit),
+/// 0..=255 per channel.
+fn black_body_rgb(kelvin: f64) -> [f64; 3] {
+    let t = kelvin / 100.0;
+    let r = if t <= 66.0 {
+        255.0
+    } else {
+        329.698727446 * (t - 60.0).powf(-0.1332047592)
+    };
+    let g = if t <= 66.0 {
+        99.4708025861 * t.ln() - 161.1195681661
+    } else {
+        288.1221695283 * (t - 60.0).powf(-0.0755148492)
+    };
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.5177312231 * (t - 10.0).ln() - 305.0447927307
+    };
+    [r, g, b].map(|c| c.clamp(0.0, 255.0))
+}
+
+/// Per-channel gains that move a D65 (6500 K) white to `kelvin`. The
+/// strongest channel stays at 1.0, so nothing is pushed past full scale.
+pub fn white_point_gains(kelvin: f64) -> [f64; 3] {
+    let target = black_body_rgb(kelvin);
+    let d65 = black_body_rgb(6500.0);
+    let gains = [0, 1, 2].map(|i| target[i] / d65[i]);
+    let max = gains.iter().cloned().fold(f64::MIN, f64::max);
+    gains.map(|g| g / max)
+}
+
+/// `profile_bytes` with its `vcgt` scaled to the `variant` white point.
+/// 6500 K keeps the curve as it is.
+pub fn generate_white_point_variant_bytes(
+    profile_bytes: &[u8],
+    variant: WhitePointVariant,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let gains = white_point_gains(variant.kelvin());
+    map_vcgt_profile_bytes(profile_bytes, |channel, value| value * gains[channel])
+}
+
+/// Parse ICC bytes with cmx. Profiles come from disk and may be corrupted;
+/// a parser panic on one is turned into an error so it can't take the
+/// service down with it.
+fn parse_raw_profile(profile_bytes: &[u8]) -> Result<RawProfile, Box<dyn Error>> {
+    match std::panic::catch_unwind(|| RawProfile::from_bytes(profile_bytes)) {
+        Ok(parsed) => Ok(parsed?),
+        Err(_) => Err("cmx parser panicked on malformed profile data".into()),
+    }
+}
+
 fn read_be_u32(bytes: &[u8], offset: usize) -> Option<u32> {
     let slice = bytes.get(offset..offset + 4)?;
     let mut arr = [0u8; 4];
@@ -2331,7 +2636,9 @@ pub fn ensure_profile_installed_with_gamma_luminance_tuning_identity_and_extra_t
             std::fs::create_dir_all(parent)?;
         }
     }
-    std::fs::write(profile_path, &generated)?;
+    audit::file_change("write_profile", profile_path, || {
+        std::fs::write(profile_path, &generated)
+    })?;
     info!(
         "Dynamic ICC profile generated (gamma {:.3}, luminance {:.1} cd/m^2) and written to {}",
         gamma,
@@ -2454,6 +2761,7 @@ pub fn ensure_active_profile_installed_tuned(
     let preset = parse_dynamic_icc_preset(active_preset);
     let luminance_cd_m2 = sanitize_dynamic_luminance_cd_m2(luminance_cd_m2);
     let effective_tuning = tuning;
+    let active_path = color_dir.join(preset.profile_name(custom_profile_name));
 
     if install_specialized_profiles {
         let _ = ensure_specialized_profiles_installed_tuned(
@@ -2461,9 +2769,16 @@ pub fn ensure_active_profile_installed_tuned(
             luminance_cd_m2,
             effective_tuning,
         )?;
+        // The gamma22/gamma24 presets are the specialized profiles themselves;
+        // they were just written and registered, so don't do it twice.
+        if matches!(
+            preset,
+            DynamicIccPreset::Gamma22 | DynamicIccPreset::Gamma24
+        ) {
+            return Ok(active_path);
+        }
     }
 
-    let active_path = color_dir.join(preset.profile_name(custom_profile_name));
     let gamma = preset.gamma(custom_gamma);
     let _ = ensure_profile_installed_with_gamma_luminance_and_tuning(
         &active_path,
@@ -2590,11 +2905,15 @@ pub fn ensure_mode_profiles_installed_tuned_for_monitor(
 }
 
 /// Re-apply the currently-active profile while also refreshing SDR/HDR display associations.
+///
+/// `hdr_profile_path` of `None` skips the HDR/advanced-color association.
+/// Associations are always written in both scopes (see below), so
+/// `_per_user` is only kept for symmetry with [`reapply_profile`].
 pub fn reapply_profile_with_mode_associations(
     device_key: &str,
     active_profile_path: &Path,
     sdr_profile_path: &Path,
-    hdr_profile_path: &Path,
+    hdr_profile_path: Option<&Path>,
     toggle_delay_ms: u64,
     _per_user: bool,
 ) -> Result<(), Box<dyn Error>> {
@@ -2604,13 +2923,15 @@ pub fn reapply_profile_with_mode_associations(
     if !sdr_profile_path.exists() {
         return Err(format!("SDR profile not found: {}", sdr_profile_path.display()).into());
     }
-    if !hdr_profile_path.exists() {
+    if let Some(hdr_profile_path) = hdr_profile_path.filter(|p| !p.exists()) {
         return Err(format!("HDR profile not found: {}", hdr_profile_path.display()).into());
     }
+    // Registered and re-associated only when it differs from the SDR profile.
+    let distinct_hdr_profile = hdr_profile_path.filter(|p| *p != sdr_profile_path);
 
     register_color_profile(active_profile_path)?;
     register_color_profile(sdr_profile_path)?;
-    if !hdr_profile_path.eq(sdr_profile_path) {
+    if let Some(hdr_profile_path) = distinct_hdr_profile {
         register_color_profile(hdr_profile_path)?;
     }
 
@@ -2624,7 +2945,9 @@ pub fn reapply_profile_with_mode_associations(
         // even when system-wide APIs report success.
         reapply_profile(device_key, active_profile_path, toggle_delay_ms, true)?;
         set_display_default_association(device_key, sdr_profile_path, true)?;
-        add_hdr_display_association(device_key, hdr_profile_path, true)?;
+        if let Some(hdr_profile_path) = hdr_profile_path {
+            add_hdr_display_association(device_key, hdr_profile_path, true)?;
+        }
         set_generic_default(device_key, sdr_profile_path, true)?;
         let icm_ok = match set_icm_profile_for_display_device(device_key, active_profile_path) {
             Ok(()) => true,
@@ -2696,7 +3019,7 @@ pub fn reapply_profile_with_mode_associations(
         if let Err(e) = associate_profile_with_device_legacy(device_key, sdr_profile_path) {
             warn!("Legacy SDR association fallback failed: {}", e);
         }
-        if !hdr_profile_path.eq(sdr_profile_path) {
+        if let Some(hdr_profile_path) = distinct_hdr_profile {
             if let Err(e) = associate_profile_with_device_legacy(device_key, hdr_profile_path) {
                 warn!("Legacy HDR association fallback failed: {}", e);
             }
@@ -2748,21 +3071,25 @@ fn associate_profile_with_device_legacy(
         return Err(format!("Profile not found: {}", profile_path.display()).into());
     }
 
-    let profile_wide: Vec<u16> = profile_path
-        .as_os_str()
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect();
-    let device_wide = to_wide(device_key);
+    let profile_wide = WideString::new(profile_path);
+    let device_wide = WideString::new(device_key);
 
     unsafe {
-        let result = AssociateColorProfileWithDeviceW(
-            PCWSTR(ptr::null()),
-            PCWSTR(profile_wide.as_ptr()),
-            PCWSTR(device_wide.as_ptr()),
+        let profile_label = profile_path.display().to_string();
+        let result = audited_association(
+            "associate_legacy",
+            WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
+            device_key,
+            &profile_label,
+            || {
+                win32_result(AssociateColorProfileWithDeviceW(
+                    PCWSTR(ptr::null()),
+                    profile_wide.as_pcwstr(),
+                    device_wide.as_pcwstr(),
+                ))
+            },
         );
-        if !result.as_bool() {
-            let err = io::Error::last_os_error();
+        if let Err(err) = result {
             return Err(format!(
                 "AssociateColorProfileWithDeviceW failed for {} (Win32={})",
                 device_key, err
@@ -2779,29 +3106,35 @@ fn associate_profile_with_device_legacy(
     Ok(())
 }
 
-/// Best-effort cleanup of legacy profile names from the Windows color store.
-fn cleanup_legacy_profile_files(active_profile_path: &Path) {
+/// Legacy profile files present in the color store, other than
+/// `active_profile_path` itself.
+fn legacy_profile_paths(active_profile_path: &Path) -> Vec<PathBuf> {
     if !is_in_color_directory(active_profile_path) {
-        return;
+        return Vec::new();
     }
 
     let Some(active_name) = active_profile_path
         .file_name()
         .map(|n| n.to_string_lossy().to_lowercase())
     else {
-        return;
+        return Vec::new();
     };
 
     let color_dir = color_directory();
-    for legacy_name in LEGACY_PROFILE_NAMES {
-        if active_name == *legacy_name {
-            continue;
-        }
-        let legacy_path = color_dir.join(legacy_name);
-        if !legacy_path.exists() {
-            continue;
-        }
-        match std::fs::remove_file(&legacy_path) {
+    LEGACY_PROFILE_NAMES
+        .iter()
+        .filter(|legacy_name| active_name != **legacy_name)
+        .map(|legacy_name| color_dir.join(legacy_name))
+        .filter(|legacy_path| legacy_path.exists())
+        .collect()
+}
+
+/// Best-effort cleanup of legacy profile names from the Windows color store.
+fn cleanup_legacy_profile_files(active_profile_path: &Path) {
+    for legacy_path in legacy_profile_paths(active_profile_path) {
+        match audit::file_change("remove_profile", &legacy_path, || {
+            std::fs::remove_file(&legacy_path)
+        }) {
             Ok(()) => info!(
                 "Removed legacy ICC profile from color store: {}",
                 legacy_path.display()
@@ -2815,6 +3148,77 @@ fn cleanup_legacy_profile_files(active_profile_path: &Path) {
     }
 }
 
+/// A process that has a file open, as reported by the Restart Manager.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockHolder {
+    pub pid: u32,
+    pub name: String,
+    /// Short service name when the holder is a service (e.g. `Spooler`).
+    pub service: Option<String>,
+}
+
+impl std::fmt::Display for LockHolder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (PID {}", self.name, self.pid)?;
+        if let Some(service) = &self.service {
+            write!(f, ", service {}", service)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// `InstallColorProfileW` kept failing with a sharing/lock violation.
+///
+/// Returned boxed like every other error here, so installers can
+/// `downcast_ref::<ProfileLockedError>()` to show who holds the file.
+pub struct ProfileLockedError {
+    pub path: PathBuf,
+    pub attempts: usize,
+    /// Win32 error from the last attempt.
+    pub os_error: i32,
+    /// Processes holding the file; empty when the Restart Manager found none
+    /// (the lock was released in the meantime, or it is held by the kernel).
+    pub holders: Vec<LockHolder>,
+}
+
+impl std::fmt::Display for ProfileLockedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "InstallColorProfileW could not register {} after {} attempts ({})",
+            self.path.display(),
+            self.attempts,
+            io::Error::from_raw_os_error(self.os_error)
+        )?;
+        if self.holders.is_empty() {
+            write!(f, "; no process holding it could be identified")
+        } else {
+            let names: Vec<String> = self.holders.iter().map(|h| h.to_string()).collect();
+            write!(f, "; held open by {}", names.join(", "))
+        }
+    }
+}
+
+// `main` prints errors with `{:?}` — keep it readable.
+impl std::fmt::Debug for ProfileLockedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+impl Error for ProfileLockedError {}
+
+/// Back-off between `InstallColorProfileW` attempts (total ~4 s).
+const REGISTER_RETRY_DELAYS_MS: &[u64] = &[0, 100, 250, 500, 1000, 2000];
+
+/// Sharing and lock violations are transient: the WCS engine, the spooler
+/// or an indexer is scanning the color store.
+fn is_lock_error(code: i32) -> bool {
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+    const ERROR_LOCK_VIOLATION: i32 = 33;
+    code == ERROR_SHARING_VIOLATION || code == ERROR_LOCK_VIOLATION
+}
+
 /// Register an ICC profile with the Windows Color System via
 /// `InstallColorProfileW` (mscms.dll).
 ///
@@ -2822,10 +3226,16 @@ fn cleanup_legacy_profile_files(active_profile_path: &Path) {
 /// Calling it on an already-registered profile is harmless.
 ///
 /// **Important:** `InstallColorProfileW` copies the file into the system color
-/// directory (`%WINDIR%\System32\spool\drivers\color`).  If the profile is
-/// *not* already in that directory, calling this would create an unwanted copy
-/// (e.g. from test paths).  To prevent that, this function is a no-op when the
-/// profile path is outside the color directory.
+/// directory (the one `GetColorDirectoryW` reports).  If the profile is
+/// *not* already in the color directory, calling this would create an
+/// unwanted copy (e.g. from test paths).  To prevent that, this function is a
+/// no-op when the profile path is outside [`color_directory`]. Profiles in a
+/// configured `color_directory` are copied into the system directory, which
+/// is where WCS looks them up.
+///
+/// Sharing violations are retried with back-off. If the file stays locked,
+/// the Restart Manager is asked who holds it and a [`ProfileLockedError`]
+/// is returned.
 pub fn register_color_profile(profile_path: &Path) -> Result<(), Box<dyn Error>> {
     if !is_in_color_directory(profile_path) {
         info!(
@@ -2838,36 +3248,141 @@ pub fn register_color_profile(profile_path: &Path) -> Result<(), Box<dyn Error>>
         return Err(format!("Profile not found: {}", profile_path.display()).into());
     }
 
-    let path_wide: Vec<u16> = profile_path
-        .as_os_str()
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect();
+    let path_wide = WideString::new(profile_path);
 
-    let install_ok =
-        unsafe { InstallColorProfileW(PCWSTR(ptr::null()), PCWSTR(path_wide.as_ptr())) };
-    if install_ok.as_bool() {
-        info!("Profile registered with WCS: {}", profile_path.display());
-        Ok(())
-    } else {
-        let code = io::Error::last_os_error();
-        Err(format!(
-            "InstallColorProfileW failed for {} ({})",
-            profile_path.display(),
-            code
-        )
-        .into())
+    let mut last_code = 0;
+    for (attempt, &ms) in REGISTER_RETRY_DELAYS_MS.iter().enumerate() {
+        if ms > 0 {
+            thread::sleep(Duration::from_millis(ms));
+        }
+        let result = win32_result(unsafe {
+            InstallColorProfileW(PCWSTR(ptr::null()), path_wide.as_pcwstr())
+        });
+        AuditEntry::new("wcs", "install_profile", profile_path.display().to_string())
+            .result(&result)
+            .record();
+        let Err(err) = result else {
+            info!(
+                "Profile registered with WCS: {} (attempt {})",
+                profile_path.display(),
+                attempt + 1
+            );
+            return Ok(());
+        };
+        last_code = err.raw_os_error().unwrap_or(0);
+        if !is_lock_error(last_code) {
+            return Err(format!(
+                "InstallColorProfileW failed for {} ({})",
+                profile_path.display(),
+                err
+            )
+            .into());
+        }
+        info!(
+            "Profile locked during registration (attempt {}): {} — retrying",
+            attempt + 1,
+            profile_path.display()
+        );
+    }
+
+    let holders = match lock_holders(profile_path) {
+        Ok(holders) => holders,
+        Err(e) => {
+            warn!(
+                "Restart Manager query failed for {}: {}",
+                profile_path.display(),
+                e
+            );
+            Vec::new()
+        }
+    };
+    let error = ProfileLockedError {
+        path: profile_path.to_path_buf(),
+        attempts: REGISTER_RETRY_DELAYS_MS.len(),
+        os_error: last_code,
+        holders,
+    };
+    warn!("{}", error);
+    Err(error.into())
+}
+
+fn wide_to_string(buf: &[u16]) -> String {
+    let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+    String::from_utf16_lossy(&buf[..len])
+}
+
+/// Ask the Restart Manager which processes have `path` open.
+pub fn lock_holders(path: &Path) -> Result<Vec<LockHolder>, Box<dyn Error>> {
+    use windows::Win32::Foundation::ERROR_MORE_DATA;
+    use windows::Win32::System::RestartManager::{
+        RmEndSession, RmGetList, RmRegisterResources, RmStartSession, CCH_RM_SESSION_KEY,
+        RM_PROCESS_INFO,
+    };
+
+    let path_wide = WideString::new(path);
+    let mut session = 0u32;
+    let mut key = [0u16; CCH_RM_SESSION_KEY as usize + 1];
+    let status = unsafe { RmStartSession(&mut session, 0, PWSTR(key.as_mut_ptr())) };
+    if status != ERROR_SUCCESS {
+        return Err(format!("RmStartSession failed ({})", status.0).into());
+    }
+
+    let result = (|| -> Result<Vec<LockHolder>, Box<dyn Error>> {
+        let files = [path_wide.as_pcwstr()];
+        let status = unsafe { RmRegisterResources(session, Some(&files), None, None) };
+        if status != ERROR_SUCCESS {
+            return Err(format!("RmRegisterResources failed ({})", status.0).into());
+        }
+
+        // The list can grow between the sizing call and the real one.
+        let mut infos: Vec<RM_PROCESS_INFO> = Vec::new();
+        for _ in 0..3 {
+            let mut needed = 0u32;
+            let mut count = infos.len() as u32;
+            let mut reasons = 0u32;
+            let status = unsafe {
+                RmGetList(
+                    session,
+                    &mut needed,
+                    &mut count,
+                    (!infos.is_empty()).then(|| infos.as_mut_ptr()),
+                    &mut reasons,
+                )
+            };
+            match status {
+                ERROR_SUCCESS => {
+                    infos.truncate(count as usize);
+                    return Ok(infos
+                        .iter()
+                        .map(|info| {
+                            let service = wide_to_string(&info.strServiceShortName);
+                            LockHolder {
+                                pid: info.Process.dwProcessId,
+                                name: wide_to_string(&info.strAppName),
+                                service: (!service.is_empty()).then_some(service),
+                            }
+                        })
+                        .collect());
+                }
+                ERROR_MORE_DATA => {
+                    infos = vec![RM_PROCESS_INFO::default(); needed as usize];
+                }
+                other => return Err(format!("RmGetList failed ({})", other.0).into()),
+            }
+        }
+        Err("RmGetList kept reporting more holders".into())
+    })();
+
+    unsafe {
+        let _ = RmEndSession(session);
     }
+    result
 }
 
-/// Return the Windows system color profile directory.
+/// Return the Windows color profile directory: the configured
+/// `color_directory`, or the one `GetColorDirectoryW` reports.
 pub fn color_directory() -> PathBuf {
-    let windir = std::env::var("WINDIR").unwrap_or_else(|_| r"C:\Windows".to_string());
-    PathBuf::from(windir)
-        .join("System32")
-        .join("spool")
-        .join("drivers")
-        .join("color")
+    lg_core::color_dir::directory()
 }
 
 /// Return the app-owned profile export directory.
@@ -2875,11 +3390,7 @@ pub fn color_directory() -> PathBuf {
 /// This mirrors generated profiles for easy inspection/backups outside
 /// the Windows color store.
 pub fn app_profiles_directory() -> PathBuf {
-    let program_data =
-        std::env::var("ProgramData").unwrap_or_else(|_| r"C:\ProgramData".to_string());
-    PathBuf::from(program_data)
-        .join("LG-UltraGear-Monitor")
-        .join("profiles")
+    lg_core::config::config_dir().join("profiles")
 }
 
 fn export_profile_to_app_profiles_dir(profile_path: &Path) -> Result<PathBuf, Box<dyn Error>> {
@@ -2897,90 +3408,102 @@ fn export_profile_to_app_profiles_dir(profile_path: &Path) -> Result<PathBuf, Bo
             return Ok(dst);
         }
     }
-    std::fs::write(&dst, src_bytes)?;
+    audit::file_change("export_profile", &dst, || std::fs::write(&dst, src_bytes))?;
     info!("Exported ICC profile artifact to {}", dst.display());
     Ok(dst)
 }
 
 /// Check whether `path` resides in the Windows color directory.
 fn is_in_color_directory(path: &Path) -> bool {
-    let color_dir = color_directory();
-    match path.parent() {
-        Some(parent) => {
-            // Case-insensitive comparison for Windows paths.
-            parent.to_string_lossy().to_lowercase() == color_dir.to_string_lossy().to_lowercase()
-        }
-        None => false,
-    }
+    let Some(parent) = path.parent() else {
+        return false;
+    };
+    // Case-insensitive comparison for Windows paths. The system directory
+    // counts even when `color_directory` points elsewhere.
+    let parent = parent.to_string_lossy().to_lowercase();
+    [color_directory(), lg_core::color_dir::system_directory()]
+        .iter()
+        .any(|dir| dir.to_string_lossy().to_lowercase() == parent)
 }
 
-/// Remove stale/leftover ICM files from the system color directory.
-///
-/// Scans for files that do NOT match `expected_name` and whose names
-/// match patterns known to come from test runs or previous versions of
-/// this tool.  Returns a list of paths that were deleted.
-pub fn cleanup_stale_profiles(expected_name: &str) -> Vec<PathBuf> {
-    let color_dir = color_directory();
-    let mut removed = Vec::new();
+/// Known stale file names from test runs and development.
+const STALE_PROFILE_NAMES: &[&str] = &[
+    "lg-ultragear-full-cal.icm",
+    "test-embedded.icm",
+    "edge-test.icm",
+    "wrong-size.icm",
+    "nested.icm",
+    "remove-test.icm",
+    "check.icm",
+    "test-extract.icm",
+    "test-idempotent.icm",
+    "test-roundtrip.icm",
+    "test-re-extract.icm",
+    "test-is-installed.icm",
+    "test-content.icm",
+    "test-overwrite.icm",
+    "register-test.icm",
+    "wrong.icm",
+    "size-check.icm",
+];
+
+/// Prefixes of monitor-scoped profiles written by older versions.
+const MONITOR_SCOPED_PROFILE_PREFIXES: &[&str] = &[
+    "lg-ultragear-gamma22-cmx-",
+    "lg-ultragear-gamma24-cmx-",
+    "lg-ultragear-reader-cmx-",
+    "lg-ultragear-dynamic-cmx-",
+];
+
+/// True for `.icm` names known to be leftovers from test runs or older
+/// versions of this tool.
+fn is_stale_profile_name(name: &str) -> bool {
+    let name_lower = name.to_lowercase();
+    STALE_PROFILE_NAMES.iter().any(|p| name_lower == *p)
+        || MONITOR_SCOPED_PROFILE_PREFIXES
+            .iter()
+            .any(|p| name_lower.starts_with(p))
+}
 
+/// Stale/leftover ICM files in the system color directory: files that do
+/// NOT match `expected_name` and whose names match patterns known to come
+/// from test runs or previous versions of this tool.
+pub fn stale_profiles(expected_name: &str) -> Vec<PathBuf> {
+    let color_dir = color_directory();
     let entries = match std::fs::read_dir(&color_dir) {
         Ok(entries) => entries,
         Err(e) => {
             warn!("Cannot read color directory {}: {}", color_dir.display(), e);
-            return removed;
+            return Vec::new();
         }
     };
 
-    // Known stale patterns from test runs and development.
-    let stale_patterns: &[&str] = &[
-        "lg-ultragear-full-cal.icm",
-        "test-embedded.icm",
-        "edge-test.icm",
-        "wrong-size.icm",
-        "nested.icm",
-        "remove-test.icm",
-        "check.icm",
-        "test-extract.icm",
-        "test-idempotent.icm",
-        "test-roundtrip.icm",
-        "test-re-extract.icm",
-        "test-is-installed.icm",
-        "test-content.icm",
-        "test-overwrite.icm",
-        "register-test.icm",
-        "wrong.icm",
-        "size-check.icm",
-    ];
-
-    for entry in entries.flatten() {
-        let name = entry.file_name();
-        let name_str = name.to_string_lossy();
-
-        // Skip the expected profile
-        if name_str.eq_ignore_ascii_case(expected_name) {
-            continue;
-        }
-
-        // Only consider .icm files
-        if !name_str.to_lowercase().ends_with(".icm") {
-            continue;
-        }
+    entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name_str = name.to_string_lossy();
+            // Skip the expected profile; only consider .icm files.
+            !name_str.eq_ignore_ascii_case(expected_name)
+                && name_str.to_lowercase().ends_with(".icm")
+                && is_stale_profile_name(&name_str)
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
 
-        let name_lower = name_str.to_lowercase();
-        let is_monitor_scoped_generated = name_lower.starts_with("lg-ultragear-gamma22-cmx-")
-            || name_lower.starts_with("lg-ultragear-gamma24-cmx-")
-            || name_lower.starts_with("lg-ultragear-reader-cmx-")
-            || name_lower.starts_with("lg-ultragear-dynamic-cmx-");
-        if stale_patterns.iter().any(|p| name_lower == *p) || is_monitor_scoped_generated {
-            let path = entry.path();
-            match std::fs::remove_file(&path) {
-                Ok(()) => {
-                    info!("Removed stale profile: {}", path.display());
-                    removed.push(path);
-                }
-                Err(e) => {
-                    warn!("Failed to remove stale profile {}: {}", path.display(), e);
-                }
+/// Remove the [`stale_profiles`] from the system color directory.
+/// Returns a list of paths that were deleted.
+pub fn cleanup_stale_profiles(expected_name: &str) -> Vec<PathBuf> {
+    let mut removed = Vec::new();
+    for path in stale_profiles(expected_name) {
+        match audit::file_change("remove_profile", &path, || std::fs::remove_file(&path)) {
+            Ok(()) => {
+                info!("Removed stale profile: {}", path.display());
+                removed.push(path);
+            }
+            Err(e) => {
+                warn!("Failed to remove stale profile {}: {}", path.display(), e);
             }
         }
     }
@@ -2993,19 +3516,1245 @@ pub fn cleanup_stale_profiles(expected_name: &str) -> Vec<PathBuf> {
 }
 
 // ============================================================================
-// mscms.dll FFI — WCS color profile APIs
+// Install planning
 // ============================================================================
 
-/// Check if the ICC profile is installed at the given path.
-pub fn is_profile_installed(profile_path: &Path) -> bool {
-    profile_path.exists()
-}
-
-/// Remove the ICC profile from the Windows color store.
-///
-/// Retries with exponential back-off if the file is locked (e.g. by the WCS
-/// engine or the service process).  After all retries, schedules the file for
-/// deletion on next reboot via `MoveFileExW(MOVEFILE_DELAY_UNTIL_REBOOT)`.
+/// Plan counterpart of
+/// [`ensure_profile_installed_with_gamma_luminance_tuning_identity_and_extra_tags`]:
+/// the write (unless the file is already current), WCS registration, legacy
+/// cleanup and export it would perform. Returns whether the file is written.
+pub fn plan_profile_install(
+    plan: &mut Plan,
+    profile_path: &Path,
+    gamma: f64,
+    luminance_cd_m2: f64,
+    tuning: DynamicIccTuning,
+    identity: Option<&DynamicMonitorIdentity>,
+) -> Result<bool, Box<dyn Error>> {
+    let generated = generate_dynamic_profile_bytes_with_luminance_tuning_identity_and_extra_tags(
+        sanitize_dynamic_gamma(gamma),
+        sanitize_dynamic_luminance_cd_m2(luminance_cd_m2),
+        tuning,
+        identity,
+        &[],
+    )?;
+    let written = plan.write_file("write_profile", profile_path, &generated);
+    if !is_in_color_directory(profile_path) {
+        return Ok(written);
+    }
+    plan.push(
+        OpKind::Wcs,
+        "install_profile",
+        profile_path.display().to_string(),
+    )
+    .detail(if written {
+        "InstallColorProfileW"
+    } else {
+        "InstallColorProfileW; file already current"
+    });
+    for legacy_path in legacy_profile_paths(profile_path) {
+        plan.remove_file("remove_profile", &legacy_path);
+    }
+    if let Some(name) = profile_path.file_name() {
+        plan.write_file(
+            "export_profile",
+            &app_profiles_directory().join(name),
+            &generated,
+        );
+    }
+    Ok(written)
+}
+
+/// Plan counterpart of [`ensure_active_profile_installed_tuned`], or of
+/// [`ensure_active_profile_installed_tuned_for_monitor`] with an `identity`.
+#[allow(clippy::too_many_arguments)]
+pub fn plan_active_profile_install(
+    plan: &mut Plan,
+    color_dir: &Path,
+    active_preset: &str,
+    custom_profile_name: &str,
+    custom_gamma: f64,
+    luminance_cd_m2: f64,
+    install_specialized_profiles: bool,
+    tuning: DynamicIccTuning,
+    identity: Option<&DynamicMonitorIdentity>,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let preset = parse_dynamic_icc_preset(active_preset);
+    let luminance_cd_m2 = sanitize_dynamic_luminance_cd_m2(luminance_cd_m2);
+    let base_name = preset.profile_name(custom_profile_name);
+    let active_path = match identity {
+        Some(identity) => resolve_monitor_scoped_profile_path(color_dir, &base_name, identity),
+        None => color_dir.join(&base_name),
+    };
+
+    if install_specialized_profiles {
+        for (name, gamma) in [
+            (GAMMA22_PROFILE_NAME, PRESET_GAMMA_22),
+            (GAMMA24_PROFILE_NAME, PRESET_GAMMA_24),
+        ] {
+            plan_profile_install(
+                plan,
+                &color_dir.join(name),
+                gamma,
+                luminance_cd_m2,
+                tuning,
+                None,
+            )?;
+        }
+        if identity.is_none()
+            && matches!(
+                preset,
+                DynamicIccPreset::Gamma22 | DynamicIccPreset::Gamma24
+            )
+        {
+            return Ok(active_path);
+        }
+    }
+
+    plan_profile_install(
+        plan,
+        &active_path,
+        preset.gamma(custom_gamma),
+        luminance_cd_m2,
+        tuning,
+        identity,
+    )?;
+    Ok(active_path)
+}
+
+/// Plan counterpart of [`ensure_mode_profiles_installed_tuned`], or of
+/// [`ensure_mode_profiles_installed_tuned_for_monitor`] with an `identity`.
+#[allow(clippy::too_many_arguments)]
+pub fn plan_mode_profiles_install(
+    plan: &mut Plan,
+    color_dir: &Path,
+    sdr_preset: &str,
+    hdr_preset: &str,
+    custom_profile_name: &str,
+    custom_gamma: f64,
+    luminance_cd_m2: f64,
+    install_specialized_profiles: bool,
+    tuning: DynamicIccTuning,
+    identity: Option<&DynamicMonitorIdentity>,
+) -> Result<(PathBuf, PathBuf), Box<dyn Error>> {
+    let plan_preset = |plan: &mut Plan, preset: &str| {
+        plan_active_profile_install(
+            plan,
+            color_dir,
+            preset,
+            custom_profile_name,
+            custom_gamma,
+            luminance_cd_m2,
+            install_specialized_profiles,
+            tuning,
+            identity,
+        )
+    };
+    let sdr_path = plan_preset(plan, sdr_preset)?;
+    let hdr_path = if sdr_preset.eq_ignore_ascii_case(hdr_preset) {
+        sdr_path.clone()
+    } else {
+        plan_preset(plan, hdr_preset)?
+    };
+    Ok((sdr_path, hdr_path))
+}
+
+/// Plan counterpart of one [`reapply_profile_with_mode_associations`] pass,
+/// followed by [`set_generic_default`] when `generic_default` is set.
+pub fn plan_mode_associations(
+    plan: &mut Plan,
+    device_key: &str,
+    active_profile_path: &Path,
+    sdr_profile_path: &Path,
+    hdr_profile_path: Option<&Path>,
+    generic_default: bool,
+    per_user: bool,
+) {
+    let name = |path: &Path| {
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string())
+    };
+    let target = |path: &Path| format!("{} {}", device_key, name(path));
+    let both_scopes = "system and user scope";
+
+    if !use_per_user_profiles(device_key).unwrap_or(false) {
+        plan.push(OpKind::Wcs, "set_use_per_user_profiles", device_key)
+            .detail("off -> on");
+    }
+    plan.push(OpKind::Wcs, "disassociate", target(active_profile_path))
+        .detail(both_scopes);
+    plan.push(OpKind::Wcs, "associate", target(active_profile_path))
+        .detail(both_scopes);
+    plan.push(OpKind::Wcs, "set_sdr_default", target(sdr_profile_path))
+        .detail(both_scopes);
+    if let Some(hdr_profile_path) = hdr_profile_path {
+        plan.push(
+            OpKind::Wcs,
+            "add_display_association",
+            target(hdr_profile_path),
+        )
+        .detail(both_scopes);
+        plan.push(OpKind::Wcs, "set_hdr_default", target(hdr_profile_path))
+            .detail(both_scopes);
+    }
+    plan.push(OpKind::Wcs, "set_default", target(sdr_profile_path))
+        .detail(both_scopes);
+    plan.push(
+        OpKind::Display,
+        "set_icm_profile",
+        target(active_profile_path),
+    )
+    .detail("SetICMProfileW");
+    plan.push(
+        OpKind::Display,
+        "set_gamma_ramp",
+        target(active_profile_path),
+    )
+    .detail("SetDeviceGammaRamp from the profile's vcgt");
+    if generic_default {
+        plan.push(OpKind::Wcs, "set_default", target(active_profile_path))
+            .detail(if per_user {
+                both_scopes
+            } else {
+                "system scope"
+            });
+    }
+}
+
+// ============================================================================
+// Profile store audit
+// ============================================================================
+
+/// Display devices (`HKLM\SYSTEM\CurrentControlSet\Enum\DISPLAY`); each
+/// instance's `Driver` value names its key under the monitor class.
+const DISPLAY_ENUM_KEY: &str = r"SYSTEM\CurrentControlSet\Enum\DISPLAY";
+
+/// Monitor device class; legacy `Wcs*` associations are the `ICMProfile`
+/// value of each driver key.
+const MONITOR_CLASS_KEY: &str = r"SYSTEM\CurrentControlSet\Control\Class";
+
+/// Prefix shared by every profile this tool writes.
+const OWN_PROFILE_PREFIX: &str = "lg-ultragear-";
+
+/// One problem found by [`audit_profile_store`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileAuditFinding {
+    /// Leftover file from a test run or an older version.
+    Stale { path: PathBuf },
+    /// Byte-identical copy of one of our profiles under another name.
+    Duplicate { path: PathBuf, original: PathBuf },
+    /// Association with a profile that is no longer in the color directory.
+    OrphanAssociation { device_key: String, profile: String },
+    /// One of our profiles still associated with a monitor that is not
+    /// connected (usually an old instance left behind by a driver update).
+    DisconnectedDevice { device_key: String, profile: String },
+}
+
+impl std::fmt::Display for ProfileAuditFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProfileAuditFinding::Stale { path } => {
+                write!(f, "stale profile {}", path.display())
+            }
+            ProfileAuditFinding::Duplicate { path, original } => write!(
+                f,
+                "{} is a copy of {}",
+                path.display(),
+                original.file_name().unwrap_or_default().to_string_lossy()
+            ),
+            ProfileAuditFinding::OrphanAssociation {
+                device_key,
+                profile,
+            } => write!(
+                f,
+                "{} is associated with missing file {}",
+                device_key, profile
+            ),
+            ProfileAuditFinding::DisconnectedDevice {
+                device_key,
+                profile,
+            } => write!(
+                f,
+                "{} is associated with disconnected monitor {}",
+                profile, device_key
+            ),
+        }
+    }
+}
+
+/// Profiles associated with one display device in the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceAssociations {
+    pub device_key: String,
+    pub profiles: Vec<String>,
+}
+
+fn is_own_profile_name(name: &str) -> bool {
+    name.to_lowercase().starts_with(OWN_PROFILE_PREFIX)
+}
+
+fn is_associated(name: &str, associations: &[DeviceAssociations]) -> bool {
+    associations
+        .iter()
+        .flat_map(|a| a.profiles.iter())
+        .any(|p| p.eq_ignore_ascii_case(name))
+}
+
+/// Group `files` by content fingerprint and report every file that shares
+/// content with one of our profiles but doesn't carry our name. Files that
+/// are associated with a device are left alone; removing them would break
+/// that association.
+fn find_duplicate_profiles(
+    files: &[(PathBuf, u64)],
+    associations: &[DeviceAssociations],
+) -> Vec<ProfileAuditFinding> {
+    let name_of = |path: &Path| {
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    let mut groups: HashMap<u64, Vec<&PathBuf>> = HashMap::new();
+    for (path, fingerprint) in files {
+        groups.entry(*fingerprint).or_default().push(path);
+    }
+
+    let mut findings = Vec::new();
+    for mut group in groups.into_values() {
+        if group.len() < 2 {
+            continue;
+        }
+        group.sort();
+        let Some(original) = group.iter().find(|p| is_own_profile_name(&name_of(p))) else {
+            continue;
+        };
+        for path in &group {
+            let name = name_of(path);
+            if is_own_profile_name(&name) || is_associated(&name, associations) {
+                continue;
+            }
+            findings.push(ProfileAuditFinding::Duplicate {
+                path: (*path).clone(),
+                original: (*original).clone(),
+            });
+        }
+    }
+    findings.sort_by_key(|f| f.to_string());
+    findings
+}
+
+/// Classify registry associations against the files in the color directory
+/// and the currently connected monitors.
+fn classify_associations(
+    associations: &[DeviceAssociations],
+    installed: &[String],
+    connected_device_keys: &[String],
+) -> Vec<ProfileAuditFinding> {
+    let mut findings = Vec::new();
+    for assoc in associations {
+        let connected = connected_device_keys
+            .iter()
+            .any(|k| device_keys_match(k, &assoc.device_key));
+        for profile in &assoc.profiles {
+            if !installed.iter().any(|i| i.eq_ignore_ascii_case(profile)) {
+                findings.push(ProfileAuditFinding::OrphanAssociation {
+                    device_key: assoc.device_key.clone(),
+                    profile: profile.clone(),
+                });
+            } else if !connected && is_own_profile_name(profile) {
+                findings.push(ProfileAuditFinding::DisconnectedDevice {
+                    device_key: assoc.device_key.clone(),
+                    profile: profile.clone(),
+                });
+            }
+        }
+    }
+    findings
+}
+
+/// Read the legacy per-device associations of every display Windows has
+/// ever enumerated, connected or not.
+pub fn read_device_associations() -> Result<Vec<DeviceAssociations>, Box<dyn Error>> {
+    use winreg::enums::HKEY_LOCAL_MACHINE;
+
+    let hklm = winreg::RegKey::predef(HKEY_LOCAL_MACHINE);
+    let display = hklm.open_subkey(DISPLAY_ENUM_KEY)?;
+    let mut out = Vec::new();
+    for model in display.enum_keys().flatten() {
+        let Ok(model_key) = display.open_subkey(&model) else {
+            continue;
+        };
+        for instance in model_key.enum_keys().flatten() {
+            let driver: String = match model_key
+                .open_subkey(&instance)
+                .and_then(|k| k.get_value("Driver"))
+            {
+                Ok(driver) => driver,
+                Err(_) => continue,
+            };
+            let profiles: Vec<String> = hklm
+                .open_subkey(format!(r"{}\{}", MONITOR_CLASS_KEY, driver))
+                .and_then(|k| k.get_value("ICMProfile"))
+                .unwrap_or_default();
+            if profiles.is_empty() {
+                continue;
+            }
+            out.push(DeviceAssociations {
+                device_key: format!(r"DISPLAY\{}\{}", model, instance),
+                profiles,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// Content fingerprint of a profile: length plus a hash of the bytes.
+fn profile_fingerprint(bytes: &[u8]) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    bytes.len().hash(&mut hasher);
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Look for stale files, duplicate copies of our profiles, associations
+/// with missing files, and our profiles left associated with monitors that
+/// are no longer connected. `expected_name` is never reported as stale.
+pub fn audit_profile_store(
+    expected_name: &str,
+    connected_device_keys: &[String],
+) -> Result<Vec<ProfileAuditFinding>, Box<dyn Error>> {
+    let color_dir = color_directory();
+    let associations = read_device_associations().unwrap_or_else(|e| {
+        warn!("Cannot read device associations: {}", e);
+        Vec::new()
+    });
+
+    let mut installed = Vec::new();
+    let mut fingerprints = Vec::new();
+    let mut findings = Vec::new();
+    for entry in std::fs::read_dir(&color_dir)?.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        installed.push(name.clone());
+
+        let lower = name.to_lowercase();
+        if !(lower.ends_with(".icm") || lower.ends_with(".icc")) {
+            continue;
+        }
+        if !name.eq_ignore_ascii_case(expected_name)
+            && is_stale_profile_name(&name)
+            && !is_associated(&name, &associations)
+        {
+            findings.push(ProfileAuditFinding::Stale { path });
+            continue;
+        }
+        match std::fs::read(&path) {
+            Ok(bytes) => fingerprints.push((path, profile_fingerprint(&bytes))),
+            Err(e) => warn!("Cannot read {}: {}", path.display(), e),
+        }
+    }
+    findings.sort_by_key(|f| f.to_string());
+
+    findings.extend(find_duplicate_profiles(&fingerprints, &associations));
+    findings.extend(classify_associations(
+        &associations,
+        &installed,
+        connected_device_keys,
+    ));
+    Ok(findings)
+}
+
+/// Remove `profile` from the `ICMProfile` list of `device_key` directly.
+/// Fallback for devices WCS refuses to touch (not present, or the file is
+/// already gone).
+fn remove_registry_association(device_key: &str, profile: &str) -> Result<(), Box<dyn Error>> {
+    let mut profiles = read_scope_profiles(Scope::SystemWide, &device_driver(device_key)?);
+    profiles.retain(|p| !p.eq_ignore_ascii_case(profile));
+    write_scope_profiles(device_key, Scope::SystemWide, &profiles)
+}
+
+/// Resolve one audit finding: delete the file, or drop the association.
+pub fn fix_profile_audit_finding(finding: &ProfileAuditFinding) -> Result<(), Box<dyn Error>> {
+    match finding {
+        ProfileAuditFinding::Stale { path } | ProfileAuditFinding::Duplicate { path, .. } => {
+            audit::file_change("remove_profile", path, || std::fs::remove_file(path))?;
+            info!("Removed {}", path.display());
+        }
+        ProfileAuditFinding::OrphanAssociation {
+            device_key,
+            profile,
+        }
+        | ProfileAuditFinding::DisconnectedDevice {
+            device_key,
+            profile,
+        } => {
+            remove_device_association(device_key, profile)?;
+        }
+    }
+    Ok(())
+}
+
+/// Drop the system-wide association of `profile` (a file name) from
+/// `device_key`, editing the registry directly when WCS refuses.
+pub fn remove_device_association(device_key: &str, profile: &str) -> Result<(), Box<dyn Error>> {
+    if let Err(e) = disassociate_profile(device_key, Path::new(profile), false) {
+        info!("{}; editing the association directly", e);
+        remove_registry_association(device_key, profile)?;
+    }
+    info!("Removed association {} -> {}", device_key, profile);
+    Ok(())
+}
+
+// ============================================================================
+// Color store backup
+// ============================================================================
+
+/// Per-user associations, one subkey per monitor driver key (the part of
+/// the `Driver` value after the class GUID).
+const USER_ASSOCIATIONS_KEY: &str =
+    r"Software\Microsoft\Windows NT\CurrentVersion\ICM\ProfileAssociations\Display";
+
+fn wcs_scope(scope: Scope) -> WCS_PROFILE_MANAGEMENT_SCOPE {
+    match scope {
+        Scope::SystemWide => WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
+        Scope::CurrentUser => WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
+    }
+}
+
+/// `(device_key, driver)` of every display Windows has ever enumerated.
+fn display_drivers() -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    use winreg::enums::HKEY_LOCAL_MACHINE;
+
+    let display = winreg::RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey(DISPLAY_ENUM_KEY)?;
+    let mut out = Vec::new();
+    for model in display.enum_keys().flatten() {
+        let Ok(model_key) = display.open_subkey(&model) else {
+            continue;
+        };
+        for instance in model_key.enum_keys().flatten() {
+            if let Ok(driver) = model_key
+                .open_subkey(&instance)
+                .and_then(|k| k.get_value::<String, _>("Driver"))
+            {
+                out.push((format!(r"DISPLAY\{}\{}", model, instance), driver));
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Registry key holding a driver's associations in `scope`.
+fn association_key_path(scope: Scope, driver: &str) -> (winreg::RegKey, String) {
+    use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+    match scope {
+        Scope::SystemWide => (
+            winreg::RegKey::predef(HKEY_LOCAL_MACHINE),
+            format!(r"{}\{}", MONITOR_CLASS_KEY, driver),
+        ),
+        Scope::CurrentUser => (
+            winreg::RegKey::predef(HKEY_CURRENT_USER),
+            format!(r"{}\{}", USER_ASSOCIATIONS_KEY, driver),
+        ),
+    }
+}
+
+fn read_scope_profiles(scope: Scope, driver: &str) -> Vec<String> {
+    let (root, path) = association_key_path(scope, driver);
+    root.open_subkey(path)
+        .and_then(|k| k.get_value("ICMProfile"))
+        .unwrap_or_default()
+}
+
+/// Snapshot every display's associations and defaults in both scopes.
+/// Displays without any association are left out unless connected; the
+/// SDR/HDR display defaults are only read for connected displays.
+pub fn capture_color_store(
+    connected_device_keys: &[String],
+) -> Result<ColorStoreBackup, Box<dyn Error>> {
+    let mut devices = Vec::new();
+    for (device_key, driver) in display_drivers()? {
+        let connected = connected_device_keys
+            .iter()
+            .any(|k| device_keys_match(k, &device_key));
+        let mut state = DeviceColorState {
+            device_key: device_key.clone(),
+            use_per_user_profiles: false,
+            system_wide: ScopeColorState {
+                profiles: read_scope_profiles(Scope::SystemWide, &driver),
+                ..Default::default()
+            },
+            current_user: ScopeColorState {
+                profiles: read_scope_profiles(Scope::CurrentUser, &driver),
+                ..Default::default()
+            },
+        };
+        state.use_per_user_profiles = use_per_user_profiles(&device_key).unwrap_or(false);
+        if !connected
+            && !state.use_per_user_profiles
+            && state.system_wide.profiles.is_empty()
+            && state.current_user.profiles.is_empty()
+        {
+            continue;
+        }
+
+        let target = if connected {
+            resolve_display_color_target(&device_key).unwrap_or(None)
+        } else {
+            None
+        };
+        for scope in [Scope::SystemWide, Scope::CurrentUser] {
+            let default = query_wcs_default_profile_name(&device_key, wcs_scope(scope))
+                .unwrap_or_else(|e| {
+                    warn!("{}", e);
+                    None
+                });
+            let (sdr_default, hdr_default) = match &target {
+                Some(target) => (
+                    query_display_default(
+                        target,
+                        wcs_scope(scope),
+                        CPST_STANDARD_DISPLAY_COLOR_MODE,
+                    ),
+                    query_display_default(
+                        target,
+                        wcs_scope(scope),
+                        CPST_EXTENDED_DISPLAY_COLOR_MODE,
+                    ),
+                ),
+                None => (None, None),
+            };
+            let slot = match scope {
+                Scope::SystemWide => &mut state.system_wide,
+                Scope::CurrentUser => &mut state.current_user,
+            };
+            slot.default = default;
+            slot.sdr_default = sdr_default;
+            slot.hdr_default = hdr_default;
+        }
+        devices.push(state);
+    }
+    Ok(ColorStoreBackup::new(devices))
+}
+
+/// Write a device's association list in `scope` exactly as given.
+fn write_scope_profiles(
+    device_key: &str,
+    scope: Scope,
+    profiles: &[String],
+) -> Result<(), Box<dyn Error>> {
+    use winreg::enums::{KEY_READ, KEY_SET_VALUE};
+
+    let driver = device_driver(device_key)?;
+    let before = read_scope_profiles(scope, &driver);
+    let (root, path) = association_key_path(scope, &driver);
+    let result = (|| -> io::Result<()> {
+        let key = match scope {
+            Scope::SystemWide => root.open_subkey_with_flags(&path, KEY_READ | KEY_SET_VALUE)?,
+            Scope::CurrentUser => root.create_subkey(&path)?.0,
+        };
+        if profiles.is_empty() {
+            match key.delete_value("ICMProfile") {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        } else {
+            key.set_value("ICMProfile", &profiles.to_vec())?;
+        }
+        Ok(())
+    })();
+    AuditEntry::new(
+        "registry",
+        "set_associations",
+        format!(r"{}\{}\ICMProfile", scope_root_label(scope), path),
+    )
+    .before(profile_list_label(&before))
+    .after(profile_list_label(&read_scope_profiles(scope, &driver)))
+    .result(&result)
+    .record();
+    Ok(result?)
+}
+
+// ============================================================================
+// Audit log
+// ============================================================================
+
+fn scope_root_label(scope: Scope) -> &'static str {
+    match scope {
+        Scope::SystemWide => "HKLM",
+        Scope::CurrentUser => "HKCU",
+    }
+}
+
+fn scope_for(scope: WCS_PROFILE_MANAGEMENT_SCOPE) -> Scope {
+    if scope == WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER {
+        Scope::CurrentUser
+    } else {
+        Scope::SystemWide
+    }
+}
+
+fn profile_list_label(profiles: &[String]) -> String {
+    if profiles.is_empty() {
+        "(none)".to_string()
+    } else {
+        profiles.join(", ")
+    }
+}
+
+/// `Driver` value (`{class-guid}\NNNN`) of a display device key.
+fn device_driver(device_key: &str) -> Result<String, Box<dyn Error>> {
+    use winreg::enums::HKEY_LOCAL_MACHINE;
+
+    let normalized = normalize_device_key(device_key);
+    let instance = normalized
+        .strip_prefix(r"DISPLAY\")
+        .ok_or_else(|| format!("Not a display device key: {}", device_key))?;
+    Ok(winreg::RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(format!(r"{}\{}", DISPLAY_ENUM_KEY, instance))?
+        .get_value("Driver")?)
+}
+
+/// `device_key`'s association list in `scope`, as the audit log shows it.
+fn association_state(device_key: &str, scope: Scope) -> String {
+    match device_driver(device_key) {
+        Ok(driver) => profile_list_label(&read_scope_profiles(scope, &driver)),
+        Err(_) => "unknown".to_string(),
+    }
+}
+
+/// `Ok` for a successful Win32 `BOOL`, else the thread's last error.
+fn win32_result(ok: BOOL) -> io::Result<()> {
+    if ok.as_bool() {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Run a WCS call that changes `device_key`'s associations in `scope` and
+/// record it with the device's association list before and after.
+fn audited_association(
+    action: &str,
+    scope: WCS_PROFILE_MANAGEMENT_SCOPE,
+    device_key: &str,
+    profile: &str,
+    call: impl FnOnce() -> io::Result<()>,
+) -> io::Result<()> {
+    let scope = scope_for(scope);
+    let before = association_state(device_key, scope);
+    let result = call();
+    AuditEntry::new(
+        "wcs",
+        action,
+        format!("{} {} ({})", device_key, profile, scope.label()),
+    )
+    .before(before)
+    .after(association_state(device_key, scope))
+    .result(&result)
+    .record();
+    result
+}
+
+/// Run a call that changes one of `device_key`'s default profiles and record
+/// it with `current()` before and after.
+fn audited_default(
+    action: &str,
+    scope: WCS_PROFILE_MANAGEMENT_SCOPE,
+    device_key: &str,
+    profile: &str,
+    current: impl Fn() -> Option<String>,
+    call: impl FnOnce() -> io::Result<()>,
+) -> io::Result<()> {
+    let label = |value: Option<String>| value.unwrap_or_else(|| "(none)".to_string());
+    let before = label(current());
+    let result = call();
+    AuditEntry::new(
+        "wcs",
+        action,
+        format!("{} {} ({})", device_key, profile, scope_for(scope).label()),
+    )
+    .before(before)
+    .after(label(current()))
+    .result(&result)
+    .record();
+    result
+}
+
+/// Carry out one step of a [`lg_core::colorstore::restore_plan`].
+pub fn apply_restore_change(change: &RestoreChange) -> Result<(), Box<dyn Error>> {
+    match change {
+        RestoreChange::SetProfiles {
+            device_key,
+            scope,
+            to,
+            ..
+        } => write_scope_profiles(device_key, *scope, to)?,
+        RestoreChange::SetUsePerUser {
+            device_key,
+            enabled,
+        } => {
+            set_use_per_user_profiles(device_key, *enabled).map_err(|e| {
+                format!(
+                    "WcsSetUsePerUserProfiles failed for {} (Win32={})",
+                    device_key, e
+                )
+            })?;
+        }
+        RestoreChange::SetDefault {
+            device_key,
+            scope,
+            kind: DefaultKind::Device,
+            profile,
+        } => {
+            let device_wide = WideString::new(device_key);
+            let profile_wide = WideString::new(profile);
+            let scope = wcs_scope(*scope);
+            audited_default(
+                "set_default",
+                scope,
+                device_key,
+                profile,
+                || {
+                    query_wcs_default_profile_name(device_key, scope)
+                        .ok()
+                        .flatten()
+                },
+                || unsafe {
+                    win32_result(WcsSetDefaultColorProfile(
+                        scope,
+                        device_wide.as_pcwstr(),
+                        CPT_ICC,
+                        CPST_NONE,
+                        0,
+                        profile_wide.as_pcwstr(),
+                    ))
+                },
+            )
+            .map_err(|e| {
+                format!(
+                    "WcsSetDefaultColorProfile failed for {} (Win32={})",
+                    device_key, e
+                )
+            })?;
+        }
+        RestoreChange::SetDefault {
+            device_key,
+            scope,
+            kind,
+            profile,
+        } => {
+            let set_display_default = modern_wcs_api()
+                .set_display_default
+                .ok_or("display defaults require Windows 10 1703+")?;
+            let target = resolve_display_color_target(device_key)?
+                .ok_or_else(|| format!("{} is not an active display", device_key))?;
+            let (action, subtype) = match kind {
+                DefaultKind::Hdr => ("set_hdr_default", CPST_EXTENDED_DISPLAY_COLOR_MODE),
+                _ => ("set_sdr_default", CPST_STANDARD_DISPLAY_COLOR_MODE),
+            };
+            let profile_wide = WideString::new(profile);
+            let scope = wcs_scope(*scope);
+            audited_default(
+                action,
+                scope,
+                device_key,
+                profile,
+                || query_display_default(&target, scope, subtype),
+                || unsafe {
+                    set_display_default(
+                        scope,
+                        profile_wide.as_pcwstr(),
+                        CPT_ICC,
+                        subtype,
+                        target.adapter_id,
+                        target.source_id,
+                    )
+                    .ok()
+                    .map_err(io::Error::other)
+                },
+            )?;
+        }
+    }
+    info!("Restored: {}", change);
+    Ok(())
+}
+
+// ============================================================================
+// Device key matching
+// ============================================================================
+
+/// Canonical form of a display device key for comparison: trimmed,
+/// upper-case, and without the `_N` suffix WMI appends to instance names.
+pub fn normalize_device_key(device_key: &str) -> String {
+    let trimmed = device_key.trim();
+    let key = match trimmed.rsplit_once('_') {
+        Some((key, suffix))
+            if !key.is_empty()
+                && !suffix.is_empty()
+                && suffix.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            key
+        }
+        _ => trimmed,
+    };
+    key.to_uppercase()
+}
+
+/// True when both keys name the same device instance.
+pub fn device_keys_match(a: &str, b: &str) -> bool {
+    normalize_device_key(a) == normalize_device_key(b)
+}
+
+/// Hardware ID segment of a normalized key (`GSM5BBF` in
+/// `DISPLAY\GSM5BBF\5&1A2B3C&0&UID4352`), i.e. the monitor model.
+fn device_key_hardware_id(normalized: &str) -> Option<&str> {
+    let mut parts = normalized.split('\\');
+    let _class = parts.next()?;
+    parts.next().filter(|id| !id.is_empty())
+}
+
+/// `UID` part of a normalized key's instance ID. It identifies the
+/// connector and usually survives a driver reinstall, while the
+/// bus-relative prefix before it does not.
+fn device_key_uid(normalized: &str) -> Option<&str> {
+    normalized
+        .rsplit('\\')
+        .next()?
+        .rsplit('&')
+        .next()
+        .filter(|part| part.starts_with("UID"))
+}
+
+/// Find the associations `device_key` most likely had before a driver
+/// update gave it a new instance ID: a device of the same model that is no
+/// longer connected. When several qualify, one on the same connector (`UID`)
+/// wins; otherwise the match is ambiguous and `None` is returned rather
+/// than guessing.
+pub fn find_previous_device_associations<'a>(
+    device_key: &str,
+    associations: &'a [DeviceAssociations],
+    connected_device_keys: &[String],
+) -> Option<&'a DeviceAssociations> {
+    let normalized = normalize_device_key(device_key);
+    let hardware_id = device_key_hardware_id(&normalized)?;
+    let candidates: Vec<(&DeviceAssociations, String)> = associations
+        .iter()
+        .filter(|a| !a.profiles.is_empty())
+        .filter(|a| {
+            !connected_device_keys
+                .iter()
+                .any(|k| device_keys_match(k, &a.device_key))
+                && !device_keys_match(&a.device_key, device_key)
+        })
+        .map(|a| (a, normalize_device_key(&a.device_key)))
+        .filter(|(_, key)| device_key_hardware_id(key) == Some(hardware_id))
+        .collect();
+
+    if let [(only, _)] = candidates.as_slice() {
+        return Some(*only);
+    }
+    let uid = device_key_uid(&normalized)?;
+    let mut same_uid = candidates
+        .iter()
+        .filter(|(_, key)| device_key_uid(key) == Some(uid));
+    match (same_uid.next(), same_uid.next()) {
+        (Some((found, _)), None) => Some(*found),
+        _ => None,
+    }
+}
+
+/// Give `device_key` the associations of its previous instance when it has
+/// none of its own, so a driver update doesn't silently drop calibration
+/// profiles the user had associated. Returns the previous instance with the
+/// profiles that were carried over, or `None` when there was nothing to do.
+pub fn inherit_previous_device_associations(
+    device_key: &str,
+    connected_device_keys: &[String],
+) -> Result<Option<DeviceAssociations>, Box<dyn Error>> {
+    let associations = read_device_associations()?;
+    if associations
+        .iter()
+        .any(|a| device_keys_match(&a.device_key, device_key) && !a.profiles.is_empty())
+    {
+        return Ok(None);
+    }
+    let Some(previous) =
+        find_previous_device_associations(device_key, &associations, connected_device_keys)
+    else {
+        return Ok(None);
+    };
+
+    let color_dir = color_directory();
+    let mut carried = Vec::new();
+    for profile in &previous.profiles {
+        let path = color_dir.join(profile);
+        if !path.exists() {
+            continue;
+        }
+        match associate_profile_with_device_legacy(device_key, &path) {
+            Ok(()) => carried.push(profile.clone()),
+            Err(e) => warn!("Could not carry {} over to {}: {}", profile, device_key, e),
+        }
+    }
+    if carried.is_empty() {
+        return Ok(None);
+    }
+    info!(
+        "Carried {} association(s) over from {} to {}",
+        carried.len(),
+        previous.device_key,
+        device_key
+    );
+    Ok(Some(DeviceAssociations {
+        device_key: previous.device_key.clone(),
+        profiles: carried,
+    }))
+}
+
+// ============================================================================
+// Scoped profile install
+// ============================================================================
+
+/// Copy the ICC/ICM file `source` into the color store for `scope` and
+/// register it there. Returns the installed path.
+///
+/// System-wide, the file goes to the directory `GetColorDirectoryW` reports
+/// and is registered with `InstallColorProfileW`, which needs administrator
+/// rights. Per user, it goes to the user store (the configured
+/// `color_directory`, or one under `%LOCALAPPDATA%`) without registration:
+/// WCS only registers profiles machine-wide, so no elevation is needed and
+/// the file is there for anything that takes a profile path.
+pub fn install_profile_for_scope(source: &Path, scope: Scope) -> Result<PathBuf, Box<dyn Error>> {
+    let bytes =
+        std::fs::read(source).map_err(|e| format!("cannot read {}: {}", source.display(), e))?;
+    let validation = validate_icc_profile_bytes(&bytes);
+    if !validation.is_valid() {
+        return Err(format!(
+            "{} is not a valid ICC profile: {}",
+            source.display(),
+            validation.errors.join("; ")
+        )
+        .into());
+    }
+    let name = source
+        .file_name()
+        .ok_or_else(|| format!("{} has no file name", source.display()))?;
+    let dir = lg_core::color_dir::directory_for(scope);
+    let dest = dir.join(name);
+    if std::fs::read(&dest).ok().as_deref() != Some(&bytes[..]) {
+        std::fs::create_dir_all(&dir)?;
+        audit::file_change("install_profile", &dest, || std::fs::write(&dest, &bytes))?;
+        info!(
+            "Profile copied to {} ({} store)",
+            dest.display(),
+            scope.label()
+        );
+    }
+    if scope == Scope::SystemWide {
+        register_color_profile(&dest)?;
+    }
+    Ok(dest)
+}
+
+// ============================================================================
+// Installed profile lookup
+// ============================================================================
+
+/// File names of every profile installed in the color store, sorted
+/// case-insensitively.
+pub fn installed_color_profiles() -> Result<Vec<String>, Box<dyn Error>> {
+    use windows::Win32::UI::ColorSystem::{EnumColorProfilesW, ENUMTYPEW, ENUM_TYPE_VERSION};
+
+    let record = ENUMTYPEW {
+        dwSize: std::mem::size_of::<ENUMTYPEW>() as u32,
+        dwVersion: ENUM_TYPE_VERSION,
+        ..Default::default()
+    };
+    let mut size = 0u32;
+    // The first call only reports the buffer size.
+    unsafe {
+        let _ = EnumColorProfilesW(PCWSTR::null(), &record, None, &mut size, None);
+    }
+    if size == 0 {
+        return Ok(Vec::new());
+    }
+    let mut buf = vec![0u16; (size as usize).div_ceil(2)];
+    unsafe {
+        EnumColorProfilesW(
+            PCWSTR::null(),
+            &record,
+            Some(buf.as_mut_ptr().cast()),
+            &mut size,
+            None,
+        )
+        .ok()?;
+    }
+    let mut names = parse_multi_sz(&buf);
+    names.sort_by_key(|n| n.to_lowercase());
+    Ok(names)
+}
+
+/// Split a `REG_MULTI_SZ`-style UTF-16 buffer into its strings.
+fn parse_multi_sz(buf: &[u16]) -> Vec<String> {
+    buf.split(|&c| c == 0)
+        .take_while(|s| !s.is_empty())
+        .map(String::from_utf16_lossy)
+        .collect()
+}
+
+/// The entry of `installed` that `name` refers to: an exact
+/// case-insensitive match, or the name with `.icm`/`.icc` added.
+pub fn match_installed_profile<'a>(name: &str, installed: &'a [String]) -> Option<&'a str> {
+    let name = name.trim();
+    installed
+        .iter()
+        .find(|p| p.eq_ignore_ascii_case(name))
+        .or_else(|| {
+            installed.iter().find(|p| {
+                [".icm", ".icc"]
+                    .iter()
+                    .any(|ext| p.eq_ignore_ascii_case(&format!("{}{}", name, ext)))
+            })
+        })
+        .map(String::as_str)
+}
+
+/// Path in the color directory of the installed profile called `name`.
+pub fn resolve_installed_profile(name: &str) -> Result<PathBuf, Box<dyn Error>> {
+    if name.contains(['\\', '/']) {
+        return Err(format!(
+            "'{}' is a path, not an installed profile name (use --profile-path)",
+            name
+        )
+        .into());
+    }
+    let installed = installed_color_profiles()?;
+    match match_installed_profile(name, &installed) {
+        Some(found) => Ok(color_directory().join(found)),
+        None => Err(format!(
+            "no installed color profile named '{}' (see `profile list`)",
+            name
+        )
+        .into()),
+    }
+}
+
+// ============================================================================
+// mscms.dll FFI — WCS color profile APIs
+// ============================================================================
+
+// `ColorProfileSetDisplayDefaultAssociation`, `ColorProfileAddDisplayAssociation`
+// and `ColorProfileGetDisplayDefault` only exist on Windows 10 1703+. A static
+// import of any of them keeps the binary from loading at all on older (LTSB)
+// builds, so they are resolved at runtime and callers fall back to the legacy
+// `Wcs*` device-association path when they are missing.
+
+type ColorProfileSetDisplayDefaultAssociationFn = unsafe extern "system" fn(
+    WCS_PROFILE_MANAGEMENT_SCOPE,
+    PCWSTR,
+    COLORPROFILETYPE,
+    COLORPROFILESUBTYPE,
+    LUID,
+    u32,
+) -> HRESULT;
+
+type ColorProfileAddDisplayAssociationFn = unsafe extern "system" fn(
+    WCS_PROFILE_MANAGEMENT_SCOPE,
+    PCWSTR,
+    LUID,
+    u32,
+    BOOL,
+    BOOL,
+) -> HRESULT;
+
+type ColorProfileGetDisplayDefaultFn = unsafe extern "system" fn(
+    WCS_PROFILE_MANAGEMENT_SCOPE,
+    LUID,
+    u32,
+    COLORPROFILETYPE,
+    COLORPROFILESUBTYPE,
+    *mut PWSTR,
+) -> HRESULT;
+
+/// Runtime-resolved entry points for the Win10 1703+ display-association APIs.
+#[derive(Clone, Copy, Default)]
+struct ModernWcsApi {
+    set_display_default: Option<ColorProfileSetDisplayDefaultAssociationFn>,
+    add_display_association: Option<ColorProfileAddDisplayAssociationFn>,
+    get_display_default: Option<ColorProfileGetDisplayDefaultFn>,
+}
+
+static MODERN_WCS_API: OnceLock<ModernWcsApi> = OnceLock::new();
+
+/// Which association API family this system supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WcsApiPath {
+    /// `ColorProfile*` display associations (Windows 10 1703+).
+    Modern,
+    /// `Wcs*` device associations only (older builds).
+    Legacy,
+}
+
+impl WcsApiPath {
+    pub fn label(self) -> &'static str {
+        match self {
+            WcsApiPath::Modern => "modern (ColorProfile* display associations)",
+            WcsApiPath::Legacy => "legacy (Wcs* device associations)",
+        }
+    }
+}
+
+fn wcs_api_path_for(api: &ModernWcsApi) -> WcsApiPath {
+    if api.set_display_default.is_some() {
+        WcsApiPath::Modern
+    } else {
+        WcsApiPath::Legacy
+    }
+}
+
+fn modern_wcs_api() -> &'static ModernWcsApi {
+    MODERN_WCS_API.get_or_init(|| {
+        let api = unsafe { resolve_modern_wcs_api() };
+        info!("WCS API path: {}", wcs_api_path_for(&api).label());
+        api
+    })
+}
+
+unsafe fn resolve_modern_wcs_api() -> ModernWcsApi {
+    let Ok(module) = LoadLibraryW(w!("mscms.dll")) else {
+        warn!("mscms.dll could not be loaded — modern WCS APIs unavailable");
+        return ModernWcsApi::default();
+    };
+    let lookup = |name: PCSTR| GetProcAddress(module, name);
+    ModernWcsApi {
+        set_display_default: lookup(s!("ColorProfileSetDisplayDefaultAssociation"))
+            .map(|f| std::mem::transmute::<_, ColorProfileSetDisplayDefaultAssociationFn>(f)),
+        add_display_association: lookup(s!("ColorProfileAddDisplayAssociation"))
+            .map(|f| std::mem::transmute::<_, ColorProfileAddDisplayAssociationFn>(f)),
+        get_display_default: lookup(s!("ColorProfileGetDisplayDefault"))
+            .map(|f| std::mem::transmute::<_, ColorProfileGetDisplayDefaultFn>(f)),
+    }
+}
+
+/// Report which association API path is in use (resolved once, then cached).
+pub fn wcs_api_path() -> WcsApiPath {
+    wcs_api_path_for(modern_wcs_api())
+}
+
+/// Check if the ICC profile is installed at the given path.
+pub fn is_profile_installed(profile_path: &Path) -> bool {
+    profile_path.exists()
+}
+
+/// Remove the ICC profile from the Windows color store.
+///
+/// Retries with exponential back-off if the file is locked (e.g. by the WCS
+/// engine or the service process).  After all retries, schedules the file for
+/// deletion on next reboot via `MoveFileExW(MOVEFILE_DELAY_UNTIL_REBOOT)`.
 ///
 /// Returns `Ok(true)` if the file was removed (or scheduled for removal),
 /// `Ok(false)` if it didn't exist.
@@ -3023,7 +4772,9 @@ pub fn remove_profile(profile_path: &Path) -> Result<bool, Box<dyn Error>> {
         if ms > 0 {
             thread::sleep(Duration::from_millis(ms));
         }
-        match std::fs::remove_file(profile_path) {
+        match audit::file_change("remove_profile", profile_path, || {
+            std::fs::remove_file(profile_path)
+        }) {
             Ok(()) => {
                 info!(
                     "ICC profile removed: {} (attempt {})",
@@ -3047,12 +4798,8 @@ pub fn remove_profile(profile_path: &Path) -> Result<bool, Box<dyn Error>> {
     }
 
     // Last resort: schedule for deletion on next reboot.
-    let wide: Vec<u16> = profile_path
-        .as_os_str()
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect();
-    let ok = unsafe { MoveFileExW(PCWSTR(wide.as_ptr()), None, MOVEFILE_DELAY_UNTIL_REBOOT) };
+    let wide = WideString::new(profile_path);
+    let ok = unsafe { MoveFileExW(wide.as_pcwstr(), None, MOVEFILE_DELAY_UNTIL_REBOOT) };
     match ok {
         Ok(()) => {
             warn!(
@@ -3070,38 +4817,57 @@ pub fn remove_profile(profile_path: &Path) -> Result<bool, Box<dyn Error>> {
     }
 }
 
-fn enable_per_user_monitor_profiles(device_key: &str) {
-    let device_wide = to_wide(device_key);
+fn use_per_user_profiles(device_key: &str) -> io::Result<bool> {
+    let device_wide = WideString::new(device_key);
     let mut enabled = BOOL::from(false);
-    let get_ok = unsafe {
+    win32_result(unsafe {
         WcsGetUsePerUserProfiles(
-            PCWSTR(device_wide.as_ptr()),
+            device_wide.as_pcwstr(),
             CLASS_MONITOR_SIGNATURE,
             &mut enabled,
         )
-    };
-    if !get_ok.as_bool() {
-        let err = io::Error::last_os_error();
-        warn!(
-            "WcsGetUsePerUserProfiles failed for {} (class='mntr', err={})",
-            device_key, err
-        );
-        return;
-    }
-
-    if enabled.as_bool() {
-        return;
-    }
+    })?;
+    Ok(enabled.as_bool())
+}
 
-    let set_ok = unsafe {
+/// `WcsSetUsePerUserProfiles` for a monitor, audited.
+fn set_use_per_user_profiles(device_key: &str, enabled: bool) -> io::Result<()> {
+    let state = || match use_per_user_profiles(device_key) {
+        Ok(true) => "on",
+        Ok(false) => "off",
+        Err(_) => "unknown",
+    };
+    let before = state();
+    let device_wide = WideString::new(device_key);
+    let result = win32_result(unsafe {
         WcsSetUsePerUserProfiles(
-            PCWSTR(device_wide.as_ptr()),
+            device_wide.as_pcwstr(),
             CLASS_MONITOR_SIGNATURE,
-            BOOL::from(true),
+            BOOL::from(enabled),
         )
-    };
-    if !set_ok.as_bool() {
-        let err = io::Error::last_os_error();
+    });
+    AuditEntry::new("wcs", "set_use_per_user_profiles", device_key)
+        .before(before)
+        .after(state())
+        .result(&result)
+        .record();
+    result
+}
+
+fn enable_per_user_monitor_profiles(device_key: &str) {
+    match use_per_user_profiles(device_key) {
+        Ok(true) => return,
+        Ok(false) => {}
+        Err(err) => {
+            warn!(
+                "WcsGetUsePerUserProfiles failed for {} (class='mntr', err={})",
+                device_key, err
+            );
+            return;
+        }
+    }
+
+    if let Err(err) = set_use_per_user_profiles(device_key, true) {
         warn!(
             "WcsSetUsePerUserProfiles failed for {} (class='mntr', err={})",
             device_key, err
@@ -3115,12 +4881,12 @@ fn query_wcs_default_profile_name(
     device_key: &str,
     scope: WCS_PROFILE_MANAGEMENT_SCOPE,
 ) -> Result<Option<String>, Box<dyn Error>> {
-    let device_wide = to_wide(device_key);
+    let device_wide = WideString::new(device_key);
     let mut size_bytes = 0u32;
     let size_ok = unsafe {
         WcsGetDefaultColorProfileSize(
             scope,
-            PCWSTR(device_wide.as_ptr()),
+            device_wide.as_pcwstr(),
             CPT_ICC,
             CPST_NONE,
             0,
@@ -3148,7 +4914,7 @@ fn query_wcs_default_profile_name(
     let ok = unsafe {
         WcsGetDefaultColorProfile(
             scope,
-            PCWSTR(device_wide.as_ptr()),
+            device_wide.as_pcwstr(),
             CPT_ICC,
             CPST_NONE,
             0,
@@ -3334,43 +5100,55 @@ fn canonical_profile_file_name(value: &str) -> String {
         .unwrap_or_else(|| trimmed.to_string())
 }
 
-fn query_display_default_profile_name(
-    device_key: &str,
+/// SDR or HDR display default of an active display in `scope`.
+fn query_display_default(
+    target: &DisplayColorTarget,
     scope: WCS_PROFILE_MANAGEMENT_SCOPE,
-) -> Result<Option<String>, Box<dyn Error>> {
-    let Some(target) = resolve_display_color_target(device_key)? else {
-        return Err(format!(
-            "Could not resolve active display path for device key '{}'",
-            device_key
-        )
-        .into());
-    };
-
-    let profile = unsafe {
-        ColorProfileGetDisplayDefault(
+    subtype: COLORPROFILESUBTYPE,
+) -> Option<String> {
+    let get_display_default = modern_wcs_api().get_display_default?;
+    let mut profile_ptr = PWSTR::null();
+    let hr = unsafe {
+        get_display_default(
             scope,
             target.adapter_id,
             target.source_id,
             CPT_ICC,
-            CPST_STANDARD_DISPLAY_COLOR_MODE,
+            subtype,
+            &mut profile_ptr,
         )
     };
-    let Ok(profile_ptr) = profile else {
-        return Ok(None);
-    };
-    if profile_ptr.is_null() {
-        return Ok(None);
+    if hr.is_err() || profile_ptr.is_null() {
+        return None;
     }
-
     let value = unsafe { PCWSTR(profile_ptr.0).to_string().unwrap_or_default() };
     unsafe {
         let _ = LocalFree(windows::Win32::Foundation::HLOCAL(profile_ptr.0 as *mut _));
     }
     let canonical = canonical_profile_file_name(&value);
-    if canonical.trim().is_empty() {
+    (!canonical.trim().is_empty()).then_some(canonical)
+}
+
+fn query_display_default_profile_name(
+    device_key: &str,
+    scope: WCS_PROFILE_MANAGEMENT_SCOPE,
+) -> Result<Option<String>, Box<dyn Error>> {
+    // Legacy systems: callers fall through to the Wcs* default query.
+    if modern_wcs_api().get_display_default.is_none() {
         return Ok(None);
     }
-    Ok(Some(canonical))
+    let Some(target) = resolve_display_color_target(device_key)? else {
+        return Err(format!(
+            "Could not resolve active display path for device key '{}'",
+            device_key
+        )
+        .into());
+    };
+    Ok(query_display_default(
+        &target,
+        scope,
+        CPST_STANDARD_DISPLAY_COLOR_MODE,
+    ))
 }
 
 fn set_icm_profile_for_display_device(
@@ -3392,18 +5170,14 @@ fn set_icm_profile_for_display_device(
         .into());
     };
 
-    let driver_wide = to_wide("DISPLAY");
-    let gdi_wide = to_wide(gdi_name);
-    let profile_wide: Vec<u16> = profile_path
-        .as_os_str()
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect();
+    let driver_wide = WideString::new("DISPLAY");
+    let gdi_wide = WideString::new(gdi_name);
+    let profile_wide = WideString::new(profile_path);
 
     unsafe {
         let hdc = CreateDCW(
-            PCWSTR(driver_wide.as_ptr()),
-            PCWSTR(gdi_wide.as_ptr()),
+            driver_wide.as_pcwstr(),
+            gdi_wide.as_pcwstr(),
             PCWSTR(ptr::null()),
             None,
         );
@@ -3412,7 +5186,7 @@ fn set_icm_profile_for_display_device(
             return Err(format!("CreateDCW failed for {} ({})", gdi_name, err).into());
         }
 
-        let set_ok = SetICMProfileW(hdc, PCWSTR(profile_wide.as_ptr()));
+        let set_ok = SetICMProfileW(hdc, profile_wide.as_pcwstr());
 
         // Best-effort readback verification from GDI.
         let mut size = 1024u32;
@@ -3546,7 +5320,7 @@ fn apply_vcgt_gamma_ramp_from_profile(
     profile_path: &Path,
 ) -> Result<Option<()>, Box<dyn Error>> {
     let bytes = std::fs::read(profile_path)?;
-    let raw = RawProfile::from_bytes(&bytes)?;
+    let raw = parse_raw_profile(&bytes)?;
     let Some(record) = raw.tags.get(&TagSignature::Vcgt) else {
         return Ok(None);
     };
@@ -3568,12 +5342,12 @@ fn apply_vcgt_gamma_ramp_from_profile(
         .into());
     };
 
-    let driver_wide = to_wide("DISPLAY");
-    let gdi_wide = to_wide(gdi_name);
+    let driver_wide = WideString::new("DISPLAY");
+    let gdi_wide = WideString::new(gdi_name);
     unsafe {
         let hdc = CreateDCW(
-            PCWSTR(driver_wide.as_ptr()),
-            PCWSTR(gdi_wide.as_ptr()),
+            driver_wide.as_pcwstr(),
+            gdi_wide.as_pcwstr(),
             PCWSTR(ptr::null()),
             None,
         );
@@ -3597,6 +5371,62 @@ fn apply_vcgt_gamma_ramp_from_profile(
     Ok(Some(()))
 }
 
+/// Read back the current gamma ramp (256 red, green, then blue entries) of
+/// the display driving `device_key`.
+pub fn read_gamma_ramp(device_key: &str) -> Result<Vec<u16>, Box<dyn Error>> {
+    let Some(target) = resolve_display_color_target(device_key)? else {
+        return Err(format!(
+            "Could not resolve active display path for device key '{}'",
+            device_key
+        )
+        .into());
+    };
+    let Some(gdi_name) = target.gdi_device_name.as_ref() else {
+        return Err(format!(
+            "Could not resolve GDI display name for device key '{}'",
+            device_key
+        )
+        .into());
+    };
+
+    let driver_wide = WideString::new("DISPLAY");
+    let gdi_wide = WideString::new(gdi_name);
+    let mut ramp = vec![0u16; CURVE_TABLE_SIZE * 3];
+    unsafe {
+        let hdc = CreateDCW(
+            driver_wide.as_pcwstr(),
+            gdi_wide.as_pcwstr(),
+            PCWSTR(ptr::null()),
+            None,
+        );
+        if hdc.0.is_null() {
+            let err = io::Error::last_os_error();
+            return Err(format!("CreateDCW failed for {} ({})", gdi_name, err).into());
+        }
+
+        let ok = GetDeviceGammaRamp(hdc, ramp.as_mut_ptr() as *mut core::ffi::c_void);
+        let _ = DeleteDC(hdc);
+        if !ok.as_bool() {
+            let err = io::Error::last_os_error();
+            return Err(format!("GetDeviceGammaRamp failed for {} ({})", gdi_name, err).into());
+        }
+    }
+    Ok(ramp)
+}
+
+/// Largest per-entry difference between two gamma ramps. Ramps of different
+/// lengths are treated as completely different.
+pub fn gamma_ramp_deviation(a: &[u16], b: &[u16]) -> u16 {
+    if a.len() != b.len() {
+        return u16::MAX;
+    }
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| x.abs_diff(*y))
+        .max()
+        .unwrap_or(0)
+}
+
 fn verify_wcs_default_profile_name(
     device_key: &str,
     expected_profile_path: &Path,
@@ -3644,6 +5474,17 @@ fn verify_wcs_default_profile_name(
     }
 }
 
+/// File name of the system-wide default profile currently associated with
+/// `device_key`, preferring the modern display default when available.
+pub fn current_default_profile_name(device_key: &str) -> Result<Option<String>, Box<dyn Error>> {
+    if let Some(name) =
+        query_display_default_profile_name(device_key, WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE)?
+    {
+        return Ok(Some(name));
+    }
+    query_wcs_default_profile_name(device_key, WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE)
+}
+
 /// Reapply the color profile for a single monitor device key using the toggle
 /// approach: disassociate (reverts to default) → pause → reassociate (applies fix).
 /// This forces Windows to actually reload the ICC profile.
@@ -3668,22 +5509,27 @@ pub fn reapply_profile(
     let profile_name = profile_path
         .file_name()
         .ok_or_else(|| format!("Invalid profile path: {}", profile_path.display()))?;
-    let profile_wide: Vec<u16> = profile_name
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect();
-    let device_wide = to_wide(device_key);
+    let profile_wide = WideString::new(profile_name);
+    let profile_label = profile_name.to_string_lossy();
+    let device_wide = WideString::new(device_key);
 
     unsafe {
         // Step 1: Disassociate (reverts to default profile)
         // Failure here is non-fatal — the profile may not be currently associated.
-        let result = WcsDisassociateColorProfileFromDevice(
+        let result = audited_association(
+            "disassociate",
             WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
-            PCWSTR(profile_wide.as_ptr()),
-            PCWSTR(device_wide.as_ptr()),
+            device_key,
+            &profile_label,
+            || {
+                win32_result(WcsDisassociateColorProfileFromDevice(
+                    WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
+                    profile_wide.as_pcwstr(),
+                    device_wide.as_pcwstr(),
+                ))
+            },
         );
-        if !result.as_bool() {
-            let err = io::Error::last_os_error();
+        if let Err(err) = result {
             warn!(
                 "WcsDisassociateColorProfileFromDevice failed for {} (Win32={}) (non-fatal)",
                 device_key, err
@@ -3692,13 +5538,20 @@ pub fn reapply_profile(
 
         // Per-user disassociate (non-fatal)
         if per_user {
-            let result = WcsDisassociateColorProfileFromDevice(
+            let result = audited_association(
+                "disassociate",
                 WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
-                PCWSTR(profile_wide.as_ptr()),
-                PCWSTR(device_wide.as_ptr()),
+                device_key,
+                &profile_label,
+                || {
+                    win32_result(WcsDisassociateColorProfileFromDevice(
+                        WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
+                        profile_wide.as_pcwstr(),
+                        device_wide.as_pcwstr(),
+                    ))
+                },
             );
-            if !result.as_bool() {
-                let err = io::Error::last_os_error();
+            if let Err(err) = result {
                 warn!(
                     "WcsDisassociateColorProfileFromDevice (per-user) failed for {} (Win32={}) (non-fatal)",
                     device_key, err
@@ -3711,13 +5564,20 @@ pub fn reapply_profile(
 
         // Step 3: Re-associate (applies the fix profile)
         // Failure here IS fatal — the profile was NOT applied.
-        let result = WcsAssociateColorProfileWithDevice(
+        let result = audited_association(
+            "associate",
             WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
-            PCWSTR(profile_wide.as_ptr()),
-            PCWSTR(device_wide.as_ptr()),
+            device_key,
+            &profile_label,
+            || {
+                win32_result(WcsAssociateColorProfileWithDevice(
+                    WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
+                    profile_wide.as_pcwstr(),
+                    device_wide.as_pcwstr(),
+                ))
+            },
         );
-        if !result.as_bool() {
-            let err = io::Error::last_os_error();
+        if let Err(err) = result {
             return Err(format!(
                 "WcsAssociateColorProfileWithDevice failed for {} (Win32={})",
                 device_key, err
@@ -3727,13 +5587,20 @@ pub fn reapply_profile(
 
         // Per-user associate
         if per_user {
-            let result = WcsAssociateColorProfileWithDevice(
+            let result = audited_association(
+                "associate",
                 WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
-                PCWSTR(profile_wide.as_ptr()),
-                PCWSTR(device_wide.as_ptr()),
+                device_key,
+                &profile_label,
+                || {
+                    win32_result(WcsAssociateColorProfileWithDevice(
+                        WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
+                        profile_wide.as_pcwstr(),
+                        device_wide.as_pcwstr(),
+                    ))
+                },
             );
-            if !result.as_bool() {
-                let err = io::Error::last_os_error();
+            if let Err(err) = result {
                 warn!(
                     "WcsAssociateColorProfileWithDevice (per-user) failed for {} (Win32={}) (non-fatal)",
                     device_key, err
@@ -3746,6 +5613,77 @@ pub fn reapply_profile(
     Ok(())
 }
 
+/// Remove a profile's association from a display device without re-adding it.
+///
+/// Windows falls back to the next associated profile (or the monitor's
+/// factory/sRGB default). Used for per-application overrides that need the
+/// dimming fix out of the way while a color-critical app is focused.
+///
+/// # Arguments
+/// * `device_key` — WMI device instance path
+/// * `profile_path` — Full path to the ICC profile file
+/// * `per_user` — If true, also remove the per-user association
+pub fn disassociate_profile(
+    device_key: &str,
+    profile_path: &Path,
+    per_user: bool,
+) -> Result<(), Box<dyn Error>> {
+    let profile_name = profile_path
+        .file_name()
+        .ok_or_else(|| format!("Invalid profile path: {}", profile_path.display()))?;
+    let profile_wide = WideString::new(profile_name);
+    let profile_label = profile_name.to_string_lossy();
+    let device_wide = WideString::new(device_key);
+
+    unsafe {
+        let result = audited_association(
+            "disassociate",
+            WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
+            device_key,
+            &profile_label,
+            || {
+                win32_result(WcsDisassociateColorProfileFromDevice(
+                    WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
+                    profile_wide.as_pcwstr(),
+                    device_wide.as_pcwstr(),
+                ))
+            },
+        );
+        if let Err(err) = result {
+            return Err(format!(
+                "WcsDisassociateColorProfileFromDevice failed for {} (Win32={})",
+                device_key, err
+            )
+            .into());
+        }
+
+        if per_user {
+            let result = audited_association(
+                "disassociate",
+                WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
+                device_key,
+                &profile_label,
+                || {
+                    win32_result(WcsDisassociateColorProfileFromDevice(
+                        WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
+                        profile_wide.as_pcwstr(),
+                        device_wide.as_pcwstr(),
+                    ))
+                },
+            );
+            if let Err(err) = result {
+                warn!(
+                    "WcsDisassociateColorProfileFromDevice (per-user) failed for {} (Win32={}) (non-fatal)",
+                    device_key, err
+                );
+            }
+        }
+    }
+
+    info!("Profile disassociated from device: {}", device_key);
+    Ok(())
+}
+
 /// Set the profile as the generic default using the legacy `WcsSetDefaultColorProfile` API.
 ///
 /// This is an optional operation — some systems or monitors benefit from having the
@@ -3765,24 +5703,34 @@ pub fn set_generic_default(
     let profile_name = profile_path
         .file_name()
         .ok_or_else(|| format!("Invalid profile path: {}", profile_path.display()))?;
-    let profile_wide: Vec<u16> = profile_name
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect();
-    let device_wide = to_wide(device_key);
+    let profile_wide = WideString::new(profile_name);
+    let profile_label = profile_name.to_string_lossy();
+    let device_wide = WideString::new(device_key);
 
     unsafe {
         // System-wide generic default
-        let result = WcsSetDefaultColorProfile(
+        let result = audited_default(
+            "set_default",
             WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
-            PCWSTR(device_wide.as_ptr()),
-            CPT_ICC,
-            CPST_NONE,
-            0,
-            PCWSTR(profile_wide.as_ptr()),
+            device_key,
+            &profile_label,
+            || {
+                query_wcs_default_profile_name(device_key, WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE)
+                    .ok()
+                    .flatten()
+            },
+            || {
+                win32_result(WcsSetDefaultColorProfile(
+                    WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
+                    device_wide.as_pcwstr(),
+                    CPT_ICC,
+                    CPST_NONE,
+                    0,
+                    profile_wide.as_pcwstr(),
+                ))
+            },
         );
-        if !result.as_bool() {
-            let err = io::Error::last_os_error();
+        if let Err(err) = result {
             warn!(
                 "WcsSetDefaultColorProfile (system) failed for {} (Win32={}) (non-fatal)",
                 device_key, err
@@ -3793,16 +5741,31 @@ pub fn set_generic_default(
 
         // Per-user generic default
         if per_user {
-            let result = WcsSetDefaultColorProfile(
+            let result = audited_default(
+                "set_default",
                 WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
-                PCWSTR(device_wide.as_ptr()),
-                CPT_ICC,
-                CPST_NONE,
-                0,
-                PCWSTR(profile_wide.as_ptr()),
+                device_key,
+                &profile_label,
+                || {
+                    query_wcs_default_profile_name(
+                        device_key,
+                        WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
+                    )
+                    .ok()
+                    .flatten()
+                },
+                || {
+                    win32_result(WcsSetDefaultColorProfile(
+                        WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
+                        device_wide.as_pcwstr(),
+                        CPT_ICC,
+                        CPST_NONE,
+                        0,
+                        profile_wide.as_pcwstr(),
+                    ))
+                },
             );
-            if !result.as_bool() {
-                let err = io::Error::last_os_error();
+            if let Err(err) = result {
                 warn!(
                     "WcsSetDefaultColorProfile (per-user) failed for {} (Win32={}) (non-fatal)",
                     device_key, err
@@ -3818,9 +5781,10 @@ pub fn set_generic_default(
 
 /// Set the SDR display-default association for a display device.
 ///
-/// Calls `ColorProfileSetDisplayDefaultAssociation` (Win10+) which is the
+/// Calls `ColorProfileSetDisplayDefaultAssociation` (Win10 1703+) which is the
 /// modern API that the Color Management control panel uses.  This tells the
-/// WCS display pipeline to actually apply the profile.
+/// WCS display pipeline to actually apply the profile.  On builds without it,
+/// falls back to [`set_generic_default`].
 ///
 /// # Arguments
 /// * `device_key` — WMI device instance path
@@ -3831,14 +5795,19 @@ pub fn set_display_default_association(
     profile_path: &Path,
     per_user: bool,
 ) -> Result<(), Box<dyn Error>> {
+    let Some(set_display_default) = modern_wcs_api().set_display_default else {
+        info!(
+            "ColorProfileSetDisplayDefaultAssociation unavailable — using legacy default for {}",
+            device_key
+        );
+        return set_generic_default(device_key, profile_path, per_user);
+    };
     // WCS APIs expect just the filename, not the full path.
     let profile_name = profile_path
         .file_name()
         .ok_or_else(|| format!("Invalid profile path: {}", profile_path.display()))?;
-    let profile_wide: Vec<u16> = profile_name
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect();
+    let profile_wide = WideString::new(profile_name);
+    let profile_label = profile_name.to_string_lossy();
     let Some(target) = resolve_display_color_target(device_key)? else {
         warn!(
             "Could not map {} to an active display path for ColorProfileSetDisplayDefaultAssociation",
@@ -3848,13 +5817,30 @@ pub fn set_display_default_association(
     };
 
     unsafe {
-        let result = ColorProfileSetDisplayDefaultAssociation(
+        let result = audited_default(
+            "set_sdr_default",
             WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
-            PCWSTR(profile_wide.as_ptr()),
-            CPT_ICC,
-            CPST_STANDARD_DISPLAY_COLOR_MODE,
-            target.adapter_id,
-            target.source_id,
+            device_key,
+            &profile_label,
+            || {
+                query_display_default(
+                    &target,
+                    WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
+                    CPST_STANDARD_DISPLAY_COLOR_MODE,
+                )
+            },
+            || {
+                set_display_default(
+                    WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
+                    profile_wide.as_pcwstr(),
+                    CPT_ICC,
+                    CPST_STANDARD_DISPLAY_COLOR_MODE,
+                    target.adapter_id,
+                    target.source_id,
+                )
+                .ok()
+                .map_err(io::Error::other)
+            },
         );
         if let Err(err) = result {
             warn!(
@@ -3869,13 +5855,30 @@ pub fn set_display_default_association(
         }
 
         if per_user {
-            let result = ColorProfileSetDisplayDefaultAssociation(
+            let result = audited_default(
+                "set_sdr_default",
                 WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
-                PCWSTR(profile_wide.as_ptr()),
-                CPT_ICC,
-                CPST_STANDARD_DISPLAY_COLOR_MODE,
-                target.adapter_id,
-                target.source_id,
+                device_key,
+                &profile_label,
+                || {
+                    query_display_default(
+                        &target,
+                        WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
+                        CPST_STANDARD_DISPLAY_COLOR_MODE,
+                    )
+                },
+                || {
+                    set_display_default(
+                        WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
+                        profile_wide.as_pcwstr(),
+                        CPT_ICC,
+                        CPST_STANDARD_DISPLAY_COLOR_MODE,
+                        target.adapter_id,
+                        target.source_id,
+                    )
+                    .ok()
+                    .map_err(io::Error::other)
+                },
             );
             if let Err(err) = result {
                 warn!(
@@ -3896,8 +5899,9 @@ pub fn set_display_default_association(
 
 /// Add the profile to the HDR/advanced-color association for a display device.
 ///
-/// Calls `ColorProfileAddDisplayAssociation` (Win10+).
-/// This is an opt-in operation for HDR displays.
+/// Calls `ColorProfileAddDisplayAssociation` (Win10 1703+).
+/// This is an opt-in operation for HDR displays; it is skipped with a warning
+/// on builds that lack the API.
 ///
 /// # Arguments
 /// * `device_key` — WMI device instance path
@@ -3908,14 +5912,22 @@ pub fn add_hdr_display_association(
     profile_path: &Path,
     per_user: bool,
 ) -> Result<(), Box<dyn Error>> {
+    let api = modern_wcs_api();
+    let (Some(add_display_association), Some(set_display_default)) =
+        (api.add_display_association, api.set_display_default)
+    else {
+        warn!(
+            "HDR display associations require Windows 10 1703+ — skipping for {}",
+            device_key
+        );
+        return Ok(());
+    };
     // WCS APIs expect just the filename, not the full path.
     let profile_name = profile_path
         .file_name()
         .ok_or_else(|| format!("Invalid profile path: {}", profile_path.display()))?;
-    let profile_wide: Vec<u16> = profile_name
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect();
+    let profile_wide = WideString::new(profile_name);
+    let profile_label = profile_name.to_string_lossy();
     let Some(target) = resolve_display_color_target(device_key)? else {
         warn!(
             "Could not map {} to an active display path for ColorProfileAddDisplayAssociation",
@@ -3925,13 +5937,23 @@ pub fn add_hdr_display_association(
     };
 
     unsafe {
-        let result = ColorProfileAddDisplayAssociation(
+        let result = audited_association(
+            "add_display_association",
             WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
-            PCWSTR(profile_wide.as_ptr()),
-            target.adapter_id,
-            target.source_id,
-            BOOL::from(true),
-            BOOL::from(true),
+            device_key,
+            &profile_label,
+            || {
+                add_display_association(
+                    WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
+                    profile_wide.as_pcwstr(),
+                    target.adapter_id,
+                    target.source_id,
+                    BOOL::from(true),
+                    BOOL::from(true),
+                )
+                .ok()
+                .map_err(io::Error::other)
+            },
         );
         if let Err(err) = result {
             warn!(
@@ -3940,13 +5962,30 @@ pub fn add_hdr_display_association(
             );
         } else {
             info!("HDR display association added (system) for {}", device_key);
-            if let Err(err) = ColorProfileSetDisplayDefaultAssociation(
+            if let Err(err) = audited_default(
+                "set_hdr_default",
                 WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
-                PCWSTR(profile_wide.as_ptr()),
-                CPT_ICC,
-                CPST_EXTENDED_DISPLAY_COLOR_MODE,
-                target.adapter_id,
-                target.source_id,
+                device_key,
+                &profile_label,
+                || {
+                    query_display_default(
+                        &target,
+                        WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
+                        CPST_EXTENDED_DISPLAY_COLOR_MODE,
+                    )
+                },
+                || {
+                    set_display_default(
+                        WCS_PROFILE_MANAGEMENT_SCOPE_SYSTEM_WIDE,
+                        profile_wide.as_pcwstr(),
+                        CPT_ICC,
+                        CPST_EXTENDED_DISPLAY_COLOR_MODE,
+                        target.adapter_id,
+                        target.source_id,
+                    )
+                    .ok()
+                    .map_err(io::Error::other)
+                },
             ) {
                 warn!(
                     "ColorProfileSetDisplayDefaultAssociation (HDR/system) failed for {} ({}) (non-fatal)",
@@ -3956,13 +5995,23 @@ pub fn add_hdr_display_association(
         }
 
         if per_user {
-            let result = ColorProfileAddDisplayAssociation(
+            let result = audited_association(
+                "add_display_association",
                 WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
-                PCWSTR(profile_wide.as_ptr()),
-                target.adapter_id,
-                target.source_id,
-                BOOL::from(true),
-                BOOL::from(true),
+                device_key,
+                &profile_label,
+                || {
+                    add_display_association(
+                        WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
+                        profile_wide.as_pcwstr(),
+                        target.adapter_id,
+                        target.source_id,
+                        BOOL::from(true),
+                        BOOL::from(true),
+                    )
+                    .ok()
+                    .map_err(io::Error::other)
+                },
             );
             if let Err(err) = result {
                 warn!(
@@ -3974,13 +6023,30 @@ pub fn add_hdr_display_association(
                     "HDR display association added (per-user) for {}",
                     device_key
                 );
-                if let Err(err) = ColorProfileSetDisplayDefaultAssociation(
+                if let Err(err) = audited_default(
+                    "set_hdr_default",
                     WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
-                    PCWSTR(profile_wide.as_ptr()),
-                    CPT_ICC,
-                    CPST_EXTENDED_DISPLAY_COLOR_MODE,
-                    target.adapter_id,
-                    target.source_id,
+                    device_key,
+                    &profile_label,
+                    || {
+                        query_display_default(
+                            &target,
+                            WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
+                            CPST_EXTENDED_DISPLAY_COLOR_MODE,
+                        )
+                    },
+                    || {
+                        set_display_default(
+                            WCS_PROFILE_MANAGEMENT_SCOPE_CURRENT_USER,
+                            profile_wide.as_pcwstr(),
+                            CPT_ICC,
+                            CPST_EXTENDED_DISPLAY_COLOR_MODE,
+                            target.adapter_id,
+                            target.source_id,
+                        )
+                        .ok()
+                        .map_err(io::Error::other)
+                    },
                 ) {
                     warn!(
                         "ColorProfileSetDisplayDefaultAssociation (HDR/per-user) failed for {} ({}) (non-fatal)",
@@ -4198,14 +6264,10 @@ fn run_calibration_loader_task() -> Result<(), Box<dyn Error>> {
     result
 }
 
-/// Convert a Rust string to a null-terminated wide string (UTF-16).
-fn to_wide(s: &str) -> Vec<u16> {
-    OsStr::new(s)
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect()
-}
-
 #[cfg(test)]
 #[path = "tests/profile_tests.rs"]
 mod tests;
+
+#[cfg(test)]
+#[path = "tests/icc_fuzz_tests.rs"]
+mod icc_fuzz_tests;
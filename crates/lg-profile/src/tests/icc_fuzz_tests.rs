@@ -0,0 +1,132 @@
+use super::*;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+fn generated_icm_bytes() -> Vec<u8> {
+    generate_dynamic_profile_bytes(DEFAULT_DYNAMIC_GAMMA)
+        .expect("should generate default dynamic ICC")
+}
+
+/// Every entry point that takes profile bytes. Only panics matter here;
+/// rejecting garbage with an error is the expected outcome.
+fn exercise_parsers(bytes: &[u8]) {
+    let _ = validate_icc_profile_bytes(bytes);
+    let _ = inspect_icc_profile_bytes(bytes);
+    let _ = normalize_icc_profile_bytes(bytes);
+    let _ = patch_icc_profile_bytes(bytes, &[], &[u32::from_be_bytes(*b"desc")]);
+    let _ = adjust_vcgt_profile_bytes(bytes, VcgtAdjustment::default());
+    let _ = generate_white_point_variant_bytes(bytes, WhitePointVariant::K5000);
+}
+
+/// `vcgt` table header: signature, reserved, mode 0, then the given
+/// channel count, entry count and entry size.
+fn vcgt_header(channels: u16, entries: u16, entry_size: u16) -> Vec<u8> {
+    let mut payload = b"vcgt\0\0\0\0".to_vec();
+    payload.extend_from_slice(&0u32.to_be_bytes());
+    payload.extend_from_slice(&channels.to_be_bytes());
+    payload.extend_from_slice(&entries.to_be_bytes());
+    payload.extend_from_slice(&entry_size.to_be_bytes());
+    payload
+}
+
+// ── Malformed profiles ───────────────────────────────────────────
+
+proptest! {
+    #[test]
+    fn arbitrary_bytes_never_panic(bytes in vec(any::<u8>(), 0..2048)) {
+        exercise_parsers(&bytes);
+    }
+
+    #[test]
+    fn arbitrary_bytes_after_a_valid_header_never_panic(
+        tail in vec(any::<u8>(), 0..2048),
+        tag_count in 0u32..64,
+    ) {
+        let mut bytes = generated_icm_bytes()[..ICC_HEADER_SIZE].to_vec();
+        bytes.extend_from_slice(&tag_count.to_be_bytes());
+        bytes.extend_from_slice(&tail);
+        let size = bytes.len() as u32;
+        bytes[..4].copy_from_slice(&size.to_be_bytes());
+        exercise_parsers(&bytes);
+    }
+
+    #[test]
+    fn truncated_profiles_never_panic(cut in 0usize..4096) {
+        let bytes = generated_icm_bytes();
+        exercise_parsers(&bytes[..cut.min(bytes.len())]);
+    }
+
+    #[test]
+    fn corrupted_profiles_never_panic(
+        edits in vec((any::<prop::sample::Index>(), any::<u8>()), 1..32),
+    ) {
+        let mut bytes = generated_icm_bytes();
+        for (index, value) in edits {
+            let at = index.index(bytes.len());
+            bytes[at] = value;
+        }
+        exercise_parsers(&bytes);
+    }
+
+    #[test]
+    fn corrupted_tag_table_never_panics(
+        edits in vec((0usize..ICC_TAG_RECORD_SIZE * 16, any::<u8>()), 1..16),
+    ) {
+        let mut bytes = generated_icm_bytes();
+        let table = ICC_HEADER_SIZE + 4;
+        for (offset, value) in edits {
+            if let Some(byte) = bytes.get_mut(table + offset) {
+                *byte = value;
+            }
+        }
+        exercise_parsers(&bytes);
+    }
+}
+
+#[test]
+fn oversized_tag_count_is_rejected_up_front() {
+    let mut bytes = generated_icm_bytes();
+    let count = (ICC_MAX_TAG_COUNT as u32 + 1).to_be_bytes();
+    bytes[ICC_TAG_COUNT_OFFSET..ICC_TAG_COUNT_OFFSET + 4].copy_from_slice(&count);
+    // Pad so the oversized table fits and the count itself is the only fault.
+    bytes.resize(
+        ICC_HEADER_SIZE + 4 + (ICC_MAX_TAG_COUNT + 1) * ICC_TAG_RECORD_SIZE,
+        0,
+    );
+    let report = validate_icc_profile_bytes(&bytes);
+    assert!(report
+        .errors
+        .iter()
+        .any(|e| e.contains("exceeds the limit")));
+}
+
+// ── Malformed vcgt payloads ──────────────────────────────────────
+
+proptest! {
+    #[test]
+    fn arbitrary_vcgt_payloads_never_panic(payload in vec(any::<u8>(), 0..1024)) {
+        let _ = parse_vcgt_gamma_ramp(&payload);
+    }
+
+    #[test]
+    fn vcgt_tables_with_any_shape_never_panic(
+        channels in 0u16..5,
+        entries in any::<u16>(),
+        entry_size in 0u16..4,
+        data in vec(any::<u8>(), 0..2048),
+    ) {
+        let mut payload = vcgt_header(channels, entries, entry_size);
+        payload.extend_from_slice(&data);
+        let _ = parse_vcgt_gamma_ramp(&payload);
+    }
+
+    #[test]
+    fn complete_vcgt_tables_always_parse(entries in 1u16..512, fill in any::<u16>()) {
+        let mut payload = vcgt_header(3, entries, 2);
+        for _ in 0..entries as usize * 3 {
+            payload.extend_from_slice(&fill.to_be_bytes());
+        }
+        let ramp = parse_vcgt_gamma_ramp(&payload).expect("well-formed vcgt");
+        prop_assert!(ramp.iter().all(|&v| v == fill));
+    }
+}
@@ -268,6 +268,25 @@ fn select_effective_preset_uses_schedule_when_both_present() {
     );
 }
 
+#[test]
+fn select_effective_preset_for_period_follows_daytime_flag() {
+    let day = select_effective_preset_for_period(
+        "custom", "gamma22", "gamma24", "gamma22", "reader", false, true,
+    );
+    let night = select_effective_preset_for_period(
+        "custom", "gamma22", "gamma24", "gamma22", "reader", false, false,
+    );
+    assert_eq!(day, "gamma22");
+    assert_eq!(night, "reader");
+}
+
+#[test]
+fn select_effective_preset_for_period_ignores_flag_without_schedule() {
+    let selected =
+        select_effective_preset_for_period("custom", "gamma22", "gamma24", "", "", true, false);
+    assert_eq!(selected, "gamma24");
+}
+
 #[test]
 fn sanitize_dynamic_luminance_clamps_values() {
     assert_eq!(
@@ -567,7 +586,7 @@ fn reapply_profile_with_mode_associations_rejects_missing_mode_profiles() {
         r"DISPLAY\FAKE\999",
         &missing,
         &missing,
-        &missing,
+        Some(&missing),
         50,
         false,
     );
@@ -578,44 +597,50 @@ fn reapply_profile_with_mode_associations_rejects_missing_mode_profiles() {
     );
 }
 
-// ── to_wide helper ───────────────────────────────────────────────
+// ── Wide strings ─────────────────────────────────────────────────
 
 #[test]
-fn to_wide_empty_string() {
-    let result = to_wide("");
-    assert_eq!(result, vec![0]); // just the null terminator
+fn wide_string_empty_string() {
+    let wide = WideString::new("");
+    let result = wide.as_slice_with_nul();
+    assert_eq!(result, [0]); // just the null terminator
 }
 
 #[test]
-fn to_wide_ascii() {
-    let result = to_wide("ABC");
-    assert_eq!(result, vec![65, 66, 67, 0]);
+fn wide_string_ascii() {
+    let wide = WideString::new("ABC");
+    let result = wide.as_slice_with_nul();
+    assert_eq!(result, [65, 66, 67, 0]);
 }
 
 #[test]
-fn to_wide_null_terminated() {
-    let result = to_wide("test");
+fn wide_string_null_terminated() {
+    let wide = WideString::new("test");
+    let result = wide.as_slice_with_nul();
     assert_eq!(*result.last().unwrap(), 0u16);
 }
 
 #[test]
-fn to_wide_path() {
-    let result = to_wide(r"C:\Windows\System32\spool\drivers\color\test.icm");
+fn wide_string_path() {
+    let wide = WideString::new(r"C:\Windows\System32\spool\drivers\color\test.icm");
+    let result = wide.as_slice_with_nul();
     assert!(!result.is_empty());
     assert_eq!(*result.last().unwrap(), 0u16);
     assert_eq!(result[0], 67u16); // 'C'
 }
 
 #[test]
-fn to_wide_unicode() {
-    let result = to_wide("日本語");
+fn wide_string_unicode() {
+    let wide = WideString::new("日本語");
+    let result = wide.as_slice_with_nul();
     assert_eq!(*result.last().unwrap(), 0u16);
     assert_eq!(result.len(), 4); // 3 chars + null
 }
 
 #[test]
-fn to_wide_spaces_and_special() {
-    let result = to_wide("LG ULTRAGEAR (27GP950)");
+fn wide_string_spaces_and_special() {
+    let wide = WideString::new("LG ULTRAGEAR (27GP950)");
+    let result = wide.as_slice_with_nul();
     assert_eq!(*result.last().unwrap(), 0u16);
     assert_eq!(result.len(), 23); // 22 chars + null
 }
@@ -852,6 +877,155 @@ fn validate_icc_detects_vcgt_when_enabled() {
     assert!(report.has_vcgt_tag, "expected vcgt tag to be reported");
 }
 
+#[test]
+fn identity_profile_is_valid_with_a_linear_vcgt() {
+    let bytes = generate_identity_profile_bytes().unwrap();
+    let report = validate_icc_profile_bytes(&bytes);
+    assert!(report.is_valid(), "{}", report.errors.join(" | "));
+    assert!(report.has_vcgt_tag, "expected vcgt tag to be reported");
+    assert_eq!(bytes, generate_identity_profile_bytes().unwrap());
+
+    let linear = identity_curve_points();
+    assert_eq!(linear[0], 0);
+    assert_eq!(linear[CURVE_TABLE_SIZE - 1], 65535);
+    let vcgt = build_vcgt_table_payload(&linear, &linear, &linear, 1.0);
+    let table = &vcgt[10..];
+    for channel in table.chunks(CURVE_TABLE_SIZE * 2) {
+        let values: Vec<u16> = channel
+            .chunks(2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+            .collect();
+        assert_eq!(values, linear.to_vec());
+    }
+}
+
+fn vcgt_ramp(bytes: &[u8]) -> [u16; CURVE_TABLE_SIZE * 3] {
+    let raw = RawProfile::from_bytes(bytes).unwrap();
+    let record = raw.tags.get(&TagSignature::Vcgt).expect("vcgt tag");
+    parse_vcgt_gamma_ramp(record.tag.as_slice()).unwrap()
+}
+
+#[test]
+fn adjust_vcgt_defaults_keep_the_curve() {
+    let source = generate_identity_profile_bytes().unwrap();
+    let adjusted = adjust_vcgt_profile_bytes(&source, VcgtAdjustment::default()).unwrap();
+    assert_eq!(vcgt_ramp(&adjusted), vcgt_ramp(&source));
+    let report = validate_icc_profile_bytes(&adjusted);
+    assert!(report.is_valid(), "{}", report.errors.join(" | "));
+}
+
+#[test]
+fn adjust_vcgt_brightness_and_gamma_move_the_curve() {
+    let source = generate_identity_profile_bytes().unwrap();
+    let mid = CURVE_TABLE_SIZE / 2;
+    let before = vcgt_ramp(&source);
+
+    let brighter = vcgt_ramp(
+        &adjust_vcgt_profile_bytes(
+            &source,
+            VcgtAdjustment {
+                brightness: 5.0,
+                ..VcgtAdjustment::default()
+            },
+        )
+        .unwrap(),
+    );
+    assert!(brighter[0] > before[0]);
+    assert!(brighter[mid] > before[mid]);
+    assert_eq!(brighter[CURVE_TABLE_SIZE - 1], 65535);
+
+    let darker = vcgt_ramp(
+        &adjust_vcgt_profile_bytes(
+            &source,
+            VcgtAdjustment {
+                gamma: 2.4,
+                ..VcgtAdjustment::default()
+            },
+        )
+        .unwrap(),
+    );
+    assert!(darker[mid] < before[mid]);
+    assert_eq!(darker[0], 0);
+}
+
+#[test]
+fn adjust_vcgt_adds_a_curve_to_profiles_without_one() {
+    let source = generate_dynamic_profile_bytes(2.2).unwrap();
+    assert!(!validate_icc_profile_bytes(&source).has_vcgt_tag);
+    let adjusted = adjust_vcgt_profile_bytes(
+        &source,
+        VcgtAdjustment {
+            contrast: 10.0,
+            ..VcgtAdjustment::default()
+        },
+    )
+    .unwrap();
+    assert!(validate_icc_profile_bytes(&adjusted).has_vcgt_tag);
+}
+
+#[test]
+fn adjust_vcgt_rejects_out_of_range_values() {
+    let source = generate_identity_profile_bytes().unwrap();
+    for adjustment in [
+        VcgtAdjustment {
+            brightness: 80.0,
+            ..VcgtAdjustment::default()
+        },
+        VcgtAdjustment {
+            contrast: -60.0,
+            ..VcgtAdjustment::default()
+        },
+        VcgtAdjustment {
+            gamma: 5.0,
+            ..VcgtAdjustment::default()
+        },
+    ] {
+        assert!(adjust_vcgt_profile_bytes(&source, adjustment).is_err());
+    }
+}
+
+#[test]
+fn white_point_variant_parses_and_names() {
+    assert_eq!(
+        WhitePointVariant::parse(" 9300K "),
+        Some(WhitePointVariant::K9300)
+    );
+    assert_eq!(
+        WhitePointVariant::parse("5000"),
+        Some(WhitePointVariant::K5000)
+    );
+    assert_eq!(WhitePointVariant::parse("7000k"), None);
+    assert_eq!(
+        WhitePointVariant::K6500.profile_name(),
+        "lg-ultragear-6500k.icm"
+    );
+}
+
+#[test]
+fn white_point_gains_warm_and_cool_relative_to_d65() {
+    assert_eq!(white_point_gains(6500.0), [1.0, 1.0, 1.0]);
+    let [r, g, b] = white_point_gains(5000.0);
+    assert_eq!(r, 1.0);
+    assert!(g < 1.0 && b < g, "{:?}", [r, g, b]);
+    let [r, g, b] = white_point_gains(9300.0);
+    assert_eq!(b, 1.0);
+    assert!(r < g && g < 1.0, "{:?}", [r, g, b]);
+}
+
+#[test]
+fn white_point_variant_scales_the_vcgt() {
+    let source = generate_identity_profile_bytes().unwrap();
+    let top = CURVE_TABLE_SIZE - 1;
+    let d65 =
+        vcgt_ramp(&generate_white_point_variant_bytes(&source, WhitePointVariant::K6500).unwrap());
+    assert_eq!(d65, vcgt_ramp(&source));
+
+    let warm =
+        vcgt_ramp(&generate_white_point_variant_bytes(&source, WhitePointVariant::K5000).unwrap());
+    assert_eq!(warm[top], 65535);
+    assert!(warm[CURVE_TABLE_SIZE * 2 + top] < warm[CURVE_TABLE_SIZE + top]);
+}
+
 #[test]
 fn ensure_profile_installed_writes_to_temp() {
     let dir = std::env::temp_dir().join("lg-test-ensure-profile");
@@ -976,6 +1150,27 @@ fn trigger_calibration_loader_disabled_does_not_panic() {
     trigger_calibration_loader(false);
 }
 
+// ── Modern WCS API resolution ────────────────────────────────────
+
+#[test]
+fn wcs_api_path_unresolved_falls_back_to_legacy() {
+    assert_eq!(
+        wcs_api_path_for(&ModernWcsApi::default()),
+        WcsApiPath::Legacy
+    );
+}
+
+#[test]
+fn wcs_api_path_is_stable_across_calls() {
+    assert_eq!(wcs_api_path(), wcs_api_path());
+}
+
+#[test]
+fn wcs_api_path_labels_are_distinct() {
+    assert_ne!(WcsApiPath::Modern.label(), WcsApiPath::Legacy.label());
+    assert!(WcsApiPath::Legacy.label().contains("Wcs*"));
+}
+
 // ── WCS scope constants ──────────────────────────────────────────
 
 #[test]
@@ -1001,11 +1196,12 @@ fn profile_path_for_reapply_check() {
 // Edge case tests — extended coverage
 // ================================================================
 
-// ── to_wide edge cases ───────────────────────────────────────────
+// ── WideString edge cases ────────────────────────────────────────
 
 #[test]
-fn to_wide_unicode_characters() {
-    let result = to_wide("日本語テスト");
+fn wide_string_unicode_characters() {
+    let wide = WideString::new("日本語テスト");
+    let result = wide.as_slice_with_nul();
     assert!(!result.is_empty());
     assert_eq!(*result.last().unwrap(), 0u16);
     // Each character maps to at least one u16
@@ -1013,8 +1209,9 @@ fn to_wide_unicode_characters() {
 }
 
 #[test]
-fn to_wide_backslashes_in_device_path() {
-    let result = to_wide(r"DISPLAY\LG\ULTRAGEAR_001\INSTANCE_0");
+fn wide_string_backslashes_in_device_path() {
+    let wide = WideString::new(r"DISPLAY\LG\ULTRAGEAR_001\INSTANCE_0");
+    let result = wide.as_slice_with_nul();
     assert_eq!(*result.last().unwrap(), 0u16);
     // Count backslashes ('\' = 0x5C)
     let backslash_count = result.iter().filter(|&&c| c == 0x5C).count();
@@ -1022,24 +1219,27 @@ fn to_wide_backslashes_in_device_path() {
 }
 
 #[test]
-fn to_wide_spaces_in_path() {
-    let result = to_wide(r"C:\Program Files\Some App\profile.icm");
+fn wide_string_spaces_in_path() {
+    let wide = WideString::new(r"C:\Program Files\Some App\profile.icm");
+    let result = wide.as_slice_with_nul();
     assert_eq!(*result.last().unwrap(), 0u16);
     let space_count = result.iter().filter(|&&c| c == 0x20).count();
     assert_eq!(space_count, 2, "should encode 2 spaces");
 }
 
 #[test]
-fn to_wide_very_long_string() {
+fn wide_string_very_long_string() {
     let long = "A".repeat(1000);
-    let result = to_wide(&long);
+    let wide = WideString::new(&long);
+    let result = wide.as_slice_with_nul();
     assert_eq!(result.len(), 1001); // 1000 chars + null
     assert_eq!(*result.last().unwrap(), 0u16);
 }
 
 #[test]
-fn to_wide_mixed_ascii_and_unicode() {
-    let result = to_wide("Monitor-LG-日本語");
+fn wide_string_mixed_ascii_and_unicode() {
+    let wide = WideString::new("Monitor-LG-日本語");
+    let result = wide.as_slice_with_nul();
     assert_eq!(*result.last().unwrap(), 0u16);
     assert!(result.len() > 1);
 }
@@ -1392,6 +1592,70 @@ fn register_color_profile_temp_file_is_noop() {
     let _ = std::fs::remove_dir_all(&dir);
 }
 
+#[test]
+fn only_sharing_and_lock_violations_are_retried() {
+    assert!(is_lock_error(32));
+    assert!(is_lock_error(33));
+    assert!(!is_lock_error(5));
+    assert!(!is_lock_error(2));
+}
+
+#[test]
+fn profile_locked_error_names_the_holders() {
+    let err = ProfileLockedError {
+        path: PathBuf::from(r"C:\Windows\System32\spool\drivers\color\x.icm"),
+        attempts: 6,
+        os_error: 32,
+        holders: vec![
+            LockHolder {
+                pid: 1234,
+                name: "Windows Search".to_string(),
+                service: Some("WSearch".to_string()),
+            },
+            LockHolder {
+                pid: 42,
+                name: "dispcal.exe".to_string(),
+                service: None,
+            },
+        ],
+    };
+    let msg = err.to_string();
+    assert!(msg.contains("after 6 attempts"), "{msg}");
+    assert!(
+        msg.contains(
+            "held open by Windows Search (PID 1234, service WSearch), dispcal.exe (PID 42)"
+        ),
+        "{msg}"
+    );
+    assert_eq!(format!("{err:?}"), msg);
+
+    let boxed: Box<dyn Error> = Box::new(err);
+    assert!(boxed.downcast_ref::<ProfileLockedError>().is_some());
+}
+
+#[test]
+fn profile_locked_error_without_holders() {
+    let err = ProfileLockedError {
+        path: PathBuf::from("x.icm"),
+        attempts: 6,
+        os_error: 33,
+        holders: Vec::new(),
+    };
+    assert!(err
+        .to_string()
+        .ends_with("no process holding it could be identified"));
+}
+
+#[test]
+fn lock_holders_of_unopened_file_is_empty() {
+    let dir = std::env::temp_dir().join("lg-profile-lock-holders-test");
+    let _ = std::fs::create_dir_all(&dir);
+    let path = dir.join("free.icm");
+    std::fs::write(&path, b"x").unwrap();
+    assert_eq!(lock_holders(&path).unwrap(), Vec::new());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
 // ── set_display_default_association ──────────────────────────────
 
 #[test]
@@ -1525,3 +1789,209 @@ fn reapply_profile_rejects_directory_path() {
     // This is a safety-net test: the call should not panic.
     let _ = result;
 }
+
+// ── Gamma ramp deviation ─────────────────────────────────────────
+
+#[test]
+fn gamma_ramp_deviation_is_largest_entry_difference() {
+    let a = vec![0u16, 1000, 65535];
+    let b = vec![10u16, 3000, 65000];
+    assert_eq!(gamma_ramp_deviation(&a, &b), 2000);
+    assert_eq!(gamma_ramp_deviation(&a, &a), 0);
+}
+
+#[test]
+fn gamma_ramp_deviation_length_mismatch_is_max() {
+    assert_eq!(gamma_ramp_deviation(&[0u16; 768], &[0u16; 256]), u16::MAX);
+}
+
+// ── Profile store audit ──────────────────────────────────────────
+
+fn assoc(device_key: &str, profiles: &[&str]) -> DeviceAssociations {
+    DeviceAssociations {
+        device_key: device_key.to_string(),
+        profiles: profiles.iter().map(|p| p.to_string()).collect(),
+    }
+}
+
+#[test]
+fn stale_names_include_test_leftovers_and_scoped_profiles() {
+    assert!(is_stale_profile_name("Test-Roundtrip.icm"));
+    assert!(is_stale_profile_name(
+        "lg-ultragear-gamma22-cmx-27gr95qe-1a2b.icm"
+    ));
+    assert!(!is_stale_profile_name(GAMMA22_PROFILE_NAME));
+    assert!(!is_stale_profile_name("sRGB Color Space Profile.icm"));
+}
+
+#[test]
+fn copy_of_own_profile_under_another_name_is_duplicate() {
+    let files = vec![
+        (PathBuf::from(r"C:\color\lg-ultragear-gamma22-cmx.icm"), 7),
+        (PathBuf::from(r"C:\color\my-monitor.icm"), 7),
+        (PathBuf::from(r"C:\color\sRGB Color Space Profile.icm"), 9),
+    ];
+    assert_eq!(
+        find_duplicate_profiles(&files, &[]),
+        vec![ProfileAuditFinding::Duplicate {
+            path: PathBuf::from(r"C:\color\my-monitor.icm"),
+            original: PathBuf::from(r"C:\color\lg-ultragear-gamma22-cmx.icm"),
+        }]
+    );
+}
+
+#[test]
+fn identical_foreign_profiles_and_associated_copies_are_not_duplicates() {
+    let files = vec![
+        (PathBuf::from(r"C:\color\vendor-a.icm"), 3),
+        (PathBuf::from(r"C:\color\vendor-b.icm"), 3),
+        (PathBuf::from(r"C:\color\lg-ultragear-gamma22-cmx.icm"), 7),
+        (PathBuf::from(r"C:\color\in-use.icm"), 7),
+    ];
+    let associations = [assoc(r"DISPLAY\GSM5BBF\1", &["In-Use.icm"])];
+    assert!(find_duplicate_profiles(&files, &associations).is_empty());
+}
+
+#[test]
+fn association_with_missing_file_is_orphan() {
+    let associations = [assoc(r"DISPLAY\GSM5BBF\1", &["gone.icm"])];
+    let findings = classify_associations(&associations, &[], &[r"DISPLAY\GSM5BBF\1".into()]);
+    assert_eq!(
+        findings,
+        vec![ProfileAuditFinding::OrphanAssociation {
+            device_key: r"DISPLAY\GSM5BBF\1".into(),
+            profile: "gone.icm".into(),
+        }]
+    );
+}
+
+#[test]
+fn own_profile_on_disconnected_device_is_reported() {
+    let associations = [
+        assoc(
+            r"DISPLAY\GSM5BBF\old",
+            &[GAMMA22_PROFILE_NAME, "vendor.icm"],
+        ),
+        assoc(r"DISPLAY\GSM5BBF\new", &[GAMMA22_PROFILE_NAME]),
+    ];
+    let installed = vec![GAMMA22_PROFILE_NAME.to_string(), "vendor.icm".to_string()];
+    let findings = classify_associations(
+        &associations,
+        &installed,
+        &[r"display\gsm5bbf\NEW".to_string()],
+    );
+    assert_eq!(
+        findings,
+        vec![ProfileAuditFinding::DisconnectedDevice {
+            device_key: r"DISPLAY\GSM5BBF\old".into(),
+            profile: GAMMA22_PROFILE_NAME.into(),
+        }]
+    );
+}
+
+#[test]
+fn profile_fingerprint_distinguishes_content() {
+    assert_eq!(profile_fingerprint(b"abc"), profile_fingerprint(b"abc"));
+    assert_ne!(profile_fingerprint(b"abc"), profile_fingerprint(b"abd"));
+}
+
+// ── Device key matching ──────────────────────────────────────────
+
+#[test]
+fn normalize_device_key_strips_wmi_suffix_and_case() {
+    assert_eq!(
+        normalize_device_key(r" display\gsm5bbf\5&abc&0&uid4352_1"),
+        r"DISPLAY\GSM5BBF\5&ABC&0&UID4352"
+    );
+    assert!(device_keys_match(r"DISPLAY\LGS\001_0", r"display\lgs\001"));
+    assert!(!device_keys_match(r"DISPLAY\LGS\001", r"DISPLAY\LGS\002"));
+}
+
+#[test]
+fn previous_instance_of_same_model_is_found() {
+    let associations = [
+        assoc(r"DISPLAY\GSM5BBF\4&old&0&UID4352", &["calibrated.icm"]),
+        assoc(r"DISPLAY\DEL4321\4&dell&0&UID4353", &["dell.icm"]),
+    ];
+    let found = find_previous_device_associations(
+        r"DISPLAY\GSM5BBF\5&new&0&UID4352",
+        &associations,
+        &[r"DISPLAY\GSM5BBF\5&new&0&UID4352".to_string()],
+    );
+    assert_eq!(
+        found.map(|a| a.device_key.as_str()),
+        Some(r"DISPLAY\GSM5BBF\4&old&0&UID4352")
+    );
+}
+
+#[test]
+fn connected_or_identical_devices_are_not_predecessors() {
+    let associations = [
+        assoc(r"DISPLAY\GSM5BBF\5&new&0&UID4352", &["ours.icm"]),
+        assoc(r"DISPLAY\GSM5BBF\5&other&0&UID4353", &["other.icm"]),
+    ];
+    let found = find_previous_device_associations(
+        r"DISPLAY\GSM5BBF\5&new&0&UID4352_0",
+        &associations,
+        &[r"DISPLAY\GSM5BBF\5&other&0&UID4353".to_string()],
+    );
+    assert!(found.is_none());
+}
+
+#[test]
+fn ambiguous_predecessors_are_resolved_by_uid_or_rejected() {
+    let associations = [
+        assoc(r"DISPLAY\GSM5BBF\4&a&0&UID4352", &["left.icm"]),
+        assoc(r"DISPLAY\GSM5BBF\4&b&0&UID4353", &["right.icm"]),
+    ];
+    let found =
+        find_previous_device_associations(r"DISPLAY\GSM5BBF\5&c&0&UID4353", &associations, &[]);
+    assert_eq!(found.map(|a| a.profiles[0].as_str()), Some("right.icm"));
+
+    let none =
+        find_previous_device_associations(r"DISPLAY\GSM5BBF\5&c&0&UID9999", &associations, &[]);
+    assert!(none.is_none());
+}
+
+// ── Installed profile lookup ─────────────────────────────────────
+
+#[test]
+fn parse_multi_sz_stops_at_the_empty_string() {
+    let buf: Vec<u16> = "a.icm\0sRGB Color Space Profile.icm\0\0junk"
+        .encode_utf16()
+        .collect();
+    assert_eq!(
+        parse_multi_sz(&buf),
+        vec!["a.icm", "sRGB Color Space Profile.icm"]
+    );
+    assert!(parse_multi_sz(&[0, 0]).is_empty());
+}
+
+#[test]
+fn installed_profile_matches_with_or_without_extension() {
+    let installed = vec![
+        "lg-ultragear-gamma22-cmx.icm".to_string(),
+        "sRGB Color Space Profile.icm".to_string(),
+        "vendor.icc".to_string(),
+    ];
+    assert_eq!(
+        match_installed_profile("LG-UltraGear-Gamma22-CMX.icm", &installed),
+        Some("lg-ultragear-gamma22-cmx.icm")
+    );
+    assert_eq!(
+        match_installed_profile(" srgb color space profile ", &installed),
+        Some("sRGB Color Space Profile.icm")
+    );
+    assert_eq!(
+        match_installed_profile("vendor", &installed),
+        Some("vendor.icc")
+    );
+    assert_eq!(match_installed_profile("vendor.icm", &installed), None);
+    assert_eq!(match_installed_profile("missing", &installed), None);
+}
+
+#[test]
+fn resolve_installed_profile_rejects_paths() {
+    let err = resolve_installed_profile(r"C:\profiles\mine.icm").unwrap_err();
+    assert!(err.to_string().contains("--profile-path"));
+}
@@ -0,0 +1,222 @@
+//! Per-application profile overrides.
+//!
+//! `[app_overrides]` maps a process name to either a profile filename in the
+//! color store or `"none"`. A foreground WinEvent hook tracks which process
+//! is focused; when that changes the active override, [`EVENT_APP_OVERRIDE`]
+//! is queued and the debounce worker either applies the override (e.g. the
+//! factory profile while Photoshop is focused) or reapplies the dimming fix
+//! once the app loses focus or exits.
+//!
+//! Like the fullscreen hook, this only observes the desktop of the session
+//! it runs in.
+
+use super::fullscreen::{normalize_process_name, process_name_for_window};
+use super::{
+    effective_preset_for_mode, events::DisplayEvent, find_matching_monitors_for_config,
+    monitor_identity_from_match, refresh, tamper, EVENT_SENDER,
+};
+use lg_core::config::Config;
+use log::{info, warn};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::sync::{Mutex, OnceLock};
+
+use windows::Win32::Foundation::{HMODULE, HWND};
+use windows::Win32::UI::Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK};
+use windows::Win32::UI::WindowsAndMessaging::{
+    EVENT_SYSTEM_FOREGROUND, WINEVENT_OUTOFCONTEXT, WINEVENT_SKIPOWNPROCESS,
+};
+
+/// Override value that removes the dimming-fix profile instead of swapping.
+pub(crate) const OVERRIDE_NONE: &str = "none";
+
+static TRACKER: OnceLock<Mutex<OverrideTracker>> = OnceLock::new();
+
+/// An override that is currently in effect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ActiveOverride {
+    pub(crate) process: String,
+    pub(crate) profile: String,
+}
+
+/// Tracks the focused process against the configured overrides.
+#[derive(Debug, Default)]
+pub(crate) struct OverrideTracker {
+    overrides: Vec<(String, String)>,
+    active: Option<ActiveOverride>,
+}
+
+impl OverrideTracker {
+    pub(crate) fn new(overrides: &BTreeMap<String, String>) -> Self {
+        Self {
+            overrides: overrides
+                .iter()
+                .map(|(process, profile)| {
+                    (normalize_process_name(process), profile.trim().to_string())
+                })
+                .filter(|(process, profile)| !process.is_empty() && !profile.is_empty())
+                .collect(),
+            active: None,
+        }
+    }
+
+    /// Feed a foreground change. Returns true when the active override changed.
+    pub(crate) fn on_foreground(&mut self, process: &str) -> bool {
+        let process = normalize_process_name(process);
+        let next = self
+            .overrides
+            .iter()
+            .find(|(p, _)| *p == process)
+            .map(|(p, profile)| ActiveOverride {
+                process: p.clone(),
+                profile: profile.clone(),
+            });
+        if next == self.active {
+            return false;
+        }
+        self.active = next;
+        true
+    }
+
+    pub(crate) fn active(&self) -> Option<ActiveOverride> {
+        self.active.clone()
+    }
+}
+
+/// The override currently in effect, if any.
+pub(crate) fn active_override() -> Option<ActiveOverride> {
+    TRACKER
+        .get()
+        .and_then(|t| t.lock().ok())
+        .and_then(|t| t.active())
+}
+
+/// Install the foreground hook on the current (message-pump) thread.
+pub(crate) fn install(overrides: &BTreeMap<String, String>) -> Option<HWINEVENTHOOK> {
+    let tracker = TRACKER.get_or_init(|| Mutex::new(OverrideTracker::default()));
+    if let Ok(mut t) = tracker.lock() {
+        *t = OverrideTracker::new(overrides);
+    }
+
+    let hook = unsafe {
+        SetWinEventHook(
+            EVENT_SYSTEM_FOREGROUND,
+            EVENT_SYSTEM_FOREGROUND,
+            HMODULE::default(),
+            Some(foreground_proc),
+            0,
+            0,
+            WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+        )
+    };
+    if hook.is_invalid() {
+        warn!("SetWinEventHook(EVENT_SYSTEM_FOREGROUND) failed — app overrides disabled");
+        return None;
+    }
+    info!("App override hook installed ({} rule(s))", overrides.len());
+    Some(hook)
+}
+
+/// Remove a hook returned by [`install`].
+pub(crate) fn uninstall(hook: HWINEVENTHOOK) {
+    unsafe {
+        let _ = UnhookWinEvent(hook);
+    }
+}
+
+unsafe extern "system" fn foreground_proc(
+    _hook: HWINEVENTHOOK,
+    _event: u32,
+    hwnd: HWND,
+    _id_object: i32,
+    _id_child: i32,
+    _thread: u32,
+    _time: u32,
+) {
+    let _ = super::crash::guard("app override hook", || on_foreground_change(hwnd));
+}
+
+fn on_foreground_change(hwnd: HWND) {
+    if hwnd.0.is_null() {
+        return;
+    }
+    let process = process_name_for_window(hwnd).unwrap_or_default();
+    let changed = TRACKER
+        .get()
+        .and_then(|t| t.lock().ok())
+        .is_some_and(|mut t| t.on_foreground(&process));
+
+    if changed {
+        EVENT_SENDER.with(|s| {
+            if let Some(tx) = s.borrow().as_ref() {
+                let _ = tx.send(DisplayEvent::AppOverride);
+            }
+        });
+    }
+}
+
+/// Apply an override to every matched monitor.
+///
+/// `"none"` removes the dimming-fix SDR/HDR profiles so Windows falls back
+/// to the monitor's own default; anything else is treated as a profile
+/// filename in the color store and made the display default.
+pub(crate) fn apply(config: &Config, active: &ActiveOverride) -> Result<usize, Box<dyn Error>> {
+    let devices = find_matching_monitors_for_config(config)?;
+    let color_dir = lg_profile::color_directory();
+    let mut applied = 0usize;
+
+    if active.profile.eq_ignore_ascii_case(OVERRIDE_NONE) {
+        let mut presets = vec![
+            effective_preset_for_mode(config, false),
+            effective_preset_for_mode(config, true),
+        ];
+        presets.dedup();
+        for device in &devices {
+            for preset in &presets {
+                let path = if config.icc_per_monitor_profiles {
+                    lg_profile::resolve_monitor_active_profile_path(
+                        &color_dir,
+                        preset,
+                        &config.profile_name,
+                        &monitor_identity_from_match(device),
+                    )
+                } else {
+                    lg_profile::resolve_active_profile_path(
+                        &color_dir,
+                        preset,
+                        &config.profile_name,
+                    )
+                };
+                if let Err(e) = lg_profile::disassociate_profile(&device.device_key, &path, false) {
+                    warn!("App override: {} (non-fatal)", e);
+                }
+            }
+            tamper::forget(&device.device_key);
+            applied += 1;
+        }
+    } else {
+        let path = color_dir.join(&active.profile);
+        if !path.exists() {
+            return Err(format!(
+                "override profile for {} not found: {}",
+                active.process,
+                path.display()
+            )
+            .into());
+        }
+        for device in &devices {
+            lg_profile::reapply_profile(&device.device_key, &path, config.toggle_delay_ms, false)?;
+            lg_profile::set_display_default_association(&device.device_key, &path, false)?;
+            tamper::record_applied(&device.device_key, &path);
+            applied += 1;
+        }
+    }
+
+    refresh::refresh_display(config);
+    lg_profile::trigger_calibration_loader(config.refresh_calibration_loader);
+    Ok(applied)
+}
+
+#[cfg(test)]
+#[path = "tests/app_override_tests.rs"]
+mod tests;
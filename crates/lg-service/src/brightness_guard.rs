@@ -0,0 +1,189 @@
+//! DDC/CI brightness restore guard.
+//!
+//! Polls VCP 0x10 on every physical monitor matched by the configured
+//! `monitor_match` pattern (name substring or match expression) and
+//! rewrites `ddc_brightness_value` whenever the firmware lowers it on its
+//! own. This attacks auto-dimming at the source, independently of the ICC
+//! profile trick.
+//!
+//! Two safeguards keep the guard from fighting the user or the monitor:
+//!   - **Hysteresis**: only drops larger than `brightness_guard_threshold`
+//!     below the target are restored; raising brightness is never undone.
+//!   - **Rate limit**: at most `brightness_guard_max_per_minute` restores per
+//!     monitor per minute, so a monitor that keeps re-dimming can't turn the
+//!     guard into a DDC write storm.
+
+use lg_core::config::Config;
+use lg_core::shutdown::ShutdownToken;
+use lg_core::state as app_state;
+use lg_monitor::ddc::{DdcSession, VCP_BRIGHTNESS};
+use log::{info, warn};
+use std::collections::{HashMap, VecDeque};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Lower bound for the poll interval — DDC/CI reads take tens of ms and
+/// tighter loops only produce I2C bus errors.
+const MIN_INTERVAL_MS: u64 = 250;
+
+/// Sliding window used by the rate limiter.
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// True when `current` has dropped far enough below `target` to restore.
+pub(crate) fn should_restore(current: u32, target: u32, threshold: u32) -> bool {
+    current.saturating_add(threshold) < target
+}
+
+/// Sliding-window limiter for restore writes on one monitor.
+#[derive(Debug)]
+pub(crate) struct RestoreRateLimiter {
+    max_per_minute: u32,
+    recent: VecDeque<Instant>,
+}
+
+impl RestoreRateLimiter {
+    pub(crate) fn new(max_per_minute: u32) -> Self {
+        Self {
+            max_per_minute,
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Record a restore at `now` if the budget allows it.
+    pub(crate) fn try_acquire(&mut self, now: Instant) -> bool {
+        if self.max_per_minute == 0 {
+            return true;
+        }
+        while self
+            .recent
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= RATE_WINDOW)
+        {
+            self.recent.pop_front();
+        }
+        if self.recent.len() as u32 >= self.max_per_minute {
+            return false;
+        }
+        self.recent.push_back(now);
+        true
+    }
+}
+
+/// Spawn the guard thread. Returns `None` when the guard is disabled.
+pub(crate) fn spawn(
+    config: &Config,
+    shutdown: &ShutdownToken,
+) -> Option<std::io::Result<thread::JoinHandle<()>>> {
+    if !config.brightness_guard {
+        return None;
+    }
+    if !lg_monitor::ddc::is_supported() {
+        warn!("Brightness guard disabled: DDC/CI is not supported on this system");
+        return None;
+    }
+    let config = config.clone();
+    Some(super::supervisor::spawn(
+        "brightness-guard",
+        shutdown,
+        move |shutdown| run(&config, shutdown),
+    ))
+}
+
+fn run(config: &Config, shutdown: &ShutdownToken) {
+    let target = config.ddc_brightness_value.min(100);
+    let interval = Duration::from_millis(config.brightness_guard_interval_ms.max(MIN_INTERVAL_MS));
+    let mut limiters: HashMap<usize, RestoreRateLimiter> = HashMap::new();
+    let mut throttled: HashMap<usize, bool> = HashMap::new();
+    let mut match_failed = false;
+
+    info!(
+        "Brightness guard started: target={} threshold={} interval={}ms",
+        target,
+        config.brightness_guard_threshold,
+        interval.as_millis()
+    );
+
+    while shutdown.sleep(interval) {
+        // One session per poll: the cached handles are reused and the DDC
+        // lock is taken once, not per read and write.
+        let session = match DdcSession::open() {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("Brightness guard: monitor enumeration failed: {}", e);
+                continue;
+            }
+        };
+
+        // Expressions resolve by device key, so a NOT/AND clause can't be
+        // bypassed by another monitor sharing the model name.
+        let targets = match session.find_all(&config.monitor_match) {
+            Ok(t) => {
+                match_failed = false;
+                t
+            }
+            Err(e) => {
+                // Log once per failing streak (monitor unplugged, asleep...).
+                if !match_failed {
+                    warn!("Brightness guard: {}", e);
+                    match_failed = true;
+                }
+                continue;
+            }
+        };
+        let names = session.monitors();
+
+        for index in targets {
+            let name = &names[index].1;
+            let current = match session.get_vcp(index, VCP_BRIGHTNESS) {
+                Ok(v) => v.current,
+                Err(_) => continue,
+            };
+            if !should_restore(current, target, config.brightness_guard_threshold) {
+                throttled.remove(&index);
+                continue;
+            }
+
+            let limiter = limiters
+                .entry(index)
+                .or_insert_with(|| RestoreRateLimiter::new(config.brightness_guard_max_per_minute));
+            if !limiter.try_acquire(Instant::now()) {
+                // Log once per throttled streak instead of every poll.
+                if !throttled.insert(index, true).unwrap_or(false) {
+                    warn!(
+                        "Brightness guard rate limit reached for {} (#{})",
+                        name, index
+                    );
+                    app_state::append_diagnostic_event(
+                        "service",
+                        "WARN",
+                        "brightness_guard_rate_limited",
+                        &format!("monitor={} current={} target={}", name, current, target),
+                    );
+                }
+                continue;
+            }
+
+            match session.set_vcp(index, VCP_BRIGHTNESS, target) {
+                Ok(()) => {
+                    info!(
+                        "Brightness guard restored {} (#{}) from {} to {}",
+                        name, index, current, target
+                    );
+                    app_state::append_diagnostic_event(
+                        "service",
+                        "INFO",
+                        "brightness_guard_restore",
+                        &format!("monitor={} from={} to={}", name, current, target),
+                    );
+                }
+                Err(e) => warn!("Brightness guard write failed for {}: {}", name, e),
+            }
+        }
+    }
+
+    info!("Brightness guard stopped");
+}
+
+#[cfg(test)]
+#[path = "tests/brightness_guard_tests.rs"]
+mod tests;
@@ -0,0 +1,79 @@
+//! Panics outside the supervised workers.
+//!
+//! The supervisor catches panics in background workers, but a panic on the
+//! service thread or in a Win32 callback ended the process with a bare
+//! 1067 ("terminated unexpectedly") and nothing in the Event Log. A panic
+//! cannot unwind out of an `extern "system"` callback at all, so the
+//! window procedure and WinEvent hooks run their bodies through [`guard`],
+//! and the event loop is run through [`catch`] so the service can still
+//! report a stopped state with a [`ServiceError`] exit code.
+
+use super::supervisor::panic_message;
+use lg_core::state as app_state;
+use log::error;
+use std::error::Error;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Why the service stopped with an error.
+#[derive(Debug)]
+pub(crate) enum ServiceError {
+    /// The event loop returned an error.
+    EventLoop(Box<dyn Error>),
+    /// The service thread panicked; holds the panic message.
+    Panicked(String),
+}
+
+impl ServiceError {
+    /// Service-specific exit code reported to the SCM (`sc query` shows it).
+    pub(crate) fn exit_code(&self) -> u32 {
+        match self {
+            Self::EventLoop(_) => 1,
+            Self::Panicked(_) => 2,
+        }
+    }
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EventLoop(e) => write!(f, "event loop error: {}", e),
+            Self::Panicked(message) => write!(f, "service thread panicked: {}", message),
+        }
+    }
+}
+
+impl Error for ServiceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::EventLoop(e) => Some(e.as_ref()),
+            Self::Panicked(_) => None,
+        }
+    }
+}
+
+/// Run `f`, turning a panic into `Err` with its message after logging it
+/// as `context`.
+pub(crate) fn catch<R>(context: &str, f: impl FnOnce() -> R) -> Result<R, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let message = panic_message(payload.as_ref());
+        error!("Panic in {}: {}", context, message);
+        app_state::append_diagnostic_event(
+            "service",
+            "ERROR",
+            "panic",
+            &format!("context={} message={}", context, message),
+        );
+        message
+    })
+}
+
+/// Body of an `extern "system"` callback: `None` when it panicked (logged),
+/// so the caller can fall back to the default handling.
+pub(crate) fn guard<R>(context: &str, f: impl FnOnce() -> R) -> Option<R> {
+    catch(context, f).ok()
+}
+
+#[cfg(test)]
+#[path = "tests/crash_tests.rs"]
+mod tests;
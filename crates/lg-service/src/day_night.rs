@@ -0,0 +1,119 @@
+//! Day/night ICC preset scheduler.
+//!
+//! When both `icc_schedule_day_preset` and `icc_schedule_night_preset` are
+//! set, this thread sleeps until the next schedule boundary (fixed times or
+//! sunrise/sunset, see [`lg_core::schedule`]) and queues [`EVENT_SCHEDULE`]
+//! so the debounce worker runs the normal reapply pipeline, which picks the
+//! preset for the new period.
+//!
+//! With `[schedule] use_windows_location` the position for solar mode comes
+//! from the Windows Location API ([`lookup_location`]), which needs location
+//! access enabled for the machine. The lookup is registered with
+//! [`schedule::set_location_lookup`] and only runs once solar times are
+//! actually needed.
+
+use super::events::DisplayEvent;
+use lg_core::config::Config;
+use lg_core::schedule::{self, DayPeriod};
+use lg_core::shutdown::ShutdownToken;
+use lg_core::state as app_state;
+use log::{info, warn};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Slack added past each boundary so the new period is already in effect
+/// when the reapply resolves the preset.
+const BOUNDARY_GRACE: Duration = Duration::from_secs(2);
+
+/// Longest single sleep, so shutdown is noticed promptly.
+const MAX_SLEEP_STEP: Duration = Duration::from_secs(1);
+
+/// Toast body announcing a scheduled switch.
+pub(crate) fn toast_body(period: DayPeriod, preset: &str) -> String {
+    format!("Switched to {} profile ({})", period.label(), preset)
+}
+
+/// Location lookup handed to [`schedule::set_location_lookup`].
+pub(crate) fn lookup_location() -> Option<(f64, f64)> {
+    let location = windows_location();
+    match location {
+        Some((latitude, longitude)) => {
+            info!("Windows location: {:.4}, {:.4}", latitude, longitude)
+        }
+        None => warn!("Windows location unavailable — using configured coordinates"),
+    }
+    location
+}
+
+fn windows_location() -> Option<(f64, f64)> {
+    use windows::Devices::Geolocation::Geolocator;
+
+    let locator = Geolocator::new().ok()?;
+    let position = locator.GetGeopositionAsync().ok()?.get().ok()?;
+    let point = position.Coordinate().ok()?.Point().ok()?.Position().ok()?;
+    Some((point.Latitude, point.Longitude))
+}
+
+/// Spawn the scheduler thread. Returns `None` when no schedule is configured.
+pub(crate) fn spawn(
+    config: &Config,
+    shutdown: &ShutdownToken,
+    tx: mpsc::Sender<DisplayEvent>,
+) -> Option<std::io::Result<thread::JoinHandle<()>>> {
+    if !schedule::is_enabled(config) {
+        return None;
+    }
+    let config = config.clone();
+    Some(super::supervisor::spawn(
+        "day-night-scheduler",
+        shutdown,
+        move |shutdown| run(&config, shutdown, &tx),
+    ))
+}
+
+fn run(config: &Config, shutdown: &ShutdownToken, tx: &mpsc::Sender<DisplayEvent>) {
+    info!(
+        "Day/night scheduler started: day={} night={} ({})",
+        config.icc_schedule_day_preset,
+        config.icc_schedule_night_preset,
+        if schedule::is_solar(config) {
+            "sunrise/sunset"
+        } else {
+            "fixed times"
+        }
+    );
+
+    'outer: while !shutdown.is_cancelled() {
+        let wait = schedule::until_next_transition(config) + BOUNDARY_GRACE;
+        info!("Next day/night switch in {}s", wait.as_secs());
+        let deadline = Instant::now() + wait;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            if !shutdown.sleep(remaining.min(MAX_SLEEP_STEP)) {
+                break 'outer;
+            }
+        }
+
+        let period = schedule::current_period(config);
+        info!("Day/night schedule switched to {}", period.label());
+        app_state::append_diagnostic_event(
+            "service",
+            "INFO",
+            "schedule_switch",
+            &format!("period={}", period.label()),
+        );
+        if tx.send(DisplayEvent::Schedule).is_err() {
+            break;
+        }
+    }
+
+    info!("Day/night scheduler stopped");
+}
+
+#[cfg(test)]
+#[path = "tests/day_night_tests.rs"]
+mod tests;
@@ -0,0 +1,430 @@
+//! Event coalescing for the debounce worker.
+//!
+//! A replug or wake produces a burst of device and session notifications
+//! over a second or two. [`Debouncer`] turns that stream into one reapply:
+//! events are OR'd together for the stabilize window, a device-only batch is
+//! checked against the matching monitors, and device or session batches wait
+//! `reapply_delay_ms` for the display to initialize before the reapply runs.
+//! A batch made only of monitor arrivals is reapplied to just those monitors
+//! (see [`Targets`]).
+//!
+//! With `kvm_mode` on, a monitor that comes back within
+//! [`KVM_SWITCH_WINDOW`] of being removed is taken as a KVM switch: the
+//! batch settles as fast as a user request and skips both the monitor
+//! check and the initialization wait (see [`Debouncer::kvm_switch`]).
+//!
+//! With `dock_mode` on, every device event pushes the settle deadline out
+//! to a full stabilize window again (up to [`DOCK_SETTLE_MAX`]), so a
+//! dock's USB topology has gone quiet before anything runs, and device
+//! batches need [`DOCK_CONFIRMATIONS`] monitor checks in a row,
+//! [`DOCK_CONFIRM_INTERVAL`] apart, before the initialization wait starts.
+//!
+//! The type does no I/O and never reads the clock: the worker feeds it
+//! events and the current time, and carries out the [`Action`] it returns
+//! (block on the channel, query WMI, run the reapply). That keeps the timing
+//! rules testable without a message loop.
+
+use super::events::{DisplayEvent, Targets};
+use super::{EVENT_HOTKEY_BRIGHTNESS, EVENT_MASK_DEVICE, EVENT_MASK_SESSION, EVENT_MASK_USER};
+use std::time::{Duration, Instant};
+
+/// Coalescing window for user requests — short, since the user is waiting.
+pub(crate) const USER_SETTLE: Duration = Duration::from_millis(150);
+
+/// Longest gap between a monitor's removal and its return that still
+/// counts as a KVM switch.
+pub(crate) const KVM_SWITCH_WINDOW: Duration = Duration::from_secs(10);
+
+/// Longest a device burst can keep a batch settling in `dock_mode`.
+pub(crate) const DOCK_SETTLE_MAX: Duration = Duration::from_secs(30);
+
+/// Monitor checks in a row a device batch needs in `dock_mode`.
+pub(crate) const DOCK_CONFIRMATIONS: u8 = 2;
+
+/// Gap between those checks.
+pub(crate) const DOCK_CONFIRM_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What the worker should do next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Action {
+    /// Block for the next event; at `Some(deadline)` report a timeout.
+    Wait(Option<Instant>),
+    /// The batch settled: log it, apply any brightness hotkey step, then
+    /// call [`Debouncer::settled`].
+    Settled(u32),
+    /// Check that a matching monitor is present, then call
+    /// [`Debouncer::validated`].
+    Validate(u32),
+    /// Run the reapply pipeline for the batch, then call
+    /// [`Debouncer::reapplied`].
+    Reapply(u32),
+    /// The channel closed; the worker should exit.
+    Stop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Idle,
+    /// Coalescing events until `deadline`.
+    Settling {
+        flags: u32,
+        deadline: Instant,
+    },
+    /// Waiting for the worker to handle [`Action::Settled`].
+    Settled {
+        flags: u32,
+    },
+    /// Waiting for the worker's monitor check.
+    Validating {
+        flags: u32,
+    },
+    /// `dock_mode`: the last check found the monitors; check again at
+    /// `deadline`.
+    Confirming {
+        flags: u32,
+        deadline: Instant,
+    },
+    /// Giving the display until `deadline` to initialize.
+    Initializing {
+        flags: u32,
+        deadline: Instant,
+    },
+    /// The reapply is running; events arriving now are covered by it
+    /// unless they concern a monitor it doesn't reach.
+    Applying,
+}
+
+/// How an event bears on the KVM switch detection of its batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KvmKind {
+    /// A monitor returned shortly after its removal.
+    Switch,
+    /// Devnode noise that accompanies any switch.
+    Neutral,
+    /// Anything else; the batch gets the normal handling.
+    Other,
+}
+
+/// Coalescing, validation and delay state of the debounce worker.
+#[derive(Debug)]
+pub(crate) struct Debouncer {
+    stabilize: Duration,
+    reapply_delay: Duration,
+    phase: Phase,
+    /// Monitors the current batch has to reach.
+    targets: Targets,
+    /// Events the running reapply doesn't cover; they start the next batch.
+    pending: Option<(u32, Targets)>,
+    kvm_mode: bool,
+    /// Monitors removed recently, and when (only kept in `kvm_mode`).
+    removed: Vec<(String, Instant)>,
+    /// The current batch contains a KVM switch ...
+    kvm_switch_seen: bool,
+    /// ... and something that isn't one.
+    kvm_other_seen: bool,
+    dock_mode: bool,
+    /// When the current batch started settling.
+    opened: Option<Instant>,
+    /// Monitor checks in a row that found the monitors.
+    confirmations: u8,
+}
+
+impl Debouncer {
+    pub(crate) fn new(stabilize: Duration, reapply_delay: Duration) -> Self {
+        Self {
+            stabilize,
+            reapply_delay,
+            phase: Phase::Idle,
+            targets: Targets::All,
+            pending: None,
+            kvm_mode: false,
+            removed: Vec::new(),
+            kvm_switch_seen: false,
+            kvm_other_seen: false,
+            dock_mode: false,
+            opened: None,
+            confirmations: 0,
+        }
+    }
+
+    /// Turn on the docking-station handling (`dock_mode`).
+    pub(crate) fn with_dock_mode(mut self, on: bool) -> Self {
+        self.dock_mode = on;
+        self
+    }
+
+    /// True while waiting to repeat a `dock_mode` monitor check.
+    pub(crate) fn confirming(&self) -> bool {
+        matches!(self.phase, Phase::Confirming { .. })
+    }
+
+    /// Turn on KVM switch detection (`kvm_mode`).
+    pub(crate) fn with_kvm_mode(mut self, on: bool) -> Self {
+        self.kvm_mode = on;
+        self
+    }
+
+    /// True when the current batch is a KVM switch and nothing else; read
+    /// it for [`Action::Reapply`] to reapply quietly.
+    pub(crate) fn kvm_switch(&self) -> bool {
+        self.kvm_switch_seen && !self.kvm_other_seen
+    }
+
+    /// Monitors the current batch has to reach; read it for
+    /// [`Action::Validate`] and [`Action::Reapply`].
+    pub(crate) fn targets(&self) -> &Targets {
+        &self.targets
+    }
+
+    /// An event arrived at `now`.
+    pub(crate) fn event(&mut self, event: DisplayEvent, now: Instant) -> Action {
+        if let DisplayEvent::DeviceRemoval { device_key } = event {
+            if let Some(key) = device_key.filter(|_| self.kvm_mode) {
+                self.removed
+                    .retain(|(_, at)| now.saturating_duration_since(*at) <= KVM_SWITCH_WINDOW);
+                self.removed.push((key, now));
+            }
+            return self.timeout(now);
+        }
+        let flag = event.flag();
+        let targets = event.targets();
+        let kvm = self.kvm_kind(&event, now);
+        match self.phase {
+            Phase::Idle => {
+                self.begin(flag, targets, now);
+                self.note_kvm(kvm, now);
+                self.timeout(now)
+            }
+            Phase::Settling { flags, deadline } => {
+                let deadline = match self.opened {
+                    Some(opened) if self.dock_mode && flag & EVENT_MASK_DEVICE != 0 => {
+                        deadline.max((now + self.stabilize).min(opened + DOCK_SETTLE_MAX))
+                    }
+                    _ => deadline,
+                };
+                self.targets.merge(targets);
+                self.phase = Phase::Settling {
+                    flags: flags | flag,
+                    deadline,
+                };
+                self.note_kvm(kvm, now);
+                self.timeout(now)
+            }
+            Phase::Settled { flags } => {
+                self.note_kvm(kvm, now);
+                self.targets.merge(targets);
+                self.phase = Phase::Settled {
+                    flags: flags | flag,
+                };
+                Action::Settled(flags | flag)
+            }
+            Phase::Validating { flags } => {
+                self.note_kvm(kvm, now);
+                self.targets.merge(targets);
+                self.phase = Phase::Validating {
+                    flags: flags | flag,
+                };
+                Action::Validate(flags | flag)
+            }
+            // The dock is still changing; the count starts over.
+            Phase::Confirming { flags, deadline } => {
+                self.note_kvm(kvm, now);
+                self.targets.merge(targets);
+                if flag & EVENT_MASK_DEVICE != 0 {
+                    self.confirmations = 0;
+                }
+                self.phase = Phase::Confirming {
+                    flags: flags | flag,
+                    deadline,
+                };
+                self.timeout(now)
+            }
+            // Something new happened while waiting for the display; it has
+            // had long enough, so reapply now (to a new monitor as well).
+            Phase::Initializing { flags, .. } => {
+                self.note_kvm(kvm, now);
+                self.targets.merge(targets);
+                self.phase = Phase::Applying;
+                Action::Reapply(flags)
+            }
+            Phase::Applying => {
+                if !self.targets.covers(&targets) {
+                    match &mut self.pending {
+                        Some((flags, pending)) => {
+                            *flags |= flag;
+                            pending.merge(targets);
+                        }
+                        None => self.pending = Some((flag, targets)),
+                    }
+                }
+                Action::Wait(None)
+            }
+        }
+    }
+
+    /// No event arrived before the deadline of the last [`Action::Wait`].
+    pub(crate) fn timeout(&mut self, now: Instant) -> Action {
+        match self.phase {
+            Phase::Settling { flags, deadline } if now >= deadline => {
+                self.phase = Phase::Settled { flags };
+                Action::Settled(flags)
+            }
+            Phase::Initializing { flags, deadline } if now >= deadline => {
+                self.phase = Phase::Applying;
+                Action::Reapply(flags)
+            }
+            Phase::Confirming { flags, deadline } if now >= deadline => {
+                self.phase = Phase::Validating { flags };
+                Action::Validate(flags)
+            }
+            Phase::Settling { deadline, .. }
+            | Phase::Initializing { deadline, .. }
+            | Phase::Confirming { deadline, .. } => Action::Wait(Some(deadline)),
+            Phase::Settled { flags } => Action::Settled(flags),
+            Phase::Validating { flags } => Action::Validate(flags),
+            Phase::Idle | Phase::Applying => Action::Wait(None),
+        }
+    }
+
+    /// The worker handled [`Action::Settled`] at `now`.
+    pub(crate) fn settled(&mut self, now: Instant) -> Action {
+        let Phase::Settled { flags } = self.phase else {
+            return self.timeout(now);
+        };
+        // A brightness hotkey is a direct DDC step, not a profile reapply.
+        if flags & !EVENT_HOTKEY_BRIGHTNESS == 0 {
+            self.phase = Phase::Idle;
+            return Action::Wait(None);
+        }
+        // Device-only batches are checked before the long wait, so an
+        // unrelated device doesn't cost a reapply; in `dock_mode` every
+        // device batch is. A KVM switch brings back a monitor that was
+        // just there.
+        if flags & EVENT_MASK_DEVICE != 0
+            && (flags & EVENT_MASK_SESSION == 0 || self.dock_mode)
+            && !self.kvm_switch()
+        {
+            self.phase = Phase::Validating { flags };
+            return Action::Validate(flags);
+        }
+        self.initialize(flags, now)
+    }
+
+    /// Result of the monitor check requested by [`Action::Validate`].
+    pub(crate) fn validated(&mut self, monitors_present: bool, now: Instant) -> Action {
+        let Phase::Validating { flags } = self.phase else {
+            return self.timeout(now);
+        };
+        if !monitors_present {
+            self.phase = Phase::Idle;
+            return Action::Wait(None);
+        }
+        if self.dock_mode {
+            self.confirmations += 1;
+            if self.confirmations < DOCK_CONFIRMATIONS {
+                let deadline = now + DOCK_CONFIRM_INTERVAL;
+                self.phase = Phase::Confirming { flags, deadline };
+                return Action::Wait(Some(deadline));
+            }
+        }
+        self.initialize(flags, now)
+    }
+
+    /// The reapply requested by [`Action::Reapply`] finished at `now`.
+    pub(crate) fn reapplied(&mut self, now: Instant) -> Action {
+        self.phase = Phase::Idle;
+        match self.pending.take() {
+            Some((flags, targets)) => {
+                self.begin(flags, targets, now);
+                self.timeout(now)
+            }
+            None => Action::Wait(None),
+        }
+    }
+
+    /// The channel closed.
+    pub(crate) fn disconnected(&mut self) -> Action {
+        self.phase = Phase::Idle;
+        self.pending = None;
+        Action::Stop
+    }
+
+    /// Open a new batch. The first event picks the window: user requests
+    /// settle quickly, everything else waits out the whole burst.
+    fn begin(&mut self, flags: u32, targets: Targets, now: Instant) {
+        let window = if flags & EVENT_MASK_USER != 0 {
+            USER_SETTLE
+        } else {
+            self.stabilize
+        };
+        self.targets = targets;
+        self.kvm_switch_seen = false;
+        self.kvm_other_seen = false;
+        self.opened = Some(now);
+        self.confirmations = 0;
+        self.phase = Phase::Settling {
+            flags,
+            deadline: now + window,
+        };
+    }
+
+    /// Classify `event` for KVM switch detection, consuming the matching
+    /// removal.
+    fn kvm_kind(&mut self, event: &DisplayEvent, now: Instant) -> KvmKind {
+        match event {
+            DisplayEvent::DeviceArrival {
+                device_key: Some(key),
+            } => {
+                let recent = self.removed.iter().position(|(removed, at)| {
+                    removed.eq_ignore_ascii_case(key)
+                        && now.saturating_duration_since(*at) <= KVM_SWITCH_WINDOW
+                });
+                match recent {
+                    Some(index) => {
+                        self.removed.remove(index);
+                        KvmKind::Switch
+                    }
+                    None => KvmKind::Other,
+                }
+            }
+            DisplayEvent::DevNodesChanged => KvmKind::Neutral,
+            _ => KvmKind::Other,
+        }
+    }
+
+    /// Record `kind` in the current batch; a KVM switch cuts the settle
+    /// window down to [`USER_SETTLE`].
+    fn note_kvm(&mut self, kind: KvmKind, now: Instant) {
+        match kind {
+            KvmKind::Switch => self.kvm_switch_seen = true,
+            KvmKind::Other => self.kvm_other_seen = true,
+            KvmKind::Neutral => {}
+        }
+        if self.kvm_switch() {
+            if let Phase::Settling { deadline, .. } = &mut self.phase {
+                *deadline = (*deadline).min(now + USER_SETTLE);
+            }
+        }
+    }
+
+    /// Device and session changes wait for the display to initialize;
+    /// everything else (and a KVM switch) reapplies straight away.
+    fn initialize(&mut self, flags: u32, now: Instant) -> Action {
+        if flags & (EVENT_MASK_DEVICE | EVENT_MASK_SESSION) != 0
+            && !self.reapply_delay.is_zero()
+            && !self.kvm_switch()
+        {
+            self.phase = Phase::Initializing {
+                flags,
+                deadline: now + self.reapply_delay,
+            };
+            return Action::Wait(Some(now + self.reapply_delay));
+        }
+        self.phase = Phase::Applying;
+        Action::Reapply(flags)
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/debounce_tests.rs"]
+mod tests;
@@ -0,0 +1,133 @@
+//! Idle-aware deferral of background reapplies.
+//!
+//! A reapply briefly flashes the screen while the profile is toggled and the
+//! display refreshed. On the desktop that goes unnoticed; in a fullscreen
+//! video call or game it doesn't. With `defer_reapply` on, reapplies that
+//! nobody asked for (device changes, automation polls, the day/night
+//! schedule) wait until input has been idle for `defer_reapply_idle_ms` or
+//! the foreground window is no longer fullscreen. `defer_reapply_max_ms`
+//! caps the wait so the profile is never left off indefinitely.
+//!
+//! Idle time and the foreground window belong to the session the process
+//! runs in. The session-0 service has no foreground window, so there the
+//! check always lets the reapply through; use `watch` in the user session
+//! for deferral to take effect.
+
+use super::events::DisplayEvent;
+use super::fullscreen::fullscreen_foreground;
+use super::EVENT_MASK_USER;
+use lg_core::config::Config;
+use lg_core::state as app_state;
+use log::info;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use windows::Win32::System::SystemInformation::GetTickCount;
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+/// How often the busy state is re-checked while a reapply is held.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Outcome of one busy check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Decision {
+    /// Go ahead; the reason is recorded in the diagnostics log.
+    Apply(&'static str),
+    /// The user is busy in a fullscreen app — keep holding.
+    Wait,
+}
+
+/// Decide whether a held reapply may proceed.
+pub(crate) fn decide(
+    idle: Duration,
+    fullscreen: bool,
+    waited: Duration,
+    idle_threshold: Duration,
+    max_wait: Duration,
+) -> Decision {
+    if !fullscreen {
+        Decision::Apply("not_fullscreen")
+    } else if idle >= idle_threshold {
+        Decision::Apply("idle")
+    } else if waited >= max_wait {
+        Decision::Apply("timeout")
+    } else {
+        Decision::Wait
+    }
+}
+
+/// Time since the last keyboard or mouse input in this session.
+fn idle_time() -> Duration {
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    unsafe {
+        if !GetLastInputInfo(&mut info).as_bool() {
+            return Duration::MAX;
+        }
+        // Both tick counts wrap after ~49 days; wrapping_sub keeps the
+        // difference right across the wrap.
+        Duration::from_millis(u64::from(GetTickCount().wrapping_sub(info.dwTime)))
+    }
+}
+
+/// Hold a background reapply while the user is busy. Returns the event flags
+/// that arrived in the meantime (a user request among them ends the wait
+/// early), or `None` when the channel closes for shutdown.
+pub(crate) fn wait_while_busy(rx: &mpsc::Receiver<DisplayEvent>, config: &Config) -> Option<u32> {
+    let idle_threshold = Duration::from_millis(config.defer_reapply_idle_ms);
+    let max_wait = Duration::from_millis(config.defer_reapply_max_ms);
+    let started = Instant::now();
+    let mut received = 0u32;
+    let mut deferred = false;
+
+    loop {
+        let waited = started.elapsed();
+        let decision = if received & EVENT_MASK_USER != 0 {
+            Decision::Apply("user_request")
+        } else {
+            decide(
+                idle_time(),
+                fullscreen_foreground().is_some(),
+                waited,
+                idle_threshold,
+                max_wait,
+            )
+        };
+        match decision {
+            Decision::Apply(reason) => {
+                if deferred {
+                    info!(
+                        "Deferred reapply released after {}ms ({})",
+                        waited.as_millis(),
+                        reason
+                    );
+                    app_state::append_diagnostic_event(
+                        "service",
+                        "INFO",
+                        "reapply_deferred",
+                        &format!("reason={} waited_ms={}", reason, waited.as_millis()),
+                    );
+                }
+                return Some(received);
+            }
+            Decision::Wait if !deferred => {
+                info!("User is active in a fullscreen app — deferring reapply");
+                deferred = true;
+            }
+            Decision::Wait => {}
+        }
+
+        let timeout = POLL_INTERVAL.min(max_wait.saturating_sub(waited));
+        match rx.recv_timeout(timeout) {
+            Ok(event) => received |= event.flag(),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/defer_tests.rs"]
+mod tests;
@@ -0,0 +1,153 @@
+//! Console display power tracking.
+//!
+//! Reapplying while the monitor is off or asleep is wasted work: the
+//! association toggle and refresh have nothing to act on, and the monitor
+//! re-reads its state when it wakes anyway. The event loop registers for
+//! `GUID_CONSOLE_DISPLAY_STATE`; while the console display is off, reapplies
+//! are held and a single pending one runs when it turns back on.
+//!
+//! The console display state covers every monitor attached to the console,
+//! so a single monitor switched off at its power button while another stays
+//! on is still treated as on.
+
+use lg_core::state as app_state;
+use log::info;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicU8, Ordering};
+
+use windows::core::GUID;
+use windows::Win32::Foundation::{HANDLE, HWND, LPARAM, WPARAM};
+use windows::Win32::System::Power::{
+    RegisterPowerSettingNotification, UnregisterPowerSettingNotification, HPOWERNOTIFY,
+    POWERBROADCAST_SETTING,
+};
+use windows::Win32::UI::WindowsAndMessaging::DEVICE_NOTIFY_WINDOW_HANDLE;
+
+pub(crate) const WM_POWERBROADCAST: u32 = 0x0218;
+const PBT_POWERSETTINGCHANGE: usize = 0x8013;
+
+/// `GUID_CONSOLE_DISPLAY_STATE`: 0 = off, 1 = on, 2 = dimmed.
+const GUID_CONSOLE_DISPLAY_STATE: GUID = GUID::from_u128(0x6fe69556_704a_47a0_8f24_c28d936fda47);
+
+/// Power state of the console display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DisplayState {
+    Off,
+    On,
+    Dimmed,
+}
+
+impl DisplayState {
+    pub(crate) fn from_raw(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Off),
+            1 => Some(Self::On),
+            2 => Some(Self::Dimmed),
+            _ => None,
+        }
+    }
+
+    fn as_raw(self) -> u8 {
+        match self {
+            Self::Off => 0,
+            Self::On => 1,
+            Self::Dimmed => 2,
+        }
+    }
+}
+
+/// Assumed on until the first notification arrives.
+static STATE: AtomicU8 = AtomicU8::new(1);
+
+/// A reapply was held while the display was off.
+static PENDING: AtomicBool = AtomicBool::new(false);
+
+static NOTIFY_HANDLE: AtomicIsize = AtomicIsize::new(0);
+
+/// Register `hwnd` for display state changes. Returns false on failure, in
+/// which case the display is always treated as on.
+pub(crate) fn register(hwnd: HWND) -> bool {
+    let handle = unsafe {
+        RegisterPowerSettingNotification(
+            HANDLE(hwnd.0),
+            &GUID_CONSOLE_DISPLAY_STATE,
+            DEVICE_NOTIFY_WINDOW_HANDLE,
+        )
+    };
+    let handle = handle.map_or(0, |h| h.0);
+    NOTIFY_HANDLE.store(handle, Ordering::SeqCst);
+    handle != 0
+}
+
+pub(crate) fn unregister() {
+    let handle = NOTIFY_HANDLE.swap(0, Ordering::SeqCst);
+    if handle != 0 {
+        unsafe {
+            let _ = UnregisterPowerSettingNotification(HPOWERNOTIFY(handle));
+        }
+    }
+}
+
+/// Current console display state.
+pub(crate) fn state() -> DisplayState {
+    DisplayState::from_raw(STATE.load(Ordering::SeqCst) as u32).unwrap_or(DisplayState::On)
+}
+
+/// True when a reapply should be held. Dimmed still counts as on: the
+/// panel is lit and a reapply is visible.
+pub(crate) fn display_off() -> bool {
+    state() == DisplayState::Off
+}
+
+/// Whether going from `previous` to `current` should release a held reapply.
+pub(crate) fn turned_on(previous: DisplayState, current: DisplayState) -> bool {
+    previous == DisplayState::Off && current != DisplayState::Off
+}
+
+/// Hold a reapply until the display is back on. Only one is kept; the
+/// pipeline rebuilds everything from the current state anyway.
+pub(crate) fn hold(trigger: &str) {
+    if !PENDING.swap(true, Ordering::SeqCst) {
+        info!(
+            "Display is off; {} reapply held until it turns back on",
+            trigger
+        );
+        app_state::append_diagnostic_event(
+            "service",
+            "INFO",
+            "apply_held_display_off",
+            &format!("trigger={}", trigger),
+        );
+    }
+}
+
+/// Handle `WM_POWERBROADCAST`. Returns true when the display just turned
+/// back on with a reapply held.
+///
+/// # Safety
+/// `lparam` must be the `lParam` of a `WM_POWERBROADCAST` message.
+pub(crate) unsafe fn on_power_broadcast(wparam: WPARAM, lparam: LPARAM) -> bool {
+    if wparam.0 != PBT_POWERSETTINGCHANGE || lparam.0 == 0 {
+        return false;
+    }
+    let setting = &*(lparam.0 as *const POWERBROADCAST_SETTING);
+    if setting.PowerSetting != GUID_CONSOLE_DISPLAY_STATE
+        || (setting.DataLength as usize) < std::mem::size_of::<u32>()
+    {
+        return false;
+    }
+    let raw = ptr::read_unaligned(setting.Data.as_ptr() as *const u32);
+    let Some(current) = DisplayState::from_raw(raw) else {
+        return false;
+    };
+    let previous = state();
+    STATE.store(current.as_raw(), Ordering::SeqCst);
+    if previous != current {
+        info!("Console display state: {:?} -> {:?}", previous, current);
+    }
+    turned_on(previous, current) && PENDING.swap(false, Ordering::SeqCst)
+}
+
+#[cfg(test)]
+#[path = "tests/display_power_tests.rs"]
+mod tests;
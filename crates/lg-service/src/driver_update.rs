@@ -0,0 +1,132 @@
+//! Display driver update detection.
+//!
+//! A GPU driver update or clean reinstall resets the color state and often
+//! re-enumerates the monitors under new instance IDs, after which the
+//! profile had to be reapplied by hand. The service records the device key
+//! and driver version of every matched monitor in
+//! `state\display_drivers.toml` and compares on startup and after device
+//! changes. A difference is written to the Event Log, and the reapply that
+//! follows is not deferred and carries associations over from the old
+//! instance IDs.
+//!
+//! On hybrid graphics laptops the monitor moves between GPUs, and gets a
+//! different instance ID under each. The GPU is recorded as well, and a
+//! move is logged as a warning and handled like a reinstall.
+
+use super::find_matching_monitors_for_config;
+use super::tamper;
+use lg_core::config::Config;
+use lg_core::state::{self as app_state, DisplayDriverChange, DisplayDriverRecord};
+use log::{info, warn};
+
+/// What each matched monitor is driven by right now.
+fn current_records(config: &Config) -> Option<Vec<DisplayDriverRecord>> {
+    let monitors = match find_matching_monitors_for_config(config) {
+        Ok(monitors) => monitors,
+        Err(e) => {
+            warn!("Driver check: monitor query failed: {}", e);
+            return None;
+        }
+    };
+    let adapters = lg_monitor::query_display_adapters().unwrap_or_else(|e| {
+        warn!("Driver check: adapter query failed: {}", e);
+        Vec::new()
+    });
+    Some(
+        monitors
+            .iter()
+            .map(|monitor| {
+                let adapter = lg_monitor::adapter_for_monitor(&adapters, monitor);
+                DisplayDriverRecord {
+                    device_key: monitor.device_key.clone(),
+                    adapter_name: adapter.map(|a| a.adapter_name.clone()).unwrap_or_default(),
+                    adapter_instance: adapter
+                        .map(|a| a.adapter_instance.clone())
+                        .unwrap_or_default(),
+                    driver_version: adapter
+                        .map(|a| a.driver_version.clone())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Compare the current drivers with the recorded ones, store the new state,
+/// and report whether the display driver was updated or reinstalled.
+pub(crate) fn detect(config: &Config) -> bool {
+    let Some(current) = current_records(config) else {
+        return false;
+    };
+    if current.is_empty() {
+        return false;
+    }
+    let mut state = app_state::load_display_driver_state();
+    let changes = app_state::detect_display_driver_changes(&state.displays, &current);
+
+    for change in &changes {
+        let (level, event, details) = match change {
+            DisplayDriverChange::Updated {
+                device_key,
+                from,
+                to,
+            } => {
+                info!(
+                    "Display driver updated for {} ({} -> {}); reassociating profiles",
+                    device_key, from, to
+                );
+                (
+                    "INFO",
+                    "driver_update",
+                    format!("device={} from={} to={}", device_key, from, to),
+                )
+            }
+            DisplayDriverChange::Reenumerated {
+                device_key,
+                previous_key,
+            } => {
+                info!(
+                    "Monitor {} came back as {} after a driver reinstall; reassociating profiles",
+                    previous_key, device_key
+                );
+                tamper::forget(previous_key);
+                (
+                    "INFO",
+                    "driver_update",
+                    format!("device={} previous={}", device_key, previous_key),
+                )
+            }
+            DisplayDriverChange::AdapterChanged {
+                device_key,
+                previous_key,
+                from,
+                to,
+            } => {
+                warn!(
+                    "Monitor {} moved to another GPU ({} -> {}), now {}; reassociating profiles",
+                    previous_key, from, to, device_key
+                );
+                if !previous_key.eq_ignore_ascii_case(device_key) {
+                    tamper::forget(previous_key);
+                }
+                (
+                    "WARN",
+                    "gpu_changed",
+                    format!(
+                        "device={} previous={} from={} to={}",
+                        device_key, previous_key, from, to
+                    ),
+                )
+            }
+        };
+        app_state::append_diagnostic_event("service", level, event, &details);
+    }
+
+    state.displays = app_state::merge_display_driver_records(&state.displays, &current, &changes);
+    state.updated_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    if let Err(e) = app_state::save_display_driver_state(&state) {
+        warn!("Driver check: could not save state: {}", e);
+    }
+
+    !changes.is_empty()
+}
@@ -0,0 +1,288 @@
+//! Reading the service's entries back out of the Application event log.
+//!
+//! `service logs` queries the log through the Windows Event Log API
+//! (`EvtQuery`) with an XPath filter on our event source and, optionally, a
+//! minimum level, so what the service did can be read without opening Event
+//! Viewer. Each event is rendered as XML and the few fields we show are
+//! picked out of it; the message is the event's insertion string, which is
+//! how `winlog` writes it. `--follow` polls for records newer than the last
+//! one printed.
+
+use super::SERVICE_NAME;
+use chrono::{DateTime, Local};
+use lg_core::wide::WideString;
+use std::error::Error;
+use std::fmt;
+use windows::Win32::Foundation::ERROR_NO_MORE_ITEMS;
+use windows::Win32::System::EventLog::{
+    EvtClose, EvtNext, EvtQuery, EvtQueryChannelPath, EvtQueryForwardDirection,
+    EvtQueryReverseDirection, EvtRender, EvtRenderEventXml, EVT_HANDLE,
+};
+
+/// Log the service's event source writes to.
+const CHANNEL: &str = "Application";
+
+/// Events fetched per `EvtNext` call.
+const BATCH: usize = 32;
+
+/// Severity of an event, most severe first, so `level <= min` keeps an
+/// event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EventLevel {
+    Error,
+    Warning,
+    Info,
+}
+
+impl EventLevel {
+    /// `error`, `warn`/`warning` or `info`/`information`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "error" => Some(Self::Error),
+            "warn" | "warning" => Some(Self::Warning),
+            "info" | "information" => Some(Self::Info),
+            _ => None,
+        }
+    }
+
+    /// The `<Level>` of a rendered event: 1 critical, 2 error, 3 warning,
+    /// anything else informational (classic sources log 0 or 4).
+    fn from_xml(level: u32) -> Self {
+        match level {
+            1 | 2 => Self::Error,
+            3 => Self::Warning,
+            _ => Self::Info,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Error => "ERROR",
+            Self::Warning => "WARN",
+            Self::Info => "INFO",
+        }
+    }
+}
+
+/// One event from our source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEvent {
+    pub record_id: u64,
+    /// `TimeCreated` as logged (UTC, RFC 3339).
+    pub time: String,
+    pub level: EventLevel,
+    pub event_id: u32,
+    pub message: String,
+}
+
+impl fmt::Display for LogEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let time = DateTime::parse_from_rfc3339(&self.time)
+            .map(|t| {
+                t.with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_else(|_| self.time.clone());
+        write!(f, "{}  {:<5}  {}", time, self.level.label(), self.message)
+    }
+}
+
+/// XPath selecting our events at `min_level` or more severe, newer than
+/// record `after` when given.
+fn build_query(min_level: EventLevel, after: Option<u64>) -> String {
+    let mut conditions = vec![format!("Provider[@Name='{}']", SERVICE_NAME)];
+    match min_level {
+        EventLevel::Error => conditions.push("(Level=1 or Level=2)".to_string()),
+        EventLevel::Warning => conditions.push("(Level=1 or Level=2 or Level=3)".to_string()),
+        EventLevel::Info => {}
+    }
+    if let Some(record_id) = after {
+        conditions.push(format!("EventRecordID > {}", record_id));
+    }
+    format!("*[System[{}]]", conditions.join(" and "))
+}
+
+/// Text of the first `<tag ...>...</tag>` element in `xml`.
+fn element_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    element_texts(xml, tag).next()
+}
+
+/// Text of every `<tag ...>...</tag>` element in `xml`, in order. Empty
+/// elements (`<tag/>`) are skipped.
+fn element_texts<'a>(xml: &'a str, tag: &str) -> impl Iterator<Item = &'a str> + 'a {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut rest = xml;
+    std::iter::from_fn(move || loop {
+        let start = rest.find(&open)?;
+        let after_name = &rest[start + open.len()..];
+        // `<Data` must not match `<DataItem`.
+        if !after_name.starts_with(['>', ' ', '/']) {
+            rest = after_name;
+            continue;
+        }
+        let tag_end = after_name.find('>')?;
+        if after_name[..tag_end].ends_with('/') {
+            rest = &after_name[tag_end + 1..];
+            continue;
+        }
+        let body = &after_name[tag_end + 1..];
+        let end = body.find(&close)?;
+        rest = &body[end + close.len()..];
+        return Some(&body[..end]);
+    })
+}
+
+/// Value of `attr` on the first `<tag ...>` element in `xml`.
+fn attribute<'a>(xml: &'a str, tag: &str, attr: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{} ", tag))?;
+    let element = &xml[start..start + xml[start..].find('>')?];
+    let pattern = format!("{}=", attr);
+    let value = &element[element.find(&pattern)? + pattern.len()..];
+    let quote = value.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+    let value = &value[1..];
+    Some(&value[..value.find(quote)?])
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Pick our fields out of an event rendered with `EvtRenderEventXml`.
+fn parse_event_xml(xml: &str) -> Option<LogEvent> {
+    let record_id = element_text(xml, "EventRecordID")?.trim().parse().ok()?;
+    let level = element_text(xml, "Level")
+        .and_then(|l| l.trim().parse().ok())
+        .map(EventLevel::from_xml)
+        .unwrap_or(EventLevel::Info);
+    let event_id = element_text(xml, "EventID")
+        .and_then(|id| id.trim().parse().ok())
+        .unwrap_or(0);
+    let time = attribute(xml, "TimeCreated", "SystemTime")
+        .unwrap_or_default()
+        .to_string();
+    let message = element_texts(xml, "Data")
+        .map(|data| unescape(data).trim_end().to_string())
+        .filter(|data| !data.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(LogEvent {
+        record_id,
+        time,
+        level,
+        event_id,
+        message,
+    })
+}
+
+/// Closes an event log handle on drop.
+struct EvtHandle(EVT_HANDLE);
+
+impl Drop for EvtHandle {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = EvtClose(self.0);
+        }
+    }
+}
+
+fn render_xml(event: &EvtHandle) -> Result<String, Box<dyn Error>> {
+    let mut used = 0u32;
+    let mut count = 0u32;
+    // The first call only reports the size needed.
+    unsafe {
+        let _ = EvtRender(
+            EVT_HANDLE::default(),
+            event.0,
+            EvtRenderEventXml.0,
+            0,
+            None,
+            &mut used,
+            &mut count,
+        );
+    }
+    let mut buffer = vec![0u16; (used as usize).div_ceil(2)];
+    unsafe {
+        EvtRender(
+            EVT_HANDLE::default(),
+            event.0,
+            EvtRenderEventXml.0,
+            (buffer.len() * 2) as u32,
+            Some(buffer.as_mut_ptr().cast()),
+            &mut used,
+            &mut count,
+        )?;
+    }
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Ok(String::from_utf16_lossy(&buffer[..len]))
+}
+
+/// Run `query` and return up to `limit` matching events in the order the
+/// query yields them.
+fn run_query(
+    query: &str,
+    newest_first: bool,
+    limit: usize,
+) -> Result<Vec<LogEvent>, Box<dyn Error>> {
+    let channel = WideString::new(CHANNEL);
+    let query = WideString::new(query);
+    let direction = if newest_first {
+        EvtQueryReverseDirection
+    } else {
+        EvtQueryForwardDirection
+    };
+    let results = EvtHandle(unsafe {
+        EvtQuery(
+            EVT_HANDLE::default(),
+            channel.as_pcwstr(),
+            query.as_pcwstr(),
+            EvtQueryChannelPath.0 | direction.0,
+        )?
+    });
+
+    let mut events = Vec::new();
+    while events.len() < limit {
+        let mut handles = [0isize; BATCH];
+        let mut returned = 0u32;
+        match unsafe { EvtNext(results.0, &mut handles, 1000, 0, &mut returned) } {
+            Ok(()) => {}
+            Err(e) if e.code() == ERROR_NO_MORE_ITEMS.to_hresult() => break,
+            Err(e) => return Err(e.into()),
+        }
+        // Wrap every handle first so all of them are closed.
+        let batch: Vec<EvtHandle> = handles[..returned as usize]
+            .iter()
+            .map(|&h| EvtHandle(EVT_HANDLE(h)))
+            .collect();
+        for event in &batch {
+            if events.len() >= limit {
+                break;
+            }
+            if let Some(parsed) = parse_event_xml(&render_xml(event)?) {
+                events.push(parsed);
+            }
+        }
+    }
+    Ok(events)
+}
+
+/// The newest `limit` events at `min_level` or above, oldest first.
+pub fn recent_events(limit: usize, min_level: EventLevel) -> Result<Vec<LogEvent>, Box<dyn Error>> {
+    let mut events = run_query(&build_query(min_level, None), true, limit)?;
+    events.reverse();
+    Ok(events)
+}
+
+/// Events logged after record `after`, oldest first, for `--follow`.
+pub fn events_after(after: u64, min_level: EventLevel) -> Result<Vec<LogEvent>, Box<dyn Error>> {
+    run_query(&build_query(min_level, Some(after)), false, usize::MAX)
+}
+
+#[cfg(test)]
+#[path = "tests/event_log_tests.rs"]
+mod tests;
@@ -0,0 +1,140 @@
+//! Events sent to the debounce worker.
+//!
+//! Every source (window procedure, pollers, watchers, hotkeys, control
+//! pipe) sends a [`DisplayEvent`] over one channel. Each event belongs to
+//! one of the `EVENT_*` classes, and a debounced batch is the set of classes
+//! it saw, so masks like `EVENT_MASK_DEVICE` still apply to the batch.
+//! Events that concern one monitor also carry its device key, which lets a
+//! plug-in reapply to just that monitor instead of every matching one.
+
+use super::{
+    EVENT_APP_OVERRIDE, EVENT_AUTOMATION_POLL, EVENT_CALIBRATION_UPDATED, EVENT_CONSOLE_CONNECT,
+    EVENT_DEVICE_ARRIVAL, EVENT_DEVNODES_CHANGED, EVENT_DISPLAY_ON, EVENT_FULLSCREEN_EXIT,
+    EVENT_GAMMA_RESET, EVENT_HOTKEY_BRIGHTNESS, EVENT_HOTKEY_REAPPLY, EVENT_PROFILE_TAMPERED,
+    EVENT_REMOTE_REAPPLY, EVENT_SCHEDULE, EVENT_SESSION_LOGON, EVENT_SESSION_UNLOCK,
+    EVENT_WATCHDOG,
+};
+
+/// Something that may call for a reapply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum DisplayEvent {
+    /// A monitor device interface arrived. `device_key` is `None` when the
+    /// notification carried no usable interface path.
+    DeviceArrival {
+        device_key: Option<String>,
+    },
+    /// A monitor device interface was removed. Not a reapply trigger on
+    /// its own; `kvm_mode` pairs it with the monitor's return.
+    DeviceRemoval {
+        device_key: Option<String>,
+    },
+    DevNodesChanged,
+    SessionLogon,
+    SessionUnlock,
+    ConsoleConnect,
+    AutomationPoll,
+    FullscreenExit,
+    AppOverride,
+    Schedule,
+    HotkeyReapply,
+    HotkeyBrightness,
+    RemoteReapply,
+    ProfileTampered,
+    GammaReset,
+    Watchdog,
+    DisplayOn,
+    CalibrationUpdated,
+}
+
+impl DisplayEvent {
+    /// The event's class bit (none for [`Self::DeviceRemoval`]).
+    pub(crate) fn flag(&self) -> u32 {
+        match self {
+            Self::DeviceArrival { .. } => EVENT_DEVICE_ARRIVAL,
+            Self::DeviceRemoval { .. } => 0,
+            Self::DevNodesChanged => EVENT_DEVNODES_CHANGED,
+            Self::SessionLogon => EVENT_SESSION_LOGON,
+            Self::SessionUnlock => EVENT_SESSION_UNLOCK,
+            Self::ConsoleConnect => EVENT_CONSOLE_CONNECT,
+            Self::AutomationPoll => EVENT_AUTOMATION_POLL,
+            Self::FullscreenExit => EVENT_FULLSCREEN_EXIT,
+            Self::AppOverride => EVENT_APP_OVERRIDE,
+            Self::Schedule => EVENT_SCHEDULE,
+            Self::HotkeyReapply => EVENT_HOTKEY_REAPPLY,
+            Self::HotkeyBrightness => EVENT_HOTKEY_BRIGHTNESS,
+            Self::RemoteReapply => EVENT_REMOTE_REAPPLY,
+            Self::ProfileTampered => EVENT_PROFILE_TAMPERED,
+            Self::GammaReset => EVENT_GAMMA_RESET,
+            Self::Watchdog => EVENT_WATCHDOG,
+            Self::DisplayOn => EVENT_DISPLAY_ON,
+            Self::CalibrationUpdated => EVENT_CALIBRATION_UPDATED,
+        }
+    }
+
+    /// Monitors a reapply for this event has to reach.
+    pub(crate) fn targets(&self) -> Targets {
+        match self {
+            Self::DeviceArrival {
+                device_key: Some(key),
+            } if !key.is_empty() => Targets::Only(vec![key.clone()]),
+            _ => Targets::All,
+        }
+    }
+}
+
+/// Monitors a batch is reapplied to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Targets {
+    /// Every monitor matching `monitor_match`.
+    All,
+    /// Only the monitors with these device keys.
+    Only(Vec<String>),
+}
+
+impl Targets {
+    /// Widen to cover `other` as well.
+    pub(crate) fn merge(&mut self, other: Targets) {
+        match (&mut *self, other) {
+            (Targets::Only(keys), Targets::Only(more)) => {
+                for key in more {
+                    if !keys.iter().any(|k| k.eq_ignore_ascii_case(&key)) {
+                        keys.push(key);
+                    }
+                }
+            }
+            (Targets::Only(_), Targets::All) => *self = Targets::All,
+            (Targets::All, _) => {}
+        }
+    }
+
+    /// True when a reapply to `self` also takes care of `other`.
+    pub(crate) fn covers(&self, other: &Targets) -> bool {
+        match (self, other) {
+            (Targets::All, _) => true,
+            (Targets::Only(_), Targets::All) => false,
+            (Targets::Only(keys), Targets::Only(wanted)) => wanted
+                .iter()
+                .all(|w| keys.iter().any(|k| k.eq_ignore_ascii_case(w))),
+        }
+    }
+
+    /// `all`, or the device keys separated by commas.
+    pub(crate) fn label(&self) -> String {
+        match self {
+            Targets::All => "all".to_string(),
+            Targets::Only(keys) => keys.join(","),
+        }
+    }
+
+    /// True when the monitor with `device_key` is one of the targets.
+    pub(crate) fn includes(&self, device_key: &str) -> bool {
+        match self {
+            Targets::All => true,
+            Targets::Only(keys) => keys.iter().any(|k| k.eq_ignore_ascii_case(device_key)),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/events_tests.rs"]
+mod tests;
@@ -0,0 +1,236 @@
+//! Fullscreen-exit reassert via a foreground WinEvent hook.
+//!
+//! Fullscreen games frequently reset the gamma ramp on exit while the ICC
+//! association stays in place, so no device or session event ever fires.
+//! This module installs an out-of-context `EVENT_SYSTEM_FOREGROUND` hook on
+//! the event-loop thread; when a fullscreen window from an allowed process
+//! loses the foreground, it queues [`EVENT_FULLSCREEN_EXIT`] to the debounce
+//! worker, which runs the normal reapply pipeline.
+//!
+//! WinEvent hooks only observe the desktop of the session they run in, so
+//! this is effective in `watch` mode (or any user-session host), not from the
+//! session-0 service.
+
+use super::events::DisplayEvent;
+use super::EVENT_SENDER;
+use log::{info, warn};
+use std::sync::{Mutex, OnceLock};
+
+use windows::core::PWSTR;
+use windows::Win32::Foundation::{CloseHandle, HMODULE, HWND, RECT};
+use windows::Win32::Graphics::Gdi::{
+    GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONEAREST,
+};
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK};
+use windows::Win32::UI::Shell::{
+    SHQueryUserNotificationState, QUERY_USER_NOTIFICATION_STATE, QUNS_BUSY, QUNS_PRESENTATION_MODE,
+    QUNS_RUNNING_D3D_FULL_SCREEN,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetDesktopWindow, GetForegroundWindow, GetShellWindow, GetWindowRect, GetWindowThreadProcessId,
+    EVENT_SYSTEM_FOREGROUND, WINEVENT_OUTOFCONTEXT, WINEVENT_SKIPOWNPROCESS,
+};
+
+static TRACKER: OnceLock<Mutex<FullscreenTracker>> = OnceLock::new();
+
+/// Normalize a process name or image path to a lowercase stem (`game`).
+pub(crate) fn normalize_process_name(name: &str) -> String {
+    let file = name.rsplit(['\\', '/']).next().unwrap_or(name);
+    let lower = file.trim().to_ascii_lowercase();
+    lower.strip_suffix(".exe").unwrap_or(&lower).to_string()
+}
+
+/// Parse the comma-separated `fullscreen_exit_apps` setting.
+pub(crate) fn parse_app_allowlist(csv: &str) -> Vec<String> {
+    csv.split(',')
+        .map(normalize_process_name)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Tracks which allowed app currently owns the foreground in fullscreen.
+#[derive(Debug, Default)]
+pub(crate) struct FullscreenTracker {
+    allowlist: Vec<String>,
+    active_app: Option<String>,
+}
+
+impl FullscreenTracker {
+    pub(crate) fn new(allowlist_csv: &str) -> Self {
+        Self {
+            allowlist: parse_app_allowlist(allowlist_csv),
+            active_app: None,
+        }
+    }
+
+    fn allows(&self, process: &str) -> bool {
+        self.allowlist.is_empty() || self.allowlist.iter().any(|a| a == process)
+    }
+
+    /// Feed a foreground change. Returns the app that just left fullscreen
+    /// when a reassert should fire.
+    pub(crate) fn on_foreground(&mut self, process: &str, fullscreen: bool) -> Option<String> {
+        let process = normalize_process_name(process);
+        let exited = match &self.active_app {
+            Some(prev) if *prev != process || !fullscreen => self.active_app.take(),
+            _ => None,
+        };
+        if fullscreen && !process.is_empty() && self.allows(&process) {
+            self.active_app = Some(process);
+        }
+        exited
+    }
+}
+
+/// Install the foreground hook on the current (message-pump) thread.
+/// Returns the hook handle, or `None` if installation failed.
+pub(crate) fn install(allowlist_csv: &str) -> Option<HWINEVENTHOOK> {
+    let tracker = TRACKER.get_or_init(|| Mutex::new(FullscreenTracker::default()));
+    if let Ok(mut t) = tracker.lock() {
+        *t = FullscreenTracker::new(allowlist_csv);
+    }
+
+    let hook = unsafe {
+        SetWinEventHook(
+            EVENT_SYSTEM_FOREGROUND,
+            EVENT_SYSTEM_FOREGROUND,
+            HMODULE::default(),
+            Some(foreground_proc),
+            0,
+            0,
+            WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+        )
+    };
+    if hook.is_invalid() {
+        warn!("SetWinEventHook(EVENT_SYSTEM_FOREGROUND) failed — fullscreen reassert disabled");
+        return None;
+    }
+    info!("Fullscreen exit reassert hook installed");
+    Some(hook)
+}
+
+/// Remove a hook returned by [`install`].
+pub(crate) fn uninstall(hook: HWINEVENTHOOK) {
+    unsafe {
+        let _ = UnhookWinEvent(hook);
+    }
+}
+
+unsafe extern "system" fn foreground_proc(
+    _hook: HWINEVENTHOOK,
+    _event: u32,
+    hwnd: HWND,
+    _id_object: i32,
+    _id_child: i32,
+    _thread: u32,
+    _time: u32,
+) {
+    let _ = super::crash::guard("fullscreen hook", || on_foreground_change(hwnd));
+}
+
+fn on_foreground_change(hwnd: HWND) {
+    if hwnd.0.is_null() {
+        return;
+    }
+    let process = process_name_for_window(hwnd).unwrap_or_default();
+    let fullscreen = is_fullscreen_window(hwnd);
+
+    let exited = TRACKER
+        .get()
+        .and_then(|t| t.lock().ok())
+        .and_then(|mut t| t.on_foreground(&process, fullscreen));
+
+    if let Some(app) = exited {
+        info!("Fullscreen app left foreground: {}", app);
+        lg_core::state::append_diagnostic_event(
+            "service",
+            "INFO",
+            "fullscreen_exit",
+            &format!("app={} next={}", app, normalize_process_name(&process)),
+        );
+        EVENT_SENDER.with(|s| {
+            if let Some(tx) = s.borrow().as_ref() {
+                let _ = tx.send(DisplayEvent::FullscreenExit);
+            }
+        });
+    }
+}
+
+/// Kind of fullscreen app the shell reports, if any.
+pub(crate) fn notification_state_kind(
+    state: QUERY_USER_NOTIFICATION_STATE,
+) -> Option<&'static str> {
+    match state {
+        QUNS_RUNNING_D3D_FULL_SCREEN => Some("exclusive"),
+        QUNS_BUSY => Some("fullscreen"),
+        QUNS_PRESENTATION_MODE => Some("presentation"),
+        _ => None,
+    }
+}
+
+/// Whether a fullscreen app owns the foreground, and which kind: the shell's
+/// notification state covers exclusive D3D and presentation mode, and a
+/// monitor-sized foreground window covers borderless. Session-local, like
+/// the hooks.
+pub(crate) fn fullscreen_foreground() -> Option<&'static str> {
+    let shell = unsafe { SHQueryUserNotificationState() }
+        .ok()
+        .and_then(notification_state_kind);
+    shell.or_else(|| {
+        let hwnd = unsafe { GetForegroundWindow() };
+        (!hwnd.0.is_null() && is_fullscreen_window(hwnd)).then_some("borderless")
+    })
+}
+
+/// True if `hwnd` covers its whole monitor (borderless or exclusive fullscreen).
+fn is_fullscreen_window(hwnd: HWND) -> bool {
+    unsafe {
+        if hwnd == GetDesktopWindow() || hwnd == GetShellWindow() {
+            return false;
+        }
+        let mut rect = RECT::default();
+        if GetWindowRect(hwnd, &mut rect).is_err() {
+            return false;
+        }
+        let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+            return false;
+        }
+        let m = info.rcMonitor;
+        rect.left <= m.left && rect.top <= m.top && rect.right >= m.right && rect.bottom >= m.bottom
+    }
+}
+
+/// Image path of the process owning `hwnd`.
+pub(crate) fn process_name_for_window(hwnd: HWND) -> Option<String> {
+    unsafe {
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid as *mut u32));
+        if pid == 0 {
+            return None;
+        }
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buf = [0u16; 260];
+        let mut len = buf.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            handle,
+            PROCESS_NAME_WIN32,
+            PWSTR(buf.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = CloseHandle(handle);
+        result.ok()?;
+        Some(String::from_utf16_lossy(&buf[..len as usize]))
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/fullscreen_tests.rs"]
+mod tests;
@@ -0,0 +1,267 @@
+//! GPU driver gamma reset detection.
+//!
+//! NVIDIA and AMD control panels (and their background services) rewrite
+//! the gamma ramp whenever color settings change, which wipes the `vcgt`
+//! calibration we loaded. The association itself is untouched, so the
+//! registry-based tamper watch never notices. With `gamma_watch` set, a
+//! thread reads the ramp back every `gamma_watch_interval_ms` and compares
+//! it with the one captured right after our last apply; a difference is
+//! logged with the process that most likely caused it, toasted, or
+//! reasserted depending on the configured action.
+//!
+//! The ramp belongs to the session that reads it, so like the fullscreen
+//! hook this works best with `watch` running in your session.
+
+use super::brightness_guard::RestoreRateLimiter;
+use super::events::DisplayEvent;
+use super::fullscreen::{normalize_process_name, process_name_for_window};
+use super::tamper::{self, TamperAction};
+use lg_core::config::Config;
+use lg_core::shutdown::ShutdownToken;
+use lg_core::state as app_state;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+};
+use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+/// Lower bound for the poll interval.
+const MIN_INTERVAL_MS: u64 = 1000;
+
+/// Largest per-entry ramp difference treated as noise (out of 65535).
+/// Drivers round the ramp they hand back, so exact equality is too strict.
+const DEVIATION_THRESHOLD: u16 = 768;
+
+/// Reasserts allowed per minute before the watch backs off.
+const MAX_REASSERTS_PER_MINUTE: u32 = 3;
+
+/// Known GPU vendor processes that rewrite the gamma ramp, most specific
+/// first: an open control panel is a better suspect than a resident service.
+const GPU_PROCESSES: &[(&str, &str)] = &[
+    ("nvcplui", "NVIDIA Control Panel"),
+    ("nvidia app", "NVIDIA App"),
+    ("radeonsoftware", "AMD Radeon Software"),
+    ("intelgraphicssoftware", "Intel Graphics Software"),
+    ("igfxem", "Intel Graphics Command Center"),
+    ("nvdisplay.container", "NVIDIA Display Container service"),
+    ("amdrsserv", "AMD Radeon Settings service"),
+    ("atieclxx", "AMD External Events client"),
+];
+
+/// Device key → gamma ramp read back right after our last apply.
+static BASELINES: OnceLock<Mutex<HashMap<String, Vec<u16>>>> = OnceLock::new();
+
+fn baselines() -> &'static Mutex<HashMap<String, Vec<u16>>> {
+    BASELINES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Capture the ramp of `device_key` as the state to watch for. A no-op
+/// unless `gamma_watch` is on.
+pub(crate) fn record_baseline(config: &Config, device_key: &str) {
+    if TamperAction::parse(&config.gamma_watch) == TamperAction::Off {
+        return;
+    }
+    match lg_profile::read_gamma_ramp(device_key) {
+        Ok(ramp) => {
+            if let Ok(mut map) = baselines().lock() {
+                map.insert(device_key.to_string(), ramp);
+            }
+        }
+        Err(e) => warn!("Gamma watch: could not read ramp for {}: {}", device_key, e),
+    }
+}
+
+/// True when `current` has drifted from `baseline` by more than rounding.
+pub(crate) fn is_reset(baseline: &[u16], current: &[u16]) -> bool {
+    lg_profile::gamma_ramp_deviation(baseline, current) > DEVIATION_THRESHOLD
+}
+
+/// Best guess at who reset the ramp: a known GPU process in the foreground,
+/// otherwise the most specific one that is running. Names are normalized
+/// process stems (see [`normalize_process_name`]).
+pub(crate) fn likely_culprit(foreground: Option<&str>, running: &[String]) -> Option<&'static str> {
+    let label = |stem: &str| {
+        GPU_PROCESSES
+            .iter()
+            .find(|(name, _)| *name == stem)
+            .map(|(_, label)| *label)
+    };
+    if let Some(found) = foreground.and_then(label) {
+        return Some(found);
+    }
+    GPU_PROCESSES
+        .iter()
+        .find(|(name, _)| running.iter().any(|r| r == name))
+        .map(|(_, label)| *label)
+}
+
+/// Normalized names of every running process.
+fn running_processes() -> Vec<String> {
+    let mut names = Vec::new();
+    unsafe {
+        let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else {
+            return names;
+        };
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut ok = Process32FirstW(snapshot, &mut entry).is_ok();
+        while ok {
+            let len = entry
+                .szExeFile
+                .iter()
+                .position(|&c| c == 0)
+                .unwrap_or(entry.szExeFile.len());
+            names.push(normalize_process_name(&String::from_utf16_lossy(
+                &entry.szExeFile[..len],
+            )));
+            ok = Process32NextW(snapshot, &mut entry).is_ok();
+        }
+        let _ = CloseHandle(snapshot);
+    }
+    names
+}
+
+fn foreground_process() -> Option<String> {
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd.0.is_null() {
+        return None;
+    }
+    process_name_for_window(hwnd).map(|p| normalize_process_name(&p))
+}
+
+/// Spawn the watch thread. Returns `None` when `gamma_watch` is off.
+pub(crate) fn spawn(
+    config: &Config,
+    shutdown: &ShutdownToken,
+    tx: mpsc::Sender<DisplayEvent>,
+) -> Option<std::io::Result<thread::JoinHandle<()>>> {
+    let action = TamperAction::parse(&config.gamma_watch);
+    if action == TamperAction::Off {
+        return None;
+    }
+    let config = config.clone();
+    Some(super::supervisor::spawn(
+        "gamma-watch",
+        shutdown,
+        move |shutdown| run(&config, action, shutdown, &tx),
+    ))
+}
+
+fn run(
+    config: &Config,
+    action: TamperAction,
+    shutdown: &ShutdownToken,
+    tx: &mpsc::Sender<DisplayEvent>,
+) {
+    let interval = Duration::from_millis(config.gamma_watch_interval_ms.max(MIN_INTERVAL_MS));
+    let mut limiter = RestoreRateLimiter::new(MAX_REASSERTS_PER_MINUTE);
+    let mut throttled = false;
+
+    info!(
+        "Gamma watch started: action={:?} interval={}ms",
+        action,
+        interval.as_millis()
+    );
+
+    while shutdown.sleep(interval) {
+        if tamper::applying() {
+            continue;
+        }
+
+        let snapshot: Vec<(String, Vec<u16>)> = match baselines().lock() {
+            Ok(map) => map.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            Err(_) => continue,
+        };
+        let reset: Vec<(String, u16)> = snapshot
+            .into_iter()
+            .filter_map(|(device_key, baseline)| {
+                // Unplugged displays can't be read; that's not a reset.
+                let current = lg_profile::read_gamma_ramp(&device_key).ok()?;
+                is_reset(&baseline, &current).then(|| {
+                    let deviation = lg_profile::gamma_ramp_deviation(&baseline, &current);
+                    (device_key, deviation)
+                })
+            })
+            .collect();
+        if reset.is_empty() {
+            continue;
+        }
+
+        let foreground = foreground_process();
+        let culprit = likely_culprit(foreground.as_deref(), &running_processes());
+        for (device_key, deviation) in &reset {
+            warn!(
+                "Gamma ramp for {} was reset by another program (deviation={}, likely {})",
+                device_key,
+                deviation,
+                culprit.unwrap_or("unknown")
+            );
+            app_state::append_diagnostic_event(
+                "service",
+                "WARN",
+                "gamma_reset",
+                &format!(
+                    "device={} deviation={} likely=\"{}\" foreground={} action={:?}",
+                    device_key,
+                    deviation,
+                    culprit.unwrap_or("unknown"),
+                    foreground.as_deref().unwrap_or("none"),
+                    action
+                ),
+            );
+        }
+        // Don't report the same reset again on every poll.
+        if let Ok(mut map) = baselines().lock() {
+            for (device_key, _) in &reset {
+                map.remove(device_key);
+            }
+        }
+
+        match action {
+            TamperAction::Off | TamperAction::Log => {}
+            TamperAction::Notify => lg_notify::show_reapply_toast_quiet_aware(
+                true,
+                &config.toast_title,
+                &format!(
+                    "Display calibration was reset{}",
+                    culprit
+                        .map(|c| format!(" (likely by {})", c))
+                        .unwrap_or_default()
+                ),
+                lg_notify::QuietHours::parse(&config.toast_quiet_hours),
+                config.verbose,
+            ),
+            TamperAction::Reassert => {
+                if limiter.try_acquire(Instant::now()) {
+                    throttled = false;
+                    if tx.send(DisplayEvent::GammaReset).is_err() {
+                        break;
+                    }
+                } else if !throttled {
+                    throttled = true;
+                    warn!("Gamma watch: reassert rate limit reached, leaving the ramp alone");
+                    app_state::append_diagnostic_event(
+                        "service",
+                        "WARN",
+                        "gamma_reset_rate_limited",
+                        &format!("max_per_minute={}", MAX_REASSERTS_PER_MINUTE),
+                    );
+                }
+            }
+        }
+    }
+
+    info!("Gamma watch stopped");
+}
+
+#[cfg(test)]
+#[path = "tests/gamma_watch_tests.rs"]
+mod tests;
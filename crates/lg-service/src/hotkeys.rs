@@ -0,0 +1,292 @@
+//! Global hotkeys registered on the event-loop message window.
+//!
+//! `WM_HOTKEY` arrives on the message pump; the window procedure hands it to
+//! [`on_hotkey`], which queues [`EVENT_HOTKEY_REAPPLY`] or
+//! [`EVENT_HOTKEY_BRIGHTNESS`] to the debounce worker. Brightness presses
+//! accumulate into a pending delta so several quick presses coalesce into a
+//! single DDC write.
+//!
+//! Hotkeys belong to the interactive desktop, so they only fire for a
+//! watcher running in the user's session (`watch` / the tray), not the
+//! session-0 service.
+
+use super::events::DisplayEvent;
+use super::EVENT_SENDER;
+use lg_core::config::Config;
+use log::{info, warn};
+use std::error::Error;
+use std::sync::atomic::{AtomicI32, AtomicU32, Ordering};
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    RegisterHotKey, UnregisterHotKey, HOT_KEY_MODIFIERS,
+};
+
+pub(crate) const WM_HOTKEY: u32 = 0x0312;
+
+// `MOD_*` as plain bits so parsed bindings compare and combine as `u32`.
+const MOD_ALT: u32 = 0x0001;
+const MOD_CONTROL: u32 = 0x0002;
+const MOD_SHIFT: u32 = 0x0004;
+const MOD_WIN: u32 = 0x0008;
+const MOD_NOREPEAT: u32 = 0x4000;
+
+/// Net brightness change requested by presses not yet applied.
+static PENDING_BRIGHTNESS_DELTA: AtomicI32 = AtomicI32::new(0);
+
+/// `hotkey_brightness_step` captured at registration for the window proc.
+static BRIGHTNESS_STEP: AtomicU32 = AtomicU32::new(0);
+
+/// What a registered hotkey does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HotkeyAction {
+    Reapply,
+    BrightnessUp,
+    BrightnessDown,
+}
+
+impl HotkeyAction {
+    const ALL: [HotkeyAction; 3] = [
+        HotkeyAction::Reapply,
+        HotkeyAction::BrightnessUp,
+        HotkeyAction::BrightnessDown,
+    ];
+
+    fn id(self) -> i32 {
+        match self {
+            HotkeyAction::Reapply => 1,
+            HotkeyAction::BrightnessUp => 2,
+            HotkeyAction::BrightnessDown => 3,
+        }
+    }
+
+    fn from_id(id: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.id() == id)
+    }
+
+    fn binding(self, config: &Config) -> &str {
+        match self {
+            HotkeyAction::Reapply => &config.hotkey_reapply,
+            HotkeyAction::BrightnessUp => &config.hotkey_brightness_up,
+            HotkeyAction::BrightnessDown => &config.hotkey_brightness_down,
+        }
+    }
+}
+
+/// A parsed key combination (`MOD_*` flags plus a virtual-key code).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Hotkey {
+    pub(crate) modifiers: u32,
+    pub(crate) vk: u32,
+}
+
+/// Parse a binding such as `"Ctrl+Alt+F9"`. Empty means unbound (`Ok(None)`).
+pub(crate) fn parse_hotkey(binding: &str) -> Result<Option<Hotkey>, String> {
+    let binding = binding.trim();
+    if binding.is_empty() {
+        return Ok(None);
+    }
+
+    let mut modifiers = 0u32;
+    let mut vk = None;
+    for part in binding.split('+').map(str::trim) {
+        let lower = part.to_ascii_lowercase();
+        let modifier = match lower.as_str() {
+            "ctrl" | "control" => Some(MOD_CONTROL),
+            "alt" => Some(MOD_ALT),
+            "shift" => Some(MOD_SHIFT),
+            "win" | "super" => Some(MOD_WIN),
+            _ => None,
+        };
+        if let Some(m) = modifier {
+            modifiers |= m;
+            continue;
+        }
+        if vk.is_some() {
+            return Err(format!("hotkey '{}' has more than one key", binding));
+        }
+        vk = Some(
+            virtual_key(&lower)
+                .ok_or_else(|| format!("unknown key '{}' in hotkey '{}'", part, binding))?,
+        );
+    }
+
+    let vk = vk.ok_or_else(|| format!("hotkey '{}' has no key", binding))?;
+    if modifiers == 0 {
+        return Err(format!("hotkey '{}' needs at least one modifier", binding));
+    }
+    Ok(Some(Hotkey { modifiers, vk }))
+}
+
+fn virtual_key(key: &str) -> Option<u32> {
+    let vk = match key {
+        "up" => 0x26,
+        "down" => 0x28,
+        "left" => 0x25,
+        "right" => 0x27,
+        "pageup" | "pgup" => 0x21,
+        "pagedown" | "pgdn" => 0x22,
+        "home" => 0x24,
+        "end" => 0x23,
+        "insert" | "ins" => 0x2D,
+        "delete" | "del" => 0x2E,
+        "space" => 0x20,
+        _ => {
+            if let Some(n) = key.strip_prefix('f').and_then(|n| n.parse::<u32>().ok()) {
+                return (1..=24).contains(&n).then_some(0x70 + n - 1);
+            }
+            let mut chars = key.chars();
+            return match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphanumeric() => Some(c.to_ascii_uppercase() as u32),
+                _ => None,
+            };
+        }
+    };
+    Some(vk)
+}
+
+/// New brightness after applying `delta` to `current`, clamped to 0–100.
+pub(crate) fn step_brightness(current: u32, delta: i32) -> u32 {
+    (current.min(100) as i32 + delta).clamp(0, 100) as u32
+}
+
+/// A requested DDC brightness change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BrightnessChange {
+    /// Step by this many percentage points.
+    Relative(i32),
+    /// Set to this level (0–100).
+    Absolute(u32),
+}
+
+impl BrightnessChange {
+    pub(crate) fn target(self, current: u32) -> u32 {
+        match self {
+            BrightnessChange::Relative(delta) => step_brightness(current, delta),
+            BrightnessChange::Absolute(value) => value.min(100),
+        }
+    }
+}
+
+/// Register every configured binding on `hwnd`. Returns the registered ids.
+pub(crate) fn register(hwnd: HWND, config: &Config) -> Vec<i32> {
+    BRIGHTNESS_STEP.store(config.hotkey_brightness_step.min(100), Ordering::SeqCst);
+    let mut registered = Vec::new();
+    for action in HotkeyAction::ALL {
+        let binding = action.binding(config);
+        let hotkey = match parse_hotkey(binding) {
+            Ok(Some(h)) => h,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!("Hotkey for {:?} ignored: {}", action, e);
+                continue;
+            }
+        };
+        let ok = unsafe {
+            RegisterHotKey(
+                hwnd,
+                action.id(),
+                HOT_KEY_MODIFIERS(hotkey.modifiers | MOD_NOREPEAT),
+                hotkey.vk,
+            )
+            .is_ok()
+        };
+        if ok {
+            info!("Hotkey registered: {} → {:?}", binding, action);
+            registered.push(action.id());
+        } else {
+            warn!(
+                "RegisterHotKey({}) failed — already taken by another app?",
+                binding
+            );
+        }
+    }
+    registered
+}
+
+/// Remove hotkeys returned by [`register`].
+pub(crate) fn unregister(hwnd: HWND, ids: &[i32]) {
+    for &id in ids {
+        unsafe {
+            let _ = UnregisterHotKey(hwnd, id);
+        }
+    }
+}
+
+/// Handle `WM_HOTKEY` from the window procedure.
+pub(crate) fn on_hotkey(id: i32) {
+    let Some(action) = HotkeyAction::from_id(id) else {
+        return;
+    };
+    let step = BRIGHTNESS_STEP.load(Ordering::SeqCst) as i32;
+    let event = match action {
+        HotkeyAction::Reapply => DisplayEvent::HotkeyReapply,
+        HotkeyAction::BrightnessUp => {
+            PENDING_BRIGHTNESS_DELTA.fetch_add(step, Ordering::SeqCst);
+            DisplayEvent::HotkeyBrightness
+        }
+        HotkeyAction::BrightnessDown => {
+            PENDING_BRIGHTNESS_DELTA.fetch_sub(step, Ordering::SeqCst);
+            DisplayEvent::HotkeyBrightness
+        }
+    };
+    info!("Hotkey pressed: {:?}", action);
+    EVENT_SENDER.with(|s| {
+        if let Some(tx) = s.borrow().as_ref() {
+            let _ = tx.send(event);
+        }
+    });
+}
+
+/// Take the accumulated brightness delta, resetting it to zero.
+pub(crate) fn take_brightness_delta() -> i32 {
+    PENDING_BRIGHTNESS_DELTA.swap(0, Ordering::SeqCst)
+}
+
+/// Apply `change` to DDC brightness on every monitor matching
+/// `monitor_match` (a name substring or a match expression, resolved to
+/// handles by [`DdcSession::find_all`](lg_monitor::ddc::DdcSession::find_all)).
+/// Returns `(monitor, new value)` for each monitor written.
+pub(crate) fn adjust_brightness(
+    config: &Config,
+    change: BrightnessChange,
+) -> Result<Vec<(String, u32)>, Box<dyn Error>> {
+    if !lg_monitor::ddc::is_supported() {
+        return Err(lg_monitor::ddc::DDC_UNSUPPORTED_MESSAGE.into());
+    }
+    let session = lg_monitor::ddc::DdcSession::open()?;
+    let names = session.monitors();
+    let targets = session.find_all(&config.monitor_match)?;
+    let matched = targets.len();
+    let mut changed = Vec::new();
+    let mut read_failures = Vec::new();
+    for index in targets {
+        let name = names[index].1.clone();
+        let current = match session.get_vcp(index, lg_monitor::ddc::VCP_BRIGHTNESS) {
+            Ok(v) => v.current,
+            Err(e) => {
+                warn!("Brightness read failed for {}: {}", name, e);
+                read_failures.extend(lg_monitor::ddc::error_kind(e.as_ref()));
+                continue;
+            }
+        };
+        let target = change.target(current);
+        if target == current {
+            continue;
+        }
+        session.set_vcp(index, lg_monitor::ddc::VCP_BRIGHTNESS, target)?;
+        changed.push((name, target));
+    }
+    // Every matched monitor failed the same way: report why instead of
+    // "unchanged".
+    if matched > 0 && read_failures.len() == matched {
+        if let Some(kind) = lg_monitor::ddc::common_failure(&read_failures) {
+            return Err(lg_monitor::ddc::DdcError::new("Brightness read", kind).into());
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+#[path = "tests/hotkeys_tests.rs"]
+mod tests;
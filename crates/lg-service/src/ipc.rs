@@ -0,0 +1,496 @@
+//! Local control pipe used by the `quick` CLI commands.
+//!
+//! The event loop owns a named pipe (`\\.\pipe\lg-ultragear-dimming-fix`)
+//! and answers one line-based request per connection:
+//!
+//! ```text
+//! reapply               → queue a reapply
+//! brightness +10 | -10  → step DDC brightness on matched monitors
+//! brightness 60         → set DDC brightness on matched monitors
+//! preset reader | auto  → pin the ICC preset until restart (auto = clear)
+//! history [N]           → the last N reapply attempts as a JSON array
+//! workers               → background worker health as a JSON array
+//! lid                   → laptop lid position: open, closed or unknown
+//! ```
+//!
+//! Replies are `ok <message>` or `error <message>`. Clients never touch
+//! COM/WMI themselves, so a button press completes in well under a second.
+//!
+//! Each connection is answered on its own short-lived thread while the
+//! listener goes back to accepting. A client gets [`REQUEST_TIMEOUT`] and
+//! [`MAX_REQUEST_LEN`] bytes to send its line, so one that connects and
+//! never finishes it can't keep other clients out or hold up shutdown.
+
+use super::events::DisplayEvent;
+use super::hotkeys::{self, BrightnessChange};
+use lg_core::config::Config;
+use lg_core::display_gate::LidState;
+use lg_core::report::{ReapplyHistory, ReapplyRecord};
+use lg_core::shutdown::ShutdownToken;
+use lg_core::state::{self as app_state, WorkerHealth};
+use lg_core::wide::WideString;
+use log::{info, warn};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::windows::io::{AsRawHandle, FromRawHandle};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use windows::Win32::Foundation::{
+    GetLastError, LocalFree, BOOL, ERROR_PIPE_CONNECTED, HANDLE, HLOCAL, INVALID_HANDLE_VALUE,
+};
+use windows::Win32::Security::Authorization::{
+    ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+};
+use windows::Win32::Security::{PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES};
+use windows::Win32::Storage::FileSystem::{
+    FILE_FLAGS_AND_ATTRIBUTES, FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX,
+};
+use windows::Win32::System::Pipes::{
+    ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, PeekNamedPipe,
+    PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES,
+};
+
+/// Pipe the running service/watcher listens on.
+pub const PIPE_NAME: &str = r"\\.\pipe\lg-ultragear-dimming-fix";
+
+/// Any interactive user may send commands; SYSTEM and admins keep full
+/// control. The default pipe DACL only grants Everyone read access, which
+/// would lock out non-elevated controllers such as Stream Deck.
+const PIPE_SDDL: &str = "D:(A;;GRGW;;;AU)(A;;GA;;;SY)(A;;GA;;;BA)";
+
+/// How long a client keeps retrying while every pipe instance is busy.
+const CLIENT_BUSY_TIMEOUT: Duration = Duration::from_millis(250);
+
+const PIPE_BUFFER_SIZE: u32 = 512;
+const ERROR_PIPE_BUSY: i32 = 231;
+
+/// Longest request line, newline included.
+pub(crate) const MAX_REQUEST_LEN: usize = PIPE_BUFFER_SIZE as usize;
+
+/// How long a client has to send its request line.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often a client thread checks for request bytes and shutdown.
+const REQUEST_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Connections answered at once; further clients are told the service is
+/// busy.
+const MAX_CLIENTS: usize = 8;
+
+/// Reapply attempts kept for `service history`.
+const HISTORY_CAPACITY: usize = 20;
+
+static PRESET_OVERRIDE: OnceLock<Mutex<Option<String>>> = OnceLock::new();
+
+static HISTORY: OnceLock<Mutex<ReapplyHistory>> = OnceLock::new();
+
+/// A parsed control request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Request {
+    Reapply,
+    Brightness(BrightnessChange),
+    /// `None` clears a pinned preset.
+    Preset(Option<String>),
+    /// `None` returns everything kept.
+    History(Option<usize>),
+    Workers,
+    Lid,
+}
+
+/// Parse `+N` / `-N` (step) or `N` (absolute) brightness.
+pub(crate) fn parse_brightness(value: &str) -> Result<BrightnessChange, String> {
+    let value = value.trim();
+    let invalid = || format!("invalid brightness '{}' (use 0-100, +N or -N)", value);
+    if let Some(step) = value.strip_prefix('+') {
+        let step: u32 = step.parse().map_err(|_| invalid())?;
+        return Ok(BrightnessChange::Relative(step.min(100) as i32));
+    }
+    if let Some(step) = value.strip_prefix('-') {
+        let step: u32 = step.parse().map_err(|_| invalid())?;
+        return Ok(BrightnessChange::Relative(-(step.min(100) as i32)));
+    }
+    match value.parse::<u32>() {
+        Ok(level) if level <= 100 => Ok(BrightnessChange::Absolute(level)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Parse one request line.
+pub(crate) fn parse_request(line: &str) -> Result<Request, String> {
+    let mut parts = line.split_whitespace();
+    let command = parts.next().unwrap_or("").to_ascii_lowercase();
+    let arg = parts.next();
+    if parts.next().is_some() {
+        return Err(format!("too many arguments: {}", line.trim()));
+    }
+    match (command.as_str(), arg) {
+        ("reapply", None) => Ok(Request::Reapply),
+        ("brightness", Some(value)) => parse_brightness(value).map(Request::Brightness),
+        ("preset", Some(name)) => {
+            let name = name.to_ascii_lowercase();
+            if name == "auto" {
+                return Ok(Request::Preset(None));
+            }
+            let known = name == "custom"
+                || lg_profile::parse_dynamic_icc_preset(&name)
+                    != lg_profile::DynamicIccPreset::Custom;
+            if known {
+                Ok(Request::Preset(Some(name)))
+            } else {
+                Err(format!(
+                    "unknown preset '{}' (expected gamma22, gamma24, reader, custom or auto)",
+                    name
+                ))
+            }
+        }
+        ("history", None) => Ok(Request::History(None)),
+        ("history", Some(count)) => match count.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(Request::History(Some(n))),
+            _ => Err(format!("invalid history count '{}'", count)),
+        },
+        ("workers", None) => Ok(Request::Workers),
+        ("lid", None) => Ok(Request::Lid),
+        ("", _) => Err("empty request".to_string()),
+        _ => Err(format!("unknown request: {}", line.trim())),
+    }
+}
+
+/// Preset pinned with `quick preset`, if any.
+pub(crate) fn preset_override() -> Option<String> {
+    PRESET_OVERRIDE
+        .get()
+        .and_then(|p| p.lock().ok())
+        .and_then(|p| p.clone())
+}
+
+fn set_preset_override(preset: Option<String>) {
+    let slot = PRESET_OVERRIDE.get_or_init(|| Mutex::new(None));
+    if let Ok(mut p) = slot.lock() {
+        *p = preset;
+    }
+}
+
+fn history() -> &'static Mutex<ReapplyHistory> {
+    HISTORY.get_or_init(|| Mutex::new(ReapplyHistory::new(HISTORY_CAPACITY)))
+}
+
+/// Remember a finished reapply attempt for `history` requests.
+pub(crate) fn record_reapply(record: ReapplyRecord) {
+    if let Ok(mut h) = history().lock() {
+        h.push(record);
+    }
+}
+
+/// Spawn the pipe server thread.
+pub(crate) fn spawn(
+    config: &Config,
+    shutdown: &ShutdownToken,
+    tx: mpsc::Sender<DisplayEvent>,
+) -> std::io::Result<thread::JoinHandle<()>> {
+    let config = Arc::new(config.clone());
+    super::supervisor::spawn("control-pipe", shutdown, move |shutdown| {
+        serve(&config, shutdown, &tx)
+    })
+}
+
+/// Unblock a server waiting in `ConnectNamedPipe` so it can see shutdown.
+pub(crate) fn wake() {
+    let _ = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(PIPE_NAME);
+}
+
+fn serve(config: &Arc<Config>, shutdown: &ShutdownToken, tx: &mpsc::Sender<DisplayEvent>) {
+    let name = WideString::new(PIPE_NAME);
+    let sddl = WideString::new(PIPE_SDDL);
+    let mut descriptor = PSECURITY_DESCRIPTOR::default();
+    let have_descriptor = unsafe {
+        ConvertStringSecurityDescriptorToSecurityDescriptorW(
+            sddl.as_pcwstr(),
+            SDDL_REVISION_1,
+            &mut descriptor,
+            None,
+        )
+        .is_ok()
+    };
+    if !have_descriptor {
+        warn!("Control pipe: custom ACL unavailable, using the default pipe security");
+    }
+    let attributes = SECURITY_ATTRIBUTES {
+        nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+        lpSecurityDescriptor: descriptor.0,
+        bInheritHandle: BOOL(0),
+    };
+
+    let mut first = true;
+    let mut clients: Vec<thread::JoinHandle<()>> = Vec::new();
+    while !shutdown.is_cancelled() {
+        let pipe = unsafe {
+            CreateNamedPipeW(
+                name.as_pcwstr(),
+                PIPE_ACCESS_DUPLEX
+                    | if first {
+                        FILE_FLAG_FIRST_PIPE_INSTANCE
+                    } else {
+                        FILE_FLAGS_AND_ATTRIBUTES(0)
+                    },
+                PIPE_TYPE_BYTE | PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_UNLIMITED_INSTANCES,
+                PIPE_BUFFER_SIZE,
+                PIPE_BUFFER_SIZE,
+                0,
+                have_descriptor.then_some(&attributes as *const _),
+            )
+        };
+        if pipe == INVALID_HANDLE_VALUE {
+            warn!(
+                "Control pipe unavailable ({:?}) — is another watcher running? `quick` commands disabled",
+                unsafe { GetLastError() }
+            );
+            break;
+        }
+        if first {
+            info!("Control pipe listening on {}", PIPE_NAME);
+            first = false;
+        }
+
+        // A client that connected between create and connect is still good.
+        let connected = match unsafe { ConnectNamedPipe(pipe, None) } {
+            Ok(()) => true,
+            Err(e) => e.code() == ERROR_PIPE_CONNECTED.to_hresult(),
+        };
+        // The File takes ownership of the handle and closes it on drop.
+        let mut stream = unsafe { File::from_raw_handle(pipe.0) };
+        if !connected || shutdown.is_cancelled() {
+            disconnect(&stream);
+            continue;
+        }
+        clients.retain(|client| !client.is_finished());
+        if clients.len() >= MAX_CLIENTS {
+            let _ = stream.write_all(b"error service busy, try again\n");
+            disconnect(&stream);
+            continue;
+        }
+        let (config, tx, token) = (Arc::clone(config), tx.clone(), shutdown.clone());
+        match thread::Builder::new()
+            .name("control-pipe-client".into())
+            .spawn(move || serve_client(stream, &config, &tx, &token))
+        {
+            Ok(client) => clients.push(client),
+            Err(e) => warn!("Control pipe: cannot start a client thread: {}", e),
+        }
+    }
+
+    // Clients notice the shutdown within one poll interval.
+    for client in clients {
+        let _ = client.join();
+    }
+
+    if have_descriptor {
+        unsafe {
+            LocalFree(HLOCAL(descriptor.0));
+        }
+    }
+    info!("Control pipe stopped");
+}
+
+fn disconnect(stream: &File) {
+    unsafe {
+        let _ = DisconnectNamedPipe(HANDLE(stream.as_raw_handle()));
+    }
+}
+
+/// Answer one connection, then disconnect it.
+fn serve_client(
+    mut stream: File,
+    config: &Config,
+    tx: &mpsc::Sender<DisplayEvent>,
+    shutdown: &ShutdownToken,
+) {
+    let reply =
+        match read_request(&mut stream, shutdown).and_then(|line| handle_line(config, tx, &line)) {
+            Ok(message) => format!("ok {}\n", message),
+            Err(message) => format!("error {}\n", message),
+        };
+    let _ = stream.write_all(reply.as_bytes());
+    let _ = stream.flush();
+    disconnect(&stream);
+}
+
+/// Read the client's request line. Only bytes already in the pipe are
+/// read, so the wait can end on [`REQUEST_TIMEOUT`] or shutdown instead
+/// of blocking in `ReadFile`.
+fn read_request(stream: &mut File, shutdown: &ShutdownToken) -> Result<String, String> {
+    let pipe = HANDLE(stream.as_raw_handle());
+    let deadline = Instant::now() + REQUEST_TIMEOUT;
+    let mut request = Vec::with_capacity(MAX_REQUEST_LEN);
+    let mut chunk = [0u8; MAX_REQUEST_LEN];
+    loop {
+        if let Some(line) = request_line(&request)? {
+            return Ok(line);
+        }
+        let mut available = 0u32;
+        unsafe { PeekNamedPipe(pipe, None, 0, None, Some(&mut available), None) }
+            .map_err(|_| "client disconnected".to_string())?;
+        if available == 0 {
+            if shutdown.is_cancelled() {
+                return Err("service is shutting down".to_string());
+            }
+            if Instant::now() >= deadline {
+                return Err("timed out waiting for the request".to_string());
+            }
+            thread::sleep(REQUEST_POLL_INTERVAL);
+            continue;
+        }
+        let wanted = (available as usize).min(MAX_REQUEST_LEN - request.len());
+        let read = stream
+            .read(&mut chunk[..wanted])
+            .map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Err("client disconnected".to_string());
+        }
+        request.extend_from_slice(&chunk[..read]);
+    }
+}
+
+/// The request line in `received` once its newline has arrived; an error
+/// once [`MAX_REQUEST_LEN`] bytes came without one.
+pub(crate) fn request_line(received: &[u8]) -> Result<Option<String>, String> {
+    match received.iter().position(|&b| b == b'\n') {
+        Some(end) if end < MAX_REQUEST_LEN => {
+            Ok(Some(String::from_utf8_lossy(&received[..end]).into_owned()))
+        }
+        None if received.len() < MAX_REQUEST_LEN => Ok(None),
+        _ => Err(format!("request longer than {} bytes", MAX_REQUEST_LEN)),
+    }
+}
+
+fn handle_line(
+    config: &Config,
+    tx: &mpsc::Sender<DisplayEvent>,
+    line: &str,
+) -> Result<String, String> {
+    let request = parse_request(line)?;
+    // Status queries are read-only; not worth a diagnostics entry each.
+    if !matches!(
+        request,
+        Request::History(_) | Request::Workers | Request::Lid
+    ) {
+        app_state::append_diagnostic_event("service", "INFO", "quick_command", line.trim());
+    }
+    match request {
+        Request::Reapply => {
+            queue_reapply(tx)?;
+            Ok("reapply queued".to_string())
+        }
+        Request::Preset(preset) => {
+            let message = match &preset {
+                Some(name) => format!("preset {} pinned, reapply queued", name),
+                None => "preset back to automatic, reapply queued".to_string(),
+            };
+            set_preset_override(preset);
+            queue_reapply(tx)?;
+            Ok(message)
+        }
+        Request::Brightness(change) => {
+            let changed = hotkeys::adjust_brightness(config, change).map_err(|e| e.to_string())?;
+            if changed.is_empty() {
+                return Ok("brightness unchanged".to_string());
+            }
+            Ok(changed
+                .iter()
+                .map(|(monitor, value)| format!("{}={}", monitor, value))
+                .collect::<Vec<_>>()
+                .join(", "))
+        }
+        Request::History(limit) => {
+            let records = history()
+                .lock()
+                .map(|h| h.recent(limit))
+                .unwrap_or_default();
+            serde_json::to_string(&records).map_err(|e| e.to_string())
+        }
+        Request::Workers => {
+            serde_json::to_string(&super::supervisor::snapshot()).map_err(|e| e.to_string())
+        }
+        Request::Lid => Ok(super::lid::state()
+            .map_or("unknown", LidState::as_str)
+            .to_string()),
+    }
+}
+
+fn queue_reapply(tx: &mpsc::Sender<DisplayEvent>) -> Result<(), String> {
+    tx.send(DisplayEvent::RemoteReapply)
+        .map_err(|_| "service is shutting down".to_string())
+}
+
+/// Send one request to the running service/watcher and return its reply.
+pub fn send_quick_command(request: &str) -> Result<String, Box<dyn Error>> {
+    let deadline = Instant::now() + CLIENT_BUSY_TIMEOUT;
+    let mut stream = loop {
+        match std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(PIPE_NAME)
+        {
+            Ok(s) => break s,
+            Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY) && Instant::now() < deadline => {
+                thread::sleep(Duration::from_millis(10));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err("service is not running (start it or run `watch`)".into());
+            }
+            Err(e) => return Err(format!("cannot reach service: {}", e).into()),
+        }
+    };
+
+    stream.write_all(format!("{}\n", request.trim()).as_bytes())?;
+    stream.flush()?;
+    let mut reply = String::new();
+    BufReader::new(&stream).read_line(&mut reply)?;
+    let reply = reply.trim();
+    match reply.split_once(' ') {
+        Some(("ok", message)) => Ok(message.to_string()),
+        Some(("error", message)) => Err(message.to_string().into()),
+        _ if reply == "ok" => Ok(String::new()),
+        _ => Err(format!("unexpected reply from service: {}", reply).into()),
+    }
+}
+
+/// Fetch the service's recent reapply attempts, oldest first.
+pub fn fetch_reapply_history(limit: Option<usize>) -> Result<Vec<ReapplyRecord>, Box<dyn Error>> {
+    let request = match limit {
+        Some(n) => format!("history {}", n),
+        None => "history".to_string(),
+    };
+    let reply = send_quick_command(&request)?;
+    serde_json::from_str(&reply)
+        .map_err(|e| format!("unexpected history reply from service: {}", e).into())
+}
+
+/// Ask the running service/watcher for the health of its background workers.
+pub fn fetch_worker_health() -> Result<Vec<WorkerHealth>, Box<dyn Error>> {
+    let reply = send_quick_command("workers")?;
+    serde_json::from_str(&reply)
+        .map_err(|e| format!("unexpected workers reply from service: {}", e).into())
+}
+
+/// Ask the running service/watcher for the lid position; `None` when it
+/// has none to report (no lid, or no notification yet).
+pub fn fetch_lid_state() -> Result<Option<LidState>, Box<dyn Error>> {
+    let reply = send_quick_command("lid")?;
+    match reply.trim() {
+        "unknown" => Ok(None),
+        other => LidState::parse(other)
+            .map(Some)
+            .ok_or_else(|| format!("unexpected lid reply from service: {}", other).into()),
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/ipc_tests.rs"]
+mod tests;
@@ -0,0 +1,192 @@
+//! Migration from the legacy standalone `lg-ultragear-color-svc` binary.
+//!
+//! The old `service/` build registered the same service name but ran its own
+//! executable and kept its settings as values under the service's
+//! `Parameters` registry key. Reinstalling over it deletes that registration
+//! (and the key with it), so [`detect`] captures the image path and
+//! parameters first and [`import_parameters`] folds them into [`Config`].
+
+use super::{CONFIG_REG_KEY, SERVICE_NAME};
+use lg_core::config::{self, Config};
+use log::info;
+use std::path::Path;
+
+use windows_service::service::ServiceAccess;
+use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+
+/// Registry values the legacy service understood.
+const LEGACY_PARAMETER_NAMES: &[&str] = &[
+    "MonitorMatch",
+    "ProfileName",
+    "ToastEnabled",
+    "StabilizeDelayMs",
+    "ToggleDelayMs",
+    "ReapplyDelayMs",
+    "Verbose",
+];
+
+/// A legacy service registration found in SCM.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct LegacyService {
+    pub(crate) image_path: String,
+    pub(crate) parameters: Vec<(String, String)>,
+}
+
+/// Outcome of migrating a legacy standalone service install.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LegacyMigration {
+    /// Image path the legacy service was registered with.
+    pub legacy_binary: String,
+    /// Config fields imported from the legacy `Parameters` key (`field=value`).
+    pub imported: Vec<String>,
+}
+
+/// Extract the lowercase executable file name from an SCM image path, which
+/// may be quoted and followed by arguments.
+pub(crate) fn image_file_name(image_path: &str) -> Option<String> {
+    let trimmed = image_path.trim();
+    let exe = if let Some(rest) = trimmed.strip_prefix('"') {
+        rest.split('"').next().unwrap_or(rest)
+    } else {
+        match trimmed.to_ascii_lowercase().find(".exe") {
+            Some(idx) => &trimmed[..idx + 4],
+            None => trimmed.split_whitespace().next().unwrap_or(trimmed),
+        }
+    };
+    Path::new(exe)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_ascii_lowercase())
+        .filter(|n| !n.is_empty())
+}
+
+/// True if `image_path` points at something other than this tool's binary.
+pub(crate) fn is_legacy_image(image_path: &str, install_path: &Path) -> bool {
+    let Some(current) = install_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_ascii_lowercase())
+    else {
+        return false;
+    };
+    image_file_name(image_path).is_some_and(|name| name != current)
+}
+
+/// Look for a legacy registration under [`SERVICE_NAME`].
+///
+/// Must run before the service is deleted: SCM removes the `Parameters`
+/// key together with the service key.
+pub(crate) fn detect() -> Option<LegacyService> {
+    let manager =
+        ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT).ok()?;
+    let service = manager
+        .open_service(SERVICE_NAME, ServiceAccess::QUERY_CONFIG)
+        .ok()?;
+    let image_path = service
+        .query_config()
+        .ok()?
+        .executable_path
+        .to_string_lossy()
+        .to_string();
+    if !is_legacy_image(&image_path, &config::install_path()) {
+        return None;
+    }
+    info!("Legacy service binary detected: {}", image_path);
+    Some(LegacyService {
+        image_path,
+        parameters: read_parameters(),
+    })
+}
+
+fn read_parameters() -> Vec<(String, String)> {
+    use winreg::enums::*;
+    use winreg::RegKey;
+
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let Ok(key) = hklm.open_subkey_with_flags(CONFIG_REG_KEY, KEY_READ) else {
+        return Vec::new();
+    };
+    LEGACY_PARAMETER_NAMES
+        .iter()
+        .filter_map(|&name| {
+            let value = key
+                .get_value::<String, _>(name)
+                .ok()
+                .or_else(|| key.get_value::<u32, _>(name).ok().map(|v| v.to_string()))?;
+            Some((name.to_string(), value))
+        })
+        .collect()
+}
+
+/// Fold legacy parameters into `cfg`.
+///
+/// A field is only imported while it still holds its default, so anything
+/// already customized in `config.toml` wins. Returns `field=value` entries
+/// for everything that was imported.
+pub(crate) fn import_parameters(cfg: &mut Config, parameters: &[(String, String)]) -> Vec<String> {
+    let defaults = Config::default();
+    let mut imported = Vec::new();
+
+    for (name, raw) in parameters {
+        let value = raw.trim();
+        let field = match name.as_str() {
+            "MonitorMatch" if cfg.monitor_match == defaults.monitor_match && !value.is_empty() => {
+                cfg.monitor_match = value.to_string();
+                "monitor_match"
+            }
+            "ProfileName" if cfg.profile_name == defaults.profile_name && !value.is_empty() => {
+                cfg.profile_name = value.to_string();
+                "profile_name"
+            }
+            "ToastEnabled" if cfg.toast_enabled == defaults.toast_enabled => {
+                match parse_flag(value) {
+                    Some(v) => cfg.toast_enabled = v,
+                    None => continue,
+                }
+                "toast_enabled"
+            }
+            "Verbose" if cfg.verbose == defaults.verbose => {
+                match parse_flag(value) {
+                    Some(v) => cfg.verbose = v,
+                    None => continue,
+                }
+                "verbose"
+            }
+            "StabilizeDelayMs" if cfg.stabilize_delay_ms == defaults.stabilize_delay_ms => {
+                match value.parse() {
+                    Ok(v) => cfg.stabilize_delay_ms = v,
+                    Err(_) => continue,
+                }
+                "stabilize_delay_ms"
+            }
+            "ToggleDelayMs" if cfg.toggle_delay_ms == defaults.toggle_delay_ms => {
+                match value.parse() {
+                    Ok(v) => cfg.toggle_delay_ms = v,
+                    Err(_) => continue,
+                }
+                "toggle_delay_ms"
+            }
+            "ReapplyDelayMs" if cfg.reapply_delay_ms == defaults.reapply_delay_ms => {
+                match value.parse() {
+                    Ok(v) => cfg.reapply_delay_ms = v,
+                    Err(_) => continue,
+                }
+                "reapply_delay_ms"
+            }
+            _ => continue,
+        };
+        imported.push(format!("{}={}", field, value));
+    }
+
+    imported
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/legacy_tests.rs"]
+mod tests;
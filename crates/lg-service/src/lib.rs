@@ -6,26 +6,71 @@
 //!   - Window receives `WM_DEVICECHANGE` (monitor plug/unplug) and
 //!     `WM_WTSSESSION_CHANGE` (logon, unlock, console connect)
 //!   - On relevant events, triggers the profile reapply pipeline
+//!   - Background workers (debounce, control pipe, pollers, watchers) are
+//!     owned by one [`tasks::TaskSet`] and joined together at shutdown
 //!   - Service stop signal cleanly destroys the window and exits
 //!
 //! Also provides a `watch()` entry point for foreground console mode
 //! (same event loop, Ctrl+C to stop).
 
+mod app_override;
+mod brightness_guard;
+mod crash;
+mod day_night;
+mod debounce;
+mod defer;
+mod display_power;
+mod driver_update;
+mod event_log;
+mod events;
+mod fullscreen;
+mod gamma_watch;
+mod hotkeys;
+mod ipc;
+mod legacy;
+mod lid;
+mod profile_watch;
+mod refresh;
+mod resource_audit;
+mod supervisor;
+mod tamper;
+mod tasks;
+mod telemetry;
+mod watchdog;
+mod wmi_gate;
+
+pub use event_log::{events_after, recent_events, EventLevel, LogEvent};
+pub use ipc::{fetch_lid_state, fetch_reapply_history, send_quick_command, PIPE_NAME};
+pub use legacy::LegacyMigration;
+pub use telemetry::{payload as telemetry_payload, windows_build};
+
 use chrono::{Local, NaiveTime};
+use events::{DisplayEvent, Targets};
+use lg_core::audit::{self, AuditEntry};
 use lg_core::config::{self, Config};
+use lg_core::counters::{self, ServiceCounters};
+use lg_core::display_gate;
+use lg_core::pipeline::{
+    ModeProfiles, MonitorResult, ReapplyOptions, ReapplyPipeline, ReapplySteps,
+};
+use lg_core::plan::{OpKind, Plan};
+use lg_core::progress::{self, Progress, ProgressEvent};
+use lg_core::report::{self, DeviceEntry, InstallReport, ReapplyRecord};
+use lg_core::shutdown::{Cancelled, ShutdownToken};
 use lg_core::state as app_state;
+use lg_core::toast_template::{self, ToastTally};
+use lg_core::wide::WideString;
 use log::{error, info, warn};
 use regex::RegexBuilder;
 use std::error::Error;
-use std::ffi::{OsStr, OsString};
-use std::os::windows::ffi::OsStrExt;
-use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+use std::ffi::OsString;
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicPtr, Ordering};
 use std::sync::{mpsc, Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant};
 use std::{mem, ptr, thread};
 
 use windows::core::PCWSTR;
-use windows::Win32::Foundation::{BOOL, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::Foundation::{BOOL, HANDLE, HWND, LPARAM, LRESULT, WPARAM};
 use windows::Win32::System::RemoteDesktop::{
     WTSRegisterSessionNotification, WTSUnRegisterSessionNotification, NOTIFY_FOR_ALL_SESSIONS,
 };
@@ -63,6 +108,9 @@ const EVENTLOG_REG_KEY: &str =
 /// Custom window message to signal shutdown.
 const WM_QUIT_SERVICE: u32 = WM_USER + 1;
 
+/// Longest a stop waits for background threads before leaving them behind.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// GUID for display device interface notifications.
 /// GUID_DEVINTERFACE_MONITOR = {E6F07B5F-EE97-4a90-B076-33F57BF4EAA7}
 const GUID_DEVINTERFACE_MONITOR: windows::core::GUID = windows::core::GUID::from_values(
@@ -75,6 +123,7 @@ const GUID_DEVINTERFACE_MONITOR: windows::core::GUID = windows::core::GUID::from
 /// WM_DEVICECHANGE constants.
 const WM_DEVICECHANGE: u32 = 0x0219;
 const DBT_DEVICEARRIVAL: u32 = 0x8000;
+const DBT_DEVICEREMOVECOMPLETE: u32 = 0x8004;
 const DBT_DEVNODES_CHANGED: u32 = 0x0007;
 
 /// WM_WTSSESSION_CHANGE constants.
@@ -83,38 +132,88 @@ const WTS_CONSOLE_CONNECT: u32 = 0x1;
 const WTS_SESSION_LOGON: u32 = 0x5;
 const WTS_SESSION_UNLOCK: u32 = 0x8;
 
-/// DEV_BROADCAST_DEVICEINTERFACE_W for RegisterDeviceNotificationW.
-#[repr(C)]
-struct DevBroadcastDeviceInterface {
-    dbcc_size: u32,
-    dbcc_devicetype: u32,
-    dbcc_reserved: u32,
-    dbcc_classguid: windows::core::GUID,
-    dbcc_name: [u16; 1],
-}
-
+/// `dbcc_devicetype` of a device interface broadcast (the generated
+/// constant is typed for `DEV_BROADCAST_HDR`).
 const DBT_DEVTYP_DEVICEINTERFACE: u32 = 5;
-const DEVICE_NOTIFY_WINDOW_HANDLE: u32 = 0;
 
-// ── Event type bitflags ──────────────────────────────────────────
+// ── Event classes ────────────────────────────────────────────────
+//
+// One bit per `events::DisplayEvent` variant; a debounced batch is the set
+// of classes it contains.
 
 /// A monitor device interface was plugged in (GUID-filtered).
-const EVENT_DEVICE_ARRIVAL: u8 = 0b0000_0001;
+const EVENT_DEVICE_ARRIVAL: u32 = 0b0000_0001;
 /// Generic devnode topology change (could be any device class).
-const EVENT_DEVNODES_CHANGED: u8 = 0b0000_0010;
+const EVENT_DEVNODES_CHANGED: u32 = 0b0000_0010;
 /// User logged on to a new session.
-const EVENT_SESSION_LOGON: u8 = 0b0000_0100;
+const EVENT_SESSION_LOGON: u32 = 0b0000_0100;
 /// User unlocked an existing session.
-const EVENT_SESSION_UNLOCK: u8 = 0b0000_1000;
+const EVENT_SESSION_UNLOCK: u32 = 0b0000_1000;
 /// A console was connected (e.g. Remote Desktop switch).
-const EVENT_CONSOLE_CONNECT: u8 = 0b0001_0000;
+const EVENT_CONSOLE_CONNECT: u32 = 0b0001_0000;
 /// Periodic automation poll timer.
-const EVENT_AUTOMATION_POLL: u8 = 0b0010_0000;
+const EVENT_AUTOMATION_POLL: u32 = 0b0010_0000;
+/// A fullscreen application left the foreground (gamma may have been reset).
+const EVENT_FULLSCREEN_EXIT: u32 = 0b0100_0000;
+/// Focus moved onto or off an app listed in `[app_overrides]`.
+const EVENT_APP_OVERRIDE: u32 = 0b1000_0000;
+/// The day/night schedule crossed a boundary.
+const EVENT_SCHEDULE: u32 = 0b1_0000_0000;
+/// The reapply hotkey was pressed.
+const EVENT_HOTKEY_REAPPLY: u32 = 0b10_0000_0000;
+/// A brightness hotkey was pressed (delta pending in `hotkeys`).
+const EVENT_HOTKEY_BRIGHTNESS: u32 = 0b100_0000_0000;
+/// A `quick reapply` / `quick preset` request arrived over the control pipe.
+const EVENT_REMOTE_REAPPLY: u32 = 0b1000_0000_0000;
+/// Another program replaced our default profile (`tamper_watch = "reassert"`).
+const EVENT_PROFILE_TAMPERED: u32 = 0b1_0000_0000_0000;
+/// A GPU driver reset the gamma ramp (`gamma_watch = "reassert"`).
+const EVENT_GAMMA_RESET: u32 = 0b10_0000_0000_0000;
+/// The watchdog found our association missing.
+const EVENT_WATCHDOG: u32 = 0b100_0000_0000_0000;
+/// The console display turned back on with a reapply held while it was off.
+const EVENT_DISPLAY_ON: u32 = 0b1000_0000_0000_0000;
+/// The profile file of the active app override changed on disk.
+const EVENT_CALIBRATION_UPDATED: u32 = 0b1_0000_0000_0000_0000;
 
 /// Mask: any device-related event.
-const EVENT_MASK_DEVICE: u8 = EVENT_DEVICE_ARRIVAL | EVENT_DEVNODES_CHANGED;
+const EVENT_MASK_DEVICE: u32 = EVENT_DEVICE_ARRIVAL | EVENT_DEVNODES_CHANGED;
 /// Mask: any session-related event.
-const EVENT_MASK_SESSION: u8 = EVENT_SESSION_LOGON | EVENT_SESSION_UNLOCK | EVENT_CONSOLE_CONNECT;
+const EVENT_MASK_SESSION: u32 = EVENT_SESSION_LOGON | EVENT_SESSION_UNLOCK | EVENT_CONSOLE_CONNECT;
+/// Mask: user-initiated requests (hotkeys and `quick` commands).
+const EVENT_MASK_USER: u32 = EVENT_HOTKEY_REAPPLY | EVENT_HOTKEY_BRIGHTNESS | EVENT_REMOTE_REAPPLY;
+/// Mask: background triggers that `defer_reapply` may hold back.
+const EVENT_MASK_DEFERRABLE: u32 = EVENT_MASK_DEVICE | EVENT_AUTOMATION_POLL | EVENT_SCHEDULE;
+
+/// Names for the event flags, as shown by `service history`.
+const EVENT_NAMES: &[(u32, &str)] = &[
+    (EVENT_DEVICE_ARRIVAL, "device_arrival"),
+    (EVENT_DEVNODES_CHANGED, "devnodes_changed"),
+    (EVENT_SESSION_LOGON, "session_logon"),
+    (EVENT_SESSION_UNLOCK, "session_unlock"),
+    (EVENT_CONSOLE_CONNECT, "console_connect"),
+    (EVENT_AUTOMATION_POLL, "automation_poll"),
+    (EVENT_FULLSCREEN_EXIT, "fullscreen_exit"),
+    (EVENT_APP_OVERRIDE, "app_override"),
+    (EVENT_SCHEDULE, "schedule"),
+    (EVENT_HOTKEY_REAPPLY, "hotkey_reapply"),
+    (EVENT_HOTKEY_BRIGHTNESS, "hotkey_brightness"),
+    (EVENT_REMOTE_REAPPLY, "remote_reapply"),
+    (EVENT_PROFILE_TAMPERED, "profile_tampered"),
+    (EVENT_GAMMA_RESET, "gamma_reset"),
+    (EVENT_WATCHDOG, "watchdog"),
+    (EVENT_DISPLAY_ON, "display_on"),
+    (EVENT_CALIBRATION_UPDATED, "calibration_updated"),
+];
+
+/// Names of the flags set in `flags`, lowest bit first.
+fn event_names(flags: u32) -> Vec<&'static str> {
+    EVENT_NAMES
+        .iter()
+        .filter(|(flag, _)| flags & flag != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
 
 #[derive(Debug, Clone, Default)]
 struct AmbientMemory {
@@ -134,25 +233,21 @@ struct AutomationDecision {
 
 static AMBIENT_MEMORY: OnceLock<Mutex<AmbientMemory>> = OnceLock::new();
 static LAST_AUTOMATION_FINGERPRINT: OnceLock<Mutex<String>> = OnceLock::new();
-
-// FFI for RegisterDeviceNotificationW (not always in windows crate metadata)
-#[link(name = "user32")]
-extern "system" {
-    fn RegisterDeviceNotificationW(
-        recipient: HWND,
-        notification_filter: *const DevBroadcastDeviceInterface,
-        flags: u32,
-    ) -> *mut std::ffi::c_void;
-
-    fn UnregisterDeviceNotification(handle: *mut std::ffi::c_void) -> BOOL;
-}
+/// Set once the DDC/CI guidance toast has been shown this run.
+static DDC_GUIDANCE_SHOWN: AtomicBool = AtomicBool::new(false);
 
 // ============================================================================
 // Service dispatch (called by SCM)
 // ============================================================================
 
-/// Entry point when launched by the Service Control Manager.
-pub fn run() -> Result<(), Box<dyn Error>> {
+/// Version of the running binary, set by [`run`] for the usage ping.
+static APP_VERSION: OnceLock<&'static str> = OnceLock::new();
+
+/// Entry point when launched by the Service Control Manager. `version`
+/// is the application version reported by the usage ping.
+pub fn run(version: &'static str) -> Result<(), Box<dyn Error>> {
+    let _ = APP_VERSION.set(version);
+    audit::set_source("service");
     service_dispatcher::start(SERVICE_NAME, ffi_service_main)?;
     Ok(())
 }
@@ -160,14 +255,18 @@ pub fn run() -> Result<(), Box<dyn Error>> {
 windows_service::define_windows_service!(ffi_service_main, service_main);
 
 fn service_main(arguments: Vec<OsString>) {
-    if let Err(e) = run_service(arguments) {
-        error!("Service error: {}", e);
+    match crash::catch("service startup", || run_service(arguments)) {
+        Ok(Err(e)) => error!("Service error: {}", e),
+        Ok(Ok(())) | Err(_) => {}
     }
 }
 
 fn run_service(_arguments: Vec<OsString>) -> Result<(), Box<dyn Error>> {
     // Load config from file (falls back to defaults)
     let cfg = Config::load();
+    log_config_issues();
+    remove_leftover_toast_tasks();
+    start_counters();
     info!(
         "Service starting. Monitor pattern: \"{}\" ({:?}), toast: {}, profile: {}",
         cfg.monitor_match,
@@ -180,11 +279,10 @@ fn run_service(_arguments: Vec<OsString>) -> Result<(), Box<dyn Error>> {
         cfg.profile_name
     );
 
-    let running = Arc::new(AtomicBool::new(true));
-    let running_clone = running.clone();
+    let shutdown = ShutdownToken::new();
+    let shutdown_for_handler = shutdown.clone();
 
     let hwnd = Arc::new(AtomicIsize::new(0));
-    let hwnd_clone = hwnd.clone();
 
     // Register service control handler
     let status_handle = service_control_handler::register(
@@ -193,16 +291,7 @@ fn run_service(_arguments: Vec<OsString>) -> Result<(), Box<dyn Error>> {
             match control {
                 ServiceControl::Stop | ServiceControl::Shutdown => {
                     info!("Service stop/shutdown requested");
-                    running_clone.store(false, Ordering::SeqCst);
-
-                    let h = hwnd_clone.load(Ordering::SeqCst);
-                    if h != 0 {
-                        unsafe {
-                            let _ =
-                                PostMessageW(HWND(h as _), WM_QUIT_SERVICE, WPARAM(0), LPARAM(0));
-                        }
-                    }
-
+                    shutdown_for_handler.cancel();
                     ServiceControlHandlerResult::NoError
                 }
                 ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
@@ -223,14 +312,21 @@ fn run_service(_arguments: Vec<OsString>) -> Result<(), Box<dyn Error>> {
     })?;
 
     // Run the event loop. A normal stop/shutdown should return Ok(()).
-    // Unexpected errors must map to a non-zero service exit code so SCM
-    // recovery actions (restart) can trigger.
-    let result = run_event_loop(&cfg, &running, &hwnd);
+    // Unexpected errors and panics must map to a non-zero service exit code
+    // so SCM recovery actions (restart) can trigger, instead of a bare 1067.
+    let result = match crash::catch("event loop", || run_event_loop(&cfg, &shutdown, &hwnd)) {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(crash::ServiceError::EventLoop(e)),
+        Err(message) => {
+            shutdown.cancel();
+            Err(crash::ServiceError::Panicked(message))
+        }
+    };
     let exit_code = match &result {
         Ok(()) => ServiceExitCode::NO_ERROR,
         Err(e) => {
-            error!("Event loop error: {}", e);
-            ServiceExitCode::ServiceSpecific(1)
+            error!("{}", e);
+            ServiceExitCode::ServiceSpecific(e.exit_code())
         }
     };
 
@@ -250,7 +346,7 @@ fn run_service(_arguments: Vec<OsString>) -> Result<(), Box<dyn Error>> {
             info!("Service stopped cleanly");
             Ok(())
         }
-        Err(e) => Err(e),
+        Err(e) => Err(e.into()),
     }
 }
 
@@ -258,29 +354,64 @@ fn run_service(_arguments: Vec<OsString>) -> Result<(), Box<dyn Error>> {
 // Watch mode (foreground console)
 // ============================================================================
 
+fn or_unbound(binding: &str) -> &str {
+    if binding.trim().is_empty() {
+        "(unbound)"
+    } else {
+        binding
+    }
+}
+
+/// Let solar schedules with `[schedule] use_windows_location` take their
+/// position from the Windows Location API. The lookup is deferred until a
+/// schedule first needs it.
+pub fn enable_windows_location() {
+    lg_core::schedule::set_location_lookup(day_night::lookup_location);
+}
+
+/// Delete temporary toast tasks an older release left behind when it was
+/// stopped between creating and deleting one.
+fn remove_leftover_toast_tasks() {
+    match lg_notify::remove_leftover_toast_tasks() {
+        Ok(removed) => {
+            for name in removed {
+                info!("Removed leftover scheduled task {}", name);
+            }
+        }
+        Err(e) => warn!("Could not check for leftover toast tasks: {}", e),
+    }
+}
+
+/// Log `config validate` findings for the active config file, so a typo or
+/// out-of-range value shows up in the Event Log instead of being silently
+/// ignored or replaced by a default.
+fn log_config_issues() {
+    lg_core::validate::set_pattern_check(lg_monitor::check_match_pattern);
+    let path = config::config_path();
+    if let Ok(issues) = lg_core::validate::validate_file(&path) {
+        for issue in issues {
+            warn!("Config {}: {}", path.display(), issue);
+        }
+    }
+}
+
 /// Run the event watcher in foreground console mode.
 ///
 /// Listens for the same display and session events as the service,
 /// but runs interactively with Ctrl+C to stop. Useful for testing.
-pub fn watch(config: &Config) -> Result<(), Box<dyn Error>> {
-    let running = Arc::new(AtomicBool::new(true));
-    let running_for_handler = running.clone();
+/// The startup banner and shutdown notice go to `report`, line by line.
+pub fn watch(config: &Config, report: fn(&str)) -> Result<(), Box<dyn Error>> {
+    let shutdown = ShutdownToken::new();
+    let shutdown_for_handler = shutdown.clone();
     let hwnd = Arc::new(AtomicIsize::new(0));
-    let hwnd_for_handler = hwnd.clone();
 
     ctrlc::set_handler(move || {
-        println!("\n[WATCH] Shutting down...");
-        running_for_handler.store(false, Ordering::SeqCst);
-        let h = hwnd_for_handler.load(Ordering::SeqCst);
-        if h != 0 {
-            unsafe {
-                let _ = PostMessageW(HWND(h as _), WM_QUIT_SERVICE, WPARAM(0), LPARAM(0));
-            }
-        }
+        report("\n[WATCH] Shutting down...");
+        shutdown_for_handler.cancel();
     })?;
 
-    println!("[WATCH] Starting event watcher (Ctrl+C to stop)");
-    println!(
+    report("[WATCH] Starting event watcher (Ctrl+C to stop)");
+    report(&format!(
         "[WATCH] Monitor: \"{}\" ({})  Profile: {}  Toast: {}",
         config.monitor_match,
         if config.monitor_match_regex {
@@ -290,10 +421,36 @@ pub fn watch(config: &Config) -> Result<(), Box<dyn Error>> {
         },
         config.profile_name,
         if config.toast_enabled { "on" } else { "off" }
-    );
-    println!();
+    ));
+    if config.brightness_guard {
+        report(&format!(
+            "[WATCH] Brightness guard: target {} (threshold {}, every {}ms)",
+            config.ddc_brightness_value,
+            config.brightness_guard_threshold,
+            config.brightness_guard_interval_ms
+        ));
+    }
+    if config.hotkeys_enabled {
+        report(&format!(
+            "[WATCH] Hotkeys: reapply {}  brightness +{} {} / -{} {}",
+            or_unbound(&config.hotkey_reapply),
+            config.hotkey_brightness_step,
+            or_unbound(&config.hotkey_brightness_up),
+            config.hotkey_brightness_step,
+            or_unbound(&config.hotkey_brightness_down)
+        ));
+    }
+    if lg_core::schedule::is_enabled(config) {
+        report(&format!(
+            "[WATCH] Day/night schedule: {} / {} (now {})",
+            config.icc_schedule_day_preset,
+            config.icc_schedule_night_preset,
+            lg_core::schedule::current_period(config).label()
+        ));
+    }
+    report("");
 
-    run_event_loop(config, &running, &hwnd)
+    run_event_loop(config, &shutdown, &hwnd)
 }
 
 // ============================================================================
@@ -301,9 +458,9 @@ pub fn watch(config: &Config) -> Result<(), Box<dyn Error>> {
 // ============================================================================
 
 // Thread-local channel sender for the window proc to dispatch events
-// to the single debounce worker thread (zero-allocation, lock-free dispatch).
+// to the single debounce worker thread (lock-free dispatch).
 thread_local! {
-    static EVENT_SENDER: std::cell::RefCell<Option<mpsc::Sender<u8>>> =
+    static EVENT_SENDER: std::cell::RefCell<Option<mpsc::Sender<DisplayEvent>>> =
         const { std::cell::RefCell::new(None) };
 }
 
@@ -347,13 +504,14 @@ fn tuning_from_config(config: &Config) -> lg_profile::DynamicIccTuning {
 }
 
 fn effective_preset_for_mode(config: &Config, hdr_mode: bool) -> String {
-    lg_profile::select_effective_preset(
+    lg_profile::select_effective_preset_for_period(
         &config.icc_active_preset,
         &config.icc_sdr_preset,
         &config.icc_hdr_preset,
         &config.icc_schedule_day_preset,
         &config.icc_schedule_night_preset,
         hdr_mode,
+        lg_core::schedule::is_daytime_now(config),
     )
 }
 
@@ -381,6 +539,30 @@ fn find_matching_monitors_for_config(
     lg_monitor::find_matching_monitors_with_mode(&config.monitor_match, monitor_match_mode(config))
 }
 
+/// Why the display-count conditions hold a reapply back, if they do. When
+/// the displays can't be counted the reapply goes ahead.
+fn display_gate_skip(config: &Config) -> Option<String> {
+    if !display_gate::is_enabled(config) {
+        return None;
+    }
+    let counted = find_matching_monitors_for_config(config).and_then(|matching| {
+        let topology = lg_monitor::query_display_topology()?;
+        Ok(display_gate::DisplayCounts {
+            matching: matching.len(),
+            active: topology.active_displays,
+            internal_panel: topology.internal_panel,
+            lid: lid::state(),
+        })
+    });
+    match counted {
+        Ok(counts) => display_gate::skip_reason(config, &counts),
+        Err(e) => {
+            warn!("Could not count displays, reapplying anyway: {}", e);
+            None
+        }
+    }
+}
+
 fn monitor_identity_from_match(
     mon: &lg_monitor::MatchedMonitor,
 ) -> lg_profile::DynamicMonitorIdentity {
@@ -394,10 +576,10 @@ fn monitor_identity_from_match(
 }
 
 fn parse_first_number(text: &str) -> Option<f64> {
-    static NUMBER_RE: OnceLock<regex::Regex> = OnceLock::new();
-    let re = NUMBER_RE.get_or_init(|| {
-        regex::Regex::new(r"[-+]?(?:\d+\.?\d*|\.\d+)(?:[eE][-+]?\d+)?").expect("valid number regex")
-    });
+    static NUMBER_RE: OnceLock<Option<regex::Regex>> = OnceLock::new();
+    let re = NUMBER_RE
+        .get_or_init(|| regex::Regex::new(r"[-+]?(?:\d+\.?\d*|\.\d+)(?:[eE][-+]?\d+)?").ok())
+        .as_ref()?;
     re.find(text).and_then(|m| m.as_str().parse::<f64>().ok())
 }
 
@@ -712,7 +894,7 @@ fn evaluate_app_rule_decision(cfg: &app_state::AppRulesConfig) -> Option<Automat
     None
 }
 
-fn resolve_automation_decision(event_flags: u8) -> AutomationDecision {
+fn resolve_automation_decision(event_flags: u32) -> AutomationDecision {
     let cfg = app_state::load_automation_config();
     let mut resolved = AutomationDecision::default();
     let mut details = Vec::new();
@@ -759,7 +941,7 @@ fn resolve_automation_decision(event_flags: u8) -> AutomationDecision {
     resolved
 }
 
-fn maybe_run_self_heal(config: &Config, effective_preset: &str, trigger: &str, event_flags: u8) {
+fn maybe_run_self_heal(config: &Config, effective_preset: &str, trigger: &str, event_flags: u32) {
     let cfg = app_state::load_automation_config();
     let health = &cfg.health;
     if !health.enabled {
@@ -873,6 +1055,35 @@ fn should_skip_automation_apply(fingerprint: &str, trigger: &str) -> bool {
     }
 }
 
+/// Performance counters of the running service; `None` outside service mode
+/// so `watch` never overwrites them.
+static COUNTERS: Mutex<Option<ServiceCounters>> = Mutex::new(None);
+
+/// Zero the performance counters for this service run.
+fn start_counters() {
+    let fresh = ServiceCounters::new();
+    if let Err(e) = counters::save_counters(&fresh) {
+        warn!("Could not write performance counters: {}", e);
+    }
+    *COUNTERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(fresh);
+}
+
+/// Count one reapply in the performance counters (service mode only).
+fn record_counters(started: Instant, success: bool) {
+    let mut guard = COUNTERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let Some(totals) = guard.as_mut() else {
+        return;
+    };
+    totals.record_apply(started.elapsed().as_millis() as u64, success);
+    if let Err(e) = counters::save_counters(totals) {
+        warn!("Could not write performance counters: {}", e);
+    }
+}
+
 fn emit_apply_latency(source: &str, started: Instant, success: bool, details: &str) {
     let metrics_cfg = app_state::load_automation_config().metrics;
     if !metrics_cfg.enabled || !metrics_cfg.collect_latency {
@@ -906,47 +1117,75 @@ fn automation_poll_interval_ms() -> Option<u64> {
 
 fn run_event_loop(
     config: &Config,
-    running: &Arc<AtomicBool>,
+    shutdown: &ShutdownToken,
     hwnd_out: &Arc<AtomicIsize>,
 ) -> Result<(), Box<dyn Error>> {
     // Create the debounce channel and a single worker thread.
     // Instead of spawning a new OS thread per event (old approach), all events
     // are dispatched via a lightweight channel send (a few nanoseconds) and
     // coalesced by one dedicated thread using recv_timeout — zero CPU when idle.
-    let (tx, rx) = mpsc::channel::<u8>();
+    let (tx, rx) = mpsc::channel::<DisplayEvent>();
     EVENT_SENDER.with(|s| *s.borrow_mut() = Some(tx.clone()));
 
-    let debounce_config = Arc::new(config.clone());
-    let debounce_handle = {
-        let cfg = debounce_config.clone();
-        thread::Builder::new()
-            .name("debounce-worker".into())
-            .spawn(move || debounce_worker(rx, cfg))
-            .map_err(|e| format!("failed to spawn debounce worker: {}", e))?
-    };
-    let automation_poller = {
-        let poll_interval = automation_poll_interval_ms();
-        poll_interval.map(|interval_ms| {
-            let tx = tx.clone();
-            let running = running.clone();
-            thread::Builder::new()
-                .name("automation-poller".into())
-                .spawn(move || {
-                    while running.load(Ordering::SeqCst) {
-                        thread::sleep(Duration::from_millis(interval_ms));
-                        if !running.load(Ordering::SeqCst) {
-                            break;
-                        }
-                        if tx.send(EVENT_AUTOMATION_POLL).is_err() {
-                            break;
-                        }
-                    }
-                })
+    // Every background thread is owned by one task set and joined together
+    // at shutdown; each runs under the supervisor, which restarts it after a
+    // panic.
+    let mut tasks = tasks::TaskSet::new();
+    let debounce_config = config.clone();
+    tasks
+        .spawn("debounce-worker", shutdown, move |_| {
+            debounce_worker(&rx, &debounce_config)
         })
-    };
+        .map_err(|e| format!("failed to spawn debounce worker: {}", e))?;
+    if let Some(interval_ms) = automation_poll_interval_ms() {
+        let tx = tx.clone();
+        let spawned = tasks.spawn("automation-poller", shutdown, move |shutdown| {
+            while shutdown.sleep(Duration::from_millis(interval_ms)) {
+                if tx.send(DisplayEvent::AutomationPoll).is_err() {
+                    break;
+                }
+            }
+        });
+        if let Err(e) = spawned {
+            warn!("Failed to start automation-poller: {}", e);
+        }
+    }
+    tasks.adopt(
+        "brightness-guard",
+        brightness_guard::spawn(config, shutdown),
+    );
+    enable_windows_location();
+    tasks.adopt(
+        "day-night-scheduler",
+        day_night::spawn(config, shutdown, tx.clone()),
+    );
+    tasks.adopt(
+        "control-pipe",
+        Some(ipc::spawn(config, shutdown, tx.clone())),
+    );
+    shutdown.on_cancel(ipc::wake);
+    tasks.adopt("tamper-watch", tamper::spawn(config, shutdown, tx.clone()));
+    tasks.adopt(
+        "gamma-watch",
+        gamma_watch::spawn(config, shutdown, tx.clone()),
+    );
+    tasks.adopt(
+        "profile-watch",
+        profile_watch::spawn(config, shutdown, tx.clone()),
+    );
+    tasks.adopt(
+        "watchdog",
+        watchdog::spawn(config, shutdown, hwnd_out, tx.clone()),
+    );
+    tasks.adopt("resource-audit", resource_audit::spawn(config, shutdown));
+    // Only the service started by the SCM pings; `watch` never does.
+    if let Some(version) = APP_VERSION.get() {
+        tasks.adopt("telemetry", telemetry::spawn(config, shutdown, version));
+    }
+    info!("Background tasks: {}", tasks.names().join(", "));
 
     // Register window class
-    let class_name = to_wide("LGUltraGearColorSvcWnd");
+    let class_name = WideString::new("LGUltraGearColorSvcWnd");
     let wc = WNDCLASSEXW {
         cbSize: mem::size_of::<WNDCLASSEXW>() as u32,
         lpfnWndProc: Some(wnd_proc),
@@ -954,7 +1193,7 @@ fn run_event_loop(
             windows::Win32::System::LibraryLoader::GetModuleHandleW(PCWSTR(ptr::null()))?
         }
         .into(),
-        lpszClassName: PCWSTR(class_name.as_ptr()),
+        lpszClassName: class_name.as_pcwstr(),
         ..Default::default()
     };
 
@@ -967,8 +1206,8 @@ fn run_event_loop(
     let hwnd = unsafe {
         match CreateWindowExW(
             Default::default(),
-            PCWSTR(class_name.as_ptr()),
-            PCWSTR(class_name.as_ptr()),
+            class_name.as_pcwstr(),
+            class_name.as_pcwstr(),
             Default::default(),
             0,
             0,
@@ -982,7 +1221,7 @@ fn run_event_loop(
             Ok(h) => h,
             Err(e) => {
                 // Clean up the registered window class before returning
-                let _ = UnregisterClassW(PCWSTR(class_name.as_ptr()), wc.hInstance);
+                let _ = UnregisterClassW(class_name.as_pcwstr(), wc.hInstance);
                 return Err(format!("Failed to create message window: {}", e).into());
             }
         }
@@ -991,39 +1230,65 @@ fn run_event_loop(
     // Store handle for control/shutdown (lock-free atomic)
     hwnd_out.store(hwnd.0 as isize, Ordering::SeqCst);
 
-    // Register for device interface notifications (monitor connect/disconnect)
-    let filter = DevBroadcastDeviceInterface {
-        dbcc_size: mem::size_of::<DevBroadcastDeviceInterface>() as u32,
-        dbcc_devicetype: DBT_DEVTYP_DEVICEINTERFACE,
-        dbcc_reserved: 0,
-        dbcc_classguid: GUID_DEVINTERFACE_MONITOR,
-        dbcc_name: [0],
-    };
+    // Cancelling the token ends the message pump from whichever thread asked.
+    let window = hwnd.0 as isize;
+    shutdown.on_cancel(move || unsafe {
+        let _ = PostMessageW(HWND(window as _), WM_QUIT_SERVICE, WPARAM(0), LPARAM(0));
+    });
 
-    let notify_handle =
-        unsafe { RegisterDeviceNotificationW(hwnd, &filter, DEVICE_NOTIFY_WINDOW_HANDLE) };
-    if notify_handle.is_null() {
+    // Register for device interface (monitor connect/disconnect) and
+    // session change notifications
+    let (device_registered, session_registered) = register_notifications(hwnd);
+    if !device_registered {
         warn!("RegisterDeviceNotificationW failed — will rely on session events only");
     }
-
-    // Register for session change notifications
-    let session_registered =
-        unsafe { WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_ALL_SESSIONS).is_ok() };
     if !session_registered {
         warn!("WTSRegisterSessionNotification failed — will rely on device events only");
     }
+    if !display_power::register(hwnd) {
+        warn!("RegisterPowerSettingNotification failed — reapplies will not wait for the display to wake");
+    }
+    if !lid::register(hwnd) {
+        warn!("Lid notifications unavailable — lid state stays unknown");
+    }
+
+    // Foreground hook must live on this thread: out-of-context WinEvent
+    // callbacks are dispatched through this thread's message pump.
+    let fullscreen_hook = if config.fullscreen_exit_reassert {
+        fullscreen::install(&config.fullscreen_exit_apps)
+    } else {
+        None
+    };
+    let app_override_hook = if config.app_overrides.is_empty() {
+        None
+    } else {
+        app_override::install(&config.app_overrides)
+    };
+    let hotkey_ids = if config.hotkeys_enabled {
+        hotkeys::register(hwnd, config)
+    } else {
+        Vec::new()
+    };
 
     info!("Event loop started, listening for display and session events");
 
-    // Initial profile apply on startup (no stabilize delay needed)
-    handle_profile_reapply(config, "startup", 0);
+    // Initial profile apply on startup (no stabilize delay needed), once
+    // WMI answers; at boot it may not be up yet.
+    if wmi_gate::wait(shutdown) {
+        let trigger = if driver_update::detect(config) {
+            "driver_update"
+        } else {
+            "startup"
+        };
+        handle_profile_reapply(config, trigger, 0, &Targets::All);
+    }
 
     let mut message_loop_error: Option<String> = None;
 
     // Message pump
     unsafe {
         let mut msg = MSG::default();
-        while running.load(Ordering::SeqCst) {
+        while !shutdown.is_cancelled() {
             let ret = GetMessageW(&mut msg, HWND::default(), 0, 0);
             if ret == BOOL(0) {
                 break;
@@ -1040,25 +1305,28 @@ fn run_event_loop(
         }
     }
 
-    // Shutdown debounce worker: drop sender to close channel, then join thread
+    // Shutdown: stop the producers (the pump may also have ended on its own)
+    // and drop our senders so the debounce worker's channel closes once they
+    // exit, then join everything within a bounded time.
+    shutdown.cancel();
     EVENT_SENDER.with(|s| *s.borrow_mut() = None);
-    let _ = debounce_handle.join();
-    if let Some(Ok(join_handle)) = automation_poller {
-        let _ = join_handle.join();
-    }
+    drop(tx);
+    tasks.join_all(SHUTDOWN_TIMEOUT);
 
     // Cleanup
-    if session_registered {
-        let _ = unsafe { WTSUnRegisterSessionNotification(hwnd) };
+    if let Some(hook) = fullscreen_hook {
+        fullscreen::uninstall(hook);
     }
-    if !notify_handle.is_null() {
-        unsafe {
-            let _ = UnregisterDeviceNotification(notify_handle);
-        }
+    if let Some(hook) = app_override_hook {
+        app_override::uninstall(hook);
     }
+    hotkeys::unregister(hwnd, &hotkey_ids);
+    unregister_notifications(hwnd);
+    display_power::unregister();
+    lid::unregister();
     unsafe {
         let _ = DestroyWindow(hwnd);
-        let _ = UnregisterClassW(PCWSTR(class_name.as_ptr()), wc.hInstance);
+        let _ = UnregisterClassW(class_name.as_pcwstr(), wc.hInstance);
     }
 
     if let Some(err) = message_loop_error {
@@ -1067,136 +1335,413 @@ fn run_event_loop(
     Ok(())
 }
 
-/// Check if a `DBT_DEVICEARRIVAL` event is for a monitor device interface.
+/// Device notification handle of the message window (null = not registered).
+static DEVICE_NOTIFY_HANDLE: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(ptr::null_mut());
+/// Whether the message window is registered for session notifications.
+static SESSION_NOTIFY_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// Register whichever of the device and session notifications isn't
+/// registered yet. Returns `(device, session)` registration state; called
+/// at startup and again by the watchdog to repair a lost registration.
+fn register_notifications(hwnd: HWND) -> (bool, bool) {
+    if DEVICE_NOTIFY_HANDLE.load(Ordering::SeqCst).is_null() {
+        let filter = DEV_BROADCAST_DEVICEINTERFACE_W {
+            dbcc_size: mem::size_of::<DEV_BROADCAST_DEVICEINTERFACE_W>() as u32,
+            dbcc_devicetype: DBT_DEVTYP_DEVICEINTERFACE,
+            dbcc_reserved: 0,
+            dbcc_classguid: GUID_DEVINTERFACE_MONITOR,
+            dbcc_name: [0],
+        };
+        let handle = unsafe {
+            RegisterDeviceNotificationW(
+                HANDLE(hwnd.0),
+                ptr::addr_of!(filter).cast(),
+                DEVICE_NOTIFY_WINDOW_HANDLE,
+            )
+        };
+        DEVICE_NOTIFY_HANDLE.store(handle.map_or(ptr::null_mut(), |h| h.0), Ordering::SeqCst);
+    }
+    if !SESSION_NOTIFY_REGISTERED.load(Ordering::SeqCst) {
+        let ok = unsafe { WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_ALL_SESSIONS).is_ok() };
+        SESSION_NOTIFY_REGISTERED.store(ok, Ordering::SeqCst);
+    }
+    notifications_registered()
+}
+
+/// Current `(device, session)` notification registration state.
+fn notifications_registered() -> (bool, bool) {
+    (
+        !DEVICE_NOTIFY_HANDLE.load(Ordering::SeqCst).is_null(),
+        SESSION_NOTIFY_REGISTERED.load(Ordering::SeqCst),
+    )
+}
+
+fn unregister_notifications(hwnd: HWND) {
+    if SESSION_NOTIFY_REGISTERED.swap(false, Ordering::SeqCst) {
+        let _ = unsafe { WTSUnRegisterSessionNotification(hwnd) };
+    }
+    let handle = DEVICE_NOTIFY_HANDLE.swap(ptr::null_mut(), Ordering::SeqCst);
+    if !handle.is_null() {
+        unsafe {
+            let _ = UnregisterDeviceNotification(HDEVNOTIFY(handle));
+        }
+    }
+}
+
+/// Check if a `DBT_DEVICEARRIVAL` or `DBT_DEVICEREMOVECOMPLETE` event is
+/// for a monitor device interface.
 unsafe fn is_monitor_device_event(lparam: LPARAM) -> bool {
     if lparam.0 == 0 {
         return false;
     }
-    let header = lparam.0 as *const DevBroadcastDeviceInterface;
+    let header = lparam.0 as *const DEV_BROADCAST_DEVICEINTERFACE_W;
     (*header).dbcc_devicetype == DBT_DEVTYP_DEVICEINTERFACE
         && (*header).dbcc_classguid == GUID_DEVINTERFACE_MONITOR
 }
 
+/// Device key of the monitor in a device interface broadcast, read from
+/// the NUL-terminated `dbcc_name` that follows the header. `None` when the
+/// name is missing or empty.
+///
+/// # Safety
+/// `lparam` must point to a `DEV_BROADCAST_DEVICEINTERFACE_W` for which
+/// [`is_monitor_device_event`] returned true.
+unsafe fn interface_device_key(lparam: LPARAM) -> Option<String> {
+    let header = lparam.0 as *const DEV_BROADCAST_DEVICEINTERFACE_W;
+    let offset = mem::offset_of!(DEV_BROADCAST_DEVICEINTERFACE_W, dbcc_name);
+    let capacity = ((*header).dbcc_size as usize).checked_sub(offset)? / 2;
+    let name =
+        std::slice::from_raw_parts(ptr::addr_of!((*header).dbcc_name).cast::<u16>(), capacity);
+    let len = name.iter().position(|&c| c == 0).unwrap_or(capacity);
+    let key = lg_monitor::device_key_from_interface_path(&String::from_utf16_lossy(&name[..len]));
+    (!key.is_empty()).then_some(key)
+}
+
 /// Single-threaded debounce worker. Receives event flags from the message
-/// loop via a channel, coalesces rapid events within the stabilize window,
-/// validates with a WMI check, waits for display initialization, then
-/// triggers the profile reapply pipeline.
+/// loop via a channel and drives a [`debounce::Debouncer`]: it blocks on the
+/// channel until the debouncer's deadline, runs the WMI check it asks for,
+/// and triggers the profile reapply pipeline once a batch is ready.
 ///
 /// Uses `recv_timeout` for efficient blocking — zero CPU when idle, no
 /// thread-per-event spawning, fully interruptible on shutdown.
-fn debounce_worker(rx: mpsc::Receiver<u8>, config: Arc<Config>) {
-    while let Ok(flag) = rx.recv() {
-        // Phase 1: Coalesce events within the stabilize window.
-        // Any events arriving during this period are OR'd together.
-        let mut accumulated = flag;
-        let deadline = Instant::now() + Duration::from_millis(config.stabilize_delay_ms);
-        loop {
-            let remaining = deadline.saturating_duration_since(Instant::now());
-            if remaining.is_zero() {
-                break;
+fn debounce_worker(rx: &mpsc::Receiver<DisplayEvent>, config: &Config) {
+    let mut debouncer = debounce::Debouncer::new(
+        Duration::from_millis(config.effective_stabilize_delay_ms()),
+        Duration::from_millis(config.effective_reapply_delay_ms()),
+    )
+    .with_kvm_mode(config.kvm_mode)
+    .with_dock_mode(config.dock_mode);
+    let mut action = debounce::Action::Wait(None);
+    loop {
+        action = match action {
+            debounce::Action::Wait(None) => match rx.recv() {
+                Ok(event) => debouncer.event(event, Instant::now()),
+                Err(_) => debouncer.disconnected(),
+            },
+            debounce::Action::Wait(Some(deadline)) => {
+                match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(event) => debouncer.event(event, Instant::now()),
+                    Err(mpsc::RecvTimeoutError::Timeout) => debouncer.timeout(Instant::now()),
+                    Err(mpsc::RecvTimeoutError::Disconnected) => debouncer.disconnected(),
+                }
             }
-            match rx.recv_timeout(remaining) {
-                Ok(f) => accumulated |= f,
-                Err(mpsc::RecvTimeoutError::Timeout) => break,
-                Err(mpsc::RecvTimeoutError::Disconnected) => return, // Shutdown
+            debounce::Action::Settled(flags) => {
+                log_settled(flags);
+                if flags & EVENT_HOTKEY_BRIGHTNESS != 0 {
+                    handle_hotkey_brightness(config);
+                }
+                log_init_wait(debouncer.settled(Instant::now()), config)
             }
-        }
+            debounce::Action::Validate(_) => {
+                let present = batch_has_monitors(config, debouncer.targets());
+                let next = debouncer.validated(present, Instant::now());
+                if debouncer.confirming() {
+                    info!(
+                        "Dock mode: monitors found, checking again in {}ms",
+                        debounce::DOCK_CONFIRM_INTERVAL.as_millis()
+                    );
+                    next
+                } else {
+                    log_init_wait(next, config)
+                }
+            }
+            debounce::Action::Reapply(flags) => {
+                let targets = debouncer.targets().clone();
+                if !reapply_batch(rx, config, flags, targets, debouncer.kvm_switch()) {
+                    break;
+                }
+                // Events that queued during the reapply are mostly covered
+                // by it; the debouncer keeps the rest for the next batch.
+                for event in rx.try_iter() {
+                    debouncer.event(event, Instant::now());
+                }
+                debouncer.reapplied(Instant::now())
+            }
+            debounce::Action::Stop => break,
+        };
+    }
 
-        let has_device = accumulated & EVENT_MASK_DEVICE != 0;
-        let has_session = accumulated & EVENT_MASK_SESSION != 0;
-        let has_poll = accumulated & EVENT_AUTOMATION_POLL != 0;
+    info!("Debounce worker stopped");
+}
 
-        if !has_device && !has_session && !has_poll {
-            continue;
-        }
+/// Log and record a batch the debouncer has settled on.
+fn log_settled(flags: u32) {
+    let fields = [
+        ("device", EVENT_MASK_DEVICE),
+        ("session", EVENT_MASK_SESSION),
+        ("poll", EVENT_AUTOMATION_POLL),
+        ("fullscreen", EVENT_FULLSCREEN_EXIT),
+        ("app_override", EVENT_APP_OVERRIDE),
+        ("schedule", EVENT_SCHEDULE),
+        ("hotkey", EVENT_HOTKEY_REAPPLY | EVENT_HOTKEY_BRIGHTNESS),
+        ("remote", EVENT_REMOTE_REAPPLY),
+        ("tamper", EVENT_PROFILE_TAMPERED),
+        ("gamma_reset", EVENT_GAMMA_RESET),
+        ("watchdog", EVENT_WATCHDOG),
+        ("display_on", EVENT_DISPLAY_ON),
+        ("calibration", EVENT_CALIBRATION_UPDATED),
+    ]
+    .map(|(name, mask)| format!("{}={}", name, flags & mask != 0));
+    info!(
+        "Debounce settled: flags=0b{:017b}, {}",
+        flags,
+        fields.join(", ")
+    );
+    app_state::append_diagnostic_event(
+        "service",
+        "INFO",
+        "event_debounce",
+        &format!("flags=0b{:017b} {}", flags, fields.join(" ")),
+    );
+}
 
+/// Post-debounce check for a device-only batch, before the long wait:
+/// is there a matching monitor to reapply to? A plug-in is checked against
+/// the monitors that arrived.
+fn batch_has_monitors(config: &Config, targets: &Targets) -> bool {
+    let devices = match find_matching_monitors_for_config(config) {
+        Ok(devices) => devices,
+        Err(e) => {
+            warn!(
+                "Post-debounce monitor check failed: {}, proceeding anyway",
+                e
+            );
+            return true;
+        }
+    };
+    let Targets::Only(keys) = targets else {
+        if devices.is_empty() {
+            info!("Post-debounce: no matching monitors found, skipping");
+            return false;
+        }
         info!(
-            "Debounce settled: flags=0b{:08b}, device={}, session={}, poll={}",
-            accumulated, has_device, has_session, has_poll
+            "Post-debounce: {} matching monitor(s) confirmed",
+            devices.len()
         );
-        app_state::append_diagnostic_event(
-            "service",
-            "INFO",
-            "event_debounce",
-            &format!(
-                "flags=0b{:08b} device={} session={} poll={}",
-                accumulated, has_device, has_session, has_poll
-            ),
+        return true;
+    };
+    if devices.iter().any(|d| targets.includes(&d.device_key)) {
+        info!(
+            "Post-debounce: arrived monitor(s) match, targeting {}",
+            targets.label()
+        );
+        return true;
+    }
+    // WMI can list a monitor a moment after it arrives. Only skip when it
+    // already knows every arrived monitor and none of them match.
+    let known = keys
+        .iter()
+        .all(|key| matches!(lg_monitor::find_monitor_by_device_key(key), Ok(Some(_))));
+    if known {
+        info!(
+            "Post-debounce: arrived monitor(s) {} don't match \"{}\", skipping",
+            targets.label(),
+            config.monitor_match
+        );
+        false
+    } else {
+        info!("Post-debounce: arrived monitor not listed by WMI yet, proceeding");
+        true
+    }
+}
+
+/// Pass `action` through, noting when it starts the display initialization wait.
+fn log_init_wait(action: debounce::Action, config: &Config) -> debounce::Action {
+    if let debounce::Action::Wait(Some(_)) = action {
+        info!(
+            "Display settled, waiting {}ms for full initialization",
+            config.effective_reapply_delay_ms()
         );
+    }
+    action
+}
 
-        // Phase 2: For device-only events, validate monitors exist before the long wait
-        if has_device && !has_session {
-            match find_matching_monitors_for_config(&config) {
-                Ok(devices) if devices.is_empty() => {
-                    info!("Post-debounce: no matching monitors found, skipping");
-                    continue;
-                }
-                Ok(devices) => {
-                    info!(
-                        "Post-debounce: {} matching monitor(s) confirmed",
-                        devices.len()
-                    );
-                }
-                Err(e) => {
-                    warn!(
-                        "Post-debounce monitor check failed: {}, proceeding anyway",
-                        e
-                    );
-                }
-            }
+/// Name recorded for a reapply caused by `flags`, most specific first.
+fn reapply_trigger(flags: u32, driver_updated: bool) -> &'static str {
+    if driver_updated {
+        "driver_update"
+    } else if flags & (EVENT_MASK_DEVICE | EVENT_MASK_SESSION) != 0 {
+        "event"
+    } else if flags & EVENT_REMOTE_REAPPLY != 0 {
+        "remote"
+    } else if flags & EVENT_HOTKEY_REAPPLY != 0 {
+        "hotkey"
+    } else if flags & EVENT_PROFILE_TAMPERED != 0 {
+        "tamper"
+    } else if flags & EVENT_CALIBRATION_UPDATED != 0 {
+        "calibration_updated"
+    } else if flags & EVENT_GAMMA_RESET != 0 {
+        "gamma_reset"
+    } else if flags & EVENT_WATCHDOG != 0 {
+        "watchdog"
+    } else if flags & EVENT_DISPLAY_ON != 0 {
+        "display_on"
+    } else if flags & EVENT_FULLSCREEN_EXIT != 0 {
+        "fullscreen_exit"
+    } else if flags & EVENT_APP_OVERRIDE != 0 {
+        "app_override_end"
+    } else if flags & EVENT_SCHEDULE != 0 {
+        "schedule"
+    } else {
+        "automation_poll"
+    }
+}
+
+/// How a reapply `trigger` reads in a toast's `{trigger}`, e.g. "Reapplied
+/// after unlock". Session and device events are told apart by `flags`.
+fn toast_trigger(trigger: &str, flags: u32) -> &'static str {
+    match trigger {
+        "event" if flags & EVENT_SESSION_UNLOCK != 0 => "unlock",
+        "event" if flags & EVENT_SESSION_LOGON != 0 => "sign-in",
+        "event" if flags & EVENT_CONSOLE_CONNECT != 0 => "session switch",
+        "event" => "monitor change",
+        "startup" => "service start",
+        "driver_update" => "driver update",
+        "kvm_switch" => "KVM switch",
+        "remote" => "quick reapply",
+        "hotkey" => "hotkey",
+        "tamper" => "profile change by another app",
+        "calibration_updated" => "calibration update",
+        "gamma_reset" => "gamma reset",
+        "watchdog" => "watchdog check",
+        "display_on" => "display wake",
+        "fullscreen_exit" => "fullscreen exit",
+        "app_override_end" => "app override end",
+        "schedule" => "schedule",
+        _ => "automation",
+    }
+}
+
+/// Run the reapply pipeline for a settled batch. Returns `false` when the
+/// channel closed while the reapply was held (shutdown).
+fn reapply_batch(
+    rx: &mpsc::Receiver<DisplayEvent>,
+    config: &Config,
+    mut accumulated: u32,
+    mut targets: Targets,
+    kvm_switch: bool,
+) -> bool {
+    // A KVM switch is the same monitor coming back: reapply right away,
+    // without the driver check or a toast on every switch.
+    if kvm_switch {
+        info!(
+            "KVM switch detected ({}), reapplying quietly",
+            targets.label()
+        );
+        let quiet = Config {
+            toast_enabled: false,
+            osd_enabled: false,
+            ..config.clone()
+        };
+        if let Some(active) = app_override::active_override() {
+            handle_app_override(&quiet, &active);
+        } else {
+            handle_profile_reapply(&quiet, "kvm_switch", accumulated, &targets);
         }
+        return true;
+    }
 
-        // Phase 3: Post-settle delay for display initialization (interruptible)
-        if (has_device || has_session) && config.reapply_delay_ms > 0 {
-            info!(
-                "Display settled, waiting {}ms for full initialization",
-                config.reapply_delay_ms
-            );
-            match rx.recv_timeout(Duration::from_millis(config.reapply_delay_ms)) {
-                Ok(_) => {
-                    // New events during delay — drain and proceed with reapply
-                    while rx.try_recv().is_ok() {}
-                }
-                Err(mpsc::RecvTimeoutError::Timeout) => {} // Expected — delay completed
-                Err(mpsc::RecvTimeoutError::Disconnected) => return, // Shutdown
+    // A device change may be a GPU driver update; that reapply reassociates
+    // every monitor and is never held back.
+    let driver_updated = accumulated & EVENT_MASK_DEVICE != 0 && driver_update::detect(config);
+
+    // Hold background-only reapplies while the user is busy in a fullscreen
+    // app (interruptible; a user request releases it).
+    if config.defer_reapply && !driver_updated && accumulated & !EVENT_MASK_DEFERRABLE == 0 {
+        match defer::wait_while_busy(rx, config) {
+            // Whatever arrived meanwhile isn't tracked per monitor.
+            Some(0) => {}
+            Some(flags) => {
+                accumulated |= flags;
+                targets = Targets::All;
             }
+            None => return false,
         }
+    }
 
-        // Phase 4: Apply the profile
-        let trigger = if has_poll && !has_device && !has_session {
-            "automation_poll"
-        } else {
-            "event"
-        };
-        handle_profile_reapply(&config, trigger, accumulated);
-
-        // Drain any events that queued during reapply to avoid redundant cycles
-        while rx.try_recv().is_ok() {}
+    // Nothing to fix while the display is off; hold a single reapply and
+    // run it once the display turns back on.
+    if display_power::display_off() {
+        display_power::hold(&event_names(accumulated).join(","));
+        return true;
     }
 
-    info!("Debounce worker stopped");
+    // While an app override is in effect it wins over every other trigger,
+    // so a poll or replug doesn't swap the dimming-fix profile back in
+    // under the focused app.
+    if let Some(active) = app_override::active_override() {
+        handle_app_override(config, &active);
+        if accumulated & EVENT_CALIBRATION_UPDATED != 0 {
+            profile_watch::notify(config);
+        }
+    } else {
+        let trigger = reapply_trigger(accumulated, driver_updated);
+        handle_profile_reapply(config, trigger, accumulated, &targets);
+    }
+    true
 }
 
 /// Window procedure — handles device change and session change messages.
+/// A panic while handling a message is logged and the message falls back
+/// to the default handling.
 unsafe extern "system" fn wnd_proc(
     hwnd: HWND,
     msg: u32,
     wparam: WPARAM,
     lparam: LPARAM,
 ) -> LRESULT {
+    crash::guard("window procedure", || {
+        handle_window_message(hwnd, msg, wparam, lparam)
+    })
+    .unwrap_or_else(|| DefWindowProcW(hwnd, msg, wparam, lparam))
+}
+
+unsafe fn handle_window_message(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     match msg {
         WM_DEVICECHANGE => {
             let event = wparam.0 as u32;
-            let flag = match event {
-                DBT_DEVICEARRIVAL if is_monitor_device_event(lparam) => Some(EVENT_DEVICE_ARRIVAL),
-                DBT_DEVNODES_CHANGED => Some(EVENT_DEVNODES_CHANGED),
+            let display_event = match event {
+                DBT_DEVICEARRIVAL if is_monitor_device_event(lparam) => {
+                    Some(DisplayEvent::DeviceArrival {
+                        device_key: interface_device_key(lparam),
+                    })
+                }
+                DBT_DEVICEREMOVECOMPLETE if is_monitor_device_event(lparam) => {
+                    Some(DisplayEvent::DeviceRemoval {
+                        device_key: interface_device_key(lparam),
+                    })
+                }
+                DBT_DEVNODES_CHANGED => Some(DisplayEvent::DevNodesChanged),
                 _ => None,
             };
-            if let Some(f) = flag {
-                info!("Device change detected (event=0x{:04X})", event);
+            if let Some(e) = display_event {
+                info!("Device change detected (event=0x{:04X}): {:?}", event, e);
+                // A replugged monitor can come back under the same HMONITOR
+                // with its old physical handle dead.
+                lg_monitor::ddc::invalidate_handle_cache();
                 EVENT_SENDER.with(|s| {
                     if let Some(tx) = s.borrow().as_ref() {
-                        let _ = tx.send(f);
+                        let _ = tx.send(e);
                     }
                 });
             }
@@ -1205,23 +1750,46 @@ unsafe extern "system" fn wnd_proc(
 
         WM_WTSSESSION_CHANGE => {
             let session_event = wparam.0 as u32;
-            let flag = match session_event {
-                WTS_CONSOLE_CONNECT => Some(EVENT_CONSOLE_CONNECT),
-                WTS_SESSION_LOGON => Some(EVENT_SESSION_LOGON),
-                WTS_SESSION_UNLOCK => Some(EVENT_SESSION_UNLOCK),
+            let display_event = match session_event {
+                WTS_CONSOLE_CONNECT => Some(DisplayEvent::ConsoleConnect),
+                WTS_SESSION_LOGON => Some(DisplayEvent::SessionLogon),
+                WTS_SESSION_UNLOCK => Some(DisplayEvent::SessionUnlock),
                 _ => None,
             };
-            if let Some(f) = flag {
+            if let Some(e) = display_event {
                 info!("Session change detected (event=0x{:04X})", session_event);
                 EVENT_SENDER.with(|s| {
                     if let Some(tx) = s.borrow().as_ref() {
-                        let _ = tx.send(f);
+                        let _ = tx.send(e);
                     }
                 });
             }
             LRESULT(0)
         }
 
+        hotkeys::WM_HOTKEY => {
+            hotkeys::on_hotkey(wparam.0 as i32);
+            LRESULT(0)
+        }
+
+        watchdog::WM_WATCHDOG_PING => {
+            watchdog::on_ping(hwnd, wparam.0 as u64);
+            LRESULT(0)
+        }
+
+        display_power::WM_POWERBROADCAST => {
+            lid::on_power_broadcast(wparam, lparam);
+            if display_power::on_power_broadcast(wparam, lparam) {
+                info!("Display turned back on, running the held reapply");
+                EVENT_SENDER.with(|s| {
+                    if let Some(tx) = s.borrow().as_ref() {
+                        let _ = tx.send(DisplayEvent::DisplayOn);
+                    }
+                });
+            }
+            LRESULT(1)
+        }
+
         WM_QUIT_SERVICE => {
             PostQuitMessage(0);
             LRESULT(0)
@@ -1231,34 +1799,465 @@ unsafe extern "system" fn wnd_proc(
     }
 }
 
-/// Detect matching monitors and reapply the profile, then refresh and toast.
-fn handle_profile_reapply(config: &Config, trigger: &str, event_flags: u8) {
-    let started = Instant::now();
-    let mut effective_cfg = config.clone();
-    let decision = resolve_automation_decision(event_flags);
-    let forced_preset = decision.preset.clone().filter(|p| !p.trim().is_empty());
-    let hdr_mode_active = detect_hdr_mode();
-
-    if let Some(ref tuning) = decision.tuning_preset {
-        effective_cfg.icc_tuning_preset = tuning.clone();
+/// Apply the pending hotkey brightness delta and record the outcome.
+fn handle_hotkey_brightness(config: &Config) {
+    let delta = hotkeys::take_brightness_delta();
+    if delta == 0 {
+        return;
     }
-    if let Some(luminance) = decision.luminance_cd_m2 {
-        effective_cfg.icc_luminance_cd_m2 = lg_profile::sanitize_dynamic_luminance_cd_m2(luminance);
+    match hotkeys::adjust_brightness(config, hotkeys::BrightnessChange::Relative(delta)) {
+        Ok(changed) => {
+            for (monitor, value) in &changed {
+                info!("Hotkey brightness: {} -> {}", monitor, value);
+                app_state::append_diagnostic_event(
+                    "service",
+                    "INFO",
+                    "hotkey_brightness",
+                    &format!("monitor={} delta={} value={}", monitor, delta, value),
+                );
+            }
+        }
+        Err(e) => {
+            warn!("Hotkey brightness step failed: {}", e);
+            notify_ddc_failure(config, e.as_ref());
+            app_state::append_diagnostic_event(
+                "service",
+                "WARN",
+                "hotkey_brightness_error",
+                &format!("delta={} error={}", delta, e),
+            );
+        }
     }
-    let active_preset = forced_preset
-        .clone()
-        .unwrap_or_else(|| effective_preset_for_mode(&effective_cfg, hdr_mode_active));
-    let sdr_preset = forced_preset
-        .clone()
-        .unwrap_or_else(|| effective_preset_for_mode(&effective_cfg, false));
-    let hdr_preset = forced_preset
-        .clone()
-        .unwrap_or_else(|| effective_preset_for_mode(&effective_cfg, true));
+}
 
-    if !decision.details.trim().is_empty() {
-        app_state::append_diagnostic_event(
-            "service",
-            "INFO",
+/// Toast the guidance for a DDC failure that points at the monitor's
+/// DDC/CI setting or the connection. Shown once per run; the log keeps
+/// every occurrence.
+fn notify_ddc_failure(config: &Config, err: &(dyn std::error::Error + 'static)) {
+    let Some(guidance) = lg_monitor::ddc::error_kind(err)
+        .filter(|kind| kind.is_link_problem())
+        .and_then(|kind| kind.guidance())
+    else {
+        return;
+    };
+    if DDC_GUIDANCE_SHOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    lg_notify::show_reapply_toast_quiet_aware(
+        config.toast_enabled,
+        &config.toast_title,
+        &format!("Brightness control failed: {}", guidance),
+        lg_notify::QuietHours::parse(&config.toast_quiet_hours),
+        config.verbose,
+    );
+}
+
+/// Apply a per-application override and record the outcome.
+fn handle_app_override(config: &Config, active: &app_override::ActiveOverride) {
+    let _applying = tamper::ApplyGuard::begin();
+    match app_override::apply(config, active) {
+        Ok(count) => {
+            info!(
+                "App override applied: {} -> {} on {} monitor(s)",
+                active.process, active.profile, count
+            );
+            app_state::append_diagnostic_event(
+                "service",
+                "INFO",
+                "app_override_apply",
+                &format!(
+                    "app={} profile={} monitors={}",
+                    active.process, active.profile, count
+                ),
+            );
+        }
+        Err(e) => {
+            error!("App override for {} failed: {}", active.process, e);
+            app_state::append_diagnostic_event(
+                "service",
+                "ERROR",
+                "app_override_error",
+                &format!("app={} error={}", active.process, e),
+            );
+        }
+    }
+}
+
+/// Detect matching monitors and reapply the profile, then refresh and toast.
+/// Stamp the outcome on a service reapply report and write it.
+fn write_service_install_report(mut report: InstallReport, failed: usize) {
+    report.success = failed == 0;
+    if failed > 0 {
+        report.error = Some(format!(
+            "{} of {} monitor(s) failed",
+            failed,
+            report.devices.len()
+        ));
+    }
+    match report::write_install_report(&report) {
+        Ok(path) => info!("Install report written to {}", path.display()),
+        Err(e) => warn!("Could not write install report: {}", e),
+    }
+}
+
+/// Put matched displays back to their recorded resolution and refresh
+/// rate (see [`app_state::display_mode_action`]), recording displays seen
+/// for the first time. Failures are logged and never fail the reapply.
+fn guard_display_modes(cfg: &Config) {
+    let monitors = match find_matching_monitors_for_config(cfg) {
+        Ok(monitors) => monitors,
+        Err(e) => {
+            warn!("Display mode guard skipped: {}", e);
+            return;
+        }
+    };
+    let mut state = app_state::load_display_mode_state();
+    let mut dirty = false;
+    for mon in &monitors {
+        let current = match lg_monitor::display_mode(&mon.device_key) {
+            Ok(Some(mode)) => mode,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!("Could not read display mode of {}: {}", mon.name, e);
+                continue;
+            }
+        };
+        let record = app_state::DisplayModeRecord {
+            device_key: mon.device_key.clone(),
+            width: current.width,
+            height: current.height,
+            refresh_hz: current.refresh_hz,
+            pinned: false,
+        };
+        let expected = state.expected(&mon.device_key).cloned();
+        match app_state::display_mode_action(expected.as_ref(), &record) {
+            app_state::DisplayModeAction::Keep => {}
+            app_state::DisplayModeAction::Record => {
+                info!("Display mode of {} recorded: {}", mon.name, current);
+                state.record(record);
+                dirty = true;
+            }
+            app_state::DisplayModeAction::Restore => {
+                let Some(expected) = expected else { continue };
+                let target = lg_monitor::DisplayMode {
+                    width: expected.width,
+                    height: expected.height,
+                    refresh_hz: expected.refresh_hz,
+                };
+                match lg_monitor::set_display_mode(&mon.device_key, target) {
+                    Ok(_) => {
+                        info!(
+                            "Display mode of {} restored: {} -> {}",
+                            mon.name, current, target
+                        );
+                        app_state::append_diagnostic_event(
+                            "service",
+                            "INFO",
+                            "display_mode_restore",
+                            &format!("{}: {} -> {}", mon.device_key, current, target),
+                        );
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Display mode restore failed on {}: {} (non-fatal)",
+                            mon.name, e
+                        );
+                        app_state::append_diagnostic_event(
+                            "service",
+                            "WARN",
+                            "display_mode_warning",
+                            &format!("{}: {}", mon.device_key, e),
+                        );
+                    }
+                }
+            }
+        }
+    }
+    if dirty {
+        if let Err(e) = app_state::save_display_mode_state(&state) {
+            warn!("Could not save display modes: {}", e);
+        }
+    }
+}
+
+/// Set the SDR content brightness on every matching monitor with HDR on.
+/// Failures are logged and never fail the reapply.
+fn apply_sdr_white_level(nits: u32, device_keys: &[String]) {
+    for key in device_keys {
+        match lg_monitor::set_sdr_white_level(key, nits) {
+            Ok(true) => info!("SDR white level set to {} nits on {}", nits, key),
+            Ok(false) => {}
+            Err(e) => {
+                warn!("SDR white level set failed on {}: {} (non-fatal)", key, e);
+                app_state::append_diagnostic_event(
+                    "service",
+                    "WARN",
+                    "sdr_white_warning",
+                    &format!("{}: {}", key, e),
+                );
+            }
+        }
+    }
+}
+
+/// Service side of the [`ReapplyPipeline`]: matches with the configured
+/// pattern, narrows to the batch's targets, and records every monitor in
+/// the install report and the tamper/gamma baselines.
+struct ServiceSteps<'a> {
+    cfg: &'a Config,
+    targets: &'a Targets,
+    sdr_preset: &'a str,
+    hdr_preset: &'a str,
+    /// Device keys of every matching monitor, reached or not.
+    connected: Vec<String>,
+    report: InstallReport,
+    toast_body: String,
+    /// Toast placeholder values.
+    tally: ToastTally,
+}
+
+impl ReapplySteps for ServiceSteps<'_> {
+    type Monitor = lg_monitor::MatchedMonitor;
+
+    fn ensure_shared_profiles(&mut self) -> Result<Option<ModeProfiles>, Box<dyn Error>> {
+        if self.cfg.icc_per_monitor_profiles {
+            return Ok(None);
+        }
+        let (sdr, hdr) = lg_profile::ensure_mode_profiles_installed_tuned(
+            &lg_profile::color_directory(),
+            self.sdr_preset,
+            self.hdr_preset,
+            &self.cfg.profile_name,
+            self.cfg.icc_gamma,
+            self.cfg.icc_luminance_cd_m2,
+            self.cfg.icc_generate_specialized_profiles,
+            tuning_from_config(self.cfg),
+        )?;
+        Ok(Some(ModeProfiles { sdr, hdr }))
+    }
+
+    fn is_installed(&self, path: &std::path::Path) -> bool {
+        lg_profile::is_profile_installed(path)
+    }
+
+    fn find_monitors(&mut self) -> Result<Vec<lg_monitor::MatchedMonitor>, Box<dyn Error>> {
+        let devices = find_matching_monitors_for_config(self.cfg)?;
+        self.connected = devices.iter().map(|d| d.device_key.clone()).collect();
+        // A plug-in only needs the monitor that arrived; fall back to every
+        // match when it isn't among them.
+        let reach = match self.targets {
+            Targets::Only(_) if devices.iter().any(|d| self.targets.includes(&d.device_key)) => {
+                info!(
+                    "Reapplying to the arrived monitor(s) only: {}",
+                    self.targets.label()
+                );
+                self.targets
+            }
+            Targets::Only(_) => {
+                info!(
+                    "Arrived monitor(s) {} not found, reapplying to every match",
+                    self.targets.label()
+                );
+                &Targets::All
+            }
+            Targets::All => self.targets,
+        };
+        Ok(devices
+            .into_iter()
+            .filter(|d| reach.includes(&d.device_key))
+            .collect())
+    }
+
+    fn inherit_associations(&mut self, device: &lg_monitor::MatchedMonitor) {
+        match lg_profile::inherit_previous_device_associations(&device.device_key, &self.connected)
+        {
+            Ok(Some(previous)) => app_state::append_diagnostic_event(
+                "service",
+                "INFO",
+                "associations_inherited",
+                &format!(
+                    "device={} from={} profiles={}",
+                    device.device_key,
+                    previous.device_key,
+                    previous.profiles.join(",")
+                ),
+            ),
+            Ok(None) => {}
+            Err(e) => warn!(
+                "Could not check previous associations for {}: {}",
+                device.device_key, e
+            ),
+        }
+    }
+
+    fn ensure_monitor_profiles(
+        &mut self,
+        device: &lg_monitor::MatchedMonitor,
+    ) -> Result<ModeProfiles, Box<dyn Error>> {
+        let (sdr, hdr) = lg_profile::ensure_mode_profiles_installed_tuned_for_monitor(
+            &lg_profile::color_directory(),
+            self.sdr_preset,
+            self.hdr_preset,
+            &self.cfg.profile_name,
+            self.cfg.icc_gamma,
+            self.cfg.icc_luminance_cd_m2,
+            self.cfg.icc_generate_specialized_profiles,
+            tuning_from_config(self.cfg),
+            &monitor_identity_from_match(device),
+        )?;
+        Ok(ModeProfiles { sdr, hdr })
+    }
+
+    fn associate(
+        &mut self,
+        device: &lg_monitor::MatchedMonitor,
+        active: &std::path::Path,
+        sdr: &std::path::Path,
+        hdr: Option<&std::path::Path>,
+        per_user: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        info!(
+            "Reapplying mode profiles for: {} ({}) active={} sdr={} hdr={}",
+            device.name,
+            device.device_key,
+            active.display(),
+            sdr.display(),
+            hdr.map(|p| p.display().to_string())
+                .unwrap_or_else(|| "(skipped)".to_string())
+        );
+        lg_profile::reapply_profile_with_mode_associations(
+            &device.device_key,
+            active,
+            sdr,
+            hdr,
+            self.cfg.toggle_delay_ms,
+            per_user,
+        )
+    }
+
+    fn set_generic_default(
+        &mut self,
+        device: &lg_monitor::MatchedMonitor,
+        active: &std::path::Path,
+        per_user: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        lg_profile::set_generic_default(&device.device_key, active, per_user)
+    }
+
+    fn refresh(&mut self) {
+        // Keep periodic/event-driven reapply refresh non-disruptive.
+        // Hard refresh is escalated internally only when verification fails.
+        refresh::refresh_display(self.cfg);
+        lg_profile::trigger_calibration_loader(self.cfg.refresh_calibration_loader);
+    }
+
+    fn set_ddc_brightness(&mut self, level: u32) -> Result<usize, Box<dyn Error>> {
+        lg_monitor::ddc::set_brightness_all(level)
+    }
+
+    fn toast(&mut self) {
+        let values = self.tally.values();
+        lg_notify::show_reapply_toast_quiet_aware(
+            self.cfg.toast_enabled,
+            &toast_template::render(&self.cfg.toast_title, &values),
+            &toast_template::render(&self.toast_body, &values),
+            lg_notify::QuietHours::parse(&self.cfg.toast_quiet_hours),
+            self.cfg.verbose,
+        );
+        lg_notify::show_reapply_osd(
+            self.cfg.osd_enabled,
+            &self.cfg.osd_position,
+            self.cfg.osd_duration_ms,
+        );
+    }
+
+    fn monitor_done(&mut self, device: &lg_monitor::MatchedMonitor, result: &MonitorResult<'_>) {
+        let path_text =
+            |p: Option<&std::path::Path>| p.map(|p| p.display().to_string()).unwrap_or_default();
+        if self.cfg.install_report {
+            if let Some(profiles) = result.profiles {
+                self.report.add_profile("sdr", &profiles.sdr);
+                self.report.add_profile("hdr", &profiles.hdr);
+            }
+        }
+        self.report.devices.push(DeviceEntry {
+            name: device.name.clone(),
+            device_key: device.device_key.clone(),
+            active_profile: path_text(result.active),
+            sdr_profile: path_text(result.profiles.map(|p| p.sdr.as_path())),
+            hdr_profile: path_text(result.profiles.map(|p| p.hdr.as_path())),
+            steps: result.steps.clone(),
+        });
+
+        match (result.error, result.profiles) {
+            (Some(e), None) => {
+                error!(
+                    "Failed to generate monitor-scoped ICC for {}: {}",
+                    device.name, e
+                );
+                app_state::append_diagnostic_event(
+                    "service",
+                    "ERROR",
+                    "apply_error",
+                    &format!(
+                        "monitor-scoped profile generation failed for {}: {}",
+                        device.name, e
+                    ),
+                );
+            }
+            (Some(e), Some(_)) => {
+                error!("Failed to reapply for {}: {}", device.name, e);
+                app_state::append_diagnostic_event(
+                    "service",
+                    "ERROR",
+                    "apply_error",
+                    &format!("reapply failed for {}: {}", device.name, e),
+                );
+            }
+            (None, Some(profiles)) => {
+                // The SDR profile is what the display default query reports.
+                tamper::record_applied(&device.device_key, &profiles.sdr);
+                gamma_watch::record_baseline(self.cfg, &device.device_key);
+                if let Some(active) = result.active {
+                    self.tally.record(&device.name, active);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+fn handle_profile_reapply(config: &Config, trigger: &str, event_flags: u32, targets: &Targets) {
+    let started = Instant::now();
+    let started_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let _applying = tamper::ApplyGuard::begin();
+    let mut effective_cfg = config.clone();
+    let decision = resolve_automation_decision(event_flags);
+    let forced_preset = ipc::preset_override()
+        .or_else(|| decision.preset.clone())
+        .filter(|p| !p.trim().is_empty());
+    let hdr_mode_active = detect_hdr_mode();
+
+    if let Some(ref tuning) = decision.tuning_preset {
+        effective_cfg.icc_tuning_preset = tuning.clone();
+    }
+    if let Some(luminance) = decision.luminance_cd_m2 {
+        effective_cfg.icc_luminance_cd_m2 = lg_profile::sanitize_dynamic_luminance_cd_m2(luminance);
+    }
+    let active_preset = forced_preset
+        .clone()
+        .unwrap_or_else(|| effective_preset_for_mode(&effective_cfg, hdr_mode_active));
+    let sdr_preset = forced_preset
+        .clone()
+        .unwrap_or_else(|| effective_preset_for_mode(&effective_cfg, false));
+    let hdr_preset = forced_preset
+        .clone()
+        .unwrap_or_else(|| effective_preset_for_mode(&effective_cfg, true));
+
+    if !decision.details.trim().is_empty() {
+        app_state::append_diagnostic_event(
+            "service",
+            "INFO",
             "automation_decision",
             &format!(
                 "trigger={} source={} preset={} details={}",
@@ -1299,7 +2298,7 @@ fn handle_profile_reapply(config: &Config, trigger: &str, event_flags: u8) {
         "INFO",
         "apply_begin",
         &format!(
-            "trigger={} pattern=\"{}\" mode={} preset={} source={}",
+            "trigger={} pattern=\"{}\" mode={} preset={} source={} targets={}",
             trigger,
             effective_cfg.monitor_match,
             if effective_cfg.monitor_match_regex {
@@ -1308,13 +2307,17 @@ fn handle_profile_reapply(config: &Config, trigger: &str, event_flags: u8) {
                 "substring"
             },
             active_preset,
-            decision.source
+            decision.source,
+            targets.label()
         ),
     );
 
+    let mut skipped: Option<String> = None;
+    let mut history_devices: Vec<DeviceEntry> = Vec::new();
     let success = (|| -> bool {
         if effective_cfg.monitor_match.is_empty() {
             warn!("Monitor match pattern is empty, skipping reapply");
+            skipped = Some("monitor match pattern is empty".to_string());
             app_state::append_diagnostic_event(
                 "service",
                 "WARN",
@@ -1329,6 +2332,7 @@ fn handle_profile_reapply(config: &Config, trigger: &str, event_flags: u8) {
         ) && effective_cfg.profile_name.trim().is_empty()
         {
             warn!("Profile name is empty, skipping reapply");
+            skipped = Some("profile_name is empty".to_string());
             app_state::append_diagnostic_event(
                 "service",
                 "WARN",
@@ -1337,211 +2341,144 @@ fn handle_profile_reapply(config: &Config, trigger: &str, event_flags: u8) {
             );
             return false;
         }
+        if let Some(reason) = display_gate_skip(&effective_cfg) {
+            info!("Reapply skipped: {}", reason);
+            app_state::append_diagnostic_event("service", "INFO", "apply_skip", &reason);
+            skipped = Some(reason);
+            return false;
+        }
 
-        let color_dir = lg_profile::color_directory();
-        let shared_mode_paths = if effective_cfg.icc_per_monitor_profiles {
-            None
+        // Always collected for `service history`; only written to disk with
+        // `install_report` on.
+        let mut report = InstallReport::new("apply", "service");
+        report.color_directory = lg_profile::color_directory().display().to_string();
+        report.association_api = lg_profile::wcs_api_path().label().to_string();
+        report.hdr_mode_active = hdr_mode_active;
+        let toast_body = if trigger == "schedule" {
+            day_night::toast_body(
+                lg_core::schedule::current_period(&effective_cfg),
+                &active_preset,
+            )
         } else {
-            match lg_profile::ensure_mode_profiles_installed_tuned(
-                &color_dir,
-                &sdr_preset,
-                &hdr_preset,
-                &effective_cfg.profile_name,
-                effective_cfg.icc_gamma,
-                effective_cfg.icc_luminance_cd_m2,
-                effective_cfg.icc_generate_specialized_profiles,
-                tuning_from_config(&effective_cfg),
-            ) {
-                Ok((sdr_path, hdr_path)) => {
-                    if !lg_profile::is_profile_installed(&sdr_path)
-                        || !lg_profile::is_profile_installed(&hdr_path)
-                    {
-                        warn!(
-                            "ICC mode profile not found (sdr={}, hdr={}), skipping reapply",
-                            sdr_path.display(),
-                            hdr_path.display()
-                        );
-                        return false;
-                    }
-                    Some((sdr_path, hdr_path))
-                }
-                Err(e) => {
-                    error!("Failed to generate/install active ICC profile: {}", e);
-                    app_state::append_diagnostic_event(
-                        "service",
-                        "ERROR",
-                        "apply_error",
-                        &format!("failed to generate active profile: {}", e),
-                    );
-                    return false;
-                }
+            lg_core::i18n::toast_body(&effective_cfg).to_string()
+        };
+        if effective_cfg.display_mode_guard {
+            // Before the profile: a mode switch resets the gamma ramp.
+            guard_display_modes(&effective_cfg);
+        }
+        let mut steps = ServiceSteps {
+            cfg: &effective_cfg,
+            targets,
+            sdr_preset: &sdr_preset,
+            hdr_preset: &hdr_preset,
+            connected: Vec::new(),
+            report,
+            toast_body,
+            tally: ToastTally::new(toast_trigger(trigger, event_flags), started),
+        };
+        let pipeline = ReapplyPipeline::new(ReapplyOptions {
+            per_user: false,
+            hdr: effective_cfg.icc_hdr_association,
+            hdr_active: hdr_mode_active,
+            generic_default: false,
+            // Skipped silently without dxva2 (logged once when resolved).
+            ddc_brightness: desired_ddc_brightness.filter(|_| lg_monitor::ddc::is_supported()),
+            toast: effective_cfg.toast_enabled || effective_cfg.osd_enabled,
+        });
+        let outcome = match pipeline.run(&mut steps) {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                error!("Profile reapply failed: {}", e);
+                skipped = Some(e.to_string());
+                app_state::append_diagnostic_event(
+                    "service",
+                    "ERROR",
+                    "apply_error",
+                    &e.to_string(),
+                );
+                return false;
             }
         };
+        if outcome.found == 0 {
+            info!("No matching monitors found, skipping");
+            skipped = Some("no matching monitors".to_string());
+            app_state::append_diagnostic_event(
+                "service",
+                "WARN",
+                "apply_skip",
+                "no matching monitors",
+            );
+            return false;
+        }
+
+        history_devices = steps.report.devices.clone();
+        if effective_cfg.install_report {
+            write_service_install_report(steps.report, outcome.failed());
+        }
 
-        match find_matching_monitors_for_config(&effective_cfg) {
-            Ok(devices) if devices.is_empty() => {
-                info!("No matching monitors found, skipping");
+        match &outcome.ddc {
+            Some(Ok(n)) => info!(
+                "DDC brightness set to {} on {} monitor(s)",
+                desired_ddc_brightness.unwrap_or_default(),
+                n
+            ),
+            Some(Err(e)) => {
+                warn!("DDC brightness set failed: {} (non-fatal)", e);
+                notify_ddc_failure(&effective_cfg, e.as_ref());
                 app_state::append_diagnostic_event(
                     "service",
                     "WARN",
-                    "apply_skip",
-                    "no matching monitors",
+                    "ddc_warning",
+                    &format!("ddc brightness write failed: {}", e),
                 );
-                false
             }
-            Ok(devices) => {
-                let mut applied_count = 0usize;
-                let mut last_applied_profile: Option<std::path::PathBuf> = None;
-                for device in &devices {
-                    let (sdr_profile_path, hdr_profile_path) =
-                        if let Some(paths) = &shared_mode_paths {
-                            paths.clone()
-                        } else {
-                            let identity = monitor_identity_from_match(device);
-                            match lg_profile::ensure_mode_profiles_installed_tuned_for_monitor(
-                                &color_dir,
-                                &sdr_preset,
-                                &hdr_preset,
-                                &effective_cfg.profile_name,
-                                effective_cfg.icc_gamma,
-                                effective_cfg.icc_luminance_cd_m2,
-                                effective_cfg.icc_generate_specialized_profiles,
-                                tuning_from_config(&effective_cfg),
-                                &identity,
-                            ) {
-                                Ok(path) => path,
-                                Err(e) => {
-                                    error!(
-                                        "Failed to generate monitor-scoped ICC for {}: {}",
-                                        device.name, e
-                                    );
-                                    app_state::append_diagnostic_event(
-                                        "service",
-                                        "ERROR",
-                                        "apply_error",
-                                        &format!(
-                                            "monitor-scoped profile generation failed for {}: {}",
-                                            device.name, e
-                                        ),
-                                    );
-                                    continue;
-                                }
-                            }
-                        };
-                    let active_profile_path = if hdr_mode_active {
-                        &hdr_profile_path
-                    } else {
-                        &sdr_profile_path
-                    };
-                    info!(
-                        "Reapplying mode profiles for: {} ({}) active={} sdr={} hdr={}",
-                        device.name,
-                        device.device_key,
-                        active_profile_path.display(),
-                        sdr_profile_path.display(),
-                        hdr_profile_path.display()
-                    );
-                    if let Err(e) = lg_profile::reapply_profile_with_mode_associations(
-                        &device.device_key,
-                        active_profile_path,
-                        &sdr_profile_path,
-                        &hdr_profile_path,
-                        effective_cfg.toggle_delay_ms,
-                        false,
-                    ) {
-                        error!("Failed to reapply for {}: {}", device.name, e);
-                        app_state::append_diagnostic_event(
-                            "service",
-                            "ERROR",
-                            "apply_error",
-                            &format!("reapply failed for {}: {}", device.name, e),
-                        );
-                    } else {
-                        applied_count += 1;
-                        last_applied_profile = Some(active_profile_path.clone());
-                    }
-                }
-                // Keep periodic/event-driven reapply refresh non-disruptive.
-                // Hard refresh is escalated internally only when verification fails.
-                lg_profile::refresh_display(
-                    false,
-                    effective_cfg.refresh_broadcast_color,
-                    effective_cfg.refresh_invalidate,
-                );
-                lg_profile::trigger_calibration_loader(effective_cfg.refresh_calibration_loader);
-
-                if let Some(level) = desired_ddc_brightness {
-                    match lg_monitor::ddc::set_brightness_all(level) {
-                        Ok(n) => info!("DDC brightness set to {} on {} monitor(s)", level, n),
-                        Err(e) => {
-                            warn!("DDC brightness set failed: {} (non-fatal)", e);
-                            app_state::append_diagnostic_event(
-                                "service",
-                                "WARN",
-                                "ddc_warning",
-                                &format!("ddc brightness write failed: {}", e),
-                            );
-                        }
-                    }
-                }
+            None => {}
+        }
 
-                if applied_count > 0 {
-                    if let Some(profile_path) = &last_applied_profile {
-                        if let Ok(snapshot) = app_state::create_profile_snapshot(
-                            &effective_cfg,
-                            "Auto Last Good (Service)",
-                            "auto",
-                            profile_path,
-                            desired_ddc_brightness,
-                            "Service successful reapply",
-                        ) {
-                            let _ = app_state::mark_snapshot_last_good(
-                                &snapshot,
-                                snapshot.ddc_brightness,
-                                "Service successful reapply",
-                            );
-                        }
-                    }
-                    app_state::append_diagnostic_event(
-                        "service",
-                        "INFO",
-                        "apply_success",
-                        &format!(
-                            "trigger={} source={} applied profiles to {} monitor(s)",
-                            trigger, decision.source, applied_count
-                        ),
-                    );
-                } else {
-                    app_state::append_diagnostic_event(
-                        "service",
-                        "WARN",
-                        "apply_skip",
-                        "no monitors successfully reapplied",
-                    );
-                }
+        if effective_cfg.sdr_white_level_nits > 0 && hdr_mode_active {
+            apply_sdr_white_level(effective_cfg.sdr_white_level_nits, &steps.connected);
+        }
 
-                lg_notify::show_reapply_toast(
-                    effective_cfg.toast_enabled,
-                    &effective_cfg.toast_title,
-                    &effective_cfg.toast_body,
-                    effective_cfg.verbose,
-                );
-                info!("Profile reapply complete for {} monitor(s)", applied_count);
-                applied_count > 0
-            }
-            Err(e) => {
-                error!("Monitor enumeration failed: {}", e);
-                app_state::append_diagnostic_event(
-                    "service",
-                    "ERROR",
-                    "apply_error",
-                    &format!("monitor enumeration failed: {}", e),
+        if let Some(profile_path) = &outcome.last_applied {
+            if let Ok(snapshot) = app_state::create_profile_snapshot(
+                &effective_cfg,
+                "Auto Last Good (Service)",
+                "auto",
+                profile_path,
+                desired_ddc_brightness,
+                "Service successful reapply",
+            ) {
+                let _ = app_state::mark_snapshot_last_good(
+                    &snapshot,
+                    snapshot.ddc_brightness,
+                    "Service successful reapply",
                 );
-                false
             }
+            app_state::append_diagnostic_event(
+                "service",
+                "INFO",
+                "apply_success",
+                &format!(
+                    "trigger={} source={} applied profiles to {} monitor(s)",
+                    trigger, decision.source, outcome.applied
+                ),
+            );
+        } else {
+            app_state::append_diagnostic_event(
+                "service",
+                "WARN",
+                "apply_skip",
+                "no monitors successfully reapplied",
+            );
         }
+        info!(
+            "Profile reapply complete for {} monitor(s)",
+            outcome.applied
+        );
+        outcome.applied > 0
     })();
 
+    record_counters(started, success);
     emit_apply_latency(
         "service",
         started,
@@ -1551,6 +2488,39 @@ fn handle_profile_reapply(config: &Config, trigger: &str, event_flags: u8) {
             trigger, active_preset, decision.source
         ),
     );
+    let luminance_cd_m2 = if success {
+        measure_luminance(config, trigger)
+    } else {
+        None
+    };
+    ipc::record_reapply(ReapplyRecord {
+        at: started_at,
+        trigger: trigger.to_string(),
+        events: event_names(event_flags)
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        success,
+        skipped,
+        devices: history_devices,
+        luminance_cd_m2,
+    });
+}
+
+/// Take the `measure_command` reading that follows a successful reapply.
+fn measure_luminance(config: &Config, trigger: &str) -> Option<f64> {
+    match lg_core::measure::measure_after_reapply(config, trigger)? {
+        Ok(cd_m2) => {
+            info!("Measured luminance after reapply: {:.1} cd/m²", cd_m2);
+            Some(cd_m2)
+        }
+        Err(e) => {
+            warn!("Luminance measurement failed: {}", e);
+            app_state::append_diagnostic_event("service", "WARN", "measure_error", &e.to_string());
+            None
+        }
+    }
 }
 
 // ============================================================================
@@ -1588,10 +2558,46 @@ fn configure_service_recovery(
     Ok(())
 }
 
-pub fn install(monitor_match: &str) -> Result<(), Box<dyn Error>> {
+/// Install (or reinstall) the service.
+///
+/// If the existing registration belongs to the legacy standalone binary, it
+/// is stopped and replaced, its registry parameters are imported into the
+/// config, and the returned [`LegacyMigration`] describes what was done.
+pub fn install(monitor_match: &str) -> Result<Option<LegacyMigration>, Box<dyn Error>> {
+    install_with_progress(monitor_match, &mut progress::ignore, &ShutdownToken::new())
+}
+
+/// Number of steps [`install_with_progress`] reports.
+pub const INSTALL_STEPS: usize = 6;
+
+/// [`install`], reporting each step to `on_progress` as it starts.
+/// Refused in portable mode, which keeps everything next to the executable.
+///
+/// Cancelling `shutdown` stops the install with [`Cancelled`] before the next
+/// step, up to the point where the old service registration is deleted;
+/// from there on it runs to the end so the service isn't left unregistered.
+pub fn install_with_progress(
+    monitor_match: &str,
+    on_progress: &mut dyn FnMut(&ProgressEvent),
+    shutdown: &ShutdownToken,
+) -> Result<Option<LegacyMigration>, Box<dyn Error>> {
+    if config::is_portable() {
+        return Err("the service cannot be installed in portable mode".into());
+    }
+    let mut progress = Progress::new(INSTALL_STEPS, on_progress);
+
+    // Capture a legacy registration before it is stopped and deleted below —
+    // its Parameters key goes away with the service.
+    let legacy_service = legacy::detect();
+
     // If the service already exists, stop it first so we can overwrite the
     // binary.  Errors here are expected (service may not exist yet).
-    stop_existing_service();
+    shutdown.check()?;
+    progress.step("Stopping existing service");
+    stop_existing_service(&mut progress, shutdown)?;
+
+    shutdown.check()?;
+    progress.step("Copying binary");
 
     // Copy the running binary to the install directory so the service
     // survives moves/deletes of the original file.
@@ -1601,11 +2607,49 @@ pub fn install(monitor_match: &str) -> Result<(), Box<dyn Error>> {
         std::fs::create_dir_all(&install_dir)?;
     }
     let dest_path = config::install_path();
-    copy_with_retry(&src_path, &dest_path)?;
+    audit::file_change("write_binary", &dest_path, || {
+        copy_with_retry(&src_path, &dest_path, &mut progress)
+    })?;
     info!("Binary copied to {}", dest_path.display());
 
+    let mut cfg = Config::load();
+    let mut monitor_match = monitor_match.to_string();
+    let migration = legacy_service.map(|found| {
+        let imported = legacy::import_parameters(&mut cfg, &found.parameters);
+        if !imported.is_empty() {
+            if let Err(e) = Config::write_config(&cfg) {
+                warn!("Could not save imported legacy settings: {}", e);
+            }
+            monitor_match = cfg.monitor_match.clone();
+        }
+        info!(
+            "Migrating legacy service {} (imported: {})",
+            found.image_path,
+            if imported.is_empty() {
+                "none".to_string()
+            } else {
+                imported.join(", ")
+            }
+        );
+        app_state::append_diagnostic_event(
+            "service",
+            "INFO",
+            "legacy_service_migrated",
+            &format!(
+                "binary={} imported={}",
+                found.image_path,
+                imported.join(",")
+            ),
+        );
+        LegacyMigration {
+            legacy_binary: found.image_path,
+            imported,
+        }
+    });
+
     // Generate active/specialized ICC profiles in the Windows color store
-    let cfg = Config::load();
+    shutdown.check()?;
+    progress.step("Generating ICC profiles");
     let color_dir = lg_profile::color_directory();
     let sdr_preset = effective_preset_for_mode(&cfg, false);
     let hdr_preset = effective_preset_for_mode(&cfg, true);
@@ -1625,6 +2669,8 @@ pub fn install(monitor_match: &str) -> Result<(), Box<dyn Error>> {
         hdr_profile_path.display()
     );
 
+    shutdown.check()?;
+    progress.step("Registering service");
     let manager = ServiceManager::local_computer(
         None::<&str>,
         ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
@@ -1633,7 +2679,7 @@ pub fn install(monitor_match: &str) -> Result<(), Box<dyn Error>> {
     // If the service already exists in SCM, delete the old registration so
     // create_service succeeds.  The binary was already stopped above.
     if let Ok(existing) = manager.open_service(SERVICE_NAME, ServiceAccess::DELETE) {
-        let _ = existing.delete();
+        let _ = audited_service_op("delete", || existing.delete());
         // Brief pause for SCM to finish the deletion.
         thread::sleep(Duration::from_millis(500));
         info!("Deleted previous service registration before reinstall");
@@ -1653,20 +2699,24 @@ pub fn install(monitor_match: &str) -> Result<(), Box<dyn Error>> {
         account_password: None,
     };
 
-    let service = manager.create_service(
-        &service_info,
-        ServiceAccess::CHANGE_CONFIG | ServiceAccess::START,
-    )?;
+    let service = audited_service_op("create", || {
+        manager.create_service(
+            &service_info,
+            ServiceAccess::CHANGE_CONFIG | ServiceAccess::START,
+        )
+    })?;
     service.set_description(SERVICE_DESCRIPTION)?;
     configure_service_recovery(&service)?;
 
     // Store monitor match pattern in registry (informational)
-    write_monitor_match(monitor_match)?;
+    progress.step("Saving monitor pattern");
+    write_monitor_match(&monitor_match)?;
 
     // Register the event log source so Event Viewer can resolve message strings.
     // The winlog crate embeds a message table resource (eventmsgs) into the
     // binary.  We point EventMessageFile at the *installed* copy so messages
     // render correctly regardless of where the installer was launched from.
+    progress.step("Registering event log source");
     if let Err(e) = register_event_source(&config::install_path()) {
         // Event log registration is useful for richer diagnostics, but it
         // should not block service install/start for end users.
@@ -1674,107 +2724,162 @@ pub fn install(monitor_match: &str) -> Result<(), Box<dyn Error>> {
     }
 
     info!("Service installed successfully");
-    Ok(())
+    Ok(migration)
 }
 
 /// Stop the existing service (if any) so we can safely overwrite the binary.
-/// All errors are silently absorbed — the service may not exist yet.
-fn stop_existing_service() {
+/// All errors are silently absorbed — the service may not exist yet — but
+/// the wait for it to stop ends early when `shutdown` is cancelled.
+fn stop_existing_service(
+    progress: &mut Progress,
+    shutdown: &ShutdownToken,
+) -> Result<(), Cancelled> {
     let Ok(manager) = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
     else {
-        return;
+        return Ok(());
     };
     let Ok(service) = manager.open_service(
         SERVICE_NAME,
         ServiceAccess::STOP | ServiceAccess::QUERY_STATUS,
     ) else {
-        return; // service doesn't exist yet
+        return Ok(()); // service doesn't exist yet
     };
 
-    let _ = service.stop();
+    let _ = audited_service_op("stop", || service.stop());
 
     // Poll until stopped (up to ~10 s).
     let deadline = Instant::now() + Duration::from_secs(10);
+    let mut waiting = false;
     loop {
         if let Ok(status) = service.query_status() {
             if status.current_state == ServiceState::Stopped {
                 info!("Existing service stopped before reinstall");
-                return;
+                return Ok(());
             }
         }
         if Instant::now() >= deadline {
             warn!("Existing service did not stop within 10 s — proceeding anyway");
-            return;
+            progress.detail("service did not stop within 10 s, continuing");
+            return Ok(());
+        }
+        if !waiting {
+            progress.detail("waiting for the service to stop (up to 10 s)");
+            waiting = true;
+        }
+        if !shutdown.sleep(Duration::from_millis(250)) {
+            return Err(Cancelled);
         }
-        thread::sleep(Duration::from_millis(250));
     }
 }
 
 /// Copy a file with retries on sharing violations (error 32).
-/// Retries up to 5 times with escalating back-off (~3.2 s total).
-fn copy_with_retry(src: &std::path::Path, dst: &std::path::Path) -> Result<u64, Box<dyn Error>> {
-    let delays_ms: &[u64] = &[0, 200, 500, 1000, 1500];
-    for (attempt, &ms) in delays_ms.iter().enumerate() {
-        if ms > 0 {
-            thread::sleep(Duration::from_millis(ms));
-        }
+/// Tries up to 5 times with escalating back-off (~3.2 s total).
+fn copy_with_retry(
+    src: &std::path::Path,
+    dst: &std::path::Path,
+    progress: &mut Progress,
+) -> std::io::Result<u64> {
+    let retry_delays_ms: &[u64] = &[200, 500, 1000, 1500];
+    let mut attempt = 0;
+    loop {
         match std::fs::copy(src, dst) {
             Ok(bytes) => return Ok(bytes),
-            Err(e) if e.raw_os_error() == Some(32) && attempt < delays_ms.len() - 1 => {
+            Err(e) if e.raw_os_error() == Some(32) && attempt < retry_delays_ms.len() => {
                 info!(
                     "Binary copy attempt {} blocked (sharing violation) — retrying",
                     attempt + 1
                 );
+                progress.detail(format!(
+                    "binary in use, retrying (attempt {} of {})",
+                    attempt + 2,
+                    retry_delays_ms.len() + 1
+                ));
+                thread::sleep(Duration::from_millis(retry_delays_ms[attempt]));
+                attempt += 1;
             }
-            Err(e) => return Err(e.into()),
+            Err(e) => return Err(e),
         }
     }
-    unreachable!()
 }
 
 pub fn uninstall() -> Result<(), Box<dyn Error>> {
+    uninstall_with_progress(&mut progress::ignore, &ShutdownToken::new())
+}
+
+/// Number of steps [`uninstall_with_progress`] reports.
+pub const UNINSTALL_STEPS: usize = 4;
+
+/// [`uninstall`], reporting each step to `on_progress` as it starts.
+///
+/// Cancelling `shutdown` stops the uninstall with [`Cancelled`] until the
+/// service registration is deleted; the remaining cleanup always runs.
+pub fn uninstall_with_progress(
+    on_progress: &mut dyn FnMut(&ProgressEvent),
+    shutdown: &ShutdownToken,
+) -> Result<(), Box<dyn Error>> {
+    let mut progress = Progress::new(UNINSTALL_STEPS, on_progress);
     let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)?;
 
     // Open the service — if it doesn't exist, that's fine (already removed).
-    match manager.open_service(
+    shutdown.check()?;
+    progress.step("Stopping service");
+    let service = match manager.open_service(
         SERVICE_NAME,
         ServiceAccess::STOP | ServiceAccess::DELETE | ServiceAccess::QUERY_STATUS,
     ) {
-        Ok(service) => {
-            // Try to stop first, then poll until actually stopped (up to ~10 s).
-            let _ = service.stop();
-            let deadline = Instant::now() + Duration::from_secs(10);
-            loop {
-                if let Ok(status) = service.query_status() {
-                    if status.current_state == ServiceState::Stopped {
-                        break;
-                    }
-                }
-                if Instant::now() >= deadline {
-                    warn!("Service did not stop within 10 s — proceeding with delete");
+        Ok(service) => Some(service),
+        Err(e) => {
+            // Service not installed / already deleted — not an error.
+            info!("Service not found (already removed): {}", e);
+            progress.detail("service is not installed");
+            None
+        }
+    };
+
+    if let Some(service) = &service {
+        // Try to stop first, then poll until actually stopped (up to ~10 s).
+        let _ = audited_service_op("stop", || service.stop());
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut waiting = false;
+        loop {
+            if let Ok(status) = service.query_status() {
+                if status.current_state == ServiceState::Stopped {
                     break;
                 }
-                thread::sleep(Duration::from_millis(250));
             }
-
-            // Delete the service registration from SCM.
-            if let Err(e) = service.delete() {
-                warn!(
-                    "service.delete() failed: {} (may already be marked for deletion)",
-                    e
-                );
+            if Instant::now() >= deadline {
+                warn!("Service did not stop within 10 s — proceeding with delete");
+                progress.detail("service did not stop within 10 s, deleting anyway");
+                break;
+            }
+            if !waiting {
+                progress.detail("waiting for the service to stop (up to 10 s)");
+                waiting = true;
+            }
+            if !shutdown.sleep(Duration::from_millis(250)) {
+                return Err(Cancelled.into());
             }
         }
-        Err(e) => {
-            // Service not installed / already deleted — not an error.
-            info!("Service not found (already removed): {}", e);
+    }
+
+    // Delete the service registration from SCM.
+    shutdown.check()?;
+    progress.step("Deleting service registration");
+    if let Some(service) = &service {
+        if let Err(e) = audited_service_op("delete", || service.delete()) {
+            warn!(
+                "service.delete() failed: {} (may already be marked for deletion)",
+                e
+            );
         }
     }
 
     // Deregister the event log source (best-effort)
+    progress.step("Removing event log source");
     deregister_event_source();
 
     // Remove the installed binary with retry + schedule-for-reboot fallback.
+    progress.step("Removing installed binary");
     let install_bin = config::install_path();
     if install_bin.exists() {
         force_remove_file(&install_bin);
@@ -1784,6 +2889,131 @@ pub fn uninstall() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Plan counterpart of [`install`]: the operations it would perform on this
+/// machine, appended to `plan`.
+pub fn plan_install(plan: &mut Plan, monitor_match: &str) -> Result<(), Box<dyn Error>> {
+    let legacy_service = legacy::detect();
+    // A reinstall plan has already stopped and deleted the service.
+    let removed = plan
+        .operations
+        .iter()
+        .any(|op| op.kind == OpKind::Service && op.action == "delete");
+    let (installed, running) = if removed {
+        (false, false)
+    } else {
+        query_service_info()
+    };
+    if running {
+        plan.push(OpKind::Service, "stop", SERVICE_NAME)
+            .detail("so the binary can be replaced");
+    }
+
+    let src_path = std::env::current_exe()?;
+    let dest_path = config::install_path();
+    if !plan.write_file("write_binary", &dest_path, &std::fs::read(&src_path)?) {
+        plan.note(format!(
+            "{} is already this build; not copied",
+            dest_path.display()
+        ));
+    }
+
+    let mut cfg = Config::load();
+    let mut monitor_match = monitor_match.to_string();
+    if let Some(found) = legacy_service {
+        let imported = legacy::import_parameters(&mut cfg, &found.parameters);
+        plan.note(format!(
+            "legacy service {} is replaced; imported: {}",
+            found.image_path,
+            if imported.is_empty() {
+                "none".to_string()
+            } else {
+                imported.join(", ")
+            }
+        ));
+        if !imported.is_empty() {
+            Config::plan_write_to(plan, &cfg, &config::config_path());
+            monitor_match = cfg.monitor_match.clone();
+        }
+    }
+
+    lg_profile::plan_mode_profiles_install(
+        plan,
+        &lg_profile::color_directory(),
+        &effective_preset_for_mode(&cfg, false),
+        &effective_preset_for_mode(&cfg, true),
+        &cfg.profile_name,
+        cfg.icc_gamma,
+        cfg.icc_luminance_cd_m2,
+        cfg.icc_generate_specialized_profiles,
+        tuning_from_config(&cfg),
+        None,
+    )?;
+
+    if installed {
+        plan.push(OpKind::Service, "delete", SERVICE_NAME)
+            .detail("previous registration");
+    }
+    plan.push(OpKind::Service, "create", SERVICE_NAME)
+        .detail(format!(
+            "\"{}\", auto start, LocalSystem, {} service run",
+            SERVICE_DISPLAY_NAME,
+            dest_path.display()
+        ));
+    plan.push(OpKind::Service, "set_description", SERVICE_NAME);
+    plan.push(OpKind::Service, "set_recovery", SERVICE_NAME)
+        .detail(format!(
+            "restart after {:?}s, reset after {}s",
+            SERVICE_FAILURE_RESTART_DELAYS_SECS, SERVICE_FAILURE_RESET_SECS
+        ));
+    plan.push(
+        OpKind::Registry,
+        "set_value",
+        format!(r"HKLM\{}\{}", CONFIG_REG_KEY, CONFIG_REG_VALUE),
+    )
+    .detail(monitor_match);
+    plan.push(
+        OpKind::Registry,
+        "register_event_source",
+        format!(r"HKLM\{}", EVENTLOG_REG_KEY),
+    )
+    .detail(format!(
+        "EventMessageFile={}, TypesSupported=7",
+        dest_path.display()
+    ));
+    Ok(())
+}
+
+/// Plan counterpart of [`start_service`].
+pub fn plan_start_service(plan: &mut Plan) {
+    plan.push(OpKind::Service, "start", SERVICE_NAME);
+}
+
+/// Plan counterpart of [`uninstall`].
+pub fn plan_uninstall(plan: &mut Plan) {
+    use winreg::enums::HKEY_LOCAL_MACHINE;
+
+    match query_service_info() {
+        (false, _) => plan.note("service is not installed"),
+        (true, running) => {
+            if running {
+                plan.push(OpKind::Service, "stop", SERVICE_NAME);
+            }
+            plan.push(OpKind::Service, "delete", SERVICE_NAME);
+        }
+    }
+    if winreg::RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(EVENTLOG_REG_KEY)
+        .is_ok()
+    {
+        plan.push(
+            OpKind::Registry,
+            "delete_key",
+            format!(r"HKLM\{}", EVENTLOG_REG_KEY),
+        );
+    }
+    plan.remove_file("remove_file", &config::install_path());
+}
+
 /// Attempt to delete a file with retries.  If still locked after all
 /// attempts, schedule it for deletion on next reboot via
 /// `MoveFileExW(MOVEFILE_DELAY_UNTIL_REBOOT)`.
@@ -1792,7 +3022,7 @@ fn force_remove_file(path: &std::path::Path) {
     let delays = [200, 500, 1000, 1500, 2000, 2000];
     for (attempt, &ms) in delays.iter().enumerate() {
         thread::sleep(Duration::from_millis(ms));
-        match std::fs::remove_file(path) {
+        match audit::file_change("remove_file", path, || std::fs::remove_file(path)) {
             Ok(()) => {
                 info!("Removed file: {} (attempt {})", path.display(), attempt + 1);
                 return;
@@ -1824,15 +3054,13 @@ pub fn schedule_reboot_delete(path: &std::path::Path) {
 }
 
 fn schedule_reboot_delete_impl(path: &std::path::Path) {
-    use std::os::windows::ffi::OsStrExt;
     use windows::Win32::Storage::FileSystem::{MoveFileExW, MOVEFILE_DELAY_UNTIL_REBOOT};
 
-    let wide: Vec<u16> = path
-        .as_os_str()
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect();
-    let ok = unsafe { MoveFileExW(PCWSTR(wide.as_ptr()), None, MOVEFILE_DELAY_UNTIL_REBOOT) };
+    let wide = WideString::new(path);
+    let ok = unsafe { MoveFileExW(wide.as_pcwstr(), None, MOVEFILE_DELAY_UNTIL_REBOOT) };
+    AuditEntry::new("file", "schedule_reboot_delete", path.display().to_string())
+        .result(&ok)
+        .record();
     match ok {
         Ok(()) => info!("Scheduled for deletion on reboot: {}", path.display()),
         Err(e) => warn!(
@@ -1846,18 +3074,20 @@ fn schedule_reboot_delete_impl(path: &std::path::Path) {
 pub fn start_service() -> Result<(), Box<dyn Error>> {
     let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)?;
     let service = manager.open_service(SERVICE_NAME, ServiceAccess::START)?;
-    service.start::<&str>(&[])?;
+    audited_service_op("start", || service.start::<&str>(&[]))?;
     Ok(())
 }
 
 pub fn stop_service() -> Result<(), Box<dyn Error>> {
     let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)?;
     let service = manager.open_service(SERVICE_NAME, ServiceAccess::STOP)?;
-    service.stop()?;
+    audited_service_op("stop", || service.stop())?;
     Ok(())
 }
 
-pub fn print_status() -> Result<(), Box<dyn Error>> {
+/// Describe the service, its config and its health to `report`, line by
+/// line.
+pub fn report_status(report: fn(&str)) -> Result<(), Box<dyn Error>> {
     let cfg = Config::load();
 
     let manager = match ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
@@ -1871,10 +3101,10 @@ pub fn print_status() -> Result<(), Box<dyn Error>> {
     let service = match manager.open_service(SERVICE_NAME, ServiceAccess::QUERY_STATUS) {
         Ok(s) => s,
         Err(_) => {
-            println!("Service: {}  (NOT INSTALLED)", SERVICE_NAME);
-            println!("Binary:  {}", config::install_path().display());
-            println!("Config:  {}", config::config_path().display());
-            println!(
+            report(&format!("Service: {}  (NOT INSTALLED)", SERVICE_NAME));
+            report(&format!("Binary:  {}", config::install_path().display()));
+            report(&format!("Config:  {}", config::config_path().display()));
+            report(&format!(
                 "Monitor: {} ({})",
                 cfg.monitor_match,
                 if cfg.monitor_match_regex {
@@ -1882,9 +3112,12 @@ pub fn print_status() -> Result<(), Box<dyn Error>> {
                 } else {
                     "substring"
                 }
-            );
-            println!("Profile: {}", cfg.profile_name);
-            println!("Toast:   {}", if cfg.toast_enabled { "on" } else { "off" });
+            ));
+            report(&format!("Profile: {}", cfg.profile_name));
+            report(&format!(
+                "Toast:   {}",
+                if cfg.toast_enabled { "on" } else { "off" }
+            ));
             return Ok(());
         }
     };
@@ -1896,12 +3129,12 @@ pub fn print_status() -> Result<(), Box<dyn Error>> {
         }
     };
 
-    println!("Service: {}", SERVICE_NAME);
-    println!("State:   {:?}", status.current_state);
-    println!("PID:     {:?}", status.process_id);
-    println!("Binary:  {}", config::install_path().display());
-    println!("Config:  {}", config::config_path().display());
-    println!(
+    report(&format!("Service: {}", SERVICE_NAME));
+    report(&format!("State:   {:?}", status.current_state));
+    report(&format!("PID:     {:?}", status.process_id));
+    report(&format!("Binary:  {}", config::install_path().display()));
+    report(&format!("Config:  {}", config::config_path().display()));
+    report(&format!(
         "Monitor: {} ({})",
         cfg.monitor_match,
         if cfg.monitor_match_regex {
@@ -1909,12 +3142,106 @@ pub fn print_status() -> Result<(), Box<dyn Error>> {
         } else {
             "substring"
         }
-    );
-    println!("Profile: {}", cfg.profile_name);
-    println!("Toast:   {}", if cfg.toast_enabled { "on" } else { "off" });
+    ));
+    report(&format!("Profile: {}", cfg.profile_name));
+    report(&format!(
+        "Toast:   {}",
+        if cfg.toast_enabled { "on" } else { "off" }
+    ));
+    let running = status.current_state == ServiceState::Running;
+    report_heartbeat(&cfg, running, report);
+    if running {
+        report_worker_health(report);
+        report(&format!(
+            "Lid:     {}",
+            match ipc::fetch_lid_state() {
+                Ok(Some(lid)) => lid.as_str().to_string(),
+                Ok(None) => "unknown (no lid reported)".to_string(),
+                Err(e) => format!("unavailable ({})", e),
+            }
+        ));
+    }
     Ok(())
 }
 
+/// Show the supervisor's view of the background workers, live from the
+/// running service.
+fn report_worker_health(report: fn(&str)) {
+    let workers = match ipc::fetch_worker_health() {
+        Ok(workers) => workers,
+        Err(e) => {
+            report(&format!("Workers: unavailable ({})", e));
+            return;
+        }
+    };
+    let restarts: u32 = workers.iter().map(|w| w.restarts).sum();
+    if workers.iter().all(|w| w.state == "running") {
+        report(&format!(
+            "Workers: {} running, {} restart(s)",
+            workers.len(),
+            restarts
+        ));
+    } else {
+        report("Workers:");
+    }
+    for worker in &workers {
+        if worker.state == "running" && worker.restarts == 0 {
+            continue;
+        }
+        report(&format!(
+            "         {} {}, {} restart(s){}",
+            worker.name,
+            worker.state,
+            worker.restarts,
+            worker
+                .last_panic
+                .as_deref()
+                .map(|p| format!(", last panic: {}", p))
+                .unwrap_or_default()
+        ));
+    }
+}
+
+/// Show the watchdog's last self-check.
+fn report_heartbeat(cfg: &Config, running: bool, report: fn(&str)) {
+    if cfg.watchdog_interval_minutes == 0 {
+        report("Health:  watchdog off");
+        return;
+    }
+    let Some(heartbeat) = app_state::load_service_heartbeat() else {
+        report("Health:  no heartbeat recorded yet");
+        return;
+    };
+    let mark = |ok: bool| if ok { "ok" } else { "FAIL" };
+    report(&format!(
+        "Health:  {} at {}{}",
+        if heartbeat.healthy() {
+            "ok"
+        } else {
+            "degraded"
+        },
+        heartbeat.at,
+        if running && heartbeat.is_overdue(chrono::Utc::now()) {
+            "  (overdue — the service may be hung)"
+        } else {
+            ""
+        }
+    ));
+    report(&format!(
+        "         window {}, notifications {}, WMI {}, association {}",
+        mark(heartbeat.window_ok),
+        mark(heartbeat.notifications_ok),
+        mark(heartbeat.wmi_ok),
+        mark(heartbeat.association_ok)
+    ));
+    if !heartbeat.repairs.is_empty() {
+        report(&format!(
+            "         repairs: {}",
+            heartbeat.repairs.join(", ")
+        ));
+    }
+}
+
 /// Query service installation and running state for display purposes.
 /// Returns `(installed, running)`. Never panics.
 pub fn query_service_info() -> (bool, bool) {
@@ -1934,14 +3261,49 @@ pub fn query_service_info() -> (bool, bool) {
 // Helpers
 // ============================================================================
 
+/// `absent`, `running` or `not running`, for the audit log.
+fn service_state_label() -> &'static str {
+    match query_service_info() {
+        (false, _) => "absent",
+        (true, true) => "running",
+        (true, false) => "not running",
+    }
+}
+
+/// Run an SCM operation on the service and record it in the audit log with
+/// the service state before and after.
+fn audited_service_op<T, E: std::fmt::Display>(
+    action: &str,
+    op: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    let before = service_state_label();
+    let result = op();
+    AuditEntry::new("service", action, SERVICE_NAME)
+        .before(before)
+        .after(service_state_label())
+        .result(&result)
+        .record();
+    result
+}
+
 fn write_monitor_match(pattern: &str) -> Result<(), Box<dyn Error>> {
     use winreg::enums::*;
     use winreg::RegKey;
 
     let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
     let (key, _) = hklm.create_subkey(CONFIG_REG_KEY)?;
-    key.set_value(CONFIG_REG_VALUE, &pattern)?;
-    Ok(())
+    let before: Option<String> = key.get_value(CONFIG_REG_VALUE).ok();
+    let result = key.set_value(CONFIG_REG_VALUE, &pattern);
+    AuditEntry::new(
+        "registry",
+        "set_value",
+        format!(r"HKLM\{}\{}", CONFIG_REG_KEY, CONFIG_REG_VALUE),
+    )
+    .before(before.unwrap_or_else(|| "(none)".to_string()))
+    .after(pattern)
+    .result(&result)
+    .record();
+    Ok(result?)
 }
 
 /// Register the Windows Event Log source so Event Viewer can find the
@@ -1954,10 +3316,21 @@ fn register_event_source(exe_path: &std::path::Path) -> Result<(), Box<dyn Error
     use winreg::RegKey;
 
     let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
-    let (key, _) = hklm.create_subkey(EVENTLOG_REG_KEY)?;
-    key.set_value("EventMessageFile", &exe_path.to_string_lossy().as_ref())?;
-    // EVENTLOG_ERROR_TYPE | EVENTLOG_WARNING_TYPE | EVENTLOG_INFORMATION_TYPE
-    key.set_value("TypesSupported", &7u32)?;
+    let result = (|| -> std::io::Result<()> {
+        let (key, _) = hklm.create_subkey(EVENTLOG_REG_KEY)?;
+        key.set_value("EventMessageFile", &exe_path.to_string_lossy().as_ref())?;
+        // EVENTLOG_ERROR_TYPE | EVENTLOG_WARNING_TYPE | EVENTLOG_INFORMATION_TYPE
+        key.set_value("TypesSupported", &7u32)
+    })();
+    AuditEntry::new(
+        "registry",
+        "register_event_source",
+        format!(r"HKLM\{}", EVENTLOG_REG_KEY),
+    )
+    .after(exe_path.display().to_string())
+    .result(&result)
+    .record();
+    result?;
     info!("Event log source registered: {}", exe_path.display());
     Ok(())
 }
@@ -1972,21 +3345,21 @@ fn deregister_event_source() {
         r"SYSTEM\CurrentControlSet\Services\EventLog\Application",
         KEY_WRITE,
     ) {
-        match parent.delete_subkey(SERVICE_NAME) {
+        let result = parent.delete_subkey(SERVICE_NAME);
+        AuditEntry::new(
+            "registry",
+            "delete_key",
+            format!(r"HKLM\{}", EVENTLOG_REG_KEY),
+        )
+        .result(&result)
+        .record();
+        match result {
             Ok(()) => info!("Event log source deregistered"),
             Err(e) => warn!("Could not deregister event log source: {}", e),
         }
     }
 }
 
-/// Convert a Rust string to a null-terminated wide string (UTF-16).
-fn to_wide(s: &str) -> Vec<u16> {
-    OsStr::new(s)
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect()
-}
-
 #[cfg(test)]
 #[path = "tests/service_tests.rs"]
 mod tests;
@@ -0,0 +1,120 @@
+//! Laptop lid tracking.
+//!
+//! The event window registers for `GUID_LIDSWITCH_STATE_CHANGE`. Windows
+//! answers the registration with the current position and then reports
+//! every change, so the state is known from startup on machines with a lid
+//! and stays unknown on desktops. `skip_reapply_lid_closed` reads it, and
+//! `service status` and `probe` ask for it over the control pipe.
+
+use lg_core::display_gate::LidState;
+use lg_core::state as app_state;
+use log::info;
+use std::ptr;
+use std::sync::atomic::{AtomicIsize, AtomicU8, Ordering};
+
+use windows::core::GUID;
+use windows::Win32::Foundation::{HANDLE, HWND, LPARAM, WPARAM};
+use windows::Win32::System::Power::{
+    RegisterPowerSettingNotification, UnregisterPowerSettingNotification, HPOWERNOTIFY,
+    POWERBROADCAST_SETTING,
+};
+use windows::Win32::UI::WindowsAndMessaging::DEVICE_NOTIFY_WINDOW_HANDLE;
+
+const PBT_POWERSETTINGCHANGE: usize = 0x8013;
+
+/// `GUID_LIDSWITCH_STATE_CHANGE`: 0 = closed, 1 = open.
+const GUID_LIDSWITCH_STATE_CHANGE: GUID = GUID::from_u128(0xba3e0f4d_b817_4094_a2d1_d56379e6a0f3);
+
+const UNKNOWN: u8 = 0;
+
+/// Last reported position; [`UNKNOWN`] until the first notification.
+static STATE: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+static NOTIFY_HANDLE: AtomicIsize = AtomicIsize::new(0);
+
+pub(crate) fn from_raw(value: u32) -> Option<LidState> {
+    match value {
+        0 => Some(LidState::Closed),
+        1 => Some(LidState::Open),
+        _ => None,
+    }
+}
+
+fn to_u8(state: Option<LidState>) -> u8 {
+    match state {
+        None => UNKNOWN,
+        Some(LidState::Closed) => 1,
+        Some(LidState::Open) => 2,
+    }
+}
+
+fn from_u8(value: u8) -> Option<LidState> {
+    match value {
+        1 => Some(LidState::Closed),
+        2 => Some(LidState::Open),
+        _ => None,
+    }
+}
+
+/// Register `hwnd` for lid changes. Returns false on failure, in which case
+/// the position stays unknown.
+pub(crate) fn register(hwnd: HWND) -> bool {
+    let handle = unsafe {
+        RegisterPowerSettingNotification(
+            HANDLE(hwnd.0),
+            &GUID_LIDSWITCH_STATE_CHANGE,
+            DEVICE_NOTIFY_WINDOW_HANDLE,
+        )
+    };
+    let handle = handle.map_or(0, |h| h.0);
+    NOTIFY_HANDLE.store(handle, Ordering::SeqCst);
+    handle != 0
+}
+
+pub(crate) fn unregister() {
+    let handle = NOTIFY_HANDLE.swap(0, Ordering::SeqCst);
+    if handle != 0 {
+        unsafe {
+            let _ = UnregisterPowerSettingNotification(HPOWERNOTIFY(handle));
+        }
+    }
+}
+
+/// Current lid position; `None` before the first report or without a lid.
+pub(crate) fn state() -> Option<LidState> {
+    from_u8(STATE.load(Ordering::SeqCst))
+}
+
+/// Handle `WM_POWERBROADCAST` for the lid setting.
+///
+/// # Safety
+/// `lparam` must be the `lParam` of a `WM_POWERBROADCAST` message.
+pub(crate) unsafe fn on_power_broadcast(wparam: WPARAM, lparam: LPARAM) {
+    if wparam.0 != PBT_POWERSETTINGCHANGE || lparam.0 == 0 {
+        return;
+    }
+    let setting = &*(lparam.0 as *const POWERBROADCAST_SETTING);
+    if setting.PowerSetting != GUID_LIDSWITCH_STATE_CHANGE
+        || (setting.DataLength as usize) < std::mem::size_of::<u32>()
+    {
+        return;
+    }
+    let raw = ptr::read_unaligned(setting.Data.as_ptr() as *const u32);
+    let Some(current) = from_raw(raw) else {
+        return;
+    };
+    let previous = from_u8(STATE.swap(to_u8(Some(current)), Ordering::SeqCst));
+    if previous != Some(current) {
+        info!("Lid {}", current.as_str());
+        app_state::append_diagnostic_event(
+            "service",
+            "INFO",
+            "lid_state",
+            &format!("lid={}", current.as_str()),
+        );
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/lid_tests.rs"]
+mod tests;
@@ -0,0 +1,218 @@
+//! Profile watch: pick up a recalibrated override profile without a reboot.
+//!
+//! The service generates its own profiles from the config (a reapply
+//! rewrites them), but the profiles named in `[app_overrides]` are the
+//! user's, typically the output of a calibration tool. When one of them is
+//! replaced on disk, Windows keeps using the copy it loaded until the
+//! association changes. A thread waits on a change notification for the
+//! color directory, compares each override profile's size and timestamp
+//! with what it saw last, and re-registers a changed profile with WCS. If
+//! it belongs to the override in effect, a reapply re-associates it and a
+//! "Calibration updated" toast is shown.
+
+use super::app_override::{self, OVERRIDE_NONE};
+use super::events::DisplayEvent;
+use lg_core::config::Config;
+use lg_core::shutdown::ShutdownToken;
+use lg_core::state as app_state;
+use lg_core::wide::WideString;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use windows::Win32::Foundation::{BOOL, WAIT_OBJECT_0};
+use windows::Win32::Storage::FileSystem::{
+    FindCloseChangeNotification, FindFirstChangeNotificationW, FindNextChangeNotification,
+    FILE_NOTIFY_CHANGE_FILE_NAME, FILE_NOTIFY_CHANGE_LAST_WRITE, FILE_NOTIFY_CHANGE_SIZE,
+};
+use windows::Win32::System::Threading::WaitForSingleObject;
+
+/// How often the wait wakes up to check for shutdown.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Quiet period after a change before comparing, so a tool that writes the
+/// file in several steps is only picked up once it is done.
+const SETTLE: Duration = Duration::from_secs(2);
+
+/// What the file looked like: a new calibration changes at least one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Fingerprint {
+    pub(crate) len: u64,
+    pub(crate) modified: Option<SystemTime>,
+}
+
+impl Fingerprint {
+    fn of(path: &Path) -> Option<Self> {
+        let meta = fs::metadata(path).ok()?;
+        Some(Self {
+            len: meta.len(),
+            modified: meta.modified().ok(),
+        })
+    }
+}
+
+/// True when a file seen as `before` is now `after` and worth reloading.
+/// A deleted file is not a new calibration.
+pub(crate) fn is_replaced(before: Option<Fingerprint>, after: Option<Fingerprint>) -> bool {
+    after.is_some() && after != before
+}
+
+/// Override profile file names from `[app_overrides]`, without duplicates
+/// or the `none` entries that only remove our profile.
+pub(crate) fn override_profiles(config: &Config) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for profile in config.app_overrides.values() {
+        let profile = profile.trim();
+        if profile.is_empty()
+            || profile.eq_ignore_ascii_case(OVERRIDE_NONE)
+            || names.iter().any(|n| n.eq_ignore_ascii_case(profile))
+        {
+            continue;
+        }
+        names.push(profile.to_string());
+    }
+    names
+}
+
+/// Spawn the watch thread. Returns `None` when no override names a profile.
+pub(crate) fn spawn(
+    config: &Config,
+    shutdown: &ShutdownToken,
+    tx: mpsc::Sender<DisplayEvent>,
+) -> Option<std::io::Result<thread::JoinHandle<()>>> {
+    let names = override_profiles(config);
+    if names.is_empty() {
+        return None;
+    }
+    Some(super::supervisor::spawn(
+        "profile-watch",
+        shutdown,
+        move |shutdown| run(&names, shutdown, &tx),
+    ))
+}
+
+fn run(names: &[String], shutdown: &ShutdownToken, tx: &mpsc::Sender<DisplayEvent>) {
+    let color_dir = lg_profile::color_directory();
+    let dir = WideString::new(&color_dir);
+    let handle = match unsafe {
+        FindFirstChangeNotificationW(
+            dir.as_pcwstr(),
+            BOOL::from(false),
+            FILE_NOTIFY_CHANGE_FILE_NAME | FILE_NOTIFY_CHANGE_SIZE | FILE_NOTIFY_CHANGE_LAST_WRITE,
+        )
+    } {
+        Ok(h) => h,
+        Err(e) => {
+            warn!(
+                "Profile watch disabled: cannot watch {}: {}",
+                color_dir.display(),
+                e
+            );
+            return;
+        }
+    };
+
+    let mut seen: HashMap<PathBuf, Option<Fingerprint>> = names
+        .iter()
+        .map(|name| {
+            let path = color_dir.join(name);
+            let fingerprint = Fingerprint::of(&path);
+            (path, fingerprint)
+        })
+        .collect();
+    info!("Profile watch started: {} override profile(s)", seen.len());
+
+    'outer: while !shutdown.is_cancelled() {
+        loop {
+            if shutdown.is_cancelled() {
+                break 'outer;
+            }
+            let wait = unsafe { WaitForSingleObject(handle, POLL_INTERVAL.as_millis() as u32) };
+            if wait == WAIT_OBJECT_0 {
+                break;
+            }
+        }
+
+        // Let the writer finish, then re-arm before comparing so a change
+        // made during the comparison isn't lost.
+        if !shutdown.sleep(SETTLE) {
+            break;
+        }
+        if unsafe { FindNextChangeNotification(handle) }.is_err() {
+            warn!("Profile watch stopped: FindNextChangeNotification failed");
+            break;
+        }
+
+        let mut reapply = false;
+        for (path, before) in seen.iter_mut() {
+            let after = Fingerprint::of(path);
+            let replaced = is_replaced(*before, after);
+            *before = after;
+            if replaced && reload(path) {
+                reapply |= is_active_override(path);
+            }
+        }
+        if reapply && tx.send(DisplayEvent::CalibrationUpdated).is_err() {
+            break;
+        }
+    }
+
+    unsafe {
+        let _ = FindCloseChangeNotification(handle);
+    }
+    info!("Profile watch stopped");
+}
+
+/// Re-register a replaced profile so WCS loads the new file.
+fn reload(path: &Path) -> bool {
+    info!("Override profile replaced on disk: {}", path.display());
+    match lg_profile::register_color_profile(path) {
+        Ok(()) => {
+            app_state::append_diagnostic_event(
+                "service",
+                "INFO",
+                "profile_file_changed",
+                &format!("profile={}", path.display()),
+            );
+            true
+        }
+        Err(e) => {
+            warn!("Could not re-register {}: {}", path.display(), e);
+            app_state::append_diagnostic_event(
+                "service",
+                "WARN",
+                "profile_file_changed",
+                &format!("profile={} error={}", path.display(), e),
+            );
+            false
+        }
+    }
+}
+
+/// True when `path` is the profile of the override in effect.
+fn is_active_override(path: &Path) -> bool {
+    let Some(active) = app_override::active_override() else {
+        return false;
+    };
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(&active.profile))
+}
+
+/// Toast that the active override profile was reloaded.
+pub(crate) fn notify(config: &Config) {
+    lg_notify::show_reapply_toast_quiet_aware(
+        config.toast_enabled,
+        &config.toast_title,
+        "Calibration updated",
+        lg_notify::QuietHours::parse(&config.toast_quiet_hours),
+        config.verbose,
+    );
+}
+
+#[cfg(test)]
+#[path = "tests/profile_watch_tests.rs"]
+mod tests;
@@ -0,0 +1,61 @@
+//! Display refresh that steps aside for fullscreen apps.
+//!
+//! The `WM_SETTINGCHANGE` broadcast and repaint that follow a reapply can make
+//! games stutter or drop out of exclusive fullscreen. With
+//! `postpone_refresh_in_fullscreen` on, the profile association is written
+//! immediately as usual, but the refresh is handed to a waiter thread that
+//! runs it once no fullscreen app holds the foreground. Repeated reapplies
+//! while a refresh is pending share the one waiter.
+
+use super::fullscreen::fullscreen_foreground;
+use lg_core::config::Config;
+use lg_core::state as app_state;
+use log::info;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// How often the waiter checks whether the fullscreen app has gone.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Set while a waiter thread holds a postponed refresh.
+static PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Run the soft display refresh now, or postpone it while a fullscreen app
+/// has the foreground.
+pub(crate) fn refresh_display(config: &Config) {
+    let broadcast_color = config.refresh_broadcast_color;
+    let invalidate = config.refresh_invalidate;
+
+    let fullscreen = config
+        .postpone_refresh_in_fullscreen
+        .then(fullscreen_foreground)
+        .flatten();
+    let Some(kind) = fullscreen else {
+        lg_profile::refresh_display(false, broadcast_color, invalidate);
+        return;
+    };
+
+    if PENDING.swap(true, Ordering::SeqCst) {
+        info!("Display refresh already postponed ({} fullscreen)", kind);
+        return;
+    }
+    info!(
+        "{} fullscreen app in the foreground — postponing display refresh",
+        kind
+    );
+    app_state::append_diagnostic_event(
+        "service",
+        "INFO",
+        "refresh_postponed",
+        &format!("fullscreen={}", kind),
+    );
+    thread::spawn(move || {
+        while fullscreen_foreground().is_some() {
+            thread::sleep(POLL_INTERVAL);
+        }
+        PENDING.store(false, Ordering::SeqCst);
+        info!("Fullscreen app left the foreground — running postponed display refresh");
+        lg_profile::refresh_display(false, broadcast_color, invalidate);
+    });
+}
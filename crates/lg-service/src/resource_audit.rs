@@ -0,0 +1,68 @@
+//! Resource audit: log the service's handle count and private bytes.
+//!
+//! A debugging aid for leaks that only show after weeks of uptime. Every
+//! `resource_audit_interval_minutes` the thread samples the process, logs
+//! the figures, and warns with a `resource_growth` diagnostic event when
+//! [`ResourceAudit`] sees them grow past normal fluctuation.
+
+use lg_core::config::Config;
+use lg_core::resource_audit::{self, ResourceAudit};
+use lg_core::shutdown::ShutdownToken;
+use lg_core::state as app_state;
+use log::{info, warn};
+use std::thread;
+use std::time::Duration;
+
+/// Spawn the audit thread. Returns `None` when the interval is 0.
+pub(crate) fn spawn(
+    config: &Config,
+    shutdown: &ShutdownToken,
+) -> Option<std::io::Result<thread::JoinHandle<()>>> {
+    if config.resource_audit_interval_minutes == 0 {
+        return None;
+    }
+    let interval = Duration::from_secs(config.resource_audit_interval_minutes * 60);
+    Some(super::supervisor::spawn(
+        "resource-audit",
+        shutdown,
+        move |shutdown| run(interval, shutdown),
+    ))
+}
+
+fn run(interval: Duration, shutdown: &ShutdownToken) {
+    let mut audit = ResourceAudit::default();
+    info!(
+        "Resource audit started: every {} minute(s)",
+        interval.as_secs() / 60
+    );
+
+    // The first sample, taken at startup, is the baseline.
+    let mut wait = Duration::ZERO;
+    while shutdown.sleep(wait) {
+        wait = interval;
+        let Some(sample) = resource_audit::sample() else {
+            warn!("Resource audit stopped: process counters unavailable");
+            return;
+        };
+        info!(
+            "Resources: {} handles, {} KiB private",
+            sample.handles,
+            sample.private_bytes / 1024
+        );
+        if let Some(growth) = audit.record(sample) {
+            warn!("Resource growth since start: {}", growth);
+            app_state::append_diagnostic_event(
+                "service",
+                "WARN",
+                "resource_growth",
+                &format!(
+                    "handles={} handles_delta={} private_bytes={} private_bytes_delta={}",
+                    growth.current.handles,
+                    growth.handles_delta(),
+                    growth.current.private_bytes,
+                    growth.private_bytes_delta()
+                ),
+            );
+        }
+    }
+}
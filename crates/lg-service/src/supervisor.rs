@@ -0,0 +1,168 @@
+//! Restarting background workers that panic.
+//!
+//! A panic in a worker used to end its thread quietly: a dead debounce
+//! worker meant no event ever led to a reapply again, while the service
+//! still showed as running. Every worker is now started through [`spawn`],
+//! which runs it under `catch_unwind` and restarts it after a panic with an
+//! exponential backoff. A worker that keeps panicking right after each
+//! restart is given up on and reported as `failed`.
+//!
+//! The state of every worker is kept in a registry that `service status`
+//! reads over the control pipe and the watchdog copies into the heartbeat.
+
+use lg_core::shutdown::ShutdownToken;
+use lg_core::state::{self as app_state, WorkerHealth};
+use log::{error, info, warn};
+use std::any::Any;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Delay before the first restart; doubles with every quick restart.
+const FIRST_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest delay between restarts.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A worker that ran this long before panicking gets a fresh budget.
+const STABLE_AFTER: Duration = Duration::from_secs(10 * 60);
+
+/// Restarts in a row (each panicking before [`STABLE_AFTER`]) before the
+/// supervisor gives up on a worker.
+const MAX_QUICK_RESTARTS: u32 = 5;
+
+static WORKERS: OnceLock<Mutex<Vec<WorkerHealth>>> = OnceLock::new();
+
+/// Decides whether and when a panicked worker is restarted.
+#[derive(Debug, Default)]
+pub(crate) struct RestartPolicy {
+    quick_restarts: u32,
+}
+
+impl RestartPolicy {
+    /// Called after a panic in a run that lasted `ran_for`. Returns the delay
+    /// before restarting, or `None` to give up.
+    pub(crate) fn on_panic(&mut self, ran_for: Duration) -> Option<Duration> {
+        if ran_for >= STABLE_AFTER {
+            self.quick_restarts = 0;
+        }
+        if self.quick_restarts >= MAX_QUICK_RESTARTS {
+            return None;
+        }
+        let backoff = FIRST_BACKOFF
+            .saturating_mul(1 << self.quick_restarts.min(16))
+            .min(MAX_BACKOFF);
+        self.quick_restarts += 1;
+        Some(backoff)
+    }
+}
+
+/// Start `body` on a named thread that restarts it after a panic. `body`
+/// is handed the shutdown token; returning normally ends the worker.
+pub(crate) fn spawn<F>(
+    name: &'static str,
+    shutdown: &ShutdownToken,
+    body: F,
+) -> io::Result<thread::JoinHandle<()>>
+where
+    F: FnMut(&ShutdownToken) + Send + 'static,
+{
+    let shutdown = shutdown.clone();
+    update(name, |w| w.state = "running".to_string());
+    thread::Builder::new()
+        .name(name.into())
+        .spawn(move || supervise(name, &shutdown, body))
+}
+
+/// Health of every worker started through [`spawn`].
+pub(crate) fn snapshot() -> Vec<WorkerHealth> {
+    registry().lock().map(|w| w.clone()).unwrap_or_default()
+}
+
+fn registry() -> &'static Mutex<Vec<WorkerHealth>> {
+    WORKERS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn update(name: &str, change: impl FnOnce(&mut WorkerHealth)) {
+    let Ok(mut workers) = registry().lock() else {
+        return;
+    };
+    let index = match workers.iter().position(|w| w.name == name) {
+        Some(index) => index,
+        None => {
+            workers.push(WorkerHealth {
+                name: name.to_string(),
+                ..WorkerHealth::default()
+            });
+            workers.len() - 1
+        }
+    };
+    change(&mut workers[index]);
+}
+
+/// Text of a panic payload (`panic!` with a literal or a formatted message).
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+fn supervise<F: FnMut(&ShutdownToken)>(name: &'static str, shutdown: &ShutdownToken, mut body: F) {
+    let mut policy = RestartPolicy::default();
+    loop {
+        update(name, |w| w.state = "running".to_string());
+        let started = Instant::now();
+        let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| body(shutdown))) else {
+            update(name, |w| w.state = "stopped".to_string());
+            return;
+        };
+
+        let message = panic_message(payload.as_ref());
+        error!("Worker {} panicked: {}", name, message);
+        app_state::append_diagnostic_event(
+            "service",
+            "ERROR",
+            "worker_panic",
+            &format!("worker={} message={}", name, message),
+        );
+        update(name, |w| {
+            w.last_panic = Some(message.clone());
+            w.last_panic_at =
+                Some(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+        });
+
+        let Some(backoff) = policy.on_panic(started.elapsed()) else {
+            error!(
+                "Worker {} keeps panicking; giving up after {} restarts",
+                name, MAX_QUICK_RESTARTS
+            );
+            app_state::append_diagnostic_event(
+                "service",
+                "ERROR",
+                "worker_failed",
+                &format!("worker={}", name),
+            );
+            update(name, |w| w.state = "failed".to_string());
+            return;
+        };
+
+        update(name, |w| w.state = "restarting".to_string());
+        warn!("Restarting worker {} in {:?}", name, backoff);
+        if !shutdown.sleep(backoff) {
+            update(name, |w| w.state = "stopped".to_string());
+            return;
+        }
+        update(name, |w| w.restarts += 1);
+        info!("Worker {} restarted", name);
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/supervisor_tests.rs"]
+mod tests;
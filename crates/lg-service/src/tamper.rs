@@ -0,0 +1,316 @@
+//! Tamper watch: notice when another program replaces our profile.
+//!
+//! Vendor control panels, game launchers and calibration tools like to set
+//! their own default profile, which quietly brings the dimming back. With
+//! `tamper_watch` set, a thread waits on `RegNotifyChangeKeyValue` for the
+//! WCS association keys and, when they change, compares the default profile
+//! of every monitor we last applied to against what we set. A mismatch is
+//! logged, toasted, or reasserted depending on the configured action.
+//!
+//! Our own reapplies toggle the association and would look like tampering,
+//! so checks are skipped while one is in progress, and reasserts are
+//! rate-limited so two tools fighting over a display can't loop forever.
+
+use super::brightness_guard::RestoreRateLimiter;
+use super::events::DisplayEvent;
+use lg_core::config::Config;
+use lg_core::shutdown::ShutdownToken;
+use lg_core::state as app_state;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{CloseHandle, BOOL, HANDLE, WAIT_OBJECT_0};
+use windows::Win32::System::Registry::{
+    RegNotifyChangeKeyValue, HKEY, REG_NOTIFY_CHANGE_LAST_SET, REG_NOTIFY_CHANGE_NAME,
+};
+use windows::Win32::System::Threading::{CreateEventW, WaitForSingleObject};
+
+/// Monitor device class (`GUID_DEVCLASS_MONITOR`) — legacy `Wcs*`
+/// associations live in its driver keys.
+const MONITOR_CLASS_KEY: &str =
+    r"SYSTEM\CurrentControlSet\Control\Class\{4d36e96e-e325-11ce-bfc1-08002be10318}";
+
+/// System-wide `ColorProfile*` display associations.
+const DISPLAY_ASSOCIATIONS_KEY: &str =
+    r"SOFTWARE\Microsoft\Windows NT\CurrentVersion\ICM\ProfileAssociations\Display";
+
+/// How often the wait wakes up to check for shutdown.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Quiet period after a change before comparing, so a tool that writes
+/// several values in a row is only reported once.
+const SETTLE: Duration = Duration::from_millis(1500);
+
+/// Reasserts allowed per minute before the watch backs off.
+const MAX_REASSERTS_PER_MINUTE: u32 = 3;
+
+/// What to do when the profile was changed behind our back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TamperAction {
+    Off,
+    /// Record it in the log and diagnostics only.
+    Log,
+    /// Also show a toast.
+    Notify,
+    /// Put our profile back immediately.
+    Reassert,
+}
+
+impl TamperAction {
+    /// Parse `tamper_watch`; unknown values turn the watch off.
+    pub(crate) fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "log" => TamperAction::Log,
+            "notify" => TamperAction::Notify,
+            "reassert" => TamperAction::Reassert,
+            _ => TamperAction::Off,
+        }
+    }
+}
+
+/// Set while the service itself is changing associations.
+static APPLYING: AtomicBool = AtomicBool::new(false);
+
+/// Device key → profile file name we last made the default.
+static EXPECTED: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+fn expected() -> &'static Mutex<HashMap<String, String>> {
+    EXPECTED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Marks an apply in progress for as long as it is held.
+pub(crate) struct ApplyGuard;
+
+impl ApplyGuard {
+    pub(crate) fn begin() -> Self {
+        APPLYING.store(true, Ordering::SeqCst);
+        ApplyGuard
+    }
+}
+
+impl Drop for ApplyGuard {
+    fn drop(&mut self) {
+        APPLYING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// True while the service is applying a profile itself.
+pub(crate) fn applying() -> bool {
+    APPLYING.load(Ordering::SeqCst)
+}
+
+/// Remember that `profile_path` is now the default for `device_key`.
+pub(crate) fn record_applied(device_key: &str, profile_path: &Path) {
+    let Some(name) = profile_path.file_name() else {
+        return;
+    };
+    if let Ok(mut map) = expected().lock() {
+        map.insert(device_key.to_string(), name.to_string_lossy().to_string());
+    }
+}
+
+/// Stop watching `device_key` (e.g. an app override removed our profile).
+pub(crate) fn forget(device_key: &str) {
+    if let Ok(mut map) = expected().lock() {
+        map.remove(device_key);
+    }
+}
+
+/// True when `current` is not the profile we set. A device with no default
+/// at all counts as tampered: something removed our association.
+pub(crate) fn is_tampered(expected: &str, current: Option<&str>) -> bool {
+    !current.is_some_and(|c| c.eq_ignore_ascii_case(expected))
+}
+
+/// Spawn the watch thread. Returns `None` when `tamper_watch` is off.
+pub(crate) fn spawn(
+    config: &Config,
+    shutdown: &ShutdownToken,
+    tx: mpsc::Sender<DisplayEvent>,
+) -> Option<std::io::Result<thread::JoinHandle<()>>> {
+    let action = TamperAction::parse(&config.tamper_watch);
+    if action == TamperAction::Off {
+        return None;
+    }
+    let config = config.clone();
+    Some(super::supervisor::spawn(
+        "tamper-watch",
+        shutdown,
+        move |shutdown| run(&config, action, shutdown, &tx),
+    ))
+}
+
+fn open_watched_keys() -> Vec<winreg::RegKey> {
+    use winreg::enums::{HKEY_LOCAL_MACHINE, KEY_NOTIFY, KEY_READ};
+
+    let hklm = winreg::RegKey::predef(HKEY_LOCAL_MACHINE);
+    [MONITOR_CLASS_KEY, DISPLAY_ASSOCIATIONS_KEY]
+        .into_iter()
+        .filter_map(
+            |path| match hklm.open_subkey_with_flags(path, KEY_READ | KEY_NOTIFY) {
+                Ok(key) => Some(key),
+                Err(e) => {
+                    // The modern key only exists once something has used it.
+                    info!("Tamper watch: not watching HKLM\\{} ({})", path, e);
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+/// Arm a one-shot change notification on every key.
+fn arm(keys: &[winreg::RegKey], event: HANDLE) -> bool {
+    keys.iter().all(|key| {
+        let status = unsafe {
+            RegNotifyChangeKeyValue(
+                HKEY(key.raw_handle() as _),
+                BOOL::from(true),
+                REG_NOTIFY_CHANGE_NAME | REG_NOTIFY_CHANGE_LAST_SET,
+                event,
+                BOOL::from(true),
+            )
+        };
+        if status.is_err() {
+            warn!("RegNotifyChangeKeyValue failed (status={})", status.0);
+        }
+        status.is_ok()
+    })
+}
+
+fn run(
+    config: &Config,
+    action: TamperAction,
+    shutdown: &ShutdownToken,
+    tx: &mpsc::Sender<DisplayEvent>,
+) {
+    let keys = open_watched_keys();
+    if keys.is_empty() {
+        warn!("Tamper watch disabled: no association keys could be opened");
+        return;
+    }
+    let event = match unsafe { CreateEventW(None, false, false, PCWSTR::null()) } {
+        Ok(h) => h,
+        Err(e) => {
+            warn!("Tamper watch disabled: CreateEventW failed: {}", e);
+            return;
+        }
+    };
+    let mut limiter = RestoreRateLimiter::new(MAX_REASSERTS_PER_MINUTE);
+    let mut throttled = false;
+
+    info!(
+        "Tamper watch started: action={:?} keys={}",
+        action,
+        keys.len()
+    );
+
+    let mut armed = arm(&keys, event);
+    'outer: while armed && !shutdown.is_cancelled() {
+        loop {
+            if shutdown.is_cancelled() {
+                break 'outer;
+            }
+            let wait = unsafe { WaitForSingleObject(event, POLL_INTERVAL.as_millis() as u32) };
+            if wait == WAIT_OBJECT_0 {
+                break;
+            }
+        }
+
+        // Let the writer finish, then re-arm before comparing so a change
+        // made during the comparison isn't lost.
+        thread::sleep(SETTLE);
+        armed = arm(&keys, event);
+        if applying() {
+            continue;
+        }
+        let tampered = find_tampered();
+        if tampered.is_empty() {
+            continue;
+        }
+
+        for (device_key, expected, current) in &tampered {
+            warn!(
+                "Color profile for {} changed by another program: expected '{}', now '{}'",
+                device_key,
+                expected,
+                current.as_deref().unwrap_or("none")
+            );
+            app_state::append_diagnostic_event(
+                "service",
+                "WARN",
+                "profile_tampered",
+                &format!(
+                    "device={} expected={} current={} action={:?}",
+                    device_key,
+                    expected,
+                    current.as_deref().unwrap_or("none"),
+                    action
+                ),
+            );
+        }
+
+        match action {
+            TamperAction::Off | TamperAction::Log => {}
+            TamperAction::Notify => lg_notify::show_reapply_toast_quiet_aware(
+                true,
+                &config.toast_title,
+                &format!(
+                    "Another program changed the color profile on {} monitor(s)",
+                    tampered.len()
+                ),
+                lg_notify::QuietHours::parse(&config.toast_quiet_hours),
+                config.verbose,
+            ),
+            TamperAction::Reassert => {
+                if limiter.try_acquire(Instant::now()) {
+                    throttled = false;
+                    if tx.send(DisplayEvent::ProfileTampered).is_err() {
+                        break;
+                    }
+                } else if !throttled {
+                    throttled = true;
+                    warn!("Tamper watch: reassert rate limit reached, leaving the profile alone");
+                    app_state::append_diagnostic_event(
+                        "service",
+                        "WARN",
+                        "tamper_rate_limited",
+                        &format!("max_per_minute={}", MAX_REASSERTS_PER_MINUTE),
+                    );
+                }
+            }
+        }
+    }
+
+    unsafe {
+        let _ = CloseHandle(event);
+    }
+    info!("Tamper watch stopped");
+}
+
+/// Devices whose current default differs from what we last applied, as
+/// `(device_key, expected, current)`.
+pub(crate) fn find_tampered() -> Vec<(String, String, Option<String>)> {
+    let snapshot: Vec<(String, String)> = match expected().lock() {
+        Ok(map) => map.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+        Err(_) => return Vec::new(),
+    };
+    snapshot
+        .into_iter()
+        .filter_map(|(device_key, expected)| {
+            // Unplugged or unresolvable displays are not tampering.
+            let current = lg_profile::current_default_profile_name(&device_key).ok()?;
+            is_tampered(&expected, current.as_deref()).then_some((device_key, expected, current))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "tests/tamper_tests.rs"]
+mod tests;
@@ -0,0 +1,123 @@
+//! Structured ownership of the event loop's background threads.
+//!
+//! Every background worker the event loop starts (debounce worker, control
+//! pipe, pollers, watchers) is registered in one [`TaskSet`] instead of
+//! living in its own `Option<io::Result<JoinHandle>>` local. Once the
+//! shutdown token is cancelled the set joins every task in reverse
+//! registration order, logging any that panicked, and gives up on tasks
+//! still running at the deadline so a stop never hangs. A new worker only
+//! has to be added in one place to get the same lifetime as the rest.
+//!
+//! This is plain threads rather than an async executor: most of the work is
+//! blocking COM, WMI and Win32 calls that would need a blocking pool anyway.
+
+use lg_core::shutdown::ShutdownToken;
+use log::{error, info, warn};
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often [`TaskSet::join_all`] checks whether a task has finished.
+const JOIN_POLL: Duration = Duration::from_millis(20);
+
+/// A named background thread owned by a [`TaskSet`].
+struct Task {
+    name: &'static str,
+    handle: thread::JoinHandle<()>,
+}
+
+/// Background threads that share the event loop's lifetime.
+#[derive(Default)]
+pub(crate) struct TaskSet {
+    tasks: Vec<Task>,
+}
+
+/// What [`TaskSet::join_all`] found.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct JoinOutcome {
+    /// Tasks whose thread panicked outside a supervisor.
+    pub(crate) panicked: Vec<&'static str>,
+    /// Tasks still running at the deadline; left detached.
+    pub(crate) abandoned: Vec<&'static str>,
+}
+
+impl TaskSet {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn `f` on a named, supervised thread owned by the set (see
+    /// [`supervisor::spawn`](super::supervisor::spawn)).
+    pub(crate) fn spawn<F>(
+        &mut self,
+        name: &'static str,
+        shutdown: &ShutdownToken,
+        f: F,
+    ) -> io::Result<()>
+    where
+        F: FnMut(&ShutdownToken) + Send + 'static,
+    {
+        let handle = super::supervisor::spawn(name, shutdown, f)?;
+        self.tasks.push(Task { name, handle });
+        Ok(())
+    }
+
+    /// Take ownership of a thread started by one of the modules' `spawn`
+    /// functions. `None` means the feature is off; a spawn error is logged
+    /// and the service carries on without it.
+    pub(crate) fn adopt(
+        &mut self,
+        name: &'static str,
+        spawned: Option<io::Result<thread::JoinHandle<()>>>,
+    ) {
+        match spawned {
+            Some(Ok(handle)) => self.tasks.push(Task { name, handle }),
+            Some(Err(e)) => warn!("Failed to start {}: {}", name, e),
+            None => {}
+        }
+    }
+
+    /// Names of the tasks currently owned, in registration order.
+    pub(crate) fn names(&self) -> Vec<&'static str> {
+        self.tasks.iter().map(|t| t.name).collect()
+    }
+
+    /// Join every task, last registered first, spending at most `timeout`
+    /// in total.
+    ///
+    /// Reverse order lets a consumer registered first (the debounce worker)
+    /// drain until the producers registered after it have exited and dropped
+    /// their senders. The shutdown token must already be cancelled; tasks
+    /// that haven't finished by the deadline are left running and reported.
+    pub(crate) fn join_all(self, timeout: Duration) -> JoinOutcome {
+        let deadline = Instant::now() + timeout;
+        let mut outcome = JoinOutcome::default();
+        for task in self.tasks.into_iter().rev() {
+            while !task.handle.is_finished() && Instant::now() < deadline {
+                thread::sleep(JOIN_POLL);
+            }
+            if !task.handle.is_finished() {
+                outcome.abandoned.push(task.name);
+                continue;
+            }
+            if task.handle.join().is_err() {
+                error!("Background task {} panicked", task.name);
+                outcome.panicked.push(task.name);
+            }
+        }
+        if !outcome.abandoned.is_empty() {
+            warn!(
+                "Background tasks still running after {:?}, not waiting for them: {}",
+                timeout,
+                outcome.abandoned.join(", ")
+            );
+        } else if outcome.panicked.is_empty() {
+            info!("All background tasks stopped");
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/tasks_tests.rs"]
+mod tests;
@@ -0,0 +1,238 @@
+//! Opt-in weekly usage ping.
+//!
+//! Off unless `telemetry_enabled` is on and `telemetry_endpoint` is an
+//! https URL. The worker waits a few minutes after startup, then checks
+//! every hour whether a ping is due (see [`TelemetryState::ping_due`]) and
+//! POSTs the [`TelemetryPayload`] through WinHTTP with the system proxy
+//! settings. Every attempt is recorded in `state\telemetry.toml` and the
+//! diagnostics log; a failure is never retried within a day.
+
+use super::find_matching_monitors_for_config;
+use lg_core::config::Config;
+use lg_core::shutdown::ShutdownToken;
+use lg_core::state as app_state;
+use lg_core::telemetry::{self, TelemetryPayload, TelemetryState};
+use lg_core::wide::WideString;
+use log::{info, warn};
+use std::error::Error;
+use std::thread;
+use std::time::Duration;
+use windows::core::{w, PCWSTR};
+use windows::Win32::Networking::WinHttp::{
+    WinHttpCloseHandle, WinHttpConnect, WinHttpOpen, WinHttpOpenRequest, WinHttpQueryHeaders,
+    WinHttpReceiveResponse, WinHttpSendRequest, WinHttpSetTimeouts,
+    WINHTTP_ACCESS_TYPE_AUTOMATIC_PROXY, WINHTTP_FLAG_SECURE, WINHTTP_QUERY_FLAG_NUMBER,
+    WINHTTP_QUERY_STATUS_CODE,
+};
+
+/// Delay after service start before the first check, so a ping never
+/// competes with the startup reapply.
+const STARTUP_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// How often the worker checks whether a ping is due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Resolve, connect, send and receive timeout for one request, in ms.
+const REQUEST_TIMEOUT_MS: i32 = 15_000;
+
+/// What a ping would send right now.
+pub fn payload(config: &Config, version: &str) -> TelemetryPayload {
+    let models = find_matching_monitors_for_config(config)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|m| telemetry::model_code(&m.manufacturer_id, &m.product_code))
+        .collect::<Vec<_>>();
+    let metrics_cfg = app_state::load_automation_config().metrics;
+    let metrics = if metrics_cfg.collect_success_rate {
+        app_state::compute_apply_latency_metrics(metrics_cfg.rolling_window)
+    } else {
+        app_state::ApplyLatencyMetrics::default()
+    };
+    TelemetryPayload::new(version, &windows_build(), models, &metrics)
+}
+
+/// `CurrentBuildNumber.UBR` from the registry, e.g. `22631.4317`.
+pub fn windows_build() -> String {
+    use winreg::enums::*;
+    use winreg::RegKey;
+
+    let Ok(key) = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(r"SOFTWARE\Microsoft\Windows NT\CurrentVersion")
+    else {
+        return "unknown".to_string();
+    };
+    let build: String = key
+        .get_value("CurrentBuildNumber")
+        .unwrap_or_else(|_| "unknown".to_string());
+    match key.get_value::<u32, _>("UBR") {
+        Ok(ubr) => format!("{}.{}", build, ubr),
+        Err(_) => build,
+    }
+}
+
+/// Spawn the ping worker. Returns `None` when the ping is off.
+pub(crate) fn spawn(
+    config: &Config,
+    shutdown: &ShutdownToken,
+    version: &'static str,
+) -> Option<std::io::Result<thread::JoinHandle<()>>> {
+    if !config.telemetry_enabled {
+        return None;
+    }
+    if telemetry::split_https_url(&config.telemetry_endpoint).is_none() {
+        warn!(
+            "Usage ping disabled: telemetry_endpoint \"{}\" is not an https URL",
+            config.telemetry_endpoint
+        );
+        return None;
+    }
+    let config = config.clone();
+    Some(super::supervisor::spawn(
+        "telemetry",
+        shutdown,
+        move |shutdown| run(&config, shutdown, version),
+    ))
+}
+
+fn run(config: &Config, shutdown: &ShutdownToken, version: &str) {
+    info!(
+        "Usage ping enabled: weekly to {}",
+        config.telemetry_endpoint
+    );
+    if !shutdown.sleep(STARTUP_DELAY) {
+        return;
+    }
+    loop {
+        let mut state = telemetry::load_telemetry_state();
+        let now = chrono::Utc::now();
+        if state.ping_due(now) {
+            send(config, version, &mut state, now);
+        }
+        if !shutdown.sleep(CHECK_INTERVAL) {
+            break;
+        }
+    }
+}
+
+/// Send one ping and record the outcome.
+fn send(
+    config: &Config,
+    version: &str,
+    state: &mut TelemetryState,
+    now: chrono::DateTime<chrono::Utc>,
+) {
+    let body = payload(config, version).to_json();
+    let result = match post_json(&config.telemetry_endpoint, &body) {
+        Ok(status) if (200..300).contains(&status) => Ok(()),
+        Ok(status) => Err(format!("HTTP {}", status)),
+        Err(e) => Err(e.to_string()),
+    };
+    match &result {
+        Ok(()) => {
+            info!("Usage ping sent");
+            app_state::append_diagnostic_event("service", "INFO", "telemetry_ping", "sent");
+        }
+        Err(e) => {
+            warn!("Usage ping failed: {}", e);
+            app_state::append_diagnostic_event(
+                "service",
+                "WARN",
+                "telemetry_ping",
+                &format!("failed: {}", e),
+            );
+        }
+    }
+    state.record(now, result);
+    if let Err(e) = telemetry::save_telemetry_state(state) {
+        warn!("Could not save usage ping state: {}", e);
+    }
+}
+
+/// WinHTTP handle closed on drop.
+struct Handle(*mut std::ffi::c_void);
+
+impl Handle {
+    fn new(raw: *mut std::ffi::c_void, what: &str) -> Result<Self, Box<dyn Error>> {
+        if raw.is_null() {
+            Err(format!("{} failed: {}", what, windows::core::Error::from_win32()).into())
+        } else {
+            Ok(Self(raw))
+        }
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = WinHttpCloseHandle(self.0);
+        }
+    }
+}
+
+/// POST `body` as JSON to the https `url` and return the HTTP status.
+fn post_json(url: &str, body: &str) -> Result<u32, Box<dyn Error>> {
+    let (host, port, path) =
+        telemetry::split_https_url(url).ok_or("telemetry_endpoint is not an https URL")?;
+    let host = WideString::new(host);
+    let path = WideString::new(path);
+    let headers: Vec<u16> = "Content-Type: application/json\r\n"
+        .encode_utf16()
+        .collect();
+
+    unsafe {
+        let session = Handle::new(
+            WinHttpOpen(
+                w!("lg-ultragear-dimming-fix"),
+                WINHTTP_ACCESS_TYPE_AUTOMATIC_PROXY,
+                PCWSTR::null(),
+                PCWSTR::null(),
+                0,
+            ),
+            "WinHttpOpen",
+        )?;
+        WinHttpSetTimeouts(
+            session.0,
+            REQUEST_TIMEOUT_MS,
+            REQUEST_TIMEOUT_MS,
+            REQUEST_TIMEOUT_MS,
+            REQUEST_TIMEOUT_MS,
+        )?;
+        let connection = Handle::new(
+            WinHttpConnect(session.0, host.as_pcwstr(), port, 0),
+            "WinHttpConnect",
+        )?;
+        let request = Handle::new(
+            WinHttpOpenRequest(
+                connection.0,
+                w!("POST"),
+                path.as_pcwstr(),
+                PCWSTR::null(),
+                PCWSTR::null(),
+                std::ptr::null(),
+                WINHTTP_FLAG_SECURE,
+            ),
+            "WinHttpOpenRequest",
+        )?;
+        WinHttpSendRequest(
+            request.0,
+            Some(&headers),
+            Some(body.as_ptr().cast()),
+            body.len() as u32,
+            body.len() as u32,
+            0,
+        )?;
+        WinHttpReceiveResponse(request.0, std::ptr::null_mut())?;
+
+        let mut status = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        WinHttpQueryHeaders(
+            request.0,
+            WINHTTP_QUERY_STATUS_CODE | WINHTTP_QUERY_FLAG_NUMBER,
+            PCWSTR::null(),
+            Some((&mut status as *mut u32).cast()),
+            &mut size,
+            std::ptr::null_mut(),
+        )?;
+        Ok(status)
+    }
+}
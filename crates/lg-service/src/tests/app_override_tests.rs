@@ -0,0 +1,57 @@
+use super::*;
+
+fn overrides(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+// ── Tracker ──────────────────────────────────────────────────────
+
+#[test]
+fn tracker_activates_on_matching_process() {
+    let mut t = OverrideTracker::new(&overrides(&[("Photoshop.exe", "factory.icm")]));
+    assert!(t.on_foreground(r"C:\Program Files\Adobe\Photoshop.exe"));
+    assert_eq!(
+        t.active(),
+        Some(ActiveOverride {
+            process: "photoshop".into(),
+            profile: "factory.icm".into(),
+        })
+    );
+}
+
+#[test]
+fn tracker_reports_change_only_once() {
+    let mut t = OverrideTracker::new(&overrides(&[("photoshop.exe", "none")]));
+    assert!(t.on_foreground("photoshop.exe"));
+    assert!(!t.on_foreground("PHOTOSHOP.EXE"));
+}
+
+#[test]
+fn tracker_clears_when_focus_leaves() {
+    let mut t = OverrideTracker::new(&overrides(&[("photoshop.exe", "none")]));
+    assert!(t.on_foreground("photoshop.exe"));
+    assert!(t.on_foreground("explorer.exe"));
+    assert_eq!(t.active(), None);
+    assert!(!t.on_foreground("notepad.exe"));
+}
+
+#[test]
+fn tracker_switches_between_overridden_apps() {
+    let mut t = OverrideTracker::new(&overrides(&[
+        ("photoshop.exe", "factory.icm"),
+        ("lightroom.exe", "none"),
+    ]));
+    assert!(t.on_foreground("photoshop.exe"));
+    assert!(t.on_foreground("lightroom.exe"));
+    assert_eq!(t.active().unwrap().profile, "none");
+}
+
+#[test]
+fn tracker_ignores_blank_entries() {
+    let mut t = OverrideTracker::new(&overrides(&[("", "factory.icm"), ("app.exe", "  ")]));
+    assert!(!t.on_foreground("app.exe"));
+    assert!(!t.on_foreground(""));
+}
@@ -0,0 +1,60 @@
+use super::*;
+
+// ── Hysteresis ───────────────────────────────────────────────────
+
+#[test]
+fn should_restore_ignores_drops_within_threshold() {
+    assert!(!should_restore(50, 50, 3));
+    assert!(!should_restore(47, 50, 3));
+}
+
+#[test]
+fn should_restore_triggers_beyond_threshold() {
+    assert!(should_restore(46, 50, 3));
+    assert!(should_restore(0, 50, 3));
+}
+
+#[test]
+fn should_restore_never_undoes_user_increase() {
+    assert!(!should_restore(80, 50, 0));
+}
+
+#[test]
+fn should_restore_zero_threshold_restores_any_drop() {
+    assert!(should_restore(49, 50, 0));
+}
+
+// ── Rate limiter ─────────────────────────────────────────────────
+
+#[test]
+fn rate_limiter_allows_up_to_budget() {
+    let mut limiter = RestoreRateLimiter::new(2);
+    let now = Instant::now();
+    assert!(limiter.try_acquire(now));
+    assert!(limiter.try_acquire(now));
+    assert!(!limiter.try_acquire(now));
+}
+
+#[test]
+fn rate_limiter_frees_budget_after_window() {
+    let mut limiter = RestoreRateLimiter::new(1);
+    let start = Instant::now();
+    assert!(limiter.try_acquire(start));
+    assert!(!limiter.try_acquire(start + Duration::from_secs(30)));
+    assert!(limiter.try_acquire(start + Duration::from_secs(61)));
+}
+
+#[test]
+fn rate_limiter_zero_means_unlimited() {
+    let mut limiter = RestoreRateLimiter::new(0);
+    let now = Instant::now();
+    for _ in 0..100 {
+        assert!(limiter.try_acquire(now));
+    }
+}
+
+#[test]
+fn spawn_returns_none_when_disabled() {
+    let cfg = Config::default();
+    assert!(spawn(&cfg, &ShutdownToken::new()).is_none());
+}
@@ -0,0 +1,31 @@
+use super::*;
+
+// ── Catching panics ──────────────────────────────────────────────
+
+#[test]
+fn catch_passes_the_result_through() {
+    assert_eq!(catch("test", || 7), Ok(7));
+    assert_eq!(guard("test", || "ok"), Some("ok"));
+}
+
+#[test]
+fn catch_turns_a_panic_into_its_message() {
+    let result: Result<(), String> = catch("test", || panic!("bad state {}", 3));
+    assert_eq!(result, Err("bad state 3".to_string()));
+    assert_eq!(guard("test", || -> u32 { panic!("boom") }), None);
+}
+
+// ── Exit codes ───────────────────────────────────────────────────
+
+#[test]
+fn service_errors_map_to_distinct_exit_codes() {
+    let event_loop = ServiceError::EventLoop("GetMessageW failed".into());
+    let panicked = ServiceError::Panicked("index out of bounds".to_string());
+    assert_eq!(event_loop.exit_code(), 1);
+    assert_eq!(panicked.exit_code(), 2);
+    assert_eq!(
+        panicked.to_string(),
+        "service thread panicked: index out of bounds"
+    );
+    assert!(event_loop.source().is_some());
+}
@@ -0,0 +1,23 @@
+use super::*;
+
+// ── Toast ────────────────────────────────────────────────────────
+
+#[test]
+fn toast_body_names_period_and_preset() {
+    assert_eq!(
+        toast_body(DayPeriod::Night, "reader"),
+        "Switched to night profile (reader)"
+    );
+    assert_eq!(
+        toast_body(DayPeriod::Day, "gamma22"),
+        "Switched to day profile (gamma22)"
+    );
+}
+
+// ── Spawn ────────────────────────────────────────────────────────
+
+#[test]
+fn spawn_is_noop_without_schedule_presets() {
+    let (tx, _rx) = mpsc::channel::<DisplayEvent>();
+    assert!(spawn(&Config::default(), &ShutdownToken::new(), tx).is_none());
+}
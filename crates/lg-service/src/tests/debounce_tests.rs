@@ -0,0 +1,555 @@
+use super::*;
+use crate::{
+    EVENT_AUTOMATION_POLL, EVENT_DEVICE_ARRIVAL, EVENT_DEVNODES_CHANGED, EVENT_HOTKEY_REAPPLY,
+    EVENT_SESSION_UNLOCK,
+};
+
+const STABILIZE: Duration = Duration::from_millis(1500);
+const DELAY: Duration = Duration::from_millis(3000);
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn device() -> DisplayEvent {
+    DisplayEvent::DeviceArrival { device_key: None }
+}
+
+fn arrival(key: &str) -> DisplayEvent {
+    DisplayEvent::DeviceArrival {
+        device_key: Some(key.to_string()),
+    }
+}
+
+fn debouncer() -> Debouncer {
+    Debouncer::new(STABILIZE, DELAY)
+}
+
+// ── Burst coalescing ─────────────────────────────────────────────
+
+#[test]
+fn first_event_opens_the_stabilize_window() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    assert_eq!(d.event(device(), t0), Action::Wait(Some(t0 + STABILIZE)));
+}
+
+#[test]
+fn burst_is_coalesced_into_one_batch() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    d.event(device(), t0);
+    d.event(DisplayEvent::DevNodesChanged, t0 + ms(200));
+    d.event(DisplayEvent::SessionUnlock, t0 + ms(900));
+    assert_eq!(
+        d.timeout(t0 + STABILIZE),
+        Action::Settled(EVENT_DEVICE_ARRIVAL | EVENT_DEVNODES_CHANGED | EVENT_SESSION_UNLOCK)
+    );
+}
+
+#[test]
+fn later_events_do_not_extend_the_window() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    d.event(device(), t0);
+    assert_eq!(
+        d.event(DisplayEvent::DevNodesChanged, t0 + ms(1400)),
+        Action::Wait(Some(t0 + STABILIZE))
+    );
+}
+
+#[test]
+fn early_timeout_keeps_waiting_for_the_deadline() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    d.event(device(), t0);
+    assert_eq!(d.timeout(t0 + ms(10)), Action::Wait(Some(t0 + STABILIZE)));
+}
+
+#[test]
+fn event_past_the_deadline_settles_the_batch() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    d.event(DisplayEvent::AutomationPoll, t0);
+    assert_eq!(
+        d.event(DisplayEvent::SessionUnlock, t0 + STABILIZE + ms(5)),
+        Action::Settled(EVENT_AUTOMATION_POLL | EVENT_SESSION_UNLOCK)
+    );
+}
+
+#[test]
+fn user_request_settles_quickly() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    assert_eq!(
+        d.event(DisplayEvent::HotkeyReapply, t0),
+        Action::Wait(Some(t0 + USER_SETTLE))
+    );
+}
+
+#[test]
+fn window_is_chosen_by_the_first_event() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    d.event(device(), t0);
+    assert_eq!(
+        d.event(DisplayEvent::RemoteReapply, t0 + ms(10)),
+        Action::Wait(Some(t0 + STABILIZE))
+    );
+}
+
+#[test]
+fn zero_stabilize_settles_immediately() {
+    let t0 = Instant::now();
+    let mut d = Debouncer::new(Duration::ZERO, DELAY);
+    assert_eq!(
+        d.event(DisplayEvent::AutomationPoll, t0),
+        Action::Settled(EVENT_AUTOMATION_POLL)
+    );
+}
+
+// ── Validation ───────────────────────────────────────────────────
+
+#[test]
+fn device_only_batch_is_validated() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    d.event(device(), t0);
+    d.timeout(t0 + STABILIZE);
+    assert_eq!(
+        d.settled(t0 + STABILIZE),
+        Action::Validate(EVENT_DEVICE_ARRIVAL)
+    );
+}
+
+#[test]
+fn missing_monitor_skips_the_reapply() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    d.event(device(), t0);
+    d.timeout(t0 + STABILIZE);
+    d.settled(t0 + STABILIZE);
+    assert_eq!(d.validated(false, t0 + STABILIZE), Action::Wait(None));
+    // Idle again: the next event opens a fresh window.
+    let t1 = t0 + ms(10_000);
+    assert_eq!(d.event(device(), t1), Action::Wait(Some(t1 + STABILIZE)));
+}
+
+#[test]
+fn present_monitor_starts_the_initialization_delay() {
+    let t0 = Instant::now();
+    let t1 = t0 + STABILIZE;
+    let mut d = debouncer();
+    d.event(device(), t0);
+    d.timeout(t1);
+    d.settled(t1);
+    assert_eq!(d.validated(true, t1), Action::Wait(Some(t1 + DELAY)));
+}
+
+#[test]
+fn session_batch_skips_validation() {
+    let t0 = Instant::now();
+    let t1 = t0 + STABILIZE;
+    let mut d = debouncer();
+    d.event(device(), t0);
+    d.event(DisplayEvent::SessionUnlock, t0 + ms(100));
+    d.timeout(t1);
+    assert_eq!(d.settled(t1), Action::Wait(Some(t1 + DELAY)));
+}
+
+// ── Initialization delay and trailing events ─────────────────────
+
+#[test]
+fn reapply_runs_when_the_delay_ends() {
+    let t0 = Instant::now();
+    let t1 = t0 + STABILIZE;
+    let mut d = debouncer();
+    d.event(DisplayEvent::SessionUnlock, t0);
+    d.timeout(t1);
+    d.settled(t1);
+    assert_eq!(d.timeout(t1 + ms(100)), Action::Wait(Some(t1 + DELAY)));
+    assert_eq!(d.timeout(t1 + DELAY), Action::Reapply(EVENT_SESSION_UNLOCK));
+}
+
+#[test]
+fn trailing_event_cuts_the_delay_short() {
+    let t0 = Instant::now();
+    let t1 = t0 + STABILIZE;
+    let mut d = debouncer();
+    d.event(DisplayEvent::SessionUnlock, t0);
+    d.timeout(t1);
+    d.settled(t1);
+    assert_eq!(
+        d.event(DisplayEvent::DevNodesChanged, t1 + ms(500)),
+        Action::Reapply(EVENT_SESSION_UNLOCK)
+    );
+}
+
+#[test]
+fn zero_delay_reapplies_straight_away() {
+    let t0 = Instant::now();
+    let mut d = Debouncer::new(STABILIZE, Duration::ZERO);
+    d.event(DisplayEvent::SessionUnlock, t0);
+    d.timeout(t0 + STABILIZE);
+    assert_eq!(
+        d.settled(t0 + STABILIZE),
+        Action::Reapply(EVENT_SESSION_UNLOCK)
+    );
+}
+
+#[test]
+fn background_trigger_has_no_initialization_delay() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    d.event(DisplayEvent::AutomationPoll, t0);
+    d.timeout(t0 + STABILIZE);
+    assert_eq!(
+        d.settled(t0 + STABILIZE),
+        Action::Reapply(EVENT_AUTOMATION_POLL)
+    );
+}
+
+#[test]
+fn brightness_only_batch_does_not_reapply() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    d.event(DisplayEvent::HotkeyBrightness, t0);
+    d.timeout(t0 + USER_SETTLE);
+    assert_eq!(d.settled(t0 + USER_SETTLE), Action::Wait(None));
+}
+
+#[test]
+fn brightness_with_another_trigger_still_reapplies() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    d.event(DisplayEvent::HotkeyBrightness, t0);
+    d.event(DisplayEvent::HotkeyReapply, t0 + ms(50));
+    d.timeout(t0 + USER_SETTLE);
+    assert_eq!(
+        d.settled(t0 + USER_SETTLE),
+        Action::Reapply(EVENT_HOTKEY_BRIGHTNESS | EVENT_HOTKEY_REAPPLY)
+    );
+}
+
+// ── Cooldown ─────────────────────────────────────────────────────
+
+#[test]
+fn events_during_the_reapply_are_dropped() {
+    let t0 = Instant::now();
+    let mut d = Debouncer::new(STABILIZE, Duration::ZERO);
+    d.event(DisplayEvent::SessionUnlock, t0);
+    d.timeout(t0 + STABILIZE);
+    d.settled(t0 + STABILIZE);
+    assert_eq!(d.event(device(), t0 + ms(2000)), Action::Wait(None));
+    assert_eq!(d.timeout(t0 + ms(9000)), Action::Wait(None));
+    assert_eq!(d.reapplied(t0 + ms(4000)), Action::Wait(None));
+}
+
+#[test]
+fn event_after_the_reapply_starts_a_new_batch() {
+    let t0 = Instant::now();
+    let mut d = Debouncer::new(STABILIZE, Duration::ZERO);
+    d.event(DisplayEvent::AutomationPoll, t0);
+    d.timeout(t0 + STABILIZE);
+    d.settled(t0 + STABILIZE);
+    d.event(DisplayEvent::AutomationPoll, t0 + ms(1600));
+    d.reapplied(t0 + ms(4000));
+    let t1 = t0 + ms(5000);
+    assert_eq!(
+        d.event(DisplayEvent::AutomationPoll, t1),
+        Action::Wait(Some(t1 + STABILIZE))
+    );
+    assert_eq!(
+        d.timeout(t1 + STABILIZE),
+        Action::Settled(EVENT_AUTOMATION_POLL)
+    );
+}
+
+// ── Targets ──────────────────────────────────────────────────────
+
+#[test]
+fn arrivals_target_just_the_arrived_monitors() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    d.event(arrival("A"), t0);
+    d.event(arrival("B"), t0 + ms(100));
+    assert_eq!(
+        d.targets(),
+        &Targets::Only(vec!["A".to_string(), "B".to_string()])
+    );
+}
+
+#[test]
+fn any_other_event_targets_every_monitor() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    d.event(arrival("A"), t0);
+    d.event(DisplayEvent::DevNodesChanged, t0 + ms(100));
+    assert_eq!(d.targets(), &Targets::All);
+}
+
+#[test]
+fn new_batch_starts_with_fresh_targets() {
+    let t0 = Instant::now();
+    let mut d = Debouncer::new(STABILIZE, Duration::ZERO);
+    d.event(DisplayEvent::SessionUnlock, t0);
+    d.timeout(t0 + STABILIZE);
+    d.settled(t0 + STABILIZE);
+    d.reapplied(t0 + ms(2000));
+    d.event(arrival("A"), t0 + ms(3000));
+    assert_eq!(d.targets(), &Targets::Only(vec!["A".to_string()]));
+}
+
+#[test]
+fn arrival_during_the_delay_widens_the_reapply() {
+    let t0 = Instant::now();
+    let t1 = t0 + STABILIZE;
+    let mut d = debouncer();
+    d.event(arrival("A"), t0);
+    d.timeout(t1);
+    d.settled(t1);
+    d.validated(true, t1);
+    assert_eq!(
+        d.event(arrival("B"), t1 + ms(500)),
+        Action::Reapply(EVENT_DEVICE_ARRIVAL)
+    );
+    assert_eq!(
+        d.targets(),
+        &Targets::Only(vec!["A".to_string(), "B".to_string()])
+    );
+}
+
+#[test]
+fn event_outside_a_targeted_reapply_starts_the_next_batch() {
+    let t0 = Instant::now();
+    let t1 = t0 + STABILIZE;
+    let mut d = Debouncer::new(STABILIZE, Duration::ZERO);
+    d.event(arrival("A"), t0);
+    d.timeout(t1);
+    d.settled(t1);
+    assert_eq!(d.validated(true, t1), Action::Reapply(EVENT_DEVICE_ARRIVAL));
+    // Covered by the running reapply.
+    assert_eq!(d.event(arrival("a"), t1 + ms(10)), Action::Wait(None));
+    // Not covered: kept for later.
+    assert_eq!(d.event(arrival("B"), t1 + ms(20)), Action::Wait(None));
+    let t2 = t1 + ms(1000);
+    assert_eq!(d.reapplied(t2), Action::Wait(Some(t2 + STABILIZE)));
+    assert_eq!(d.targets(), &Targets::Only(vec!["B".to_string()]));
+}
+
+// ── KVM mode ─────────────────────────────────────────────────────
+
+fn removal(key: &str) -> DisplayEvent {
+    DisplayEvent::DeviceRemoval {
+        device_key: Some(key.to_string()),
+    }
+}
+
+fn kvm_debouncer() -> Debouncer {
+    debouncer().with_kvm_mode(true)
+}
+
+#[test]
+fn removal_alone_never_opens_a_batch() {
+    let t0 = Instant::now();
+    let mut d = kvm_debouncer();
+    assert_eq!(d.event(removal("A"), t0), Action::Wait(None));
+    assert_eq!(d.timeout(t0 + STABILIZE), Action::Wait(None));
+}
+
+#[test]
+fn quick_return_of_a_removed_monitor_reapplies_without_waits() {
+    let t0 = Instant::now();
+    let mut d = kvm_debouncer();
+    d.event(removal("A"), t0);
+    let t1 = t0 + ms(2000);
+    assert_eq!(
+        d.event(arrival("a"), t1),
+        Action::Wait(Some(t1 + USER_SETTLE))
+    );
+    assert!(d.kvm_switch());
+    let t2 = t1 + USER_SETTLE;
+    assert_eq!(d.timeout(t2), Action::Settled(EVENT_DEVICE_ARRIVAL));
+    // No monitor check, no initialization wait.
+    assert_eq!(d.settled(t2), Action::Reapply(EVENT_DEVICE_ARRIVAL));
+}
+
+#[test]
+fn devnode_noise_keeps_a_kvm_switch_and_gets_its_window_cut() {
+    let t0 = Instant::now();
+    let mut d = kvm_debouncer();
+    d.event(removal("A"), t0);
+    d.event(DisplayEvent::DevNodesChanged, t0 + ms(100));
+    let t1 = t0 + ms(300);
+    assert_eq!(
+        d.event(arrival("A"), t1),
+        Action::Wait(Some(t1 + USER_SETTLE))
+    );
+    assert!(d.kvm_switch());
+}
+
+#[test]
+fn late_return_gets_the_normal_handling() {
+    let t0 = Instant::now();
+    let mut d = kvm_debouncer();
+    d.event(removal("A"), t0);
+    let t1 = t0 + KVM_SWITCH_WINDOW + ms(1);
+    assert_eq!(
+        d.event(arrival("A"), t1),
+        Action::Wait(Some(t1 + STABILIZE))
+    );
+    assert!(!d.kvm_switch());
+}
+
+#[test]
+fn other_events_in_the_batch_cancel_the_kvm_shortcut() {
+    let t0 = Instant::now();
+    let mut d = kvm_debouncer();
+    d.event(removal("A"), t0);
+    d.event(arrival("A"), t0 + ms(100));
+    d.event(DisplayEvent::SessionUnlock, t0 + ms(120));
+    assert!(!d.kvm_switch());
+    let t1 = t0 + ms(100) + USER_SETTLE;
+    d.timeout(t1);
+    assert_eq!(d.settled(t1), Action::Wait(Some(t1 + DELAY)));
+}
+
+#[test]
+fn removals_are_ignored_without_kvm_mode() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    d.event(removal("A"), t0);
+    assert_eq!(
+        d.event(arrival("A"), t0 + ms(100)),
+        Action::Wait(Some(t0 + ms(100) + STABILIZE))
+    );
+    assert!(!d.kvm_switch());
+}
+
+// ── Dock mode ────────────────────────────────────────────────────
+
+fn dock_debouncer() -> Debouncer {
+    debouncer().with_dock_mode(true)
+}
+
+#[test]
+fn dock_mode_device_events_push_the_settle_window_out() {
+    let t0 = Instant::now();
+    let mut d = dock_debouncer();
+    d.event(device(), t0);
+    let t1 = t0 + ms(1000);
+    assert_eq!(
+        d.event(DisplayEvent::DevNodesChanged, t1),
+        Action::Wait(Some(t1 + STABILIZE))
+    );
+    // Non-device events don't.
+    assert_eq!(
+        d.event(DisplayEvent::AutomationPoll, t1 + ms(500)),
+        Action::Wait(Some(t1 + STABILIZE))
+    );
+}
+
+#[test]
+fn dock_mode_settle_window_is_capped() {
+    let t0 = Instant::now();
+    let mut d = dock_debouncer();
+    d.event(device(), t0);
+    let late = t0 + DOCK_SETTLE_MAX - ms(100);
+    assert_eq!(
+        d.event(DisplayEvent::DevNodesChanged, late),
+        Action::Wait(Some(t0 + DOCK_SETTLE_MAX))
+    );
+}
+
+#[test]
+fn dock_mode_needs_two_checks_in_a_row() {
+    let t0 = Instant::now();
+    let t1 = t0 + STABILIZE;
+    let mut d = dock_debouncer();
+    d.event(device(), t0);
+    d.timeout(t1);
+    assert_eq!(d.settled(t1), Action::Validate(EVENT_DEVICE_ARRIVAL));
+    assert_eq!(
+        d.validated(true, t1),
+        Action::Wait(Some(t1 + DOCK_CONFIRM_INTERVAL))
+    );
+    assert!(d.confirming());
+    let t2 = t1 + DOCK_CONFIRM_INTERVAL;
+    assert_eq!(d.timeout(t2), Action::Validate(EVENT_DEVICE_ARRIVAL));
+    assert!(!d.confirming());
+    assert_eq!(d.validated(true, t2), Action::Wait(Some(t2 + DELAY)));
+}
+
+#[test]
+fn dock_mode_device_event_while_confirming_restarts_the_count() {
+    let t0 = Instant::now();
+    let t1 = t0 + STABILIZE;
+    let mut d = dock_debouncer();
+    d.event(device(), t0);
+    d.timeout(t1);
+    d.settled(t1);
+    d.validated(true, t1);
+    d.event(DisplayEvent::DevNodesChanged, t1 + ms(500));
+    let t2 = t1 + DOCK_CONFIRM_INTERVAL;
+    d.timeout(t2);
+    // This check is the first in a row again.
+    assert_eq!(
+        d.validated(true, t2),
+        Action::Wait(Some(t2 + DOCK_CONFIRM_INTERVAL))
+    );
+}
+
+#[test]
+fn dock_mode_missing_monitor_drops_the_batch() {
+    let t0 = Instant::now();
+    let t1 = t0 + STABILIZE;
+    let mut d = dock_debouncer();
+    d.event(device(), t0);
+    d.timeout(t1);
+    d.settled(t1);
+    d.validated(true, t1);
+    let t2 = t1 + DOCK_CONFIRM_INTERVAL;
+    d.timeout(t2);
+    assert_eq!(d.validated(false, t2), Action::Wait(None));
+}
+
+#[test]
+fn dock_mode_checks_device_batches_with_session_events_too() {
+    let t0 = Instant::now();
+    let t1 = t0 + STABILIZE;
+    let mut d = dock_debouncer();
+    d.event(device(), t0);
+    d.event(DisplayEvent::SessionUnlock, t0 + ms(10));
+    d.timeout(t1);
+    assert_eq!(
+        d.settled(t1),
+        Action::Validate(EVENT_DEVICE_ARRIVAL | EVENT_SESSION_UNLOCK)
+    );
+}
+
+// ── Disconnect ───────────────────────────────────────────────────
+
+#[test]
+fn disconnect_while_idle_stops() {
+    assert_eq!(debouncer().disconnected(), Action::Stop);
+}
+
+#[test]
+fn disconnect_while_settling_drops_the_batch() {
+    let t0 = Instant::now();
+    let mut d = debouncer();
+    d.event(device(), t0);
+    assert_eq!(d.disconnected(), Action::Stop);
+}
+
+#[test]
+fn disconnect_during_the_delay_drops_the_reapply() {
+    let t0 = Instant::now();
+    let t1 = t0 + STABILIZE;
+    let mut d = debouncer();
+    d.event(DisplayEvent::SessionUnlock, t0);
+    d.timeout(t1);
+    d.settled(t1);
+    assert_eq!(d.disconnected(), Action::Stop);
+    assert_eq!(d.timeout(t1 + DELAY), Action::Wait(None));
+}
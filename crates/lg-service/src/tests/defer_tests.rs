@@ -0,0 +1,56 @@
+use super::*;
+
+const IDLE: Duration = Duration::from_secs(60);
+const MAX: Duration = Duration::from_secs(600);
+
+fn secs(s: u64) -> Duration {
+    Duration::from_secs(s)
+}
+
+// ── Busy decision ────────────────────────────────────────────────
+
+#[test]
+fn applies_when_foreground_is_not_fullscreen() {
+    assert_eq!(
+        decide(secs(0), false, secs(0), IDLE, MAX),
+        Decision::Apply("not_fullscreen")
+    );
+}
+
+#[test]
+fn waits_while_active_in_fullscreen() {
+    assert_eq!(decide(secs(5), true, secs(0), IDLE, MAX), Decision::Wait);
+    assert_eq!(decide(secs(59), true, secs(599), IDLE, MAX), Decision::Wait);
+}
+
+#[test]
+fn applies_once_idle_long_enough() {
+    assert_eq!(
+        decide(secs(60), true, secs(10), IDLE, MAX),
+        Decision::Apply("idle")
+    );
+}
+
+#[test]
+fn applies_after_max_deferral() {
+    assert_eq!(
+        decide(secs(1), true, secs(600), IDLE, MAX),
+        Decision::Apply("timeout")
+    );
+}
+
+#[test]
+fn zero_max_deferral_never_waits() {
+    assert_eq!(
+        decide(secs(0), true, secs(0), IDLE, Duration::ZERO),
+        Decision::Apply("timeout")
+    );
+}
+
+// ── Deferrable triggers ──────────────────────────────────────────
+
+#[test]
+fn user_and_session_events_are_not_deferrable() {
+    assert_eq!(EVENT_MASK_USER & crate::EVENT_MASK_DEFERRABLE, 0);
+    assert_eq!(crate::EVENT_MASK_SESSION & crate::EVENT_MASK_DEFERRABLE, 0);
+}
@@ -0,0 +1,39 @@
+use super::*;
+
+// ── Display state ────────────────────────────────────────────────
+
+#[test]
+fn raw_display_state_values() {
+    assert_eq!(DisplayState::from_raw(0), Some(DisplayState::Off));
+    assert_eq!(DisplayState::from_raw(1), Some(DisplayState::On));
+    assert_eq!(DisplayState::from_raw(2), Some(DisplayState::Dimmed));
+    assert_eq!(DisplayState::from_raw(7), None);
+}
+
+#[test]
+fn raw_display_state_roundtrips() {
+    for state in [DisplayState::Off, DisplayState::On, DisplayState::Dimmed] {
+        assert_eq!(DisplayState::from_raw(state.as_raw() as u32), Some(state));
+    }
+}
+
+// ── Releasing a held reapply ─────────────────────────────────────
+
+#[test]
+fn waking_from_off_releases_the_held_reapply() {
+    assert!(turned_on(DisplayState::Off, DisplayState::On));
+    assert!(turned_on(DisplayState::Off, DisplayState::Dimmed));
+}
+
+#[test]
+fn dimming_and_repeated_states_do_not_release() {
+    assert!(!turned_on(DisplayState::On, DisplayState::Dimmed));
+    assert!(!turned_on(DisplayState::Dimmed, DisplayState::On));
+    assert!(!turned_on(DisplayState::Off, DisplayState::Off));
+    assert!(!turned_on(DisplayState::On, DisplayState::Off));
+}
+
+#[test]
+fn display_on_trigger_is_not_deferrable() {
+    assert_eq!(crate::EVENT_DISPLAY_ON & crate::EVENT_MASK_DEFERRABLE, 0);
+}
@@ -0,0 +1,54 @@
+use super::*;
+
+const EVENT: &str = "<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'>\
+<System><Provider Name='lg-ultragear-color-svc'/><EventID Qualifiers='0'>3</EventID>\
+<Version>0</Version><Level>3</Level><Task>0</Task>\
+<TimeCreated SystemTime='2026-10-16T09:30:00.1234567Z'/><EventRecordID>4242</EventRecordID>\
+<Channel>Application</Channel><Computer>PC</Computer><Security/></System>\
+<EventData><Data>Reapply failed: monitor &lt;27GN950&gt; not found</Data></EventData></Event>";
+
+// ── Levels ───────────────────────────────────────────────────────
+
+#[test]
+fn level_parses_names_and_orders_by_severity() {
+    assert_eq!(EventLevel::parse("warn"), Some(EventLevel::Warning));
+    assert_eq!(EventLevel::parse(" Warning "), Some(EventLevel::Warning));
+    assert_eq!(EventLevel::parse("information"), Some(EventLevel::Info));
+    assert_eq!(EventLevel::parse("debug"), None);
+    assert!(EventLevel::Error < EventLevel::Warning);
+    assert_eq!(EventLevel::from_xml(1), EventLevel::Error);
+    assert_eq!(EventLevel::from_xml(0), EventLevel::Info);
+}
+
+// ── Query ────────────────────────────────────────────────────────
+
+#[test]
+fn query_filters_on_source_level_and_record() {
+    assert_eq!(
+        build_query(EventLevel::Info, None),
+        "*[System[Provider[@Name='lg-ultragear-color-svc']]]"
+    );
+    assert_eq!(
+        build_query(EventLevel::Warning, Some(10)),
+        "*[System[Provider[@Name='lg-ultragear-color-svc'] and \
+         (Level=1 or Level=2 or Level=3) and EventRecordID > 10]]"
+    );
+}
+
+// ── Rendered XML ─────────────────────────────────────────────────
+
+#[test]
+fn parse_event_xml_reads_fields_and_unescapes_message() {
+    let event = parse_event_xml(EVENT).unwrap();
+    assert_eq!(event.record_id, 4242);
+    assert_eq!(event.level, EventLevel::Warning);
+    assert_eq!(event.event_id, 3);
+    assert_eq!(event.time, "2026-10-16T09:30:00.1234567Z");
+    assert_eq!(event.message, "Reapply failed: monitor <27GN950> not found");
+    assert!(event.to_string().contains("WARN   Reapply failed"));
+}
+
+#[test]
+fn parse_event_xml_needs_a_record_id() {
+    assert!(parse_event_xml("<Event><System><Level>2</Level></System></Event>").is_none());
+}
@@ -0,0 +1,111 @@
+use super::*;
+
+fn arrival(key: &str) -> DisplayEvent {
+    DisplayEvent::DeviceArrival {
+        device_key: Some(key.to_string()),
+    }
+}
+
+fn only(keys: &[&str]) -> Targets {
+    Targets::Only(keys.iter().map(|k| k.to_string()).collect())
+}
+
+// ── DisplayEvent ─────────────────────────────────────────────────
+
+#[test]
+fn every_event_has_its_own_class_bit() {
+    let events = [
+        arrival(r"DISPLAY\GSM5BBF\1"),
+        DisplayEvent::DevNodesChanged,
+        DisplayEvent::SessionLogon,
+        DisplayEvent::SessionUnlock,
+        DisplayEvent::ConsoleConnect,
+        DisplayEvent::AutomationPoll,
+        DisplayEvent::FullscreenExit,
+        DisplayEvent::AppOverride,
+        DisplayEvent::Schedule,
+        DisplayEvent::HotkeyReapply,
+        DisplayEvent::HotkeyBrightness,
+        DisplayEvent::RemoteReapply,
+        DisplayEvent::ProfileTampered,
+        DisplayEvent::GammaReset,
+        DisplayEvent::Watchdog,
+        DisplayEvent::DisplayOn,
+        DisplayEvent::CalibrationUpdated,
+    ];
+    let all = events.iter().fold(0u32, |acc, e| {
+        assert_eq!(e.flag().count_ones(), 1, "{:?}", e);
+        assert_eq!(acc & e.flag(), 0, "{:?} shares a bit", e);
+        acc | e.flag()
+    });
+    assert_eq!(all, 0b1_1111_1111_1111_1111);
+}
+
+#[test]
+fn removal_has_no_class_bit() {
+    let event = DisplayEvent::DeviceRemoval {
+        device_key: Some(r"DISPLAY\GSM5BBF\1".to_string()),
+    };
+    assert_eq!(event.flag(), 0);
+}
+
+#[test]
+fn arrival_with_a_key_targets_that_monitor() {
+    assert_eq!(
+        arrival(r"DISPLAY\GSM5BBF\1").targets(),
+        only(&[r"DISPLAY\GSM5BBF\1"])
+    );
+}
+
+#[test]
+fn arrival_without_a_key_targets_every_monitor() {
+    let event = DisplayEvent::DeviceArrival { device_key: None };
+    assert_eq!(event.targets(), Targets::All);
+    assert_eq!(arrival("").targets(), Targets::All);
+    assert_eq!(DisplayEvent::DevNodesChanged.targets(), Targets::All);
+    assert_eq!(DisplayEvent::SessionUnlock.targets(), Targets::All);
+}
+
+// ── Targets ──────────────────────────────────────────────────────
+
+#[test]
+fn merge_collects_keys_once() {
+    let mut targets = only(&["A"]);
+    targets.merge(only(&["B", "a"]));
+    assert_eq!(targets, only(&["A", "B"]));
+}
+
+#[test]
+fn merge_with_all_widens_to_all() {
+    let mut targets = only(&["A"]);
+    targets.merge(Targets::All);
+    assert_eq!(targets, Targets::All);
+    targets.merge(only(&["B"]));
+    assert_eq!(targets, Targets::All);
+}
+
+#[test]
+fn all_covers_everything() {
+    assert!(Targets::All.covers(&Targets::All));
+    assert!(Targets::All.covers(&only(&["A"])));
+}
+
+#[test]
+fn only_covers_its_own_keys() {
+    assert!(only(&["A", "B"]).covers(&only(&["b"])));
+    assert!(!only(&["A"]).covers(&only(&["B"])));
+    assert!(!only(&["A"]).covers(&Targets::All));
+}
+
+#[test]
+fn includes_matches_device_keys_case_insensitively() {
+    assert!(Targets::All.includes("anything"));
+    assert!(only(&[r"DISPLAY\GSM5BBF\5&ABC"]).includes(r"display\gsm5bbf\5&abc"));
+    assert!(!only(&["A"]).includes("B"));
+}
+
+#[test]
+fn label_names_all_or_the_device_keys() {
+    assert_eq!(Targets::All.label(), "all");
+    assert_eq!(only(&["A", "B"]).label(), "A,B");
+}
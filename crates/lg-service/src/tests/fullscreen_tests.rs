@@ -0,0 +1,76 @@
+use super::*;
+
+// ── Process name normalization ───────────────────────────────────
+
+#[test]
+fn normalize_process_name_strips_path_and_extension() {
+    assert_eq!(
+        normalize_process_name(r"C:\Games\Elden Ring\eldenring.exe"),
+        "eldenring"
+    );
+    assert_eq!(normalize_process_name("CS2.EXE"), "cs2");
+    assert_eq!(normalize_process_name(" game "), "game");
+}
+
+#[test]
+fn parse_app_allowlist_skips_empty_entries() {
+    assert_eq!(
+        parse_app_allowlist("Game.exe, ,other"),
+        vec!["game".to_string(), "other".to_string()]
+    );
+    assert!(parse_app_allowlist("").is_empty());
+}
+
+// ── Tracker ──────────────────────────────────────────────────────
+
+#[test]
+fn tracker_fires_when_fullscreen_app_loses_foreground() {
+    let mut t = FullscreenTracker::new("");
+    assert_eq!(t.on_foreground("game.exe", true), None);
+    assert_eq!(t.on_foreground("explorer.exe", false), Some("game".into()));
+    assert_eq!(t.on_foreground("explorer.exe", false), None);
+}
+
+#[test]
+fn tracker_ignores_non_fullscreen_windows() {
+    let mut t = FullscreenTracker::new("");
+    assert_eq!(t.on_foreground("notepad.exe", false), None);
+    assert_eq!(t.on_foreground("explorer.exe", false), None);
+}
+
+#[test]
+fn tracker_respects_allowlist() {
+    let mut t = FullscreenTracker::new("game.exe");
+    assert_eq!(t.on_foreground("video.exe", true), None);
+    assert_eq!(t.on_foreground("explorer.exe", false), None);
+    assert_eq!(t.on_foreground("GAME.EXE", true), None);
+    assert_eq!(t.on_foreground("explorer.exe", false), Some("game".into()));
+}
+
+#[test]
+fn tracker_fires_when_switching_between_fullscreen_apps() {
+    let mut t = FullscreenTracker::new("");
+    assert_eq!(t.on_foreground("a.exe", true), None);
+    assert_eq!(t.on_foreground("b.exe", true), Some("a".into()));
+    assert_eq!(t.on_foreground("b.exe", true), None);
+}
+
+// ── Fullscreen foreground ────────────────────────────────────────
+
+#[test]
+fn notification_state_kind_covers_fullscreen_states() {
+    assert_eq!(
+        notification_state_kind(QUNS_RUNNING_D3D_FULL_SCREEN),
+        Some("exclusive")
+    );
+    assert_eq!(notification_state_kind(QUNS_BUSY), Some("fullscreen"));
+    assert_eq!(
+        notification_state_kind(QUNS_PRESENTATION_MODE),
+        Some("presentation")
+    );
+    assert_eq!(
+        notification_state_kind(QUERY_USER_NOTIFICATION_STATE(5)),
+        None,
+        "QUNS_ACCEPTS_NOTIFICATIONS"
+    );
+}
@@ -0,0 +1,51 @@
+use super::*;
+
+fn names(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+// ── Reset detection ──────────────────────────────────────────────
+
+#[test]
+fn rounding_noise_is_not_a_reset() {
+    let baseline: Vec<u16> = (0..768).map(|i| (i % 256) as u16 * 257).collect();
+    let current: Vec<u16> = baseline.iter().map(|v| v.saturating_sub(200)).collect();
+    assert!(!is_reset(&baseline, &current));
+}
+
+#[test]
+fn linear_ramp_replacing_a_curve_is_a_reset() {
+    let linear: Vec<u16> = (0..768).map(|i| (i % 256) as u16 * 257).collect();
+    let curved: Vec<u16> = linear.iter().map(|v| v / 2).collect();
+    assert!(is_reset(&curved, &linear));
+}
+
+// ── Attribution ──────────────────────────────────────────────────
+
+#[test]
+fn foreground_control_panel_is_the_culprit() {
+    let running = names(&["nvdisplay.container", "radeonsoftware"]);
+    assert_eq!(
+        likely_culprit(Some("nvcplui"), &running),
+        Some("NVIDIA Control Panel")
+    );
+}
+
+#[test]
+fn running_control_panel_beats_resident_service() {
+    let running = names(&["explorer", "nvdisplay.container", "radeonsoftware"]);
+    assert_eq!(
+        likely_culprit(Some("explorer"), &running),
+        Some("AMD Radeon Software")
+    );
+}
+
+#[test]
+fn no_known_process_means_unknown() {
+    assert_eq!(likely_culprit(None, &names(&["explorer", "game"])), None);
+}
+
+#[test]
+fn gamma_reset_trigger_is_not_deferrable() {
+    assert_eq!(crate::EVENT_GAMMA_RESET & crate::EVENT_MASK_DEFERRABLE, 0);
+}
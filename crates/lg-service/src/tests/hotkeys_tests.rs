@@ -0,0 +1,84 @@
+use super::*;
+
+// ── Binding parsing ──────────────────────────────────────────────
+
+#[test]
+fn parse_hotkey_reads_modifiers_and_function_key() {
+    assert_eq!(
+        parse_hotkey("Ctrl+Alt+F9"),
+        Ok(Some(Hotkey {
+            modifiers: MOD_CONTROL | MOD_ALT,
+            vk: 0x78,
+        }))
+    );
+}
+
+#[test]
+fn parse_hotkey_is_case_and_space_insensitive() {
+    assert_eq!(
+        parse_hotkey(" control + shift + up "),
+        Ok(Some(Hotkey {
+            modifiers: MOD_CONTROL | MOD_SHIFT,
+            vk: 0x26,
+        }))
+    );
+    assert_eq!(
+        parse_hotkey("Win+b"),
+        Ok(Some(Hotkey {
+            modifiers: MOD_WIN,
+            vk: 'B' as u32,
+        }))
+    );
+}
+
+#[test]
+fn parse_hotkey_empty_is_unbound() {
+    assert_eq!(parse_hotkey(""), Ok(None));
+    assert_eq!(parse_hotkey("   "), Ok(None));
+}
+
+#[test]
+fn parse_hotkey_rejects_invalid_bindings() {
+    assert!(parse_hotkey("F9").is_err(), "modifier required");
+    assert!(parse_hotkey("Ctrl+Alt").is_err(), "key required");
+    assert!(parse_hotkey("Ctrl+A+B").is_err(), "single key only");
+    assert!(parse_hotkey("Ctrl+F25").is_err());
+    assert!(parse_hotkey("Ctrl+Escape").is_err());
+}
+
+#[test]
+fn default_bindings_parse() {
+    let cfg = Config::default();
+    for action in HotkeyAction::ALL {
+        assert!(
+            matches!(parse_hotkey(action.binding(&cfg)), Ok(Some(_))),
+            "{:?}",
+            action
+        );
+    }
+}
+
+#[test]
+fn action_ids_round_trip() {
+    for action in HotkeyAction::ALL {
+        assert_eq!(HotkeyAction::from_id(action.id()), Some(action));
+    }
+    assert_eq!(HotkeyAction::from_id(0), None);
+}
+
+// ── Brightness stepping ──────────────────────────────────────────
+
+#[test]
+fn step_brightness_clamps_to_range() {
+    assert_eq!(step_brightness(50, 10), 60);
+    assert_eq!(step_brightness(95, 10), 100);
+    assert_eq!(step_brightness(5, -10), 0);
+    assert_eq!(step_brightness(150, -10), 90);
+}
+
+#[test]
+fn brightness_change_target() {
+    assert_eq!(BrightnessChange::Relative(-10).target(40), 30);
+    assert_eq!(BrightnessChange::Absolute(70).target(40), 70);
+    assert_eq!(BrightnessChange::Absolute(250).target(40), 100);
+}
@@ -0,0 +1,111 @@
+use super::*;
+
+// ── Brightness argument ──────────────────────────────────────────
+
+#[test]
+fn parse_brightness_relative_and_absolute() {
+    assert_eq!(parse_brightness("+10"), Ok(BrightnessChange::Relative(10)));
+    assert_eq!(parse_brightness("-5"), Ok(BrightnessChange::Relative(-5)));
+    assert_eq!(parse_brightness("60"), Ok(BrightnessChange::Absolute(60)));
+}
+
+#[test]
+fn parse_brightness_rejects_out_of_range_and_garbage() {
+    assert!(parse_brightness("101").is_err());
+    assert!(parse_brightness("+").is_err());
+    assert!(parse_brightness("bright").is_err());
+    assert!(parse_brightness("+-3").is_err());
+}
+
+#[test]
+fn parse_brightness_caps_large_steps() {
+    assert_eq!(
+        parse_brightness("+500"),
+        Ok(BrightnessChange::Relative(100))
+    );
+}
+
+// ── Requests ─────────────────────────────────────────────────────
+
+#[test]
+fn parse_request_reapply() {
+    assert_eq!(parse_request("reapply\n"), Ok(Request::Reapply));
+    assert_eq!(parse_request("REAPPLY"), Ok(Request::Reapply));
+    assert!(parse_request("reapply now").is_err());
+}
+
+#[test]
+fn parse_request_brightness() {
+    assert_eq!(
+        parse_request("brightness -10"),
+        Ok(Request::Brightness(BrightnessChange::Relative(-10)))
+    );
+    assert!(parse_request("brightness").is_err());
+}
+
+#[test]
+fn parse_request_preset() {
+    assert_eq!(
+        parse_request("preset Reader"),
+        Ok(Request::Preset(Some("reader".into())))
+    );
+    assert_eq!(parse_request("preset auto"), Ok(Request::Preset(None)));
+    assert!(parse_request("preset srgb").is_err());
+}
+
+#[test]
+fn parse_request_history() {
+    assert_eq!(parse_request("history"), Ok(Request::History(None)));
+    assert_eq!(parse_request("HISTORY 5"), Ok(Request::History(Some(5))));
+    assert!(parse_request("history 0").is_err());
+    assert!(parse_request("history all").is_err());
+}
+
+#[test]
+fn parse_request_workers() {
+    assert_eq!(parse_request("workers"), Ok(Request::Workers));
+    assert!(parse_request("workers all").is_err());
+}
+
+#[test]
+fn parse_request_lid() {
+    assert_eq!(parse_request("lid"), Ok(Request::Lid));
+    assert!(parse_request("lid open").is_err());
+}
+
+#[test]
+fn parse_request_rejects_unknown_and_empty() {
+    assert!(parse_request("").is_err());
+    assert!(parse_request("reboot").is_err());
+}
+
+// ── Request line ─────────────────────────────────────────────────
+
+#[test]
+fn request_line_waits_for_the_newline() {
+    assert_eq!(request_line(b""), Ok(None));
+    assert_eq!(request_line(b"brightness +1"), Ok(None));
+    assert_eq!(
+        request_line(b"brightness +10\n"),
+        Ok(Some("brightness +10".to_string()))
+    );
+    // Anything after the first line is ignored.
+    assert_eq!(request_line(b"lid\nworkers\n"), Ok(Some("lid".to_string())));
+}
+
+#[test]
+fn request_line_is_bounded() {
+    let mut received = vec![b'x'; MAX_REQUEST_LEN - 1];
+    assert_eq!(request_line(&received), Ok(None));
+    received.push(b'x');
+    assert!(request_line(&received).is_err());
+    // A newline past the limit doesn't make it a request either.
+    received.push(b'\n');
+    assert!(request_line(&received).is_err());
+    let mut longest = vec![b'x'; MAX_REQUEST_LEN - 1];
+    longest.push(b'\n');
+    assert_eq!(
+        request_line(&longest).map(|line| line.map(|l| l.len())),
+        Ok(Some(MAX_REQUEST_LEN - 1))
+    );
+}
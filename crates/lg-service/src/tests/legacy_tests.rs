@@ -0,0 +1,94 @@
+use super::*;
+use std::path::PathBuf;
+
+fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+// ── Image path parsing ───────────────────────────────────────────
+
+#[test]
+fn image_file_name_handles_quoted_path_with_args() {
+    assert_eq!(
+        image_file_name(r#""C:\Program Files\LG\lg-ultragear-color-svc.exe" --service"#),
+        Some("lg-ultragear-color-svc.exe".into())
+    );
+}
+
+#[test]
+fn image_file_name_handles_unquoted_path_with_args() {
+    assert_eq!(
+        image_file_name(
+            r"C:\ProgramData\LG-UltraGear-Monitor\LG-UltraGear-Dimming-Fix.EXE service run"
+        ),
+        Some("lg-ultragear-dimming-fix.exe".into())
+    );
+}
+
+#[test]
+fn image_file_name_empty_is_none() {
+    assert_eq!(image_file_name("   "), None);
+}
+
+#[test]
+fn is_legacy_image_detects_standalone_binary() {
+    let install =
+        PathBuf::from(r"C:\ProgramData\LG-UltraGear-Monitor\lg-ultragear-dimming-fix.exe");
+    assert!(is_legacy_image(
+        r"C:\Tools\lg-ultragear-color-svc.exe",
+        &install
+    ));
+    assert!(!is_legacy_image(
+        r#""C:\ProgramData\LG-UltraGear-Monitor\lg-ultragear-dimming-fix.exe" service run"#,
+        &install
+    ));
+}
+
+// ── Parameter import ─────────────────────────────────────────────
+
+#[test]
+fn import_parameters_maps_known_values() {
+    let mut cfg = Config::default();
+    let imported = import_parameters(
+        &mut cfg,
+        &params(&[
+            ("MonitorMatch", "LG 27GR95QE"),
+            ("ToastEnabled", "0"),
+            ("ReapplyDelayMs", "5000"),
+        ]),
+    );
+    assert_eq!(cfg.monitor_match, "LG 27GR95QE");
+    assert!(!cfg.toast_enabled);
+    assert_eq!(cfg.reapply_delay_ms, 5000);
+    assert_eq!(imported.len(), 3);
+    assert!(imported.contains(&"monitor_match=LG 27GR95QE".to_string()));
+}
+
+#[test]
+fn import_parameters_keeps_customized_fields() {
+    let mut cfg = Config {
+        monitor_match: "CUSTOM".into(),
+        ..Config::default()
+    };
+    let imported = import_parameters(&mut cfg, &params(&[("MonitorMatch", "LG")]));
+    assert_eq!(cfg.monitor_match, "CUSTOM");
+    assert!(imported.is_empty());
+}
+
+#[test]
+fn import_parameters_skips_unparseable_and_unknown_values() {
+    let mut cfg = Config::default();
+    let imported = import_parameters(
+        &mut cfg,
+        &params(&[
+            ("StabilizeDelayMs", "soon"),
+            ("Verbose", "maybe"),
+            ("SomethingElse", "1"),
+        ]),
+    );
+    assert!(imported.is_empty());
+    assert_eq!(cfg, Config::default());
+}
@@ -0,0 +1,17 @@
+use super::*;
+
+// ── Lid state ────────────────────────────────────────────────────
+
+#[test]
+fn raw_lid_values() {
+    assert_eq!(from_raw(0), Some(LidState::Closed));
+    assert_eq!(from_raw(1), Some(LidState::Open));
+    assert_eq!(from_raw(2), None);
+}
+
+#[test]
+fn stored_state_roundtrips() {
+    for state in [None, Some(LidState::Open), Some(LidState::Closed)] {
+        assert_eq!(from_u8(to_u8(state)), state);
+    }
+}
@@ -0,0 +1,43 @@
+use super::*;
+use std::time::UNIX_EPOCH;
+
+fn fingerprint(len: u64, secs: u64) -> Option<Fingerprint> {
+    Some(Fingerprint {
+        len,
+        modified: Some(UNIX_EPOCH + Duration::from_secs(secs)),
+    })
+}
+
+// ── Change detection ─────────────────────────────────────────────
+
+#[test]
+fn new_size_or_timestamp_is_a_replacement() {
+    assert!(!is_replaced(fingerprint(3144, 10), fingerprint(3144, 10)));
+    assert!(is_replaced(fingerprint(3144, 10), fingerprint(3144, 20)));
+    assert!(is_replaced(fingerprint(3144, 10), fingerprint(4096, 10)));
+    // A profile copied in after the service started counts too.
+    assert!(is_replaced(None, fingerprint(3144, 10)));
+}
+
+#[test]
+fn deleted_profile_is_not_a_replacement() {
+    assert!(!is_replaced(fingerprint(3144, 10), None));
+    assert!(!is_replaced(None, None));
+}
+
+// ── Watched profiles ─────────────────────────────────────────────
+
+#[test]
+fn override_profiles_skip_none_and_duplicates() {
+    let mut config = Config::default();
+    config
+        .app_overrides
+        .insert("game.exe".to_string(), "Calibrated.icm".to_string());
+    config
+        .app_overrides
+        .insert("photo.exe".to_string(), "calibrated.icm".to_string());
+    config
+        .app_overrides
+        .insert("video.exe".to_string(), "none".to_string());
+    assert_eq!(override_profiles(&config), ["Calibrated.icm"]);
+}
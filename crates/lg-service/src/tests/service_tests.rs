@@ -173,12 +173,12 @@ fn guid_devinterface_monitor_data4() {
     );
 }
 
-// ── DevBroadcastDeviceInterface ──────────────────────────────────
+// ── DEV_BROADCAST_DEVICEINTERFACE_W ──────────────────────────────
 
 #[test]
 fn dev_broadcast_struct_size() {
-    let size = std::mem::size_of::<DevBroadcastDeviceInterface>();
-    assert!(size >= 28, "DevBroadcastDeviceInterface size: {}", size);
+    let size = std::mem::size_of::<DEV_BROADCAST_DEVICEINTERFACE_W>();
+    assert!(size >= 28, "DEV_BROADCAST_DEVICEINTERFACE_W size: {}", size);
 }
 
 #[test]
@@ -188,27 +188,30 @@ fn dbt_devtyp_deviceinterface_value() {
 
 #[test]
 fn device_notify_window_handle_value() {
-    assert_eq!(DEVICE_NOTIFY_WINDOW_HANDLE, 0);
+    assert_eq!(DEVICE_NOTIFY_WINDOW_HANDLE.0, 0);
 }
 
-// ── to_wide helper ───────────────────────────────────────────────
+// ── Wide strings ─────────────────────────────────────────────────
 
 #[test]
-fn to_wide_service_class_name() {
-    let result = to_wide("LGUltraGearColorSvcWnd");
+fn wide_string_service_class_name() {
+    let wide = WideString::new("LGUltraGearColorSvcWnd");
+    let result = wide.as_slice_with_nul();
     assert!(!result.is_empty());
     assert_eq!(*result.last().unwrap(), 0u16);
 }
 
 #[test]
-fn to_wide_empty() {
-    let result = to_wide("");
-    assert_eq!(result, vec![0u16]);
+fn wide_string_empty() {
+    let wide = WideString::new("");
+    let result = wide.as_slice_with_nul();
+    assert_eq!(result, [0u16]);
 }
 
 #[test]
-fn to_wide_backslash_path() {
-    let result = to_wide(r"DISPLAY\LGS\001");
+fn wide_string_backslash_path() {
+    let wide = WideString::new(r"DISPLAY\LGS\001");
+    let result = wide.as_slice_with_nul();
     assert_eq!(*result.last().unwrap(), 0u16);
     assert!(result.len() > 1);
 }
@@ -260,7 +263,7 @@ fn event_sender_defaults_to_none() {
 
 #[test]
 fn event_sender_can_be_set_and_cleared() {
-    let (tx, _rx) = mpsc::channel::<u8>();
+    let (tx, _rx) = mpsc::channel::<u32>();
     EVENT_SENDER.with(|s| *s.borrow_mut() = Some(tx));
     EVENT_SENDER.with(|s| assert!(s.borrow().is_some()));
     EVENT_SENDER.with(|s| *s.borrow_mut() = None);
@@ -271,7 +274,7 @@ fn event_sender_can_be_set_and_cleared() {
 
 #[test]
 fn channel_event_send_receive() {
-    let (tx, rx) = mpsc::channel::<u8>();
+    let (tx, rx) = mpsc::channel::<u32>();
     tx.send(EVENT_DEVICE_ARRIVAL).unwrap();
     let received = rx.recv().unwrap();
     assert_eq!(received, EVENT_DEVICE_ARRIVAL);
@@ -279,11 +282,11 @@ fn channel_event_send_receive() {
 
 #[test]
 fn channel_coalesces_multiple_events() {
-    let (tx, rx) = mpsc::channel::<u8>();
+    let (tx, rx) = mpsc::channel::<u32>();
     tx.send(EVENT_DEVICE_ARRIVAL).unwrap();
     tx.send(EVENT_DEVNODES_CHANGED).unwrap();
     tx.send(EVENT_SESSION_UNLOCK).unwrap();
-    let mut accumulated: u8 = 0;
+    let mut accumulated: u32 = 0;
     while let Ok(f) = rx.try_recv() {
         accumulated |= f;
     }
@@ -291,9 +294,39 @@ fn channel_coalesces_multiple_events() {
     assert_ne!(accumulated & EVENT_MASK_SESSION, 0);
 }
 
+#[test]
+fn event_names_lists_each_set_flag() {
+    assert_eq!(
+        event_names(EVENT_DEVICE_ARRIVAL | EVENT_SESSION_UNLOCK | EVENT_WATCHDOG),
+        ["device_arrival", "session_unlock", "watchdog"]
+    );
+    assert!(event_names(0).is_empty());
+}
+
+#[test]
+fn toast_trigger_names_the_session_event() {
+    assert_eq!(
+        toast_trigger("event", EVENT_SESSION_UNLOCK | EVENT_DEVICE_ARRIVAL),
+        "unlock"
+    );
+    assert_eq!(toast_trigger("event", EVENT_SESSION_LOGON), "sign-in");
+    assert_eq!(
+        toast_trigger("event", EVENT_DEVNODES_CHANGED),
+        "monitor change"
+    );
+    assert_eq!(toast_trigger("hotkey", EVENT_HOTKEY_REAPPLY), "hotkey");
+    assert_eq!(toast_trigger("automation_poll", 0), "automation");
+}
+
+#[test]
+fn event_names_cover_every_flag() {
+    let all = EVENT_NAMES.iter().fold(0u32, |acc, (flag, _)| acc | flag);
+    assert_eq!(all, 0b1_1111_1111_1111_1111);
+}
+
 #[test]
 fn channel_recv_timeout_returns_on_timeout() {
-    let (_tx, rx) = mpsc::channel::<u8>();
+    let (_tx, rx) = mpsc::channel::<u32>();
     let start = Instant::now();
     let result = rx.recv_timeout(Duration::from_millis(50));
     assert!(result.is_err());
@@ -302,14 +335,14 @@ fn channel_recv_timeout_returns_on_timeout() {
 
 #[test]
 fn channel_disconnects_on_sender_drop() {
-    let (tx, rx) = mpsc::channel::<u8>();
+    let (tx, rx) = mpsc::channel::<u32>();
     drop(tx);
     assert!(rx.recv().is_err());
 }
 
 #[test]
 fn channel_try_recv_drains_queue() {
-    let (tx, rx) = mpsc::channel::<u8>();
+    let (tx, rx) = mpsc::channel::<u32>();
     tx.send(EVENT_DEVICE_ARRIVAL).unwrap();
     tx.send(EVENT_SESSION_LOGON).unwrap();
     drop(tx);
@@ -322,7 +355,7 @@ fn channel_try_recv_drains_queue() {
 
 #[test]
 fn channel_recv_timeout_interruptible_on_disconnect() {
-    let (tx, rx) = mpsc::channel::<u8>();
+    let (tx, rx) = mpsc::channel::<u32>();
     let handle = std::thread::spawn(move || {
         std::thread::sleep(Duration::from_millis(30));
         drop(tx);
@@ -343,15 +376,30 @@ fn event_flags_are_distinct_bits() {
         EVENT_SESSION_UNLOCK,
         EVENT_CONSOLE_CONNECT,
         EVENT_AUTOMATION_POLL,
+        EVENT_FULLSCREEN_EXIT,
+        EVENT_APP_OVERRIDE,
+        EVENT_SCHEDULE,
+        EVENT_HOTKEY_REAPPLY,
+        EVENT_HOTKEY_BRIGHTNESS,
+        EVENT_REMOTE_REAPPLY,
     ];
     for (i, &a) in all.iter().enumerate() {
-        assert!(a.count_ones() == 1, "Flag 0b{:08b} is not a single bit", a);
+        assert!(a.count_ones() == 1, "Flag 0b{:012b} is not a single bit", a);
         for &b in &all[i + 1..] {
-            assert_eq!(a & b, 0, "Flags 0b{:08b} and 0b{:08b} overlap", a, b);
+            assert_eq!(a & b, 0, "Flags 0b{:012b} and 0b{:012b} overlap", a, b);
         }
     }
 }
 
+#[test]
+fn event_mask_user_is_disjoint_from_other_masks() {
+    assert_ne!(EVENT_MASK_USER & EVENT_HOTKEY_REAPPLY, 0);
+    assert_ne!(EVENT_MASK_USER & EVENT_HOTKEY_BRIGHTNESS, 0);
+    assert_ne!(EVENT_MASK_USER & EVENT_REMOTE_REAPPLY, 0);
+    assert_eq!(EVENT_MASK_USER & EVENT_MASK_DEVICE, 0);
+    assert_eq!(EVENT_MASK_USER & EVENT_MASK_SESSION, 0);
+}
+
 #[test]
 fn event_mask_device_covers_device_flags() {
     assert_ne!(EVENT_MASK_DEVICE & EVENT_DEVICE_ARRIVAL, 0);
@@ -383,7 +431,7 @@ fn event_masks_are_disjoint() {
 
 #[test]
 fn event_accumulation_single_flag() {
-    let mut accumulated: u8 = 0;
+    let mut accumulated: u32 = 0;
     accumulated |= EVENT_DEVICE_ARRIVAL;
     assert_ne!(accumulated & EVENT_DEVICE_ARRIVAL, 0);
     assert_eq!(accumulated & EVENT_SESSION_LOGON, 0);
@@ -391,7 +439,7 @@ fn event_accumulation_single_flag() {
 
 #[test]
 fn event_accumulation_multiple_flags() {
-    let mut accumulated: u8 = 0;
+    let mut accumulated: u32 = 0;
     accumulated |= EVENT_DEVICE_ARRIVAL;
     accumulated |= EVENT_DEVNODES_CHANGED;
     accumulated |= EVENT_SESSION_UNLOCK;
@@ -401,7 +449,7 @@ fn event_accumulation_multiple_flags() {
 
 #[test]
 fn event_accumulation_or_is_idempotent() {
-    let mut accumulated: u8 = 0;
+    let mut accumulated: u32 = 0;
     accumulated |= EVENT_DEVICE_ARRIVAL;
     accumulated |= EVENT_DEVICE_ARRIVAL;
     accumulated |= EVENT_DEVICE_ARRIVAL;
@@ -410,7 +458,7 @@ fn event_accumulation_or_is_idempotent() {
 
 #[test]
 fn event_accumulation_device_only() {
-    let mut accumulated: u8 = 0;
+    let mut accumulated: u32 = 0;
     accumulated |= EVENT_DEVICE_ARRIVAL | EVENT_DEVNODES_CHANGED;
     let has_device = accumulated & EVENT_MASK_DEVICE != 0;
     let has_session = accumulated & EVENT_MASK_SESSION != 0;
@@ -423,7 +471,7 @@ fn event_accumulation_device_only() {
 
 #[test]
 fn event_accumulation_session_only() {
-    let mut accumulated: u8 = 0;
+    let mut accumulated: u32 = 0;
     accumulated |= EVENT_SESSION_UNLOCK;
     let has_device = accumulated & EVENT_MASK_DEVICE != 0;
     let has_session = accumulated & EVENT_MASK_SESSION != 0;
@@ -436,7 +484,7 @@ fn event_accumulation_session_only() {
 
 #[test]
 fn event_accumulation_mixed_storm() {
-    let mut accumulated: u8 = 0;
+    let mut accumulated: u32 = 0;
     accumulated |= EVENT_DEVICE_ARRIVAL;
     accumulated |= EVENT_DEVNODES_CHANGED;
     accumulated |= EVENT_SESSION_UNLOCK;
@@ -461,8 +509,8 @@ fn is_monitor_device_event_null_lparam_is_false() {
 
 #[test]
 fn is_monitor_device_event_monitor_guid_is_true() {
-    let filter = DevBroadcastDeviceInterface {
-        dbcc_size: std::mem::size_of::<DevBroadcastDeviceInterface>() as u32,
+    let filter = DEV_BROADCAST_DEVICEINTERFACE_W {
+        dbcc_size: std::mem::size_of::<DEV_BROADCAST_DEVICEINTERFACE_W>() as u32,
         dbcc_devicetype: DBT_DEVTYP_DEVICEINTERFACE,
         dbcc_reserved: 0,
         dbcc_classguid: GUID_DEVINTERFACE_MONITOR,
@@ -474,8 +522,8 @@ fn is_monitor_device_event_monitor_guid_is_true() {
 
 #[test]
 fn is_monitor_device_event_wrong_guid_is_false() {
-    let filter = DevBroadcastDeviceInterface {
-        dbcc_size: std::mem::size_of::<DevBroadcastDeviceInterface>() as u32,
+    let filter = DEV_BROADCAST_DEVICEINTERFACE_W {
+        dbcc_size: std::mem::size_of::<DEV_BROADCAST_DEVICEINTERFACE_W>() as u32,
         dbcc_devicetype: DBT_DEVTYP_DEVICEINTERFACE,
         dbcc_reserved: 0,
         dbcc_classguid: windows::core::GUID::from_values(0x12345678, 0, 0, [0; 8]),
@@ -487,8 +535,8 @@ fn is_monitor_device_event_wrong_guid_is_false() {
 
 #[test]
 fn is_monitor_device_event_wrong_device_type_is_false() {
-    let filter = DevBroadcastDeviceInterface {
-        dbcc_size: std::mem::size_of::<DevBroadcastDeviceInterface>() as u32,
+    let filter = DEV_BROADCAST_DEVICEINTERFACE_W {
+        dbcc_size: std::mem::size_of::<DEV_BROADCAST_DEVICEINTERFACE_W>() as u32,
         dbcc_devicetype: 99,
         dbcc_reserved: 0,
         dbcc_classguid: GUID_DEVINTERFACE_MONITOR,
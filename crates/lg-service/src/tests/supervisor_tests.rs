@@ -0,0 +1,85 @@
+use super::*;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+// ── Restart policy ───────────────────────────────────────────────
+
+#[test]
+fn backoff_doubles_up_to_the_cap() {
+    let mut policy = RestartPolicy::default();
+    let delays: Vec<_> = (0..MAX_QUICK_RESTARTS)
+        .map(|_| policy.on_panic(Duration::ZERO).unwrap())
+        .collect();
+    assert_eq!(delays[0], FIRST_BACKOFF);
+    assert_eq!(delays[1], FIRST_BACKOFF * 2);
+    assert!(delays.iter().all(|d| *d <= MAX_BACKOFF));
+}
+
+#[test]
+fn repeated_quick_panics_give_up() {
+    let mut policy = RestartPolicy::default();
+    for _ in 0..MAX_QUICK_RESTARTS {
+        assert!(policy.on_panic(Duration::from_secs(1)).is_some());
+    }
+    assert_eq!(policy.on_panic(Duration::from_secs(1)), None);
+}
+
+#[test]
+fn a_stable_run_resets_the_budget() {
+    let mut policy = RestartPolicy::default();
+    for _ in 0..MAX_QUICK_RESTARTS {
+        policy.on_panic(Duration::ZERO);
+    }
+    assert_eq!(policy.on_panic(STABLE_AFTER), Some(FIRST_BACKOFF));
+}
+
+// ── Panic payloads ───────────────────────────────────────────────
+
+#[test]
+fn panic_message_reads_literal_and_formatted_payloads() {
+    let literal = panic::catch_unwind(|| panic!("boom")).unwrap_err();
+    assert_eq!(panic_message(literal.as_ref()), "boom");
+    let formatted = panic::catch_unwind(|| panic!("code {}", 7)).unwrap_err();
+    assert_eq!(panic_message(formatted.as_ref()), "code 7");
+}
+
+// ── Supervision ──────────────────────────────────────────────────
+
+#[test]
+fn panicked_worker_is_restarted_and_reported() {
+    let shutdown = ShutdownToken::new();
+    let runs = Arc::new(AtomicU32::new(0));
+    let counter = runs.clone();
+    let handle = spawn("test-restarts", &shutdown, move |_| {
+        if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+            panic!("first run fails");
+        }
+    })
+    .unwrap();
+    handle.join().unwrap();
+
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+    let health = snapshot()
+        .into_iter()
+        .find(|w| w.name == "test-restarts")
+        .unwrap();
+    assert_eq!(health.state, "stopped");
+    assert_eq!(health.restarts, 1);
+    assert_eq!(health.last_panic.as_deref(), Some("first run fails"));
+}
+
+#[test]
+fn shutdown_during_backoff_stops_the_worker() {
+    let shutdown = ShutdownToken::new();
+    let handle = spawn("test-shutdown", &shutdown, |_| panic!("always fails")).unwrap();
+    thread::sleep(Duration::from_millis(50));
+    shutdown.cancel();
+    handle.join().unwrap();
+
+    let health = snapshot()
+        .into_iter()
+        .find(|w| w.name == "test-shutdown")
+        .unwrap();
+    assert_eq!(health.state, "stopped");
+    assert_eq!(health.restarts, 0);
+}
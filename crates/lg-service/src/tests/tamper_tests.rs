@@ -0,0 +1,44 @@
+use super::*;
+
+// ── Action parsing ───────────────────────────────────────────────
+
+#[test]
+fn parses_actions_case_insensitively() {
+    assert_eq!(TamperAction::parse("log"), TamperAction::Log);
+    assert_eq!(TamperAction::parse(" Notify "), TamperAction::Notify);
+    assert_eq!(TamperAction::parse("REASSERT"), TamperAction::Reassert);
+}
+
+#[test]
+fn unknown_or_empty_action_is_off() {
+    assert_eq!(TamperAction::parse(""), TamperAction::Off);
+    assert_eq!(TamperAction::parse("off"), TamperAction::Off);
+    assert_eq!(TamperAction::parse("fix"), TamperAction::Off);
+}
+
+// ── Comparison ───────────────────────────────────────────────────
+
+#[test]
+fn same_profile_is_not_tampered() {
+    assert!(!is_tampered(
+        "lg-ultragear-gamma22-cmx.icm",
+        Some("LG-UltraGear-Gamma22-CMX.icm")
+    ));
+}
+
+#[test]
+fn different_or_missing_profile_is_tampered() {
+    assert!(is_tampered(
+        "lg-ultragear-gamma22-cmx.icm",
+        Some("sRGB Color Space Profile.icm")
+    ));
+    assert!(is_tampered("lg-ultragear-gamma22-cmx.icm", None));
+}
+
+#[test]
+fn reassert_trigger_is_not_deferrable() {
+    assert_eq!(
+        crate::EVENT_PROFILE_TAMPERED & crate::EVENT_MASK_DEFERRABLE,
+        0
+    );
+}
@@ -0,0 +1,103 @@
+use super::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// ── Spawning and adopting ────────────────────────────────────────
+
+#[test]
+fn spawned_and_adopted_tasks_are_owned_in_order() {
+    let shutdown = ShutdownToken::new();
+    let mut tasks = TaskSet::new();
+    tasks.spawn("first", &shutdown, |_| {}).unwrap();
+    tasks.adopt("second", Some(thread::Builder::new().spawn(|| {})));
+    tasks.adopt("disabled", None);
+    tasks.adopt("failed", Some(Err(io::Error::other("no threads left"))));
+    assert_eq!(tasks.names(), ["first", "second"]);
+    assert_eq!(tasks.join_all(TIMEOUT), JoinOutcome::default());
+}
+
+// ── Shutdown ─────────────────────────────────────────────────────
+
+#[test]
+fn cancelled_token_stops_waiting_tasks() {
+    let shutdown = ShutdownToken::new();
+    let mut tasks = TaskSet::new();
+    tasks
+        .spawn("sleeper", &shutdown, |shutdown| {
+            while shutdown.sleep(Duration::from_secs(60)) {}
+        })
+        .unwrap();
+    shutdown.cancel();
+    assert_eq!(tasks.join_all(TIMEOUT), JoinOutcome::default());
+}
+
+#[test]
+fn join_all_waits_for_every_task_and_reports_panics() {
+    let shutdown = ShutdownToken::new();
+    let finished = Arc::new(AtomicUsize::new(0));
+    let mut tasks = TaskSet::new();
+    for name in ["a", "b"] {
+        let finished = finished.clone();
+        tasks
+            .spawn(name, &shutdown, move |_| {
+                finished.fetch_add(1, Ordering::SeqCst);
+            })
+            .unwrap();
+    }
+    tasks.adopt(
+        "broken",
+        Some(thread::Builder::new().spawn(|| panic!("worker failed"))),
+    );
+    assert_eq!(tasks.join_all(TIMEOUT).panicked, ["broken"]);
+    assert_eq!(finished.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn consumer_registered_first_is_joined_last() {
+    let shutdown = ShutdownToken::new();
+    let (tx, rx) = std::sync::mpsc::channel::<u32>();
+    let received = Arc::new(AtomicUsize::new(0));
+    let mut tasks = TaskSet::new();
+    {
+        let received = received.clone();
+        tasks
+            .spawn("consumer", &shutdown, move |_| {
+                while rx.recv().is_ok() {
+                    received.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .unwrap();
+    }
+    tasks
+        .spawn("producer", &shutdown, move |_| {
+            for i in 0..3 {
+                tx.send(i).unwrap();
+            }
+        })
+        .unwrap();
+    assert_eq!(tasks.join_all(TIMEOUT), JoinOutcome::default());
+    assert_eq!(received.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn stuck_task_is_abandoned_at_the_deadline() {
+    let shutdown = ShutdownToken::new();
+    let release = ShutdownToken::new();
+    let mut tasks = TaskSet::new();
+    {
+        let release = release.clone();
+        tasks
+            .spawn("stuck", &shutdown, move |_| release.wait())
+            .unwrap();
+    }
+    tasks.spawn("quick", &shutdown, |_| {}).unwrap();
+    shutdown.cancel();
+
+    let started = Instant::now();
+    let outcome = tasks.join_all(Duration::from_millis(100));
+    assert!(started.elapsed() < TIMEOUT);
+    assert_eq!(outcome.abandoned, ["stuck"]);
+    release.cancel();
+}
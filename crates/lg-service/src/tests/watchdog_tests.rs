@@ -0,0 +1,11 @@
+use super::*;
+
+#[test]
+fn watchdog_trigger_is_not_deferrable() {
+    assert_eq!(crate::EVENT_WATCHDOG & crate::EVENT_MASK_DEFERRABLE, 0);
+}
+
+#[test]
+fn ping_without_a_window_fails() {
+    assert!(!ping(HWND::default(), 1, &ShutdownToken::new()));
+}
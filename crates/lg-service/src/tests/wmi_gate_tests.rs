@@ -0,0 +1,79 @@
+use super::*;
+
+// ── Backoff ──────────────────────────────────────────────────────
+
+#[test]
+fn retry_delay_doubles_up_to_the_cap() {
+    assert_eq!(retry_delay(0), Duration::from_millis(500));
+    assert_eq!(retry_delay(1), Duration::from_secs(1));
+    assert_eq!(retry_delay(3), Duration::from_secs(4));
+    assert_eq!(retry_delay(10), MAX_RETRY);
+}
+
+// ── Waiting ──────────────────────────────────────────────────────
+
+#[test]
+fn ready_at_once_does_not_sleep() {
+    let mut slept = Vec::new();
+    let result = wait_with(
+        || Ok(()),
+        |d| {
+            slept.push(d);
+            true
+        },
+        GATE_TIMEOUT,
+    );
+    assert_eq!(result, Readiness::Ready { retries: 0 });
+    assert!(slept.is_empty());
+}
+
+#[test]
+fn retries_with_backoff_until_ready() {
+    let mut failures = 3;
+    let mut slept = Vec::new();
+    let result = wait_with(
+        || {
+            if failures == 0 {
+                return Ok(());
+            }
+            failures -= 1;
+            Err("Winmgmt is StartPending".to_string())
+        },
+        |d| {
+            slept.push(d);
+            true
+        },
+        GATE_TIMEOUT,
+    );
+    assert_eq!(result, Readiness::Ready { retries: 3 });
+    assert_eq!(
+        slept,
+        [
+            Duration::from_millis(500),
+            Duration::from_secs(1),
+            Duration::from_secs(2)
+        ]
+    );
+}
+
+#[test]
+fn gives_up_once_the_delays_reach_the_timeout() {
+    let mut slept = Vec::new();
+    let result = wait_with(
+        || Err("query failed".to_string()),
+        |d| {
+            slept.push(d);
+            true
+        },
+        Duration::from_secs(2),
+    );
+    // 500 ms + 1 s, then the last 500 ms left, then one final check.
+    assert_eq!(result, Readiness::TimedOut { attempts: 4 });
+    assert_eq!(slept.iter().sum::<Duration>(), Duration::from_secs(2));
+}
+
+#[test]
+fn shutdown_stops_the_wait() {
+    let result = wait_with(|| Err("query failed".to_string()), |_| false, GATE_TIMEOUT);
+    assert_eq!(result, Readiness::Cancelled);
+}
@@ -0,0 +1,234 @@
+//! Service watchdog: periodic self-check and heartbeat.
+//!
+//! "Service running but doing nothing" reports come down to one of the
+//! pieces the event loop depends on failing quietly: the message window
+//! stops pumping, a device or session notification registration is lost,
+//! WMI stops answering, or our association disappears. Every
+//! `watchdog_interval_minutes` a thread checks each of them, repairs what
+//! it can, and writes the result to `state\heartbeat.toml`, which
+//! `service status` shows.
+//!
+//! A message pump that stays unresponsive can't be fixed from another
+//! thread, so after two failed checks in a row the process exits with an
+//! error and the SCM recovery actions restart the service.
+
+use super::events::DisplayEvent;
+use super::find_matching_monitors_for_config;
+use super::tamper::{self, TamperAction};
+use lg_core::config::Config;
+use lg_core::shutdown::ShutdownToken;
+use lg_core::state as app_state;
+use log::{error, info, warn};
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+use windows::Win32::UI::WindowsAndMessaging::{IsWindow, PostMessageW, WM_USER};
+
+/// Sent to the message window; answered by [`on_ping`].
+pub(crate) const WM_WATCHDOG_PING: u32 = WM_USER + 2;
+
+/// Delay before the first check, so startup has finished.
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(30);
+
+/// How long the message window gets to answer a ping.
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Consecutive unanswered pings before the process gives up.
+const MAX_MISSED_PINGS: u32 = 2;
+
+/// Sequence number of the last ping the window answered.
+static LAST_PONG: AtomicU64 = AtomicU64::new(0);
+
+/// Set by [`on_ping`] when it had to re-register a notification.
+static NOTIFICATIONS_REPAIRED: AtomicBool = AtomicBool::new(false);
+
+/// Called on the message thread for [`WM_WATCHDOG_PING`]: proves the pump
+/// is alive and re-registers lost notifications from the window's own thread.
+pub(crate) fn on_ping(hwnd: HWND, sequence: u64) {
+    let before = super::notifications_registered();
+    let after = super::register_notifications(hwnd);
+    if before != after {
+        NOTIFICATIONS_REPAIRED.store(true, Ordering::SeqCst);
+        info!(
+            "Watchdog: notification registration repaired (device={}, session={})",
+            after.0, after.1
+        );
+    }
+    LAST_PONG.store(sequence, Ordering::SeqCst);
+}
+
+/// Spawn the watchdog thread. Returns `None` when the interval is 0.
+pub(crate) fn spawn(
+    config: &Config,
+    shutdown: &ShutdownToken,
+    hwnd: &Arc<AtomicIsize>,
+    tx: mpsc::Sender<DisplayEvent>,
+) -> Option<std::io::Result<thread::JoinHandle<()>>> {
+    if config.watchdog_interval_minutes == 0 {
+        return None;
+    }
+    let config = config.clone();
+    let hwnd = hwnd.clone();
+    Some(super::supervisor::spawn(
+        "watchdog",
+        shutdown,
+        move |shutdown| run(&config, shutdown, &hwnd, &tx),
+    ))
+}
+
+fn run(
+    config: &Config,
+    shutdown: &ShutdownToken,
+    hwnd: &AtomicIsize,
+    tx: &mpsc::Sender<DisplayEvent>,
+) {
+    let interval = Duration::from_secs(config.watchdog_interval_minutes * 60);
+    let mut sequence = 0u64;
+    let mut missed_pings = 0u32;
+
+    info!(
+        "Watchdog started: every {} minute(s)",
+        config.watchdog_interval_minutes
+    );
+
+    let mut wait = FIRST_CHECK_DELAY;
+    while shutdown.sleep(wait) {
+        wait = interval;
+        sequence += 1;
+        let mut heartbeat = app_state::new_service_heartbeat(config.watchdog_interval_minutes);
+
+        // Message window: must exist and answer a ping through the pump.
+        let window = HWND(hwnd.load(Ordering::SeqCst) as _);
+        heartbeat.window_ok = ping(window, sequence, shutdown);
+        if heartbeat.window_ok {
+            missed_pings = 0;
+        } else {
+            missed_pings += 1;
+            warn!(
+                "Watchdog: message window did not respond ({}/{})",
+                missed_pings, MAX_MISSED_PINGS
+            );
+        }
+
+        // Notifications: on_ping re-registered anything that was missing.
+        if NOTIFICATIONS_REPAIRED.swap(false, Ordering::SeqCst) {
+            heartbeat
+                .repairs
+                .push("notifications_reregistered".to_string());
+        }
+        let (device, session) = super::notifications_registered();
+        heartbeat.notifications_ok = device && session;
+
+        // WMI: a failed query drops the cached connection, so one retry
+        // reconnects from scratch.
+        heartbeat.wmi_ok = match find_matching_monitors_for_config(config) {
+            Ok(_) => true,
+            Err(e) => {
+                warn!("Watchdog: WMI query failed, reconnecting: {}", e);
+                heartbeat.repairs.push("wmi_reconnect".to_string());
+                find_matching_monitors_for_config(config).is_ok()
+            }
+        };
+
+        // Association: every monitor we applied to still has our default.
+        // With `tamper_watch = "log"` / `"notify"` the user asked us not to
+        // fight other programs, so that is only recorded.
+        let missing = tamper::find_tampered();
+        heartbeat.association_ok = missing.is_empty();
+        if !heartbeat.association_ok {
+            warn!(
+                "Watchdog: profile no longer the default on {} monitor(s)",
+                missing.len()
+            );
+            if matches!(
+                TamperAction::parse(&config.tamper_watch),
+                TamperAction::Off | TamperAction::Reassert
+            ) {
+                heartbeat.repairs.push("reapply".to_string());
+                if tx.send(DisplayEvent::Watchdog).is_err() {
+                    break;
+                }
+            }
+        }
+
+        // Workers: the supervisor restarts panicked ones; a failed one has
+        // been given up on and only a service restart brings it back.
+        heartbeat.workers = super::supervisor::snapshot();
+
+        if let Err(e) = app_state::save_service_heartbeat(&heartbeat) {
+            warn!("Watchdog: could not write heartbeat: {}", e);
+        }
+        if !heartbeat.healthy() {
+            app_state::append_diagnostic_event(
+                "service",
+                "WARN",
+                "watchdog",
+                &format!(
+                    "window={} notifications={} wmi={} association={} repairs={} failed_workers={}",
+                    heartbeat.window_ok,
+                    heartbeat.notifications_ok,
+                    heartbeat.wmi_ok,
+                    heartbeat.association_ok,
+                    heartbeat.repairs.join(","),
+                    heartbeat
+                        .workers
+                        .iter()
+                        .filter(|w| w.failed())
+                        .map(|w| w.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                ),
+            );
+        }
+
+        if missed_pings >= MAX_MISSED_PINGS {
+            error!("Watchdog: message window is unresponsive; exiting so the service restarts");
+            app_state::append_diagnostic_event(
+                "service",
+                "ERROR",
+                "watchdog_restart",
+                "message window unresponsive",
+            );
+            std::process::exit(1);
+        }
+    }
+
+    info!("Watchdog stopped");
+}
+
+/// Post a ping and wait for the window to answer it.
+fn ping(window: HWND, sequence: u64, shutdown: &ShutdownToken) -> bool {
+    if window.0.is_null() || !unsafe { IsWindow(window) }.as_bool() {
+        return false;
+    }
+    if unsafe {
+        PostMessageW(
+            window,
+            WM_WATCHDOG_PING,
+            WPARAM(sequence as usize),
+            LPARAM(0),
+        )
+    }
+    .is_err()
+    {
+        return false;
+    }
+    let deadline = Instant::now() + PING_TIMEOUT;
+    while Instant::now() < deadline {
+        if LAST_PONG.load(Ordering::SeqCst) >= sequence {
+            return true;
+        }
+        if !shutdown.sleep(Duration::from_millis(100)) {
+            // Shutting down is not a failure.
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+#[path = "tests/watchdog_tests.rs"]
+mod tests;
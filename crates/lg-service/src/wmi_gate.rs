@@ -0,0 +1,129 @@
+//! Waiting for WMI before the first apply at service start.
+//!
+//! The service starts early in boot, sometimes before the WMI service
+//! (`Winmgmt`) is running or before its providers answer queries. The
+//! initial apply looks monitors up through WMI, so it used to fail and the
+//! profile stayed unapplied until the next display event. [`wait`] holds
+//! the initial apply until `Winmgmt` reports running and a trivial query
+//! succeeds, retrying with a growing delay and logging every attempt. It
+//! gives up after [`GATE_TIMEOUT`] and lets the apply run anyway, so a
+//! broken WMI never keeps the service from starting.
+
+use lg_core::shutdown::ShutdownToken;
+use lg_core::state as app_state;
+use log::{info, warn};
+use std::time::Duration;
+use windows_service::service::{ServiceAccess, ServiceState};
+use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+
+/// Name of the WMI service.
+const WMI_SERVICE: &str = "Winmgmt";
+
+/// Delay after the first failed attempt; doubles with every attempt.
+const FIRST_RETRY: Duration = Duration::from_millis(500);
+
+/// Longest delay between attempts.
+const MAX_RETRY: Duration = Duration::from_secs(8);
+
+/// Total time spent waiting before the apply runs regardless.
+const GATE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How the wait ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Readiness {
+    /// WMI answered after this many failed attempts.
+    Ready { retries: u32 },
+    /// WMI still failed when the wait ran out.
+    TimedOut { attempts: u32 },
+    /// Shutdown was requested while waiting.
+    Cancelled,
+}
+
+/// Delay after failed attempt number `attempt` (counted from zero).
+fn retry_delay(attempt: u32) -> Duration {
+    FIRST_RETRY
+        .saturating_mul(1 << attempt.min(16))
+        .min(MAX_RETRY)
+}
+
+/// Run `check` until it succeeds, sleeping between attempts with `sleep`
+/// (which returns `false` on shutdown) until the delays add up to
+/// `timeout`. Each failure is logged with its reason.
+pub(crate) fn wait_with(
+    mut check: impl FnMut() -> Result<(), String>,
+    mut sleep: impl FnMut(Duration) -> bool,
+    timeout: Duration,
+) -> Readiness {
+    let mut waited = Duration::ZERO;
+    let mut attempt = 0;
+    loop {
+        let reason = match check() {
+            Ok(()) => return Readiness::Ready { retries: attempt },
+            Err(reason) => reason,
+        };
+        let remaining = timeout.saturating_sub(waited);
+        if remaining.is_zero() {
+            return Readiness::TimedOut {
+                attempts: attempt + 1,
+            };
+        }
+        let delay = retry_delay(attempt).min(remaining);
+        info!(
+            "WMI not ready (attempt {}): {}; retrying in {} ms",
+            attempt + 1,
+            reason,
+            delay.as_millis()
+        );
+        if !sleep(delay) {
+            return Readiness::Cancelled;
+        }
+        waited += delay;
+        attempt += 1;
+    }
+}
+
+/// One readiness check: `Winmgmt` running, then a query answered.
+fn check_wmi() -> Result<(), String> {
+    let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
+        .map_err(|e| format!("cannot connect to the Service Control Manager: {}", e))?;
+    let service = manager
+        .open_service(WMI_SERVICE, ServiceAccess::QUERY_STATUS)
+        .map_err(|e| format!("cannot open {}: {}", WMI_SERVICE, e))?;
+    let status = service
+        .query_status()
+        .map_err(|e| format!("cannot query {}: {}", WMI_SERVICE, e))?;
+    if status.current_state != ServiceState::Running {
+        return Err(format!("{} is {:?}", WMI_SERVICE, status.current_state));
+    }
+    lg_monitor::wmi_responds().map_err(|e| format!("query failed: {}", e))
+}
+
+/// Wait for WMI before the initial apply. Returns `false` when shutdown was
+/// requested and the apply should be skipped.
+pub(crate) fn wait(shutdown: &ShutdownToken) -> bool {
+    match wait_with(check_wmi, |delay| shutdown.sleep(delay), GATE_TIMEOUT) {
+        Readiness::Ready { retries: 0 } => true,
+        Readiness::Ready { retries } => {
+            info!("WMI ready after {} retries", retries);
+            true
+        }
+        Readiness::TimedOut { attempts } => {
+            warn!(
+                "WMI still not ready after {} attempts; applying anyway",
+                attempts
+            );
+            app_state::append_diagnostic_event(
+                "service",
+                "WARN",
+                "wmi_not_ready",
+                &format!("attempts={}", attempts),
+            );
+            true
+        }
+        Readiness::Cancelled => false,
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/wmi_gate_tests.rs"]
+mod tests;
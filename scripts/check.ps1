@@ -4,7 +4,9 @@
     Type-check all crates with cargo check.
 .DESCRIPTION
     Runs `cargo check --all-targets --all-features` to verify the project
-    compiles without producing a binary (faster than a full build).
+    compiles without producing a binary (faster than a full build), then
+    checks the slim CLI build (`--no-default-features`) so feature gates
+    don't rot.
 #>
 [CmdletBinding()]
 param()
@@ -21,6 +23,14 @@ try {
         Write-Host '[check] FAILED' -ForegroundColor Red
         exit $LASTEXITCODE
     }
+
+    Write-Host '[check] Running cargo check on the slim CLI build...' -ForegroundColor Cyan
+    cargo check -p lg-cli --no-default-features
+
+    if ($LASTEXITCODE -ne 0) {
+        Write-Host '[check] FAILED (slim build)' -ForegroundColor Red
+        exit $LASTEXITCODE
+    }
     Write-Host '[check] OK' -ForegroundColor Green
 }
 finally {